@@ -0,0 +1,224 @@
+use super::batcher::Batcher;
+use crate::tensor::{Bool, Tensor, TensorData, backend::Backend};
+use burn_dataset::audio::transform::{AudioFeatures, MelFilterbank, SpecAugment};
+use rand::rngs::StdRng;
+
+/// A batch of [audio features](AudioFeatures).
+#[derive(Debug, Clone)]
+pub struct AudioFeaturesBatch<B: Backend> {
+    /// The features, padded with zeros to the longest features of the batch, of shape
+    /// `[batch_size, num_bins, num_frames]`.
+    pub features: Tensor<B, 3>,
+    /// The padding mask, true on the padding frames, of shape `[batch_size, num_frames]`.
+    pub mask_pad: Tensor<B, 2, Bool>,
+}
+
+/// Batcher stacking audio features, such as log-mel spectrograms, padded along the time axis.
+#[derive(Clone, Default)]
+pub struct AudioFeaturesBatcher {
+    max_frames: Option<usize>,
+}
+
+impl AudioFeaturesBatcher {
+    /// Create an audio features batcher.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Truncate the features longer than `max_frames` frames.
+    pub fn with_max_frames(mut self, max_frames: usize) -> Self {
+        self.max_frames = Some(max_frames);
+        self
+    }
+}
+
+impl<B: Backend> Batcher<B, AudioFeatures, AudioFeaturesBatch<B>> for AudioFeaturesBatcher {
+    fn batch(&self, items: Vec<AudioFeatures>, device: &B::Device) -> AudioFeaturesBatch<B> {
+        let batch_size = items.len();
+        let num_bins = items.first().map_or(0, |item| item.num_bins);
+        assert!(
+            items.iter().all(|item| item.num_bins == num_bins),
+            "The audio features must have the same number of bins"
+        );
+
+        let longest = items.iter().map(|item| item.num_frames).max().unwrap_or(0);
+        let num_frames = self.max_frames.map_or(longest, |max| longest.min(max));
+
+        let mut features = vec![0.0; batch_size * num_bins * num_frames];
+        let mut mask_pad = vec![true; batch_size * num_frames];
+        for (index, item) in items.iter().enumerate() {
+            let length = item.num_frames.min(num_frames);
+            for bin in 0..num_bins {
+                let start = (index * num_bins + bin) * num_frames;
+                features[start..start + length].copy_from_slice(&item.bin(bin)[..length]);
+            }
+            mask_pad[index * num_frames..index * num_frames + length].fill(false);
+        }
+
+        AudioFeaturesBatch {
+            features: Tensor::from_data(
+                TensorData::new(features, [batch_size, num_bins, num_frames]),
+                device,
+            ),
+            mask_pad: Tensor::from_data(
+                TensorData::new(mask_pad, [batch_size, num_frames]),
+                device,
+            ),
+        }
+    }
+}
+
+/// Convert batched spectrograms of shape `[batch_size, num_freqs, num_frames]` to mel bins,
+/// returning a tensor of shape `[batch_size, num_mels, num_frames]`.
+pub fn mel_scale<B: Backend>(
+    spectrogram: Tensor<B, 3>,
+    filterbank: &MelFilterbank,
+) -> Tensor<B, 3> {
+    let device = spectrogram.device();
+    let weights = Tensor::<B, 2>::from_data(
+        TensorData::new(
+            filterbank.weights().to_vec(),
+            [filterbank.num_mels(), filterbank.num_freqs()],
+        ),
+        &device,
+    );
+
+    weights.unsqueeze::<3>().matmul(spectrogram)
+}
+
+/// Convert batched features to decibels, with `multiplier * log10(x)` (10 for power features,
+/// 20 for magnitude features), optionally clamped to `top_db` decibels below the maximum of
+/// each item.
+pub fn amplitude_to_db<B: Backend>(
+    features: Tensor<B, 3>,
+    multiplier: f32,
+    top_db: Option<f32>,
+) -> Tensor<B, 3> {
+    let db = features
+        .clamp_min(1e-10)
+        .log()
+        .mul_scalar(multiplier / 10f32.ln());
+
+    match top_db {
+        Some(top_db) => {
+            let min = db.clone().max_dim(2).max_dim(1).sub_scalar(top_db);
+            let dims = db.dims();
+            db.max_pair(min.expand(dims))
+        }
+        None => db,
+    }
+}
+
+/// Apply the random frequency and time masks of [SpecAugment] to batched features of shape
+/// `[batch_size, num_bins, num_frames]`, with different masks for each item.
+///
+/// The masks are drawn on the CPU and applied on the device of the features.
+pub fn spec_augment<B: Backend>(
+    features: Tensor<B, 3>,
+    spec_augment: &SpecAugment,
+    rng: &mut StdRng,
+) -> Tensor<B, 3> {
+    let [batch_size, num_bins, num_frames] = features.dims();
+
+    let mut frequency_mask = vec![false; batch_size * num_bins * num_frames];
+    let mut time_mask = vec![false; batch_size * num_bins * num_frames];
+    for index in 0..batch_size {
+        let item = index * num_bins * num_frames;
+        for bins in spec_augment.frequency.sample_masks(num_bins, rng) {
+            frequency_mask[item + bins.start * num_frames..item + bins.end * num_frames].fill(true);
+        }
+        for frames in spec_augment.time.sample_masks(num_frames, rng) {
+            for bin in 0..num_bins {
+                let start = item + bin * num_frames;
+                time_mask[start + frames.start..start + frames.end].fill(true);
+            }
+        }
+    }
+
+    let device = features.device();
+    let mask = |values: Vec<bool>| {
+        Tensor::<B, 3, Bool>::from_data(
+            TensorData::new(values, [batch_size, num_bins, num_frames]),
+            &device,
+        )
+    };
+
+    features
+        .mask_fill(mask(frequency_mask), spec_augment.frequency.mask_value())
+        .mask_fill(mask(time_mask), spec_augment.time.mask_value())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestBackend;
+    use burn_dataset::audio::transform::{FrequencyMasking, TimeMasking};
+    use burn_tensor::Tolerance;
+    use rand::SeedableRng;
+
+    fn features(num_frames: usize, value: f32) -> AudioFeatures {
+        AudioFeatures {
+            values: vec![value; 2 * num_frames],
+            num_bins: 2,
+            num_frames,
+        }
+    }
+
+    #[test]
+    fn test_audio_features_batcher() {
+        let device = Default::default();
+        let batch: AudioFeaturesBatch<TestBackend> = AudioFeaturesBatcher::new()
+            .with_max_frames(3)
+            .batch(vec![features(2, 1.0), features(4, 2.0)], &device);
+
+        batch.features.into_data().assert_eq(
+            &TensorData::from([
+                [[1.0, 1.0, 0.0], [1.0, 1.0, 0.0]],
+                [[2.0, 2.0, 2.0], [2.0, 2.0, 2.0]],
+            ]),
+            false,
+        );
+        batch.mask_pad.into_data().assert_eq(
+            &TensorData::from([[false, false, true], [false, false, false]]),
+            false,
+        );
+    }
+
+    #[test]
+    fn test_tensor_transforms_match_the_cpu_transforms() {
+        let device = Default::default();
+        let filterbank = MelFilterbank::new(8000, 16, 3);
+        let spectrogram = AudioFeatures {
+            values: (0..9 * 2).map(|value| value as f32).collect(),
+            num_bins: 9,
+            num_frames: 2,
+        };
+
+        let expected = filterbank.apply(&spectrogram);
+        let tensor = Tensor::<TestBackend, 3>::from_data(
+            TensorData::new(spectrogram.values.clone(), [1, 9, 2]),
+            &device,
+        );
+        let mels = mel_scale(tensor, &filterbank);
+        mels.clone().into_data().assert_approx_eq::<f32>(
+            &TensorData::new(expected.values, [1, 3, 2]),
+            Tolerance::default(),
+        );
+
+        let db = amplitude_to_db(mels, 10.0, Some(10.0));
+        let max = db.clone().max().into_scalar();
+        assert!(db.min().into_scalar() >= max - 10.0 - 1e-4);
+
+        let masking = SpecAugment::new(
+            FrequencyMasking::new(1).with_mask_value(-1.0),
+            TimeMasking::new(10),
+        );
+        let masked = spec_augment(
+            Tensor::<TestBackend, 3>::ones([4, 3, 10], &device),
+            &masking,
+            &mut StdRng::seed_from_u64(0),
+        );
+        assert_eq!(masked.dims(), [4, 3, 10]);
+        assert!(masked.lower_elem(1.0).any().into_scalar());
+    }
+}
@@ -2,6 +2,7 @@ use burn_tensor::backend::Backend;
 
 pub use crate::data::dataset::{Dataset, DatasetIterator};
 use core::iter::Iterator;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 /// A progress struct that can be used to track the progress of a data loader.
@@ -14,6 +15,16 @@ pub struct Progress {
     pub items_total: usize,
 }
 
+/// The position of an iteration over a [data loader](DataLoader), used to resume it.
+#[derive(new, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataLoaderState {
+    /// The number of complete iterations over the data loader done before the current one.
+    pub epoch: usize,
+
+    /// The number of batches already yielded by the current iteration.
+    pub batches: usize,
+}
+
 /// A data loader iterator that can be used to iterate over a data loader.
 pub trait DataLoaderIterator<O>: Iterator<Item = O> {
     /// Returns the progress of the data loader.
@@ -25,6 +36,26 @@ pub trait DataLoader<B: Backend, O>: Send {
     /// Returns a boxed [iterator](DataLoaderIterator) to iterate over the data loader.
     fn iter<'a>(&'a self) -> Box<dyn DataLoaderIterator<O> + 'a>;
 
+    /// Returns a boxed [iterator](DataLoaderIterator) resuming the iteration at the given
+    /// [state](DataLoaderState), as if the data loader was iterated over `state.epoch` times
+    /// before, skipping the batches already yielded by the current iteration.
+    ///
+    /// The following calls to [iter](DataLoader::iter) continue from the resumed iteration.
+    ///
+    /// # Notes
+    ///
+    /// The default implementation only skips the batches, data loaders shuffling their items
+    /// differently at each iteration should replay their shuffling.
+    fn iter_from<'a>(&'a self, state: DataLoaderState) -> Box<dyn DataLoaderIterator<O> + 'a> {
+        let mut iterator = self.iter();
+        for _ in 0..state.batches {
+            if iterator.next().is_none() {
+                break;
+            }
+        }
+        iterator
+    }
+
     /// The number of items (not the number of batches nor the number of iterations),
     /// corresponding to the items_total of the progress returned by the iterator.
     fn num_items(&self) -> usize;
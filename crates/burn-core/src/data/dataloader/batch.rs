@@ -1,11 +1,16 @@
-use super::{BatchStrategy, DataLoader, DataLoaderIterator, Progress, batcher::Batcher};
+use super::{
+    BatchStrategy, DataLoader, DataLoaderIterator, DataLoaderState, Progress, batcher::Batcher,
+};
 use burn_dataset::{
     Dataset,
-    transform::{PartialDataset, ShuffledDataset},
+    transform::{IndexSampler, PartialDataset, SampledDataset, ShuffledDataset},
 };
 use burn_tensor::backend::Backend;
-use rand::{Rng, distr::StandardUniform};
-use std::sync::Arc;
+use rand::{Rng, SeedableRng, distr::StandardUniform, rngs::StdRng};
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
 
 /// A data loader that can be used to iterate over a dataset in batches.
 pub struct BatchDataLoader<B: Backend, I, O> {
@@ -14,6 +19,51 @@ pub struct BatchDataLoader<B: Backend, I, O> {
     batcher: Arc<dyn Batcher<B, I, O>>,
     device: B::Device,
     rng: Option<Arc<spin::Mutex<rand::rngs::StdRng>>>,
+    // The rng before the first iteration, to replay the shuffling when resuming an iteration.
+    rng_initial: Option<rand::rngs::StdRng>,
+    sampler: Option<SampledPart>,
+    // The number of iterations, which seeds the sampler without rng.
+    epoch: Arc<AtomicU64>,
+}
+
+/// The part of the items drawn by an [index sampler](IndexSampler) at each epoch that is loaded.
+#[derive(Clone)]
+pub(crate) struct SampledPart {
+    pub(crate) sampler: Arc<dyn IndexSampler>,
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+}
+
+impl SampledPart {
+    pub(crate) fn new(sampler: Arc<dyn IndexSampler>, len: usize) -> Self {
+        let end = sampler.num_items(len);
+        Self {
+            sampler,
+            start: 0,
+            end,
+        }
+    }
+
+    pub(crate) fn num_items(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// The part of this part between `start` and `end`.
+    pub(crate) fn slice(&self, start: usize, end: usize) -> Self {
+        Self {
+            sampler: self.sampler.clone(),
+            start: (self.start + start).min(self.end),
+            end: (self.start + end).min(self.end),
+        }
+    }
+
+    /// The indices of the part for an epoch, drawn with the given seed.
+    fn indices(&self, len: usize, seed: u64) -> Vec<usize> {
+        let mut indices = self.sampler.indices(len, &mut StdRng::seed_from_u64(seed));
+        indices.truncate(self.end);
+        indices.drain(..self.start.min(indices.len()));
+        indices
+    }
 }
 
 impl<B: Backend, I, O> Clone for BatchDataLoader<B, I, O> {
@@ -24,6 +74,9 @@ impl<B: Backend, I, O> Clone for BatchDataLoader<B, I, O> {
             batcher: self.batcher.clone(),
             device: self.device.clone(),
             rng: self.rng.clone(),
+            rng_initial: self.rng_initial.clone(),
+            sampler: self.sampler.clone(),
+            epoch: self.epoch.clone(),
         }
     }
 }
@@ -55,9 +108,36 @@ impl<B: Backend, I, O> BatchDataLoader<B, I, O> {
             dataset,
             batcher,
             device,
+            rng_initial: rng.clone(),
             rng: rng.map(|rng| Arc::new(spin::Mutex::new(rng))),
+            sampler: None,
+            epoch: Arc::new(AtomicU64::new(0)),
         }
     }
+
+    /// Draws the items of each epoch with an [index sampler](IndexSampler) instead of iterating
+    /// over the dataset.
+    ///
+    /// The sampler draws its indices from the rng when provided, and with the number of the
+    /// epoch as seed otherwise, so that every epoch loads different items in a reproducible way.
+    ///
+    /// # Arguments
+    ///
+    /// * `sampler` - The index sampler.
+    ///
+    /// # Returns
+    ///
+    /// The batch data loader.
+    pub fn with_sampler(mut self, sampler: Arc<dyn IndexSampler>) -> Self {
+        self.sampler = Some(SampledPart::new(sampler, self.dataset.len()));
+        self
+    }
+
+    /// Only loads a part of the items drawn by a sampler from the whole dataset.
+    pub(crate) fn with_sampled_part(mut self, part: SampledPart) -> Self {
+        self.sampler = Some(part);
+        self
+    }
 }
 
 /// A data loader iterator that can be used to iterate over a data loader.
@@ -69,37 +149,113 @@ struct BatchDataloaderIterator<B: Backend, I, O> {
     device: B::Device,
 }
 
-impl<B, I, O> DataLoader<B, O> for BatchDataLoader<B, I, O>
+impl<B, I, O> BatchDataLoader<B, I, O>
 where
     B: Backend,
     I: Send + Sync + Clone + 'static,
     O: Send + 'static,
 {
-    fn iter<'a>(&'a self) -> Box<dyn DataLoaderIterator<O> + 'a> {
+    /// The data loader of the items between `start` and `end`.
+    pub(crate) fn sliced(&self, start: usize, end: usize) -> Self {
+        let rng = self.rng.as_ref().map(|rng| {
+            let rng = rng.lock();
+            rng.clone()
+        });
+        let dataloader = match &self.sampler {
+            // The sampled items are sliced, the sampler drawing from the whole dataset.
+            Some(sampler) => Self::new(
+                self.strategy.clone_dyn(),
+                self.dataset.clone(),
+                self.batcher.clone(),
+                self.device.clone(),
+                rng,
+            )
+            .with_sampled_part(sampler.slice(start, end)),
+            None => Self::new(
+                self.strategy.clone_dyn(),
+                Arc::new(PartialDataset::new(self.dataset.clone(), start, end)),
+                self.batcher.clone(),
+                self.device.clone(),
+                rng,
+            ),
+        };
+        dataloader
+            .epoch
+            .store(self.epoch.load(Ordering::Relaxed), Ordering::Relaxed);
+        dataloader
+    }
+
+    fn iterator(&self) -> BatchDataloaderIterator<B, I, O> {
         // When starting a new iteration, we first check if the dataloader was created with an rng,
         // implying that we should shuffle the dataset beforehand, while advancing the current
         // rng to ensure that each new iteration shuffles the dataset differently.
-        let dataset = match &self.rng {
-            Some(rng) => {
-                let mut rng = rng.lock();
-
-                Arc::new(ShuffledDataset::with_seed(
-                    self.dataset.clone(),
-                    rng.sample(StandardUniform),
-                ))
-            }
-            None => self.dataset.clone(),
+        // With a sampler, the rng only provides the seed of the sampler, so that the shuffling
+        // is replayed the same way.
+        let seed = self
+            .rng
+            .as_ref()
+            .map(|rng| rng.lock().sample(StandardUniform));
+        let epoch = self.epoch.fetch_add(1, Ordering::Relaxed);
+        let dataset: Arc<dyn Dataset<I>> = match (&self.sampler, seed) {
+            (Some(sampler), seed) => Arc::new(SampledDataset::new(
+                self.dataset.clone(),
+                sampler.indices(self.dataset.len(), seed.unwrap_or(epoch)),
+            )),
+            (None, Some(seed)) => Arc::new(ShuffledDataset::with_seed(self.dataset.clone(), seed)),
+            (None, None) => self.dataset.clone(),
         };
-        Box::new(BatchDataloaderIterator::new(
+        BatchDataloaderIterator::new(
             self.strategy.clone_dyn(),
             dataset,
             self.batcher.clone(),
             self.device.clone(),
-        ))
+        )
+    }
+}
+
+impl<B, I, O> DataLoader<B, O> for BatchDataLoader<B, I, O>
+where
+    B: Backend,
+    I: Send + Sync + Clone + 'static,
+    O: Send + 'static,
+{
+    fn iter<'a>(&'a self) -> Box<dyn DataLoaderIterator<O> + 'a> {
+        Box::new(self.iterator())
+    }
+
+    fn iter_from<'a>(&'a self, state: DataLoaderState) -> Box<dyn DataLoaderIterator<O> + 'a> {
+        // The rng is reset to replay the shuffling of the previous iterations.
+        if let (Some(rng), Some(rng_initial)) = (&self.rng, &self.rng_initial) {
+            let mut rng = rng.lock();
+            *rng = rng_initial.clone();
+
+            for _ in 0..state.epoch {
+                let _seed: u64 = rng.sample(StandardUniform);
+            }
+        }
+        self.epoch.store(state.epoch as u64, Ordering::Relaxed);
+
+        let mut iterator = self.iterator();
+        match iterator.strategy.skip(state.batches) {
+            Some(num_items) => {
+                iterator.current_index = num_items.min(self.num_items());
+            }
+            None => {
+                for _ in 0..state.batches {
+                    if iterator.next_items().is_none() {
+                        break;
+                    }
+                }
+            }
+        }
+        Box::new(iterator)
     }
 
     fn num_items(&self) -> usize {
-        self.dataset.len()
+        match &self.sampler {
+            Some(sampler) => sampler.num_items(),
+            None => self.dataset.len(),
+        }
     }
 
     fn to_device(&self, device: &B::Device) -> Arc<dyn DataLoader<B, O>> {
@@ -107,28 +263,24 @@ where
             let rng = rng.lock();
             rng.clone()
         });
-        Arc::new(Self::new(
+        let mut dataloader = Self::new(
             self.strategy.clone_dyn(),
             self.dataset.clone(),
             self.batcher.clone(),
             device.clone(),
             rng,
-        ))
+        );
+        if let Some(sampler) = &self.sampler {
+            dataloader = dataloader.with_sampled_part(sampler.clone());
+        }
+        dataloader
+            .epoch
+            .store(self.epoch.load(Ordering::Relaxed), Ordering::Relaxed);
+        Arc::new(dataloader)
     }
 
     fn slice(&self, start: usize, end: usize) -> Arc<dyn DataLoader<B, O>> {
-        let rng = self.rng.as_ref().map(|rng| {
-            let rng = rng.lock();
-            rng.clone()
-        });
-        let dataloader = Self::new(
-            self.strategy.clone_dyn(),
-            Arc::new(PartialDataset::new(self.dataset.clone(), start, end)),
-            self.batcher.clone(),
-            self.device.clone(),
-            rng,
-        );
-        Arc::new(dataloader)
+        Arc::new(self.sliced(start, end))
     }
 }
 
@@ -159,26 +311,28 @@ impl<B: Backend, I, O> BatchDataloaderIterator<B, I, O> {
             device,
         }
     }
-}
-
-impl<B: Backend, I, O> Iterator for BatchDataloaderIterator<B, I, O> {
-    type Item = O;
 
-    fn next(&mut self) -> Option<O> {
+    /// The items of the next batch, without batching them.
+    fn next_items(&mut self) -> Option<Vec<I>> {
         while let Some(item) = self.dataset.get(self.current_index) {
             self.current_index += 1;
             self.strategy.add(item);
 
             if let Some(items) = self.strategy.batch(false) {
-                return Some(self.batcher.batch(items, &self.device));
+                return Some(items);
             }
         }
 
-        if let Some(items) = self.strategy.batch(true) {
-            return Some(self.batcher.batch(items, &self.device));
-        }
+        self.strategy.batch(true)
+    }
+}
+
+impl<B: Backend, I, O> Iterator for BatchDataloaderIterator<B, I, O> {
+    type Item = O;
 
-        None
+    fn next(&mut self) -> Option<O> {
+        self.next_items()
+            .map(|items| self.batcher.batch(items, &self.device))
     }
 }
 
@@ -193,9 +347,10 @@ mod tests {
     use std::collections::HashSet;
 
     use super::*;
-    use crate::data::dataloader::FixBatchStrategy;
     use crate::data::dataloader::batcher::TestBatcher;
+    use crate::data::dataloader::{FixBatchStrategy, ScheduledBatchStrategy};
     use crate::data::dataset::FakeDataset;
+    use burn_dataset::transform::StratifiedSampler;
 
     #[test]
     fn test_batch_dataloader() {
@@ -259,4 +414,116 @@ mod tests {
 
         assert_eq!(items_dataloader, items_dataloader_slice);
     }
+
+    #[test]
+    fn test_batch_dataloader_iter_from_replays_shuffling() {
+        let dataset = Arc::new(FakeDataset::<String>::new(11));
+        let new_dataloader = || {
+            BatchDataLoader::new(
+                Box::new(FixBatchStrategy::new(3)),
+                dataset.clone(),
+                Arc::new(TestBatcher::new()),
+                Default::default(),
+                Some(rand::SeedableRng::seed_from_u64(42)),
+            )
+        };
+        let dataloader = new_dataloader();
+        let epochs = (0..3)
+            .map(|_| dataloader.iter().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_ne!(epochs[0], epochs[1]);
+
+        let dataloader = new_dataloader();
+        let resumed = dataloader
+            .iter_from(DataLoaderState::new(1, 2))
+            .collect::<Vec<_>>();
+
+        assert_eq!(resumed, epochs[1][2..]);
+        assert_eq!(dataloader.iter().collect::<Vec<_>>(), epochs[2]);
+    }
+
+    #[test]
+    fn test_batch_dataloader_iter_from_skips_scheduled_batches() {
+        let dataset = Arc::new(burn_dataset::InMemDataset::new(
+            (0..14).collect::<Vec<usize>>(),
+        ));
+        let dataloader = BatchDataLoader::new(
+            Box::new(ScheduledBatchStrategy::linear(2, 6, 8)),
+            dataset,
+            Arc::new(TestBatcher::new()),
+            Default::default(),
+            None,
+        );
+
+        let batches = dataloader.iter().collect::<Vec<_>>();
+        let resumed = dataloader
+            .iter_from(DataLoaderState::new(0, 2))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            batches.iter().map(Vec::len).collect::<Vec<_>>(),
+            [2, 3, 5, 4]
+        );
+        assert_eq!(resumed, batches[2..]);
+        // The previous iterations don't change the batch sizes.
+        assert_eq!(dataloader.iter().collect::<Vec<_>>(), batches);
+    }
+
+    #[test]
+    fn test_batch_dataloader_with_sampler() {
+        let labels = (0..12).map(|index| index % 3 / 2).collect::<Vec<_>>();
+        let dataset = Arc::new(burn_dataset::InMemDataset::new(labels.clone()));
+        let dataloader = BatchDataLoader::new(
+            Box::new(FixBatchStrategy::new(3)),
+            dataset,
+            Arc::new(TestBatcher::new()),
+            Default::default(),
+            Some(rand::SeedableRng::seed_from_u64(7)),
+        )
+        .with_sampler(Arc::new(StratifiedSampler::new(labels, 3)));
+
+        let epochs = (0..2)
+            .map(|_| dataloader.iter().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        for batch in epochs.iter().flatten() {
+            let mut batch = batch.clone();
+            batch.sort();
+            assert_eq!(batch, vec![0, 0, 1]);
+        }
+
+        let resumed = dataloader
+            .iter_from(DataLoaderState::new(1, 1))
+            .collect::<Vec<_>>();
+        assert_eq!(resumed, epochs[1][1..]);
+
+        let sliced = dataloader.slice(3, 9);
+        assert_eq!(sliced.num_items(), 6);
+        assert_eq!(sliced.iter().count(), 2);
+    }
+
+    #[test]
+    fn test_batch_dataloader_with_sampler_without_rng() {
+        let labels = (0..12).map(|index| index % 3 / 2).collect::<Vec<_>>();
+        let dataset = Arc::new(burn_dataset::InMemDataset::new(
+            (0..12).collect::<Vec<usize>>(),
+        ));
+        let dataloader = BatchDataLoader::new(
+            Box::new(FixBatchStrategy::new(3)),
+            dataset,
+            Arc::new(TestBatcher::new()),
+            Default::default(),
+            None,
+        )
+        .with_sampler(Arc::new(StratifiedSampler::new(labels, 3)));
+
+        let epochs = (0..2)
+            .map(|_| dataloader.iter().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_ne!(epochs[0], epochs[1]);
+
+        let resumed = dataloader
+            .iter_from(DataLoaderState::new(1, 1))
+            .collect::<Vec<_>>();
+        assert_eq!(resumed, epochs[1][1..]);
+    }
 }
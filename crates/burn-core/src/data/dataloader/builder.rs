@@ -1,8 +1,8 @@
 use super::{
-    BatchDataLoader, BatchStrategy, DataLoader, FixBatchStrategy, MultiThreadDataLoader,
-    batcher::Batcher,
+    BatchDataLoader, BatchStrategy, DataLoader, DistributedSampler, FixBatchStrategy,
+    MultiThreadDataLoader, StreamDataLoader, batcher::Batcher,
 };
-use burn_dataset::Dataset;
+use burn_dataset::{Dataset, IterableDataset, transform::IndexSampler};
 use burn_tensor::backend::Backend;
 use rand::{SeedableRng, rngs::StdRng};
 use std::sync::Arc;
@@ -12,7 +12,11 @@ pub struct DataLoaderBuilder<B: Backend, I, O> {
     strategy: Option<Box<dyn BatchStrategy<I>>>,
     batcher: Arc<dyn Batcher<B, I, O>>,
     num_threads: Option<usize>,
+    ordered: bool,
     shuffle: Option<u64>,
+    shuffle_buffer: Option<usize>,
+    distributed: Option<DistributedSampler>,
+    sampler: Option<Arc<dyn IndexSampler>>,
     device: Option<B::Device>,
 }
 
@@ -39,7 +43,11 @@ where
             batcher: Arc::new(batcher),
             strategy: None,
             num_threads: None,
+            ordered: false,
             shuffle: None,
+            shuffle_buffer: None,
+            distributed: None,
+            sampler: None,
             device: None,
         }
     }
@@ -59,6 +67,25 @@ where
         self
     }
 
+    /// Sets the [strategy](BatchStrategy) grouping the items into batches, e.g. a
+    /// [token budget](super::TokenBudgetBatchStrategy), [bucket](super::BucketBatchStrategy) or
+    /// [scheduled](super::ScheduledBatchStrategy) strategy for variable-length items.
+    ///
+    /// # Arguments
+    ///
+    /// * `strategy` - The batch strategy.
+    ///
+    /// # Returns
+    ///
+    /// The data loader builder.
+    pub fn batch_strategy<S>(mut self, strategy: S) -> Self
+    where
+        S: BatchStrategy<I> + 'static,
+    {
+        self.strategy = Some(Box::new(strategy));
+        self
+    }
+
     /// Sets the seed for shuffling.
     ///
     /// Each time the dataloader starts a new iteration, the dataset will be shuffled.
@@ -75,6 +102,69 @@ where
         self
     }
 
+    /// Sets the number of items of the [shuffle buffer](burn_dataset::transform::ShuffleBuffer)
+    /// of the data loaders built from an [iterable dataset](IterableDataset) when
+    /// [shuffling](Self::shuffle) is enabled, which defaults to
+    /// [DEFAULT_SHUFFLE_BUFFER_SIZE](super::DEFAULT_SHUFFLE_BUFFER_SIZE).
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The number of items.
+    ///
+    /// # Returns
+    ///
+    /// The data loader builder.
+    pub fn shuffle_buffer(mut self, size: usize) -> Self {
+        self.shuffle_buffer = Some(size);
+        self
+    }
+
+    /// Only load the shard of the dataset of the current rank of a distributed training.
+    ///
+    /// When [shuffling](Self::shuffle) is enabled, the items are randomly assigned to the ranks
+    /// at each iteration with the shuffle seed, which must be the same on all the ranks.
+    ///
+    /// # Arguments
+    ///
+    /// * `sampler` - The rank and the number of ranks.
+    ///
+    /// # Returns
+    ///
+    /// The data loader builder.
+    pub fn distributed(mut self, sampler: DistributedSampler) -> Self {
+        self.distributed = Some(sampler);
+        self
+    }
+
+    /// Draws the items of each iteration with an [index sampler](IndexSampler), e.g. a
+    /// [weighted](burn_dataset::transform::WeightedSampler),
+    /// [stratified](burn_dataset::transform::StratifiedSampler) or
+    /// [bucket](burn_dataset::transform::BucketSampler) sampler, instead of iterating over the
+    /// dataset.
+    ///
+    /// The sampler draws from the [shuffle](Self::shuffle) seed when set, and from the number of
+    /// the iteration otherwise, so that every iteration loads different items in a reproducible
+    /// way.
+    ///
+    /// In a [distributed](Self::distributed) training, all the ranks draw the same items and
+    /// each rank loads one item out of `world_size`, so the samplers controlling the content of
+    /// the batches should use a batch size of `batch_size * world_size`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sampler` - The index sampler.
+    ///
+    /// # Returns
+    ///
+    /// The data loader builder.
+    pub fn sampler<S>(mut self, sampler: S) -> Self
+    where
+        S: IndexSampler + 'static,
+    {
+        self.sampler = Some(Arc::new(sampler));
+        self
+    }
+
     /// Sets the number of workers.
     ///
     /// # Arguments
@@ -89,6 +179,19 @@ where
         self
     }
 
+    /// Yields the batches from each [worker](Self::num_workers) in turn, instead of as soon as
+    /// they are loaded, so that the iterations are reproducible and can be resumed exactly.
+    ///
+    /// See [ordered polling](MultiThreadDataLoader::with_ordered_polling).
+    ///
+    /// # Returns
+    ///
+    /// The data loader builder.
+    pub fn ordered_workers(mut self) -> Self {
+        self.ordered = true;
+        self
+    }
+
     /// Sets the data loader device.
     ///
     /// # Arguments
@@ -116,7 +219,17 @@ where
     where
         D: Dataset<I> + 'static,
     {
-        let dataset = Arc::new(dataset);
+        let (dataset, sampler): (Arc<dyn Dataset<I>>, _) = match (self.distributed, self.sampler) {
+            (Some(distributed), Some(sampler)) => (
+                Arc::new(dataset),
+                Some(Arc::new(distributed.shard(sampler)) as Arc<dyn IndexSampler>),
+            ),
+            (Some(distributed), None) => (
+                Arc::new(dataset),
+                Some(Arc::new(distributed.shard_epochs(self.shuffle.is_some())) as Arc<_>),
+            ),
+            (None, sampler) => (Arc::new(dataset), sampler),
+        };
 
         let device = self.device.unwrap_or_default();
         let rng = self.shuffle.map(StdRng::seed_from_u64);
@@ -125,31 +238,110 @@ where
             None => Box::new(FixBatchStrategy::new(1)),
         };
         if let Some(num_threads) = self.num_threads {
-            return Arc::new(MultiThreadDataLoader::new(
+            let mut dataloader = MultiThreadDataLoader::new(
                 strategy,
                 dataset,
                 self.batcher,
                 num_threads,
                 device,
                 rng,
-            ));
+            );
+            if self.ordered {
+                dataloader = dataloader.with_ordered_polling();
+            }
+            return match sampler {
+                Some(sampler) => Arc::new(dataloader.with_sampler(sampler)),
+                None => Arc::new(dataloader),
+            };
         }
 
-        Arc::new(BatchDataLoader::new(
+        let dataloader = BatchDataLoader::new(strategy, dataset, self.batcher, device, rng);
+        match sampler {
+            Some(sampler) => Arc::new(dataloader.with_sampler(sampler)),
+            None => Arc::new(dataloader),
+        }
+    }
+
+    /// Builds a [stream data loader](StreamDataLoader) for an iterable dataset.
+    ///
+    /// The shards of the dataset are assigned in turn to the [workers](Self::num_workers),
+    /// and to the ranks of a [distributed](Self::distributed) training. A worker without any
+    /// shard loads no item, so the dataset should have at least as many shards as workers on
+    /// every rank. Every rank must load the same number of batches for the gradients to be
+    /// reduced in sync, so the shards must be split evenly between the ranks and have the same
+    /// number of items.
+    ///
+    /// # Arguments
+    ///
+    /// * `dataset` - The iterable dataset.
+    ///
+    /// # Returns
+    ///
+    /// The data loader.
+    ///
+    /// # Panics
+    ///
+    /// If a [sampler](Self::sampler) is set, since samplers require random access to the items,
+    /// or if the number of shards isn't a multiple of the number of ranks of a distributed
+    /// training.
+    pub fn build_iterable<D>(self, dataset: D) -> Arc<dyn DataLoader<B, O>>
+    where
+        D: IterableDataset<I> + 'static,
+    {
+        assert!(
+            self.sampler.is_none(),
+            "Index samplers require a dataset with random access"
+        );
+        if let Some(sampler) = &self.distributed {
+            let num_shards = dataset.num_shards();
+            assert!(
+                num_shards % sampler.world_size() == 0,
+                "The {num_shards} shards of the dataset can't be split evenly between the {} ranks",
+                sampler.world_size()
+            );
+        }
+        let strategy = match self.strategy {
+            Some(strategy) => strategy,
+            None => Box::new(FixBatchStrategy::new(1)),
+        };
+        let mut dataloader = StreamDataLoader::new(
             strategy,
-            dataset,
+            Arc::new(dataset),
             self.batcher,
-            device,
-            rng,
-        ))
+            self.device.unwrap_or_default(),
+            self.shuffle.map(StdRng::seed_from_u64),
+        );
+        if let Some(size) = self.shuffle_buffer {
+            dataloader = dataloader.with_shuffle_buffer(size);
+        }
+        if let Some(sampler) = self.distributed {
+            dataloader = dataloader.with_worker(sampler.rank(), sampler.world_size());
+        }
+
+        match self.num_threads {
+            Some(num_threads) => {
+                let dataloader = MultiThreadDataLoader::from_stream(dataloader, num_threads);
+                match self.ordered {
+                    true => Arc::new(dataloader.with_ordered_polling()),
+                    false => Arc::new(dataloader),
+                }
+            }
+            None => Arc::new(dataloader),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::data::dataset::FakeDataset;
-    use crate::{TestBackend, data::dataloader::batcher::Batcher};
+    use crate::data::dataloader::TokenBudgetBatchStrategy;
+    use crate::data::dataset::{
+        FakeDataset, InMemDataset, ShardedDataset, transform::BucketSampler,
+    };
+    use crate::{
+        TestBackend,
+        data::dataloader::batcher::{Batcher, TestBatcher},
+    };
 
     #[test]
     fn test_dataloader_default_device() {
@@ -178,6 +370,96 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_dataloader_sampler_multi_thread() {
+        let lengths = (0..30).map(|index| (index * 7) % 30).collect::<Vec<_>>();
+        let build = |num_workers| {
+            let builder = DataLoaderBuilder::new(TestBatcher::new())
+                .batch_size(5)
+                .shuffle(42)
+                .sampler(BucketSampler::new(lengths.clone(), 5));
+            let builder = match num_workers {
+                Some(num_workers) => builder.num_workers(num_workers),
+                None => builder,
+            };
+            builder.build(InMemDataset::new(lengths.clone()))
+        };
+
+        let batches = build(None).iter().collect::<Vec<Vec<usize>>>();
+        for batch in batches.iter() {
+            assert_eq!(batch.iter().max().unwrap() - batch.iter().min().unwrap(), 4);
+        }
+
+        // The threads load the same batches, in a different order, even when the items aren't
+        // evenly split between the threads.
+        let mut batches = batches;
+        batches.sort();
+        for num_workers in [2, 4] {
+            let mut batches_threads = build(Some(num_workers)).iter().collect::<Vec<_>>();
+            batches_threads.sort();
+            assert_eq!(batches, batches_threads);
+        }
+    }
+
+    #[test]
+    fn test_dataloader_distributed_reshuffles_the_shards() {
+        let build = |rank| {
+            DataLoaderBuilder::new(TestBatcher::new())
+                .batch_size(2)
+                .shuffle(42)
+                .distributed(DistributedSampler::new(rank, 2))
+                .build(InMemDataset::new((0..8).collect::<Vec<usize>>()))
+        };
+        let items = |dataloader: &Arc<dyn DataLoader<TestBackend, Vec<usize>>>| {
+            let mut items = dataloader.iter().flatten().collect::<Vec<_>>();
+            items.sort();
+            items
+        };
+        let dataloaders = [build(0), build(1)];
+
+        let epochs = (0..2)
+            .map(|_| dataloaders.each_ref().map(items))
+            .collect::<Vec<_>>();
+
+        for [rank_0, rank_1] in epochs.iter() {
+            let mut all = [rank_0.clone(), rank_1.clone()].concat();
+            all.sort();
+            assert_eq!(all, (0..8).collect::<Vec<_>>());
+        }
+        assert_ne!(epochs[0][0], epochs[1][0]);
+    }
+
+    #[test]
+    #[should_panic = "The 3 shards of the dataset can't be split evenly between the 2 ranks"]
+    fn test_dataloader_iterable_distributed_uneven_shards() {
+        let _dataloader = DataLoaderBuilder::new(TestBatcher::new())
+            .batch_size(2)
+            .distributed(DistributedSampler::new(0, 2))
+            .build_iterable(ShardedDataset::new(
+                InMemDataset::new((0..6).collect::<Vec<usize>>()),
+                3,
+            ));
+    }
+
+    #[test]
+    fn test_dataloader_token_budget_multi_thread() {
+        let build = |num_workers| {
+            DataLoaderBuilder::new(TestBatcher::new())
+                .batch_strategy(TokenBudgetBatchStrategy::new(12, |length: &usize| *length))
+                .num_workers(num_workers)
+                .build(InMemDataset::new((1..=16).map(|index| index % 8).collect()))
+        };
+
+        for num_workers in [1, 2] {
+            let batches = build(num_workers).iter().collect::<Vec<Vec<usize>>>();
+            assert_eq!(batches.iter().map(Vec::len).sum::<usize>(), 16);
+            for batch in batches {
+                let max = batch.iter().max().unwrap();
+                assert!(batch.len() == 1 || batch.len() * max <= 12);
+            }
+        }
+    }
+
     #[test]
     fn test_dataloader_slice_multi_device() {
         type TestDevice = <TestBackend as Backend>::Device;
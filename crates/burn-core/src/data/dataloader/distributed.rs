@@ -0,0 +1,246 @@
+use burn_dataset::{Dataset, transform::IndexSampler};
+use rand::{SeedableRng, rngs::StdRng, seq::SliceRandom};
+use std::sync::Arc;
+
+/// Shards a dataset between the ranks of a distributed training, so that each process only
+/// loads its own part of the dataset.
+///
+/// Every rank gets the same number of items, repeating items from the start of the dataset
+/// when it can't be evenly split, so that all the ranks run the same number of iterations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DistributedSampler {
+    rank: usize,
+    world_size: usize,
+}
+
+impl DistributedSampler {
+    /// Creates the sampler of the given rank.
+    ///
+    /// # Arguments
+    ///
+    /// * `rank` - The rank of the current process.
+    /// * `world_size` - The number of processes.
+    ///
+    /// # Panics
+    ///
+    /// If the world size is zero or if the rank isn't lower than the world size.
+    pub fn new(rank: usize, world_size: usize) -> Self {
+        assert!(world_size > 0, "The world size should be at least 1");
+        assert!(
+            rank < world_size,
+            "The rank {rank} should be lower than the world size {world_size}"
+        );
+
+        Self { rank, world_size }
+    }
+
+    /// The rank of the current process.
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// The number of processes.
+    pub fn world_size(&self) -> usize {
+        self.world_size
+    }
+
+    /// The number of items of each rank for a dataset of the given length.
+    pub fn num_items(&self, len: usize) -> usize {
+        len.div_ceil(self.world_size)
+    }
+
+    /// The indices of the items of the current rank for a dataset of the given length.
+    ///
+    /// When a seed is provided, the items are assigned randomly to the ranks, otherwise they are
+    /// assigned in a round-robin fashion. The seed must be the same for all the ranks.
+    pub fn indices(&self, len: usize, seed: Option<u64>) -> Vec<usize> {
+        let mut indices = (0..len).collect::<Vec<_>>();
+        if let Some(seed) = seed {
+            indices.shuffle(&mut StdRng::seed_from_u64(seed));
+        }
+
+        let total = self.num_items(len) * self.world_size;
+        (0..total)
+            .skip(self.rank)
+            .step_by(self.world_size)
+            .filter_map(|index| indices.get(index % len.max(1)).copied())
+            .collect()
+    }
+
+    /// Only keep the items of the current rank from the dataset.
+    ///
+    /// The shard is fixed, see [shard_epochs](Self::shard_epochs) to assign the items to the
+    /// ranks again at each epoch.
+    pub fn sample<I>(
+        &self,
+        dataset: Arc<dyn Dataset<I>>,
+        seed: Option<u64>,
+    ) -> DistributedDataset<I> {
+        let indices = self.indices(dataset.len(), seed);
+        DistributedDataset { dataset, indices }
+    }
+
+    /// Only keep the items of the current rank from the items drawn by an
+    /// [index sampler](IndexSampler).
+    ///
+    /// All the ranks draw the same items when their samplers use the same seed, each rank keeping
+    /// one item out of `world_size` of the sampled items.
+    pub fn shard(&self, sampler: Arc<dyn IndexSampler>) -> ShardedSampler {
+        ShardedSampler {
+            distributed: *self,
+            sampler,
+        }
+    }
+
+    /// Only keep the items of the current rank from the items of each epoch.
+    ///
+    /// When `shuffle` is set, the items are randomly assigned to the ranks at each epoch with the
+    /// rng of the data loader, which must be seeded the same way on all the ranks. Otherwise, they
+    /// are assigned in a round-robin fashion, as with [indices](Self::indices).
+    pub fn shard_epochs(&self, shuffle: bool) -> ShardedSampler {
+        self.shard(Arc::new(EpochItems { shuffle }))
+    }
+}
+
+/// All the items of a dataset, in a different order at each epoch when shuffled.
+struct EpochItems {
+    shuffle: bool,
+}
+
+impl IndexSampler for EpochItems {
+    fn indices(&self, len: usize, rng: &mut StdRng) -> Vec<usize> {
+        let mut indices = (0..len).collect::<Vec<_>>();
+        if self.shuffle {
+            indices.shuffle(rng);
+        }
+        indices
+    }
+}
+
+/// The items of one rank of a distributed training among the items drawn by an index sampler,
+/// see [DistributedSampler::shard].
+pub struct ShardedSampler {
+    distributed: DistributedSampler,
+    sampler: Arc<dyn IndexSampler>,
+}
+
+impl IndexSampler for ShardedSampler {
+    fn num_items(&self, len: usize) -> usize {
+        self.distributed.num_items(self.sampler.num_items(len))
+    }
+
+    fn indices(&self, len: usize, rng: &mut StdRng) -> Vec<usize> {
+        let indices = self.sampler.indices(len, rng);
+        self.distributed
+            .indices(indices.len(), None)
+            .into_iter()
+            .map(|index| indices[index])
+            .collect()
+    }
+}
+
+/// The shard of a dataset loaded by one rank of a distributed training, see
+/// [DistributedSampler].
+pub struct DistributedDataset<I> {
+    dataset: Arc<dyn Dataset<I>>,
+    indices: Vec<usize>,
+}
+
+impl<I> Dataset<I> for DistributedDataset<I>
+where
+    I: Clone + Send + Sync,
+{
+    fn get(&self, index: usize) -> Option<I> {
+        let index = self.indices.get(index)?;
+        self.dataset.get(*index)
+    }
+
+    fn len(&self) -> usize {
+        self.indices.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use burn_dataset::{InMemDataset, transform::WeightedSampler};
+
+    #[test]
+    fn test_round_robin_shards_are_padded() {
+        let shards = (0..3)
+            .map(|rank| DistributedSampler::new(rank, 3).indices(7, None))
+            .collect::<Vec<_>>();
+
+        assert_eq!(shards, vec![vec![0, 3, 6], vec![1, 4, 0], vec![2, 5, 1]]);
+    }
+
+    #[test]
+    fn test_shuffled_shards_cover_the_dataset() {
+        let mut indices = (0..4)
+            .flat_map(|rank| DistributedSampler::new(rank, 4).indices(20, Some(42)))
+            .collect::<Vec<_>>();
+        indices.sort();
+
+        assert_eq!(indices, (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_sharded_sampler() {
+        let sampler = Arc::new(WeightedSampler::new(vec![1.0; 10], 7));
+        let expected = sampler.indices(10, &mut StdRng::seed_from_u64(3));
+
+        let shards = (0..2)
+            .map(|rank| {
+                let shard = DistributedSampler::new(rank, 2).shard(sampler.clone());
+                assert_eq!(shard.num_items(10), 4);
+                shard.indices(10, &mut StdRng::seed_from_u64(3))
+            })
+            .collect::<Vec<_>>();
+
+        let round_robin = |rank: usize| {
+            (rank..8)
+                .step_by(2)
+                .map(|index| expected[index % 7])
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(shards, vec![round_robin(0), round_robin(1)]);
+    }
+
+    #[test]
+    fn test_distributed_dataset() {
+        let dataset = Arc::new(InMemDataset::new(vec!["a", "b", "c", "d", "e"]));
+        let shard = DistributedSampler::new(1, 2).sample(dataset, None);
+
+        assert_eq!(shard.iter().collect::<Vec<_>>(), vec!["b", "d", "a"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "world size should be at least 1")]
+    fn test_empty_world_is_rejected() {
+        DistributedSampler::new(0, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "rank 2 should be lower than the world size 2")]
+    fn test_out_of_bounds_rank_is_rejected() {
+        DistributedSampler::new(2, 2);
+    }
+
+    #[test]
+    fn test_shards_change_between_epochs() {
+        let shards = (0..2)
+            .map(|rank| {
+                let shard = DistributedSampler::new(rank, 2).shard_epochs(true);
+                let mut rng = StdRng::seed_from_u64(42);
+                [shard.indices(8, &mut rng), shard.indices(8, &mut rng)]
+            })
+            .collect::<Vec<_>>();
+
+        for (rank_0, rank_1) in shards[0].iter().zip(shards[1].iter()) {
+            let mut indices = [rank_0.clone(), rank_1.clone()].concat();
+            indices.sort();
+            assert_eq!(indices, (0..8).collect::<Vec<_>>());
+        }
+        assert_ne!(shards[0][0], shards[0][1]);
+    }
+}
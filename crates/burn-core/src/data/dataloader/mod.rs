@@ -1,8 +1,15 @@
 mod base;
 mod batch;
 mod builder;
+mod distributed;
 mod multithread;
 mod strategy;
+mod stream;
+
+#[cfg(feature = "audio")]
+mod audio;
+#[cfg(feature = "text")]
+mod text;
 
 /// Module for batching items.
 pub mod batcher;
@@ -12,5 +19,12 @@ pub mod split;
 pub use base::*;
 pub use batch::*;
 pub use builder::*;
+pub use distributed::*;
 pub use multithread::*;
 pub use strategy::*;
+pub use stream::*;
+
+#[cfg(feature = "audio")]
+pub use audio::*;
+#[cfg(feature = "text")]
+pub use text::*;
@@ -1,12 +1,16 @@
 use burn_dataset::Dataset;
-use burn_dataset::transform::PartialDataset;
+use burn_dataset::transform::{IndexSampler, PartialDataset};
 use burn_tensor::backend::Backend;
 use rand::SeedableRng;
 use rand::distr::{Distribution, StandardUniform};
 use rand::rngs::StdRng;
 
+use super::batch::SampledPart;
 use super::batcher::Batcher;
-use super::{BatchDataLoader, BatchStrategy, DataLoader, DataLoaderIterator, Progress};
+use super::{
+    BatchDataLoader, BatchStrategy, DataLoader, DataLoaderIterator, DataLoaderState, Progress,
+    StreamDataLoader,
+};
 use core::cell::OnceCell;
 use std::sync::{Arc, mpsc};
 use std::thread;
@@ -14,17 +18,62 @@ use std::thread;
 const MAX_QUEUED_ITEMS: usize = 100;
 
 /// A multi-threaded data loader that can be used to iterate over a dataset.
+///
+/// Each thread loads the batches of a contiguous part of the dataset, or of its own shards for a
+/// [stream data loader](StreamDataLoader), and the batches are yielded as soon as a thread has
+/// loaded them, unless the threads are [polled in turn](Self::with_ordered_polling).
 pub struct MultiThreadDataLoader<B: Backend, I, O> {
     // Configuration parameters needed for initialization
-    strategy: Box<dyn BatchStrategy<I>>,
-    dataset: Arc<dyn Dataset<I>>,
-    batcher: Arc<dyn Batcher<B, I, O>>,
-    device: B::Device,
-    rng: Option<rand::rngs::StdRng>,
+    source: Source<B, I, O>,
     num_threads: usize,
+    ordered: bool,
 
     // The lazily initialized data loaders
-    dataloaders: OnceCell<Vec<BatchDataLoader<B, I, O>>>,
+    dataloaders: OnceCell<Vec<ThreadDataLoader<B, I, O>>>,
+}
+
+/// The items loaded by the threads.
+enum Source<B: Backend, I, O> {
+    /// A dataset split into contiguous parts, one per thread.
+    Dataset {
+        strategy: Box<dyn BatchStrategy<I>>,
+        dataset: Arc<dyn Dataset<I>>,
+        batcher: Arc<dyn Batcher<B, I, O>>,
+        device: B::Device,
+        rng: Option<rand::rngs::StdRng>,
+        sampler: Option<SampledPart>,
+    },
+    /// An iterable dataset whose shards are assigned to the threads in turn.
+    Stream(StreamDataLoader<B, I, O>),
+}
+
+/// The data loader of a thread.
+enum ThreadDataLoader<B: Backend, I, O> {
+    Batch(BatchDataLoader<B, I, O>),
+    Stream(StreamDataLoader<B, I, O>),
+}
+
+impl<B: Backend, I, O> Clone for ThreadDataLoader<B, I, O> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Batch(dataloader) => Self::Batch(dataloader.clone()),
+            Self::Stream(dataloader) => Self::Stream(dataloader.clone()),
+        }
+    }
+}
+
+impl<B, I, O> ThreadDataLoader<B, I, O>
+where
+    B: Backend,
+    I: Send + Sync + Clone + 'static,
+    O: Send + 'static,
+{
+    fn dataloader(&self) -> &dyn DataLoader<B, O> {
+        match self {
+            Self::Batch(dataloader) => dataloader,
+            Self::Stream(dataloader) => dataloader,
+        }
+    }
 }
 
 /// A message that can be sent between threads.
@@ -38,9 +87,12 @@ pub enum Message<O> {
 }
 
 struct MultiThreadsDataloaderIterator<O> {
+    ordered: bool,
+    current: usize,
     num_done: usize,
+    done: Vec<bool>,
     workers: Vec<thread::JoinHandle<()>>,
-    receiver: mpsc::Receiver<Message<O>>,
+    receivers: Vec<mpsc::Receiver<Message<O>>>,
     progresses: Vec<Progress>,
 }
 
@@ -73,24 +125,137 @@ where
         rng: Option<rand::rngs::StdRng>,
     ) -> Self {
         Self {
-            strategy,
-            dataset,
-            batcher,
+            source: Source::Dataset {
+                strategy,
+                dataset,
+                batcher,
+                device,
+                rng,
+                sampler: None,
+            },
             num_threads,
-            device,
-            rng,
+            ordered: false,
+            dataloaders: OnceCell::new(),
+        }
+    }
+
+    /// Draws the items of each epoch with an [index sampler](IndexSampler), each thread loading
+    /// a contiguous part of the sampled items.
+    ///
+    /// # Arguments
+    ///
+    /// * `sampler` - The index sampler.
+    ///
+    /// # Returns
+    ///
+    /// The multi-threaded batch data loader.
+    ///
+    /// # Panics
+    ///
+    /// If the data loader was created [from a stream data loader](Self::from_stream).
+    pub fn with_sampler(mut self, sampler: Arc<dyn IndexSampler>) -> Self {
+        match &mut self.source {
+            Source::Dataset {
+                dataset,
+                sampler: part,
+                ..
+            } => *part = Some(SampledPart::new(sampler, dataset.len())),
+            Source::Stream(_) => panic!("Index samplers require a dataset with random access"),
+        }
+        self
+    }
+
+    /// Yields the batches from each thread in turn, so that the order of the batches doesn't
+    /// depend on the scheduling of the threads.
+    ///
+    /// # Returns
+    ///
+    /// The multi-threaded data loader.
+    ///
+    /// # Notes
+    ///
+    /// Polling the threads in turn makes the iterations reproducible, so an iteration can be
+    /// [resumed](DataLoader::iter_from) by counting the batches already yielded by each thread,
+    /// while the batches loaded first are otherwise skipped. The cost is that a thread slower than
+    /// the others, e.g. loading larger items, delays the batches of the other threads once their
+    /// queues are full, instead of them being yielded first.
+    pub fn with_ordered_polling(mut self) -> Self {
+        self.ordered = true;
+        self
+    }
+
+    /// Creates a new multi-threaded data loader from a [stream data loader](StreamDataLoader),
+    /// splitting its shards between the threads.
+    ///
+    /// # Arguments
+    ///
+    /// * `dataloader` - The stream data loader.
+    /// * `num_threads` - The number of threads.
+    ///
+    /// # Returns
+    ///
+    /// The multi-threaded stream data loader.
+    pub fn from_stream(dataloader: StreamDataLoader<B, I, O>, num_threads: usize) -> Self {
+        Self {
+            source: Source::Stream(dataloader),
+            num_threads,
+            ordered: false,
             dataloaders: OnceCell::new(),
         }
     }
 
     /// Force initialization if needed.
-    fn initialize(&self) -> &[BatchDataLoader<B, I, O>] {
+    fn initialize(&self) -> &[ThreadDataLoader<B, I, O>] {
         self.dataloaders
             .get_or_init(|| {
-                let datasets = PartialDataset::split(self.dataset.clone(), self.num_threads);
+                let (strategy, dataset, batcher, device, rng, sampler) = match &self.source {
+                    Source::Dataset {
+                        strategy,
+                        dataset,
+                        batcher,
+                        device,
+                        rng,
+                        sampler,
+                    } => (strategy, dataset, batcher, device, rng, sampler),
+                    Source::Stream(dataloader) => {
+                        return dataloader
+                            .split(self.num_threads)
+                            .into_iter()
+                            .map(ThreadDataLoader::Stream)
+                            .collect();
+                    }
+                };
+
+                if let Some(sampler) = sampler {
+                    // Every thread draws the same items with the same rng, and loads its own part.
+                    // The parts are a multiple of the batch size, so that the threads load the
+                    // same batches as a single thread, the last parts being shorter or empty.
+                    let batch_size = strategy.batch_size().unwrap_or(1);
+                    let num_items = sampler
+                        .num_items()
+                        .div_ceil(self.num_threads)
+                        .next_multiple_of(batch_size);
+                    return (0..self.num_threads)
+                        .map(|index| {
+                            let end = (index + 1) * num_items;
+                            let dataloader = BatchDataLoader::new(
+                                strategy.clone_dyn(),
+                                dataset.clone(),
+                                batcher.clone(),
+                                device.clone(),
+                                rng.clone(),
+                            );
+                            ThreadDataLoader::Batch(
+                                dataloader.with_sampled_part(sampler.slice(index * num_items, end)),
+                            )
+                        })
+                        .collect();
+                }
+
+                let datasets = PartialDataset::split(dataset.clone(), self.num_threads);
 
                 // Create more rngs from the first one, one for each new dataloader.
-                let mut rng = self.rng.clone();
+                let mut rng = rng.clone();
                 let rngs = (0..self.num_threads).map(|_| {
                     rng.as_mut().map(|rng| {
                         StdRng::seed_from_u64(Distribution::sample(&StandardUniform, rng))
@@ -101,14 +266,13 @@ where
                     .into_iter()
                     .zip(rngs)
                     .map(|(dataset, rng)| {
-                        let strategy = self.strategy.clone_dyn();
-                        BatchDataLoader::new(
-                            strategy,
+                        ThreadDataLoader::Batch(BatchDataLoader::new(
+                            strategy.clone_dyn(),
                             Arc::new(dataset),
-                            self.batcher.clone(),
-                            self.device.clone(),
+                            batcher.clone(),
+                            device.clone(),
                             rng,
-                        )
+                        ))
                     })
                     .collect()
             })
@@ -116,33 +280,50 @@ where
     }
 }
 
-impl<B: Backend, I, O> DataLoader<B, O> for MultiThreadDataLoader<B, I, O>
+impl<B: Backend, I, O> MultiThreadDataLoader<B, I, O>
 where
     I: Send + Sync + Clone + 'static,
     O: Send + 'static + std::fmt::Debug,
 {
-    fn iter<'a>(&'a self) -> Box<dyn DataLoaderIterator<O> + 'a> {
+    /// Spawn the threads loading the batches, resuming their iteration from the given states if
+    /// provided.
+    fn iterator(&self, states: Option<Vec<DataLoaderState>>) -> MultiThreadsDataloaderIterator<O> {
         // This will initialize the loader if it hasn't been initialized yet
         let dataloaders = self.initialize();
 
-        let (sender, receiver) = mpsc::sync_channel::<Message<O>>(MAX_QUEUED_ITEMS);
-
+        // With ordered polling, each thread has its own channel, so the batches can be received
+        // in a fixed order, otherwise the threads share a channel.
+        let capacity = (MAX_QUEUED_ITEMS / dataloaders.len().max(1)).max(1);
+        let shared = (!self.ordered).then(|| mpsc::sync_channel::<Message<O>>(MAX_QUEUED_ITEMS));
         let mut progresses = Vec::with_capacity(dataloaders.len());
+        let mut receivers = Vec::with_capacity(dataloaders.len());
 
         let handlers: Vec<_> = dataloaders
             .iter()
             .enumerate()
             .map(|(index, dataloader)| {
                 let dataloader_cloned = dataloader.clone();
-                let sender_cloned = sender.clone();
-                progresses.push(Progress::new(0, dataloader_cloned.num_items()));
+                let state = states.as_ref().map(|states| states[index]);
+                let sender = match &shared {
+                    Some((sender, _)) => sender.clone(),
+                    None => {
+                        let (sender, receiver) = mpsc::sync_channel::<Message<O>>(capacity);
+                        receivers.push(receiver);
+                        sender
+                    }
+                };
+                progresses.push(Progress::new(0, dataloader_cloned.dataloader().num_items()));
 
                 thread::spawn(move || {
-                    let mut iterator = dataloader_cloned.iter();
+                    let dataloader = dataloader_cloned.dataloader();
+                    let mut iterator = match state {
+                        Some(state) => dataloader.iter_from(state),
+                        None => dataloader.iter(),
+                    };
                     while let Some(item) = iterator.next() {
                         let progress = iterator.progress();
 
-                        match sender_cloned.send(Message::Batch(index, item, progress)) {
+                        match sender.send(Message::Batch(index, item, progress)) {
                             Ok(_) => {}
                             // The receiver is probably gone, no need to panic, just need to stop
                             // iterating.
@@ -150,56 +331,180 @@ where
                         };
                     }
                     // Same thing.
-                    sender_cloned.send(Message::Done).ok();
+                    sender.send(Message::Done).ok();
                 })
             })
             .collect();
+        if let Some((_, receiver)) = shared {
+            receivers.push(receiver);
+        }
+
+        MultiThreadsDataloaderIterator::new(receivers, handlers, progresses, self.ordered)
+    }
+}
+
+impl<B: Backend, I, O> DataLoader<B, O> for MultiThreadDataLoader<B, I, O>
+where
+    I: Send + Sync + Clone + 'static,
+    O: Send + 'static + std::fmt::Debug,
+{
+    fn iter<'a>(&'a self) -> Box<dyn DataLoaderIterator<O> + 'a> {
+        Box::new(self.iterator(None))
+    }
+
+    fn iter_from<'a>(&'a self, state: DataLoaderState) -> Box<dyn DataLoaderIterator<O> + 'a> {
+        let num_threads = self.initialize().len();
+
+        let batch_size = match &self.source {
+            Source::Dataset { strategy, .. } if self.ordered => strategy.batch_size(),
+            _ => None,
+        };
+        let Some(batch_size) = batch_size else {
+            // The batches yielded by each thread depend on their scheduling or on the items, so
+            // the batches already yielded are loaded again and skipped.
+            let states = vec![DataLoaderState::new(state.epoch, 0); num_threads];
+            let mut iterator = self.iterator(Some(states));
+            for _ in 0..state.batches {
+                if iterator.next().is_none() {
+                    break;
+                }
+            }
+            return Box::new(iterator);
+        };
+
+        // The threads are polled in turn, so the batches already yielded by each thread can be
+        // counted from the number of batches of each thread.
+        let num_batches = self
+            .initialize()
+            .iter()
+            .map(|dataloader| dataloader.dataloader().num_items().div_ceil(batch_size))
+            .collect::<Vec<_>>();
+        let mut skipped = vec![0; num_threads];
+        let mut current = 0;
+        for _ in 0..state.batches {
+            let Some(index) = (0..num_threads)
+                .map(|offset| (current + offset) % num_threads)
+                .find(|index| skipped[*index] < num_batches[*index])
+            else {
+                break;
+            };
+            skipped[index] += 1;
+            current = (index + 1) % num_threads;
+        }
 
-        Box::new(MultiThreadsDataloaderIterator::new(
-            receiver, handlers, progresses,
-        ))
+        let states = skipped
+            .into_iter()
+            .map(|batches| DataLoaderState::new(state.epoch, batches))
+            .collect();
+        let mut iterator = self.iterator(Some(states));
+        iterator.current = current;
+        Box::new(iterator)
     }
 
     fn num_items(&self) -> usize {
         // For num_items, we can directly use the dataset size without
         // necessarily initializing the full loader
-        self.dataset.len()
+        match &self.source {
+            Source::Dataset {
+                sampler: Some(sampler),
+                ..
+            } => sampler.num_items(),
+            Source::Dataset { dataset, .. } => dataset.len(),
+            Source::Stream(dataloader) => dataloader.num_items(),
+        }
     }
 
     fn to_device(&self, device: &B::Device) -> Arc<dyn DataLoader<B, O>> {
-        Arc::new(Self::new(
-            self.strategy.clone_dyn(),
-            self.dataset.clone(),
-            self.batcher.clone(),
-            self.num_threads,
-            device.clone(),
-            self.rng.clone(),
-        ))
+        match &self.source {
+            Source::Dataset {
+                strategy,
+                dataset,
+                batcher,
+                rng,
+                sampler,
+                ..
+            } => Arc::new(Self {
+                source: Source::Dataset {
+                    strategy: strategy.clone_dyn(),
+                    dataset: dataset.clone(),
+                    batcher: batcher.clone(),
+                    device: device.clone(),
+                    rng: rng.clone(),
+                    sampler: sampler.clone(),
+                },
+                num_threads: self.num_threads,
+                ordered: self.ordered,
+                dataloaders: OnceCell::new(),
+            }),
+            Source::Stream(dataloader) => Arc::new(Self {
+                ordered: self.ordered,
+                ..Self::from_stream(dataloader.on_device(device), self.num_threads)
+            }),
+        }
     }
 
     fn slice(&self, start: usize, end: usize) -> Arc<dyn DataLoader<B, O>> {
-        let dataloader = Self::new(
-            self.strategy.clone_dyn(),
-            Arc::new(PartialDataset::new(self.dataset.clone(), start, end)),
-            self.batcher.clone(),
-            self.num_threads,
-            self.device.clone(),
-            self.rng.clone(),
-        );
-        Arc::new(dataloader)
+        match &self.source {
+            Source::Dataset {
+                strategy,
+                dataset,
+                batcher,
+                device,
+                rng,
+                sampler: Some(sampler),
+            } => Arc::new(Self {
+                source: Source::Dataset {
+                    strategy: strategy.clone_dyn(),
+                    dataset: dataset.clone(),
+                    batcher: batcher.clone(),
+                    device: device.clone(),
+                    rng: rng.clone(),
+                    sampler: Some(sampler.slice(start, end)),
+                },
+                num_threads: self.num_threads,
+                ordered: self.ordered,
+                dataloaders: OnceCell::new(),
+            }),
+            Source::Dataset {
+                strategy,
+                dataset,
+                batcher,
+                device,
+                rng,
+                sampler: None,
+            } => Arc::new(Self {
+                ordered: self.ordered,
+                ..Self::new(
+                    strategy.clone_dyn(),
+                    Arc::new(PartialDataset::new(dataset.clone(), start, end)),
+                    batcher.clone(),
+                    self.num_threads,
+                    device.clone(),
+                    rng.clone(),
+                )
+            }),
+            Source::Stream(dataloader) => Arc::new(Self {
+                ordered: self.ordered,
+                ..Self::from_stream(dataloader.sliced(start, end), self.num_threads)
+            }),
+        }
     }
 }
 
 impl<O> MultiThreadsDataloaderIterator<O> {
     pub fn new(
-        receiver: mpsc::Receiver<Message<O>>,
+        receivers: Vec<mpsc::Receiver<Message<O>>>,
         workers: Vec<thread::JoinHandle<()>>,
         progresses: Vec<Progress>,
+        ordered: bool,
     ) -> Self {
         MultiThreadsDataloaderIterator {
+            ordered,
+            current: 0,
             num_done: 0,
+            done: vec![false; receivers.len()],
             workers,
-            receiver,
+            receivers,
             progresses,
         }
     }
@@ -227,10 +532,24 @@ impl<O: std::fmt::Debug> Iterator for MultiThreadsDataloaderIterator<O> {
         }
 
         loop {
-            let item = self.receiver.recv();
-            let item = item.unwrap();
+            if self.num_done == self.workers.len() {
+                while let Some(worker) = self.workers.pop() {
+                    worker.join().unwrap();
+                }
+                return None;
+            }
 
-            match item {
+            // With ordered polling, the threads are polled in turn, skipping the ones that are
+            // done, otherwise the threads share a single channel.
+            let index = self.current;
+            if self.ordered {
+                self.current = (self.current + 1) % self.receivers.len();
+                if self.done[index] {
+                    continue;
+                }
+            }
+
+            match self.receivers[index].recv().unwrap() {
                 Message::Batch(index, item, progress) => {
                     if let Some(current) = self.progresses.get_mut(index) {
                         *current = progress;
@@ -239,15 +558,9 @@ impl<O: std::fmt::Debug> Iterator for MultiThreadsDataloaderIterator<O> {
                 }
                 Message::Done => {
                     self.num_done += 1;
+                    self.done[index] = true;
                 }
             };
-
-            if self.num_done == self.workers.len() {
-                while let Some(worker) = self.workers.pop() {
-                    worker.join().unwrap();
-                }
-                return None;
-            }
         }
     }
 }
@@ -298,4 +611,88 @@ mod tests {
 
         assert_eq!(items_single_thread, items_multi_thread);
     }
+
+    #[test]
+    fn test_multi_thread_batch_dataloader_order_and_resume() {
+        let dataset = Arc::new(FakeDataset::<String>::new(27));
+        let new_dataloader = || {
+            MultiThreadDataLoader::new(
+                Box::new(FixBatchStrategy::new(2)),
+                dataset.clone(),
+                Arc::new(TestBatcher::new()),
+                4,
+                Default::default(),
+                Some(rand::SeedableRng::seed_from_u64(42)),
+            )
+            .with_ordered_polling()
+        };
+        let dataloader = new_dataloader();
+        let epochs = (0..2)
+            .map(|_| dataloader.iter().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let dataloader = new_dataloader();
+        assert_eq!(dataloader.iter().collect::<Vec<_>>(), epochs[0]);
+
+        let dataloader = new_dataloader();
+        let resumed = dataloader
+            .iter_from(DataLoaderState::new(1, 5))
+            .collect::<Vec<_>>();
+        assert_eq!(resumed, epochs[1][5..]);
+
+        // The last thread has one batch less than the others.
+        let dataloader = new_dataloader();
+        let resumed = dataloader
+            .iter_from(DataLoaderState::new(1, 13))
+            .collect::<Vec<_>>();
+        assert_eq!(resumed, epochs[1][13..]);
+    }
+
+    #[test]
+    fn test_multi_thread_batch_dataloader_resume_skips_the_batches() {
+        let dataloader = MultiThreadDataLoader::new(
+            Box::new(FixBatchStrategy::new(2)),
+            Arc::new(FakeDataset::<String>::new(27)),
+            Arc::new(TestBatcher::new()),
+            4,
+            Default::default(),
+            None,
+        );
+
+        let resumed = dataloader.iter_from(DataLoaderState::new(0, 5));
+        assert_eq!(resumed.count(), 14 - 5);
+    }
+
+    #[test]
+    fn test_multi_thread_stream_dataloader() {
+        use crate::data::dataset::{InMemDataset, ShardedDataset};
+
+        let dataset = Arc::new(ShardedDataset::new(
+            InMemDataset::new((0..24).collect::<Vec<i32>>()),
+            6,
+        ));
+        let new_dataloader = || {
+            let dataloader = StreamDataLoader::new(
+                Box::new(FixBatchStrategy::new(2)),
+                dataset.clone(),
+                Arc::new(TestBatcher::new()),
+                Default::default(),
+                Some(rand::SeedableRng::seed_from_u64(42)),
+            );
+            MultiThreadDataLoader::from_stream(dataloader, 3).with_ordered_polling()
+        };
+        let dataloader = new_dataloader();
+        assert_eq!(dataloader.num_items(), 24);
+        let epoch = dataloader.iter().collect::<Vec<_>>();
+
+        let mut items = epoch.concat();
+        items.sort();
+        assert_eq!(items, (0..24).collect::<Vec<_>>());
+
+        let dataloader = new_dataloader();
+        let resumed = dataloader
+            .iter_from(DataLoaderState::new(0, 5))
+            .collect::<Vec<_>>();
+        assert_eq!(resumed, epoch[5..]);
+    }
 }
@@ -24,6 +24,24 @@ pub trait BatchStrategy<I>: Send {
     ///
     /// The new strategy.
     fn clone_dyn(&self) -> Box<dyn BatchStrategy<I>>;
+
+    /// The number of items of every batch but the last one, when it doesn't depend on the items.
+    ///
+    /// Allows the data loaders to skip batches without loading their items when an iteration is
+    /// [resumed](super::DataLoader::iter_from).
+    fn batch_size(&self) -> Option<usize> {
+        None
+    }
+
+    /// Skips the next `num_batches` batches without their items, returning their number of
+    /// items, when it doesn't depend on the items.
+    ///
+    /// Allows the data loaders to skip batches without loading their items when an iteration is
+    /// [resumed](super::DataLoader::iter_from), for the strategies whose batch size changes
+    /// independently of the items.
+    fn skip(&mut self, num_batches: usize) -> Option<usize> {
+        self.batch_size().map(|batch_size| num_batches * batch_size)
+    }
 }
 
 /// A strategy to batch items with a fixed batch size.
@@ -73,4 +91,8 @@ impl<I: Send + 'static> BatchStrategy<I> for FixBatchStrategy<I> {
     fn clone_dyn(&self) -> Box<dyn BatchStrategy<I>> {
         Box::new(Self::new(self.batch_size))
     }
+
+    fn batch_size(&self) -> Option<usize> {
+        Some(self.batch_size)
+    }
 }
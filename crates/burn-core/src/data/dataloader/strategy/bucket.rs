@@ -0,0 +1,128 @@
+use super::BatchStrategy;
+use std::{collections::VecDeque, sync::Arc};
+
+/// A strategy to batch items of similar lengths together, minimizing the padding of
+/// variable-length items.
+///
+/// The items are assigned to buckets by length, each bucket having its own batch size, so that
+/// the buckets of short items can have more items per batch. A batch is yielded each time a
+/// bucket is full, and the incomplete buckets are yielded at the end of the iteration.
+pub struct BucketBatchStrategy<I> {
+    buckets: Vec<Vec<I>>,
+    ready: VecDeque<Vec<I>>,
+    boundaries: Vec<usize>,
+    batch_sizes: Vec<usize>,
+    length: Arc<dyn Fn(&I) -> usize + Send + Sync>,
+}
+
+impl<I> BucketBatchStrategy<I> {
+    /// Creates a new strategy to batch items in buckets by length.
+    ///
+    /// # Arguments
+    ///
+    /// * `boundaries` - The increasing length boundaries of the buckets: the bucket `i` contains
+    ///   the items with a length lower than `boundaries[i]`, and the last bucket contains the
+    ///   longest items.
+    /// * `batch_sizes` - The batch size of each bucket, one more than the boundaries.
+    /// * `length` - The length of an item.
+    ///
+    /// # Returns
+    ///
+    /// The strategy.
+    pub fn new<F>(boundaries: Vec<usize>, batch_sizes: Vec<usize>, length: F) -> Self
+    where
+        F: Fn(&I) -> usize + Send + Sync + 'static,
+    {
+        assert!(
+            boundaries.windows(2).all(|window| window[0] < window[1]),
+            "The boundaries must be increasing"
+        );
+        assert_eq!(
+            batch_sizes.len(),
+            boundaries.len() + 1,
+            "There must be one batch size per bucket"
+        );
+        assert!(
+            batch_sizes.iter().all(|batch_size| *batch_size > 0),
+            "The batch sizes must be positive"
+        );
+
+        Self::from_length(boundaries, batch_sizes, Arc::new(length))
+    }
+
+    fn from_length(
+        boundaries: Vec<usize>,
+        batch_sizes: Vec<usize>,
+        length: Arc<dyn Fn(&I) -> usize + Send + Sync>,
+    ) -> Self {
+        Self {
+            buckets: batch_sizes.iter().map(|_| Vec::new()).collect(),
+            ready: VecDeque::new(),
+            boundaries,
+            batch_sizes,
+            length,
+        }
+    }
+}
+
+impl<I: Send + 'static> BatchStrategy<I> for BucketBatchStrategy<I> {
+    fn add(&mut self, item: I) {
+        let length = (self.length)(&item);
+        let bucket = self
+            .boundaries
+            .partition_point(|boundary| *boundary <= length);
+
+        self.buckets[bucket].push(item);
+        if self.buckets[bucket].len() >= self.batch_sizes[bucket] {
+            self.ready
+                .push_back(std::mem::take(&mut self.buckets[bucket]));
+        }
+    }
+
+    fn batch(&mut self, force: bool) -> Option<Vec<I>> {
+        if let Some(items) = self.ready.pop_front() {
+            return Some(items);
+        }
+        if !force {
+            return None;
+        }
+
+        self.buckets
+            .iter_mut()
+            .find(|bucket| !bucket.is_empty())
+            .map(std::mem::take)
+    }
+
+    fn clone_dyn(&self) -> Box<dyn BatchStrategy<I>> {
+        Box::new(Self::from_length(
+            self.boundaries.clone(),
+            self.batch_sizes.clone(),
+            self.length.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_batches() {
+        let mut strategy =
+            BucketBatchStrategy::new(vec![5, 10], vec![3, 2, 1], |length: &usize| *length);
+
+        let mut batches = Vec::new();
+        for item in [1, 7, 12, 2, 5, 3, 4, 9] {
+            strategy.add(item);
+            batches.extend(strategy.batch(false));
+        }
+        while let Some(items) = strategy.batch(true) {
+            batches.push(items);
+        }
+
+        assert_eq!(
+            batches,
+            vec![vec![12], vec![7, 5], vec![1, 2, 3], vec![4], vec![9]]
+        );
+    }
+}
@@ -0,0 +1,9 @@
+mod base;
+mod bucket;
+mod schedule;
+mod token_budget;
+
+pub use base::*;
+pub use bucket::*;
+pub use schedule::*;
+pub use token_budget::*;
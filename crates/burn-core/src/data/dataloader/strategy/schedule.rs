@@ -0,0 +1,191 @@
+use super::BatchStrategy;
+use std::sync::Arc;
+
+/// A strategy to batch items with a batch size changing over the iteration, e.g. a batch size
+/// warmup.
+///
+/// The batch size is a function of the number of items batched since the start of the iteration
+/// over the data loader, so the batch sizes of an iteration don't depend on the previous ones and
+/// are replayed identically when an iteration is [resumed](crate::data::dataloader::DataLoader::iter_from).
+/// With a [multi-threaded data loader](crate::data::dataloader::MultiThreadDataLoader), every
+/// thread batches its own part of the items with its own schedule.
+///
+/// To continue a schedule lasting multiple epochs, e.g. when resuming a training, the number of
+/// items batched before the iteration can be [set](Self::with_items_seen).
+pub struct ScheduledBatchStrategy<I> {
+    items: Vec<I>,
+    schedule: Arc<dyn Fn(usize) -> usize + Send + Sync>,
+    // The number of items batched before the iteration.
+    items_start: usize,
+    items_seen: usize,
+}
+
+impl<I> ScheduledBatchStrategy<I> {
+    /// Creates a new strategy to batch items with a scheduled batch size.
+    ///
+    /// # Arguments
+    ///
+    /// * `schedule` - The batch size for a number of items already batched.
+    ///
+    /// # Returns
+    ///
+    /// The strategy.
+    pub fn new<F>(schedule: F) -> Self
+    where
+        F: Fn(usize) -> usize + Send + Sync + 'static,
+    {
+        Self {
+            items: Vec::new(),
+            schedule: Arc::new(schedule),
+            items_start: 0,
+            items_seen: 0,
+        }
+    }
+
+    /// Creates a new strategy with a batch size increasing linearly from `start` to `end` over
+    /// the first `num_items` items, and staying at `end` afterward.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The initial batch size.
+    /// * `end` - The final batch size.
+    /// * `num_items` - The number of items over which the batch size changes.
+    ///
+    /// # Returns
+    ///
+    /// The strategy.
+    pub fn linear(start: usize, end: usize, num_items: usize) -> Self {
+        Self::new(move |items_seen| {
+            if items_seen >= num_items {
+                return end;
+            }
+            let progress = items_seen as f64 / num_items as f64;
+            (start as f64 + (end as f64 - start as f64) * progress).round() as usize
+        })
+    }
+
+    /// Creates a new strategy with a piecewise constant batch size.
+    ///
+    /// # Arguments
+    ///
+    /// * `steps` - The number of items from which each batch size is used, in increasing
+    ///   order, starting at 0.
+    ///
+    /// # Returns
+    ///
+    /// The strategy.
+    pub fn piecewise(steps: Vec<(usize, usize)>) -> Self {
+        assert_eq!(
+            steps.first().map(|(start, _)| *start),
+            Some(0),
+            "The first step must start at 0 items"
+        );
+        assert!(
+            steps.windows(2).all(|window| window[0].0 < window[1].0),
+            "The steps must be in increasing order"
+        );
+
+        Self::new(move |items_seen| {
+            let index = steps.partition_point(|(start, _)| *start <= items_seen);
+            steps[index - 1].1
+        })
+    }
+
+    /// Sets the number of items batched before every iteration, e.g. to resume a training.
+    ///
+    /// # Arguments
+    ///
+    /// * `items_seen` - The number of items.
+    ///
+    /// # Returns
+    ///
+    /// The strategy.
+    pub fn with_items_seen(mut self, items_seen: usize) -> Self {
+        self.items_start = items_seen;
+        self.items_seen = items_seen;
+        self
+    }
+
+    /// The batch size of the next batch.
+    pub fn current_batch_size(&self) -> usize {
+        (self.schedule)(self.items_seen).max(1)
+    }
+}
+
+impl<I: Send + 'static> BatchStrategy<I> for ScheduledBatchStrategy<I> {
+    fn add(&mut self, item: I) {
+        self.items.push(item);
+    }
+
+    fn batch(&mut self, force: bool) -> Option<Vec<I>> {
+        if self.items.len() < self.current_batch_size() && !force {
+            return None;
+        }
+        if self.items.is_empty() {
+            return None;
+        }
+
+        let items = std::mem::take(&mut self.items);
+        self.items_seen += items.len();
+        Some(items)
+    }
+
+    fn clone_dyn(&self) -> Box<dyn BatchStrategy<I>> {
+        Box::new(Self {
+            items: Vec::new(),
+            schedule: self.schedule.clone(),
+            items_start: self.items_start,
+            items_seen: self.items_start,
+        })
+    }
+
+    fn skip(&mut self, num_batches: usize) -> Option<usize> {
+        let items_start = self.items_seen;
+        for _ in 0..num_batches {
+            self.items_seen += self.current_batch_size();
+        }
+
+        Some(self.items_seen - items_start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn batch_sizes(strategy: &mut dyn BatchStrategy<usize>, num_items: usize) -> Vec<usize> {
+        let mut batch_sizes = Vec::new();
+        for item in 0..num_items {
+            strategy.add(item);
+            batch_sizes.extend(strategy.batch(false).map(|items| items.len()));
+        }
+        batch_sizes.extend(strategy.batch(true).map(|items| items.len()));
+        batch_sizes
+    }
+
+    #[test]
+    fn test_scheduled_batch_sizes() {
+        let strategy = ScheduledBatchStrategy::linear(2, 6, 8);
+        assert_eq!(
+            batch_sizes(strategy.clone_dyn().as_mut(), 14),
+            vec![2, 3, 5, 4]
+        );
+        // Every iteration restarts the schedule.
+        assert_eq!(
+            batch_sizes(strategy.clone_dyn().as_mut(), 14),
+            vec![2, 3, 5, 4]
+        );
+
+        let strategy = ScheduledBatchStrategy::piecewise(vec![(0, 4), (8, 2)]).with_items_seen(4);
+        assert_eq!(batch_sizes(strategy.clone_dyn().as_mut(), 7), vec![4, 2, 1]);
+    }
+
+    #[test]
+    fn test_skipped_batches_follow_the_schedule() {
+        let strategy = ScheduledBatchStrategy::linear(2, 6, 8);
+        let mut skipped = strategy.clone_dyn();
+
+        assert_eq!(skipped.skip(2), Some(5));
+        assert_eq!(batch_sizes(skipped.as_mut(), 9), vec![5, 4]);
+    }
+}
@@ -0,0 +1,165 @@
+use super::BatchStrategy;
+use std::{collections::VecDeque, sync::Arc};
+
+/// A strategy to batch variable-length items, e.g. token sequences, with a maximum number of
+/// tokens per batch instead of a fixed number of items.
+///
+/// By default, the tokens of a batch are counted with the padding, i.e. the number of items
+/// multiplied by the length of the longest item, which bounds the size of the padded batch.
+/// An item longer than the budget is batched alone.
+pub struct TokenBudgetBatchStrategy<I> {
+    items: Vec<I>,
+    ready: VecDeque<Vec<I>>,
+    max_length: usize,
+    total_length: usize,
+    max_tokens: usize,
+    max_items: Option<usize>,
+    padded: bool,
+    length: Arc<dyn Fn(&I) -> usize + Send + Sync>,
+}
+
+impl<I> TokenBudgetBatchStrategy<I> {
+    /// Creates a new strategy to batch items with a maximum number of tokens per batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_tokens` - The maximum number of tokens per batch.
+    /// * `length` - The number of tokens of an item.
+    ///
+    /// # Returns
+    ///
+    /// The strategy.
+    pub fn new<F>(max_tokens: usize, length: F) -> Self
+    where
+        F: Fn(&I) -> usize + Send + Sync + 'static,
+    {
+        Self::from_length(max_tokens, Arc::new(length))
+    }
+
+    fn from_length(max_tokens: usize, length: Arc<dyn Fn(&I) -> usize + Send + Sync>) -> Self {
+        Self {
+            items: Vec::new(),
+            ready: VecDeque::new(),
+            max_length: 0,
+            total_length: 0,
+            max_tokens,
+            max_items: None,
+            padded: true,
+            length,
+        }
+    }
+
+    /// Sets the maximum number of items per batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_items` - The maximum number of items.
+    ///
+    /// # Returns
+    ///
+    /// The strategy.
+    pub fn with_max_items(mut self, max_items: usize) -> Self {
+        self.max_items = Some(max_items);
+        self
+    }
+
+    /// Sets whether the padding is counted in the tokens of a batch, otherwise only the tokens of
+    /// the items are counted.
+    ///
+    /// # Arguments
+    ///
+    /// * `padded` - Whether the padding is counted.
+    ///
+    /// # Returns
+    ///
+    /// The strategy.
+    pub fn with_padded_tokens(mut self, padded: bool) -> Self {
+        self.padded = padded;
+        self
+    }
+
+    /// The number of tokens of the current items with another item of the given length.
+    fn tokens_with(&self, length: usize) -> usize {
+        match self.padded {
+            true => (self.items.len() + 1) * self.max_length.max(length),
+            false => self.total_length + length,
+        }
+    }
+}
+
+impl<I: Send + 'static> BatchStrategy<I> for TokenBudgetBatchStrategy<I> {
+    fn add(&mut self, item: I) {
+        let length = (self.length)(&item);
+        let full = self
+            .max_items
+            .is_some_and(|max_items| self.items.len() >= max_items)
+            || self.tokens_with(length) > self.max_tokens;
+
+        if full && !self.items.is_empty() {
+            self.ready.push_back(std::mem::take(&mut self.items));
+            self.max_length = 0;
+            self.total_length = 0;
+        }
+
+        self.items.push(item);
+        self.max_length = self.max_length.max(length);
+        self.total_length += length;
+    }
+
+    fn batch(&mut self, force: bool) -> Option<Vec<I>> {
+        if let Some(items) = self.ready.pop_front() {
+            return Some(items);
+        }
+        if !force || self.items.is_empty() {
+            return None;
+        }
+
+        self.max_length = 0;
+        self.total_length = 0;
+        Some(std::mem::take(&mut self.items))
+    }
+
+    fn clone_dyn(&self) -> Box<dyn BatchStrategy<I>> {
+        let mut strategy = Self::from_length(self.max_tokens, self.length.clone());
+        strategy.max_items = self.max_items;
+        strategy.padded = self.padded;
+        Box::new(strategy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn batches(strategy: &mut dyn BatchStrategy<usize>, items: &[usize]) -> Vec<Vec<usize>> {
+        let mut batches = Vec::new();
+        for item in items {
+            strategy.add(*item);
+            batches.extend(strategy.batch(false));
+        }
+        while let Some(items) = strategy.batch(true) {
+            batches.push(items);
+        }
+        batches
+    }
+
+    #[test]
+    fn test_token_budget_batches() {
+        let items = [3, 4, 2, 10, 1, 1, 1, 1];
+
+        let mut padded = TokenBudgetBatchStrategy::new(10, |length: &usize| *length);
+        assert_eq!(
+            batches(&mut padded, &items),
+            vec![vec![3, 4], vec![2], vec![10], vec![1, 1, 1, 1]]
+        );
+
+        let mut total = TokenBudgetBatchStrategy::new(10, |length: &usize| *length)
+            .with_padded_tokens(false)
+            .with_max_items(3);
+        assert_eq!(
+            batches(total.clone_dyn().as_mut(), &items),
+            vec![vec![3, 4, 2], vec![10], vec![1, 1, 1], vec![1]]
+        );
+        assert!(total.batch(true).is_none());
+    }
+}
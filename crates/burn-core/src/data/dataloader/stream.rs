@@ -0,0 +1,373 @@
+use super::{
+    BatchStrategy, DataLoader, DataLoaderIterator, DataLoaderState, Progress, batcher::Batcher,
+};
+use burn_dataset::{IterableDataset, transform::ShuffleBuffer};
+use burn_tensor::backend::Backend;
+use rand::{Rng, SeedableRng, distr::StandardUniform, rngs::StdRng, seq::SliceRandom};
+use std::ops::Range;
+use std::sync::Arc;
+
+/// The default number of items of the shuffle buffer of a [stream data loader](StreamDataLoader).
+pub const DEFAULT_SHUFFLE_BUFFER_SIZE: usize = 1000;
+
+/// A data loader that can be used to iterate over an [iterable dataset](IterableDataset) in
+/// batches, for datasets too large to be indexed.
+///
+/// The shards of the dataset are assigned in turn to the workers loading it, e.g. the threads of
+/// a [multi-threaded data loader](super::MultiThreadDataLoader) or the ranks of a distributed
+/// training. When the data loader was created with an rng, the order of the shards changes at
+/// each iteration, and the items of each worker are shuffled with a
+/// [shuffle buffer](ShuffleBuffer).
+pub struct StreamDataLoader<B: Backend, I, O> {
+    strategy: Box<dyn BatchStrategy<I>>,
+    dataset: Arc<dyn IterableDataset<I>>,
+    batcher: Arc<dyn Batcher<B, I, O>>,
+    device: B::Device,
+    shards: Range<usize>,
+    worker: usize,
+    num_workers: usize,
+    shuffle_buffer_size: usize,
+    rng: Option<Arc<spin::Mutex<StdRng>>>,
+    // The rng before the first iteration, to replay the shuffling when resuming an iteration.
+    rng_initial: Option<StdRng>,
+}
+
+impl<B: Backend, I, O> Clone for StreamDataLoader<B, I, O> {
+    fn clone(&self) -> Self {
+        Self {
+            strategy: self.strategy.clone_dyn(),
+            dataset: self.dataset.clone(),
+            batcher: self.batcher.clone(),
+            device: self.device.clone(),
+            shards: self.shards.clone(),
+            worker: self.worker,
+            num_workers: self.num_workers,
+            shuffle_buffer_size: self.shuffle_buffer_size,
+            rng: self.rng.clone(),
+            rng_initial: self.rng_initial.clone(),
+        }
+    }
+}
+
+impl<B: Backend, I, O> StreamDataLoader<B, I, O> {
+    /// Creates a new stream data loader, loading all the shards of the dataset.
+    ///
+    /// # Arguments
+    ///
+    /// * `strategy` - The batch strategy.
+    /// * `dataset` - The iterable dataset.
+    /// * `batcher` - The batcher.
+    /// * `device`  - The device to use when loading a batch.
+    /// * `rng`     - The rng determining if the shards and the items are shuffled each time a
+    ///   dataloader iterator is created.
+    ///
+    /// # Returns
+    ///
+    /// The stream data loader.
+    pub fn new(
+        strategy: Box<dyn BatchStrategy<I>>,
+        dataset: Arc<dyn IterableDataset<I>>,
+        batcher: Arc<dyn Batcher<B, I, O>>,
+        device: B::Device,
+        rng: Option<StdRng>,
+    ) -> Self {
+        Self {
+            strategy,
+            shards: 0..dataset.num_shards(),
+            dataset,
+            batcher,
+            device,
+            worker: 0,
+            num_workers: 1,
+            shuffle_buffer_size: DEFAULT_SHUFFLE_BUFFER_SIZE,
+            rng_initial: rng.clone(),
+            rng: rng.map(|rng| Arc::new(spin::Mutex::new(rng))),
+        }
+    }
+
+    /// Only load the shards of a worker, the shards being assigned to the workers in turn.
+    ///
+    /// # Arguments
+    ///
+    /// * `worker` - The index of the worker.
+    /// * `num_workers` - The number of workers.
+    ///
+    /// # Returns
+    ///
+    /// The stream data loader.
+    pub fn with_worker(mut self, worker: usize, num_workers: usize) -> Self {
+        assert!(
+            worker < num_workers,
+            "The worker index should be lower than the number of workers"
+        );
+        self.worker = worker;
+        self.num_workers = num_workers;
+        self
+    }
+
+    /// Sets the number of items of the shuffle buffer, used when the data loader was created
+    /// with an rng.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The number of items.
+    ///
+    /// # Returns
+    ///
+    /// The stream data loader.
+    pub fn with_shuffle_buffer(mut self, size: usize) -> Self {
+        self.shuffle_buffer_size = size;
+        self
+    }
+
+    /// Split the shards of the data loader between the given number of workers, e.g. the
+    /// threads of a multi-threaded data loader.
+    pub(crate) fn split(&self, num_workers: usize) -> Vec<Self> {
+        // The rng is shared so that every worker shuffles the shards in the same order.
+        let rng = self.rng.as_ref().map(|rng| rng.lock().clone());
+
+        (0..num_workers)
+            .map(|index| {
+                let mut dataloader = self.with_rng(rng.clone());
+                dataloader.worker = self.worker + self.num_workers * index;
+                dataloader.num_workers = self.num_workers * num_workers;
+                dataloader
+            })
+            .collect()
+    }
+
+    /// The same data loader on another device.
+    pub(crate) fn on_device(&self, device: &B::Device) -> Self {
+        let mut dataloader = self.with_rng(self.rng.as_ref().map(|rng| rng.lock().clone()));
+        dataloader.device = device.clone();
+        dataloader
+    }
+
+    /// The data loader of the shards containing the given range of items, assuming the items
+    /// are evenly split between the shards.
+    ///
+    /// The range can't be mapped to the shards when the number of items of the dataset isn't
+    /// known, so all the shards are kept.
+    pub(crate) fn sliced(&self, start: usize, end: usize) -> Self {
+        let mut dataloader = self.with_rng(self.rng.as_ref().map(|rng| rng.lock().clone()));
+        let num_items = self.worker_num_items();
+        if num_items > 0 {
+            let num_shards = self.shards.len();
+            let shard =
+                |index: usize| self.shards.start + index.min(num_items) * num_shards / num_items;
+            dataloader.shards = shard(start)..shard(end);
+        }
+        dataloader
+    }
+
+    fn with_rng(&self, rng: Option<StdRng>) -> Self {
+        Self {
+            rng_initial: rng.clone(),
+            rng: rng.map(|rng| Arc::new(spin::Mutex::new(rng))),
+            ..self.clone()
+        }
+    }
+
+    /// The shards of the worker, in the order of the iteration.
+    fn worker_shards(&self, seed: Option<u64>) -> Vec<usize> {
+        let mut shards = self.shards.clone().collect::<Vec<_>>();
+        if let Some(seed) = seed {
+            shards.shuffle(&mut StdRng::seed_from_u64(seed));
+        }
+
+        shards
+            .into_iter()
+            .skip(self.worker)
+            .step_by(self.num_workers)
+            .collect()
+    }
+
+    /// The number of items of the worker, assuming the items are evenly split between the
+    /// shards, or zero when the number of items of the dataset isn't known.
+    fn worker_num_items(&self) -> usize {
+        let num_shards = self.dataset.num_shards();
+        if num_shards == 0 {
+            return 0;
+        }
+        let num_worker_shards = self.worker_shards(None).len();
+
+        self.dataset.num_items().unwrap_or(0) * num_worker_shards / num_shards
+    }
+}
+
+impl<B, I, O> StreamDataLoader<B, I, O>
+where
+    B: Backend,
+    I: Send + Sync + 'static,
+    O: Send + 'static,
+{
+    fn iterator(&self) -> StreamDataLoaderIterator<'_, B, I, O> {
+        // Each new iteration shuffles the shards and the items differently, by advancing the
+        // current rng.
+        let seed = self
+            .rng
+            .as_ref()
+            .map(|rng| rng.lock().sample(StandardUniform));
+
+        let dataset = &self.dataset;
+        let items = self
+            .worker_shards(seed)
+            .into_iter()
+            .flat_map(move |shard| dataset.iter_shard(shard));
+        let items: Box<dyn Iterator<Item = I> + Send + '_> = match seed {
+            Some(seed) => Box::new(ShuffleBuffer::with_seed(
+                items,
+                self.shuffle_buffer_size,
+                seed.wrapping_add(self.worker as u64 + 1),
+            )),
+            None => Box::new(items),
+        };
+
+        StreamDataLoaderIterator {
+            items,
+            items_processed: 0,
+            items_total: self.worker_num_items(),
+            strategy: self.strategy.clone_dyn(),
+            batcher: self.batcher.clone(),
+            device: self.device.clone(),
+        }
+    }
+}
+
+impl<B, I, O> DataLoader<B, O> for StreamDataLoader<B, I, O>
+where
+    B: Backend,
+    I: Send + Sync + 'static,
+    O: Send + 'static,
+{
+    fn iter<'a>(&'a self) -> Box<dyn DataLoaderIterator<O> + 'a> {
+        Box::new(self.iterator())
+    }
+
+    fn iter_from<'a>(&'a self, state: DataLoaderState) -> Box<dyn DataLoaderIterator<O> + 'a> {
+        // The rng is reset to replay the shuffling of the previous iterations.
+        if let (Some(rng), Some(rng_initial)) = (&self.rng, &self.rng_initial) {
+            let mut rng = rng.lock();
+            *rng = rng_initial.clone();
+
+            for _ in 0..state.epoch {
+                let _seed: u64 = rng.sample(StandardUniform);
+            }
+        }
+
+        // The items can only be skipped by reading them.
+        let mut iterator = self.iterator();
+        for _ in 0..state.batches {
+            if iterator.next_items().is_none() {
+                break;
+            }
+        }
+        Box::new(iterator)
+    }
+
+    fn num_items(&self) -> usize {
+        self.worker_num_items()
+    }
+
+    fn to_device(&self, device: &B::Device) -> Arc<dyn DataLoader<B, O>> {
+        Arc::new(self.on_device(device))
+    }
+
+    fn slice(&self, start: usize, end: usize) -> Arc<dyn DataLoader<B, O>> {
+        Arc::new(self.sliced(start, end))
+    }
+}
+
+struct StreamDataLoaderIterator<'a, B: Backend, I, O> {
+    items: Box<dyn Iterator<Item = I> + Send + 'a>,
+    items_processed: usize,
+    items_total: usize,
+    strategy: Box<dyn BatchStrategy<I>>,
+    batcher: Arc<dyn Batcher<B, I, O>>,
+    device: B::Device,
+}
+
+impl<B: Backend, I, O> StreamDataLoaderIterator<'_, B, I, O> {
+    /// The items of the next batch, without batching them.
+    fn next_items(&mut self) -> Option<Vec<I>> {
+        for item in self.items.by_ref() {
+            self.items_processed += 1;
+            self.strategy.add(item);
+
+            if let Some(items) = self.strategy.batch(false) {
+                return Some(items);
+            }
+        }
+
+        self.strategy.batch(true)
+    }
+}
+
+impl<B: Backend, I, O> Iterator for StreamDataLoaderIterator<'_, B, I, O> {
+    type Item = O;
+
+    fn next(&mut self) -> Option<O> {
+        self.next_items()
+            .map(|items| self.batcher.batch(items, &self.device))
+    }
+}
+
+impl<B: Backend, I, O> DataLoaderIterator<O> for StreamDataLoaderIterator<'_, B, I, O> {
+    fn progress(&self) -> Progress {
+        Progress::new(self.items_processed, self.items_total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::dataloader::FixBatchStrategy;
+    use crate::data::dataloader::batcher::TestBatcher;
+    use crate::data::dataset::{InMemDataset, ShardedDataset};
+
+    fn new_dataloader(rng: Option<StdRng>) -> StreamDataLoader<crate::TestBackend, i32, Vec<i32>> {
+        StreamDataLoader::new(
+            Box::new(FixBatchStrategy::new(4)),
+            Arc::new(ShardedDataset::new(InMemDataset::new((0..20).collect()), 5)),
+            Arc::new(TestBatcher::new()),
+            Default::default(),
+            rng,
+        )
+        .with_shuffle_buffer(8)
+    }
+
+    #[test]
+    fn test_workers_load_their_shards() {
+        let items = |dataloader: &StreamDataLoader<_, _, _>| {
+            dataloader.iter().flatten().collect::<Vec<_>>()
+        };
+
+        let dataloader = new_dataloader(None);
+        assert_eq!(items(&dataloader), (0..20).collect::<Vec<_>>());
+        assert_eq!(dataloader.num_items(), 20);
+
+        let workers = dataloader.with_worker(1, 2).split(2);
+        assert_eq!(items(&workers[0]), vec![4, 5, 6, 7]);
+        assert_eq!(items(&workers[1]), vec![12, 13, 14, 15]);
+        assert_eq!(workers[1].num_items(), 4);
+    }
+
+    #[test]
+    fn test_shuffled_iterations_are_replayed() {
+        let dataloader = new_dataloader(Some(StdRng::seed_from_u64(42)));
+        let epochs = (0..2)
+            .map(|_| dataloader.iter().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_ne!(epochs[0], epochs[1]);
+
+        let mut items = epochs[0].concat();
+        items.sort();
+        assert_eq!(items, (0..20).collect::<Vec<_>>());
+
+        let dataloader = new_dataloader(Some(StdRng::seed_from_u64(42)));
+        let resumed = dataloader
+            .iter_from(DataLoaderState::new(1, 2))
+            .collect::<Vec<_>>();
+        assert_eq!(resumed, epochs[1][2..]);
+    }
+}
@@ -0,0 +1,228 @@
+use super::batcher::Batcher;
+use crate::tensor::{Bool, Int, Tensor, TensorData, backend::Backend};
+use burn_dataset::text::{PackedSequence, Tokenizer};
+use std::sync::Arc;
+
+/// A batch of tokenized texts.
+#[derive(Debug, Clone)]
+pub struct TextBatch<B: Backend> {
+    /// The tokens, padded to the longest text of the batch, of shape `[batch_size, seq_length]`.
+    pub tokens: Tensor<B, 2, Int>,
+    /// The padding mask, true on the padding tokens, of shape `[batch_size, seq_length]`.
+    pub mask_pad: Tensor<B, 2, Bool>,
+}
+
+/// Batcher tokenizing texts, with their special tokens, and padding them to the longest text of
+/// the batch.
+#[derive(Clone)]
+pub struct TextBatcher {
+    tokenizer: Arc<dyn Tokenizer>,
+    pad_token: usize,
+    max_seq_length: Option<usize>,
+}
+
+impl TextBatcher {
+    /// Create a batcher padding the texts with `pad_token`.
+    pub fn new(tokenizer: Arc<dyn Tokenizer>, pad_token: usize) -> Self {
+        Self {
+            tokenizer,
+            pad_token,
+            max_seq_length: None,
+        }
+    }
+
+    /// Truncate the texts longer than `max_seq_length` tokens.
+    pub fn with_max_seq_length(mut self, max_seq_length: usize) -> Self {
+        self.max_seq_length = Some(max_seq_length);
+        self
+    }
+}
+
+impl<B: Backend> Batcher<B, String, TextBatch<B>> for TextBatcher {
+    fn batch(&self, items: Vec<String>, device: &B::Device) -> TextBatch<B> {
+        let mut texts = items
+            .iter()
+            .map(|text| self.tokenizer.encode(text, true))
+            .collect::<Vec<_>>();
+        if let Some(max_seq_length) = self.max_seq_length {
+            texts
+                .iter_mut()
+                .for_each(|tokens| tokens.truncate(max_seq_length));
+        }
+
+        // The mask is built from the lengths, the pad token can also be a token of the texts.
+        let batch_size = texts.len();
+        let seq_length = texts.iter().map(Vec::len).max().unwrap_or(0);
+        let tokens = texts
+            .iter()
+            .flat_map(|tokens| {
+                (0..seq_length).map(|i| *tokens.get(i).unwrap_or(&self.pad_token) as i64)
+            })
+            .collect();
+        let mask_pad = texts
+            .iter()
+            .flat_map(|tokens| (0..seq_length).map(|i| i >= tokens.len()))
+            .collect();
+
+        TextBatch {
+            tokens: Tensor::from_data(TensorData::new(tokens, [batch_size, seq_length]), device),
+            mask_pad: Tensor::from_data(
+                TensorData::new(mask_pad, [batch_size, seq_length]),
+                device,
+            ),
+        }
+    }
+}
+
+/// A batch of [packed sequences](PackedSequence).
+#[derive(Debug, Clone)]
+pub struct PackedBatch<B: Backend> {
+    /// The tokens, of shape `[batch_size, block_size]`.
+    pub tokens: Tensor<B, 2, Int>,
+    /// The padding mask, true on the padding tokens, of shape `[batch_size, block_size]`.
+    pub mask_pad: Tensor<B, 2, Bool>,
+    /// The index of the sequence of each token within its block, of shape
+    /// `[batch_size, block_size]`.
+    pub sequence_ids: Tensor<B, 2, Int>,
+}
+
+/// Batcher stacking [packed sequences](PackedSequence), which all have the same length.
+#[derive(Clone, Default)]
+pub struct PackedBatcher;
+
+impl PackedBatcher {
+    /// Create a packed sequences batcher.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<B: Backend> Batcher<B, PackedSequence, PackedBatch<B>> for PackedBatcher {
+    fn batch(&self, items: Vec<PackedSequence>, device: &B::Device) -> PackedBatch<B> {
+        let batch_size = items.len();
+        let block_size = items.first().map_or(0, |item| item.tokens.len());
+        assert!(
+            items.iter().all(|item| item.tokens.len() == block_size),
+            "The packed sequences must have the same length"
+        );
+
+        let ints = |values: Vec<i64>| {
+            Tensor::from_data(TensorData::new(values, [batch_size, block_size]), device)
+        };
+        let tokens = ints(
+            items
+                .iter()
+                .flat_map(|item| item.tokens.iter().map(|token| *token as i64))
+                .collect(),
+        );
+        let sequence_ids = ints(
+            items
+                .iter()
+                .flat_map(|item| item.sequence_ids.iter().map(|id| *id as i64))
+                .collect(),
+        );
+        let mask_pad = Tensor::from_data(
+            TensorData::new(
+                items
+                    .iter()
+                    .flat_map(|item| item.attention_mask.iter().map(|attend| !attend))
+                    .collect(),
+                [batch_size, block_size],
+            ),
+            device,
+        );
+
+        PackedBatch {
+            tokens,
+            mask_pad,
+            sequence_ids,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestBackend;
+    use burn_dataset::text::SequencePacker;
+
+    struct CharTokenizer;
+
+    impl Tokenizer for CharTokenizer {
+        fn encode(&self, text: &str, _special_tokens: bool) -> Vec<usize> {
+            text.bytes().map(|byte| byte as usize).collect()
+        }
+
+        fn decode(&self, tokens: &[usize]) -> String {
+            tokens.iter().map(|token| *token as u8 as char).collect()
+        }
+
+        fn vocab_size(&self) -> usize {
+            256
+        }
+
+        fn token_to_id(&self, token: &str) -> Option<usize> {
+            token.bytes().next().map(|byte| byte as usize)
+        }
+    }
+
+    #[test]
+    fn test_text_batcher_pads_and_truncates() {
+        let device = Default::default();
+        let batcher = TextBatcher::new(Arc::new(CharTokenizer), 0).with_max_seq_length(3);
+
+        let batch: TextBatch<TestBackend> =
+            batcher.batch(vec!["ab".to_string(), "abcd".to_string()], &device);
+
+        batch.tokens.into_data().assert_eq(
+            &TensorData::from([[97, 98, 0], [97, 98, 99]]).convert::<i64>(),
+            false,
+        );
+        batch.mask_pad.into_data().assert_eq(
+            &TensorData::from([[false, false, true], [false, false, false]]),
+            false,
+        );
+    }
+
+    #[test]
+    fn test_text_batcher_masks_only_the_padding() {
+        let device = Default::default();
+        let batcher = TextBatcher::new(Arc::new(CharTokenizer), 0);
+
+        // The text contains the pad token, which is not padding.
+        let batch: TextBatch<TestBackend> =
+            batcher.batch(vec!["a\0".to_string(), "abc".to_string()], &device);
+
+        batch.tokens.into_data().assert_eq(
+            &TensorData::from([[97, 0, 0], [97, 98, 99]]).convert::<i64>(),
+            false,
+        );
+        batch.mask_pad.into_data().assert_eq(
+            &TensorData::from([[false, false, true], [false, false, false]]),
+            false,
+        );
+    }
+
+    #[test]
+    fn test_packed_batcher() {
+        let device = Default::default();
+        let blocks = SequencePacker::new(3, 0)
+            .pack(vec![vec![1, 2], vec![3, 4, 5]])
+            .collect();
+
+        let batch: PackedBatch<TestBackend> = PackedBatcher::new().batch(blocks, &device);
+
+        batch.tokens.into_data().assert_eq(
+            &TensorData::from([[1, 2, 3], [4, 5, 0]]).convert::<i64>(),
+            false,
+        );
+        batch.sequence_ids.into_data().assert_eq(
+            &TensorData::from([[0, 0, 1], [0, 0, 1]]).convert::<i64>(),
+            false,
+        );
+        batch.mask_pad.into_data().assert_eq(
+            &TensorData::from([[false, false, false], [false, false, true]]),
+            false,
+        );
+    }
+}
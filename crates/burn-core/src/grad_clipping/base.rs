@@ -1,7 +1,9 @@
 use crate as burn;
 
-use crate::{config::Config, tensor::Tensor};
-use burn_tensor::backend::Backend;
+use crate::{config::Config, module::AutodiffModule, optim::GradientsParams, tensor::Tensor};
+use burn_tensor::backend::{AutodiffBackend, Backend};
+
+use super::visitor::{GradientsParamsClipper, GradientsParamsSquaredNorm};
 
 /// Gradient Clipping provides a way to mitigate exploding gradients
 #[derive(Config)]
@@ -11,6 +13,12 @@ pub enum GradientClippingConfig {
 
     /// Clip the gradient by norm.
     Norm(f32),
+
+    /// Clip the gradients by the L2 norm computed over all parameters.
+    GlobalNorm(f32),
+
+    /// Clip the gradients relative to the norm of their parameter (AGC).
+    Adaptive(f32),
 }
 
 impl GradientClippingConfig {
@@ -23,6 +31,8 @@ impl GradientClippingConfig {
         match self {
             GradientClippingConfig::Value(val) => GradientClipping::Value(*val),
             GradientClippingConfig::Norm(val) => GradientClipping::Norm(*val),
+            GradientClippingConfig::GlobalNorm(val) => GradientClipping::GlobalNorm(*val),
+            GradientClippingConfig::Adaptive(val) => GradientClipping::Adaptive(*val),
         }
     }
 }
@@ -30,15 +40,62 @@ impl GradientClippingConfig {
 /// Gradient Clipping provides a way to mitigate exploding gradients
 /// by clipping every component of the gradient by value or by norm during
 /// backpropagation.
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub enum GradientClipping {
     /// Clip the gradient by value.
     Value(f32),
 
     /// Clip the gradient by norm.
     Norm(f32),
+
+    /// Clip the gradients so that the L2 norm computed over all parameters does not exceed the
+    /// given maximum norm.
+    ///
+    /// Unlike [Norm](GradientClipping::Norm), every gradient is scaled by the same factor, which
+    /// preserves the direction of the update. This is the clipping commonly used to train
+    /// transformers.
+    GlobalNorm(f32),
+
+    /// Adaptive gradient clipping (AGC), which clips each gradient when the ratio between its
+    /// norm and the norm of its parameter exceeds the given clipping factor.
+    ///
+    /// See [High-Performance Large-Scale Image Recognition Without Normalization](https://arxiv.org/abs/2102.06171).
+    Adaptive(f32),
 }
 
+/// Error returned when a gradient can't be clipped on its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GradientClippingError {
+    /// [Adaptive](GradientClipping::Adaptive) clipping requires the parameter of the gradient.
+    Adaptive,
+
+    /// [Global norm](GradientClipping::GlobalNorm) clipping requires all the gradients of the
+    /// module.
+    GlobalNorm,
+}
+
+impl core::fmt::Display for GradientClippingError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Adaptive => f.write_str(
+                "Adaptive gradient clipping requires the parameter, use clip_gradient_with_param",
+            ),
+            Self::GlobalNorm => f.write_str(
+                "Global norm gradient clipping requires all the gradients, use clip_gradients_params",
+            ),
+        }
+    }
+}
+
+impl core::error::Error for GradientClippingError {}
+
+/// The epsilon used to avoid clipping gradients of parameters initialized to zero with
+/// [adaptive clipping](GradientClipping::Adaptive).
+const ADAPTIVE_PARAM_EPSILON: f32 = 1e-3;
+
+/// The epsilon used to avoid dividing by a zero gradient norm.
+const NORM_EPSILON: f32 = 1e-6;
+
 impl GradientClipping {
     /// Clip the gradient.
     ///
@@ -49,10 +106,127 @@ impl GradientClipping {
     /// # Returns
     ///
     /// The clipped gradient.
+    ///
+    /// # Panics
+    ///
+    /// With [adaptive](GradientClipping::Adaptive) and [global norm](GradientClipping::GlobalNorm)
+    /// clipping, which can't clip a gradient on its own. See
+    /// [try_clip_gradient](GradientClipping::try_clip_gradient).
     pub fn clip_gradient<B: Backend, const D: usize>(&self, grad: Tensor<B, D>) -> Tensor<B, D> {
+        match self.try_clip_gradient(grad) {
+            Ok(grad) => grad,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Clip the gradient, when it can be clipped on its own.
+    ///
+    /// # Arguments
+    ///
+    /// * `grad` - The gradient to clip.
+    ///
+    /// # Returns
+    ///
+    /// The clipped gradient, or an error with [adaptive](GradientClipping::Adaptive) clipping,
+    /// which requires the parameter, and with [global norm](GradientClipping::GlobalNorm)
+    /// clipping, which requires all the gradients. Use
+    /// [clip_gradient_with_param](GradientClipping::clip_gradient_with_param) and
+    /// [clip_gradients_params](GradientClipping::clip_gradients_params) instead.
+    pub fn try_clip_gradient<B: Backend, const D: usize>(
+        &self,
+        grad: Tensor<B, D>,
+    ) -> Result<Tensor<B, D>, GradientClippingError> {
+        match self {
+            GradientClipping::Value(threshold) => Ok(self.clip_by_value(grad, *threshold)),
+            GradientClipping::Norm(max_norm) => Ok(self.clip_by_norm(grad, *max_norm)),
+            GradientClipping::GlobalNorm(_) => Err(GradientClippingError::GlobalNorm),
+            GradientClipping::Adaptive(_) => Err(GradientClippingError::Adaptive),
+        }
+    }
+
+    /// Clip the gradient of the given parameter.
+    ///
+    /// # Arguments
+    ///
+    /// * `grad` - The gradient to clip.
+    /// * `param` - The parameter associated with the gradient.
+    ///
+    /// # Returns
+    ///
+    /// The clipped gradient, or an error with [global norm](GradientClipping::GlobalNorm)
+    /// clipping, which requires all the gradients. Use
+    /// [clip_gradients_params](GradientClipping::clip_gradients_params) instead.
+    pub fn clip_gradient_with_param<B: Backend, const D: usize>(
+        &self,
+        grad: Tensor<B, D>,
+        param: Tensor<B, D>,
+    ) -> Result<Tensor<B, D>, GradientClippingError> {
+        match self {
+            GradientClipping::Adaptive(clipping) => Ok(self.clip_adaptive(grad, param, *clipping)),
+            _ => self.try_clip_gradient(grad),
+        }
+    }
+
+    /// Clip all the gradients of the given module in one pass.
+    ///
+    /// # Arguments
+    ///
+    /// * `module` - The module owning the parameters.
+    /// * `grads` - The gradients of the module parameters.
+    ///
+    /// # Returns
+    ///
+    /// The clipped gradients, and with [global norm](GradientClipping::GlobalNorm) clipping the
+    /// L2 norm computed over all gradients before clipping. The other clippings don't compute it,
+    /// since reading the norm requires a sync with the device.
+    pub fn clip_gradients_params<B: AutodiffBackend, M: AutodiffModule<B>>(
+        &self,
+        module: &M,
+        mut grads: GradientsParams,
+    ) -> (GradientsParams, Option<f32>) {
         match self {
-            GradientClipping::Value(threshold) => self.clip_by_value(grad, *threshold),
-            GradientClipping::Norm(max_norm) => self.clip_by_norm(grad, *max_norm),
+            GradientClipping::GlobalNorm(max_norm) => {
+                let norm = Self::global_norm(module, &grads);
+
+                if norm > *max_norm {
+                    let scale = max_norm / (norm + NORM_EPSILON);
+                    let mut clipper = GradientsParamsClipper::<M, B>::new(&mut grads, self, scale);
+                    module.visit(&mut clipper);
+                }
+
+                (grads, Some(norm))
+            }
+            _ => {
+                let mut clipper = GradientsParamsClipper::<M, B>::new(&mut grads, self, 1.0);
+                module.visit(&mut clipper);
+
+                (grads, None)
+            }
+        }
+    }
+
+    /// Compute the L2 norm over all the gradients of the given module.
+    ///
+    /// # Arguments
+    ///
+    /// * `module` - The module owning the parameters.
+    /// * `grads` - The gradients of the module parameters.
+    ///
+    /// # Returns
+    ///
+    /// The global L2 norm of the gradients.
+    pub fn global_norm<B: AutodiffBackend, M: AutodiffModule<B>>(
+        module: &M,
+        grads: &GradientsParams,
+    ) -> f32 {
+        use burn_tensor::ElementConversion;
+
+        let mut visitor = GradientsParamsSquaredNorm::<M, B>::new(grads, None);
+        module.visit(&mut visitor);
+
+        match visitor.sum {
+            Some(sum) => sum.sqrt().into_scalar().elem::<f32>(),
+            None => 0.0,
         }
     }
 
@@ -87,6 +261,25 @@ impl GradientClipping {
         }
     }
 
+    fn clip_adaptive<B: Backend, const D: usize>(
+        &self,
+        grad: Tensor<B, D>,
+        param: Tensor<B, D>,
+        clipping: f32,
+    ) -> Tensor<B, D> {
+        let param_norm = Self::l2_norm(param).clamp_min(ADAPTIVE_PARAM_EPSILON);
+        let grad_norm = Self::l2_norm(grad.clone()).clamp_min(NORM_EPSILON);
+
+        // Computed with tensor operations to avoid a sync for every parameter.
+        let scale = param_norm
+            .mul_scalar(clipping)
+            .div(grad_norm)
+            .clamp_max(1.0)
+            .unsqueeze::<D>();
+
+        grad.mul(scale)
+    }
+
     fn l2_norm<B: Backend, const D: usize>(tensor: Tensor<B, D>) -> Tensor<B, 1> {
         let squared = tensor.powf_scalar(2.0);
         let sum = squared.sum();
@@ -98,7 +291,11 @@ impl GradientClipping {
 mod tests {
     use super::*;
     use crate::TestBackend;
-    use crate::tensor::Tensor;
+    use crate::tensor::{Distribution, Tensor};
+    use crate::{
+        TestAutodiffBackend,
+        nn::{Linear, LinearConfig},
+    };
 
     #[test]
     fn test_clip_by_value() {
@@ -135,4 +332,85 @@ mod tests {
             assert!(value <= 0.88);
         }
     }
+
+    #[test]
+    fn test_clip_by_global_norm() {
+        let device = Default::default();
+        let (layer, grads) = layer_with_grads(&device);
+        let norm_before = GradientClipping::global_norm(&layer, &grads);
+
+        let (grads, norm) = GradientClipping::GlobalNorm(0.5).clip_gradients_params(&layer, grads);
+        let norm_after = GradientClipping::global_norm(&layer, &grads);
+
+        assert_eq!(norm, Some(norm_before));
+        assert!(norm_before > 0.5);
+        assert!((norm_after - 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_clip_by_global_norm_keeps_small_gradients() {
+        let device = Default::default();
+        let (layer, grads) = layer_with_grads(&device);
+        let norm_before = GradientClipping::global_norm(&layer, &grads);
+
+        let (grads, _) =
+            GradientClipping::GlobalNorm(norm_before * 2.0).clip_gradients_params(&layer, grads);
+        let norm_after = GradientClipping::global_norm(&layer, &grads);
+
+        assert!((norm_after - norm_before).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_clip_adaptive() {
+        let device = Default::default();
+        let param: Tensor<TestBackend, 2> = Tensor::from_floats([[3.0, 0.0], [0.0, 4.0]], &device);
+        let gradient: Tensor<TestBackend, 2> =
+            Tensor::from_floats([[6.0, 0.0], [0.0, 8.0]], &device);
+
+        // The parameter norm is 5, so the gradient norm can't be above 0.5.
+        let clipped_gradient = GradientClipping::Adaptive(0.1)
+            .clip_gradient_with_param(gradient, param)
+            .unwrap();
+        let norm = GradientClipping::l2_norm(clipped_gradient).into_scalar();
+
+        assert!((norm - 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_clip_gradient_requires_the_whole_context() {
+        let device = Default::default();
+        let gradient = || Tensor::<TestBackend, 1>::from_floats([3.0, 4.0], &device);
+
+        assert_eq!(
+            GradientClipping::Adaptive(0.1)
+                .try_clip_gradient(gradient())
+                .err(),
+            Some(GradientClippingError::Adaptive)
+        );
+        assert_eq!(
+            GradientClipping::GlobalNorm(1.0)
+                .clip_gradient_with_param(gradient(), gradient())
+                .err(),
+            Some(GradientClippingError::GlobalNorm)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_clip_gradient_panics_without_the_parameter() {
+        let gradient = Tensor::<TestBackend, 1>::from_floats([3.0, 4.0], &Default::default());
+
+        GradientClipping::Adaptive(0.1).clip_gradient(gradient);
+    }
+
+    fn layer_with_grads(
+        device: &<TestAutodiffBackend as Backend>::Device,
+    ) -> (Linear<TestAutodiffBackend>, GradientsParams) {
+        let layer: Linear<TestAutodiffBackend> = LinearConfig::new(8, 8).init(device);
+        let input = Tensor::<TestAutodiffBackend, 2>::random([4, 8], Distribution::Default, device);
+        let loss = layer.forward(input).sum().mul_scalar(10.0);
+        let grads = GradientsParams::from_grads(loss.backward(), &layer);
+
+        (layer, grads)
+    }
 }
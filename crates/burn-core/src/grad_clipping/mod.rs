@@ -1,2 +1,4 @@
 mod base;
+mod visitor;
+
 pub use base::*;
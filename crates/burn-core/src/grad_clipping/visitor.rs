@@ -0,0 +1,64 @@
+use super::GradientClipping;
+use crate::module::{AutodiffModule, ModuleVisitor, ParamId};
+use crate::optim::GradientsParams;
+use burn_tensor::{Tensor, backend::AutodiffBackend};
+use core::marker::PhantomData;
+
+#[derive(new)]
+pub struct GradientsParamsSquaredNorm<'a, M: AutodiffModule<B>, B: AutodiffBackend> {
+    grads: &'a GradientsParams,
+    pub(crate) sum: Option<Tensor<B::InnerBackend, 1>>,
+    phantom: PhantomData<M>,
+}
+
+#[derive(new)]
+pub struct GradientsParamsClipper<'a, M: AutodiffModule<B>, B: AutodiffBackend> {
+    grads: &'a mut GradientsParams,
+    clipping: &'a GradientClipping,
+    scale: f32,
+    phantom: PhantomData<(M, B)>,
+}
+
+impl<B, M> ModuleVisitor<B> for GradientsParamsSquaredNorm<'_, M, B>
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B>,
+{
+    fn visit_float<const D: usize>(&mut self, id: ParamId, _tensor: &Tensor<B, D>) {
+        let Some(grad) = self.grads.get::<B::InnerBackend, D>(id) else {
+            return;
+        };
+
+        let squared = grad.powf_scalar(2.0).sum();
+
+        self.sum = Some(match self.sum.take() {
+            Some(sum) => {
+                let device = sum.device();
+                sum.add(squared.to_device(&device))
+            }
+            None => squared,
+        });
+    }
+}
+
+impl<B, M> ModuleVisitor<B> for GradientsParamsClipper<'_, M, B>
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B>,
+{
+    fn visit_float<const D: usize>(&mut self, id: ParamId, tensor: &Tensor<B, D>) {
+        let Some(grad) = self.grads.remove::<B::InnerBackend, D>(id) else {
+            return;
+        };
+
+        let grad = match self.clipping {
+            // The global norm is already computed, only the scaling is left.
+            GradientClipping::GlobalNorm(_) => grad.mul_scalar(self.scale),
+            clipping => clipping
+                .clip_gradient_with_param(grad, tensor.clone().inner())
+                .expect("Only the global norm clipping requires all the gradients"),
+        };
+
+        self.grads.register::<B::InnerBackend, D>(id, grad);
+    }
+}
@@ -1,4 +1,4 @@
-use super::{ParamId, Quantizer};
+use super::{ParamId, Quantizer, RunningState};
 use crate::{
     record::Record,
     tensor::backend::{AutodiffBackend, Backend},
@@ -217,6 +217,20 @@ pub trait ModuleVisitor<B: Backend> {
     fn visit_int<const D: usize>(&mut self, _id: ParamId, _tensor: &Tensor<B, D, Int>) {}
     /// Visit a bool tensor in the module.
     fn visit_bool<const D: usize>(&mut self, _id: ParamId, _tensor: &Tensor<B, D, Bool>) {}
+    /// Visit a running state in the module, e.g. the statistics of a batch norm layer.
+    ///
+    /// By default, its value is visited as a float tensor.
+    fn visit_running_state<const D: usize>(&mut self, state: &RunningState<Tensor<B, D>>) {
+        self.visit_float(state.id(), &state.value())
+    }
+    /// Enter a submodule, named after its field, or its index in a collection of modules.
+    ///
+    /// The tensors visited until the matching [exit](ModuleVisitor::exit_module) belong to the
+    /// submodule, so the path of a tensor in the module is the list of the entered names, e.g.
+    /// `layers.0.weight`.
+    fn enter_module(&mut self, _name: &str) {}
+    /// Exit the submodule entered with the same name.
+    fn exit_module(&mut self, _name: &str) {}
 }
 
 /// Module mapper trait.
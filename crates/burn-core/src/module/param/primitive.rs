@@ -100,8 +100,11 @@ where
     }
 
     fn visit<V: ModuleVisitor<B>>(&self, visitor: &mut V) {
-        self.iter().for_each(|module| {
+        self.iter().enumerate().for_each(|(index, module)| {
+            let name = index.to_string();
+            visitor.enter_module(&name);
             module.visit(visitor);
+            visitor.exit_module(&name);
         });
     }
 
@@ -199,8 +202,11 @@ where
     }
 
     fn visit<V: ModuleVisitor<B>>(&self, visitor: &mut V) {
-        self.iter().for_each(|module| {
+        self.iter().enumerate().for_each(|(index, module)| {
+            let name = index.to_string();
+            visitor.enter_module(&name);
             module.visit(visitor);
+            visitor.exit_module(&name);
         });
     }
 
@@ -288,7 +294,11 @@ macro_rules! impl_module_tuple {
             }
 
             fn visit<V: ModuleVisitor<B>>(&self, visitor: &mut V) {
-                $(self.$i.visit(visitor);)*
+                $(
+                    visitor.enter_module(stringify!($i));
+                    self.$i.visit(visitor);
+                    visitor.exit_module(stringify!($i));
+                )*
             }
 
             fn map<M: ModuleMapper<B>>(self, mapper: &mut M) -> Self {
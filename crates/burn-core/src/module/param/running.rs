@@ -55,6 +55,8 @@ pub struct RunningState<V> {
     id: ParamId,
     values: Arc<Mutex<HashMap<ThreadId, V>>>,
     value: Arc<Mutex<V>>,
+    // The number of updates of a cumulative average.
+    cumulative: Arc<Mutex<Option<usize>>>,
 }
 
 // Implement display for the module
@@ -79,8 +81,7 @@ impl<const D: usize, B: Backend> Module<B> for RunningState<Tensor<B, D>> {
     type Record = Param<Tensor<B, D>>;
 
     fn visit<V: ModuleVisitor<B>>(&self, visitor: &mut V) {
-        let tensor = self.value.lock().unwrap();
-        visitor.visit_float(self.id, &tensor)
+        visitor.visit_running_state(self)
     }
 
     fn map<M: ModuleMapper<B>>(self, mapper: &mut M) -> Self {
@@ -142,6 +143,7 @@ impl<const D: usize, B: Backend> RunningState<Tensor<B, D>> {
             id: ParamId::new(),
             values: Arc::new(Mutex::new(HashMap::new())),
             value: Arc::new(Mutex::new(value)),
+            cumulative: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -151,6 +153,7 @@ impl<const D: usize, B: Backend> RunningState<Tensor<B, D>> {
             id,
             values: Arc::new(Mutex::new(HashMap::new())),
             value: Arc::new(Mutex::new(value)),
+            cumulative: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -161,11 +164,40 @@ impl<const D: usize, B: Backend> RunningState<Tensor<B, D>> {
             id: record.id,
             values: Arc::new(Mutex::new(HashMap::new())),
             value: Arc::new(Mutex::new(tensor)),
+            cumulative: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// The id of the state.
+    pub fn id(&self) -> ParamId {
+        self.id
+    }
+
+    /// Whether the state is the cumulative average of its next updates, where every update has
+    /// the same weight and the current value is discarded, instead of an exponential moving
+    /// average. The state is updated with the [momentum](Self::momentum) of the next update.
+    ///
+    /// This is used to compute the statistics of a batch norm layer over a whole dataset, e.g.
+    /// for the averaged model of the stochastic weight averaging.
+    pub fn set_cumulative(&self, cumulative: bool) {
+        *self.cumulative.lock().unwrap() = cumulative.then_some(0);
+    }
+
+    /// The momentum of the next update of an exponential moving average: the given momentum, or
+    /// the one computing the [cumulative average](Self::set_cumulative) of the updates.
+    pub fn momentum(&self, momentum: f64) -> f64 {
+        match *self.cumulative.lock().unwrap() {
+            Some(count) => 1.0 / (count + 1) as f64,
+            None => momentum,
         }
     }
 
     /// Update the value on the current thread.
     pub fn update(&self, value: Tensor<B, D>) {
+        if let Some(count) = self.cumulative.lock().unwrap().as_mut() {
+            *count += 1;
+        }
+
         let thread_id = get_thread_current_id();
         let mut map = self.values.lock().unwrap();
 
@@ -102,13 +102,13 @@ impl<B: Backend> ModuleDisplay for MultiHeadAttention<B> {
 #[derive(Debug, Clone)]
 pub struct MhaInput<B: Backend> {
     /// Shape `[batch_size, seq_length_1, d_model]`
-    query: Tensor<B, 3>,
+    pub(crate) query: Tensor<B, 3>,
     /// Shape `[batch_size, seq_length_2, d_model]`
-    key: Tensor<B, 3>,
+    pub(crate) key: Tensor<B, 3>,
     /// Shape `[batch_size, seq_length_2, d_model]`
-    value: Tensor<B, 3>,
-    mask_pad: Option<Tensor<B, 2, Bool>>,
-    mask_attn: Option<Tensor<B, 3, Bool>>,
+    pub(crate) value: Tensor<B, 3>,
+    pub(crate) mask_pad: Option<Tensor<B, 2, Bool>>,
+    pub(crate) mask_attn: Option<Tensor<B, 3, Bool>>,
 }
 
 impl MultiHeadAttentionConfig {
@@ -259,33 +259,17 @@ impl<B: Backend> MultiHeadAttention<B> {
 
     fn attn_weights(
         &self,
-        mut attn_scores: Tensor<B, 4>,
+        attn_scores: Tensor<B, 4>,
         mask_pad: Option<Tensor<B, 2, Bool>>,
         mask_attn: Option<Tensor<B, 3, Bool>>,
     ) -> Tensor<B, 4> {
-        if let Some(mask_pad) = mask_pad {
-            let [batch_size, seq_length] = mask_pad.dims();
-
-            attn_scores = attn_scores.mask_fill(
-                mask_pad.reshape([batch_size, 1, 1, seq_length]),
-                self.min_float,
-            );
-        }
-
-        if let Some(mask_attn) = mask_attn {
-            let [batch_size, seq_length_1, seq_length_2] = mask_attn.dims();
-
-            attn_scores = attn_scores.mask_fill(
-                mask_attn.reshape([batch_size, 1, seq_length_1, seq_length_2]),
-                self.min_float,
-            );
-        }
-
-        if self.quiet_softmax {
-            activation::quiet_softmax(attn_scores, 3)
-        } else {
-            activation::softmax(attn_scores, 3)
-        }
+        attention_weights(
+            attn_scores,
+            mask_pad,
+            mask_attn,
+            self.min_float,
+            self.quiet_softmax,
+        )
     }
 
     fn attention_linear(&self, x: Tensor<B, 3>, linear: &nn::Linear<B>) -> Tensor<B, 4> {
@@ -297,6 +281,37 @@ impl<B: Backend> MultiHeadAttention<B> {
     }
 }
 
+/// Masks the attention scores and normalizes them into attention weights.
+pub(crate) fn attention_weights<B: Backend>(
+    mut attn_scores: Tensor<B, 4>,
+    mask_pad: Option<Tensor<B, 2, Bool>>,
+    mask_attn: Option<Tensor<B, 3, Bool>>,
+    min_float: f64,
+    quiet_softmax: bool,
+) -> Tensor<B, 4> {
+    if let Some(mask_pad) = mask_pad {
+        let [batch_size, seq_length] = mask_pad.dims();
+
+        attn_scores =
+            attn_scores.mask_fill(mask_pad.reshape([batch_size, 1, 1, seq_length]), min_float);
+    }
+
+    if let Some(mask_attn) = mask_attn {
+        let [batch_size, seq_length_1, seq_length_2] = mask_attn.dims();
+
+        attn_scores = attn_scores.mask_fill(
+            mask_attn.reshape([batch_size, 1, seq_length_1, seq_length_2]),
+            min_float,
+        );
+    }
+
+    if quiet_softmax {
+        activation::quiet_softmax(attn_scores, 3)
+    } else {
+        activation::softmax(attn_scores, 3)
+    }
+}
+
 /// Cache for the [Multi Head Attention](MultiHeadAttention) layer.
 ///
 /// To be used during inference when decoding tokens.
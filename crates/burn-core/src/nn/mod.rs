@@ -19,6 +19,9 @@ pub mod transformer;
 /// Interpolate module
 pub mod interpolate;
 
+/// Model parallelism module
+pub mod parallel;
+
 mod dropout;
 mod embedding;
 mod gelu;
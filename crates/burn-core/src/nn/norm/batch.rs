@@ -146,17 +146,19 @@ impl<const D: usize, B: Backend> BatchNorm<B, D> {
 
         let running_mean = self.running_mean.value_sync().to_device(&device);
         let running_var = self.running_var.value_sync().to_device(&device);
+        let momentum_mean = self.running_mean.momentum(self.momentum);
+        let momentum_var = self.running_var.momentum(self.momentum);
 
-        let running_mean = running_mean.mul_scalar(1.0 - self.momentum).add(
+        let running_mean = running_mean.mul_scalar(1.0 - momentum_mean).add(
             mean.clone()
                 .detach()
-                .mul_scalar(self.momentum)
+                .mul_scalar(momentum_mean)
                 .reshape([channels]),
         );
-        let running_var = running_var.mul_scalar(1.0 - self.momentum).add(
+        let running_var = running_var.mul_scalar(1.0 - momentum_var).add(
             var.clone()
                 .detach()
-                .mul_scalar(self.momentum)
+                .mul_scalar(momentum_var)
                 .reshape([channels]),
         );
 
@@ -358,6 +360,30 @@ mod tests_2d {
             .assert_approx_eq::<FT>(&expected, Tolerance::rel_abs(1e-4, 2e-3));
     }
 
+    #[test]
+    fn batch_norm_cumulative_running_mean() {
+        let device = Default::default();
+        let module = BatchNormConfig::new(3).init::<TestAutodiffBackend, 2>(&device);
+        let _output = module.forward(input_tensor(&device));
+
+        // The previous value is discarded and the batches have the same weight.
+        module.running_mean.set_cumulative(true);
+        let _output = module.forward(input_tensor(&device));
+        let _output = module.forward(input_tensor(&device).add_scalar(1.0));
+
+        let expected = input_tensor::<TestAutodiffBackend>(&device)
+            .swap_dims(0, 1)
+            .reshape([3, -1])
+            .mean_dim(1)
+            .reshape([3])
+            .add_scalar(0.5);
+        module
+            .running_mean
+            .value_sync()
+            .into_data()
+            .assert_approx_eq::<FT>(&expected.into_data(), Tolerance::rel_abs(1e-4, 1e-4));
+    }
+
     #[test]
     fn batch_norm_running_mean_inner_module() {
         let device = Default::default();
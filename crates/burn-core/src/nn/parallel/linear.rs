@@ -0,0 +1,420 @@
+use crate as burn;
+
+use alloc::vec::Vec;
+
+use super::{assert_split, split_sizes};
+use crate::config::Config;
+use crate::module::Param;
+use crate::module::{Content, DisplaySettings, Module, ModuleDisplay};
+use crate::nn::{Initializer, Linear};
+use crate::tensor::{Tensor, backend::Backend};
+
+/// Configuration to create a [column parallel linear](ColumnParallelLinear) layer using the
+/// [init function](ColumnParallelLinearConfig::init).
+#[derive(Config, Debug)]
+pub struct ColumnParallelLinearConfig {
+    /// The size of the input features.
+    pub d_input: usize,
+    /// The size of the output features, split between the devices.
+    pub d_output: usize,
+    /// If a bias should be applied during the linear transformation.
+    #[config(default = true)]
+    pub bias: bool,
+    /// The type of function used to initialize neural network parameters
+    #[config(
+        default = "Initializer::KaimingUniform{gain:1.0/num_traits::Float::sqrt(3.0), fan_out_only:false}"
+    )]
+    pub initializer: Initializer,
+}
+
+/// Configuration to create a [row parallel linear](RowParallelLinear) layer using the
+/// [init function](RowParallelLinearConfig::init).
+#[derive(Config, Debug)]
+pub struct RowParallelLinearConfig {
+    /// The size of the input features, split between the devices.
+    pub d_input: usize,
+    /// The size of the output features.
+    pub d_output: usize,
+    /// If a bias should be applied during the linear transformation.
+    #[config(default = true)]
+    pub bias: bool,
+    /// The type of function used to initialize neural network parameters
+    #[config(
+        default = "Initializer::KaimingUniform{gain:1.0/num_traits::Float::sqrt(3.0), fan_out_only:false}"
+    )]
+    pub initializer: Initializer,
+}
+
+/// A [linear](Linear) layer whose output features are split between multiple devices.
+///
+/// Each device computes its own output features from the whole input. It is usually followed
+/// by a [row parallel linear](RowParallelLinear) layer taking the
+/// [sharded outputs](ColumnParallelLinear::forward_sharded), so that the features are only
+/// gathered once.
+///
+/// Should be created with [ColumnParallelLinearConfig].
+#[derive(Module, Debug)]
+#[module(custom_display)]
+pub struct ColumnParallelLinear<B: Backend> {
+    /// The linear layer of each device, with a contiguous part of the output features.
+    pub shards: Vec<Linear<B>>,
+}
+
+/// A [linear](Linear) layer whose input features are split between multiple devices.
+///
+/// Each device computes the contribution of its own input features to the output, the
+/// contributions are summed on the device of the first shard.
+///
+/// Should be created with [RowParallelLinearConfig].
+#[derive(Module, Debug)]
+#[module(custom_display)]
+pub struct RowParallelLinear<B: Backend> {
+    /// The linear layer of each device, without bias, with a contiguous part of the input
+    /// features.
+    pub shards: Vec<Linear<B>>,
+    /// The bias, on the device of the first shard.
+    pub bias: Option<Param<Tensor<B, 1>>>,
+}
+
+impl ColumnParallelLinearConfig {
+    /// Initialize a new [column parallel linear](ColumnParallelLinear) module, with one shard per
+    /// device.
+    ///
+    /// # Panics
+    ///
+    /// If no device is given, or if there are more devices than output features.
+    pub fn init<B: Backend>(&self, devices: &[B::Device]) -> ColumnParallelLinear<B> {
+        assert_split(devices, self.d_output, "output features");
+        let shards = split_sizes(self.d_output, devices.len())
+            .into_iter()
+            .zip(devices)
+            .map(|(d_output, device)| {
+                // The fan in and out are the ones of the whole layer.
+                let fan = (Some(self.d_input), Some(self.d_output));
+
+                Linear {
+                    weight: self.initializer.init_with(
+                        [self.d_input, d_output],
+                        fan.0,
+                        fan.1,
+                        device,
+                    ),
+                    bias: self
+                        .bias
+                        .then(|| self.initializer.init_with([d_output], fan.0, fan.1, device)),
+                }
+            })
+            .collect();
+
+        ColumnParallelLinear { shards }
+    }
+}
+
+impl RowParallelLinearConfig {
+    /// Initialize a new [row parallel linear](RowParallelLinear) module, with one shard per
+    /// device.
+    ///
+    /// # Panics
+    ///
+    /// If no device is given, or if there are more devices than input features.
+    pub fn init<B: Backend>(&self, devices: &[B::Device]) -> RowParallelLinear<B> {
+        assert_split(devices, self.d_input, "input features");
+        // The fan in and out are the ones of the whole layer.
+        let fan = (Some(self.d_input), Some(self.d_output));
+
+        let shards = split_sizes(self.d_input, devices.len())
+            .into_iter()
+            .zip(devices)
+            .map(|(d_input, device)| Linear {
+                weight: self
+                    .initializer
+                    .init_with([d_input, self.d_output], fan.0, fan.1, device),
+                bias: None,
+            })
+            .collect();
+        let bias = self.bias.then(|| {
+            self.initializer
+                .init_with([self.d_output], fan.0, fan.1, &devices[0])
+        });
+
+        RowParallelLinear { shards, bias }
+    }
+}
+
+impl<B: Backend> ColumnParallelLinear<B> {
+    /// Split an existing [linear](Linear) layer between the given devices.
+    ///
+    /// # Panics
+    ///
+    /// If no device is given, or if there are more devices than output features.
+    pub fn from_linear(linear: Linear<B>, devices: &[B::Device]) -> Self {
+        let [_, d_output] = linear.weight.dims();
+        assert_split(devices, d_output, "output features");
+        let weight = linear.weight.val();
+        let bias = linear.bias.map(|bias| bias.val());
+
+        let mut start = 0;
+        let shards = split_sizes(d_output, devices.len())
+            .into_iter()
+            .zip(devices)
+            .map(|(d_output, device)| {
+                let shard = Linear {
+                    weight: Param::from_tensor(
+                        weight.clone().narrow(1, start, d_output).to_device(device),
+                    ),
+                    bias: bias.as_ref().map(|bias| {
+                        Param::from_tensor(
+                            bias.clone().narrow(0, start, d_output).to_device(device),
+                        )
+                    }),
+                };
+                start += d_output;
+                shard
+            })
+            .collect();
+
+        Self { shards }
+    }
+
+    /// Applies the forward pass on the input tensor, gathering the output features on the device
+    /// of the input.
+    ///
+    /// # Shapes
+    ///
+    /// - input: `[..., d_input]`
+    /// - output: `[..., d_output]`
+    pub fn forward<const D: usize>(&self, input: Tensor<B, D>) -> Tensor<B, D> {
+        let device = input.device();
+        let outputs = self
+            .forward_sharded(input)
+            .into_iter()
+            .map(|output| output.to_device(&device))
+            .collect();
+
+        Tensor::cat(outputs, D - 1)
+    }
+
+    /// Applies the forward pass on the input tensor, keeping the output features of each shard
+    /// on its device.
+    ///
+    /// # Shapes
+    ///
+    /// - input: `[..., d_input]`
+    /// - outputs: `[..., d_output_shard]` for each shard
+    pub fn forward_sharded<const D: usize>(&self, input: Tensor<B, D>) -> Vec<Tensor<B, D>> {
+        self.shards
+            .iter()
+            .map(|shard| shard.forward(input.clone().to_device(&shard.weight.device())))
+            .collect()
+    }
+}
+
+impl<B: Backend> RowParallelLinear<B> {
+    /// Split an existing [linear](Linear) layer between the given devices.
+    ///
+    /// # Panics
+    ///
+    /// If no device is given, or if there are more devices than input features.
+    pub fn from_linear(linear: Linear<B>, devices: &[B::Device]) -> Self {
+        let [d_input, _] = linear.weight.dims();
+        assert_split(devices, d_input, "input features");
+        let weight = linear.weight.val();
+
+        let mut start = 0;
+        let shards = split_sizes(d_input, devices.len())
+            .into_iter()
+            .zip(devices)
+            .map(|(d_input, device)| {
+                let weight = weight.clone().narrow(0, start, d_input).to_device(device);
+                start += d_input;
+                Linear {
+                    weight: Param::from_tensor(weight),
+                    bias: None,
+                }
+            })
+            .collect();
+        let bias = linear
+            .bias
+            .map(|bias| Param::from_tensor(bias.val().to_device(&devices[0])));
+
+        Self { shards, bias }
+    }
+
+    /// Applies the forward pass on the input tensor, whose features are split between the
+    /// devices. The output is on the device of the input.
+    ///
+    /// # Shapes
+    ///
+    /// - input: `[..., d_input]`
+    /// - output: `[..., d_output]`
+    pub fn forward<const D: usize>(&self, input: Tensor<B, D>) -> Tensor<B, D> {
+        let device = input.device();
+        let sizes = self
+            .shards
+            .iter()
+            .map(|shard| shard.weight.dims()[0])
+            .collect();
+
+        self.forward_sharded(input.split_with_sizes(sizes, D - 1))
+            .to_device(&device)
+    }
+
+    /// Applies the forward pass on the input features of each shard, such as the
+    /// [sharded outputs](ColumnParallelLinear::forward_sharded) of a column parallel layer.
+    /// The output is on the device of the first shard.
+    ///
+    /// # Shapes
+    ///
+    /// - inputs: `[..., d_input_shard]` for each shard
+    /// - output: `[..., d_output]`
+    pub fn forward_sharded<const D: usize>(&self, inputs: Vec<Tensor<B, D>>) -> Tensor<B, D> {
+        let device = self.shards[0].weight.device();
+        let output = self
+            .shards
+            .iter()
+            .zip(inputs)
+            .map(|(shard, input)| {
+                shard
+                    .forward(input.to_device(&shard.weight.device()))
+                    .to_device(&device)
+            })
+            .reduce(|a, b| a + b)
+            .expect("A minimum of one shard");
+
+        match &self.bias {
+            Some(bias) => output + bias.val().unsqueeze(),
+            None => output,
+        }
+    }
+}
+
+impl<B: Backend> ModuleDisplay for ColumnParallelLinear<B> {
+    fn custom_settings(&self) -> Option<DisplaySettings> {
+        DisplaySettings::new()
+            .with_new_line_after_attribute(false)
+            .optional()
+    }
+
+    fn custom_content(&self, content: Content) -> Option<Content> {
+        let [d_input, _] = self.shards[0].weight.dims();
+        let d_output = self
+            .shards
+            .iter()
+            .map(|shard| shard.weight.dims()[1])
+            .sum::<usize>();
+
+        content
+            .add("d_input", &d_input)
+            .add("d_output", &d_output)
+            .add("bias", &self.shards[0].bias.is_some())
+            .add("shards", &self.shards.len())
+            .optional()
+    }
+}
+
+impl<B: Backend> ModuleDisplay for RowParallelLinear<B> {
+    fn custom_settings(&self) -> Option<DisplaySettings> {
+        DisplaySettings::new()
+            .with_new_line_after_attribute(false)
+            .optional()
+    }
+
+    fn custom_content(&self, content: Content) -> Option<Content> {
+        let [_, d_output] = self.shards[0].weight.dims();
+        let d_input = self
+            .shards
+            .iter()
+            .map(|shard| shard.weight.dims()[0])
+            .sum::<usize>();
+
+        content
+            .add("d_input", &d_input)
+            .add("d_output", &d_output)
+            .add("bias", &self.bias.is_some())
+            .add("shards", &self.shards.len())
+            .optional()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestBackend;
+    use crate::nn::LinearConfig;
+    use burn_tensor::{Distribution, Tolerance, ops::FloatElem};
+    type FT = FloatElem<TestBackend>;
+
+    fn devices() -> Vec<<TestBackend as Backend>::Device> {
+        vec![Default::default(); 3]
+    }
+
+    #[test]
+    fn test_column_parallel_linear_matches_linear() {
+        let device = Default::default();
+        let linear = LinearConfig::new(4, 7).init::<TestBackend>(&device);
+        let input = Tensor::<TestBackend, 3>::random([2, 3, 4], Distribution::Default, &device);
+
+        let expected = linear.forward(input.clone());
+        let parallel = ColumnParallelLinear::from_linear(linear, &devices());
+
+        assert_eq!(
+            parallel
+                .shards
+                .iter()
+                .map(|shard| shard.weight.dims()[1])
+                .collect::<Vec<_>>(),
+            vec![3, 2, 2]
+        );
+        parallel
+            .forward(input)
+            .into_data()
+            .assert_approx_eq::<FT>(&expected.into_data(), Tolerance::default());
+    }
+
+    #[test]
+    fn test_row_parallel_linear_matches_linear() {
+        let device = Default::default();
+        let linear = LinearConfig::new(5, 3).init::<TestBackend>(&device);
+        let input = Tensor::<TestBackend, 2>::random([2, 5], Distribution::Default, &device);
+
+        let expected = linear.forward(input.clone());
+        let parallel = RowParallelLinear::from_linear(linear, &devices());
+
+        parallel
+            .forward(input)
+            .into_data()
+            .assert_approx_eq::<FT>(&expected.into_data(), Tolerance::default());
+    }
+
+    #[test]
+    fn test_column_then_row_parallel_linear() {
+        let device = Default::default();
+        let devices = devices();
+        let column = ColumnParallelLinearConfig::new(4, 6).init::<TestBackend>(&devices);
+        let row = RowParallelLinearConfig::new(6, 2).init::<TestBackend>(&devices);
+        let input = Tensor::<TestBackend, 2>::random([3, 4], Distribution::Default, &device);
+
+        let sharded = row.forward_sharded(column.forward_sharded(input.clone()));
+        let gathered = row.forward(column.forward(input));
+
+        sharded
+            .into_data()
+            .assert_approx_eq::<FT>(&gathered.into_data(), Tolerance::default());
+    }
+
+    #[test]
+    #[should_panic = "The 2 output features can't be split between 3 devices"]
+    fn test_column_parallel_linear_rejects_more_devices_than_outputs() {
+        let _linear = ColumnParallelLinearConfig::new(4, 2).init::<TestBackend>(&devices());
+    }
+
+    #[test]
+    fn display() {
+        let linear = ColumnParallelLinearConfig::new(3, 5).init::<TestBackend>(&devices());
+
+        assert_eq!(
+            alloc::format!("{}", linear),
+            "ColumnParallelLinear {d_input: 3, d_output: 5, bias: true, shards: 3, params: 20}"
+        );
+    }
+}
@@ -0,0 +1,363 @@
+use crate as burn;
+
+use alloc::vec::Vec;
+
+use super::{assert_split, split_sizes};
+use crate::config::Config;
+use crate::module::{Content, DisplaySettings, Module, ModuleDisplay, Param};
+use crate::nn::attention::{MhaInput, MhaOutput, MultiHeadAttention, attention_weights};
+use crate::nn::{Dropout, DropoutConfig, Initializer, Linear};
+use crate::tensor::{Tensor, backend::Backend};
+
+/// Configuration to create a [parallel multi head attention](ParallelMultiHeadAttention) layer
+/// using the [init function](ParallelMultiHeadAttentionConfig::init).
+#[derive(Config)]
+pub struct ParallelMultiHeadAttentionConfig {
+    /// The size of each linear layer.
+    pub d_model: usize,
+    /// The number of heads, split between the devices.
+    pub n_heads: usize,
+    /// The dropout rate. Default: 0.1
+    #[config(default = 0.1)]
+    pub dropout: f64,
+    /// The minimum value a float can take. Default: -1.0e4
+    /// This is used to mask attention scores before calculating attention weights.
+    /// A value too low might result in NaN.
+    #[config(default = -1.0e4)]
+    pub min_float: f64,
+    /// Use "quiet softmax" instead of regular softmax.
+    #[config(default = false)]
+    pub quiet_softmax: bool,
+    /// The type of function used to initialize neural network parameters
+    #[config(
+        default = "Initializer::KaimingUniform{gain:1.0/num_traits::Float::sqrt(3.0), fan_out_only:false}"
+    )]
+    pub initializer: Initializer,
+}
+
+/// The [multihead attention](MultiHeadAttention) module with its heads split between multiple
+/// devices.
+///
+/// The query, key and value projections of each device only compute its own heads, and the
+/// output projection only takes the features of its own heads, so the devices only exchange
+/// the inputs and the projected outputs.
+///
+/// Should be created with [ParallelMultiHeadAttentionConfig].
+#[derive(Module, Debug)]
+#[module(custom_display)]
+pub struct ParallelMultiHeadAttention<B: Backend> {
+    /// The attention heads of each device.
+    pub shards: Vec<MhaShard<B>>,
+    /// The bias of the output projection, on the device of the first shard.
+    pub output_bias: Option<Param<Tensor<B, 1>>>,
+    /// Dropout layer.
+    pub dropout: Dropout,
+    /// The size of each linear layer.
+    pub d_model: usize,
+    /// The number of heads.
+    pub n_heads: usize,
+    /// Size of the key and query vectors.
+    pub d_k: usize,
+    /// Minimum value a float can take.
+    pub min_float: f64,
+    /// Use "quiet softmax" instead of regular softmax.
+    pub quiet_softmax: bool,
+}
+
+/// The attention heads of a [parallel multi head attention](ParallelMultiHeadAttention) placed
+/// on one device.
+#[derive(Module, Debug)]
+pub struct MhaShard<B: Backend> {
+    /// Projection of the input features into the query space of the heads.
+    pub query: Linear<B>,
+    /// Projection of the input features into the key space of the heads.
+    pub key: Linear<B>,
+    /// Projection of the input features into the value space of the heads.
+    pub value: Linear<B>,
+    /// Projection of the heads features back to the original space, without bias.
+    pub output: Linear<B>,
+    /// The number of heads of the shard.
+    pub n_heads: usize,
+}
+
+impl ParallelMultiHeadAttentionConfig {
+    /// Initialize a new [parallel multihead attention](ParallelMultiHeadAttention) module, with
+    /// the heads split between the devices.
+    ///
+    /// # Panics
+    ///
+    /// If no device is given, or if there are more devices than heads.
+    pub fn init<B: Backend>(&self, devices: &[B::Device]) -> ParallelMultiHeadAttention<B> {
+        assert_split(devices, self.n_heads, "heads");
+        let d_k = self.d_model / self.n_heads;
+        // The fan in and out are the ones of the whole projections.
+        let init = |shape: [usize; 2], device| {
+            self.initializer
+                .init_with(shape, Some(self.d_model), Some(self.d_model), device)
+        };
+        let init_bias = |len: usize, device| {
+            self.initializer
+                .init_with([len], Some(self.d_model), Some(self.d_model), device)
+        };
+
+        let shards = split_sizes(self.n_heads, devices.len())
+            .into_iter()
+            .zip(devices)
+            .map(|(n_heads, device)| {
+                let d_heads = n_heads * d_k;
+                let projection = || Linear {
+                    weight: init([self.d_model, d_heads], device),
+                    bias: Some(init_bias(d_heads, device)),
+                };
+
+                MhaShard {
+                    query: projection(),
+                    key: projection(),
+                    value: projection(),
+                    output: Linear {
+                        weight: init([d_heads, self.d_model], device),
+                        bias: None,
+                    },
+                    n_heads,
+                }
+            })
+            .collect();
+
+        ParallelMultiHeadAttention {
+            shards,
+            output_bias: Some(init_bias(self.d_model, &devices[0])),
+            dropout: DropoutConfig::new(self.dropout).init(),
+            d_model: self.d_model,
+            n_heads: self.n_heads,
+            d_k,
+            min_float: self.min_float,
+            quiet_softmax: self.quiet_softmax,
+        }
+    }
+}
+
+impl<B: Backend> ParallelMultiHeadAttention<B> {
+    /// Split the heads of an existing [multihead attention](MultiHeadAttention) module between
+    /// the given devices.
+    ///
+    /// # Panics
+    ///
+    /// If no device is given, or if there are more devices than heads.
+    pub fn from_mha(mha: MultiHeadAttention<B>, devices: &[B::Device]) -> Self {
+        assert_split(devices, mha.n_heads, "heads");
+        let d_k = mha.d_k;
+        let mut start = 0;
+
+        let shards = split_sizes(mha.n_heads, devices.len())
+            .into_iter()
+            .zip(devices)
+            .map(|(n_heads, device)| {
+                let range = start * d_k..(start + n_heads) * d_k;
+                start += n_heads;
+
+                let columns = |linear: &Linear<B>| Linear {
+                    weight: Param::from_tensor(
+                        linear
+                            .weight
+                            .val()
+                            .narrow(1, range.start, range.len())
+                            .to_device(device),
+                    ),
+                    bias: linear.bias.as_ref().map(|bias| {
+                        Param::from_tensor(
+                            bias.val()
+                                .narrow(0, range.start, range.len())
+                                .to_device(device),
+                        )
+                    }),
+                };
+
+                MhaShard {
+                    query: columns(&mha.query),
+                    key: columns(&mha.key),
+                    value: columns(&mha.value),
+                    output: Linear {
+                        weight: Param::from_tensor(
+                            mha.output
+                                .weight
+                                .val()
+                                .narrow(0, range.start, range.len())
+                                .to_device(device),
+                        ),
+                        bias: None,
+                    },
+                    n_heads,
+                }
+            })
+            .collect();
+
+        Self {
+            shards,
+            output_bias: mha
+                .output
+                .bias
+                .map(|bias| Param::from_tensor(bias.val().to_device(&devices[0]))),
+            dropout: mha.dropout,
+            d_model: mha.d_model,
+            n_heads: mha.n_heads,
+            d_k,
+            min_float: mha.min_float,
+            quiet_softmax: mha.quiet_softmax,
+        }
+    }
+
+    /// Applies the forward pass on the input tensors, the outputs are on the device of the
+    /// query.
+    ///
+    /// See [MultiHeadAttention](MultiHeadAttention) for more information.
+    ///
+    /// # Shapes
+    ///
+    /// - query: `[batch_size, seq_length_1, d_model]`
+    /// - key: `[batch_size, seq_length_2, d_model]`
+    /// - value: `[batch_size, seq_length_2, d_model]`
+    /// - output: `[batch_size, seq_length_1, d_model]`
+    pub fn forward(&self, input: MhaInput<B>) -> MhaOutput<B> {
+        let device = input.query.device();
+        let [batch_size, seq_length_1, _] = input.query.dims();
+
+        let (contexts, weights): (Vec<_>, Vec<_>) = self
+            .shards
+            .iter()
+            .map(|shard| {
+                let device = shard.output.weight.device();
+                let query = self.attention_linear(input.query.clone(), &shard.query, shard);
+                let key = self.attention_linear(input.key.clone(), &shard.key, shard);
+                let value = self.attention_linear(input.value.clone(), &shard.value, shard);
+
+                let attn_scores = query
+                    .matmul(key.transpose())
+                    .div_scalar((self.d_k as f32).sqrt());
+                let weights = attention_weights(
+                    self.dropout.forward(attn_scores),
+                    input.mask_pad.clone().map(|mask| mask.to_device(&device)),
+                    input.mask_attn.clone().map(|mask| mask.to_device(&device)),
+                    self.min_float,
+                    self.quiet_softmax,
+                );
+
+                let context = weights.clone().matmul(value).swap_dims(1, 2).reshape([
+                    batch_size,
+                    seq_length_1,
+                    shard.n_heads * self.d_k,
+                ]);
+
+                (shard.output.forward(context), weights)
+            })
+            .unzip();
+
+        let context = contexts
+            .into_iter()
+            .map(|context| context.to_device(&device))
+            .reduce(|a, b| a + b)
+            .expect("A minimum of one shard");
+        let context = match &self.output_bias {
+            Some(bias) => context + bias.val().to_device(&device).unsqueeze(),
+            None => context,
+        };
+        let weights = Tensor::cat(
+            weights
+                .into_iter()
+                .map(|weights| weights.to_device(&device))
+                .collect(),
+            1,
+        );
+
+        MhaOutput { weights, context }
+    }
+
+    fn attention_linear(
+        &self,
+        x: Tensor<B, 3>,
+        linear: &Linear<B>,
+        shard: &MhaShard<B>,
+    ) -> Tensor<B, 4> {
+        let [batch_size, seq_length, _d_model] = x.dims();
+        linear
+            .forward(x.to_device(&linear.weight.device()))
+            .reshape([batch_size, seq_length, shard.n_heads, self.d_k])
+            .swap_dims(1, 2)
+    }
+}
+
+impl<B: Backend> ModuleDisplay for ParallelMultiHeadAttention<B> {
+    fn custom_settings(&self) -> Option<DisplaySettings> {
+        DisplaySettings::new()
+            .with_new_line_after_attribute(false)
+            .optional()
+    }
+
+    fn custom_content(&self, content: Content) -> Option<Content> {
+        content
+            .add("d_model", &self.d_model)
+            .add("n_heads", &self.n_heads)
+            .add("d_k", &self.d_k)
+            .add("shards", &self.shards.len())
+            .add("dropout", &self.dropout.prob)
+            .add("min_float", &self.min_float)
+            .add("quiet_softmax", &self.quiet_softmax)
+            .optional()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestBackend;
+    use crate::nn::attention::{MultiHeadAttentionConfig, generate_autoregressive_mask};
+    use burn_tensor::{Distribution, Tolerance, ops::FloatElem};
+    type FT = FloatElem<TestBackend>;
+
+    #[test]
+    fn test_parallel_mha_matches_mha() {
+        let device = Default::default();
+        let devices = vec![device; 2];
+        let mha = MultiHeadAttentionConfig::new(12, 3)
+            .with_dropout(0.0)
+            .init::<TestBackend>(&device);
+        let tensor = Tensor::<TestBackend, 3>::random([2, 4, 12], Distribution::Default, &device);
+        let mask = generate_autoregressive_mask::<TestBackend>(2, 4, &device);
+        let input = MhaInput::self_attn(tensor).mask_attn(mask);
+
+        let expected = mha.forward(input.clone());
+        let parallel = ParallelMultiHeadAttention::from_mha(mha, &devices);
+        let output = parallel.forward(input);
+
+        assert_eq!(
+            parallel
+                .shards
+                .iter()
+                .map(|shard| shard.n_heads)
+                .collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+        // The heads are computed with different matmul shapes, so the results slightly differ.
+        let tolerance = Tolerance::absolute(1e-5);
+        output
+            .context
+            .into_data()
+            .assert_approx_eq::<FT>(&expected.context.into_data(), tolerance);
+        output
+            .weights
+            .into_data()
+            .assert_approx_eq::<FT>(&expected.weights.into_data(), tolerance);
+    }
+
+    #[test]
+    fn test_parallel_mha_init_shapes() {
+        let devices = vec![Default::default(); 2];
+        let mha = ParallelMultiHeadAttentionConfig::new(8, 4).init::<TestBackend>(&devices);
+        let tensor =
+            Tensor::<TestBackend, 3>::random([1, 3, 8], Distribution::Default, &devices[0]);
+
+        let output = mha.forward(MhaInput::self_attn(tensor));
+
+        assert_eq!(output.context.dims(), [1, 3, 8]);
+        assert_eq!(output.weights.dims(), [1, 4, 3, 3]);
+    }
+}
@@ -0,0 +1,63 @@
+mod linear;
+mod mha;
+mod pipeline;
+
+pub use linear::*;
+pub use mha::*;
+pub use pipeline::*;
+
+use alloc::vec::Vec;
+
+/// Panics with a clear message when no device is given to split a module between.
+#[track_caller]
+pub(crate) fn assert_devices<D>(devices: &[D]) {
+    assert!(
+        !devices.is_empty(),
+        "At least one device is required to split a module between devices"
+    );
+}
+
+/// Panics with a clear message when the `len` items of a module can't be split between the
+/// devices, i.e. when no device is given or when some devices would have no item.
+#[track_caller]
+pub(crate) fn assert_split<D>(devices: &[D], len: usize, items: &str) {
+    assert_devices(devices);
+    assert!(
+        devices.len() <= len,
+        "The {len} {items} can't be split between {} devices",
+        devices.len()
+    );
+}
+
+/// Split `len` items into `num` contiguous parts whose sizes differ by at most one, the first
+/// parts being the largest.
+pub(crate) fn split_sizes(len: usize, num: usize) -> Vec<usize> {
+    let (size, remainder) = (len / num, len % num);
+    (0..num)
+        .map(|index| size + usize::from(index < remainder))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic = "At least one device is required"]
+    fn test_devices_should_not_be_empty() {
+        assert_devices::<usize>(&[]);
+    }
+
+    #[test]
+    #[should_panic = "The 2 features can't be split between 3 devices"]
+    fn test_devices_should_not_outnumber_the_items() {
+        assert_split(&[0, 1, 2], 2, "features");
+    }
+
+    #[test]
+    fn test_split_sizes() {
+        assert_eq!(split_sizes(10, 3), vec![4, 3, 3]);
+        assert_eq!(split_sizes(2, 4), vec![1, 1, 0, 0]);
+        assert_eq!(split_sizes(6, 2), vec![3, 3]);
+    }
+}
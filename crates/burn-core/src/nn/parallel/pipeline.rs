@@ -0,0 +1,583 @@
+use crate as burn;
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use super::{assert_devices, split_sizes};
+use crate::config::Config;
+use crate::module::{Ignored, Module};
+use crate::nn::transformer::{
+    TransformerEncoder, TransformerEncoderInput, TransformerEncoderLayer,
+};
+use crate::optim::{GradientsAccumulator, GradientsParams};
+use crate::tensor::backend::{AutodiffBackend, Backend};
+use crate::tensor::{Device, Tensor};
+
+/// The order in which the micro-batches go through the stages of a
+/// [pipeline](PipelineTransformerEncoder).
+#[derive(Config, Debug, PartialEq, Eq)]
+pub enum PipelineSchedule {
+    /// Every stage runs the forward pass of all the micro-batches before their backward pass,
+    /// as in [GPipe](https://arxiv.org/abs/1811.06965).
+    ///
+    /// Each stage keeps the activations of all the micro-batches.
+    GPipe,
+
+    /// After a warm-up, every stage alternates between the forward pass of a micro-batch and the
+    /// backward pass of an earlier one, as in [PipeDream](https://arxiv.org/abs/1806.03377).
+    ///
+    /// The first stage keeps the activations of at most as many micro-batches as there are
+    /// stages, and the last stage of only one.
+    OneForwardOneBackward,
+}
+
+/// A step of a [pipeline schedule](PipelineSchedule).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipelineStep {
+    /// The forward pass of a micro-batch through a stage.
+    Forward {
+        /// The stage.
+        stage: usize,
+        /// The micro-batch.
+        micro_batch: usize,
+    },
+    /// The backward pass of a micro-batch through a stage.
+    Backward {
+        /// The stage.
+        stage: usize,
+        /// The micro-batch.
+        micro_batch: usize,
+    },
+}
+
+impl PipelineSchedule {
+    /// The steps of the schedule, in the order they are executed.
+    ///
+    /// Each stage executes its own steps in order as soon as their inputs are available, every
+    /// step taking the same time. The steps are ordered by their start time, then by stage.
+    pub fn steps(&self, num_stages: usize, num_micro_batches: usize) -> Vec<PipelineStep> {
+        let queues = (0..num_stages)
+            .map(|stage| self.stage_steps(stage, num_stages, num_micro_batches))
+            .collect::<Vec<_>>();
+
+        // The time at which each step is done, by stage and micro-batch.
+        let mut forward_done = vec![vec![None; num_micro_batches]; num_stages];
+        let mut backward_done = vec![vec![None; num_micro_batches]; num_stages];
+        let mut positions = vec![0; num_stages];
+        let mut steps = Vec::with_capacity(2 * num_stages * num_micro_batches);
+        let is_done = |done: Option<usize>, time: usize| done.is_some_and(|done| done <= time);
+
+        let mut time = 0;
+        while steps.len() < 2 * num_stages * num_micro_batches {
+            for (stage, queue) in queues.iter().enumerate() {
+                let Some(step) = queue.get(positions[stage]) else {
+                    continue;
+                };
+
+                let ready = match *step {
+                    PipelineStep::Forward { micro_batch, .. } => {
+                        stage == 0 || is_done(forward_done[stage - 1][micro_batch], time)
+                    }
+                    PipelineStep::Backward { micro_batch, .. } => {
+                        is_done(forward_done[stage][micro_batch], time)
+                            && (stage + 1 == num_stages
+                                || is_done(backward_done[stage + 1][micro_batch], time))
+                    }
+                };
+                if !ready {
+                    continue;
+                }
+
+                match *step {
+                    PipelineStep::Forward { micro_batch, .. } => {
+                        forward_done[stage][micro_batch] = Some(time + 1)
+                    }
+                    PipelineStep::Backward { micro_batch, .. } => {
+                        backward_done[stage][micro_batch] = Some(time + 1)
+                    }
+                }
+                positions[stage] += 1;
+                steps.push(*step);
+            }
+            time += 1;
+        }
+
+        steps
+    }
+
+    /// The steps of one stage, in the order it executes them.
+    fn stage_steps(
+        &self,
+        stage: usize,
+        num_stages: usize,
+        num_micro_batches: usize,
+    ) -> Vec<PipelineStep> {
+        let forward = |micro_batch| PipelineStep::Forward { stage, micro_batch };
+        let backward = |micro_batch| PipelineStep::Backward { stage, micro_batch };
+
+        match self {
+            Self::GPipe => (0..num_micro_batches)
+                .map(forward)
+                .chain((0..num_micro_batches).map(backward))
+                .collect(),
+            Self::OneForwardOneBackward => {
+                let warmup = (num_stages - stage - 1).min(num_micro_batches);
+
+                (0..warmup)
+                    .map(forward)
+                    .chain((warmup..num_micro_batches).flat_map(|micro_batch| {
+                        [forward(micro_batch), backward(micro_batch - warmup)]
+                    }))
+                    .chain((num_micro_batches - warmup..num_micro_batches).map(backward))
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Configuration to create a [pipeline transformer encoder](PipelineTransformerEncoder) using the
+/// [init function](PipelineConfig::init).
+#[derive(Config, Debug)]
+pub struct PipelineConfig {
+    /// The number of micro-batches each batch is split into.
+    #[config(default = 4)]
+    pub num_micro_batches: usize,
+    /// The order of the forward and backward passes of the micro-batches.
+    #[config(default = "PipelineSchedule::OneForwardOneBackward")]
+    pub schedule: PipelineSchedule,
+}
+
+/// A [transformer encoder](TransformerEncoder) whose layers are split into stages placed on
+/// different devices.
+///
+/// The batches are split into micro-batches, so that the stages can work on different
+/// micro-batches at the same time with asynchronous backends.
+/// [forward_backward](PipelineTransformerEncoder::forward_backward) runs the backward pass of
+/// each stage separately, following the [schedule](PipelineSchedule), so that the activations
+/// of a micro-batch are released as soon as possible. The regular
+/// [forward](PipelineTransformerEncoder::forward) pass is also differentiable across the
+/// devices.
+///
+/// Should be created with [PipelineConfig].
+#[derive(Module, Debug)]
+pub struct PipelineTransformerEncoder<B: Backend> {
+    /// The layers of each stage, placed on the device of the stage.
+    pub stages: Vec<Vec<TransformerEncoderLayer<B>>>,
+    /// The number of micro-batches each batch is split into.
+    pub num_micro_batches: usize,
+    /// The order of the forward and backward passes of the micro-batches.
+    pub schedule: Ignored<PipelineSchedule>,
+}
+
+/// The result of the [forward and backward passes](PipelineTransformerEncoder::forward_backward)
+/// of a pipeline.
+pub struct PipelineOutput<B: AutodiffBackend> {
+    /// The gradients of the parameters of the pipeline, summed over the micro-batches.
+    pub grads: GradientsParams,
+    /// The sum of the losses of the micro-batches, on the device of the input.
+    pub loss: Tensor<B::InnerBackend, 1>,
+    /// The output of the encoder, on the device of the input.
+    pub output: Tensor<B::InnerBackend, 3>,
+    /// The gradient of the loss with respect to the input, on the device of the input.
+    ///
+    /// It can be back-propagated through the modules computing the input, such as an embedding,
+    /// by calling `backward` on the sum of the input multiplied by this gradient.
+    pub input_grad: Tensor<B::InnerBackend, 3>,
+}
+
+impl PipelineConfig {
+    /// Split the layers of the encoder into contiguous stages, one per device, each one with
+    /// about the same number of layers.
+    pub fn init<B: Backend>(
+        &self,
+        encoder: TransformerEncoder<B>,
+        devices: &[B::Device],
+    ) -> PipelineTransformerEncoder<B> {
+        assert_devices(devices);
+        assert!(
+            !encoder.layers.is_empty(),
+            "The encoder must have at least one layer to be split into stages"
+        );
+        let num_stages = devices.len().min(encoder.layers.len());
+        let mut layers = encoder.layers.into_iter();
+
+        let stages = split_sizes(layers.len(), num_stages)
+            .into_iter()
+            .zip(devices)
+            .map(|(num_layers, device)| {
+                layers
+                    .by_ref()
+                    .take(num_layers)
+                    .map(|layer| layer.fork(device))
+                    .collect()
+            })
+            .collect();
+
+        PipelineTransformerEncoder {
+            stages,
+            num_micro_batches: self.num_micro_batches,
+            schedule: Ignored(self.schedule.clone()),
+        }
+    }
+}
+
+impl<B: Backend> PipelineTransformerEncoder<B> {
+    /// Applies the forward pass on the input tensor, one micro-batch at a time. The output is on
+    /// the device of the input.
+    ///
+    /// # Shapes
+    ///
+    /// - tensor: `[batch_size, seq_length, d_model]`
+    /// - output: `[batch_size, seq_length, d_model]`
+    pub fn forward(&self, input: TransformerEncoderInput<B>) -> Tensor<B, 3> {
+        let device = input.tensor.device();
+        let devices = self.devices();
+
+        let outputs = self
+            .micro_batches(input)
+            .into_iter()
+            .map(|(_, micro_batch)| {
+                let mut x = micro_batch.tensor.clone();
+                for (stage, device) in devices.iter().enumerate() {
+                    x = self.forward_stage(stage, x.to_device(device), &micro_batch);
+                }
+                x.to_device(&device)
+            })
+            .collect();
+
+        Tensor::cat(outputs, 0)
+    }
+
+    /// The device of each stage.
+    pub fn devices(&self) -> Vec<Device<B>> {
+        self.stages
+            .iter()
+            .map(|layers| layers[0].devices()[0].clone())
+            .collect()
+    }
+
+    fn forward_stage(
+        &self,
+        stage: usize,
+        mut x: Tensor<B, 3>,
+        input: &TransformerEncoderInput<B>,
+    ) -> Tensor<B, 3> {
+        let device = x.device();
+        let mask_pad = input.mask_pad.clone().map(|mask| mask.to_device(&device));
+        let mask_attn = input.mask_attn.clone().map(|mask| mask.to_device(&device));
+
+        for layer in self.stages[stage].iter() {
+            x = layer.forward(x, mask_pad.clone(), mask_attn.clone());
+        }
+
+        x
+    }
+
+    /// Split the input into micro-batches, with the range of the items of each one.
+    fn micro_batches(
+        &self,
+        input: TransformerEncoderInput<B>,
+    ) -> Vec<(Range<usize>, TransformerEncoderInput<B>)> {
+        let [batch_size, _, _] = input.tensor.dims();
+        let num_micro_batches = self.num_micro_batches.clamp(1, batch_size.max(1));
+
+        let mut start = 0;
+        split_sizes(batch_size, num_micro_batches)
+            .into_iter()
+            .map(|size| {
+                let range = start..start + size;
+                start += size;
+
+                let micro_batch = TransformerEncoderInput {
+                    tensor: input.tensor.clone().narrow(0, range.start, size),
+                    mask_pad: input
+                        .mask_pad
+                        .clone()
+                        .map(|mask| mask.narrow(0, range.start, size)),
+                    mask_attn: input
+                        .mask_attn
+                        .clone()
+                        .map(|mask| mask.narrow(0, range.start, size)),
+                };
+                (range, micro_batch)
+            })
+            .collect()
+    }
+}
+
+impl<B: AutodiffBackend> PipelineTransformerEncoder<B> {
+    /// Applies the forward and backward passes on the input tensor, following the
+    /// [schedule](PipelineSchedule) of the pipeline.
+    ///
+    /// The loss is computed from the output of each micro-batch, along with the range of its
+    /// items in the batch, e.g. to select the targets. Since the gradients are summed over the
+    /// micro-batches, a mean over the batch requires scaling the loss of each micro-batch by
+    /// its share of the batch.
+    ///
+    /// # Shapes
+    ///
+    /// - tensor: `[batch_size, seq_length, d_model]`
+    /// - output: `[batch_size, seq_length, d_model]`
+    pub fn forward_backward<F>(
+        &self,
+        input: TransformerEncoderInput<B>,
+        mut loss_fn: F,
+    ) -> PipelineOutput<B>
+    where
+        F: FnMut(Tensor<B, 3>, Range<usize>) -> Tensor<B, 1>,
+    {
+        let device = input.tensor.device();
+        let devices = self.devices();
+        let micro_batches = self.micro_batches(input);
+        let num_stages = self.stages.len();
+        let num_micro_batches = micro_batches.len();
+
+        // The input and output of each stage for each micro-batch, released by the backward pass.
+        let mut inputs: Vec<Vec<Option<Tensor<B, 3>>>> = slots(num_stages, num_micro_batches);
+        let mut outputs: Vec<Vec<Option<Tensor<B, 3>>>> = slots(num_stages, num_micro_batches);
+        // The gradient of the output of each stage, computed by the backward pass of the next one.
+        let mut grad_outputs: Vec<Vec<Option<Tensor<B::InnerBackend, 3>>>> =
+            slots(num_stages, num_micro_batches);
+
+        let mut results = (0..num_micro_batches).map(|_| None).collect::<Vec<_>>();
+        let mut input_grads = (0..num_micro_batches).map(|_| None).collect::<Vec<_>>();
+        let mut losses = Vec::with_capacity(num_micro_batches);
+        let mut accumulator = GradientsAccumulator::<Self>::new();
+
+        for step in self.schedule.steps(num_stages, num_micro_batches) {
+            match step {
+                PipelineStep::Forward { stage, micro_batch } => {
+                    let (_, input) = &micro_batches[micro_batch];
+                    let x = match stage {
+                        0 => input.tensor.clone(),
+                        _ => outputs[stage - 1][micro_batch].clone().unwrap(),
+                    };
+                    // Each stage has its own graph, starting from its input.
+                    let x = x.detach().to_device(&devices[stage]).require_grad();
+
+                    outputs[stage][micro_batch] = Some(self.forward_stage(stage, x.clone(), input));
+                    inputs[stage][micro_batch] = Some(x);
+                }
+                PipelineStep::Backward { stage, micro_batch } => {
+                    let output = outputs[stage][micro_batch].take().unwrap();
+
+                    let grads = match grad_outputs[stage][micro_batch].take() {
+                        Some(grad) => {
+                            let grad = Tensor::from_inner(grad.to_device(&devices[stage]));
+                            (output * grad).sum().backward()
+                        }
+                        None => {
+                            let (range, _) = &micro_batches[micro_batch];
+                            results[micro_batch] = Some(output.clone().inner().to_device(&device));
+
+                            let loss = loss_fn(output, range.clone());
+                            losses.push(loss.clone().inner().to_device(&device));
+                            loss.backward()
+                        }
+                    };
+
+                    let x = inputs[stage][micro_batch].take().unwrap();
+                    let grad = x.grad(&grads).unwrap_or_else(|| x.inner().zeros_like());
+                    match stage {
+                        0 => input_grads[micro_batch] = Some(grad.to_device(&device)),
+                        _ => grad_outputs[stage - 1][micro_batch] = Some(grad),
+                    }
+
+                    accumulator.accumulate(self, GradientsParams::from_grads(grads, self));
+                }
+            }
+        }
+
+        PipelineOutput {
+            grads: accumulator.grads(),
+            loss: Tensor::cat(losses, 0).sum(),
+            output: Tensor::cat(results.into_iter().flatten().collect(), 0),
+            input_grad: Tensor::cat(input_grads.into_iter().flatten().collect(), 0),
+        }
+    }
+}
+
+fn slots<T>(num_stages: usize, num_micro_batches: usize) -> Vec<Vec<Option<T>>> {
+    (0..num_stages)
+        .map(|_| (0..num_micro_batches).map(|_| None).collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestAutodiffBackend;
+    use crate::module::{ModuleVisitor, ParamId};
+    use crate::nn::transformer::TransformerEncoderConfig;
+    use burn_tensor::{Distribution, Tolerance, ops::FloatElem};
+    type FT = FloatElem<TestAutodiffBackend>;
+    type InnerBackend = <TestAutodiffBackend as AutodiffBackend>::InnerBackend;
+
+    fn pipeline(schedule: PipelineSchedule) -> PipelineTransformerEncoder<TestAutodiffBackend> {
+        let device = Default::default();
+        let encoder = TransformerEncoderConfig::new(8, 16, 2, 3)
+            .with_dropout(0.0)
+            .init::<TestAutodiffBackend>(&device);
+
+        PipelineConfig::new()
+            .with_num_micro_batches(3)
+            .with_schedule(schedule)
+            .init(encoder, &[device, device])
+    }
+
+    fn assert_valid(steps: &[PipelineStep], num_stages: usize, num_micro_batches: usize) {
+        assert_eq!(steps.len(), 2 * num_stages * num_micro_batches);
+
+        for (index, step) in steps.iter().enumerate() {
+            let dependencies = match *step {
+                PipelineStep::Forward { stage, micro_batch } if stage > 0 => {
+                    vec![PipelineStep::Forward {
+                        stage: stage - 1,
+                        micro_batch,
+                    }]
+                }
+                PipelineStep::Forward { .. } => vec![],
+                PipelineStep::Backward { stage, micro_batch } => {
+                    let mut dependencies = vec![PipelineStep::Forward { stage, micro_batch }];
+                    if stage + 1 < num_stages {
+                        dependencies.push(PipelineStep::Backward {
+                            stage: stage + 1,
+                            micro_batch,
+                        });
+                    }
+                    dependencies
+                }
+            };
+
+            for dependency in dependencies {
+                assert!(steps[..index].contains(&dependency), "{step:?}");
+            }
+        }
+    }
+
+    /// The maximum number of micro-batches whose activations are kept by the given stage.
+    fn max_in_flight(steps: &[PipelineStep], stage: usize) -> usize {
+        let mut in_flight = 0;
+        let mut max = 0;
+        for step in steps {
+            match *step {
+                PipelineStep::Forward { stage: s, .. } if s == stage => in_flight += 1,
+                PipelineStep::Backward { stage: s, .. } if s == stage => in_flight -= 1,
+                _ => {}
+            }
+            max = max.max(in_flight);
+        }
+        max
+    }
+
+    #[test]
+    fn test_schedules_are_valid() {
+        for schedule in [
+            PipelineSchedule::GPipe,
+            PipelineSchedule::OneForwardOneBackward,
+        ] {
+            for (num_stages, num_micro_batches) in [(1, 1), (3, 2), (4, 8)] {
+                let steps = schedule.steps(num_stages, num_micro_batches);
+                assert_valid(&steps, num_stages, num_micro_batches);
+            }
+        }
+    }
+
+    #[test]
+    fn test_one_forward_one_backward_limits_activations() {
+        let gpipe = PipelineSchedule::GPipe.steps(4, 8);
+        let one_f_one_b = PipelineSchedule::OneForwardOneBackward.steps(4, 8);
+
+        for stage in 0..4 {
+            assert_eq!(max_in_flight(&gpipe, stage), 8);
+            assert_eq!(max_in_flight(&one_f_one_b, stage), 4 - stage);
+        }
+    }
+
+    #[test]
+    fn test_pipeline_forward_matches_encoder() {
+        let device = Default::default();
+        let encoder = TransformerEncoderConfig::new(8, 16, 2, 3)
+            .with_dropout(0.0)
+            .init::<TestAutodiffBackend>(&device);
+        let tensor =
+            Tensor::<TestAutodiffBackend, 3>::random([4, 5, 8], Distribution::Default, &device);
+
+        let expected = encoder.forward(TransformerEncoderInput::new(tensor.clone()));
+        let pipeline = PipelineConfig::new().init(encoder, &[device, device]);
+        let output = pipeline.forward(TransformerEncoderInput::new(tensor));
+
+        assert_eq!(pipeline.stages.len(), 2);
+        assert_eq!(pipeline.stages[0].len(), 2);
+        output
+            .into_data()
+            .assert_approx_eq::<FT>(&expected.into_data(), Tolerance::default());
+    }
+
+    struct GradsComparator<'a> {
+        actual: &'a GradientsParams,
+        expected: &'a GradientsParams,
+        count: usize,
+    }
+
+    impl ModuleVisitor<TestAutodiffBackend> for GradsComparator<'_> {
+        fn visit_float<const D: usize>(
+            &mut self,
+            id: ParamId,
+            _tensor: &Tensor<TestAutodiffBackend, D>,
+        ) {
+            let actual = self.actual.get::<InnerBackend, D>(id).unwrap();
+            let expected = self.expected.get::<InnerBackend, D>(id).unwrap();
+            actual
+                .into_data()
+                .assert_approx_eq::<FT>(&expected.into_data(), Tolerance::rel_abs(1e-4, 1e-5));
+            self.count += 1;
+        }
+    }
+
+    #[test]
+    fn test_pipeline_gradients_match_full_backward() {
+        let device = Default::default();
+        let tensor =
+            Tensor::<TestAutodiffBackend, 3>::random([5, 4, 8], Distribution::Default, &device)
+                .require_grad();
+
+        for schedule in [
+            PipelineSchedule::GPipe,
+            PipelineSchedule::OneForwardOneBackward,
+        ] {
+            let pipeline = pipeline(schedule);
+
+            let output = pipeline.forward(TransformerEncoderInput::new(tensor.clone()));
+            let mut grads = output.clone().powf_scalar(2.0).sum().backward();
+            let expected_input_grad = tensor.grad_remove(&mut grads).unwrap();
+            let expected = GradientsParams::from_grads(grads, &pipeline);
+
+            let result = pipeline.forward_backward(
+                TransformerEncoderInput::new(tensor.clone()),
+                |output, _range| output.powf_scalar(2.0).sum(),
+            );
+
+            let mut comparator = GradsComparator {
+                actual: &result.grads,
+                expected: &expected,
+                count: 0,
+            };
+            pipeline.visit(&mut comparator);
+
+            assert_eq!(comparator.count, expected.len());
+            result
+                .output
+                .into_data()
+                .assert_approx_eq::<FT>(&output.clone().inner().into_data(), Tolerance::default());
+            result.loss.into_data().assert_approx_eq::<FT>(
+                &output.inner().powf_scalar(2.0).sum().into_data(),
+                Tolerance::rel_abs(1e-4, 1e-4),
+            );
+            result.input_grad.into_data().assert_approx_eq::<FT>(
+                &expected_input_grad.into_data(),
+                Tolerance::rel_abs(1e-4, 1e-5),
+            );
+        }
+    }
+}
@@ -106,9 +106,9 @@ impl<B: Backend> ModuleDisplay for TransformerEncoder<B> {
 /// [Transformer Encoder](TransformerEncoder) forward pass input argument.
 #[derive(Debug)]
 pub struct TransformerEncoderInput<B: Backend> {
-    tensor: Tensor<B, 3>,
-    mask_pad: Option<Tensor<B, 2, Bool>>,
-    mask_attn: Option<Tensor<B, 3, Bool>>,
+    pub(crate) tensor: Tensor<B, 3>,
+    pub(crate) mask_pad: Option<Tensor<B, 2, Bool>>,
+    pub(crate) mask_attn: Option<Tensor<B, 3, Bool>>,
 }
 
 impl<B: Backend> TransformerEncoderInput<B> {
@@ -239,7 +239,7 @@ impl<B: Backend> TransformerEncoderLayer<B> {
         }
     }
 
-    fn forward(
+    pub(crate) fn forward(
         &self,
         input: Tensor<B, 3>,
         mask_pad: Option<Tensor<B, 2, Bool>>,
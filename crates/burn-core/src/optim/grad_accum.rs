@@ -38,6 +38,11 @@ impl<M> GradientsAccumulator<M> {
         module.visit(&mut visitor);
     }
 
+    /// Return the accumulated gradients without resetting the accumulator state.
+    pub fn peek(&self) -> &GradientsParams {
+        &self.grads
+    }
+
     /// Return the accumulated gradients and reset the accumulator state.
     pub fn grads(&mut self) -> GradientsParams {
         let mut grads = GradientsParams::new();
@@ -74,13 +74,22 @@ where
 {
     type Record = HashMap<ParamId, AdaptorRecord<O, B>>;
 
-    fn step(&mut self, lr: LearningRate, module: M, mut grads: GradientsParams) -> M {
+    fn step(&mut self, lr: LearningRate, module: M, grads: GradientsParams) -> M {
+        // Global norm clipping requires every gradient, so it can't be applied per parameter.
+        let (mut grads, grad_clipping) = match &self.grad_clipping {
+            Some(clipping @ GradientClipping::GlobalNorm(_)) => {
+                let (grads, _norm) = clipping.clip_gradients_params(&module, grads);
+                (grads, None)
+            }
+            grad_clipping => (grads, grad_clipping.as_ref()),
+        };
+
         let mut mapper = SimpleOptimizerMapper::<M, B, O>::new(
             &self.optim,
             &mut self.records,
             &mut grads,
             lr,
-            self.grad_clipping.as_ref(),
+            grad_clipping,
         );
         module.map(&mut mapper)
     }
@@ -124,15 +133,18 @@ where
             let is_require_grad = tensor.is_require_grad();
             let (key, record) = self.records.remove_entry(&id).unzip();
 
+            let tensor = tensor.inner();
             let clipped_grad = if let Some(g_clipping) = self.grad_clipping {
-                g_clipping.clip_gradient(grad)
+                g_clipping
+                    .clip_gradient_with_param(grad, tensor.clone())
+                    .expect("The global norm clipping is applied to all the gradients beforehand")
             } else {
                 grad
             };
 
             let (tensor, state) = self.optimizer.step(
                 self.lr,
-                tensor.inner(),
+                tensor,
                 clipped_grad,
                 record.map(|record| O::to_device(record.into_state(), &device)),
             );
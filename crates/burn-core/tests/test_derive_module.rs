@@ -234,6 +234,66 @@ mod num_params {
     }
 }
 
+mod visit {
+    use super::*;
+    use burn::module::{ModuleVisitor, ParamId};
+
+    #[derive(Default)]
+    struct PathVisitor {
+        path: Vec<String>,
+        paths: Vec<String>,
+    }
+
+    impl<B: Backend> ModuleVisitor<B> for PathVisitor {
+        fn visit_float<const D: usize>(&mut self, _id: ParamId, _tensor: &Tensor<B, D>) {
+            self.paths.push(self.path.join("."));
+        }
+
+        fn enter_module(&mut self, name: &str) {
+            self.path.push(name.to_string());
+        }
+
+        fn exit_module(&mut self, name: &str) {
+            assert_eq!(self.path.pop().as_deref(), Some(name));
+        }
+    }
+
+    #[test]
+    fn should_visit_the_paths_of_the_submodules() {
+        let device = <TestBackend as Backend>::Device::default();
+        let module = ModuleEnum::Composed(ModuleComposed::<TestBackend>::new(&device));
+        let mut visitor = PathVisitor::default();
+
+        module.visit(&mut visitor);
+
+        assert_eq!(
+            visitor.paths,
+            [
+                "weight",
+                "basic.weight_basic",
+                "tuple.0.weight_basic",
+                "tuple.1.weight_basic"
+            ]
+        );
+    }
+
+    #[test]
+    fn should_visit_the_paths_of_the_modules_of_an_array() {
+        let device = <TestBackend as Backend>::Device::default();
+        let module = ModuleWithConstGeneric::<TestBackend, 2> {
+            modules: [ModuleBasic::new(&device), ModuleBasic::new(&device)],
+        };
+        let mut visitor = PathVisitor::default();
+
+        module.visit(&mut visitor);
+
+        assert_eq!(
+            visitor.paths,
+            ["modules.0.weight_basic", "modules.1.weight_basic"]
+        );
+    }
+}
+
 #[cfg(feature = "std")]
 mod require_grad {
     use burn_tensor::backend::AutodiffBackend;
@@ -1,3 +1,6 @@
 mod speech_commands;
 
+/// Audio transformations, turning the waveforms into model inputs.
+pub mod transform;
+
 pub use speech_commands::*;
@@ -0,0 +1,151 @@
+use std::f64::consts::PI;
+use std::ops::{Add, Mul};
+
+/// A complex number.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub(crate) fn norm(&self) -> f64 {
+        self.re.hypot(self.im)
+    }
+}
+
+impl Add for Complex {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            re: self.re + rhs.re,
+            im: self.im + rhs.im,
+        }
+    }
+}
+
+impl Mul for Complex {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self {
+            re: self.re * rhs.re - self.im * rhs.im,
+            im: self.re * rhs.im + self.im * rhs.re,
+        }
+    }
+}
+
+/// Mixed-radix fast Fourier transform of a fixed size, with its twiddle factors precomputed.
+///
+/// The size is split into its prime factors, so any size is supported, the sizes with small
+/// factors (e.g. 400 or 512) being the fastest.
+#[derive(Debug, Clone)]
+pub(crate) struct Fft {
+    twiddles: Vec<Complex>,
+}
+
+impl Fft {
+    pub(crate) fn new(size: usize) -> Self {
+        let twiddles = (0..size)
+            .map(|k| {
+                let angle = -2.0 * PI * k as f64 / size as f64;
+                Complex {
+                    re: angle.cos(),
+                    im: angle.sin(),
+                }
+            })
+            .collect();
+
+        Self { twiddles }
+    }
+
+    /// The discrete Fourier transform of the input, whose length is the size of the FFT.
+    pub(crate) fn process(&self, input: &[Complex]) -> Vec<Complex> {
+        assert_eq!(input.len(), self.twiddles.len(), "Invalid FFT input size");
+
+        let mut output = vec![Complex::default(); input.len()];
+        self.transform(input, 1, &mut output);
+        output
+    }
+
+    /// The magnitudes of the non-negative frequencies of a real signal.
+    pub(crate) fn magnitudes(&self, input: &[f32]) -> Vec<f64> {
+        let input = input
+            .iter()
+            .map(|value| Complex {
+                re: *value as f64,
+                im: 0.0,
+            })
+            .collect::<Vec<_>>();
+
+        self.process(&input)[..input.len() / 2 + 1]
+            .iter()
+            .map(Complex::norm)
+            .collect()
+    }
+
+    /// Transform the elements `input[0], input[stride], ...` into the output, by splitting them
+    /// into the subsequences of the smallest factor of their size (decimation in time).
+    fn transform(&self, input: &[Complex], stride: usize, output: &mut [Complex]) {
+        let size = output.len();
+        if size == 1 {
+            output[0] = input[0];
+            return;
+        }
+
+        let factor = (2..=size).find(|factor| size % factor == 0).unwrap_or(size);
+        let sub_size = size / factor;
+        for (index, sub_output) in output.chunks_mut(sub_size).enumerate() {
+            self.transform(&input[index * stride..], stride * factor, sub_output);
+        }
+
+        // The twiddle factors of this size are a subset of the twiddle factors of the FFT size.
+        let step = self.twiddles.len() / size;
+        let sub_outputs = output.to_vec();
+        for k in 0..sub_size {
+            for q in 0..factor {
+                let frequency = k + q * sub_size;
+                output[frequency] = (0..factor).fold(Complex::default(), |sum, index| {
+                    let twiddle = self.twiddles[(index * frequency % size) * step];
+                    sum + twiddle * sub_outputs[index * sub_size + k]
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fft_matches_the_dft() {
+        for size in [1, 8, 12, 7] {
+            let input = (0..size)
+                .map(|index| Complex {
+                    re: (index as f64 * 0.7).sin(),
+                    im: (index as f64 * 0.3).cos(),
+                })
+                .collect::<Vec<_>>();
+
+            let output = Fft::new(size).process(&input);
+            for (frequency, value) in output.iter().enumerate() {
+                let expected =
+                    input
+                        .iter()
+                        .enumerate()
+                        .fold(Complex::default(), |sum, (index, x)| {
+                            let angle = -2.0 * PI * (frequency * index) as f64 / size as f64;
+                            sum + *x
+                                * Complex {
+                                    re: angle.cos(),
+                                    im: angle.sin(),
+                                }
+                        });
+                assert!((value.re - expected.re).abs() < 1e-9);
+                assert!((value.im - expected.im).abs() < 1e-9);
+            }
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use super::AudioFeatures;
+use rand::{Rng, rngs::StdRng};
+use std::ops::Range;
+
+/// A random transformation of audio features, e.g. to augment a training dataset.
+pub trait FeatureTransform: Send + Sync {
+    /// Transform the features, drawing the random parameters from the given rng.
+    fn apply(&self, features: AudioFeatures, rng: &mut StdRng) -> AudioFeatures;
+}
+
+/// The random masks of an axis of the features.
+#[derive(Debug, Clone)]
+struct Masking {
+    max_width: usize,
+    num_masks: usize,
+    mask_value: f32,
+}
+
+impl Masking {
+    fn new(max_width: usize) -> Self {
+        Self {
+            max_width,
+            num_masks: 1,
+            mask_value: 0.0,
+        }
+    }
+
+    /// Draw the masks of an axis of a size, each of a random width up to the maximum width.
+    fn sample(&self, size: usize, rng: &mut StdRng) -> Vec<Range<usize>> {
+        (0..self.num_masks)
+            .map(|_| {
+                let width = rng.random_range(0..=self.max_width.min(size));
+                let start = rng.random_range(0..=size - width);
+                start..start + width
+            })
+            .collect()
+    }
+}
+
+/// SpecAugment time masking: mask random ranges of consecutive frames of the features.
+#[derive(Debug, Clone)]
+pub struct TimeMasking {
+    masking: Masking,
+}
+
+impl TimeMasking {
+    /// Create a time masking of one mask of at most `max_width` frames.
+    pub fn new(max_width: usize) -> Self {
+        Self {
+            masking: Masking::new(max_width),
+        }
+    }
+
+    /// Set the number of masks.
+    pub fn with_num_masks(mut self, num_masks: usize) -> Self {
+        self.masking.num_masks = num_masks;
+        self
+    }
+
+    /// Set the value of the masked features, 0 by default.
+    pub fn with_mask_value(mut self, mask_value: f32) -> Self {
+        self.masking.mask_value = mask_value;
+        self
+    }
+
+    /// The value of the masked features.
+    pub fn mask_value(&self) -> f32 {
+        self.masking.mask_value
+    }
+
+    /// Draw the ranges of masked frames, for features of `num_frames` frames.
+    pub fn sample_masks(&self, num_frames: usize, rng: &mut StdRng) -> Vec<Range<usize>> {
+        self.masking.sample(num_frames, rng)
+    }
+}
+
+impl FeatureTransform for TimeMasking {
+    fn apply(&self, mut features: AudioFeatures, rng: &mut StdRng) -> AudioFeatures {
+        let num_frames = features.num_frames;
+        for mask in self.sample_masks(num_frames, rng) {
+            for bin in features.values.chunks_exact_mut(num_frames) {
+                bin[mask.clone()].fill(self.mask_value());
+            }
+        }
+        features
+    }
+}
+
+/// SpecAugment frequency masking: mask random ranges of consecutive bins of the features.
+#[derive(Debug, Clone)]
+pub struct FrequencyMasking {
+    masking: Masking,
+}
+
+impl FrequencyMasking {
+    /// Create a frequency masking of one mask of at most `max_width` bins.
+    pub fn new(max_width: usize) -> Self {
+        Self {
+            masking: Masking::new(max_width),
+        }
+    }
+
+    /// Set the number of masks.
+    pub fn with_num_masks(mut self, num_masks: usize) -> Self {
+        self.masking.num_masks = num_masks;
+        self
+    }
+
+    /// Set the value of the masked features, 0 by default.
+    pub fn with_mask_value(mut self, mask_value: f32) -> Self {
+        self.masking.mask_value = mask_value;
+        self
+    }
+
+    /// The value of the masked features.
+    pub fn mask_value(&self) -> f32 {
+        self.masking.mask_value
+    }
+
+    /// Draw the ranges of masked bins, for features of `num_bins` bins.
+    pub fn sample_masks(&self, num_bins: usize, rng: &mut StdRng) -> Vec<Range<usize>> {
+        self.masking.sample(num_bins, rng)
+    }
+}
+
+impl FeatureTransform for FrequencyMasking {
+    fn apply(&self, mut features: AudioFeatures, rng: &mut StdRng) -> AudioFeatures {
+        let num_frames = features.num_frames;
+        for mask in self.sample_masks(features.num_bins, rng) {
+            features.values[mask.start * num_frames..mask.end * num_frames].fill(self.mask_value());
+        }
+        features
+    }
+}
+
+/// [SpecAugment](https://arxiv.org/abs/1904.08779) masking: a frequency masking followed by a
+/// time masking.
+#[derive(Debug, Clone, new)]
+pub struct SpecAugment {
+    /// The frequency masking.
+    pub frequency: FrequencyMasking,
+    /// The time masking.
+    pub time: TimeMasking,
+}
+
+impl FeatureTransform for SpecAugment {
+    fn apply(&self, features: AudioFeatures, rng: &mut StdRng) -> AudioFeatures {
+        let features = self.frequency.apply(features, rng);
+        self.time.apply(features, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_spec_augment_masks() {
+        let features = AudioFeatures {
+            values: vec![1.0; 8 * 20],
+            num_bins: 8,
+            num_frames: 20,
+        };
+        let spec_augment = SpecAugment::new(
+            FrequencyMasking::new(3).with_num_masks(2),
+            TimeMasking::new(5).with_mask_value(-1.0),
+        );
+
+        let masked = spec_augment.apply(features.clone(), &mut StdRng::seed_from_u64(3));
+        assert_eq!(
+            masked,
+            spec_augment.apply(features, &mut StdRng::seed_from_u64(3))
+        );
+
+        // At most 5 frames are masked in all the bins, and 6 bins masked by frequency.
+        let masked_frames = (0..20)
+            .filter(|frame| (0..8).all(|bin| masked.get(bin, *frame) <= 0.0))
+            .count();
+        let masked_bins = (0..8)
+            .filter(|bin| masked.bin(*bin).iter().all(|value| *value == 0.0))
+            .count();
+        assert!(masked_frames <= 5);
+        assert!(masked_bins <= 6);
+        assert!(
+            masked
+                .values
+                .iter()
+                .all(|value| [-1.0, 0.0, 1.0].contains(value))
+        );
+    }
+}
@@ -0,0 +1,356 @@
+use super::{AmplitudeToDb, AudioFeatures, Spectrogram};
+use std::f64::consts::PI;
+
+/// The scale converting the frequencies to mels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MelScale {
+    /// The HTK scale, `2595 * log10(1 + f / 700)`.
+    #[default]
+    Htk,
+    /// The Slaney scale of the Auditory Toolbox, linear below 1 kHz and logarithmic above.
+    Slaney,
+}
+
+impl MelScale {
+    const SLANEY_MIN_LOG_HZ: f64 = 1000.0;
+    const SLANEY_HZ_PER_MEL: f64 = 200.0 / 3.0;
+    const SLANEY_MIN_LOG_MEL: f64 = Self::SLANEY_MIN_LOG_HZ / Self::SLANEY_HZ_PER_MEL;
+
+    fn slaney_log_step() -> f64 {
+        6.4f64.ln() / 27.0
+    }
+
+    fn hz_to_mel(&self, frequency: f64) -> f64 {
+        match self {
+            MelScale::Htk => 2595.0 * (1.0 + frequency / 700.0).log10(),
+            MelScale::Slaney if frequency < Self::SLANEY_MIN_LOG_HZ => {
+                frequency / Self::SLANEY_HZ_PER_MEL
+            }
+            MelScale::Slaney => {
+                Self::SLANEY_MIN_LOG_MEL
+                    + (frequency / Self::SLANEY_MIN_LOG_HZ).ln() / Self::slaney_log_step()
+            }
+        }
+    }
+
+    fn mel_to_hz(&self, mel: f64) -> f64 {
+        match self {
+            MelScale::Htk => 700.0 * (10f64.powf(mel / 2595.0) - 1.0),
+            MelScale::Slaney if mel < Self::SLANEY_MIN_LOG_MEL => mel * Self::SLANEY_HZ_PER_MEL,
+            MelScale::Slaney => {
+                Self::SLANEY_MIN_LOG_HZ
+                    * (Self::slaney_log_step() * (mel - Self::SLANEY_MIN_LOG_MEL)).exp()
+            }
+        }
+    }
+}
+
+/// Triangular filters converting the frequency bins of a spectrogram to mel bins.
+#[derive(Debug, Clone)]
+pub struct MelFilterbank {
+    sample_rate: usize,
+    num_freqs: usize,
+    num_mels: usize,
+    f_min: f32,
+    f_max: Option<f32>,
+    scale: MelScale,
+    slaney_norm: bool,
+    weights: Vec<f32>,
+}
+
+impl MelFilterbank {
+    /// Create `num_mels` filters for the spectrograms of `n_fft` samples FFTs, spanning the
+    /// frequencies from 0 Hz to the Nyquist frequency on the HTK mel scale.
+    pub fn new(sample_rate: usize, n_fft: usize, num_mels: usize) -> Self {
+        let mut filterbank = Self {
+            sample_rate,
+            num_freqs: n_fft / 2 + 1,
+            num_mels,
+            f_min: 0.0,
+            f_max: None,
+            scale: MelScale::default(),
+            slaney_norm: false,
+            weights: Vec::new(),
+        };
+        filterbank.compute_weights();
+        filterbank
+    }
+
+    /// Set the range of the frequencies of the filters, in Hz.
+    pub fn with_frequency_range(mut self, f_min: f32, f_max: f32) -> Self {
+        self.f_min = f_min;
+        self.f_max = Some(f_max);
+        self.compute_weights();
+        self
+    }
+
+    /// Set the mel scale.
+    pub fn with_scale(mut self, scale: MelScale) -> Self {
+        self.scale = scale;
+        self.compute_weights();
+        self
+    }
+
+    /// Set whether the filters are normalized by their width, so they have the same area (the
+    /// `slaney` normalization of librosa).
+    pub fn with_slaney_norm(mut self, slaney_norm: bool) -> Self {
+        self.slaney_norm = slaney_norm;
+        self.compute_weights();
+        self
+    }
+
+    /// The number of mel bins.
+    pub fn num_mels(&self) -> usize {
+        self.num_mels
+    }
+
+    /// The number of frequency bins of the spectrograms.
+    pub fn num_freqs(&self) -> usize {
+        self.num_freqs
+    }
+
+    /// The weights of the filters, of shape `[num_mels, num_freqs]`.
+    pub fn weights(&self) -> &[f32] {
+        &self.weights
+    }
+
+    /// Convert a spectrogram to mel bins.
+    pub fn apply(&self, spectrogram: &AudioFeatures) -> AudioFeatures {
+        assert_eq!(
+            spectrogram.num_bins, self.num_freqs,
+            "The spectrogram doesn't match the filterbank"
+        );
+
+        let num_frames = spectrogram.num_frames;
+        let mut values = vec![0.0; self.num_mels * num_frames];
+        for (mel, filter) in self.weights.chunks_exact(self.num_freqs).enumerate() {
+            let output = &mut values[mel * num_frames..(mel + 1) * num_frames];
+            for (freq, weight) in filter.iter().enumerate() {
+                if *weight == 0.0 {
+                    continue;
+                }
+                for (value, power) in output.iter_mut().zip(spectrogram.bin(freq)) {
+                    *value += weight * power;
+                }
+            }
+        }
+
+        AudioFeatures {
+            values,
+            num_bins: self.num_mels,
+            num_frames,
+        }
+    }
+
+    fn compute_weights(&mut self) {
+        let nyquist = self.sample_rate as f64 / 2.0;
+        let f_max = self.f_max.map_or(nyquist, |f_max| f_max as f64);
+        let (mel_min, mel_max) = (
+            self.scale.hz_to_mel(self.f_min as f64),
+            self.scale.hz_to_mel(f_max),
+        );
+
+        // The edges of the filters, equally spaced on the mel scale.
+        let edges = (0..self.num_mels + 2)
+            .map(|index| {
+                let mel = mel_min + (mel_max - mel_min) * index as f64 / (self.num_mels + 1) as f64;
+                self.scale.mel_to_hz(mel)
+            })
+            .collect::<Vec<_>>();
+
+        let freq_step = match self.num_freqs {
+            1 => 0.0,
+            num_freqs => nyquist / (num_freqs - 1) as f64,
+        };
+        self.weights = edges
+            .windows(3)
+            .flat_map(|edges| {
+                let (left, center, right) = (edges[0], edges[1], edges[2]);
+                let norm = match self.slaney_norm {
+                    true => 2.0 / (right - left),
+                    false => 1.0,
+                };
+
+                (0..self.num_freqs).map(move |freq| {
+                    let frequency = freq as f64 * freq_step;
+                    let up = (frequency - left) / (center - left);
+                    let down = (right - frequency) / (right - center);
+                    (up.min(down).max(0.0) * norm) as f32
+                })
+            })
+            .collect();
+    }
+}
+
+/// Compute the mel spectrogram of a waveform, i.e. its spectrogram converted to mel bins.
+#[derive(Debug, Clone)]
+pub struct MelSpectrogram {
+    spectrogram: Spectrogram,
+    filterbank: MelFilterbank,
+}
+
+impl MelSpectrogram {
+    /// Create a mel spectrogram from a spectrogram and the filterbank of its FFT size.
+    pub fn new(spectrogram: Spectrogram, filterbank: MelFilterbank) -> Self {
+        assert_eq!(
+            spectrogram.num_bins(),
+            filterbank.num_freqs(),
+            "The filterbank doesn't match the FFT size of the spectrogram"
+        );
+
+        Self {
+            spectrogram,
+            filterbank,
+        }
+    }
+
+    /// The number of mel bins.
+    pub fn num_mels(&self) -> usize {
+        self.filterbank.num_mels()
+    }
+
+    /// Compute the mel spectrogram of the samples.
+    pub fn compute(&self, samples: &[f32]) -> AudioFeatures {
+        self.filterbank.apply(&self.spectrogram.compute(samples))
+    }
+}
+
+/// Compute the log-mel spectrogram of a waveform, `ln(mel + offset)`, as the input features of
+/// speech models.
+///
+/// Use [AmplitudeToDb] on a [mel spectrogram](MelSpectrogram) for the features in decibels.
+#[derive(Debug, Clone)]
+pub struct LogMelSpectrogram {
+    mel_spectrogram: MelSpectrogram,
+    offset: f32,
+}
+
+impl LogMelSpectrogram {
+    /// Create a log-mel spectrogram, with an offset of `1e-6` avoiding the log of zero.
+    pub fn new(mel_spectrogram: MelSpectrogram) -> Self {
+        Self {
+            mel_spectrogram,
+            offset: 1e-6,
+        }
+    }
+
+    /// Set the offset added to the mel spectrogram before the log.
+    pub fn with_offset(mut self, offset: f32) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Compute the log-mel spectrogram of the samples.
+    pub fn compute(&self, samples: &[f32]) -> AudioFeatures {
+        self.mel_spectrogram
+            .compute(samples)
+            .map(|value| (value + self.offset).ln())
+    }
+}
+
+/// Compute the mel-frequency cepstral coefficients (MFCC) of a waveform, the orthonormal
+/// discrete cosine transform (DCT-II) of its mel spectrogram in decibels.
+#[derive(Debug, Clone)]
+pub struct Mfcc {
+    mel_spectrogram: MelSpectrogram,
+    to_db: AmplitudeToDb,
+    /// The DCT matrix, of shape `[num_mfcc, num_mels]`.
+    dct: Vec<f32>,
+    num_mfcc: usize,
+}
+
+impl Mfcc {
+    /// Create the computation of `num_mfcc` coefficients, from a mel spectrogram of power
+    /// features clamped to 80 decibels below their maximum.
+    pub fn new(mel_spectrogram: MelSpectrogram, num_mfcc: usize) -> Self {
+        let num_mels = mel_spectrogram.num_mels();
+        assert!(
+            num_mfcc <= num_mels,
+            "There can't be more coefficients than mel bins"
+        );
+
+        let dct = (0..num_mfcc)
+            .flat_map(|k| {
+                let scale = match k {
+                    0 => (1.0 / num_mels as f64).sqrt(),
+                    _ => (2.0 / num_mels as f64).sqrt(),
+                };
+                (0..num_mels).map(move |mel| {
+                    (scale * (PI / num_mels as f64 * (mel as f64 + 0.5) * k as f64).cos()) as f32
+                })
+            })
+            .collect();
+
+        Self {
+            mel_spectrogram,
+            to_db: AmplitudeToDb::power().with_top_db(80.0),
+            dct,
+            num_mfcc,
+        }
+    }
+
+    /// Compute the coefficients of the samples, with the shape `[num_mfcc, num_frames]`.
+    pub fn compute(&self, samples: &[f32]) -> AudioFeatures {
+        let mels = self.to_db.apply(self.mel_spectrogram.compute(samples));
+        let num_frames = mels.num_frames;
+
+        let mut values = vec![0.0; self.num_mfcc * num_frames];
+        for (k, row) in self.dct.chunks_exact(mels.num_bins).enumerate() {
+            let output = &mut values[k * num_frames..(k + 1) * num_frames];
+            for (mel, weight) in row.iter().enumerate() {
+                for (value, db) in output.iter_mut().zip(mels.bin(mel)) {
+                    *value += weight * db;
+                }
+            }
+        }
+
+        AudioFeatures {
+            values,
+            num_bins: self.num_mfcc,
+            num_frames,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mel_scales_round_trip() {
+        for scale in [MelScale::Htk, MelScale::Slaney] {
+            for frequency in [0.0, 440.0, 999.0, 4000.0] {
+                let round_trip = scale.mel_to_hz(scale.hz_to_mel(frequency));
+                assert!((round_trip - frequency).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mel_features() {
+        let filterbank = MelFilterbank::new(16000, 512, 40).with_frequency_range(20.0, 8000.0);
+        assert_eq!(filterbank.weights().len(), 40 * 257);
+        // Each filter covers some frequency bins, with a peak of at most 1.
+        for filter in filterbank.weights().chunks_exact(257) {
+            let peak = filter.iter().copied().fold(0.0, f32::max);
+            assert!(peak > 0.0 && peak <= 1.0);
+        }
+
+        let samples = (0..16000)
+            .map(|index| (index as f32 * 0.05).sin())
+            .collect::<Vec<_>>();
+        let mel_spectrogram = MelSpectrogram::new(
+            Spectrogram::new(512)
+                .with_hop_length(160)
+                .with_win_length(400),
+            filterbank,
+        );
+
+        let log_mels = LogMelSpectrogram::new(mel_spectrogram.clone()).compute(&samples);
+        assert_eq!((log_mels.num_bins, log_mels.num_frames), (40, 101));
+        assert!(log_mels.values.iter().all(|value| value.is_finite()));
+
+        let mfcc = Mfcc::new(mel_spectrogram, 13).compute(&samples);
+        assert_eq!((mfcc.num_bins, mfcc.num_frames), (13, 101));
+    }
+}
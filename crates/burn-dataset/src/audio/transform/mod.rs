@@ -0,0 +1,10 @@
+mod fft;
+mod masking;
+mod mel;
+mod spectrogram;
+mod waveform;
+
+pub use masking::*;
+pub use mel::*;
+pub use spectrogram::*;
+pub use waveform::*;
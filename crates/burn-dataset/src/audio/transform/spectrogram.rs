@@ -0,0 +1,295 @@
+use super::fft::Fft;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+/// Time-frequency features of a waveform, such as a spectrogram.
+///
+/// The values are stored bin-major, with the shape `[num_bins, num_frames]`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AudioFeatures {
+    /// The values of the features, of shape `[num_bins, num_frames]`.
+    pub values: Vec<f32>,
+    /// The number of frequency bins (or coefficients) of each frame.
+    pub num_bins: usize,
+    /// The number of frames.
+    pub num_frames: usize,
+}
+
+impl AudioFeatures {
+    /// The value of a bin of a frame.
+    pub fn get(&self, bin: usize, frame: usize) -> f32 {
+        self.values[bin * self.num_frames + frame]
+    }
+
+    /// The values of a bin, for all the frames.
+    pub fn bin(&self, bin: usize) -> &[f32] {
+        &self.values[bin * self.num_frames..(bin + 1) * self.num_frames]
+    }
+
+    /// Map each value of the features.
+    pub(crate) fn map<F: Fn(f32) -> f32>(mut self, f: F) -> Self {
+        self.values.iter_mut().for_each(|value| *value = f(*value));
+        self
+    }
+}
+
+/// The window applied to the frames of a [spectrogram](Spectrogram).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Window {
+    /// Hann window.
+    #[default]
+    Hann,
+    /// Hamming window.
+    Hamming,
+    /// Rectangular window, i.e. no window.
+    Rectangular,
+}
+
+impl Window {
+    /// The periodic window of a size.
+    fn coefficients(&self, size: usize) -> Vec<f64> {
+        let cosine = |index: usize| (2.0 * PI * index as f64 / size as f64).cos();
+        (0..size)
+            .map(|index| match self {
+                Window::Hann => 0.5 - 0.5 * cosine(index),
+                Window::Hamming => 0.54 - 0.46 * cosine(index),
+                Window::Rectangular => 1.0,
+            })
+            .collect()
+    }
+}
+
+/// Compute the spectrogram of a waveform with a short-time Fourier transform.
+///
+/// The spectrogram has `n_fft / 2 + 1` frequency bins, and a frame every `hop_length` samples.
+/// With the default options, the waveform is padded by reflection so the frames are centered on
+/// their time, and the values are the power of the frequencies.
+#[derive(Debug, Clone)]
+pub struct Spectrogram {
+    n_fft: usize,
+    hop_length: usize,
+    win_length: usize,
+    window: Window,
+    power: Option<f32>,
+    center: bool,
+    fft: Fft,
+}
+
+impl Spectrogram {
+    /// Create a spectrogram with an FFT of `n_fft` samples, a window of the same length and a
+    /// hop of a quarter of the window.
+    pub fn new(n_fft: usize) -> Self {
+        assert!(n_fft > 0, "The FFT size must be positive");
+
+        Self {
+            n_fft,
+            hop_length: (n_fft / 4).max(1),
+            win_length: n_fft,
+            window: Window::default(),
+            power: Some(2.0),
+            center: true,
+            fft: Fft::new(n_fft),
+        }
+    }
+
+    /// Set the number of samples between the frames.
+    pub fn with_hop_length(mut self, hop_length: usize) -> Self {
+        assert!(hop_length > 0, "The hop length must be positive");
+        self.hop_length = hop_length;
+        self
+    }
+
+    /// Set the length of the window, which is centered in the FFT frame and at most `n_fft`.
+    pub fn with_win_length(mut self, win_length: usize) -> Self {
+        assert!(
+            win_length > 0 && win_length <= self.n_fft,
+            "The window length must be between 1 and the FFT size"
+        );
+        self.win_length = win_length;
+        self
+    }
+
+    /// Set the window applied to the frames.
+    pub fn with_window(mut self, window: Window) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Set the exponent of the magnitudes, e.g. 1 for the magnitude spectrogram or 2 for the power
+    /// spectrogram. `None` keeps the magnitudes.
+    pub fn with_power(mut self, power: Option<f32>) -> Self {
+        self.power = power;
+        self
+    }
+
+    /// Set whether the waveform is padded so the frames are centered on their time.
+    pub fn with_center(mut self, center: bool) -> Self {
+        self.center = center;
+        self
+    }
+
+    /// The size of the FFT.
+    pub fn n_fft(&self) -> usize {
+        self.n_fft
+    }
+
+    /// The number of frequency bins.
+    pub fn num_bins(&self) -> usize {
+        self.n_fft / 2 + 1
+    }
+
+    /// Compute the spectrogram of the samples.
+    pub fn compute(&self, samples: &[f32]) -> AudioFeatures {
+        let samples = match self.center {
+            true => reflect_pad(samples, self.n_fft / 2),
+            false => samples.to_vec(),
+        };
+
+        let mut window = vec![0.0; self.n_fft];
+        let offset = (self.n_fft - self.win_length) / 2;
+        window[offset..offset + self.win_length]
+            .copy_from_slice(&self.window.coefficients(self.win_length));
+
+        let num_bins = self.num_bins();
+        let num_frames = match samples.len() >= self.n_fft {
+            true => 1 + (samples.len() - self.n_fft) / self.hop_length,
+            false => 0,
+        };
+
+        let mut values = vec![0.0; num_bins * num_frames];
+        for frame in 0..num_frames {
+            let start = frame * self.hop_length;
+            let windowed = samples[start..start + self.n_fft]
+                .iter()
+                .zip(window.iter())
+                .map(|(sample, coefficient)| (*sample as f64 * coefficient) as f32)
+                .collect::<Vec<_>>();
+
+            for (bin, magnitude) in self.fft.magnitudes(&windowed).into_iter().enumerate() {
+                values[bin * num_frames + frame] = match self.power {
+                    Some(power) => magnitude.powf(power as f64) as f32,
+                    None => magnitude as f32,
+                };
+            }
+        }
+
+        AudioFeatures {
+            values,
+            num_bins,
+            num_frames,
+        }
+    }
+}
+
+/// Pad the samples by reflection on both sides, or with zeros when there are too few of them.
+fn reflect_pad(samples: &[f32], padding: usize) -> Vec<f32> {
+    if samples.len() < 2 {
+        let mut padded = vec![0.0; padding];
+        padded.extend(samples);
+        padded.resize(samples.len() + 2 * padding, 0.0);
+        return padded;
+    }
+
+    let length = samples.len() as isize;
+    let period = 2 * (length - 1);
+    (-(padding as isize)..length + padding as isize)
+        .map(|index| {
+            let index = index.rem_euclid(period);
+            samples[(if index < length {
+                index
+            } else {
+                period - index
+            }) as usize]
+        })
+        .collect()
+}
+
+/// Convert the features to decibels, e.g. to get a log-mel spectrogram.
+#[derive(Debug, Clone)]
+pub struct AmplitudeToDb {
+    multiplier: f32,
+    amin: f32,
+    top_db: Option<f32>,
+}
+
+impl AmplitudeToDb {
+    /// Convert power features, such as a power spectrogram, with `10 * log10(x)`.
+    pub fn power() -> Self {
+        Self {
+            multiplier: 10.0,
+            amin: 1e-10,
+            top_db: None,
+        }
+    }
+
+    /// Convert magnitude features with `20 * log10(x)`.
+    pub fn magnitude() -> Self {
+        Self {
+            multiplier: 20.0,
+            ..Self::power()
+        }
+    }
+
+    /// Clamp the values to `top_db` decibels below the maximum of the features.
+    pub fn with_top_db(mut self, top_db: f32) -> Self {
+        self.top_db = Some(top_db);
+        self
+    }
+
+    /// Convert the features to decibels.
+    pub fn apply(&self, features: AudioFeatures) -> AudioFeatures {
+        let features = features.map(|value| self.multiplier * value.max(self.amin).log10());
+
+        match self.top_db {
+            Some(top_db) => {
+                let max = features
+                    .values
+                    .iter()
+                    .copied()
+                    .fold(f32::NEG_INFINITY, f32::max);
+                features.map(|value| value.max(max - top_db))
+            }
+            None => features,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spectrogram_of_a_sine() {
+        let sample_rate = 8000.0;
+        // A sine at the frequency of the bin 16 of a 128 samples FFT.
+        let frequency = 16.0 * sample_rate / 128.0;
+        let samples = (0..1024)
+            .map(|index| (2.0 * PI * frequency * index as f64 / sample_rate).sin() as f32)
+            .collect::<Vec<_>>();
+
+        let spectrogram = Spectrogram::new(128).with_hop_length(64).compute(&samples);
+        assert_eq!(spectrogram.num_bins, 65);
+        assert_eq!(spectrogram.num_frames, 1 + 1024 / 64);
+
+        let frame = 8;
+        let peak = (0..spectrogram.num_bins)
+            .max_by(|a, b| {
+                let (a, b) = (spectrogram.get(*a, frame), spectrogram.get(*b, frame));
+                a.partial_cmp(&b).unwrap()
+            })
+            .unwrap();
+        assert_eq!(peak, 16);
+
+        let db = AmplitudeToDb::power().with_top_db(80.0).apply(spectrogram);
+        let max = db.values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        assert!(db.values.iter().all(|value| *value >= max - 80.0));
+    }
+
+    #[test]
+    fn test_reflect_pad() {
+        assert_eq!(
+            reflect_pad(&[1.0, 2.0, 3.0], 2),
+            vec![3.0, 2.0, 1.0, 2.0, 3.0, 2.0, 1.0]
+        );
+    }
+}
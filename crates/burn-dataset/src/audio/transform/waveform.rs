@@ -0,0 +1,185 @@
+use rand::{Rng, rngs::StdRng};
+use std::f64::consts::PI;
+
+/// A transformation of the samples of a waveform, possibly random.
+pub trait WaveformTransform: Send + Sync {
+    /// Transform the samples, drawing the random parameters from the given rng.
+    fn apply(&self, samples: Vec<f32>, rng: &mut StdRng) -> Vec<f32>;
+}
+
+/// Resample a waveform to another sample rate, with a band-limited (Hann-windowed sinc)
+/// interpolation.
+#[derive(Debug, Clone)]
+pub struct Resample {
+    orig_rate: usize,
+    new_rate: usize,
+    /// The number of zero crossings of the sinc on each side of the interpolated point.
+    lowpass_filter_width: usize,
+    /// The cutoff frequency, as a fraction of the lowest Nyquist frequency.
+    rolloff: f64,
+}
+
+impl Resample {
+    /// Create a resampling from `orig_rate` to `new_rate`, in Hz.
+    pub fn new(orig_rate: usize, new_rate: usize) -> Self {
+        assert!(
+            orig_rate > 0 && new_rate > 0,
+            "The sample rates must be positive"
+        );
+
+        Self {
+            orig_rate,
+            new_rate,
+            lowpass_filter_width: 6,
+            rolloff: 0.99,
+        }
+    }
+
+    /// Set the number of zero crossings of the interpolation filter on each side, trading
+    /// speed for sharpness.
+    pub fn with_lowpass_filter_width(mut self, lowpass_filter_width: usize) -> Self {
+        self.lowpass_filter_width = lowpass_filter_width.max(1);
+        self
+    }
+
+    /// Resample the samples.
+    pub fn resample(&self, samples: &[f32]) -> Vec<f32> {
+        if self.orig_rate == self.new_rate || samples.is_empty() {
+            return samples.to_vec();
+        }
+
+        let ratio = self.orig_rate as f64 / self.new_rate as f64;
+        // The cutoff frequency, in cycles per input sample relative to the input Nyquist frequency.
+        let cutoff = self.rolloff * (1.0 / ratio).min(1.0);
+        let half_width = self.lowpass_filter_width as f64 / cutoff;
+        let num_samples = (samples.len() as f64 / ratio).ceil() as usize;
+
+        (0..num_samples)
+            .map(|index| {
+                let time = index as f64 * ratio;
+                let first = (time - half_width).ceil().max(0.0) as usize;
+                let last = ((time + half_width).floor() as usize).min(samples.len() - 1);
+
+                (first..=last)
+                    .map(|input| {
+                        let distance = time - input as f64;
+                        let window = (PI * distance / (2.0 * half_width)).cos().powi(2);
+                        samples[input] as f64 * cutoff * sinc(cutoff * distance) * window
+                    })
+                    .sum::<f64>() as f32
+            })
+            .collect()
+    }
+}
+
+impl WaveformTransform for Resample {
+    fn apply(&self, samples: Vec<f32>, _rng: &mut StdRng) -> Vec<f32> {
+        self.resample(&samples)
+    }
+}
+
+fn sinc(x: f64) -> f64 {
+    match x == 0.0 {
+        true => 1.0,
+        false => (PI * x).sin() / (PI * x),
+    }
+}
+
+/// Where the samples of a waveform longer than the target length are kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CropMode {
+    /// Keep the first samples.
+    #[default]
+    Start,
+    /// Keep the samples in the middle.
+    Center,
+    /// Keep the samples at a random offset.
+    Random,
+}
+
+/// Pad or crop a waveform to a fixed number of samples.
+///
+/// The shorter waveforms are padded with zeros at the end, and the longer ones are cropped
+/// according to the [crop mode](CropMode).
+#[derive(Debug, Clone)]
+pub struct PadOrCrop {
+    num_samples: usize,
+    mode: CropMode,
+}
+
+impl PadOrCrop {
+    /// Create a transform to `num_samples` samples, keeping the first samples.
+    pub fn new(num_samples: usize) -> Self {
+        Self {
+            num_samples,
+            mode: CropMode::default(),
+        }
+    }
+
+    /// Set the crop mode.
+    pub fn with_mode(mut self, mode: CropMode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
+
+impl WaveformTransform for PadOrCrop {
+    fn apply(&self, mut samples: Vec<f32>, rng: &mut StdRng) -> Vec<f32> {
+        let excess = samples.len().saturating_sub(self.num_samples);
+        let offset = match self.mode {
+            CropMode::Start => 0,
+            CropMode::Center => excess / 2,
+            CropMode::Random => rng.random_range(0..=excess),
+        };
+
+        samples.drain(..offset);
+        samples.resize(self.num_samples, 0.0);
+        samples
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn sine(frequency: f64, sample_rate: usize, num_samples: usize) -> Vec<f32> {
+        (0..num_samples)
+            .map(|index| (2.0 * PI * frequency * index as f64 / sample_rate as f64).sin() as f32)
+            .collect()
+    }
+
+    #[test]
+    fn test_resample_keeps_the_signal() {
+        let samples = sine(440.0, 16000, 1600);
+
+        let resampled = Resample::new(16000, 8000).resample(&samples);
+        assert_eq!(resampled.len(), 800);
+        let expected = sine(440.0, 8000, 800);
+        // Away from the edges, the resampled sine is the sine at the new sample rate.
+        for index in 50..750 {
+            assert!((resampled[index] - expected[index]).abs() < 0.05);
+        }
+
+        let upsampled = Resample::new(8000, 22050).resample(&expected);
+        assert_eq!(upsampled.len(), 2205);
+    }
+
+    #[test]
+    fn test_pad_or_crop() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let padded = PadOrCrop::new(5).apply(vec![1.0, 2.0], &mut rng);
+        assert_eq!(padded, vec![1.0, 2.0, 0.0, 0.0, 0.0]);
+
+        let cropped = PadOrCrop::new(2)
+            .with_mode(CropMode::Center)
+            .apply(vec![1.0, 2.0, 3.0, 4.0, 5.0], &mut rng);
+        assert_eq!(cropped, vec![2.0, 3.0]);
+
+        let cropped = PadOrCrop::new(3)
+            .with_mode(CropMode::Random)
+            .apply(vec![1.0, 2.0, 3.0, 4.0], &mut rng);
+        assert!(cropped == [1.0, 2.0, 3.0] || cropped == [2.0, 3.0, 4.0]);
+    }
+}
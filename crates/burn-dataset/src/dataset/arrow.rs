@@ -0,0 +1,457 @@
+use std::{
+    fs::File,
+    io::BufReader,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::Dataset;
+
+use arrow::{
+    array::{Array, AsArray},
+    datatypes::*,
+    error::ArrowError,
+    ipc::reader::FileReader,
+    record_batch::RecordBatch,
+    util::display::{ArrayFormatter, FormatOptions},
+};
+use globwalk::DirEntry;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+/// Error type for the Arrow and Parquet datasets.
+#[derive(thiserror::Error, Debug)]
+pub enum ArrowDatasetError {
+    /// IO related error.
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Arrow related error.
+    #[error("Arrow error: {0}")]
+    Arrow(#[from] ArrowError),
+
+    /// Parquet related error.
+    #[cfg(feature = "parquet")]
+    #[error("Parquet error: {0}")]
+    Parquet(#[from] parquet::errors::ParquetError),
+
+    /// A projected column doesn't exist in a file.
+    #[error("Column {0} not found in {1}")]
+    ColumnNotFound(String, PathBuf),
+
+    /// No file matches the glob pattern.
+    #[error("No file matches the pattern {0}")]
+    NoFiles(String),
+
+    /// Any other error.
+    #[error("{0}")]
+    Other(String),
+}
+
+/// Dataset reading the record batches of [Arrow IPC](https://arrow.apache.org/docs/format/Columnar.html#ipc-file-format)
+/// files, also known as Feather V2 files.
+///
+/// The rows are deserialized into items with serde, the columns being matched with the fields of
+/// the items by name. Multiple files can be read as the shards of a single dataset, in order.
+///
+/// The random access to the rows relies on the index of the record batches in the file footer,
+/// so only the record batch of a row is read. Every file is read once when the dataset is created
+/// to count the rows of its record batches, and its reader is kept open with the footer, so the
+/// rows of a file are read one at a time.
+///
+/// # Panics
+///
+/// [get](Dataset::get) panics if a row can't be read or deserialized into an item.
+pub struct ArrowIpcDataset<I> {
+    files: Vec<PathBuf>,
+    readers: Vec<Mutex<IpcReader>>,
+    // The first row of every record batch, with its file and batch indices.
+    batches: Vec<(usize, usize, usize)>,
+    len: usize,
+    phantom: PhantomData<I>,
+}
+
+type IpcReader = FileReader<BufReader<File>>;
+
+fn open_ipc(path: &Path, projection: Option<Vec<usize>>) -> Result<IpcReader, ArrowDatasetError> {
+    Ok(FileReader::try_new(
+        BufReader::new(File::open(path)?),
+        projection,
+    )?)
+}
+
+impl<I> ArrowIpcDataset<I> {
+    /// Create a dataset from an Arrow IPC file.
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, ArrowDatasetError> {
+        Self::from_files([path])
+    }
+
+    /// Create a dataset from the Arrow IPC files matching a glob pattern in a directory, sorted
+    /// by path, e.g. `train-*.arrow`.
+    pub fn from_glob<P: AsRef<Path>>(root: P, pattern: &str) -> Result<Self, ArrowDatasetError> {
+        Self::from_files(glob_files(root.as_ref(), pattern)?)
+    }
+
+    /// Create a dataset from Arrow IPC files, read in order as the shards of the dataset.
+    pub fn from_files<P: AsRef<Path>>(
+        paths: impl IntoIterator<Item = P>,
+    ) -> Result<Self, ArrowDatasetError> {
+        let files = paths
+            .into_iter()
+            .map(|path| path.as_ref().to_path_buf())
+            .collect::<Vec<_>>();
+
+        let mut readers = Vec::new();
+        let mut batches = Vec::new();
+        let mut len = 0;
+        for (file_index, path) in files.iter().enumerate() {
+            // Only the number of rows of the batches is needed.
+            let counter = open_ipc(path, Some(vec![]))?;
+            for (batch_index, batch) in counter.enumerate() {
+                batches.push((len, file_index, batch_index));
+                len += batch?.num_rows();
+            }
+            readers.push(Mutex::new(open_ipc(path, None)?));
+        }
+
+        Ok(Self {
+            files,
+            readers,
+            batches,
+            len,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Only read the given columns, which should contain the fields of the items.
+    pub fn with_columns<S: AsRef<str>>(mut self, columns: &[S]) -> Result<Self, ArrowDatasetError> {
+        let columns = columns
+            .iter()
+            .map(|column| column.as_ref().to_string())
+            .collect::<Vec<_>>();
+
+        let readers = self
+            .files
+            .iter()
+            .zip(self.readers.iter_mut())
+            .map(|(path, reader)| {
+                let schema = reader.get_mut().unwrap().schema();
+                let indices = projection_indices(&schema, &columns, path)?;
+                open_ipc(path, Some(indices)).map(Mutex::new)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.readers = readers;
+        Ok(self)
+    }
+
+    fn read_row(&self, index: usize) -> Result<Option<I>, ArrowDatasetError>
+    where
+        I: DeserializeOwned,
+    {
+        if index >= self.len {
+            return Ok(None);
+        }
+        let position = self
+            .batches
+            .partition_point(|(start, _, _)| *start <= index)
+            - 1;
+        let (start, file_index, batch_index) = self.batches[position];
+
+        let mut reader = self.readers[file_index].lock().unwrap();
+        reader.set_index(batch_index)?;
+
+        match reader.next() {
+            Some(batch) => deserialize_row(&batch?, index - start).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<I> Dataset<I> for ArrowIpcDataset<I>
+where
+    I: Clone + Send + Sync + DeserializeOwned,
+{
+    fn get(&self, index: usize) -> Option<I> {
+        self.read_row(index)
+            .unwrap_or_else(|err| panic!("Failed to read the row {index}: {err}"))
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+/// The files matching a glob pattern in a directory, sorted by path.
+pub(crate) fn glob_files(root: &Path, pattern: &str) -> Result<Vec<PathBuf>, ArrowDatasetError> {
+    let files = globwalk::GlobWalkerBuilder::from_patterns(root, &[pattern])
+        .follow_links(true)
+        .sort_by(|p1: &DirEntry, p2: &DirEntry| p1.path().cmp(p2.path()))
+        .build()
+        .map_err(|err| ArrowDatasetError::Other(format!("{err:?}")))?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .map(DirEntry::into_path)
+        .collect::<Vec<_>>();
+
+    if files.is_empty() {
+        return Err(ArrowDatasetError::NoFiles(format!(
+            "{}/{pattern}",
+            root.display()
+        )));
+    }
+
+    Ok(files)
+}
+
+/// The indices of the projected columns in a schema.
+pub(crate) fn projection_indices(
+    schema: &Schema,
+    columns: &[String],
+    path: &Path,
+) -> Result<Vec<usize>, ArrowDatasetError> {
+    let mut indices = columns
+        .iter()
+        .map(|column| {
+            schema
+                .index_of(column)
+                .map_err(|_| ArrowDatasetError::ColumnNotFound(column.clone(), path.into()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    indices.sort();
+
+    Ok(indices)
+}
+
+/// Deserialize a row of a record batch into an item, with a field per column.
+pub(crate) fn deserialize_row<I: DeserializeOwned>(
+    batch: &RecordBatch,
+    row: usize,
+) -> Result<I, ArrowDatasetError> {
+    let schema = batch.schema();
+    let mut fields = Map::new();
+    for (field, column) in schema.fields().iter().zip(batch.columns()) {
+        fields.insert(field.name().clone(), array_value(column.as_ref(), row)?);
+    }
+
+    serde_json::from_value(Value::Object(fields))
+        .map_err(|err| ArrowDatasetError::Other(format!("Could not deserialize the row: {err}")))
+}
+
+/// The value of an array at a row, with the lists and binaries as sequences and the structs as
+/// maps. The values without a serde equivalent, e.g. dates, are formatted as strings.
+fn array_value(array: &dyn Array, row: usize) -> Result<Value, ArrowDatasetError> {
+    if array.is_null(row) {
+        return Ok(Value::Null);
+    }
+
+    let value = match array.data_type() {
+        DataType::Null => Value::Null,
+        DataType::Boolean => array.as_boolean().value(row).into(),
+        DataType::Int8 => array.as_primitive::<Int8Type>().value(row).into(),
+        DataType::Int16 => array.as_primitive::<Int16Type>().value(row).into(),
+        DataType::Int32 => array.as_primitive::<Int32Type>().value(row).into(),
+        DataType::Int64 => array.as_primitive::<Int64Type>().value(row).into(),
+        DataType::UInt8 => array.as_primitive::<UInt8Type>().value(row).into(),
+        DataType::UInt16 => array.as_primitive::<UInt16Type>().value(row).into(),
+        DataType::UInt32 => array.as_primitive::<UInt32Type>().value(row).into(),
+        DataType::UInt64 => array.as_primitive::<UInt64Type>().value(row).into(),
+        DataType::Float16 => array
+            .as_primitive::<Float16Type>()
+            .value(row)
+            .to_f64()
+            .into(),
+        DataType::Float32 => array.as_primitive::<Float32Type>().value(row).into(),
+        DataType::Float64 => array.as_primitive::<Float64Type>().value(row).into(),
+        DataType::Utf8 => array.as_string::<i32>().value(row).into(),
+        DataType::LargeUtf8 => array.as_string::<i64>().value(row).into(),
+        DataType::Utf8View => array.as_string_view().value(row).into(),
+        DataType::Binary => array.as_binary::<i32>().value(row).into(),
+        DataType::LargeBinary => array.as_binary::<i64>().value(row).into(),
+        DataType::BinaryView => array.as_binary_view().value(row).into(),
+        DataType::FixedSizeBinary(_) => array.as_fixed_size_binary().value(row).into(),
+        DataType::List(_) => list_value(array.as_list::<i32>().value(row).as_ref())?,
+        DataType::LargeList(_) => list_value(array.as_list::<i64>().value(row).as_ref())?,
+        DataType::FixedSizeList(_, _) => {
+            list_value(array.as_fixed_size_list().value(row).as_ref())?
+        }
+        DataType::Struct(fields) => {
+            let array = array.as_struct();
+            let mut map = Map::new();
+            for (field, column) in fields.iter().zip(array.columns()) {
+                map.insert(field.name().clone(), array_value(column.as_ref(), row)?);
+            }
+            Value::Object(map)
+        }
+        DataType::Dictionary(_, value_type) => {
+            let values = arrow::compute::cast(&array.slice(row, 1), value_type)?;
+            array_value(values.as_ref(), 0)?
+        }
+        _ => {
+            let options = FormatOptions::default();
+            let formatter = ArrayFormatter::try_new(array, &options)?;
+            formatter.value(row).to_string().into()
+        }
+    };
+
+    Ok(value)
+}
+
+fn list_value(values: &dyn Array) -> Result<Value, ArrowDatasetError> {
+    (0..values.len())
+        .map(|row| array_value(values, row))
+        .collect::<Result<Vec<_>, _>>()
+        .map(Value::Array)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use arrow::{
+        array::{ArrayRef, Float64Array, Int32Array, StringArray},
+        ipc::writer::FileWriter,
+    };
+    use serde::Deserialize;
+    use std::sync::Arc;
+
+    #[derive(Deserialize, Clone, Debug, PartialEq)]
+    pub(crate) struct Item {
+        pub id: i32,
+        pub name: String,
+        pub score: f64,
+    }
+
+    #[derive(Deserialize, Clone, Debug, PartialEq)]
+    #[serde(deny_unknown_fields)]
+    pub(crate) struct Projected {
+        pub id: i32,
+        pub name: String,
+    }
+
+    /// A batch with the given ids, named after them, and scores of a tenth of the ids.
+    pub(crate) fn batch(ids: &[i32]) -> RecordBatch {
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, false),
+            Field::new("name", DataType::Utf8, false),
+            Field::new("score", DataType::Float64, false),
+        ]);
+        let names = ids
+            .iter()
+            .map(|id| format!("item {id}"))
+            .collect::<Vec<_>>();
+        let scores = ids.iter().map(|id| *id as f64 / 10.0).collect::<Vec<_>>();
+
+        RecordBatch::try_new(
+            Arc::new(schema),
+            vec![
+                Arc::new(Int32Array::from(ids.to_vec())) as ArrayRef,
+                Arc::new(StringArray::from(names)),
+                Arc::new(Float64Array::from(scores)),
+            ],
+        )
+        .unwrap()
+    }
+
+    fn write_ipc(path: &Path, batches: &[RecordBatch]) {
+        let mut writer =
+            FileWriter::try_new(File::create(path).unwrap(), &batches[0].schema()).unwrap();
+        for batch in batches {
+            writer.write(batch).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn test_rows_are_read_across_batches_and_files() {
+        let dir = tempfile::tempdir().unwrap();
+        write_ipc(
+            &dir.path().join("train-0.arrow"),
+            &[batch(&[0, 1]), batch(&[2])],
+        );
+        write_ipc(&dir.path().join("train-1.arrow"), &[batch(&[3, 4])]);
+
+        let dataset = ArrowIpcDataset::<Item>::from_glob(dir.path(), "train-*.arrow").unwrap();
+
+        assert_eq!(dataset.len(), 5);
+        let ids = dataset.iter().map(|item| item.id).collect::<Vec<_>>();
+        assert_eq!(ids, [0, 1, 2, 3, 4]);
+        assert_eq!(
+            dataset.get(3),
+            Some(Item {
+                id: 3,
+                name: "item 3".to_string(),
+                score: 0.3,
+            })
+        );
+        assert_eq!(dataset.get(5), None);
+    }
+
+    #[test]
+    fn test_only_the_projected_columns_are_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.arrow");
+        write_ipc(&path, &[batch(&[0, 1, 2])]);
+
+        let dataset = ArrowIpcDataset::<Projected>::new(&path)
+            .unwrap()
+            .with_columns(&["name", "id"])
+            .unwrap();
+
+        assert_eq!(
+            dataset.get(2),
+            Some(Projected {
+                id: 2,
+                name: "item 2".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_missing_column() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.arrow");
+        write_ipc(&path, &[batch(&[0])]);
+
+        let result = ArrowIpcDataset::<Projected>::new(&path)
+            .unwrap()
+            .with_columns(&["id", "label"]);
+
+        assert!(
+            matches!(result, Err(ArrowDatasetError::ColumnNotFound(column, _)) if column == "label")
+        );
+    }
+
+    #[test]
+    fn test_invalid_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.arrow");
+        std::fs::write(&path, b"not an arrow file").unwrap();
+
+        assert!(matches!(
+            ArrowIpcDataset::<Item>::new(&path),
+            Err(ArrowDatasetError::Arrow(_))
+        ));
+        assert!(matches!(
+            ArrowIpcDataset::<Item>::from_glob(dir.path(), "*.parquet"),
+            Err(ArrowDatasetError::NoFiles(_))
+        ));
+    }
+
+    #[test]
+    #[should_panic = "Failed to read the row 0"]
+    fn test_row_not_matching_the_item() {
+        #[derive(Deserialize, Clone)]
+        struct Labeled {
+            #[allow(dead_code)]
+            label: u8,
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.arrow");
+        write_ipc(&path, &[batch(&[0])]);
+
+        let dataset = ArrowIpcDataset::<Labeled>::new(&path).unwrap();
+        let _item = dataset.get(0);
+    }
+}
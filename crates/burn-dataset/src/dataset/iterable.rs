@@ -0,0 +1,142 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use crate::{Dataset, transform::Mapper};
+
+/// A dataset only read sequentially, for datasets too large to be indexed, e.g. streamed from
+/// [sharded archives](crate::WebDataset).
+///
+/// The items are split into shards that can be read independently, so that the workers of a data
+/// loader each read their own shards.
+pub trait IterableDataset<I>: Send + Sync {
+    /// The number of shards.
+    fn num_shards(&self) -> usize;
+
+    /// Iterate over the items of a shard.
+    fn iter_shard(&self, shard: usize) -> Box<dyn Iterator<Item = I> + Send + '_>;
+
+    /// The number of items, when it is known without reading the shards.
+    fn num_items(&self) -> Option<usize> {
+        None
+    }
+
+    /// Iterate over the items of all the shards, in order.
+    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = I> + Send + 'a>
+    where
+        I: 'a,
+    {
+        Box::new((0..self.num_shards()).flat_map(|shard| self.iter_shard(shard)))
+    }
+}
+
+impl<I, D> IterableDataset<I> for Arc<D>
+where
+    D: IterableDataset<I> + ?Sized,
+{
+    fn num_shards(&self) -> usize {
+        self.as_ref().num_shards()
+    }
+
+    fn iter_shard(&self, shard: usize) -> Box<dyn Iterator<Item = I> + Send + '_> {
+        self.as_ref().iter_shard(shard)
+    }
+
+    fn num_items(&self) -> Option<usize> {
+        self.as_ref().num_items()
+    }
+}
+
+/// Read a [dataset](Dataset) as an [iterable dataset](IterableDataset), split into shards of
+/// contiguous items.
+pub struct ShardedDataset<D, I> {
+    dataset: D,
+    num_shards: usize,
+    input: PhantomData<I>,
+}
+
+impl<D, I> ShardedDataset<D, I>
+where
+    D: Dataset<I>,
+{
+    /// Split a dataset into the given number of shards.
+    pub fn new(dataset: D, num_shards: usize) -> Self {
+        Self {
+            dataset,
+            num_shards: num_shards.max(1),
+            input: PhantomData,
+        }
+    }
+}
+
+impl<D, I> IterableDataset<I> for ShardedDataset<D, I>
+where
+    D: Dataset<I>,
+    I: Send + Sync,
+{
+    fn num_shards(&self) -> usize {
+        self.num_shards
+    }
+
+    fn iter_shard(&self, shard: usize) -> Box<dyn Iterator<Item = I> + Send + '_> {
+        let shard_size = self.dataset.len().div_ceil(self.num_shards);
+        let start = (shard * shard_size).min(self.dataset.len());
+        let end = (start + shard_size).min(self.dataset.len());
+
+        Box::new((start..end).filter_map(|index| self.dataset.get(index)))
+    }
+
+    fn num_items(&self) -> Option<usize> {
+        Some(self.dataset.len())
+    }
+}
+
+/// Iterable dataset mapping each item of an inner [iterable dataset](IterableDataset) with a
+/// [mapper](Mapper), e.g. to decode the samples of a [WebDataset](crate::WebDataset).
+#[derive(new)]
+pub struct MapperIterableDataset<D, M, I> {
+    dataset: D,
+    mapper: M,
+    input: PhantomData<I>,
+}
+
+impl<D, M, I, O> IterableDataset<O> for MapperIterableDataset<D, M, I>
+where
+    D: IterableDataset<I>,
+    M: Mapper<I, O>,
+    I: Send + Sync,
+    O: Send + Sync,
+{
+    fn num_shards(&self) -> usize {
+        self.dataset.num_shards()
+    }
+
+    fn iter_shard(&self, shard: usize) -> Box<dyn Iterator<Item = O> + Send + '_> {
+        Box::new(
+            self.dataset
+                .iter_shard(shard)
+                .map(|item| self.mapper.map(&item)),
+        )
+    }
+
+    fn num_items(&self) -> Option<usize> {
+        self.dataset.num_items()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemDataset;
+
+    #[test]
+    fn test_sharded_dataset_covers_the_items() {
+        let dataset = ShardedDataset::new(InMemDataset::new((0..10).collect()), 3);
+
+        let shards = (0..dataset.num_shards())
+            .map(|shard| dataset.iter_shard(shard).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        assert_eq!(shards, vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9]]);
+        assert_eq!(dataset.iter().count(), 10);
+        assert_eq!(dataset.num_items(), Some(10));
+    }
+}
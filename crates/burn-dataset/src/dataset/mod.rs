@@ -1,10 +1,14 @@
 mod base;
 mod in_memory;
+mod iterable;
 mod iterator;
+mod webdataset;
 
 pub use base::*;
 pub use in_memory::*;
+pub use iterable::*;
 pub use iterator::*;
+pub use webdataset::*;
 
 #[cfg(any(test, feature = "fake"))]
 mod fake;
@@ -23,3 +27,15 @@ pub use sqlite::*;
 
 #[cfg(any(feature = "sqlite", feature = "sqlite-bundled"))]
 mod sqlite;
+
+#[cfg(feature = "arrow")]
+mod arrow;
+
+#[cfg(feature = "arrow")]
+pub use self::arrow::*;
+
+#[cfg(feature = "parquet")]
+mod parquet;
+
+#[cfg(feature = "parquet")]
+pub use self::parquet::*;
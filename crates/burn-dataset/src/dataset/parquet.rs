@@ -0,0 +1,253 @@
+use std::{
+    fs::File,
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::Dataset;
+
+use super::arrow::{ArrowDatasetError, deserialize_row, glob_files, projection_indices};
+use parquet::arrow::{
+    ProjectionMask,
+    arrow_reader::{ArrowReaderMetadata, ArrowReaderOptions, ParquetRecordBatchReaderBuilder},
+};
+use serde::de::DeserializeOwned;
+
+/// Dataset reading the rows of [Parquet](https://parquet.apache.org) files, e.g. the shards of a
+/// Hugging Face dataset, without going through Python.
+///
+/// The rows are deserialized into items with serde, the columns being matched with the fields of
+/// the items by name. Multiple files can be read as the shards of a single dataset, in order.
+///
+/// The metadata of the files is read once when the dataset is created. The random access to the
+/// rows relies on the row groups of the metadata, so only the pages of the row group of a row are
+/// decoded, and only for the [projected columns](ParquetDataset::with_columns). The files are kept
+/// open, so the rows of a file are read one at a time.
+///
+/// # Panics
+///
+/// [get](Dataset::get) panics if a row can't be read or deserialized into an item.
+pub struct ParquetDataset<I> {
+    files: Vec<ParquetFile>,
+    // The first row of every row group, with its file and row group indices.
+    row_groups: Vec<(usize, usize, usize)>,
+    projection: Option<Vec<Vec<usize>>>,
+    len: usize,
+    phantom: PhantomData<I>,
+}
+
+struct ParquetFile {
+    path: PathBuf,
+    // The clones of a file share its position.
+    file: Mutex<File>,
+    metadata: ArrowReaderMetadata,
+}
+
+impl<I> ParquetDataset<I> {
+    /// Create a dataset from a Parquet file.
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, ArrowDatasetError> {
+        Self::from_files([path])
+    }
+
+    /// Create a dataset from the Parquet files matching a glob pattern in a directory, sorted by
+    /// path, e.g. `data/train-*.parquet`.
+    pub fn from_glob<P: AsRef<Path>>(root: P, pattern: &str) -> Result<Self, ArrowDatasetError> {
+        Self::from_files(glob_files(root.as_ref(), pattern)?)
+    }
+
+    /// Create a dataset from Parquet files, read in order as the shards of the dataset.
+    pub fn from_files<P: AsRef<Path>>(
+        paths: impl IntoIterator<Item = P>,
+    ) -> Result<Self, ArrowDatasetError> {
+        let mut files = Vec::new();
+        let mut row_groups = Vec::new();
+        let mut len = 0;
+
+        for (file_index, path) in paths.into_iter().enumerate() {
+            let path = path.as_ref().to_path_buf();
+            let file = File::open(&path)?;
+            let metadata = ArrowReaderMetadata::load(&file, ArrowReaderOptions::default())?;
+
+            for (row_group_index, row_group) in metadata.metadata().row_groups().iter().enumerate()
+            {
+                row_groups.push((len, file_index, row_group_index));
+                len += row_group.num_rows() as usize;
+            }
+            files.push(ParquetFile {
+                path,
+                file: Mutex::new(file),
+                metadata,
+            });
+        }
+
+        Ok(Self {
+            files,
+            row_groups,
+            projection: None,
+            len,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Only read the given columns, which should contain the fields of the items.
+    pub fn with_columns<S: AsRef<str>>(mut self, columns: &[S]) -> Result<Self, ArrowDatasetError> {
+        let columns = columns
+            .iter()
+            .map(|column| column.as_ref().to_string())
+            .collect::<Vec<_>>();
+
+        let projection = self
+            .files
+            .iter()
+            .map(|file| projection_indices(file.metadata.schema(), &columns, &file.path))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.projection = Some(projection);
+        Ok(self)
+    }
+
+    fn read_row(&self, index: usize) -> Result<Option<I>, ArrowDatasetError>
+    where
+        I: DeserializeOwned,
+    {
+        if index >= self.len {
+            return Ok(None);
+        }
+        let position = self
+            .row_groups
+            .partition_point(|(start, _, _)| *start <= index)
+            - 1;
+        let (start, file_index, row_group_index) = self.row_groups[position];
+        let file = &self.files[file_index];
+
+        let handle = file.file.lock().unwrap();
+        let mut builder = ParquetRecordBatchReaderBuilder::new_with_metadata(
+            handle.try_clone()?,
+            file.metadata.clone(),
+        )
+        .with_row_groups(vec![row_group_index])
+        .with_offset(index - start)
+        .with_limit(1)
+        .with_batch_size(1);
+
+        if let Some(projection) = &self.projection {
+            let mask = ProjectionMask::roots(
+                file.metadata.parquet_schema(),
+                projection[file_index].iter().copied(),
+            );
+            builder = builder.with_projection(mask);
+        }
+
+        match builder.build()?.next() {
+            Some(batch) => deserialize_row(&batch?, 0).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<I> Dataset<I> for ParquetDataset<I>
+where
+    I: Clone + Send + Sync + DeserializeOwned,
+{
+    fn get(&self, index: usize) -> Option<I> {
+        self.read_row(index)
+            .unwrap_or_else(|err| panic!("Failed to read the row {index}: {err}"))
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataset::arrow::tests::{Item, Projected, batch};
+    use parquet::{arrow::ArrowWriter, file::properties::WriterProperties};
+
+    // Row groups of two rows, so that the rows of a file are spread over several row groups.
+    fn write_parquet(path: &Path, ids: &[i32]) {
+        let batch = batch(ids);
+        let properties = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .build();
+        let mut writer = ArrowWriter::try_new(
+            File::create(path).unwrap(),
+            batch.schema(),
+            Some(properties),
+        )
+        .unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+    }
+
+    #[test]
+    fn test_rows_are_read_across_row_groups_and_files() {
+        let dir = tempfile::tempdir().unwrap();
+        write_parquet(&dir.path().join("train-0.parquet"), &[0, 1, 2]);
+        write_parquet(&dir.path().join("train-1.parquet"), &[3, 4]);
+
+        let dataset = ParquetDataset::<Item>::from_glob(dir.path(), "train-*.parquet").unwrap();
+
+        assert_eq!(dataset.len(), 5);
+        let ids = dataset.iter().map(|item| item.id).collect::<Vec<_>>();
+        assert_eq!(ids, [0, 1, 2, 3, 4]);
+        assert_eq!(
+            dataset.get(2),
+            Some(Item {
+                id: 2,
+                name: "item 2".to_string(),
+                score: 0.2,
+            })
+        );
+        assert_eq!(dataset.get(5), None);
+    }
+
+    #[test]
+    fn test_only_the_projected_columns_are_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.parquet");
+        write_parquet(&path, &[0, 1, 2]);
+
+        let dataset = ParquetDataset::<Projected>::new(&path)
+            .unwrap()
+            .with_columns(&["name", "id"])
+            .unwrap();
+
+        assert_eq!(
+            dataset.get(1),
+            Some(Projected {
+                id: 1,
+                name: "item 1".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_missing_column() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.parquet");
+        write_parquet(&path, &[0]);
+
+        let result = ParquetDataset::<Projected>::new(&path)
+            .unwrap()
+            .with_columns(&["id", "label"]);
+
+        assert!(
+            matches!(result, Err(ArrowDatasetError::ColumnNotFound(column, _)) if column == "label")
+        );
+    }
+
+    #[test]
+    fn test_invalid_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.parquet");
+        std::fs::write(&path, b"not a parquet file").unwrap();
+
+        assert!(matches!(
+            ParquetDataset::<Item>::new(&path),
+            Err(ArrowDatasetError::Parquet(_))
+        ));
+    }
+}
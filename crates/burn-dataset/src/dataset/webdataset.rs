@@ -0,0 +1,451 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{self, BufReader, Read},
+    path::{Path, PathBuf},
+};
+
+use crate::IterableDataset;
+
+const BLOCK_SIZE: usize = 512;
+
+/// A sample of a [WebDataset], made of the files of a shard sharing the same key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebDatasetSample {
+    /// The key of the sample, the path of its files up to the first dot of the file names.
+    pub key: String,
+
+    /// The content of the files of the sample by extension, e.g. `jpg`, `cls` or `seg.png`.
+    pub files: BTreeMap<String, Vec<u8>>,
+}
+
+impl WebDatasetSample {
+    /// The content of the file with the given extension.
+    pub fn get(&self, extension: &str) -> Option<&[u8]> {
+        self.files.get(extension).map(Vec::as_slice)
+    }
+
+    /// The content of the file with the given extension as text, e.g. for a `txt` caption or a
+    /// `cls` label.
+    pub fn text(&self, extension: &str) -> Option<&str> {
+        self.get(extension)
+            .and_then(|content| std::str::from_utf8(content).ok())
+    }
+}
+
+/// Iterable dataset reading the samples of sharded tar archives following the
+/// [WebDataset](https://github.com/webdataset/webdataset) convention.
+///
+/// Every tar archive is a shard, and the consecutive files of an archive sharing the same key
+/// form a sample, e.g. `images/000001.jpg` and `images/000001.cls`. The archives are read
+/// sequentially, so only the samples being loaded are kept in memory.
+///
+/// The samples can be decoded into items with a
+/// [mapper](crate::transform::Mapper) and a [MapperIterableDataset](crate::MapperIterableDataset).
+///
+/// # Panics
+///
+/// Iterating over a shard panics when its archive can't be read.
+#[derive(Debug, Clone)]
+pub struct WebDataset {
+    shards: Vec<PathBuf>,
+}
+
+impl WebDataset {
+    /// Create a dataset from tar archives, each archive being a shard.
+    pub fn new<P: AsRef<Path>>(shards: impl IntoIterator<Item = P>) -> Self {
+        Self {
+            shards: shards
+                .into_iter()
+                .map(|path| path.as_ref().to_path_buf())
+                .collect(),
+        }
+    }
+
+    /// Create a dataset from the tar archives of a directory, sorted by path.
+    pub fn from_dir<P: AsRef<Path>>(directory: P) -> io::Result<Self> {
+        let mut shards = Vec::new();
+        for entry in std::fs::read_dir(directory)? {
+            let path = entry?.path();
+            if path.is_file() && path.extension().is_some_and(|extension| extension == "tar") {
+                shards.push(path);
+            }
+        }
+        shards.sort();
+
+        Ok(Self { shards })
+    }
+
+    /// The paths of the tar archives.
+    pub fn shards(&self) -> &[PathBuf] {
+        &self.shards
+    }
+}
+
+impl IterableDataset<WebDatasetSample> for WebDataset {
+    fn num_shards(&self) -> usize {
+        self.shards.len()
+    }
+
+    fn iter_shard(&self, shard: usize) -> Box<dyn Iterator<Item = WebDatasetSample> + Send + '_> {
+        let path = &self.shards[shard];
+        let file = File::open(path)
+            .unwrap_or_else(|err| panic!("Could not open the shard {}: {err}", path.display()));
+
+        Box::new(Samples {
+            entries: TarEntries::new(BufReader::new(file)),
+            path,
+            current: None,
+        })
+    }
+}
+
+/// Groups the consecutive files of an archive sharing the same key into samples.
+struct Samples<'a, R> {
+    entries: TarEntries<R>,
+    path: &'a Path,
+    current: Option<WebDatasetSample>,
+}
+
+impl<R: Read> Iterator for Samples<'_, R> {
+    type Item = WebDatasetSample;
+
+    fn next(&mut self) -> Option<WebDatasetSample> {
+        loop {
+            let Some(entry) = self.entries.next() else {
+                return self.current.take();
+            };
+            let (name, content) = entry.unwrap_or_else(|err| {
+                panic!("Could not read the shard {}: {err}", self.path.display())
+            });
+            let Some((key, extension)) = split_key(&name) else {
+                continue;
+            };
+
+            match &mut self.current {
+                Some(sample) if sample.key == key => {
+                    sample.files.insert(extension.to_string(), content);
+                }
+                _ => {
+                    let sample = WebDatasetSample {
+                        key: key.to_string(),
+                        files: BTreeMap::from([(extension.to_string(), content)]),
+                    };
+                    if let Some(previous) = self.current.replace(sample) {
+                        return Some(previous);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Split a file path into the key of its sample and its extension, the key ending at the first
+/// dot of the file name. Hidden files and files without extension aren't part of a sample.
+fn split_key(name: &str) -> Option<(&str, &str)> {
+    let start = name.rfind('/').map(|index| index + 1).unwrap_or(0);
+    let dot = start + name[start..].find('.')?;
+    let extension = &name[dot + 1..];
+
+    if dot == start || extension.is_empty() {
+        return None;
+    }
+
+    Some((&name[..dot], extension))
+}
+
+/// Reads the regular files of a tar archive, with their path.
+///
+/// Supports the ustar format, with the long paths of the GNU and pax extensions and the sizes of
+/// the pax extension.
+pub(crate) struct TarEntries<R> {
+    reader: R,
+    done: bool,
+}
+
+impl<R: Read> TarEntries<R> {
+    pub(crate) fn new(reader: R) -> Self {
+        Self {
+            reader,
+            done: false,
+        }
+    }
+
+    fn next_entry(&mut self) -> io::Result<Option<(String, Vec<u8>)>> {
+        let mut long_name = None;
+        let mut pax_size = None;
+
+        loop {
+            let mut header = [0; BLOCK_SIZE];
+            if !read_block(&mut self.reader, &mut header)? || header.iter().all(|byte| *byte == 0) {
+                return Ok(None);
+            }
+
+            let header_size = parse_size(&header[124..136])?;
+            let size = match header[156] {
+                // The extended headers have their own size.
+                b'L' | b'x' | b'g' => header_size,
+                _ => pax_size.take().unwrap_or(header_size),
+            };
+            let padding = size.next_multiple_of(BLOCK_SIZE as u64) - size;
+
+            match header[156] {
+                // Regular and contiguous files.
+                b'0' | 0 | b'7' => {
+                    let content = self.read_content(size, padding)?;
+                    let name = long_name.take().unwrap_or_else(|| header_name(&header));
+                    return Ok(Some((name, content)));
+                }
+                // GNU long name of the next entry.
+                b'L' => {
+                    let content = self.read_content(size, padding)?;
+                    long_name = Some(c_string(&content));
+                }
+                // Pax extended header of the next entry.
+                b'x' => {
+                    let content = self.read_content(size, padding)?;
+                    let pax = parse_pax(&content)?;
+                    long_name = pax.path.or(long_name);
+                    pax_size = pax.size;
+                }
+                // Directories, links and other entries without a sample file.
+                _ => self.skip(size + padding)?,
+            }
+        }
+    }
+
+    fn read_content(&mut self, size: u64, padding: u64) -> io::Result<Vec<u8>> {
+        let mut content = vec![0; size as usize];
+        self.reader.read_exact(&mut content)?;
+        self.skip(padding)?;
+
+        Ok(content)
+    }
+
+    fn skip(&mut self, size: u64) -> io::Result<()> {
+        let skipped = io::copy(&mut (&mut self.reader).take(size), &mut io::sink())?;
+        if skipped < size {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: Read> Iterator for TarEntries<R> {
+    type Item = io::Result<(String, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let entry = self.next_entry().transpose();
+        if !matches!(entry, Some(Ok(_))) {
+            self.done = true;
+        }
+        entry
+    }
+}
+
+/// Read a block, returning false at the end of the archive.
+fn read_block<R: Read>(reader: &mut R, block: &mut [u8; BLOCK_SIZE]) -> io::Result<bool> {
+    let mut read = 0;
+    while read < BLOCK_SIZE {
+        match reader.read(&mut block[read..])? {
+            0 if read == 0 => return Ok(false),
+            0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+            count => read += count,
+        }
+    }
+
+    Ok(true)
+}
+
+/// Parse the size of an entry, written in octal or, for large files, in base 256.
+fn parse_size(field: &[u8]) -> io::Result<u64> {
+    if field[0] & 0x80 != 0 {
+        let size = field[1..]
+            .iter()
+            .fold(0u64, |size, byte| (size << 8) | *byte as u64);
+        return Ok(size);
+    }
+
+    let digits = c_string(field);
+    let digits = digits.trim();
+    if digits.is_empty() {
+        return Ok(0);
+    }
+
+    u64::from_str_radix(digits, 8).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// The path of an entry, with the prefix of the ustar format.
+fn header_name(header: &[u8; BLOCK_SIZE]) -> String {
+    let name = c_string(&header[0..100]);
+    let prefix = match &header[257..262] == b"ustar" {
+        true => c_string(&header[345..500]),
+        false => String::new(),
+    };
+
+    match prefix.is_empty() {
+        true => name,
+        false => format!("{prefix}/{name}"),
+    }
+}
+
+/// The records of a pax extended header applying to the next entry.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct PaxHeader {
+    path: Option<String>,
+    size: Option<u64>,
+}
+
+/// Parse the records of a pax extended header, each formatted as `{length} {key}={value}\n`.
+fn parse_pax(content: &[u8]) -> io::Result<PaxHeader> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "Invalid pax extended header");
+    let mut header = PaxHeader::default();
+    let mut records = content;
+
+    while !records.is_empty() {
+        let space = records
+            .iter()
+            .position(|byte| *byte == b' ')
+            .ok_or_else(invalid)?;
+        let length = std::str::from_utf8(&records[..space])
+            .ok()
+            .and_then(|length| length.parse::<usize>().ok())
+            .ok_or_else(invalid)?;
+        let record = records.get(space + 1..length).ok_or_else(invalid)?;
+        let record = record.strip_suffix(b"\n").unwrap_or(record);
+
+        if let Some(path) = record.strip_prefix(b"path=") {
+            header.path = Some(String::from_utf8_lossy(path).into_owned());
+        } else if let Some(size) = record.strip_prefix(b"size=") {
+            let size = std::str::from_utf8(size)
+                .ok()
+                .and_then(|size| size.parse::<u64>().ok())
+                .ok_or_else(invalid)?;
+            header.size = Some(size);
+        }
+        records = &records[length..];
+    }
+
+    Ok(header)
+}
+
+fn c_string(bytes: &[u8]) -> String {
+    let end = bytes
+        .iter()
+        .position(|byte| *byte == 0)
+        .unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn append(archive: &mut Vec<u8>, name: &str, kind: u8, content: &[u8]) {
+        let mut header = [0; BLOCK_SIZE];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        header[124..135].copy_from_slice(format!("{:011o}", content.len()).as_bytes());
+        header[156] = kind;
+        header[257..263].copy_from_slice(b"ustar\0");
+        archive.write_all(&header).unwrap();
+        archive.write_all(content).unwrap();
+        archive.resize(archive.len().next_multiple_of(BLOCK_SIZE), 0);
+    }
+
+    #[test]
+    fn test_samples_are_grouped_by_key() {
+        let long_name = format!("{}/000003.jpg", "nested".repeat(20));
+        let mut archive = Vec::new();
+        append(&mut archive, "images/", b'5', b"");
+        append(&mut archive, "images/000001.jpg", b'0', b"first image");
+        append(&mut archive, "images/000001.cls", b'0', b"3");
+        append(&mut archive, "images/000002.jpg", b'0', b"second image");
+        append(&mut archive, "images/000002.seg.png", b'0', b"mask");
+        append(&mut archive, "images/.hidden", b'0', b"skipped");
+        append(&mut archive, "././@LongLink", b'L', long_name.as_bytes());
+        append(&mut archive, "truncated", b'0', b"third image");
+        archive.resize(archive.len() + 2 * BLOCK_SIZE, 0);
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("shard-000000.tar"), &archive).unwrap();
+        std::fs::write(dir.path().join("shard-000001.tar"), [0; 2 * BLOCK_SIZE]).unwrap();
+        std::fs::write(dir.path().join("README.md"), "not a shard").unwrap();
+
+        let dataset = WebDataset::from_dir(dir.path()).unwrap();
+        assert_eq!(dataset.num_shards(), 2);
+        let samples = dataset.iter().collect::<Vec<_>>();
+
+        assert_eq!(samples.len(), 3);
+        assert_eq!(samples[0].key, "images/000001");
+        assert_eq!(samples[0].get("jpg"), Some(b"first image".as_slice()));
+        assert_eq!(samples[0].text("cls"), Some("3"));
+        assert_eq!(
+            samples[1].files.keys().collect::<Vec<_>>(),
+            vec!["jpg", "seg.png"]
+        );
+        assert_eq!(samples[2].key, long_name.trim_end_matches(".jpg"));
+        assert_eq!(samples[2].text("jpg"), Some("third image"));
+    }
+
+    #[test]
+    fn test_pax_path() {
+        let records = b"30 mtime=1350244992.023960108\n26 path=images/000001.jpg\n";
+
+        assert_eq!(
+            parse_pax(records).unwrap(),
+            PaxHeader {
+                path: Some("images/000001.jpg".to_string()),
+                size: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_pax_size_overrides_the_header_size() {
+        let mut archive = Vec::new();
+        append(&mut archive, "PaxHeader", b'x', b"11 size=11\n");
+        // The size of the header is ignored, e.g. for files larger than the ustar limit.
+        let mut header = [0; BLOCK_SIZE];
+        header[..17].copy_from_slice(b"images/000001.jpg");
+        header[124..135].copy_from_slice(b"00000000000");
+        header[156] = b'0';
+        archive.write_all(&header).unwrap();
+        archive.write_all(b"first image").unwrap();
+        archive.resize(archive.len().next_multiple_of(BLOCK_SIZE), 0);
+        append(&mut archive, "images/000001.cls", b'0', b"3");
+        archive.resize(archive.len() + 2 * BLOCK_SIZE, 0);
+
+        let entries = TarEntries::new(archive.as_slice())
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(
+            entries,
+            vec![
+                ("images/000001.jpg".to_string(), b"first image".to_vec()),
+                ("images/000001.cls".to_string(), b"3".to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_pax_header() {
+        let mut archive = Vec::new();
+        append(&mut archive, "PaxHeader", b'x', b"11 size=1x\n");
+        append(&mut archive, "images/000001.jpg", b'0', b"first image");
+
+        let mut entries = TarEntries::new(archive.as_slice());
+
+        assert_eq!(
+            entries.next().unwrap().unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        assert!(entries.next().is_none());
+    }
+}
@@ -24,6 +24,10 @@ pub mod audio;
 #[cfg(feature = "vision")]
 pub mod vision;
 
+/// Text datasets and tokenizers.
+#[cfg(feature = "text")]
+pub mod text;
+
 mod dataset;
 pub use dataset::*;
 #[cfg(any(feature = "sqlite", feature = "sqlite-bundled"))]
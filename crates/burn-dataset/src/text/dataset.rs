@@ -0,0 +1,136 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::Dataset;
+
+/// Dataset of texts read from a file, with one text per line.
+///
+/// The file is indexed when the dataset is created, and the texts are read from the file
+/// when they are loaded, so the file doesn't have to fit in memory. The empty lines are skipped.
+pub struct TextDataset {
+    reader: Mutex<BufReader<File>>,
+    /// The byte range of each line.
+    lines: Vec<(u64, usize)>,
+    field: Option<String>,
+}
+
+impl TextDataset {
+    /// Create a dataset from a text file, each line being a text.
+    pub fn from_lines<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::new(path.as_ref(), None)
+    }
+
+    /// Create a dataset from a JSON lines file, the text of each line being the string `field`
+    /// of its JSON object.
+    pub fn from_jsonl<P: AsRef<Path>>(path: P, field: &str) -> io::Result<Self> {
+        Self::new(path.as_ref(), Some(field.to_string()))
+    }
+
+    fn new(path: &Path, field: Option<String>) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+
+        let mut lines = Vec::new();
+        let mut offset = 0;
+        let mut line = String::new();
+        for line_number in 1.. {
+            line.clear();
+            let size = reader.read_line(&mut line)?;
+            if size == 0 {
+                break;
+            }
+
+            let text = line.trim_end_matches(['\n', '\r']);
+            if !text.trim().is_empty() {
+                if let Some(field) = &field {
+                    json_field(text, field).map_err(|err| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("Line {line_number} of {}: {err}", path.display()),
+                        )
+                    })?;
+                }
+                lines.push((offset, text.len()));
+            }
+            offset += size as u64;
+        }
+
+        Ok(Self {
+            reader: Mutex::new(reader),
+            lines,
+            field,
+        })
+    }
+
+    fn read_line(&self, offset: u64, size: usize) -> io::Result<String> {
+        let mut reader = self.reader.lock().unwrap();
+        reader.seek(SeekFrom::Start(offset))?;
+
+        let mut bytes = vec![0; size];
+        reader.read_exact(&mut bytes)?;
+        String::from_utf8(bytes).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+/// The string field of a JSON object.
+fn json_field(line: &str, field: &str) -> Result<String, String> {
+    let value: serde_json::Value = serde_json::from_str(line).map_err(|err| err.to_string())?;
+
+    match value.get(field) {
+        Some(serde_json::Value::String(text)) => Ok(text.clone()),
+        Some(_) => Err(format!("The field `{field}` isn't a string")),
+        None => Err(format!("Missing field `{field}`")),
+    }
+}
+
+impl Dataset<String> for TextDataset {
+    fn get(&self, index: usize) -> Option<String> {
+        let (offset, size) = *self.lines.get(index)?;
+        let line = self
+            .read_line(offset, size)
+            .unwrap_or_else(|err| panic!("Failed to read the text {index}: {err}"));
+
+        match &self.field {
+            Some(field) => Some(json_field(&line, field).expect("The lines were validated")),
+            None => Some(line),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.lines.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_text_dataset_lines_and_jsonl() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let path = dir.path().join("texts.txt");
+        fs::write(&path, "first text\r\n\nsecond, été\n  \nthird").unwrap();
+        let dataset = TextDataset::from_lines(&path).unwrap();
+        assert_eq!(
+            dataset.iter().collect::<Vec<_>>(),
+            vec!["first text", "second, été", "third"]
+        );
+
+        let path = dir.path().join("texts.jsonl");
+        fs::write(
+            &path,
+            "{\"text\": \"a\\nb\", \"id\": 1}\n{\"text\": \"c\"}\n",
+        )
+        .unwrap();
+        let dataset = TextDataset::from_jsonl(&path, "text").unwrap();
+        assert_eq!(dataset.len(), 2);
+        assert_eq!(dataset.get(0).unwrap(), "a\nb");
+        assert!(dataset.get(2).is_none());
+
+        let err = TextDataset::from_jsonl(&path, "id").err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,7 @@
+mod dataset;
+mod packing;
+mod tokenizer;
+
+pub use dataset::*;
+pub use packing::*;
+pub use tokenizer::*;
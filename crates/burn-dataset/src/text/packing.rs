@@ -0,0 +1,188 @@
+use std::collections::VecDeque;
+
+use super::Tokenizer;
+use crate::{Dataset, InMemDataset};
+
+/// A block of tokens packed from one or more sequences.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackedSequence {
+    /// The tokens of the block.
+    pub tokens: Vec<usize>,
+    /// Whether each token is part of a sequence, as opposed to padding.
+    pub attention_mask: Vec<bool>,
+    /// The index of the sequence of each token, within the block. The padding tokens have the
+    /// index following the last sequence, so the sequences can be masked from each other.
+    pub sequence_ids: Vec<usize>,
+}
+
+/// Pack token sequences into fixed-length blocks, e.g. to pretrain language models without
+/// wasting computation on padding.
+///
+/// The sequences are concatenated, optionally followed by a separator token, and the result is
+/// cut into blocks of `block_size` tokens, so a sequence can continue in the next block. The
+/// last block is padded, unless it is dropped.
+#[derive(Debug, Clone)]
+pub struct SequencePacker {
+    block_size: usize,
+    pad_token: usize,
+    separator: Option<usize>,
+    drop_last: bool,
+}
+
+impl SequencePacker {
+    /// Create a packer of blocks of `block_size` tokens, padded with `pad_token`.
+    pub fn new(block_size: usize, pad_token: usize) -> Self {
+        assert!(block_size > 0, "The block size must be positive");
+
+        Self {
+            block_size,
+            pad_token,
+            separator: None,
+            drop_last: false,
+        }
+    }
+
+    /// Append a separator token (e.g. an end of sequence token) to each sequence.
+    pub fn with_separator(mut self, separator: usize) -> Self {
+        self.separator = Some(separator);
+        self
+    }
+
+    /// Drop the last block instead of padding it.
+    pub fn with_drop_last(mut self, drop_last: bool) -> Self {
+        self.drop_last = drop_last;
+        self
+    }
+
+    /// Pack the sequences lazily.
+    pub fn pack<I>(&self, sequences: I) -> PackedSequences<I::IntoIter>
+    where
+        I: IntoIterator<Item = Vec<usize>>,
+    {
+        PackedSequences {
+            packer: self.clone(),
+            sequences: sequences.into_iter(),
+            buffer: VecDeque::new(),
+            num_sequences: 0,
+        }
+    }
+}
+
+/// Iterator over the blocks of a [sequence packer](SequencePacker).
+pub struct PackedSequences<I> {
+    packer: SequencePacker,
+    sequences: I,
+    /// The pending tokens, with the global index of their sequence.
+    buffer: VecDeque<(usize, usize)>,
+    num_sequences: usize,
+}
+
+impl<I: Iterator<Item = Vec<usize>>> Iterator for PackedSequences<I> {
+    type Item = PackedSequence;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let block_size = self.packer.block_size;
+        while self.buffer.len() < block_size {
+            let Some(sequence) = self.sequences.next() else {
+                break;
+            };
+            let id = self.num_sequences;
+            self.num_sequences += 1;
+            self.buffer.extend(
+                sequence
+                    .into_iter()
+                    .chain(self.packer.separator)
+                    .map(|token| (token, id)),
+            );
+        }
+
+        if self.buffer.is_empty() || (self.buffer.len() < block_size && self.packer.drop_last) {
+            return None;
+        }
+
+        let num_tokens = block_size.min(self.buffer.len());
+        let mut block = PackedSequence {
+            tokens: Vec::with_capacity(block_size),
+            attention_mask: Vec::with_capacity(block_size),
+            sequence_ids: Vec::with_capacity(block_size),
+        };
+
+        let first_id = self.buffer[0].1;
+        for (token, id) in self.buffer.drain(..num_tokens) {
+            block.tokens.push(token);
+            block.attention_mask.push(true);
+            block.sequence_ids.push(id - first_id);
+        }
+
+        let padding_id = block.sequence_ids.last().map_or(0, |id| id + 1);
+        block.tokens.resize(block_size, self.packer.pad_token);
+        block.attention_mask.resize(block_size, false);
+        block.sequence_ids.resize(block_size, padding_id);
+
+        Some(block)
+    }
+}
+
+/// Dataset of the [packed sequences](PackedSequence) of the texts of a dataset.
+///
+/// The texts are tokenized and packed when the dataset is created, and the blocks are stored
+/// in memory.
+pub struct PackedDataset {
+    dataset: InMemDataset<PackedSequence>,
+}
+
+impl PackedDataset {
+    /// Tokenize the texts of a dataset, with the special tokens, and pack them.
+    pub fn new<D, T>(dataset: &D, tokenizer: &T, packer: &SequencePacker) -> Self
+    where
+        D: Dataset<String>,
+        T: Tokenizer + ?Sized,
+    {
+        let sequences = dataset.iter().map(|text| tokenizer.encode(&text, true));
+        let blocks = packer.pack(sequences).collect();
+
+        Self {
+            dataset: InMemDataset::new(blocks),
+        }
+    }
+}
+
+impl Dataset<PackedSequence> for PackedDataset {
+    fn get(&self, index: usize) -> Option<PackedSequence> {
+        self.dataset.get(index)
+    }
+
+    fn len(&self) -> usize {
+        self.dataset.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequences_are_packed_into_blocks() {
+        let packer = SequencePacker::new(4, 0).with_separator(9);
+        let blocks = packer
+            .pack(vec![vec![1, 2], vec![3, 4, 5, 6], vec![7]])
+            .collect::<Vec<_>>();
+
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].tokens, vec![1, 2, 9, 3]);
+        assert_eq!(blocks[0].sequence_ids, vec![0, 0, 0, 1]);
+        assert_eq!(blocks[1].tokens, vec![4, 5, 6, 9]);
+        assert_eq!(blocks[1].sequence_ids, vec![0, 0, 0, 0]);
+        assert_eq!(blocks[2].tokens, vec![7, 9, 0, 0]);
+        assert_eq!(blocks[2].attention_mask, vec![true, true, false, false]);
+        assert_eq!(blocks[2].sequence_ids, vec![0, 0, 1, 1]);
+
+        let packer = packer.with_drop_last(true);
+        assert_eq!(
+            packer
+                .pack(vec![vec![1, 2], vec![3, 4, 5, 6], vec![7]])
+                .count(),
+            2
+        );
+    }
+}
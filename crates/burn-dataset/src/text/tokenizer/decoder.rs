@@ -0,0 +1,200 @@
+use super::normalizer::{Pattern, enabled};
+use super::pre_tokenizer::{PrependScheme, chars_to_bytes, default_replacement};
+use serde::Deserialize;
+
+/// A decoder, converting the tokens back into a text.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub(crate) enum Decoder {
+    ByteLevel,
+    WordPiece {
+        #[serde(default = "default_prefix")]
+        prefix: String,
+        #[serde(default = "enabled")]
+        cleanup: bool,
+    },
+    Metaspace {
+        #[serde(default = "default_replacement")]
+        replacement: char,
+        #[serde(default)]
+        prepend_scheme: Option<PrependScheme>,
+        #[serde(default)]
+        add_prefix_space: Option<bool>,
+    },
+    #[serde(rename = "BPEDecoder")]
+    Bpe {
+        #[serde(default = "default_suffix")]
+        suffix: String,
+    },
+    ByteFallback,
+    Fuse,
+    Strip {
+        content: char,
+        start: usize,
+        stop: usize,
+    },
+    Replace {
+        pattern: Pattern,
+        content: String,
+    },
+    Sequence {
+        decoders: Vec<Decoder>,
+    },
+}
+
+fn default_prefix() -> String {
+    "##".to_string()
+}
+
+fn default_suffix() -> String {
+    "</w>".to_string()
+}
+
+impl Decoder {
+    /// Decode the tokens, returning the pieces of text to concatenate.
+    pub(crate) fn decode(&self, tokens: Vec<String>) -> Vec<String> {
+        match self {
+            Decoder::ByteLevel => {
+                let table = chars_to_bytes();
+                let mut bytes = Vec::new();
+                for c in tokens.concat().chars() {
+                    match table.get(&c) {
+                        Some(byte) => bytes.push(*byte),
+                        None => bytes.extend(c.to_string().as_bytes()),
+                    }
+                }
+                vec![String::from_utf8_lossy(&bytes).into_owned()]
+            }
+            Decoder::WordPiece { prefix, cleanup } => tokens
+                .into_iter()
+                .enumerate()
+                .map(|(index, token)| {
+                    let token = match (index, token.strip_prefix(prefix.as_str())) {
+                        (0, _) => token,
+                        (_, Some(piece)) => piece.to_string(),
+                        (_, None) => format!(" {token}"),
+                    };
+                    match cleanup {
+                        true => clean_up_tokenization(&token),
+                        false => token,
+                    }
+                })
+                .collect(),
+            Decoder::Metaspace {
+                replacement,
+                prepend_scheme,
+                add_prefix_space,
+            } => {
+                let scheme = PrependScheme::resolve(*prepend_scheme, *add_prefix_space);
+                tokens
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, token)| {
+                        let token = token.replace(*replacement, " ");
+                        match (index, scheme) {
+                            (0, PrependScheme::Always | PrependScheme::First) => {
+                                token.strip_prefix(' ').unwrap_or(&token).to_string()
+                            }
+                            _ => token,
+                        }
+                    })
+                    .collect()
+            }
+            Decoder::Bpe { suffix } => {
+                let last = tokens.len().saturating_sub(1);
+                tokens
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, token)| {
+                        token.replace(suffix.as_str(), if index == last { "" } else { " " })
+                    })
+                    .collect()
+            }
+            Decoder::ByteFallback => {
+                let mut pieces = Vec::new();
+                let mut bytes = Vec::new();
+                for token in tokens {
+                    match parse_byte_token(&token) {
+                        Some(byte) => bytes.push(byte),
+                        None => {
+                            flush_bytes(&mut bytes, &mut pieces);
+                            pieces.push(token);
+                        }
+                    }
+                }
+                flush_bytes(&mut bytes, &mut pieces);
+                pieces
+            }
+            Decoder::Fuse => vec![tokens.concat()],
+            Decoder::Strip {
+                content,
+                start,
+                stop,
+            } => tokens
+                .into_iter()
+                .map(|token| {
+                    let chars = token.chars().collect::<Vec<_>>();
+                    let begin = chars
+                        .iter()
+                        .take(*start)
+                        .take_while(|c| *c == content)
+                        .count();
+                    let end = chars.len()
+                        - chars[begin..]
+                            .iter()
+                            .rev()
+                            .take(*stop)
+                            .take_while(|c| *c == content)
+                            .count();
+                    chars[begin..end].iter().collect()
+                })
+                .collect(),
+            Decoder::Replace {
+                pattern: Pattern::String(pattern),
+                content,
+            } => tokens
+                .into_iter()
+                .map(|token| token.replace(pattern.as_str(), content))
+                .collect(),
+            Decoder::Sequence { decoders } => decoders
+                .iter()
+                .fold(tokens, |tokens, decoder| decoder.decode(tokens)),
+        }
+    }
+}
+
+/// The byte of a `<0xXX>` token.
+fn parse_byte_token(token: &str) -> Option<u8> {
+    let hex = token.strip_prefix("<0x")?.strip_suffix('>')?;
+    match hex.len() {
+        2 => u8::from_str_radix(hex, 16).ok(),
+        _ => None,
+    }
+}
+
+/// Decode the pending bytes, with a replacement character per byte when they aren't valid UTF-8.
+fn flush_bytes(bytes: &mut Vec<u8>, pieces: &mut Vec<String>) {
+    if bytes.is_empty() {
+        return;
+    }
+
+    match String::from_utf8(std::mem::take(bytes)) {
+        Ok(text) => pieces.push(text),
+        Err(err) => pieces.extend(err.as_bytes().iter().map(|_| "\u{fffd}".to_string())),
+    }
+}
+
+/// Remove the spaces before the punctuation and in the English contractions.
+fn clean_up_tokenization(text: &str) -> String {
+    text.replace(" .", ".")
+        .replace(" ?", "?")
+        .replace(" !", "!")
+        .replace(" ,", ",")
+        .replace(" ' ", "'")
+        .replace(" n't", "n't")
+        .replace(" 'm", "'m")
+        .replace(" do not", " don't")
+        .replace(" 's", "'s")
+        .replace(" 've", "'ve")
+        .replace(" 're", "'re")
+}
@@ -0,0 +1,327 @@
+use super::decoder::Decoder;
+use super::model::{Model, ModelConfig};
+use super::normalizer::Normalizer;
+use super::post_processor::PostProcessor;
+use super::pre_tokenizer::PreTokenizer;
+use super::{Tokenizer, TokenizerError};
+use serde::Deserialize;
+use std::path::Path;
+
+/// The padding tokens looked up when the tokenizer has no padding configuration.
+const PAD_TOKENS: [&str; 3] = ["[PAD]", "<pad>", "<|padding|>"];
+
+#[derive(Deserialize)]
+struct TokenizerConfig {
+    #[serde(default)]
+    added_tokens: Vec<AddedToken>,
+    normalizer: Option<Normalizer>,
+    pre_tokenizer: Option<PreTokenizer>,
+    post_processor: Option<PostProcessor>,
+    decoder: Option<Decoder>,
+    model: ModelConfig,
+    padding: Option<PaddingConfig>,
+}
+
+#[derive(Deserialize)]
+struct AddedToken {
+    id: usize,
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct PaddingConfig {
+    pad_id: usize,
+}
+
+/// A piece of text, either a raw text or an added token.
+enum Segment<'a> {
+    Text(&'a str),
+    Added(usize),
+}
+
+/// A tokenizer loaded from a Hugging Face `tokenizer.json` file.
+///
+/// The BPE, WordPiece, Unigram and WordLevel models are supported, with the normalizers,
+/// pre-tokenizers, post-processors and decoders of the usual pretrained tokenizers (BERT, GPT-2,
+/// RoBERTa, Llama, T5...). The regular expression patterns aren't supported.
+#[derive(Debug, Clone)]
+pub struct HuggingFaceTokenizer {
+    model: Model,
+    normalizer: Option<Normalizer>,
+    pre_tokenizer: Option<PreTokenizer>,
+    post_processor: Option<PostProcessor>,
+    decoder: Option<Decoder>,
+    /// The added tokens, from the longest to the shortest.
+    added_tokens: Vec<(String, usize)>,
+    pad_token: Option<usize>,
+}
+
+impl HuggingFaceTokenizer {
+    /// Load a tokenizer from a `tokenizer.json` file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, TokenizerError> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).map_err(|err| {
+            TokenizerError::IOError(format!("Failed to read {}: {err}", path.display()))
+        })?;
+
+        Self::from_json(&json)
+    }
+
+    /// Load a tokenizer from the content of a `tokenizer.json` file.
+    pub fn from_json(json: &str) -> Result<Self, TokenizerError> {
+        let config: TokenizerConfig = serde_json::from_str(json)
+            .map_err(|err| TokenizerError::ParsingError(err.to_string()))?;
+
+        if let Some(post_processor) = &config.post_processor {
+            post_processor.validate()?;
+        }
+
+        let mut added_tokens = config
+            .added_tokens
+            .into_iter()
+            .map(|token| (token.content, token.id))
+            .collect::<Vec<_>>();
+        added_tokens.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()).then(a.cmp(b)));
+
+        let pad_token = config.padding.map(|padding| padding.pad_id).or_else(|| {
+            PAD_TOKENS.iter().find_map(|pad| {
+                added_tokens
+                    .iter()
+                    .find(|(content, _)| content == pad)
+                    .map(|(_, id)| *id)
+            })
+        });
+
+        Ok(Self {
+            model: Model::new(config.model)?,
+            normalizer: config.normalizer,
+            pre_tokenizer: config.pre_tokenizer,
+            post_processor: config.post_processor,
+            decoder: config.decoder,
+            added_tokens,
+            pad_token,
+        })
+    }
+
+    /// The padding token, from the padding configuration of the tokenizer or its added tokens.
+    pub fn pad_token(&self) -> Option<usize> {
+        self.pad_token
+    }
+
+    /// The token of an ID, if it is part of the vocabulary.
+    pub fn id_to_token(&self, id: usize) -> Option<&str> {
+        self.added_tokens
+            .iter()
+            .find(|(_, added)| *added == id)
+            .map(|(content, _)| content.as_str())
+            .or_else(|| self.model.id_to_token(id))
+    }
+
+    /// Split a text around its added tokens, which bypass the normalization and the model.
+    fn split_added_tokens<'a>(&self, text: &'a str) -> Vec<Segment<'a>> {
+        let mut segments = Vec::new();
+        let mut start = 0;
+        let mut position = 0;
+        while let Some(c) = text[position..].chars().next() {
+            let added = self
+                .added_tokens
+                .iter()
+                .find(|(content, _)| !content.is_empty() && text[position..].starts_with(content));
+
+            match added {
+                Some((content, id)) => {
+                    if start < position {
+                        segments.push(Segment::Text(&text[start..position]));
+                    }
+                    segments.push(Segment::Added(*id));
+                    position += content.len();
+                    start = position;
+                }
+                None => position += c.len_utf8(),
+            }
+        }
+        if start < text.len() {
+            segments.push(Segment::Text(&text[start..]));
+        }
+
+        segments
+    }
+}
+
+impl Tokenizer for HuggingFaceTokenizer {
+    fn encode(&self, text: &str, special_tokens: bool) -> Vec<usize> {
+        let mut tokens = Vec::new();
+        for (index, segment) in self.split_added_tokens(text).into_iter().enumerate() {
+            let text = match segment {
+                Segment::Added(id) => {
+                    tokens.push(id);
+                    continue;
+                }
+                Segment::Text(text) => text,
+            };
+
+            let normalized = match &self.normalizer {
+                Some(normalizer) => normalizer.normalize(text),
+                None => text.to_string(),
+            };
+            let words = match &self.pre_tokenizer {
+                Some(pre_tokenizer) => pre_tokenizer.pre_tokenize(&normalized, index == 0),
+                None => vec![normalized],
+            };
+
+            for word in words.iter().filter(|word| !word.is_empty()) {
+                tokens.extend(self.model.tokenize(word));
+            }
+        }
+
+        match (&self.post_processor, special_tokens) {
+            (Some(post_processor), true) => post_processor.process(tokens),
+            _ => tokens,
+        }
+    }
+
+    fn decode(&self, tokens: &[usize]) -> String {
+        let tokens = tokens
+            .iter()
+            .filter_map(|id| self.id_to_token(*id))
+            .map(|token| token.to_string())
+            .collect::<Vec<_>>();
+
+        match &self.decoder {
+            Some(decoder) => decoder.decode(tokens).concat(),
+            None => tokens.join(" "),
+        }
+    }
+
+    fn vocab_size(&self) -> usize {
+        let num_added = self
+            .added_tokens
+            .iter()
+            .filter(|(_, id)| self.model.id_to_token(*id).is_none())
+            .count();
+
+        self.model.vocab_size() + num_added
+    }
+
+    fn token_to_id(&self, token: &str) -> Option<usize> {
+        self.added_tokens
+            .iter()
+            .find(|(content, _)| content == token)
+            .map(|(_, id)| *id)
+            .or_else(|| self.model.token_to_id(token))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BERT: &str = r###"{
+        "added_tokens": [
+            {"id": 0, "content": "[PAD]", "special": true},
+            {"id": 1, "content": "[UNK]", "special": true},
+            {"id": 2, "content": "[CLS]", "special": true},
+            {"id": 3, "content": "[SEP]", "special": true}
+        ],
+        "normalizer": {"type": "BertNormalizer", "lowercase": true},
+        "pre_tokenizer": {"type": "BertPreTokenizer"},
+        "post_processor": {
+            "type": "TemplateProcessing",
+            "single": [
+                {"SpecialToken": {"id": "[CLS]", "type_id": 0}},
+                {"Sequence": {"id": "A", "type_id": 0}},
+                {"SpecialToken": {"id": "[SEP]", "type_id": 0}}
+            ],
+            "pair": [],
+            "special_tokens": {
+                "[CLS]": {"id": "[CLS]", "ids": [2], "tokens": ["[CLS]"]},
+                "[SEP]": {"id": "[SEP]", "ids": [3], "tokens": ["[SEP]"]}
+            }
+        },
+        "decoder": {"type": "WordPiece", "prefix": "##", "cleanup": true},
+        "model": {
+            "type": "WordPiece",
+            "unk_token": "[UNK]",
+            "continuing_subword_prefix": "##",
+            "max_input_chars_per_word": 100,
+            "vocab": {
+                "[PAD]": 0, "[UNK]": 1, "[CLS]": 2, "[SEP]": 3,
+                "hello": 4, "world": 5, "play": 6, "##ing": 7, "!": 8
+            }
+        }
+    }"###;
+
+    const GPT2: &str = r#"{
+        "added_tokens": [{"id": 9, "content": "<|endoftext|>", "special": true}],
+        "pre_tokenizer": {"type": "ByteLevel", "add_prefix_space": false, "trim_offsets": true},
+        "post_processor": {"type": "ByteLevel", "trim_offsets": false},
+        "decoder": {"type": "ByteLevel"},
+        "model": {
+            "type": "BPE",
+            "vocab": {"h": 0, "i": 1, "Ġ": 2, "y": 3, "o": 4, "u": 5, "hi": 6, "Ġy": 7, "Ġyou": 8, "<|endoftext|>": 9, "ou": 10},
+            "merges": ["h i", "Ġ y", "o u", "Ġy ou"]
+        }
+    }"#;
+
+    const T5: &str = r#"{
+        "added_tokens": [{"id": 0, "content": "<pad>", "special": true}],
+        "normalizer": null,
+        "pre_tokenizer": {"type": "Metaspace", "replacement": "▁", "prepend_scheme": "always", "split": true},
+        "post_processor": {
+            "type": "TemplateProcessing",
+            "single": [{"Sequence": {"id": "A", "type_id": 0}}, {"SpecialToken": {"id": "</s>", "type_id": 0}}],
+            "special_tokens": {"</s>": {"id": "</s>", "ids": [1], "tokens": ["</s>"]}}
+        },
+        "decoder": {"type": "Metaspace", "replacement": "▁", "prepend_scheme": "always", "split": true},
+        "model": {
+            "type": "Unigram",
+            "unk_id": 2,
+            "vocab": [["<pad>", 0.0], ["</s>", 0.0], ["<unk>", 0.0], ["▁the", -1.0], ["▁", -2.0], ["cat", -2.5], ["▁cat", -2.0], ["s", -3.0]]
+        }
+    }"#;
+
+    #[test]
+    fn test_wordpiece_tokenizer() {
+        let tokenizer = HuggingFaceTokenizer::from_json(BERT).unwrap();
+
+        let tokens = tokenizer.encode("Hello, PLAYING world!", true);
+        assert_eq!(tokens, vec![2, 4, 1, 6, 7, 5, 8, 3]);
+        assert_eq!(tokenizer.encode("hello [SEP]", false), vec![4, 3]);
+        assert_eq!(tokenizer.decode(&[4, 6, 7, 5, 8]), "hello playing world!");
+        assert_eq!(tokenizer.pad_token(), Some(0));
+        assert_eq!(tokenizer.vocab_size(), 9);
+    }
+
+    #[test]
+    fn test_byte_level_bpe_tokenizer() {
+        let tokenizer = HuggingFaceTokenizer::from_json(GPT2).unwrap();
+
+        let tokens = tokenizer.encode("hi you<|endoftext|>", true);
+        assert_eq!(tokens, vec![6, 8, 9]);
+        assert_eq!(tokenizer.decode(&tokens), "hi you<|endoftext|>");
+        assert_eq!(tokenizer.token_to_id("Ġy"), Some(7));
+        assert_eq!(tokenizer.pad_token(), None);
+    }
+
+    #[test]
+    fn test_unigram_tokenizer() {
+        let tokenizer = HuggingFaceTokenizer::from_json(T5).unwrap();
+
+        let tokens = tokenizer.encode("the cats", true);
+        assert_eq!(tokens, vec![3, 6, 7, 1]);
+        assert_eq!(tokenizer.decode(&tokens[..3]), "the cats");
+        assert_eq!(tokenizer.pad_token(), Some(0));
+    }
+
+    #[test]
+    fn test_invalid_tokenizer() {
+        assert!(matches!(
+            HuggingFaceTokenizer::from_json(r#"{"model": {"type": "Unknown"}}"#),
+            Err(TokenizerError::ParsingError(_))
+        ));
+        assert!(matches!(
+            HuggingFaceTokenizer::from_file("missing/tokenizer.json"),
+            Err(TokenizerError::IOError(_))
+        ));
+    }
+}
@@ -0,0 +1,40 @@
+mod decoder;
+mod huggingface;
+mod model;
+mod normalizer;
+mod post_processor;
+mod pre_tokenizer;
+
+pub use huggingface::*;
+
+use thiserror::Error;
+
+/// A tokenizer, converting texts to sequences of token IDs and back.
+///
+/// The `Send + Sync` bounds allow the tokenizers to be shared by the data loader workers.
+pub trait Tokenizer: Send + Sync {
+    /// Convert a text into a sequence of token IDs, adding the special tokens of the tokenizer
+    /// (e.g. `[CLS]` and `[SEP]`) when `special_tokens` is true.
+    fn encode(&self, text: &str, special_tokens: bool) -> Vec<usize>;
+
+    /// Convert a sequence of token IDs back into a text.
+    fn decode(&self, tokens: &[usize]) -> String;
+
+    /// The size of the vocabulary, including the added tokens.
+    fn vocab_size(&self) -> usize;
+
+    /// The ID of a token, if it is part of the vocabulary.
+    fn token_to_id(&self, token: &str) -> Option<usize>;
+}
+
+/// Error type for [tokenizers](Tokenizer).
+#[derive(Error, Debug)]
+pub enum TokenizerError {
+    /// IO error.
+    #[error("I/O error: `{0}`")]
+    IOError(String),
+
+    /// Parsing error.
+    #[error("Parsing error: `{0}`")]
+    ParsingError(String),
+}
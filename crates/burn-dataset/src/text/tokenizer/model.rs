@@ -0,0 +1,567 @@
+use super::TokenizerError;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// The configuration of a tokenizer model, in the Hugging Face `tokenizer.json` format.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub(crate) enum ModelConfig {
+    #[serde(rename = "BPE")]
+    Bpe(BpeConfig),
+    WordPiece(WordPieceConfig),
+    Unigram(UnigramConfig),
+    WordLevel(WordLevelConfig),
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct BpeConfig {
+    vocab: HashMap<String, usize>,
+    merges: Vec<Merge>,
+    #[serde(default)]
+    unk_token: Option<String>,
+    #[serde(default)]
+    continuing_subword_prefix: Option<String>,
+    #[serde(default)]
+    end_of_word_suffix: Option<String>,
+    #[serde(default)]
+    fuse_unk: bool,
+    #[serde(default)]
+    byte_fallback: bool,
+    #[serde(default)]
+    ignore_merges: bool,
+}
+
+/// A merge, either as a `"left right"` string or as a pair.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+enum Merge {
+    Joined(String),
+    Pair(String, String),
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct WordPieceConfig {
+    vocab: HashMap<String, usize>,
+    #[serde(default = "WordPieceConfig::default_unk_token")]
+    unk_token: String,
+    #[serde(default = "WordPieceConfig::default_prefix")]
+    continuing_subword_prefix: String,
+    #[serde(default = "WordPieceConfig::default_max_input_chars_per_word")]
+    max_input_chars_per_word: usize,
+}
+
+impl WordPieceConfig {
+    fn default_unk_token() -> String {
+        "[UNK]".to_string()
+    }
+
+    fn default_prefix() -> String {
+        "##".to_string()
+    }
+
+    fn default_max_input_chars_per_word() -> usize {
+        100
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct UnigramConfig {
+    vocab: Vec<(String, f64)>,
+    #[serde(default)]
+    unk_id: Option<usize>,
+    #[serde(default)]
+    byte_fallback: bool,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct WordLevelConfig {
+    vocab: HashMap<String, usize>,
+    #[serde(default)]
+    unk_token: Option<String>,
+}
+
+/// The vocabulary of a model, mapping the tokens to their IDs and back.
+#[derive(Debug, Clone, Default)]
+struct Vocab {
+    ids: HashMap<String, usize>,
+    tokens: HashMap<usize, String>,
+}
+
+impl Vocab {
+    fn new(ids: HashMap<String, usize>) -> Self {
+        let tokens = ids.iter().map(|(token, id)| (*id, token.clone())).collect();
+        Self { ids, tokens }
+    }
+
+    fn id(&self, token: &str) -> Option<usize> {
+        self.ids.get(token).copied()
+    }
+
+    /// The ID of a token, failing when the token is missing from the vocabulary.
+    fn require(&self, token: &str) -> Result<usize, TokenizerError> {
+        self.id(token).ok_or_else(|| {
+            TokenizerError::ParsingError(format!("Token `{token}` missing from the vocabulary"))
+        })
+    }
+
+    /// The IDs of the `<0xXX>` tokens of the bytes of a character, when they all exist.
+    fn byte_fallback(&self, text: &str) -> Option<Vec<usize>> {
+        text.bytes()
+            .map(|byte| self.id(&format!("<0x{byte:02X}>")))
+            .collect()
+    }
+}
+
+/// The model of a tokenizer, splitting the pre-tokenized words into tokens.
+#[derive(Debug, Clone)]
+pub(crate) enum Model {
+    Bpe(Bpe),
+    WordPiece(WordPiece),
+    Unigram(Unigram),
+    WordLevel(WordLevel),
+}
+
+impl Model {
+    pub(crate) fn new(config: ModelConfig) -> Result<Self, TokenizerError> {
+        Ok(match config {
+            ModelConfig::Bpe(config) => Model::Bpe(Bpe::new(config)?),
+            ModelConfig::WordPiece(config) => Model::WordPiece(WordPiece::new(config)?),
+            ModelConfig::Unigram(config) => Model::Unigram(Unigram::new(config)?),
+            ModelConfig::WordLevel(config) => Model::WordLevel(WordLevel::new(config)?),
+        })
+    }
+
+    pub(crate) fn tokenize(&self, word: &str) -> Vec<usize> {
+        match self {
+            Model::Bpe(model) => model.tokenize(word),
+            Model::WordPiece(model) => model.tokenize(word),
+            Model::Unigram(model) => model.tokenize(word),
+            Model::WordLevel(model) => model.tokenize(word),
+        }
+    }
+
+    fn vocab(&self) -> &Vocab {
+        match self {
+            Model::Bpe(model) => &model.vocab,
+            Model::WordPiece(model) => &model.vocab,
+            Model::Unigram(model) => &model.vocab,
+            Model::WordLevel(model) => &model.vocab,
+        }
+    }
+
+    pub(crate) fn token_to_id(&self, token: &str) -> Option<usize> {
+        self.vocab().id(token)
+    }
+
+    pub(crate) fn id_to_token(&self, id: usize) -> Option<&str> {
+        self.vocab().tokens.get(&id).map(String::as_str)
+    }
+
+    pub(crate) fn vocab_size(&self) -> usize {
+        self.vocab().ids.len()
+    }
+}
+
+/// Byte-pair encoding: merge the characters of a word with the merges of the highest priority.
+#[derive(Debug, Clone)]
+pub(crate) struct Bpe {
+    vocab: Vocab,
+    /// The rank and the merged token of each pair of tokens.
+    merges: HashMap<(usize, usize), (usize, usize)>,
+    unk: Option<usize>,
+    prefix: Option<String>,
+    suffix: Option<String>,
+    fuse_unk: bool,
+    byte_fallback: bool,
+    ignore_merges: bool,
+}
+
+impl Bpe {
+    fn new(config: BpeConfig) -> Result<Self, TokenizerError> {
+        let vocab = Vocab::new(config.vocab);
+        let prefix = config.continuing_subword_prefix;
+
+        let mut merges = HashMap::new();
+        for (rank, merge) in config.merges.into_iter().enumerate() {
+            let (left, right) = match merge {
+                Merge::Joined(merge) => {
+                    let (left, right) = merge.split_once(' ').ok_or_else(|| {
+                        TokenizerError::ParsingError(format!("Invalid merge `{merge}`"))
+                    })?;
+                    (left.to_string(), right.to_string())
+                }
+                Merge::Pair(left, right) => (left, right),
+            };
+
+            let right_part = prefix
+                .as_deref()
+                .and_then(|prefix| right.strip_prefix(prefix))
+                .unwrap_or(&right);
+            let merged = vocab.require(&format!("{left}{right_part}"))?;
+            merges.insert(
+                (vocab.require(&left)?, vocab.require(&right)?),
+                (rank, merged),
+            );
+        }
+
+        let unk = config
+            .unk_token
+            .map(|token| vocab.require(&token))
+            .transpose()?;
+
+        Ok(Self {
+            vocab,
+            merges,
+            unk,
+            prefix,
+            suffix: config.end_of_word_suffix,
+            fuse_unk: config.fuse_unk,
+            byte_fallback: config.byte_fallback,
+            ignore_merges: config.ignore_merges,
+        })
+    }
+
+    fn tokenize(&self, word: &str) -> Vec<usize> {
+        if let Some(id) = self.vocab.id(word).filter(|_| self.ignore_merges) {
+            return vec![id];
+        }
+
+        let num_chars = word.chars().count();
+        let mut symbols = Vec::with_capacity(num_chars);
+        let mut previous_unk = false;
+        for (index, (start, c)) in word.char_indices().enumerate() {
+            let mut token = String::new();
+            if index > 0 {
+                token.extend(self.prefix.as_deref());
+            }
+            token.push_str(&word[start..start + c.len_utf8()]);
+            if index == num_chars - 1 {
+                token.extend(self.suffix.as_deref());
+            }
+
+            if let Some(id) = self.vocab.id(&token) {
+                symbols.push(id);
+                previous_unk = false;
+                continue;
+            }
+
+            let bytes = match self.byte_fallback {
+                true => self.vocab.byte_fallback(&c.to_string()),
+                false => None,
+            };
+            match (bytes, self.unk) {
+                (Some(bytes), _) => {
+                    symbols.extend(bytes);
+                    previous_unk = false;
+                }
+                (None, Some(unk)) => {
+                    if !(self.fuse_unk && previous_unk) {
+                        symbols.push(unk);
+                    }
+                    previous_unk = true;
+                }
+                // The characters without a token are dropped.
+                (None, None) => {}
+            }
+        }
+
+        // Apply the merge of the highest priority, the leftmost one in case of a tie.
+        loop {
+            let best = symbols
+                .windows(2)
+                .enumerate()
+                .filter_map(|(index, pair)| {
+                    self.merges
+                        .get(&(pair[0], pair[1]))
+                        .map(|(rank, merged)| (*rank, index, *merged))
+                })
+                .min();
+
+            match best {
+                Some((_, index, merged)) => {
+                    symbols[index] = merged;
+                    symbols.remove(index + 1);
+                }
+                None => break symbols,
+            }
+        }
+    }
+}
+
+/// WordPiece: split a word into the longest tokens of the vocabulary, from left to right.
+#[derive(Debug, Clone)]
+pub(crate) struct WordPiece {
+    vocab: Vocab,
+    unk: usize,
+    prefix: String,
+    max_input_chars_per_word: usize,
+}
+
+impl WordPiece {
+    fn new(config: WordPieceConfig) -> Result<Self, TokenizerError> {
+        let vocab = Vocab::new(config.vocab);
+
+        Ok(Self {
+            unk: vocab.require(&config.unk_token)?,
+            vocab,
+            prefix: config.continuing_subword_prefix,
+            max_input_chars_per_word: config.max_input_chars_per_word,
+        })
+    }
+
+    fn tokenize(&self, word: &str) -> Vec<usize> {
+        if word.chars().count() > self.max_input_chars_per_word {
+            return vec![self.unk];
+        }
+
+        let mut tokens = Vec::new();
+        let mut start = 0;
+        while start < word.len() {
+            let mut ends = word[start..]
+                .char_indices()
+                .map(|(index, c)| start + index + c.len_utf8())
+                .collect::<Vec<_>>();
+            ends.reverse();
+
+            let token = ends.into_iter().find_map(|end| {
+                let piece = &word[start..end];
+                let id = match start {
+                    0 => self.vocab.id(piece),
+                    _ => self.vocab.id(&format!("{}{piece}", self.prefix)),
+                };
+                id.map(|id| (id, end))
+            });
+
+            match token {
+                Some((id, end)) => {
+                    tokens.push(id);
+                    start = end;
+                }
+                // A word with a part missing from the vocabulary is unknown.
+                None => return vec![self.unk],
+            }
+        }
+
+        tokens
+    }
+}
+
+/// Unigram: split a word into the tokens maximizing the sum of their log probabilities.
+#[derive(Debug, Clone)]
+pub(crate) struct Unigram {
+    vocab: Vocab,
+    scores: HashMap<usize, f64>,
+    unk: Option<usize>,
+    unk_score: f64,
+    max_piece_chars: usize,
+    byte_fallback: bool,
+}
+
+/// A node of the best tokenization of the characters of a word up to a position.
+#[derive(Clone, Copy)]
+struct Lattice {
+    score: f64,
+    start: usize,
+    /// The token ending at the position, `None` for an unknown character.
+    token: Option<usize>,
+}
+
+impl Unigram {
+    /// The penalty of the unknown characters, relative to the lowest score of the vocabulary.
+    const UNK_PENALTY: f64 = 10.0;
+
+    fn new(config: UnigramConfig) -> Result<Self, TokenizerError> {
+        let mut ids = HashMap::new();
+        let mut scores = HashMap::new();
+        for (id, (piece, score)) in config.vocab.into_iter().enumerate() {
+            ids.insert(piece, id);
+            scores.insert(id, score);
+        }
+
+        let vocab = Vocab::new(ids);
+        if let Some(unk) = config.unk_id.filter(|unk| !vocab.tokens.contains_key(unk)) {
+            return Err(TokenizerError::ParsingError(format!(
+                "Unknown token ID {unk} missing from the vocabulary"
+            )));
+        }
+
+        let min_score = scores.values().copied().fold(f64::INFINITY, f64::min);
+        let max_piece_chars = vocab
+            .ids
+            .keys()
+            .map(|piece| piece.chars().count())
+            .max()
+            .unwrap_or(1);
+
+        Ok(Self {
+            vocab,
+            scores,
+            unk: config.unk_id,
+            unk_score: min_score.min(0.0) - Self::UNK_PENALTY,
+            max_piece_chars,
+            byte_fallback: config.byte_fallback,
+        })
+    }
+
+    fn tokenize(&self, word: &str) -> Vec<usize> {
+        let bounds = word
+            .char_indices()
+            .map(|(index, _)| index)
+            .chain([word.len()])
+            .collect::<Vec<_>>();
+        let num_chars = bounds.len() - 1;
+
+        // Viterbi: the best tokenization of the first characters of the word.
+        let mut best: Vec<Option<Lattice>> = vec![None; num_chars + 1];
+        best[0] = Some(Lattice {
+            score: 0.0,
+            start: 0,
+            token: None,
+        });
+
+        for end in 1..=num_chars {
+            for start in end.saturating_sub(self.max_piece_chars)..end {
+                let Some(previous) = best[start] else {
+                    continue;
+                };
+                let piece = &word[bounds[start]..bounds[end]];
+                let candidate = match self.vocab.id(piece) {
+                    Some(id) => Lattice {
+                        score: previous.score + self.scores[&id],
+                        start,
+                        token: Some(id),
+                    },
+                    None if end - start == 1 => Lattice {
+                        score: previous.score + self.unk_score,
+                        start,
+                        token: None,
+                    },
+                    None => continue,
+                };
+
+                if best[end].is_none_or(|node| candidate.score > node.score) {
+                    best[end] = Some(candidate);
+                }
+            }
+        }
+
+        let mut pieces = Vec::new();
+        let mut end = num_chars;
+        while end > 0 {
+            let node = best[end].expect("Every character has a token");
+            pieces.push((node.token, &word[bounds[node.start]..bounds[end]]));
+            end = node.start;
+        }
+        pieces.reverse();
+
+        let mut tokens = Vec::new();
+        let mut previous_unk = false;
+        for (token, piece) in pieces {
+            if let Some(id) = token {
+                tokens.push(id);
+                previous_unk = false;
+                continue;
+            }
+
+            let bytes = match self.byte_fallback {
+                true => self.vocab.byte_fallback(piece),
+                false => None,
+            };
+            match (bytes, self.unk) {
+                (Some(bytes), _) => {
+                    tokens.extend(bytes);
+                    previous_unk = false;
+                }
+                // The consecutive unknown characters are fused.
+                (None, Some(unk)) => {
+                    if !previous_unk {
+                        tokens.push(unk);
+                    }
+                    previous_unk = true;
+                }
+                (None, None) => {}
+            }
+        }
+
+        tokens
+    }
+}
+
+/// WordLevel: map each word to a token.
+#[derive(Debug, Clone)]
+pub(crate) struct WordLevel {
+    vocab: Vocab,
+    unk: Option<usize>,
+}
+
+impl WordLevel {
+    fn new(config: WordLevelConfig) -> Result<Self, TokenizerError> {
+        let vocab = Vocab::new(config.vocab);
+        let unk = config
+            .unk_token
+            .map(|token| vocab.require(&token))
+            .transpose()?;
+
+        Ok(Self { vocab, unk })
+    }
+
+    fn tokenize(&self, word: &str) -> Vec<usize> {
+        self.vocab.id(word).or(self.unk).into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(json: &str) -> Model {
+        Model::new(serde_json::from_str(json).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_bpe_applies_merges_by_rank() {
+        let bpe = model(
+            r#"{
+                "type": "BPE",
+                "vocab": {"<unk>": 0, "l": 1, "o": 2, "w": 3, "lo": 4, "ow": 5, "low": 6},
+                "merges": ["o w", "l o", ["l", "ow"]],
+                "unk_token": "<unk>",
+                "fuse_unk": true
+            }"#,
+        );
+
+        // "o w" has the highest priority, so "lo" is never merged.
+        assert_eq!(bpe.tokenize("low"), vec![6]);
+        assert_eq!(bpe.tokenize("lo"), vec![4]);
+        assert_eq!(bpe.tokenize("xxlo"), vec![0, 4]);
+    }
+
+    #[test]
+    fn test_wordpiece_longest_match_first() {
+        let wordpiece = model(
+            r###"{
+                "type": "WordPiece",
+                "vocab": {"[UNK]": 0, "un": 1, "##aff": 2, "##able": 3, "unaff": 4, "##a": 5}
+            }"###,
+        );
+
+        assert_eq!(wordpiece.tokenize("unaffable"), vec![4, 3]);
+        assert_eq!(wordpiece.tokenize("unaffablex"), vec![0]);
+    }
+
+    #[test]
+    fn test_unigram_maximizes_the_score() {
+        let unigram = model(
+            r#"{
+                "type": "Unigram",
+                "unk_id": 0,
+                "vocab": [["<unk>", 0.0], ["a", -1.0], ["b", -1.0], ["ab", -3.0], ["abc", -1.5], ["c", -5.0]]
+            }"#,
+        );
+
+        // "a" + "b" (-2.0) is more likely than "ab" (-3.0).
+        assert_eq!(unigram.tokenize("ab"), vec![1, 2]);
+        assert_eq!(unigram.tokenize("abc"), vec![4]);
+        assert_eq!(unigram.tokenize("xyb"), vec![0, 2]);
+    }
+}
@@ -0,0 +1,166 @@
+use serde::Deserialize;
+use unicode_normalization::{UnicodeNormalization, char::is_combining_mark};
+
+/// A normalizer, cleaning up a text before it is split into words.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub(crate) enum Normalizer {
+    #[serde(rename = "BertNormalizer")]
+    Bert {
+        #[serde(default = "enabled")]
+        clean_text: bool,
+        #[serde(default = "enabled")]
+        handle_chinese_chars: bool,
+        #[serde(default)]
+        strip_accents: Option<bool>,
+        #[serde(default = "enabled")]
+        lowercase: bool,
+    },
+    Sequence {
+        normalizers: Vec<Normalizer>,
+    },
+    Lowercase,
+    StripAccents,
+    #[serde(rename = "NFC")]
+    Nfc,
+    #[serde(rename = "NFD")]
+    Nfd,
+    #[serde(rename = "NFKC")]
+    Nfkc,
+    #[serde(rename = "NFKD")]
+    Nfkd,
+    Strip {
+        #[serde(default)]
+        strip_left: bool,
+        #[serde(default)]
+        strip_right: bool,
+    },
+    Replace {
+        pattern: Pattern,
+        content: String,
+    },
+    Prepend {
+        prepend: String,
+    },
+}
+
+/// The pattern of a replacement. Only the literal patterns are supported.
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) enum Pattern {
+    String(String),
+}
+
+pub(crate) fn enabled() -> bool {
+    true
+}
+
+impl Normalizer {
+    pub(crate) fn normalize(&self, text: &str) -> String {
+        match self {
+            Normalizer::Bert {
+                clean_text,
+                handle_chinese_chars,
+                strip_accents,
+                lowercase,
+            } => {
+                let mut text = text.to_string();
+                if *clean_text {
+                    text = text
+                        .chars()
+                        .filter(|c| *c != '\0' && *c != '\u{fffd}' && !is_control(*c))
+                        .map(|c| if c.is_whitespace() { ' ' } else { c })
+                        .collect();
+                }
+                if *handle_chinese_chars {
+                    text = text
+                        .chars()
+                        .flat_map(|c| match is_chinese_char(c) {
+                            true => vec![' ', c, ' '],
+                            false => vec![c],
+                        })
+                        .collect();
+                }
+                // The accents are stripped with the lowercasing, unless specified otherwise.
+                if strip_accents.unwrap_or(*lowercase) {
+                    text = remove_accents(&text);
+                }
+                if *lowercase {
+                    text = text.to_lowercase();
+                }
+                text
+            }
+            Normalizer::Sequence { normalizers } => normalizers
+                .iter()
+                .fold(text.to_string(), |text, normalizer| {
+                    normalizer.normalize(&text)
+                }),
+            Normalizer::Lowercase => text.to_lowercase(),
+            Normalizer::StripAccents => text.chars().filter(|c| !is_combining_mark(*c)).collect(),
+            Normalizer::Nfc => text.nfc().collect(),
+            Normalizer::Nfd => text.nfd().collect(),
+            Normalizer::Nfkc => text.nfkc().collect(),
+            Normalizer::Nfkd => text.nfkd().collect(),
+            Normalizer::Strip {
+                strip_left,
+                strip_right,
+            } => {
+                let mut text = text;
+                if *strip_left {
+                    text = text.trim_start();
+                }
+                if *strip_right {
+                    text = text.trim_end();
+                }
+                text.to_string()
+            }
+            Normalizer::Replace {
+                pattern: Pattern::String(pattern),
+                content,
+            } => text.replace(pattern, content),
+            Normalizer::Prepend { prepend } => match text.is_empty() {
+                true => String::new(),
+                false => format!("{prepend}{text}"),
+            },
+        }
+    }
+}
+
+/// Whether a character is a control character, other than the whitespaces.
+fn is_control(c: char) -> bool {
+    !matches!(c, '\t' | '\n' | '\r') && c.is_control()
+}
+
+/// Whether a character is a CJK ideograph.
+fn is_chinese_char(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x4E00..=0x9FFF
+            | 0x3400..=0x4DBF
+            | 0x20000..=0x2A6DF
+            | 0x2A700..=0x2B73F
+            | 0x2B740..=0x2B81F
+            | 0x2B820..=0x2CEAF
+            | 0xF900..=0xFAFF
+            | 0x2F800..=0x2FA1F
+    )
+}
+
+fn remove_accents(text: &str) -> String {
+    text.nfd().filter(|c| !is_combining_mark(*c)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bert_normalizer() {
+        let normalizer: Normalizer =
+            serde_json::from_str(r#"{"type": "BertNormalizer", "lowercase": true}"#).unwrap();
+
+        assert_eq!(
+            normalizer.normalize("Héllo\tWörld\u{0}中"),
+            "hello world 中 "
+        );
+    }
+}
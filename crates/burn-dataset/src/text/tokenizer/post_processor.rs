@@ -0,0 +1,82 @@
+use super::TokenizerError;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// A post-processor, adding the special tokens around an encoded text.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub(crate) enum PostProcessor {
+    TemplateProcessing {
+        single: Vec<TemplatePiece>,
+        #[serde(default)]
+        special_tokens: HashMap<String, SpecialToken>,
+    },
+    BertProcessing {
+        sep: (String, usize),
+        cls: (String, usize),
+    },
+    RobertaProcessing {
+        sep: (String, usize),
+        cls: (String, usize),
+    },
+    ByteLevel,
+    Sequence {
+        processors: Vec<PostProcessor>,
+    },
+}
+
+/// A piece of a template, either a special token or the encoded text.
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) enum TemplatePiece {
+    SpecialToken { id: String },
+    Sequence {},
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct SpecialToken {
+    ids: Vec<usize>,
+}
+
+impl PostProcessor {
+    /// Check that the special tokens of the templates are defined.
+    pub(crate) fn validate(&self) -> Result<(), TokenizerError> {
+        match self {
+            PostProcessor::TemplateProcessing {
+                single,
+                special_tokens,
+            } => single.iter().try_for_each(|piece| match piece {
+                TemplatePiece::SpecialToken { id } if !special_tokens.contains_key(id) => Err(
+                    TokenizerError::ParsingError(format!("Undefined special token `{id}`")),
+                ),
+                _ => Ok(()),
+            }),
+            PostProcessor::Sequence { processors } => {
+                processors.iter().try_for_each(PostProcessor::validate)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn process(&self, tokens: Vec<usize>) -> Vec<usize> {
+        match self {
+            PostProcessor::TemplateProcessing {
+                single,
+                special_tokens,
+            } => single
+                .iter()
+                .flat_map(|piece| match piece {
+                    TemplatePiece::SpecialToken { id } => special_tokens[id].ids.clone(),
+                    TemplatePiece::Sequence { .. } => tokens.clone(),
+                })
+                .collect(),
+            PostProcessor::BertProcessing { sep, cls }
+            | PostProcessor::RobertaProcessing { sep, cls } => {
+                [cls.1].into_iter().chain(tokens).chain([sep.1]).collect()
+            }
+            PostProcessor::ByteLevel => tokens,
+            PostProcessor::Sequence { processors } => processors
+                .iter()
+                .fold(tokens, |tokens, processor| processor.process(tokens)),
+        }
+    }
+}
@@ -0,0 +1,334 @@
+use super::normalizer::enabled;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// A pre-tokenizer, splitting a normalized text into the words given to the model.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub(crate) enum PreTokenizer {
+    #[serde(rename = "BertPreTokenizer")]
+    Bert,
+    Whitespace,
+    WhitespaceSplit,
+    ByteLevel {
+        #[serde(default = "enabled")]
+        add_prefix_space: bool,
+        #[serde(default = "enabled")]
+        use_regex: bool,
+    },
+    Metaspace {
+        #[serde(default = "default_replacement")]
+        replacement: char,
+        #[serde(default)]
+        prepend_scheme: Option<PrependScheme>,
+        #[serde(default)]
+        add_prefix_space: Option<bool>,
+        #[serde(default = "enabled")]
+        split: bool,
+    },
+    Punctuation,
+    Digits {
+        #[serde(default)]
+        individual_digits: bool,
+    },
+    Sequence {
+        pretokenizers: Vec<PreTokenizer>,
+    },
+}
+
+/// When the metaspace replacement is prepended to a text.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum PrependScheme {
+    First,
+    Never,
+    Always,
+}
+
+impl PrependScheme {
+    /// The prepend scheme, given the legacy `add_prefix_space` option as a fallback.
+    pub(crate) fn resolve(scheme: Option<Self>, add_prefix_space: Option<bool>) -> Self {
+        match (scheme, add_prefix_space) {
+            (Some(scheme), _) => scheme,
+            (None, Some(false)) => PrependScheme::Never,
+            (None, _) => PrependScheme::Always,
+        }
+    }
+}
+
+pub(crate) fn default_replacement() -> char {
+    '▁'
+}
+
+impl PreTokenizer {
+    /// Split a text into words. The `first` flag tells whether the text starts the input, as
+    /// opposed to following an added token.
+    pub(crate) fn pre_tokenize(&self, text: &str, first: bool) -> Vec<String> {
+        match self {
+            PreTokenizer::Bert => text
+                .split_whitespace()
+                .flat_map(split_punctuation)
+                .collect(),
+            PreTokenizer::Whitespace => split_runs(text, |c| {
+                if c.is_whitespace() {
+                    None
+                } else if c.is_alphanumeric() || c == '_' {
+                    Some(0)
+                } else {
+                    Some(1)
+                }
+            }),
+            PreTokenizer::WhitespaceSplit => text
+                .split_whitespace()
+                .map(|word| word.to_string())
+                .collect(),
+            PreTokenizer::ByteLevel {
+                add_prefix_space,
+                use_regex,
+            } => {
+                let text = match *add_prefix_space && !text.starts_with(' ') {
+                    true => format!(" {text}"),
+                    false => text.to_string(),
+                };
+                let words = match use_regex {
+                    true => split_gpt2(&text),
+                    false => vec![text.as_str()],
+                };
+                let table = bytes_to_chars();
+
+                words
+                    .into_iter()
+                    .map(|word| word.bytes().map(|byte| table[byte as usize]).collect())
+                    .collect()
+            }
+            PreTokenizer::Metaspace {
+                replacement,
+                prepend_scheme,
+                add_prefix_space,
+                split,
+            } => {
+                let mut text = text.replace(' ', &replacement.to_string());
+                let prepend = match PrependScheme::resolve(*prepend_scheme, *add_prefix_space) {
+                    PrependScheme::Always => true,
+                    PrependScheme::First => first,
+                    PrependScheme::Never => false,
+                };
+                if prepend && !text.starts_with(*replacement) {
+                    text.insert(0, *replacement);
+                }
+                if !split {
+                    return vec![text];
+                }
+
+                // Each replacement starts a new word.
+                let mut words = Vec::new();
+                let mut word = String::new();
+                for c in text.chars() {
+                    if c == *replacement && !word.is_empty() {
+                        words.push(std::mem::take(&mut word));
+                    }
+                    word.push(c);
+                }
+                words.extend((!word.is_empty()).then_some(word));
+                words
+            }
+            PreTokenizer::Punctuation => split_punctuation(text),
+            PreTokenizer::Digits { individual_digits } => {
+                let mut index = 0;
+                split_runs(text, |c| match c.is_ascii_digit() {
+                    true if *individual_digits => {
+                        index += 1;
+                        Some(index)
+                    }
+                    true => Some(0),
+                    false => Some(usize::MAX),
+                })
+            }
+            PreTokenizer::Sequence { pretokenizers } => {
+                pretokenizers
+                    .iter()
+                    .fold(vec![text.to_string()], |words, pre_tokenizer| {
+                        words
+                            .iter()
+                            .enumerate()
+                            .flat_map(|(index, word)| {
+                                pre_tokenizer.pre_tokenize(word, first && index == 0)
+                            })
+                            .collect()
+                    })
+            }
+        }
+    }
+}
+
+/// Split a text into the runs of characters of the same class, dropping the characters without
+/// a class.
+fn split_runs<F: FnMut(char) -> Option<usize>>(text: &str, mut class: F) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut previous = None;
+    for c in text.chars() {
+        let current = class(c);
+        if current != previous && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        if current.is_some() {
+            word.push(c);
+        }
+        previous = current;
+    }
+    words.extend((!word.is_empty()).then_some(word));
+    words
+}
+
+/// Split a text around its punctuation characters, each of them being a word.
+fn split_punctuation(text: &str) -> Vec<String> {
+    let mut index = 0;
+    split_runs(text, |c| match is_punctuation(c) {
+        true => {
+            index += 1;
+            Some(index)
+        }
+        false => Some(0),
+    })
+}
+
+/// Whether a character is a punctuation character, approximating the Unicode punctuation
+/// categories with their most common blocks.
+fn is_punctuation(c: char) -> bool {
+    c.is_ascii_punctuation()
+        || matches!(
+            c,
+            '¡' | '§'
+                | '«'
+                | '¶'
+                | '·'
+                | '»'
+                | '¿'
+                | '\u{2010}'..='\u{2027}'
+                | '\u{2030}'..='\u{205E}'
+                | '\u{3001}'..='\u{3003}'
+                | '\u{3008}'..='\u{3011}'
+                | '\u{3014}'..='\u{301F}'
+                | '\u{FF01}'..='\u{FF0F}'
+                | '\u{FF1A}'..='\u{FF20}'
+        )
+}
+
+/// Split a text as the GPT-2 pattern
+/// `'s|'t|'re|'ve|'m|'ll|'d| ?\p{L}+| ?\p{N}+| ?[^\s\p{L}\p{N}]+|\s+(?!\S)|\s+`.
+fn split_gpt2(text: &str) -> Vec<&str> {
+    const CONTRACTIONS: [&str; 7] = ["'s", "'t", "'re", "'ve", "'m", "'ll", "'d"];
+
+    #[derive(PartialEq, Clone, Copy)]
+    enum Class {
+        Space,
+        Letter,
+        Number,
+        Other,
+    }
+    let class = |c: char| {
+        if c.is_whitespace() {
+            Class::Space
+        } else if c.is_alphabetic() {
+            Class::Letter
+        } else if c.is_numeric() {
+            Class::Number
+        } else {
+            Class::Other
+        }
+    };
+
+    let chars = text.char_indices().collect::<Vec<_>>();
+    let offset = |index: usize| chars.get(index).map_or(text.len(), |(offset, _)| *offset);
+    let run = |start: usize, target: Class| {
+        (start..chars.len())
+            .find(|index| class(chars[*index].1) != target)
+            .unwrap_or(chars.len())
+    };
+
+    let mut words = Vec::new();
+    let mut start = 0;
+    while start < chars.len() {
+        let c = chars[start].1;
+        let next = chars.get(start + 1).map(|(_, c)| class(*c));
+
+        let end = if let Some(contraction) = CONTRACTIONS
+            .iter()
+            .find(|contraction| text[offset(start)..].starts_with(**contraction))
+        {
+            start + contraction.len()
+        } else if c == ' ' && next.is_some_and(|next| next != Class::Space) {
+            run(start + 1, next.unwrap())
+        } else if class(c) == Class::Space {
+            // The last whitespace before a word is left to the word.
+            let end = run(start, Class::Space);
+            match end < chars.len() && end - start > 1 {
+                true => end - 1,
+                false => end,
+            }
+        } else {
+            run(start, class(c))
+        };
+
+        words.push(&text[offset(start)..offset(end)]);
+        start = end;
+    }
+
+    words
+}
+
+/// The GPT-2 mapping of the bytes to printable characters.
+pub(crate) fn bytes_to_chars() -> &'static [char; 256] {
+    static TABLE: OnceLock<[char; 256]> = OnceLock::new();
+
+    TABLE.get_or_init(|| {
+        let printable = |byte: u8| matches!(byte, b'!'..=b'~' | 0xA1..=0xAC | 0xAE..=0xFF);
+        let mut table = ['\0'; 256];
+        let mut shifted = 0;
+        for byte in 0..=255u8 {
+            table[byte as usize] = match printable(byte) {
+                true => char::from(byte),
+                false => {
+                    shifted += 1;
+                    char::from_u32(255 + shifted).unwrap()
+                }
+            };
+        }
+        table
+    })
+}
+
+/// The inverse of the [GPT-2 mapping](bytes_to_chars).
+pub(crate) fn chars_to_bytes() -> &'static HashMap<char, u8> {
+    static TABLE: OnceLock<HashMap<char, u8>> = OnceLock::new();
+
+    TABLE.get_or_init(|| {
+        bytes_to_chars()
+            .iter()
+            .enumerate()
+            .map(|(byte, c)| (*c, byte as u8))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gpt2_split() {
+        assert_eq!(
+            split_gpt2("Hello world's  42!!\n x"),
+            vec!["Hello", " world", "'s", " ", " 42", "!!", "\n", " x"]
+        );
+    }
+
+    #[test]
+    fn test_bert_pre_tokenizer() {
+        let words = PreTokenizer::Bert.pre_tokenize("Hey, you...  there!", true);
+
+        assert_eq!(words, vec!["Hey", ",", "you", ".", ".", ".", "there", "!"]);
+    }
+}
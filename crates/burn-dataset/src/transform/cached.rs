@@ -0,0 +1,600 @@
+use super::mmap::{Mmap, lock, unlock};
+use crate::Dataset;
+use sanitize_filename::sanitize;
+use serde::{Serialize, de::DeserializeOwned};
+use std::{
+    fs::{self, File, OpenOptions},
+    hash::{Hash, Hasher},
+    io::{self, Write},
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::RwLock,
+    time::UNIX_EPOCH,
+};
+
+const DATA_FILE: &str = "items.bin";
+const INDEX_FILE: &str = "index.bin";
+/// The file locked by the processes using a cache, shared while the cache is mapped.
+const LOCK_FILE: &str = "cache.lock";
+/// The size of an index record: the item index, the offset and the length of its bytes.
+const RECORD_SIZE: usize = 24;
+
+/// Result type of the cached dataset.
+pub type CacheResult<T> = core::result::Result<T, CacheError>;
+
+/// Cached dataset error.
+#[derive(thiserror::Error, Debug)]
+pub enum CacheError {
+    /// IO related error.
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+
+    /// Serde related error.
+    #[error("Serde error: {0}")]
+    Serde(String),
+}
+
+/// The key identifying the content of a [cached dataset](CachedDataset): a name and a hash of
+/// the upstream dataset and of the configuration of its transformations.
+///
+/// When the key of a cache changes, e.g. because the source files or the configuration of a
+/// [mapper](crate::transform::Mapper) changed, the cached items are computed again and the
+/// stale items of the same name are removed.
+#[derive(Debug, Clone)]
+pub struct CacheKey {
+    name: String,
+    hasher: Fnv64,
+}
+
+impl CacheKey {
+    /// Creates a new cache key with the name of the cached dataset, e.g. `mnist-train`.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: sanitize(name),
+            hasher: Fnv64::default(),
+        }
+    }
+
+    /// Adds a configuration to the key, e.g. the configuration of a mapper, hashed from its
+    /// MessagePack serialization.
+    pub fn with_config<C: Serialize>(mut self, config: &C) -> Self {
+        let bytes = rmp_serde::to_vec(config).expect("The configuration should be serializable");
+        self.hasher.write(&bytes);
+        self
+    }
+
+    /// Adds a value to the key, e.g. a version number.
+    pub fn with_value<T: Hash>(mut self, value: &T) -> Self {
+        value.hash(&mut self.hasher);
+        self
+    }
+
+    /// Adds a source file or directory to the key, from the paths, sizes and modification times
+    /// of its files, so that the key changes when the source changes.
+    pub fn with_source<P: AsRef<Path>>(mut self, path: P) -> io::Result<Self> {
+        let mut files = Vec::new();
+        collect_files(path.as_ref(), &mut files)?;
+        files.sort();
+
+        for file in files {
+            let metadata = fs::metadata(&file)?;
+            let modified = metadata
+                .modified()?
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+
+            file.hash(&mut self.hasher);
+            metadata.len().hash(&mut self.hasher);
+            modified.as_nanos().hash(&mut self.hasher);
+        }
+
+        Ok(self)
+    }
+
+    /// The name of the cached dataset.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The hash of the key.
+    pub fn hash(&self) -> u64 {
+        self.hasher.finish()
+    }
+
+    fn dir_name(&self) -> String {
+        format!("{}-{:016x}", self.name, self.hash())
+    }
+
+    /// Whether a directory holds a cache with the same name as this key.
+    fn is_same_name(&self, dir_name: &str) -> bool {
+        dir_name
+            .strip_prefix(&self.name)
+            .and_then(|suffix| suffix.strip_prefix('-'))
+            .is_some_and(|hash| {
+                hash.len() == 16 && hash.chars().all(|char| char.is_ascii_hexdigit())
+            })
+    }
+}
+
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(path)? {
+        collect_files(&entry?.path(), files)?;
+    }
+    Ok(())
+}
+
+/// The 64-bit FNV-1a hash, which unlike the default hasher of the standard library is stable
+/// across Rust versions.
+#[derive(Debug, Clone)]
+struct Fnv64(u64);
+
+impl Default for Fnv64 {
+    fn default() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for Fnv64 {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+/// The position of the bytes of an item in the data file.
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    offset: usize,
+    len: usize,
+}
+
+struct CacheState {
+    entries: Vec<Option<Entry>>,
+    data: File,
+    index: File,
+    mmap: Mmap,
+    // Holds a shared lock, so the cache isn't removed by another process while it is mapped.
+    _lock: File,
+}
+
+/// Caches the items of a dataset on disk, e.g. the items of an expensive
+/// [mapper dataset](crate::transform::MapperDataset), so they are only computed once.
+///
+/// The items are serialized with MessagePack and appended to a file, which is memory-mapped to
+/// read them. The cache is filled lazily when the items are first accessed, or ahead of time
+/// with [precompute](Self::precompute), and is reused by the next runs with the same
+/// [cache key](CacheKey).
+///
+/// A cache can be shared by several processes, e.g. the processes of a distributed training:
+/// the items are appended under an advisory file lock, and a stale cache is only removed when no
+/// other process is using it.
+pub struct CachedDataset<D, I> {
+    dataset: D,
+    dir: PathBuf,
+    state: RwLock<CacheState>,
+    input: PhantomData<I>,
+}
+
+impl<D, I> CachedDataset<D, I>
+where
+    D: Dataset<I>,
+    I: Serialize + DeserializeOwned + Send + Sync + Clone,
+{
+    /// Creates a new cached dataset, stored in `~/.cache/burn-dataset/cached`.
+    pub fn new(dataset: D, key: CacheKey) -> CacheResult<Self> {
+        let home_dir = dirs::home_dir().expect("Could not get home directory");
+        let base_dir = home_dir.join(".cache").join("burn-dataset").join("cached");
+
+        Self::with_base_dir(dataset, key, base_dir)
+    }
+
+    /// Creates a new cached dataset, stored in the given base directory.
+    ///
+    /// The caches of the same name with a different key are removed from the base directory,
+    /// unless they are used by another process.
+    pub fn with_base_dir<P: AsRef<Path>>(
+        dataset: D,
+        key: CacheKey,
+        base_dir: P,
+    ) -> CacheResult<Self> {
+        let base_dir = base_dir.as_ref();
+        let key = key.with_value(&dataset.len());
+        fs::create_dir_all(base_dir)?;
+
+        // Remove the stale caches of the dataset.
+        let dir_name = key.dir_name();
+        for entry in fs::read_dir(base_dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name != dir_name && key.is_same_name(&name) {
+                remove_stale(&entry.path())?;
+            }
+        }
+
+        let dir = base_dir.join(dir_name);
+        let state = CacheState::open(&dir, dataset.len())?;
+
+        Ok(Self {
+            dataset,
+            dir,
+            state: RwLock::new(state),
+            input: PhantomData,
+        })
+    }
+
+    /// The directory of the cache.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The number of items stored in the cache.
+    pub fn num_cached(&self) -> usize {
+        let state = self.state.read().unwrap();
+        state.entries.iter().filter(|entry| entry.is_some()).count()
+    }
+
+    /// Computes and stores the items missing from the cache, with the given number of threads.
+    pub fn precompute(&self, num_threads: usize) -> CacheResult<()> {
+        let missing = {
+            let state = self.state.read().unwrap();
+            (0..state.entries.len())
+                .filter(|index| state.entries[*index].is_none())
+                .collect::<Vec<_>>()
+        };
+
+        let num_threads = num_threads.max(1);
+        std::thread::scope(|scope| {
+            let handles = (0..num_threads)
+                .map(|thread| {
+                    let missing = &missing;
+                    scope.spawn(move || -> CacheResult<()> {
+                        for index in missing.iter().skip(thread).step_by(num_threads) {
+                            if let Some(item) = self.dataset.get(*index) {
+                                self.store(*index, &item)?;
+                            }
+                        }
+                        Ok(())
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            handles
+                .into_iter()
+                .try_for_each(|handle| handle.join().expect("The thread should not panic"))
+        })?;
+
+        self.state.write().unwrap().remap(&self.dir)
+    }
+
+    /// Loads an item from the cache.
+    fn load(&self, index: usize) -> CacheResult<Option<I>> {
+        {
+            let state = self.state.read().unwrap();
+            match state.entries[index] {
+                None => return Ok(None),
+                Some(entry) if entry.offset + entry.len <= state.mmap.len() => {
+                    return decode(&state.mmap, entry).map(Some);
+                }
+                // The item was stored after the file was mapped.
+                Some(_) => {}
+            }
+        }
+
+        let mut state = self.state.write().unwrap();
+        let entry = state.entries[index].expect("Cached items aren't removed");
+        if entry.offset + entry.len > state.mmap.len() {
+            state.remap(&self.dir)?;
+        }
+        decode(&state.mmap, entry).map(Some)
+    }
+
+    /// Stores an item in the cache.
+    fn store(&self, index: usize, item: &I) -> CacheResult<()> {
+        let bytes = rmp_serde::to_vec(item).map_err(|err| CacheError::Serde(err.to_string()))?;
+
+        let mut state = self.state.write().unwrap();
+        if state.entries[index].is_some() {
+            return Ok(());
+        }
+        state.append(index, &bytes)
+    }
+}
+
+/// Removes a stale cache if no other process holds its lock: removing the files mapped by another
+/// process would make its reads fail.
+fn remove_stale(dir: &Path) -> CacheResult<()> {
+    let file = match OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(LOCK_FILE))
+    {
+        Ok(file) => file,
+        // Removed by another process.
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err.into()),
+    };
+
+    if !lock(&file, true, false)? {
+        return Ok(());
+    }
+    match fs::remove_dir_all(dir) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Opens the lock file of a cache and takes a shared lock on it, creating the cache directory
+/// again if it was removed by another process in the meantime.
+fn open_lock(dir: &Path) -> CacheResult<File> {
+    loop {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(LOCK_FILE);
+        let file = match OpenOptions::new().create(true).append(true).open(&path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err.into()),
+        };
+        lock(&file, false, true)?;
+
+        // The lock file is removed with the directory after being locked exclusively.
+        if is_same_file(&file, &path)? {
+            return Ok(file);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_same_file(file: &File, path: &Path) -> io::Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = file.metadata()?;
+    match fs::metadata(path) {
+        Ok(other) => Ok(metadata.dev() == other.dev() && metadata.ino() == other.ino()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(not(unix))]
+fn is_same_file(_file: &File, path: &Path) -> io::Result<bool> {
+    Ok(path.exists())
+}
+
+fn decode<I: DeserializeOwned>(mmap: &Mmap, entry: Entry) -> CacheResult<I> {
+    rmp_serde::from_slice(&mmap[entry.offset..entry.offset + entry.len])
+        .map_err(|err| CacheError::Serde(err.to_string()))
+}
+
+impl CacheState {
+    /// Opens the files of a cache, loading the entries of the items already stored.
+    fn open(dir: &Path, len: usize) -> CacheResult<Self> {
+        let lock_file = open_lock(dir)?;
+        let open = |name: &str| {
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(dir.join(name))
+        };
+        let data = open(DATA_FILE)?;
+        let index = open(INDEX_FILE)?;
+
+        // The records are written after the bytes of their items, so an interrupted write leaves
+        // at most an incomplete record at the end of the index, which is ignored.
+        lock(&data, false, true)?;
+        let records = fs::read(dir.join(INDEX_FILE));
+        let data_len = data.metadata().map(|metadata| metadata.len() as usize);
+        unlock(&data)?;
+        let (records, data_len) = (records?, data_len?);
+
+        let mut entries = vec![None; len];
+        for record in records.chunks_exact(RECORD_SIZE) {
+            let field = |index: usize| {
+                let bytes = record[index * 8..(index + 1) * 8].try_into().unwrap();
+                u64::from_le_bytes(bytes) as usize
+            };
+            let (item, entry) = (
+                field(0),
+                Entry {
+                    offset: field(1),
+                    len: field(2),
+                },
+            );
+            if item < len && entry.offset + entry.len <= data_len {
+                entries[item] = Some(entry);
+            }
+        }
+
+        let mut state = Self {
+            entries,
+            data,
+            index,
+            mmap: Mmap::empty(),
+            _lock: lock_file,
+        };
+        state.remap(dir)?;
+        Ok(state)
+    }
+
+    fn remap(&mut self, dir: &Path) -> CacheResult<()> {
+        self.mmap = Mmap::map(&dir.join(DATA_FILE))?;
+        Ok(())
+    }
+
+    /// Appends the bytes of an item, under an exclusive lock since other processes can append
+    /// to the same files.
+    fn append(&mut self, index: usize, bytes: &[u8]) -> CacheResult<()> {
+        lock(&self.data, true, true)?;
+        let entry = self.write(index, bytes);
+        unlock(&self.data)?;
+
+        self.entries[index] = Some(entry?);
+        Ok(())
+    }
+
+    fn write(&mut self, index: usize, bytes: &[u8]) -> io::Result<Entry> {
+        // The other processes may have appended items since this one last wrote.
+        let entry = Entry {
+            offset: self.data.metadata()?.len() as usize,
+            len: bytes.len(),
+        };
+        self.data.write_all(bytes)?;
+
+        let mut record = [0; RECORD_SIZE];
+        for (field, value) in [index, entry.offset, entry.len].into_iter().enumerate() {
+            record[field * 8..(field + 1) * 8].copy_from_slice(&(value as u64).to_le_bytes());
+        }
+        self.index.write_all(&record)?;
+
+        Ok(entry)
+    }
+}
+
+impl<D, I> Dataset<I> for CachedDataset<D, I>
+where
+    D: Dataset<I>,
+    I: Serialize + DeserializeOwned + Send + Sync + Clone,
+{
+    fn get(&self, index: usize) -> Option<I> {
+        if index >= self.len() {
+            return None;
+        }
+        if let Some(item) = self.load(index).expect("Could not read the cached item") {
+            return Some(item);
+        }
+
+        let item = self.dataset.get(index)?;
+        self.store(index, &item)
+            .expect("Could not write the item to the cache");
+        Some(item)
+    }
+
+    fn len(&self) -> usize {
+        self.dataset.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        InMemDataset,
+        transform::{Mapper, MapperDataset},
+    };
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    /// A mapper counting its calls.
+    #[derive(Clone)]
+    struct Square(Arc<AtomicUsize>);
+
+    impl Mapper<u32, Vec<u32>> for Square {
+        fn map(&self, item: &u32) -> Vec<u32> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            vec![*item; *item as usize]
+        }
+    }
+
+    fn cached(
+        base_dir: &Path,
+        key: CacheKey,
+        calls: &Arc<AtomicUsize>,
+    ) -> CachedDataset<MapperDataset<InMemDataset<u32>, Square, u32>, Vec<u32>> {
+        let dataset =
+            MapperDataset::new(InMemDataset::new(vec![1, 2, 3, 4]), Square(calls.clone()));
+        CachedDataset::with_base_dir(dataset, key, base_dir).unwrap()
+    }
+
+    #[test]
+    fn test_cached_items_are_computed_once() {
+        let base_dir = tempfile::tempdir().unwrap();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let key = CacheKey::new("squares").with_config(&("square", 1));
+
+        let dataset = cached(base_dir.path(), key.clone(), &calls);
+        assert_eq!(dataset.get(2), Some(vec![3, 3, 3]));
+        assert_eq!(dataset.get(2), Some(vec![3, 3, 3]));
+        assert_eq!(
+            (dataset.num_cached(), calls.load(Ordering::Relaxed)),
+            (1, 1)
+        );
+
+        // The cache is reused by a new dataset with the same key.
+        drop(dataset);
+        let dataset = cached(base_dir.path(), key.clone(), &calls);
+        dataset.precompute(2).unwrap();
+        assert_eq!(calls.load(Ordering::Relaxed), 4);
+        assert_eq!(
+            dataset.iter().collect::<Vec<_>>(),
+            vec![vec![1], vec![2, 2], vec![3, 3, 3], vec![4, 4, 4, 4]]
+        );
+        assert_eq!(calls.load(Ordering::Relaxed), 4);
+
+        // A different key invalidates the cache.
+        let stale_dir = dataset.dir().to_path_buf();
+        drop(dataset);
+        let dataset = cached(base_dir.path(), key.with_value(&2), &calls);
+        assert_eq!(dataset.num_cached(), 0);
+        assert!(!stale_dir.exists());
+    }
+
+    #[test]
+    fn test_caches_are_shared_by_their_users() {
+        let base_dir = tempfile::tempdir().unwrap();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let key = CacheKey::new("squares");
+
+        // Two users of the same cache append to its files in turn.
+        let first = cached(base_dir.path(), key.clone(), &calls);
+        let second = cached(base_dir.path(), key.clone(), &calls);
+        assert_eq!(first.get(0), Some(vec![1]));
+        assert_eq!(second.get(1), Some(vec![2, 2]));
+        assert_eq!(first.get(2), Some(vec![3, 3, 3]));
+        assert_eq!(second.get(3), Some(vec![4, 4, 4, 4]));
+        drop(second);
+
+        let reopened = cached(base_dir.path(), key.clone(), &calls);
+        assert_eq!(reopened.num_cached(), 4);
+        assert_eq!(
+            reopened.iter().collect::<Vec<_>>(),
+            vec![vec![1], vec![2, 2], vec![3, 3, 3], vec![4, 4, 4, 4]]
+        );
+        drop(reopened);
+
+        // A stale cache still in use isn't removed.
+        let other = cached(base_dir.path(), key.clone().with_value(&2), &calls);
+        assert!(first.dir().exists());
+        assert_eq!(first.get(3), Some(vec![4, 4, 4, 4]));
+
+        drop((first, other));
+        let dataset = cached(base_dir.path(), key.with_value(&3), &calls);
+        assert_eq!(fs::read_dir(base_dir.path()).unwrap().count(), 1);
+        assert_eq!(dataset.num_cached(), 0);
+    }
+
+    #[test]
+    fn test_cache_key_tracks_the_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("source.txt");
+        fs::write(&path, "1").unwrap();
+
+        let key = |path: &Path| CacheKey::new("source").with_source(path).unwrap().hash();
+        let before = key(dir.path());
+        assert_eq!(before, key(dir.path()));
+
+        fs::write(&path, "12").unwrap();
+        assert_ne!(before, key(dir.path()));
+    }
+}
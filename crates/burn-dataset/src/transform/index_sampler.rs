@@ -0,0 +1,377 @@
+use crate::Dataset;
+use rand::{
+    Rng,
+    distr::{Distribution, weighted::WeightedIndex},
+    rngs::StdRng,
+    seq::SliceRandom,
+};
+use std::{collections::HashMap, marker::PhantomData};
+
+/// Decides which items of a dataset are loaded during an epoch, and in which order.
+///
+/// Unlike the [sampler dataset](crate::transform::SamplerDataset), which draws each item
+/// independently, an index sampler draws the indices of a whole epoch at once, so it can control
+/// the content of consecutive items, e.g. the items of the same batch.
+pub trait IndexSampler: Send + Sync {
+    /// The number of indices of an epoch for a dataset of the given length.
+    fn num_items(&self, len: usize) -> usize {
+        len
+    }
+
+    /// The indices of the items of an epoch, in the order they are loaded, for a dataset of the
+    /// given length.
+    fn indices(&self, len: usize, rng: &mut StdRng) -> Vec<usize>;
+}
+
+/// The items of a dataset selected by their indices, see [IndexSampler].
+pub struct SampledDataset<D, I> {
+    dataset: D,
+    indices: Vec<usize>,
+    input: PhantomData<I>,
+}
+
+impl<D, I> SampledDataset<D, I>
+where
+    D: Dataset<I>,
+{
+    /// Creates a new sampled dataset, where the item `i` is the item `indices[i]` of the dataset.
+    pub fn new(dataset: D, indices: Vec<usize>) -> Self {
+        Self {
+            dataset,
+            indices,
+            input: PhantomData,
+        }
+    }
+
+    /// Creates a new sampled dataset with the indices of an epoch drawn by the sampler.
+    pub fn from_sampler<S: IndexSampler + ?Sized>(
+        dataset: D,
+        sampler: &S,
+        rng: &mut StdRng,
+    ) -> Self {
+        let indices = sampler.indices(dataset.len(), rng);
+        Self::new(dataset, indices)
+    }
+}
+
+impl<D, I> Dataset<I> for SampledDataset<D, I>
+where
+    D: Dataset<I>,
+    I: Clone + Send + Sync,
+{
+    fn get(&self, index: usize) -> Option<I> {
+        let index = self.indices.get(index)?;
+        self.dataset.get(*index)
+    }
+
+    fn len(&self) -> usize {
+        self.indices.len()
+    }
+}
+
+/// Samples the items with a probability proportional to their weights, e.g. to oversample the
+/// rare classes of an imbalanced dataset.
+#[derive(Debug, Clone)]
+pub struct WeightedSampler {
+    weights: Vec<f64>,
+    num_samples: usize,
+    replacement: bool,
+}
+
+impl WeightedSampler {
+    /// Creates a new weighted sampler drawing `num_samples` items per epoch with replacement.
+    ///
+    /// # Panics
+    ///
+    /// If a weight is negative or not finite, or if all the weights are zero.
+    pub fn new(weights: Vec<f64>, num_samples: usize) -> Self {
+        assert!(
+            weights
+                .iter()
+                .all(|weight| weight.is_finite() && *weight >= 0.0),
+            "The weights must be positive and finite"
+        );
+        assert!(
+            weights.iter().any(|weight| *weight > 0.0),
+            "At least one weight must be positive"
+        );
+
+        Self {
+            weights,
+            num_samples,
+            replacement: true,
+        }
+    }
+
+    /// Creates a new weighted sampler balancing the classes of the items, so that every class is
+    /// drawn with the same probability.
+    ///
+    /// # Arguments
+    ///
+    /// * `labels` - The class of each item.
+    /// * `num_samples` - The number of items drawn per epoch.
+    pub fn class_balanced(labels: &[usize], num_samples: usize) -> Self {
+        let mut counts = HashMap::<usize, usize>::new();
+        for label in labels {
+            *counts.entry(*label).or_default() += 1;
+        }
+
+        let weights = labels
+            .iter()
+            .map(|label| 1.0 / counts[label] as f64)
+            .collect();
+        Self::new(weights, num_samples)
+    }
+
+    /// Sets whether an item can be drawn multiple times in the same epoch.
+    ///
+    /// Without replacement, the number of samples can't exceed the number of items with a
+    /// positive weight.
+    pub fn with_replacement(mut self, replacement: bool) -> Self {
+        self.replacement = replacement;
+        self
+    }
+}
+
+impl IndexSampler for WeightedSampler {
+    fn num_items(&self, _len: usize) -> usize {
+        self.num_samples
+    }
+
+    fn indices(&self, len: usize, rng: &mut StdRng) -> Vec<usize> {
+        assert_eq!(
+            self.weights.len(),
+            len,
+            "The sampler must have one weight per item"
+        );
+
+        if self.replacement {
+            let distribution = WeightedIndex::new(&self.weights).unwrap();
+            return distribution
+                .sample_iter(rng)
+                .take(self.num_samples)
+                .collect();
+        }
+
+        // Weighted sampling without replacement (Efraimidis and Spirakis): the items with the
+        // largest keys `u^(1 / weight)` are selected, compared through their logarithms.
+        let mut keys = self
+            .weights
+            .iter()
+            .enumerate()
+            .filter(|(_, weight)| **weight > 0.0)
+            .map(|(index, weight)| (rng.random::<f64>().ln() / weight, index))
+            .collect::<Vec<_>>();
+        assert!(
+            self.num_samples <= keys.len(),
+            "Can't draw {} items without replacement from {} items",
+            self.num_samples,
+            keys.len()
+        );
+
+        keys.sort_by(|a, b| b.0.total_cmp(&a.0));
+        keys.into_iter()
+            .take(self.num_samples)
+            .map(|(_, index)| index)
+            .collect()
+    }
+}
+
+/// Orders the items so that every batch has the class proportions of the dataset.
+///
+/// Each class count of a batch differs by less than two items from its share of the batch, the
+/// items of each class and of each batch being shuffled.
+#[derive(Debug, Clone)]
+pub struct StratifiedSampler {
+    labels: Vec<usize>,
+    batch_size: usize,
+}
+
+impl StratifiedSampler {
+    /// Creates a new stratified sampler.
+    ///
+    /// # Arguments
+    ///
+    /// * `labels` - The class of each item.
+    /// * `batch_size` - The batch size of the data loader.
+    pub fn new(labels: Vec<usize>, batch_size: usize) -> Self {
+        assert!(batch_size > 0, "The batch size must be positive");
+
+        Self { labels, batch_size }
+    }
+}
+
+impl IndexSampler for StratifiedSampler {
+    fn indices(&self, len: usize, rng: &mut StdRng) -> Vec<usize> {
+        assert_eq!(
+            self.labels.len(),
+            len,
+            "The sampler must have one label per item"
+        );
+
+        let mut classes = HashMap::<usize, Vec<usize>>::new();
+        for (index, label) in self.labels.iter().enumerate() {
+            classes.entry(*label).or_default().push(index);
+        }
+        let mut classes = classes.into_iter().collect::<Vec<_>>();
+        classes.sort_by_key(|(label, _)| *label);
+        for (_, indices) in classes.iter_mut() {
+            indices.shuffle(rng);
+        }
+
+        // Smooth weighted round-robin: the number of items of each class in the first `n` items
+        // stays within one item of its share of `n`, and within two items for any window of
+        // consecutive items.
+        let counts = classes
+            .iter()
+            .map(|(_, items)| items.len() as i64)
+            .collect::<Vec<_>>();
+        let mut credits = vec![0i64; classes.len()];
+        let mut indices = Vec::with_capacity(len);
+        for _ in 0..len {
+            for (credit, count) in credits.iter_mut().zip(counts.iter()) {
+                *credit += count;
+            }
+            let (class, _) = credits
+                .iter()
+                .enumerate()
+                .max_by_key(|(class, credit)| (**credit, std::cmp::Reverse(*class)))
+                .expect("The dataset has items");
+            credits[class] -= len as i64;
+            indices.push(classes[class].1.pop().expect("The class has items left"));
+        }
+
+        for batch in indices.chunks_mut(self.batch_size) {
+            batch.shuffle(rng);
+        }
+        indices
+    }
+}
+
+/// Groups the items of similar lengths in the same batches, minimizing the padding of
+/// variable-length sequences.
+///
+/// The shuffled items are split into pools of a few batches, whose items are sorted by length
+/// and split into batches, the batches being shuffled afterward.
+#[derive(Debug, Clone)]
+pub struct BucketSampler {
+    lengths: Vec<usize>,
+    batch_size: usize,
+    pool_size: usize,
+}
+
+impl BucketSampler {
+    /// Creates a new bucket sampler, sorting pools of 100 batches.
+    ///
+    /// # Arguments
+    ///
+    /// * `lengths` - The length of each item.
+    /// * `batch_size` - The batch size of the data loader.
+    pub fn new(lengths: Vec<usize>, batch_size: usize) -> Self {
+        assert!(batch_size > 0, "The batch size must be positive");
+
+        Self {
+            lengths,
+            batch_size,
+            pool_size: 100,
+        }
+    }
+
+    /// Sets the number of batches of the pools sorted by length. Larger pools reduce the padding,
+    /// but make the batches less random.
+    pub fn with_pool_size(mut self, num_batches: usize) -> Self {
+        assert!(num_batches > 0, "The pool size must be positive");
+        self.pool_size = num_batches;
+        self
+    }
+}
+
+impl IndexSampler for BucketSampler {
+    fn indices(&self, len: usize, rng: &mut StdRng) -> Vec<usize> {
+        assert_eq!(
+            self.lengths.len(),
+            len,
+            "The sampler must have one length per item"
+        );
+
+        let mut indices = (0..len).collect::<Vec<_>>();
+        indices.shuffle(rng);
+
+        let mut batches = Vec::with_capacity(len.div_ceil(self.batch_size));
+        for pool in indices.chunks_mut(self.batch_size * self.pool_size) {
+            pool.sort_by_key(|index| self.lengths[*index]);
+            batches.extend(pool.chunks(self.batch_size));
+        }
+
+        // The incomplete batch, if any, stays the last one so the other batches stay aligned.
+        let num_full = len / self.batch_size;
+        batches[..num_full].shuffle(rng);
+        batches.concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemDataset;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_weighted_sampler() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let sampler = WeightedSampler::class_balanced(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 1], 2000);
+        let indices = sampler.indices(10, &mut rng);
+
+        assert_eq!(indices.len(), 2000);
+        let rare = indices.iter().filter(|index| **index == 9).count();
+        assert!((800..1200).contains(&rare), "{rare}");
+
+        let sampler = WeightedSampler::new(vec![1.0, 0.0, 2.0, 3.0], 3).with_replacement(false);
+        let mut indices = sampler.indices(4, &mut rng);
+        indices.sort();
+        assert_eq!(indices, vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn test_stratified_sampler_keeps_the_class_proportions() {
+        // 30 items of class 0, 15 of class 1 and 5 of class 2.
+        let labels = (0..50)
+            .map(|index| match index % 10 {
+                0..=5 => 0,
+                6..=8 => 1,
+                _ => 2,
+            })
+            .collect::<Vec<_>>();
+        let sampler = StratifiedSampler::new(labels.clone(), 10);
+
+        let mut indices = sampler.indices(50, &mut StdRng::seed_from_u64(1));
+        for batch in indices.chunks(10) {
+            let count = |class| {
+                batch
+                    .iter()
+                    .filter(|index| labels[**index] == class)
+                    .count()
+            };
+            assert_eq!((count(0), count(1), count(2)), (6, 3, 1));
+        }
+
+        indices.sort();
+        assert_eq!(indices, (0..50).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_bucket_sampler_groups_similar_lengths() {
+        let lengths = (0..40).map(|index| (index * 7) % 40).collect::<Vec<_>>();
+        let sampler = BucketSampler::new(lengths.clone(), 4).with_pool_size(10);
+
+        let indices = sampler.indices(40, &mut StdRng::seed_from_u64(2));
+        for batch in indices.chunks(4) {
+            let lengths = batch.iter().map(|index| lengths[*index]);
+            let (min, max) = (lengths.clone().min().unwrap(), lengths.max().unwrap());
+            assert_eq!(max - min, 3);
+        }
+
+        let dataset = SampledDataset::new(InMemDataset::new(lengths), indices);
+        assert_eq!(dataset.len(), 40);
+    }
+}
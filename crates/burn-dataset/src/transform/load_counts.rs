@@ -0,0 +1,79 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
+
+/// The number of times each item of a dataset was loaded, e.g. to transform the items
+/// differently at each epoch in a reproducible way.
+///
+/// The counts are shared by the clones, so they can be kept after the dataset is moved into a
+/// data loader, and saved with the checkpoints of a training to
+/// [restore](LoadCounts::restore) them when it resumes.
+#[derive(Debug, Clone, Default)]
+pub struct LoadCounts {
+    counts: Arc<Vec<AtomicU64>>,
+}
+
+impl LoadCounts {
+    /// Create the counts of a dataset with the given number of items, all zero.
+    pub fn new(num_items: usize) -> Self {
+        Self {
+            counts: Arc::new((0..num_items).map(|_| AtomicU64::new(0)).collect()),
+        }
+    }
+
+    /// Count a load of the item at the given index, returning the number of times it was loaded
+    /// before, or zero for an index out of the counts.
+    pub fn increment(&self, index: usize) -> u64 {
+        self.counts
+            .get(index)
+            .map(|count| count.fetch_add(1, Ordering::Relaxed))
+            .unwrap_or(0)
+    }
+
+    /// The current counts, by item index.
+    pub fn to_vec(&self) -> Vec<u64> {
+        self.counts
+            .iter()
+            .map(|count| count.load(Ordering::Relaxed))
+            .collect()
+    }
+
+    /// Restore the counts saved with [to_vec](LoadCounts::to_vec).
+    ///
+    /// # Panics
+    ///
+    /// If the number of counts differs from the number of items.
+    pub fn restore(&self, counts: &[u64]) {
+        assert_eq!(
+            counts.len(),
+            self.counts.len(),
+            "The counts of {} items can't be restored for {} items",
+            counts.len(),
+            self.counts.len()
+        );
+        for (count, value) in self.counts.iter().zip(counts) {
+            count.store(*value, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counts_are_shared_and_restored() {
+        let counts = LoadCounts::new(3);
+        let shared = counts.clone();
+
+        assert_eq!(counts.increment(1), 0);
+        assert_eq!(shared.increment(1), 1);
+        assert_eq!(counts.increment(5), 0);
+        assert_eq!(shared.to_vec(), [0, 2, 0]);
+
+        let restored = LoadCounts::new(3);
+        restored.restore(&counts.to_vec());
+        assert_eq!(restored.increment(1), 2);
+    }
+}
@@ -0,0 +1,161 @@
+use std::{io, ops::Deref, path::Path};
+
+/// A read-only memory map of a file.
+///
+/// On the platforms without memory mapping support, the file is read into memory instead.
+///
+/// The file must only be appended to while it is mapped: truncating it would invalidate the
+/// mapped memory.
+pub(crate) struct Mmap {
+    #[cfg(unix)]
+    ptr: *mut libc::c_void,
+    #[cfg(unix)]
+    len: usize,
+    #[cfg(not(unix))]
+    data: Vec<u8>,
+}
+
+// SAFETY: The mapping is read-only and the mapped bytes are never modified.
+#[cfg(unix)]
+unsafe impl Send for Mmap {}
+#[cfg(unix)]
+unsafe impl Sync for Mmap {}
+
+impl Mmap {
+    /// An empty map.
+    pub(crate) fn empty() -> Self {
+        Self {
+            #[cfg(unix)]
+            ptr: std::ptr::null_mut(),
+            #[cfg(unix)]
+            len: 0,
+            #[cfg(not(unix))]
+            data: Vec::new(),
+        }
+    }
+
+    /// Maps the current content of a file.
+    #[cfg(unix)]
+    pub(crate) fn map(path: &Path) -> io::Result<Self> {
+        use std::os::fd::AsRawFd;
+
+        let file = std::fs::File::open(path)?;
+        let len = file.metadata()?.len() as usize;
+        if len == 0 {
+            return Ok(Self::empty());
+        }
+
+        // SAFETY: A new read-only mapping of the whole file, the file descriptor being valid for
+        // the duration of the call. The mapping stays valid after the file is closed.
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_SHARED,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Self { ptr, len })
+    }
+
+    /// Reads the current content of a file.
+    #[cfg(not(unix))]
+    pub(crate) fn map(path: &Path) -> io::Result<Self> {
+        Ok(Self {
+            data: std::fs::read(path)?,
+        })
+    }
+}
+
+impl Deref for Mmap {
+    type Target = [u8];
+
+    #[cfg(unix)]
+    fn deref(&self) -> &[u8] {
+        if self.len == 0 {
+            return &[];
+        }
+
+        // SAFETY: The mapping of `len` bytes is valid until it is dropped.
+        unsafe { std::slice::from_raw_parts(self.ptr as *const u8, self.len) }
+    }
+
+    #[cfg(not(unix))]
+    fn deref(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+#[cfg(unix)]
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            // SAFETY: The mapping was created by `mmap` with this length and isn't used anymore.
+            unsafe {
+                libc::munmap(self.ptr, self.len);
+            }
+        }
+    }
+}
+
+/// Takes an advisory lock on a file, shared with the other readers or exclusive, released when
+/// the file is closed or [unlocked](unlock).
+///
+/// Returns whether the lock was taken, which is always the case when `blocking` is set. The lock
+/// is a no-op on the platforms without `flock`.
+#[cfg(unix)]
+pub(crate) fn lock(file: &std::fs::File, exclusive: bool, blocking: bool) -> io::Result<bool> {
+    use std::os::fd::AsRawFd;
+
+    let mut operation = match exclusive {
+        true => libc::LOCK_EX,
+        false => libc::LOCK_SH,
+    };
+    if !blocking {
+        operation |= libc::LOCK_NB;
+    }
+
+    loop {
+        // SAFETY: The file descriptor is valid for the duration of the call.
+        if unsafe { libc::flock(file.as_raw_fd(), operation) } == 0 {
+            return Ok(true);
+        }
+
+        let err = io::Error::last_os_error();
+        match err.kind() {
+            io::ErrorKind::Interrupted => continue,
+            io::ErrorKind::WouldBlock => return Ok(false),
+            _ => return Err(err),
+        }
+    }
+}
+
+/// Takes an advisory lock on a file.
+#[cfg(not(unix))]
+pub(crate) fn lock(_file: &std::fs::File, _exclusive: bool, _blocking: bool) -> io::Result<bool> {
+    Ok(true)
+}
+
+/// Releases an advisory lock taken with [lock].
+#[cfg(unix)]
+pub(crate) fn unlock(file: &std::fs::File) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    // SAFETY: The file descriptor is valid for the duration of the call.
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_UN) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Releases an advisory lock taken with [lock].
+#[cfg(not(unix))]
+pub(crate) fn unlock(_file: &std::fs::File) -> io::Result<()> {
+    Ok(())
+}
@@ -1,13 +1,22 @@
+mod cached;
 mod composed;
+mod index_sampler;
+mod load_counts;
 mod mapper;
+mod mmap;
 mod partial;
 mod random;
 mod sampler;
+mod shuffle_buffer;
 mod window;
 
+pub use cached::*;
 pub use composed::*;
+pub use index_sampler::*;
+pub use load_counts::*;
 pub use mapper::*;
 pub use partial::*;
 pub use random::*;
 pub use sampler::*;
+pub use shuffle_buffer::*;
 pub use window::*;
@@ -0,0 +1,70 @@
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+/// Approximately shuffle the items of an [iterable dataset](crate::IterableDataset), which can't
+/// be shuffled by index.
+///
+/// The items are read into a buffer of a fixed size, and each item yielded is taken at random
+/// from the buffer and replaced by the next item read. The larger the buffer, the closer the
+/// order is to a full shuffle, at the cost of keeping more items in memory.
+pub struct ShuffleBuffer<It: Iterator> {
+    items: It,
+    buffer: Vec<It::Item>,
+    buffer_size: usize,
+    rng: StdRng,
+}
+
+impl<It: Iterator> ShuffleBuffer<It> {
+    /// Creates a new shuffle buffer over the given items.
+    pub fn new(items: It, buffer_size: usize, rng: StdRng) -> Self {
+        Self {
+            items,
+            buffer: Vec::with_capacity(buffer_size),
+            buffer_size: buffer_size.max(1),
+            rng,
+        }
+    }
+
+    /// Creates a new shuffle buffer over the given items with a fixed seed.
+    pub fn with_seed(items: It, buffer_size: usize, seed: u64) -> Self {
+        Self::new(items, buffer_size, StdRng::seed_from_u64(seed))
+    }
+}
+
+impl<It: Iterator> Iterator for ShuffleBuffer<It> {
+    type Item = It::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.buffer.len() < self.buffer_size {
+            match self.items.next() {
+                Some(item) => self.buffer.push(item),
+                None => break,
+            }
+        }
+
+        if self.buffer.is_empty() {
+            return None;
+        }
+
+        let index = self.rng.random_range(0..self.buffer.len());
+        Some(self.buffer.swap_remove(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shuffle_buffer_yields_all_items() {
+        let items = ShuffleBuffer::with_seed(0..100, 10, 42).collect::<Vec<_>>();
+        let mut sorted = items.clone();
+        sorted.sort();
+
+        assert_ne!(items, sorted);
+        assert_eq!(sorted, (0..100).collect::<Vec<_>>());
+        // An item can't be yielded before the items read after it filled the buffer.
+        for (position, item) in items.iter().enumerate() {
+            assert!(*item < position + 10);
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use super::{
+    ImageTransform,
+    color::{
+        adjust_brightness, adjust_contrast, adjust_saturation, adjust_sharpness, autocontrast,
+        equalize, posterize, solarize,
+    },
+    geometric::{Affine, warp},
+    raster::map_raster,
+};
+use crate::vision::ImageDatasetItem;
+use rand::{Rng, rngs::StdRng};
+
+/// The operations of the automatic augmentations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AugmentOp {
+    Identity,
+    ShearX,
+    ShearY,
+    TranslateX,
+    TranslateY,
+    Rotate,
+    Brightness,
+    Color,
+    Contrast,
+    Sharpness,
+    Posterize,
+    Solarize,
+    AutoContrast,
+    Equalize,
+}
+
+impl AugmentOp {
+    const ALL: [AugmentOp; 14] = [
+        AugmentOp::Identity,
+        AugmentOp::ShearX,
+        AugmentOp::ShearY,
+        AugmentOp::TranslateX,
+        AugmentOp::TranslateY,
+        AugmentOp::Rotate,
+        AugmentOp::Brightness,
+        AugmentOp::Color,
+        AugmentOp::Contrast,
+        AugmentOp::Sharpness,
+        AugmentOp::Posterize,
+        AugmentOp::Solarize,
+        AugmentOp::AutoContrast,
+        AugmentOp::Equalize,
+    ];
+
+    /// Apply the operation with a magnitude between 0 and 1, the signed operations being applied
+    /// in a random direction.
+    fn apply(
+        &self,
+        item: ImageDatasetItem,
+        magnitude: f32,
+        ranges: &MagnitudeRanges,
+        rng: &mut StdRng,
+    ) -> ImageDatasetItem {
+        let sign = if rng.random_bool(0.5) { 1.0 } else { -1.0 };
+        let signed = |max: f32| sign * magnitude * max;
+        let (width, height) = (item.width, item.height);
+        let warp_centered =
+            |item, transform: Affine| warp(item, width, height, transform.centered(width, height));
+
+        match self {
+            AugmentOp::Identity => item,
+            AugmentOp::ShearX => warp_centered(item, Affine::shear(signed(ranges.shear), 0.0)),
+            AugmentOp::ShearY => warp_centered(item, Affine::shear(0.0, signed(ranges.shear))),
+            AugmentOp::TranslateX => warp_centered(
+                item,
+                Affine::translation(signed(ranges.translate) * width as f32, 0.0),
+            ),
+            AugmentOp::TranslateY => warp_centered(
+                item,
+                Affine::translation(0.0, signed(ranges.translate) * height as f32),
+            ),
+            AugmentOp::Rotate => warp_centered(item, Affine::rotation(signed(ranges.rotate))),
+            AugmentOp::Brightness => map_raster(item, |raster| {
+                adjust_brightness(raster, 1.0 + signed(ranges.enhance))
+            }),
+            AugmentOp::Color => map_raster(item, |raster| {
+                adjust_saturation(raster, 1.0 + signed(ranges.enhance))
+            }),
+            AugmentOp::Contrast => map_raster(item, |raster| {
+                adjust_contrast(raster, 1.0 + signed(ranges.enhance))
+            }),
+            AugmentOp::Sharpness => map_raster(item, |raster| {
+                adjust_sharpness(raster, 1.0 + signed(ranges.enhance))
+            }),
+            AugmentOp::Posterize => map_raster(item, |raster| {
+                let bits = 8.0 - magnitude * (8.0 - ranges.posterize_bits as f32);
+                posterize(raster, bits.round() as u32)
+            }),
+            AugmentOp::Solarize => map_raster(item, |raster| solarize(raster, 1.0 - magnitude)),
+            AugmentOp::AutoContrast => map_raster(item, autocontrast),
+            AugmentOp::Equalize => map_raster(item, equalize),
+        }
+    }
+}
+
+/// The maximum magnitude of the operations.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct MagnitudeRanges {
+    /// The shear factor.
+    shear: f32,
+    /// The translation, as a fraction of the image size.
+    translate: f32,
+    /// The rotation, in degrees.
+    rotate: f32,
+    /// The brightness, color, contrast and sharpness factor variation.
+    enhance: f32,
+    /// The lowest number of bits kept by the posterization.
+    posterize_bits: u32,
+}
+
+impl MagnitudeRanges {
+    const RAND_AUGMENT: Self = Self {
+        shear: 0.3,
+        translate: 150.0 / 331.0,
+        rotate: 30.0,
+        enhance: 0.9,
+        posterize_bits: 4,
+    };
+
+    const TRIVIAL_AUGMENT: Self = Self {
+        shear: 0.99,
+        translate: 32.0 / 224.0,
+        rotate: 135.0,
+        enhance: 0.99,
+        posterize_bits: 2,
+    };
+}
+
+/// [RandAugment](https://arxiv.org/abs/1909.13719): apply a number of operations chosen
+/// uniformly among geometric and color operations, all with the same magnitude.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RandAugment {
+    num_ops: usize,
+    magnitude: usize,
+    num_magnitude_bins: usize,
+}
+
+impl RandAugment {
+    /// Create a new RandAugment with 2 operations of magnitude 9 out of 30.
+    pub fn new() -> Self {
+        Self {
+            num_ops: 2,
+            magnitude: 9,
+            num_magnitude_bins: 31,
+        }
+    }
+
+    /// Set the number of operations applied to each image.
+    pub fn with_num_ops(mut self, num_ops: usize) -> Self {
+        self.num_ops = num_ops;
+        self
+    }
+
+    /// Set the magnitude of the operations, between 0 and the number of magnitude bins minus one.
+    pub fn with_magnitude(mut self, magnitude: usize) -> Self {
+        self.magnitude = magnitude;
+        self
+    }
+
+    /// Set the number of magnitude bins.
+    pub fn with_num_magnitude_bins(mut self, num_magnitude_bins: usize) -> Self {
+        assert!(
+            num_magnitude_bins > 1,
+            "There should be at least two magnitude bins"
+        );
+        self.num_magnitude_bins = num_magnitude_bins;
+        self
+    }
+}
+
+impl Default for RandAugment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ImageTransform for RandAugment {
+    fn apply(&self, mut item: ImageDatasetItem, rng: &mut StdRng) -> ImageDatasetItem {
+        let magnitude = (self.magnitude as f32 / (self.num_magnitude_bins - 1) as f32).min(1.0);
+
+        for _ in 0..self.num_ops {
+            let op = AugmentOp::ALL[rng.random_range(0..AugmentOp::ALL.len())];
+            item = op.apply(item, magnitude, &MagnitudeRanges::RAND_AUGMENT, rng);
+        }
+
+        item
+    }
+}
+
+/// [TrivialAugment](https://arxiv.org/abs/2103.10158): apply a single operation chosen
+/// uniformly, with a uniformly distributed magnitude, using the wide magnitude ranges of the
+/// paper.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrivialAugment {
+    num_magnitude_bins: usize,
+}
+
+impl TrivialAugment {
+    /// Create a new TrivialAugment with 31 magnitude bins.
+    pub fn new() -> Self {
+        Self {
+            num_magnitude_bins: 31,
+        }
+    }
+
+    /// Set the number of magnitude bins.
+    pub fn with_num_magnitude_bins(mut self, num_magnitude_bins: usize) -> Self {
+        assert!(
+            num_magnitude_bins > 1,
+            "There should be at least two magnitude bins"
+        );
+        self.num_magnitude_bins = num_magnitude_bins;
+        self
+    }
+}
+
+impl Default for TrivialAugment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ImageTransform for TrivialAugment {
+    fn apply(&self, item: ImageDatasetItem, rng: &mut StdRng) -> ImageDatasetItem {
+        let op = AugmentOp::ALL[rng.random_range(0..AugmentOp::ALL.len())];
+        let magnitude = rng.random_range(0..self.num_magnitude_bins) as f32
+            / (self.num_magnitude_bins - 1) as f32;
+
+        op.apply(item, magnitude, &MagnitudeRanges::TRIVIAL_AUGMENT, rng)
+    }
+}
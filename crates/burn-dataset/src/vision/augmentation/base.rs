@@ -0,0 +1,180 @@
+use crate::Dataset;
+use crate::transform::LoadCounts;
+use crate::vision::ImageDatasetItem;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+/// A random transformation of an image item, transforming its
+/// [annotation](crate::vision::Annotation) consistently with its image, e.g. to augment a
+/// training dataset.
+///
+/// The bounding boxes and the segmentation masks follow the geometric transformations of the
+/// image, while the labels are left unchanged.
+pub trait ImageTransform: Send + Sync {
+    /// Transform an item, drawing the random parameters from the given rng.
+    fn apply(&self, item: ImageDatasetItem, rng: &mut StdRng) -> ImageDatasetItem;
+}
+
+/// Apply a sequence of [image transforms](ImageTransform), in order.
+#[derive(Default)]
+pub struct Compose {
+    transforms: Vec<Box<dyn ImageTransform>>,
+}
+
+impl Compose {
+    /// Create an empty sequence of transforms.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a transform to the sequence.
+    pub fn with<T: ImageTransform + 'static>(mut self, transform: T) -> Self {
+        self.transforms.push(Box::new(transform));
+        self
+    }
+}
+
+impl ImageTransform for Compose {
+    fn apply(&self, item: ImageDatasetItem, rng: &mut StdRng) -> ImageDatasetItem {
+        self.transforms
+            .iter()
+            .fold(item, |item, transform| transform.apply(item, rng))
+    }
+}
+
+/// Apply an [image transform](ImageTransform) with a probability.
+#[derive(new)]
+pub struct RandomApply<T> {
+    transform: T,
+    probability: f64,
+}
+
+impl<T: ImageTransform> ImageTransform for RandomApply<T> {
+    fn apply(&self, item: ImageDatasetItem, rng: &mut StdRng) -> ImageDatasetItem {
+        match rng.random_bool(self.probability) {
+            true => self.transform.apply(item, rng),
+            false => item,
+        }
+    }
+}
+
+/// Dataset applying an [image transform](ImageTransform) to the items of an image dataset.
+///
+/// The rng of an item is seeded from the seed of the dataset, the index of the item and the
+/// number of times the item was loaded before, so the items are transformed differently at each
+/// epoch while the transforms are reproducible, whatever the order the items are loaded in.
+///
+/// To transform the items as an uninterrupted training would when it resumes from a checkpoint,
+/// the [load counts](AugmentedDataset::load_counts) must be saved with the checkpoint, e.g. by
+/// registering them in the learner.
+pub struct AugmentedDataset<D, T> {
+    dataset: D,
+    transform: T,
+    seed: u64,
+    counts: LoadCounts,
+}
+
+impl<D, T> AugmentedDataset<D, T>
+where
+    D: Dataset<ImageDatasetItem>,
+    T: ImageTransform,
+{
+    /// Create a new augmented dataset.
+    pub fn new(dataset: D, transform: T, seed: u64) -> Self {
+        let counts = LoadCounts::new(dataset.len());
+        Self {
+            dataset,
+            transform,
+            seed,
+            counts,
+        }
+    }
+
+    /// The number of times each item was loaded, shared with the dataset.
+    pub fn load_counts(&self) -> LoadCounts {
+        self.counts.clone()
+    }
+}
+
+impl<D, T> Dataset<ImageDatasetItem> for AugmentedDataset<D, T>
+where
+    D: Dataset<ImageDatasetItem>,
+    T: ImageTransform,
+{
+    fn get(&self, index: usize) -> Option<ImageDatasetItem> {
+        let item = self.dataset.get(index)?;
+        let count = self.counts.increment(index);
+
+        let item_seed =
+            StdRng::seed_from_u64(self.seed ^ (index as u64).rotate_left(32)).random::<u64>();
+        let mut rng = StdRng::seed_from_u64(item_seed ^ count);
+
+        Some(self.transform.apply(item, &mut rng))
+    }
+
+    fn len(&self) -> usize {
+        self.dataset.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemDataset;
+    use crate::vision::{Annotation, PixelDepth};
+
+    struct Noise;
+
+    impl ImageTransform for Noise {
+        fn apply(&self, mut item: ImageDatasetItem, rng: &mut StdRng) -> ImageDatasetItem {
+            item.image = vec![PixelDepth::U8(rng.random()); item.image.len()];
+            item
+        }
+    }
+
+    fn dataset(seed: u64) -> AugmentedDataset<InMemDataset<ImageDatasetItem>, Compose> {
+        let items = (0..3)
+            .map(|label| ImageDatasetItem {
+                image: vec![PixelDepth::U8(0); 4],
+                width: 2,
+                height: 2,
+                annotation: Annotation::Label(label),
+                image_path: format!("{label}.png"),
+            })
+            .collect();
+
+        AugmentedDataset::new(
+            InMemDataset::new(items),
+            Compose::new().with(RandomApply::new(Noise, 1.0)),
+            seed,
+        )
+    }
+
+    #[test]
+    fn test_augmentations_are_reproducible_and_vary_between_epochs() {
+        let (first, second) = (dataset(42), dataset(42));
+
+        let epoch = |dataset: &AugmentedDataset<_, _>, order: &[usize]| {
+            let mut images = vec![vec![]; 3];
+            for index in order {
+                images[*index] = dataset.get(*index).unwrap().image;
+            }
+            images
+        };
+
+        let first_epoch = epoch(&first, &[0, 1, 2]);
+        assert_eq!(first_epoch, epoch(&second, &[2, 0, 1]));
+        assert_ne!(first_epoch, epoch(&first, &[0, 1, 2]));
+        assert!(first.get(3).is_none());
+    }
+
+    #[test]
+    fn test_augmentations_resume_from_the_load_counts() {
+        let (first, resumed) = (dataset(42), dataset(42));
+        let _first_epoch = first.get(1);
+        let second_epoch = first.get(1).unwrap().image;
+
+        resumed.load_counts().restore(&[0, 1, 0]);
+
+        assert_eq!(resumed.get(1).unwrap().image, second_epoch);
+    }
+}
@@ -0,0 +1,361 @@
+use super::{
+    ImageTransform,
+    raster::{Depth, Raster, map_raster},
+};
+use crate::vision::ImageDatasetItem;
+use rand::{Rng, rngs::StdRng, seq::SliceRandom};
+
+pub(crate) fn adjust_brightness(raster: &mut Raster, factor: f32) {
+    raster.map_colors(|colors| colors.iter_mut().for_each(|value| *value *= factor));
+}
+
+pub(crate) fn adjust_contrast(raster: &mut Raster, factor: f32) {
+    let mean = raster.mean_gray();
+    raster.map_colors(|colors| {
+        colors
+            .iter_mut()
+            .for_each(|value| *value = mean + (*value - mean) * factor)
+    });
+}
+
+pub(crate) fn adjust_saturation(raster: &mut Raster, factor: f32) {
+    raster.map_colors(|colors| {
+        let gray = Raster::gray(colors);
+        colors
+            .iter_mut()
+            .for_each(|value| *value = gray + (*value - gray) * factor)
+    });
+}
+
+/// Shift the hue of the colors, by a fraction of a turn between -0.5 and 0.5.
+pub(crate) fn adjust_hue(raster: &mut Raster, shift: f32) {
+    if raster.color_channels() < 3 {
+        return;
+    }
+
+    raster.map_colors(|colors| {
+        let (red, green, blue) = (colors[0], colors[1], colors[2]);
+        let max = red.max(green).max(blue);
+        let min = red.min(green).min(blue);
+        let chroma = max - min;
+        if chroma <= 0.0 {
+            return;
+        }
+
+        let hue = if max == red {
+            ((green - blue) / chroma).rem_euclid(6.0)
+        } else if max == green {
+            (blue - red) / chroma + 2.0
+        } else {
+            (red - green) / chroma + 4.0
+        };
+        let hue = (hue / 6.0 + shift).rem_euclid(1.0) * 6.0;
+
+        let x = chroma * (1.0 - (hue.rem_euclid(2.0) - 1.0).abs());
+        let (r, g, b) = match hue as usize {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        colors[0] = r + min;
+        colors[1] = g + min;
+        colors[2] = b + min;
+    });
+}
+
+/// Blend the image with a smoothed version of itself, sharpening it when the factor is greater
+/// than one and blurring it otherwise.
+pub(crate) fn adjust_sharpness(raster: &mut Raster, factor: f32) {
+    if raster.width < 3 || raster.height < 3 {
+        return;
+    }
+
+    let source = raster.clone();
+    let (width, channels) = (raster.width, raster.channels);
+    for y in 1..raster.height - 1 {
+        for x in 1..width - 1 {
+            for channel in 0..raster.color_channels() {
+                let mut smooth = 4.0 * source.get(x, y, channel);
+                for (dx, dy) in (0..3).flat_map(|dy| (0..3).map(move |dx| (dx, dy))) {
+                    smooth += source.get(x + dx - 1, y + dy - 1, channel);
+                }
+                let smooth = smooth / 13.0;
+
+                let value = &mut raster.data[(y * width + x) * channels + channel];
+                *value = (smooth + (*value - smooth) * factor).clamp(0.0, 1.0);
+            }
+        }
+    }
+}
+
+/// Keep the given number of most significant bits of 8-bit colors.
+pub(crate) fn posterize(raster: &mut Raster, bits: u32) {
+    let levels = 2u32.pow(8 - bits.min(8)) as f32;
+    raster.map_colors(|colors| {
+        colors
+            .iter_mut()
+            .for_each(|value| *value = ((*value * 255.0).round() / levels).floor() * levels / 255.0)
+    });
+}
+
+/// Invert the colors above a threshold.
+pub(crate) fn solarize(raster: &mut Raster, threshold: f32) {
+    raster.map_colors(|colors| {
+        colors.iter_mut().for_each(|value| {
+            if *value >= threshold {
+                *value = 1.0 - *value
+            }
+        })
+    });
+}
+
+/// Stretch the colors of each channel to the full range.
+pub(crate) fn autocontrast(raster: &mut Raster) {
+    for channel in 0..raster.color_channels() {
+        let values = raster.data.iter().skip(channel).step_by(raster.channels);
+        let min = values.clone().copied().fold(f32::INFINITY, f32::min);
+        let max = values.copied().fold(f32::NEG_INFINITY, f32::max);
+        if max <= min {
+            continue;
+        }
+
+        let channels = raster.channels;
+        raster
+            .data
+            .iter_mut()
+            .skip(channel)
+            .step_by(channels)
+            .for_each(|value| *value = (*value - min) / (max - min));
+    }
+}
+
+/// Equalize the histogram of the 8-bit colors of each channel.
+pub(crate) fn equalize(raster: &mut Raster) {
+    let channels = raster.channels;
+    for channel in 0..raster.color_channels() {
+        let bins = raster
+            .data
+            .iter()
+            .skip(channel)
+            .step_by(channels)
+            .map(|value| (value.clamp(0.0, 1.0) * 255.0).round() as usize)
+            .collect::<Vec<_>>();
+        let mut histogram = [0usize; 256];
+        bins.iter().for_each(|bin| histogram[*bin] += 1);
+
+        let cumulative = histogram
+            .iter()
+            .scan(0, |total, count| {
+                *total += count;
+                Some(*total)
+            })
+            .collect::<Vec<_>>();
+        let min = cumulative[bins.iter().copied().min().unwrap_or(0)];
+        let total = bins.len();
+        if total <= min {
+            continue;
+        }
+
+        raster
+            .data
+            .iter_mut()
+            .skip(channel)
+            .step_by(channels)
+            .zip(bins)
+            .for_each(|(value, bin)| {
+                *value = (cumulative[bin] - min) as f32 / (total - min) as f32;
+            });
+    }
+}
+
+/// Randomly change the brightness, contrast, saturation and hue of an image, in a random order.
+///
+/// The brightness, contrast and saturation factors are uniformly distributed between
+/// `1 - value` and `1 + value`, and the hue shift between `-hue` and `hue`, as a fraction of a
+/// turn of at most 0.5.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ColorJitter {
+    brightness: f32,
+    contrast: f32,
+    saturation: f32,
+    hue: f32,
+}
+
+impl ColorJitter {
+    /// Create a new color jitter, changing nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the brightness variation.
+    pub fn with_brightness(mut self, brightness: f32) -> Self {
+        self.brightness = brightness.abs();
+        self
+    }
+
+    /// Set the contrast variation.
+    pub fn with_contrast(mut self, contrast: f32) -> Self {
+        self.contrast = contrast.abs();
+        self
+    }
+
+    /// Set the saturation variation.
+    pub fn with_saturation(mut self, saturation: f32) -> Self {
+        self.saturation = saturation.abs();
+        self
+    }
+
+    /// Set the hue variation.
+    pub fn with_hue(mut self, hue: f32) -> Self {
+        assert!(hue.abs() <= 0.5, "The hue variation should be at most 0.5");
+        self.hue = hue.abs();
+        self
+    }
+}
+
+impl ImageTransform for ColorJitter {
+    fn apply(&self, item: ImageDatasetItem, rng: &mut StdRng) -> ImageDatasetItem {
+        let mut factor = |value: f32| rng.random_range((1.0 - value).max(0.0)..=1.0 + value);
+        let brightness = factor(self.brightness);
+        let contrast = factor(self.contrast);
+        let saturation = factor(self.saturation);
+        let hue = rng.random_range(-self.hue..=self.hue);
+
+        let mut order = [0, 1, 2, 3];
+        order.shuffle(rng);
+
+        map_raster(item, |raster| {
+            for adjustment in order {
+                match adjustment {
+                    0 if self.brightness > 0.0 => adjust_brightness(raster, brightness),
+                    1 if self.contrast > 0.0 => adjust_contrast(raster, contrast),
+                    2 if self.saturation > 0.0 => adjust_saturation(raster, saturation),
+                    3 if self.hue > 0.0 => adjust_hue(raster, hue),
+                    _ => {}
+                }
+            }
+        })
+    }
+}
+
+/// Normalize the channels of an image with their mean and standard deviation, converting its
+/// pixels to 32-bit floats.
+///
+/// The 8-bit and 16-bit pixels are first scaled between 0 and 1. The channels beyond the given
+/// statistics, e.g. the alpha channel, are only scaled.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Normalize {
+    mean: Vec<f32>,
+    std: Vec<f32>,
+}
+
+impl Normalize {
+    /// Create a new normalization with the mean and the standard deviation of each channel.
+    pub fn new(mean: Vec<f32>, std: Vec<f32>) -> Self {
+        assert_eq!(
+            mean.len(),
+            std.len(),
+            "The mean and the standard deviation should have the same number of channels"
+        );
+        Self { mean, std }
+    }
+
+    /// The normalization of the RGB images of ImageNet.
+    pub fn imagenet() -> Self {
+        Self::new(vec![0.485, 0.456, 0.406], vec![0.229, 0.224, 0.225])
+    }
+}
+
+impl ImageTransform for Normalize {
+    fn apply(&self, item: ImageDatasetItem, _rng: &mut StdRng) -> ImageDatasetItem {
+        map_raster(item, |raster| {
+            let channels = raster.channels;
+            for (index, value) in raster.data.iter_mut().enumerate() {
+                let channel = index % channels;
+                if let (Some(mean), Some(std)) = (self.mean.get(channel), self.std.get(channel)) {
+                    *value = (*value - mean) / std;
+                }
+            }
+            raster.depth = Depth::F32;
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vision::{Annotation, PixelDepth};
+    use rand::SeedableRng;
+
+    fn rgb_item(pixels: &[[u8; 3]]) -> ImageDatasetItem {
+        ImageDatasetItem {
+            image: pixels
+                .iter()
+                .flatten()
+                .map(|v| PixelDepth::U8(*v))
+                .collect(),
+            width: pixels.len(),
+            height: 1,
+            annotation: Annotation::Label(0),
+            image_path: "image.png".to_string(),
+        }
+    }
+
+    fn apply<F: FnOnce(&mut Raster)>(pixels: &[[u8; 3]], func: F) -> Vec<u8> {
+        map_raster(rgb_item(pixels), func)
+            .image
+            .into_iter()
+            .map(|pixel| u8::try_from(pixel).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_hue_shift() {
+        let red = [[255, 0, 0]];
+
+        assert_eq!(apply(&red, |r| adjust_hue(r, 1.0 / 3.0)), vec![0, 255, 0]);
+        assert_eq!(apply(&red, |r| adjust_hue(r, -1.0 / 3.0)), vec![0, 0, 255]);
+        assert_eq!(apply(&red, |r| adjust_saturation(r, 0.0)), vec![76, 76, 76]);
+    }
+
+    #[test]
+    fn test_histogram_operations() {
+        let pixels = [[50, 50, 50], [100, 100, 100], [150, 150, 150]];
+
+        assert_eq!(
+            apply(&pixels, autocontrast),
+            vec![0, 0, 0, 127, 127, 127, 255, 255, 255]
+        );
+        assert_eq!(
+            apply(&pixels, equalize),
+            vec![0, 0, 0, 128, 128, 128, 255, 255, 255]
+        );
+        assert_eq!(
+            apply(&pixels, |r| solarize(r, 0.5)),
+            vec![50, 50, 50, 100, 100, 100, 105, 105, 105]
+        );
+        assert_eq!(
+            apply(&pixels, |r| posterize(r, 2)),
+            vec![0, 0, 0, 64, 64, 64, 128, 128, 128]
+        );
+    }
+
+    #[test]
+    fn test_normalize_converts_to_floats() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let item = Normalize::new(vec![0.5, 0.5, 0.5], vec![0.5, 0.5, 0.5])
+            .apply(rgb_item(&[[0, 255, 0]]), &mut rng);
+
+        assert_eq!(
+            item.image,
+            vec![
+                PixelDepth::F32(-1.0),
+                PixelDepth::F32(1.0),
+                PixelDepth::F32(-1.0)
+            ]
+        );
+    }
+}
@@ -0,0 +1,503 @@
+use super::{ImageTransform, raster::Raster};
+use crate::vision::{Annotation, BoundingBox, ImageDatasetItem, SegmentationMask};
+use rand::{Rng, rngs::StdRng};
+
+/// An affine transformation of the pixel coordinates, mapping `(x, y)` to
+/// `(m[0] * x + m[1] * y + m[2], m[3] * x + m[4] * y + m[5])`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Affine {
+    matrix: [f32; 6],
+}
+
+impl Affine {
+    pub(crate) fn translation(x: f32, y: f32) -> Self {
+        Self {
+            matrix: [1.0, 0.0, x, 0.0, 1.0, y],
+        }
+    }
+
+    pub(crate) fn scale(x: f32, y: f32) -> Self {
+        Self {
+            matrix: [x, 0.0, 0.0, 0.0, y, 0.0],
+        }
+    }
+
+    /// A counter-clockwise rotation around the origin, the y axis pointing down.
+    pub(crate) fn rotation(degrees: f32) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Self {
+            matrix: [cos, sin, 0.0, -sin, cos, 0.0],
+        }
+    }
+
+    pub(crate) fn shear(x: f32, y: f32) -> Self {
+        Self {
+            matrix: [1.0, x, 0.0, y, 1.0, 0.0],
+        }
+    }
+
+    /// Apply the transformation around the center of an image instead of the origin.
+    pub(crate) fn centered(self, width: usize, height: usize) -> Self {
+        let (x, y) = (width as f32 / 2.0, height as f32 / 2.0);
+        Self::translation(-x, -y)
+            .then(self)
+            .then(Self::translation(x, y))
+    }
+
+    /// This transformation followed by another one.
+    pub(crate) fn then(self, next: Self) -> Self {
+        let [a, b, c, d, e, f] = self.matrix;
+        let [g, h, i, j, k, l] = next.matrix;
+        Self {
+            matrix: [
+                g * a + h * d,
+                g * b + h * e,
+                g * c + h * f + i,
+                j * a + k * d,
+                j * b + k * e,
+                j * c + k * f + l,
+            ],
+        }
+    }
+
+    pub(crate) fn inverse(self) -> Self {
+        let [a, b, c, d, e, f] = self.matrix;
+        let det = a * e - b * d;
+        let (a, b, d, e) = (e / det, -b / det, -d / det, a / det);
+        Self {
+            matrix: [a, b, -(a * c + b * f), d, e, -(d * c + e * f)],
+        }
+    }
+
+    pub(crate) fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        let [a, b, c, d, e, f] = self.matrix;
+        (a * x + b * y + c, d * x + e * y + f)
+    }
+}
+
+/// Transform an image and its annotation with an affine transformation of the pixel coordinates,
+/// into an image of the given size.
+///
+/// The image is interpolated bilinearly and the segmentation masks with the nearest pixel, the
+/// pixels outside of the original image being zero. The bounding boxes are replaced by the
+/// bounds of their transformed corners, and removed when they are outside of the image.
+pub(crate) fn warp(
+    item: ImageDatasetItem,
+    width: usize,
+    height: usize,
+    transform: Affine,
+) -> ImageDatasetItem {
+    let raster = Raster::new(&item);
+    let inverse = transform.inverse();
+    let channels = raster.channels;
+
+    let mut data = vec![0.0; width * height * channels];
+    for y in 0..height {
+        for x in 0..width {
+            let (source_x, source_y) = inverse.apply(x as f32 + 0.5, y as f32 + 0.5);
+            for channel in 0..channels {
+                data[(y * width + x) * channels + channel] =
+                    raster.bilinear(source_x - 0.5, source_y - 0.5, channel);
+            }
+        }
+    }
+
+    let annotation = match item.annotation {
+        Annotation::BoundingBoxes(boxes) => Annotation::BoundingBoxes(
+            boxes
+                .into_iter()
+                .filter_map(|bbox| warp_box(bbox, width, height, transform))
+                .collect(),
+        ),
+        Annotation::SegmentationMask(mask) => {
+            Annotation::SegmentationMask(warp_mask(mask, &raster, width, height, inverse))
+        }
+        annotation => annotation,
+    };
+
+    ImageDatasetItem {
+        image: Raster {
+            width,
+            height,
+            data,
+            ..raster
+        }
+        .into_pixels(),
+        width,
+        height,
+        annotation,
+        image_path: item.image_path,
+    }
+}
+
+fn warp_box(
+    bbox: BoundingBox,
+    width: usize,
+    height: usize,
+    transform: Affine,
+) -> Option<BoundingBox> {
+    let [x, y, w, h] = bbox.coords;
+    let corners =
+        [(x, y), (x + w, y), (x, y + h), (x + w, y + h)].map(|(x, y)| transform.apply(x, y));
+
+    let clamp_x = |x: f32| x.clamp(0.0, width as f32);
+    let clamp_y = |y: f32| y.clamp(0.0, height as f32);
+    let x_min = clamp_x(corners.iter().map(|c| c.0).fold(f32::INFINITY, f32::min));
+    let x_max = clamp_x(
+        corners
+            .iter()
+            .map(|c| c.0)
+            .fold(f32::NEG_INFINITY, f32::max),
+    );
+    let y_min = clamp_y(corners.iter().map(|c| c.1).fold(f32::INFINITY, f32::min));
+    let y_max = clamp_y(
+        corners
+            .iter()
+            .map(|c| c.1)
+            .fold(f32::NEG_INFINITY, f32::max),
+    );
+
+    if x_max <= x_min || y_max <= y_min {
+        return None;
+    }
+
+    Some(BoundingBox {
+        coords: [x_min, y_min, x_max - x_min, y_max - y_min],
+        label: bbox.label,
+    })
+}
+
+fn warp_mask(
+    mask: SegmentationMask,
+    raster: &Raster,
+    width: usize,
+    height: usize,
+    inverse: Affine,
+) -> SegmentationMask {
+    let num_pixels = raster.width * raster.height;
+    let channels = mask.mask.len().checked_div(num_pixels).unwrap_or(0);
+
+    let mut values = vec![0; width * height * channels];
+    for y in 0..height {
+        for x in 0..width {
+            let (source_x, source_y) = inverse.apply(x as f32 + 0.5, y as f32 + 0.5);
+            if source_x < 0.0 || source_y < 0.0 {
+                continue;
+            }
+            let (source_x, source_y) = (source_x as usize, source_y as usize);
+            if source_x >= raster.width || source_y >= raster.height {
+                continue;
+            }
+
+            let source = (source_y * raster.width + source_x) * channels;
+            let target = (y * width + x) * channels;
+            values[target..target + channels]
+                .copy_from_slice(&mask.mask[source..source + channels]);
+        }
+    }
+
+    SegmentationMask { mask: values }
+}
+
+/// Resize an image to a fixed size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Resize {
+    width: usize,
+    height: usize,
+}
+
+impl Resize {
+    /// Create a new resize transform.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { width, height }
+    }
+}
+
+impl ImageTransform for Resize {
+    fn apply(&self, item: ImageDatasetItem, _rng: &mut StdRng) -> ImageDatasetItem {
+        let scale = Affine::scale(
+            self.width as f32 / item.width.max(1) as f32,
+            self.height as f32 / item.height.max(1) as f32,
+        );
+        warp(item, self.width, self.height, scale)
+    }
+}
+
+/// Crop a random part of an image and resize it to a fixed size.
+///
+/// The area of the crop is a random fraction of the area of the image, in the `scale` range,
+/// and its aspect ratio is log-uniformly distributed in the `ratio` range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RandomResizedCrop {
+    width: usize,
+    height: usize,
+    scale: (f32, f32),
+    ratio: (f32, f32),
+}
+
+impl RandomResizedCrop {
+    /// Create a new random resized crop, with a scale between 0.08 and 1 and a ratio between
+    /// 3/4 and 4/3.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            scale: (0.08, 1.0),
+            ratio: (3.0 / 4.0, 4.0 / 3.0),
+        }
+    }
+
+    /// Set the range of the fraction of the area of the image kept by the crop.
+    pub fn with_scale(mut self, min: f32, max: f32) -> Self {
+        assert!(
+            0.0 < min && min <= max,
+            "The scale range should be positive"
+        );
+        self.scale = (min, max);
+        self
+    }
+
+    /// Set the range of the aspect ratio of the crop.
+    pub fn with_ratio(mut self, min: f32, max: f32) -> Self {
+        assert!(
+            0.0 < min && min <= max,
+            "The ratio range should be positive"
+        );
+        self.ratio = (min, max);
+        self
+    }
+
+    /// The position and size of the crop.
+    fn crop(&self, width: usize, height: usize, rng: &mut StdRng) -> (f32, f32, f32, f32) {
+        let (width, height) = (width as f32, height as f32);
+        let area = width * height;
+
+        for _ in 0..10 {
+            let target_area = area * rng.random_range(self.scale.0..=self.scale.1);
+            let log_ratio = rng.random_range(self.ratio.0.ln()..=self.ratio.1.ln());
+            let ratio = log_ratio.exp();
+
+            let w = (target_area * ratio).sqrt().round();
+            let h = (target_area / ratio).sqrt().round();
+            if 0.0 < w && w <= width && 0.0 < h && h <= height {
+                let x = rng.random_range(0..=(width - w) as usize) as f32;
+                let y = rng.random_range(0..=(height - h) as usize) as f32;
+                return (x, y, w, h);
+            }
+        }
+
+        // Fallback to a center crop with the closest valid ratio.
+        let ratio = width / height;
+        let (w, h) = if ratio < self.ratio.0 {
+            (width, (width / self.ratio.0).round())
+        } else if ratio > self.ratio.1 {
+            ((height * self.ratio.1).round(), height)
+        } else {
+            (width, height)
+        };
+        (
+            ((width - w) / 2.0).floor(),
+            ((height - h) / 2.0).floor(),
+            w,
+            h,
+        )
+    }
+}
+
+impl ImageTransform for RandomResizedCrop {
+    fn apply(&self, item: ImageDatasetItem, rng: &mut StdRng) -> ImageDatasetItem {
+        let (x, y, w, h) = self.crop(item.width, item.height, rng);
+        let transform = Affine::translation(-x, -y).then(Affine::scale(
+            self.width as f32 / w.max(1.0),
+            self.height as f32 / h.max(1.0),
+        ));
+
+        warp(item, self.width, self.height, transform)
+    }
+}
+
+/// Flip an image horizontally with a probability.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RandomHorizontalFlip {
+    probability: f64,
+}
+
+impl RandomHorizontalFlip {
+    /// Create a new random horizontal flip.
+    pub fn new(probability: f64) -> Self {
+        Self { probability }
+    }
+}
+
+impl ImageTransform for RandomHorizontalFlip {
+    fn apply(&self, item: ImageDatasetItem, rng: &mut StdRng) -> ImageDatasetItem {
+        if !rng.random_bool(self.probability) {
+            return item;
+        }
+
+        let (width, height) = (item.width, item.height);
+        let flip = Affine::scale(-1.0, 1.0).then(Affine::translation(width as f32, 0.0));
+        warp(item, width, height, flip)
+    }
+}
+
+/// Flip an image vertically with a probability.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RandomVerticalFlip {
+    probability: f64,
+}
+
+impl RandomVerticalFlip {
+    /// Create a new random vertical flip.
+    pub fn new(probability: f64) -> Self {
+        Self { probability }
+    }
+}
+
+impl ImageTransform for RandomVerticalFlip {
+    fn apply(&self, item: ImageDatasetItem, rng: &mut StdRng) -> ImageDatasetItem {
+        if !rng.random_bool(self.probability) {
+            return item;
+        }
+
+        let (width, height) = (item.width, item.height);
+        let flip = Affine::scale(1.0, -1.0).then(Affine::translation(0.0, height as f32));
+        warp(item, width, height, flip)
+    }
+}
+
+/// Rotate an image around its center by a random angle between `-degrees` and `degrees`,
+/// keeping its size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RandomRotation {
+    degrees: f32,
+}
+
+impl RandomRotation {
+    /// Create a new random rotation.
+    pub fn new(degrees: f32) -> Self {
+        Self {
+            degrees: degrees.abs(),
+        }
+    }
+}
+
+impl ImageTransform for RandomRotation {
+    fn apply(&self, item: ImageDatasetItem, rng: &mut StdRng) -> ImageDatasetItem {
+        let angle = rng.random_range(-self.degrees..=self.degrees);
+        let (width, height) = (item.width, item.height);
+        warp(
+            item,
+            width,
+            height,
+            Affine::rotation(angle).centered(width, height),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vision::PixelDepth;
+    use rand::SeedableRng;
+
+    fn item(annotation: Annotation) -> ImageDatasetItem {
+        // A 4x2 grayscale image.
+        ImageDatasetItem {
+            image: (0..8).map(|value| PixelDepth::U8(value * 10)).collect(),
+            width: 4,
+            height: 2,
+            annotation,
+            image_path: "image.png".to_string(),
+        }
+    }
+
+    fn pixels(item: &ImageDatasetItem) -> Vec<u8> {
+        item.image
+            .iter()
+            .map(|pixel| u8::try_from(pixel.clone()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_horizontal_flip_transforms_the_annotations() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let boxes = Annotation::BoundingBoxes(vec![BoundingBox {
+            coords: [0.0, 0.0, 1.0, 2.0],
+            label: 1,
+        }]);
+        let flipped = RandomHorizontalFlip::new(1.0).apply(item(boxes), &mut rng);
+
+        assert_eq!(pixels(&flipped), vec![30, 20, 10, 0, 70, 60, 50, 40]);
+        assert_eq!(
+            flipped.annotation,
+            Annotation::BoundingBoxes(vec![BoundingBox {
+                coords: [3.0, 0.0, 1.0, 2.0],
+                label: 1,
+            }])
+        );
+
+        let mask = Annotation::SegmentationMask(SegmentationMask {
+            mask: vec![0, 0, 1, 2, 0, 0, 1, 2],
+        });
+        let flipped = RandomVerticalFlip::new(1.0).apply(item(mask), &mut rng);
+        assert_eq!(pixels(&flipped), vec![40, 50, 60, 70, 0, 10, 20, 30]);
+        assert_eq!(
+            RandomHorizontalFlip::new(1.0)
+                .apply(flipped, &mut rng)
+                .annotation,
+            Annotation::SegmentationMask(SegmentationMask {
+                mask: vec![2, 1, 0, 0, 2, 1, 0, 0],
+            })
+        );
+    }
+
+    #[test]
+    fn test_resize_scales_the_boxes() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let boxes = Annotation::BoundingBoxes(vec![BoundingBox {
+            coords: [1.0, 0.0, 2.0, 1.0],
+            label: 0,
+        }]);
+        let resized = Resize::new(8, 4).apply(item(boxes), &mut rng);
+
+        assert_eq!(resized.image.len(), 32);
+        assert_eq!((resized.width, resized.height), (8, 4));
+        assert_eq!(
+            resized.annotation,
+            Annotation::BoundingBoxes(vec![BoundingBox {
+                coords: [2.0, 0.0, 4.0, 2.0],
+                label: 0,
+            }])
+        );
+    }
+
+    #[test]
+    fn test_random_resized_crop_has_the_target_size() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let crop = RandomResizedCrop::new(3, 3).with_scale(0.5, 1.0);
+
+        for _ in 0..10 {
+            let cropped = crop.apply(item(Annotation::Label(0)), &mut rng);
+            assert_eq!(
+                (cropped.width, cropped.height, cropped.image.len()),
+                (3, 3, 9)
+            );
+        }
+    }
+
+    #[test]
+    fn test_rotation_keeps_the_center() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let item = ImageDatasetItem {
+            image: (0..9).map(|value| PixelDepth::U8(value * 10)).collect(),
+            width: 3,
+            height: 3,
+            annotation: Annotation::Label(0),
+            image_path: "image.png".to_string(),
+        };
+        let rotated = RandomRotation::new(180.0).apply(item, &mut rng);
+
+        assert_eq!(pixels(&rotated)[4], 40);
+    }
+}
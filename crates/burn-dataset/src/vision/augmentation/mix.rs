@@ -0,0 +1,246 @@
+use super::raster::Raster;
+use crate::vision::{Annotation, ImageDatasetItem, PixelDepth};
+use rand::{Rng, rngs::StdRng, seq::SliceRandom};
+use rand_distr::Beta;
+
+/// An image item whose image and label were mixed with another item, with a soft target.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MixedImageItem {
+    /// Image as a vector with a valid image type.
+    pub image: Vec<PixelDepth>,
+
+    /// Image width, in pixels.
+    pub width: usize,
+
+    /// Image height, in pixels.
+    pub height: usize,
+
+    /// The weight of each class, summing to one for single-label items.
+    pub target: Vec<f32>,
+}
+
+/// A transformation of a batch of classification items, mixing the items with each other.
+///
+/// It is meant to be used when batching the items, after the
+/// [image transforms](super::ImageTransform) of the items.
+pub trait BatchImageTransform: Send + Sync {
+    /// Mix the items of a batch, which should have images of the same size and
+    /// [label](Annotation::Label) or [multi-label](Annotation::MultiLabel) annotations.
+    fn apply(
+        &self,
+        items: Vec<ImageDatasetItem>,
+        num_classes: usize,
+        rng: &mut StdRng,
+    ) -> Vec<MixedImageItem>;
+}
+
+/// [MixUp](https://arxiv.org/abs/1710.09412): blend every image of a batch with another image of
+/// the batch, and their targets with the same weight.
+///
+/// The weight is drawn once per batch from a `Beta(alpha, alpha)` distribution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MixUp {
+    alpha: f32,
+}
+
+impl MixUp {
+    /// Create a new MixUp.
+    pub fn new(alpha: f32) -> Self {
+        assert!(alpha > 0.0, "The alpha of MixUp should be positive");
+        Self { alpha }
+    }
+}
+
+impl BatchImageTransform for MixUp {
+    fn apply(
+        &self,
+        items: Vec<ImageDatasetItem>,
+        num_classes: usize,
+        rng: &mut StdRng,
+    ) -> Vec<MixedImageItem> {
+        let weight = sample_weight(self.alpha, rng);
+        let batch = MixBatch::new(items, num_classes, rng);
+
+        batch.mix(|other, raster| {
+            let pixels = raster.data.iter_mut().zip(&batch.rasters[other].data);
+            for (value, other) in pixels {
+                *value = weight * *value + (1.0 - weight) * other;
+            }
+            weight
+        })
+    }
+}
+
+/// [CutMix](https://arxiv.org/abs/1905.04899): paste a random rectangle of another image of the
+/// batch on every image, and mix their targets with the area of the rectangle.
+///
+/// The area of the rectangle is drawn once per batch, as a fraction `1 - w` of the image where
+/// `w` follows a `Beta(alpha, alpha)` distribution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CutMix {
+    alpha: f32,
+}
+
+impl CutMix {
+    /// Create a new CutMix.
+    pub fn new(alpha: f32) -> Self {
+        assert!(alpha > 0.0, "The alpha of CutMix should be positive");
+        Self { alpha }
+    }
+}
+
+impl BatchImageTransform for CutMix {
+    fn apply(
+        &self,
+        items: Vec<ImageDatasetItem>,
+        num_classes: usize,
+        rng: &mut StdRng,
+    ) -> Vec<MixedImageItem> {
+        let weight = sample_weight(self.alpha, rng);
+        let batch = MixBatch::new(items, num_classes, rng);
+        let (width, height) = (batch.width, batch.height);
+
+        let ratio = (1.0 - weight).sqrt();
+        let (cut_width, cut_height) = (width as f32 * ratio, height as f32 * ratio);
+        let center_x = rng.random_range(0.0..width.max(1) as f32);
+        let center_y = rng.random_range(0.0..height.max(1) as f32);
+        let bound = |center: f32, size: f32, max: usize| {
+            let start = (center - size / 2.0).round().clamp(0.0, max as f32) as usize;
+            let end = (center + size / 2.0).round().clamp(0.0, max as f32) as usize;
+            start..end
+        };
+        let columns = bound(center_x, cut_width, width);
+        let rows = bound(center_y, cut_height, height);
+        let area = columns.len() * rows.len();
+        let weight = 1.0 - area as f32 / (width * height).max(1) as f32;
+
+        batch.mix(|other, raster| {
+            let channels = raster.channels;
+            for y in rows.clone() {
+                let start = (y * width + columns.start) * channels;
+                let end = (y * width + columns.end) * channels;
+                raster.data[start..end].copy_from_slice(&batch.rasters[other].data[start..end]);
+            }
+            weight
+        })
+    }
+}
+
+fn sample_weight(alpha: f32, rng: &mut StdRng) -> f32 {
+    rng.sample(Beta::new(alpha, alpha).expect("Alpha should be positive"))
+}
+
+/// The images and the targets of a batch, with the item mixed with each item.
+struct MixBatch {
+    rasters: Vec<Raster>,
+    targets: Vec<Vec<f32>>,
+    others: Vec<usize>,
+    width: usize,
+    height: usize,
+}
+
+impl MixBatch {
+    fn new(items: Vec<ImageDatasetItem>, num_classes: usize, rng: &mut StdRng) -> Self {
+        let (width, height) = items
+            .first()
+            .map(|item| (item.width, item.height))
+            .unwrap_or_default();
+        assert!(
+            items
+                .iter()
+                .all(|item| item.width == width && item.height == height),
+            "The images of a mixed batch should have the same size"
+        );
+
+        let mut others = (0..items.len()).collect::<Vec<_>>();
+        others.shuffle(rng);
+
+        Self {
+            rasters: items.iter().map(Raster::new).collect(),
+            targets: items
+                .iter()
+                .map(|item| target(&item.annotation, num_classes))
+                .collect(),
+            others,
+            width,
+            height,
+        }
+    }
+
+    /// Mix the images with a function of the index of the other item, returning the weight of
+    /// the target of the item, the weight of the other target being its complement.
+    fn mix<F>(&self, mut func: F) -> Vec<MixedImageItem>
+    where
+        F: FnMut(usize, &mut Raster) -> f32,
+    {
+        self.others
+            .iter()
+            .enumerate()
+            .map(|(index, other)| {
+                let mut raster = self.rasters[index].clone();
+                let weight = func(*other, &mut raster);
+                let target = self.targets[index]
+                    .iter()
+                    .zip(&self.targets[*other])
+                    .map(|(target, other)| weight * target + (1.0 - weight) * other)
+                    .collect();
+
+                MixedImageItem {
+                    image: raster.into_pixels(),
+                    width: self.width,
+                    height: self.height,
+                    target,
+                }
+            })
+            .collect()
+    }
+}
+
+fn target(annotation: &Annotation, num_classes: usize) -> Vec<f32> {
+    let mut target = vec![0.0; num_classes];
+    match annotation {
+        Annotation::Label(label) => target[*label] = 1.0,
+        Annotation::MultiLabel(labels) => labels.iter().for_each(|label| target[*label] = 1.0),
+        _ => panic!("Only the classification items can be mixed"),
+    }
+    target
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn item(value: u8, label: usize) -> ImageDatasetItem {
+        ImageDatasetItem {
+            image: vec![PixelDepth::U8(value); 16],
+            width: 4,
+            height: 4,
+            annotation: Annotation::Label(label),
+            image_path: format!("{label}.png"),
+        }
+    }
+
+    #[test]
+    fn test_mixed_targets_match_the_images() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let items = || vec![item(0, 0), item(255, 1)];
+
+        for mixed in [
+            MixUp::new(1.0).apply(items(), 2, &mut rng),
+            CutMix::new(1.0).apply(items(), 2, &mut rng),
+        ] {
+            for item in mixed {
+                assert!((item.target.iter().sum::<f32>() - 1.0).abs() < 1e-6);
+                // The weight of the second class is the fraction of white in the image.
+                let white = item
+                    .image
+                    .iter()
+                    .map(|pixel| u8::try_from(pixel.clone()).unwrap() as f32 / 255.0)
+                    .sum::<f32>()
+                    / 16.0;
+                assert!((white - item.target[1]).abs() < 0.01);
+            }
+        }
+    }
+}
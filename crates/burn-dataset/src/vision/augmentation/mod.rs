@@ -0,0 +1,12 @@
+mod auto;
+mod base;
+mod color;
+mod geometric;
+mod mix;
+mod raster;
+
+pub use auto::*;
+pub use base::*;
+pub use color::*;
+pub use geometric::*;
+pub use mix::*;
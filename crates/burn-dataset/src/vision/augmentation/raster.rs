@@ -0,0 +1,147 @@
+use crate::vision::{ImageDatasetItem, PixelDepth};
+
+/// The pixel type of an image.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Depth {
+    U8,
+    U16,
+    F32,
+}
+
+/// An image with its values as floats, between 0 and 1 for the integer pixel types, with the
+/// channels of a pixel next to each other.
+#[derive(Debug, Clone)]
+pub(crate) struct Raster {
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+    pub(crate) channels: usize,
+    pub(crate) depth: Depth,
+    pub(crate) data: Vec<f32>,
+}
+
+impl Raster {
+    pub(crate) fn new(item: &ImageDatasetItem) -> Self {
+        let depth = match item.image.first() {
+            Some(PixelDepth::U16(_)) => Depth::U16,
+            Some(PixelDepth::F32(_)) => Depth::F32,
+            _ => Depth::U8,
+        };
+        let data = item
+            .image
+            .iter()
+            .map(|value| match value {
+                PixelDepth::U8(value) => *value as f32 / u8::MAX as f32,
+                PixelDepth::U16(value) => *value as f32 / u16::MAX as f32,
+                PixelDepth::F32(value) => *value,
+            })
+            .collect::<Vec<_>>();
+
+        let num_pixels = item.width * item.height;
+        let channels = data.len().checked_div(num_pixels).unwrap_or(0);
+        assert_eq!(
+            channels * num_pixels,
+            data.len(),
+            "The image of {} should have {}x{} pixels",
+            item.image_path,
+            item.width,
+            item.height
+        );
+
+        Self {
+            width: item.width,
+            height: item.height,
+            channels,
+            depth,
+            data,
+        }
+    }
+
+    pub(crate) fn into_pixels(self) -> Vec<PixelDepth> {
+        let depth = self.depth;
+        self.data
+            .into_iter()
+            .map(|value| match depth {
+                Depth::U8 => PixelDepth::U8((value.clamp(0.0, 1.0) * u8::MAX as f32).round() as u8),
+                Depth::U16 => {
+                    PixelDepth::U16((value.clamp(0.0, 1.0) * u16::MAX as f32).round() as u16)
+                }
+                Depth::F32 => PixelDepth::F32(value),
+            })
+            .collect()
+    }
+
+    /// The number of color channels, without the alpha channel.
+    pub(crate) fn color_channels(&self) -> usize {
+        match self.channels {
+            2 | 4 => self.channels - 1,
+            channels => channels,
+        }
+    }
+
+    pub(crate) fn get(&self, x: usize, y: usize, channel: usize) -> f32 {
+        self.data[(y * self.width + x) * self.channels + channel]
+    }
+
+    /// The bilinear interpolation of a channel at a position, the pixel centers being at integer
+    /// positions, or zero outside of the image.
+    pub(crate) fn bilinear(&self, x: f32, y: f32, channel: usize) -> f32 {
+        let (width, height) = (self.width as f32, self.height as f32);
+        if x < -0.5 || y < -0.5 || x > width - 0.5 || y > height - 0.5 {
+            return 0.0;
+        }
+
+        let x = x.clamp(0.0, width - 1.0);
+        let y = y.clamp(0.0, height - 1.0);
+        let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+        let (x1, y1) = ((x0 + 1).min(self.width - 1), (y0 + 1).min(self.height - 1));
+        let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+
+        let top = self.get(x0, y0, channel) * (1.0 - fx) + self.get(x1, y0, channel) * fx;
+        let bottom = self.get(x0, y1, channel) * (1.0 - fx) + self.get(x1, y1, channel) * fx;
+        top * (1.0 - fy) + bottom * fy
+    }
+
+    /// Apply a function to the color channels of every pixel, clamping the values between 0
+    /// and 1.
+    pub(crate) fn map_colors<F: FnMut(&mut [f32])>(&mut self, mut func: F) {
+        let color_channels = self.color_channels();
+        for pixel in self.data.chunks_mut(self.channels) {
+            let colors = &mut pixel[..color_channels];
+            func(colors);
+            colors
+                .iter_mut()
+                .for_each(|value| *value = value.clamp(0.0, 1.0));
+        }
+    }
+
+    /// The luminance of a pixel, or its value for a grayscale image.
+    pub(crate) fn gray(colors: &[f32]) -> f32 {
+        match colors {
+            [red, green, blue, ..] => 0.299 * red + 0.587 * green + 0.114 * blue,
+            [value, ..] => *value,
+            [] => 0.0,
+        }
+    }
+
+    /// The mean luminance of the image.
+    pub(crate) fn mean_gray(&self) -> f32 {
+        let color_channels = self.color_channels();
+        let num_pixels = (self.width * self.height).max(1);
+        self.data
+            .chunks(self.channels.max(1))
+            .map(|pixel| Self::gray(&pixel[..color_channels]))
+            .sum::<f32>()
+            / num_pixels as f32
+    }
+}
+
+/// Transform the image of an item without changing its annotation.
+pub(crate) fn map_raster<F: FnOnce(&mut Raster)>(
+    mut item: ImageDatasetItem,
+    func: F,
+) -> ImageDatasetItem {
+    let mut raster = Raster::new(&item);
+    func(&mut raster);
+    item.image = raster.into_pixels();
+    item
+}
@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+
+use super::{Annotation, ImageDatasetItem, ImageLoaderError, PixelDepth};
+use crate::{
+    Dataset, InMemDataset,
+    dataset::TarEntries,
+    transform::{Mapper, MapperDataset},
+};
+
+const WIDTH: usize = 32;
+const HEIGHT: usize = 32;
+const CHANNELS: usize = 3;
+const IMAGE_SIZE: usize = WIDTH * HEIGHT * CHANNELS;
+
+/// The variants of the CIFAR dataset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CifarVariant {
+    /// CIFAR-10, with 10 classes.
+    Cifar10,
+    /// CIFAR-100, with the 100 fine classes.
+    Cifar100,
+    /// CIFAR-100, with the 20 coarse superclasses.
+    Cifar100Coarse,
+}
+
+impl CifarVariant {
+    /// The batch files of a split.
+    fn batch_files(&self, split: &str) -> Vec<&'static str> {
+        match (self, split) {
+            (CifarVariant::Cifar10, "train") => vec![
+                "data_batch_1.bin",
+                "data_batch_2.bin",
+                "data_batch_3.bin",
+                "data_batch_4.bin",
+                "data_batch_5.bin",
+            ],
+            (CifarVariant::Cifar10, _) => vec!["test_batch.bin"],
+            (_, "train") => vec!["train.bin"],
+            (_, _) => vec!["test.bin"],
+        }
+    }
+
+    /// The file with the class names, one per line.
+    fn classes_file(&self) -> &'static str {
+        match self {
+            CifarVariant::Cifar10 => "batches.meta.txt",
+            CifarVariant::Cifar100 => "fine_label_names.txt",
+            CifarVariant::Cifar100Coarse => "coarse_label_names.txt",
+        }
+    }
+
+    /// The number of label bytes preceding the image of a record, and the index of the label.
+    fn labels(&self) -> (usize, usize) {
+        match self {
+            CifarVariant::Cifar10 => (1, 0),
+            // CIFAR-100 records start with the coarse label, followed by the fine label.
+            CifarVariant::Cifar100 => (2, 1),
+            CifarVariant::Cifar100Coarse => (2, 0),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CifarItemRaw {
+    image_bytes: Vec<u8>,
+    label: usize,
+    source: String,
+}
+
+struct BytesToImage;
+
+impl Mapper<CifarItemRaw, ImageDatasetItem> for BytesToImage {
+    /// Convert a raw CIFAR item (channel-major image bytes) to an image item.
+    fn map(&self, item: &CifarItemRaw) -> ImageDatasetItem {
+        let num_pixels = WIDTH * HEIGHT;
+        let image = (0..num_pixels)
+            .flat_map(|pixel| {
+                (0..CHANNELS).map(move |channel| {
+                    PixelDepth::U8(item.image_bytes[channel * num_pixels + pixel])
+                })
+            })
+            .collect();
+
+        ImageDatasetItem {
+            image,
+            width: WIDTH,
+            height: HEIGHT,
+            annotation: Annotation::Label(item.label),
+            image_path: item.source.clone(),
+        }
+    }
+}
+
+type MappedDataset = MapperDataset<InMemDataset<CifarItemRaw>, BytesToImage, CifarItemRaw>;
+
+/// The [CIFAR-10 and CIFAR-100](https://www.cs.toronto.edu/~kriz/cifar.html) datasets consist of
+/// 60,000 32x32 color images in 10 or 100 classes, with 50,000 training images and 10,000 test
+/// images. The 100 classes of CIFAR-100 are grouped into 20 superclasses.
+///
+/// The dataset is read from the binary version archive (`cifar-10-binary.tar.gz` or
+/// `cifar-100-binary.tar.gz`) or from its extracted directory, without downloading anything.
+/// The images are loaded in memory.
+pub struct CifarDataset {
+    dataset: MappedDataset,
+    classes: Vec<String>,
+}
+
+impl Dataset<ImageDatasetItem> for CifarDataset {
+    fn get(&self, index: usize) -> Option<ImageDatasetItem> {
+        self.dataset.get(index)
+    }
+
+    fn len(&self) -> usize {
+        self.dataset.len()
+    }
+}
+
+impl CifarDataset {
+    /// Creates a new train dataset from the archive or the extracted directory at the given path.
+    pub fn train<P: AsRef<Path>>(path: P, variant: CifarVariant) -> Result<Self, ImageLoaderError> {
+        Self::new(path.as_ref(), variant, "train")
+    }
+
+    /// Creates a new test dataset from the archive or the extracted directory at the given path.
+    pub fn test<P: AsRef<Path>>(path: P, variant: CifarVariant) -> Result<Self, ImageLoaderError> {
+        Self::new(path.as_ref(), variant, "test")
+    }
+
+    /// The class names, in the order of the labels.
+    pub fn classes(&self) -> &[String] {
+        &self.classes
+    }
+
+    fn new(path: &Path, variant: CifarVariant, split: &str) -> Result<Self, ImageLoaderError> {
+        let batch_files = variant.batch_files(split);
+        let mut names = batch_files.clone();
+        names.push(variant.classes_file());
+        let mut files = read_files(path, &names)?;
+
+        let classes = String::from_utf8_lossy(&files[variant.classes_file()])
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+
+        let (num_label_bytes, label_index) = variant.labels();
+        let record_size = num_label_bytes + IMAGE_SIZE;
+        let mut items = Vec::new();
+        for name in batch_files {
+            let bytes = files.remove(name).unwrap_or_default();
+            if bytes.len() % record_size != 0 {
+                return Err(ImageLoaderError::ParsingError(format!(
+                    "Invalid size of the CIFAR batch {name}"
+                )));
+            }
+
+            for (index, record) in bytes.chunks_exact(record_size).enumerate() {
+                let label = record[label_index] as usize;
+                if label >= classes.len() {
+                    return Err(ImageLoaderError::ParsingError(format!(
+                        "Invalid label {label} in the CIFAR batch {name}"
+                    )));
+                }
+
+                items.push(CifarItemRaw {
+                    image_bytes: record[num_label_bytes..].to_vec(),
+                    label,
+                    source: format!("{}[{index}]", path.join(name).display()),
+                });
+            }
+        }
+
+        let dataset = MapperDataset::new(InMemDataset::new(items), BytesToImage);
+
+        Ok(Self { dataset, classes })
+    }
+}
+
+/// Read the files with the given names from a directory, or from a tar archive, optionally
+/// gzip-compressed, where they can be nested in a directory.
+fn read_files(path: &Path, names: &[&str]) -> Result<HashMap<String, Vec<u8>>, ImageLoaderError> {
+    let io_error = |err: std::io::Error| {
+        ImageLoaderError::IOError(format!("Failed to read {}: {err}", path.display()))
+    };
+
+    let mut files = HashMap::new();
+    if path.is_dir() {
+        for name in names {
+            let content = fs::read(path.join(name)).map_err(io_error)?;
+            files.insert(name.to_string(), content);
+        }
+        return Ok(files);
+    }
+
+    let file = BufReader::new(File::open(path).map_err(io_error)?);
+    let reader: Box<dyn Read> = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("gz" | "tgz") => Box::new(GzDecoder::new(file)),
+        _ => Box::new(file),
+    };
+
+    for entry in TarEntries::new(reader) {
+        let (entry_path, content) = entry.map_err(io_error)?;
+        let name = entry_path.rsplit('/').next().unwrap_or_default();
+        if names.contains(&name) {
+            files.insert(name.to_string(), content);
+        }
+    }
+
+    match names.iter().find(|name| !files.contains_key(**name)) {
+        Some(name) => Err(ImageLoaderError::IOError(format!(
+            "File {name} not found in {}",
+            path.display()
+        ))),
+        None => Ok(files),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{Compression, write::GzEncoder};
+    use std::io::Write;
+
+    fn record(labels: &[u8], value: u8) -> Vec<u8> {
+        let mut record = labels.to_vec();
+        // Red, green and blue planes
+        record.extend([value; WIDTH * HEIGHT]);
+        record.extend([0; WIDTH * HEIGHT]);
+        record.extend([255; WIDTH * HEIGHT]);
+        record
+    }
+
+    fn tar_gz(files: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let mut archive = Vec::new();
+        for (name, content) in files {
+            let mut header = [0; 512];
+            header[..name.len()].copy_from_slice(name.as_bytes());
+            header[124..135].copy_from_slice(format!("{:011o}", content.len()).as_bytes());
+            header[156] = b'0';
+            header[257..263].copy_from_slice(b"ustar\0");
+            archive.extend(header);
+            archive.extend(content);
+            archive.resize(archive.len().next_multiple_of(512), 0);
+        }
+        archive.extend([0; 1024]);
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+        encoder.write_all(&archive).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_cifar100_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cifar-100-binary.tar.gz");
+        let train = [record(&[1, 3], 10), record(&[0, 2], 20)].concat();
+        let archive = tar_gz(&[
+            ("cifar-100-binary/train.bin", train),
+            ("cifar-100-binary/test.bin", record(&[0, 0], 30)),
+            (
+                "cifar-100-binary/fine_label_names.txt",
+                b"a\nb\nc\nd\n".to_vec(),
+            ),
+            (
+                "cifar-100-binary/coarse_label_names.txt",
+                b"x\ny\n".to_vec(),
+            ),
+        ]);
+        fs::write(&path, archive).unwrap();
+
+        let fine = CifarDataset::train(&path, CifarVariant::Cifar100).unwrap();
+        assert_eq!(fine.len(), 2);
+        assert_eq!(fine.classes(), ["a", "b", "c", "d"]);
+
+        let item = fine.get(1).unwrap();
+        assert_eq!(item.annotation, Annotation::Label(2));
+        assert_eq!((item.width, item.height), (WIDTH, HEIGHT));
+        assert_eq!(
+            &item.image[..6],
+            &[
+                PixelDepth::U8(20),
+                PixelDepth::U8(0),
+                PixelDepth::U8(255),
+                PixelDepth::U8(20),
+                PixelDepth::U8(0),
+                PixelDepth::U8(255),
+            ]
+        );
+
+        let coarse = CifarDataset::train(&path, CifarVariant::Cifar100Coarse).unwrap();
+        assert_eq!(coarse.get(0).unwrap().annotation, Annotation::Label(1));
+        assert_eq!(
+            CifarDataset::test(&path, CifarVariant::Cifar100)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(CifarDataset::train(&path, CifarVariant::Cifar10).is_err());
+    }
+
+    #[test]
+    fn test_cifar10_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let classes = (0..10)
+            .map(|class| format!("class{class}\n"))
+            .collect::<String>();
+        fs::write(dir.path().join("batches.meta.txt"), classes).unwrap();
+        fs::write(dir.path().join("test_batch.bin"), record(&[7], 0)).unwrap();
+
+        let dataset = CifarDataset::test(dir.path(), CifarVariant::Cifar10).unwrap();
+        assert_eq!(dataset.len(), 1);
+        assert_eq!(dataset.classes()[7], "class7");
+        assert_eq!(dataset.get(0).unwrap().annotation, Annotation::Label(7));
+    }
+}
@@ -0,0 +1,287 @@
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+
+use super::{Annotation, ImageDatasetItem, ImageLoaderError, PixelDepth};
+use crate::{
+    Dataset, InMemDataset,
+    transform::{Mapper, MapperDataset},
+};
+
+const TRAIN_IMAGES: &str = "train-images-idx3-ubyte";
+const TRAIN_LABELS: &str = "train-labels-idx1-ubyte";
+const TEST_IMAGES: &str = "t10k-images-idx3-ubyte";
+const TEST_LABELS: &str = "t10k-labels-idx1-ubyte";
+
+const WIDTH: usize = 28;
+const HEIGHT: usize = 28;
+const IMAGES_MAGIC: u32 = 2051;
+const LABELS_MAGIC: u32 = 2049;
+
+/// The class names of Fashion-MNIST, in the order of the labels.
+pub const FASHION_MNIST_CLASSES: [&str; 10] = [
+    "T-shirt/top",
+    "Trouser",
+    "Pullover",
+    "Dress",
+    "Coat",
+    "Sandal",
+    "Shirt",
+    "Sneaker",
+    "Bag",
+    "Ankle boot",
+];
+
+#[derive(Debug, Clone)]
+struct FashionMnistItemRaw {
+    image_bytes: Vec<u8>,
+    label: usize,
+    source: String,
+}
+
+struct BytesToImage;
+
+impl Mapper<FashionMnistItemRaw, ImageDatasetItem> for BytesToImage {
+    /// Convert a raw Fashion-MNIST item (image bytes) to a grayscale image item.
+    fn map(&self, item: &FashionMnistItemRaw) -> ImageDatasetItem {
+        ImageDatasetItem {
+            image: item
+                .image_bytes
+                .iter()
+                .map(|v| PixelDepth::U8(*v))
+                .collect(),
+            width: WIDTH,
+            height: HEIGHT,
+            annotation: Annotation::Label(item.label),
+            image_path: item.source.clone(),
+        }
+    }
+}
+
+type MappedDataset =
+    MapperDataset<InMemDataset<FashionMnistItemRaw>, BytesToImage, FashionMnistItemRaw>;
+
+/// The [Fashion-MNIST](https://github.com/zalandoresearch/fashion-mnist) dataset consists of
+/// 70,000 28x28 grayscale images of clothes in 10 classes, with 60,000 training images and
+/// 10,000 test images. It is a drop-in replacement of MNIST, with the same file format.
+///
+/// The dataset is read from a local directory containing the `*-ubyte.gz` files, or their
+/// decompressed version, without downloading anything. The images are loaded in memory.
+pub struct FashionMnistDataset {
+    dataset: MappedDataset,
+}
+
+impl Dataset<ImageDatasetItem> for FashionMnistDataset {
+    fn get(&self, index: usize) -> Option<ImageDatasetItem> {
+        self.dataset.get(index)
+    }
+
+    fn len(&self) -> usize {
+        self.dataset.len()
+    }
+}
+
+impl FashionMnistDataset {
+    /// Creates a new train dataset from the files in the given directory.
+    pub fn train<P: AsRef<Path>>(root: P) -> Result<Self, ImageLoaderError> {
+        Self::new(root.as_ref(), TRAIN_IMAGES, TRAIN_LABELS)
+    }
+
+    /// Creates a new test dataset from the files in the given directory.
+    pub fn test<P: AsRef<Path>>(root: P) -> Result<Self, ImageLoaderError> {
+        Self::new(root.as_ref(), TEST_IMAGES, TEST_LABELS)
+    }
+
+    fn new(root: &Path, images: &str, labels: &str) -> Result<Self, ImageLoaderError> {
+        let (images_path, images) = read_idx(root, images)?;
+        let (labels_path, labels) = read_idx(root, labels)?;
+
+        let image_size = WIDTH * HEIGHT;
+        let (header, images) = parse_idx(&images_path, &images, IMAGES_MAGIC, image_size)?;
+        if header[2..] != [HEIGHT as u32, WIDTH as u32] {
+            return Err(ImageLoaderError::ParsingError(format!(
+                "Expected {HEIGHT}x{WIDTH} images in {}",
+                images_path.display()
+            )));
+        }
+        let (_, labels) = parse_idx(&labels_path, &labels, LABELS_MAGIC, 1)?;
+
+        if images.len() != labels.len() * image_size {
+            return Err(ImageLoaderError::ParsingError(format!(
+                "The {} images don't match the {} labels",
+                images.len() / image_size,
+                labels.len()
+            )));
+        }
+        if let Some(label) = labels
+            .iter()
+            .find(|label| **label as usize >= FASHION_MNIST_CLASSES.len())
+        {
+            return Err(ImageLoaderError::ParsingError(format!(
+                "Invalid label {label} in {}",
+                labels_path.display()
+            )));
+        }
+
+        let items = images
+            .chunks_exact(image_size)
+            .zip(labels)
+            .enumerate()
+            .map(|(index, (image_bytes, label))| FashionMnistItemRaw {
+                image_bytes: image_bytes.to_vec(),
+                label: *label as usize,
+                source: format!("{}[{index}]", images_path.display()),
+            })
+            .collect();
+
+        let dataset = MapperDataset::new(InMemDataset::new(items), BytesToImage);
+
+        Ok(Self { dataset })
+    }
+}
+
+/// Validate the header of an IDX file, returning its values and the items, of `item_size` bytes.
+///
+/// The header starts with the magic number of the file, whose last byte is the number of
+/// dimensions, followed by the size of each dimension: the number of items and, for the images,
+/// their number of rows and columns.
+fn parse_idx<'a>(
+    path: &Path,
+    bytes: &'a [u8],
+    magic: u32,
+    item_size: usize,
+) -> Result<(Vec<u32>, &'a [u8]), ImageLoaderError> {
+    let header_size = 4 * (1 + (magic & 0xff) as usize);
+    let error =
+        |msg: &str| ImageLoaderError::ParsingError(format!("{msg} in IDX file {}", path.display()));
+
+    let header = bytes
+        .get(..header_size)
+        .ok_or_else(|| error("Truncated header"))?
+        .chunks_exact(4)
+        .map(|value| u32::from_be_bytes([value[0], value[1], value[2], value[3]]))
+        .collect::<Vec<_>>();
+    if header[0] != magic {
+        return Err(error(&format!(
+            "Invalid magic number {}, expected {magic},",
+            header[0]
+        )));
+    }
+
+    let items = &bytes[header_size..];
+    if items.len() != header[1] as usize * item_size {
+        return Err(error(&format!(
+            "Expected {} items of {item_size} bytes",
+            header[1]
+        )));
+    }
+
+    Ok((header, items))
+}
+
+/// Read an IDX file, decompressing it when only its gzip version is available.
+fn read_idx(root: &Path, name: &str) -> Result<(PathBuf, Vec<u8>), ImageLoaderError> {
+    let path = root.join(name);
+    let gz_path = root.join(format!("{name}.gz"));
+    let io_error = |path: &Path, err: std::io::Error| {
+        ImageLoaderError::IOError(format!("Failed to read {}: {err}", path.display()))
+    };
+
+    if path.exists() {
+        let bytes = fs::read(&path).map_err(|err| io_error(&path, err))?;
+        return Ok((path, bytes));
+    }
+
+    let file = fs::File::open(&gz_path).map_err(|err| io_error(&gz_path, err))?;
+    let mut bytes = Vec::new();
+    GzDecoder::new(file)
+        .read_to_end(&mut bytes)
+        .map_err(|err| io_error(&gz_path, err))?;
+
+    Ok((gz_path, bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{Compression, write::GzEncoder};
+    use std::io::Write;
+
+    fn idx_header(values: &[u32]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_be_bytes())
+            .collect()
+    }
+
+    fn images_file(num_images: u32) -> Vec<u8> {
+        let mut images = idx_header(&[IMAGES_MAGIC, num_images, HEIGHT as u32, WIDTH as u32]);
+        for index in 0..num_images {
+            images.extend([index as u8 + 1; WIDTH * HEIGHT]);
+        }
+        images
+    }
+
+    fn labels_file(labels: &[u8]) -> Vec<u8> {
+        let mut file = idx_header(&[LABELS_MAGIC, labels.len() as u32]);
+        file.extend(labels);
+        file
+    }
+
+    #[test]
+    fn test_fashion_mnist_gzip_files() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+        encoder.write_all(&images_file(2)).unwrap();
+        let path = dir.path().join(format!("{TEST_IMAGES}.gz"));
+        fs::write(path, encoder.finish().unwrap()).unwrap();
+        fs::write(dir.path().join(TEST_LABELS), labels_file(&[9, 3])).unwrap();
+
+        let dataset = FashionMnistDataset::test(dir.path()).unwrap();
+        assert_eq!(dataset.len(), 2);
+
+        let item = dataset.get(1).unwrap();
+        assert_eq!(item.annotation, Annotation::Label(3));
+        assert_eq!(FASHION_MNIST_CLASSES[3], "Dress");
+        assert_eq!(item.image, vec![PixelDepth::U8(2); WIDTH * HEIGHT]);
+        assert!(FashionMnistDataset::train(dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_fashion_mnist_invalid_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let images_path = dir.path().join(TEST_IMAGES);
+        let labels_path = dir.path().join(TEST_LABELS);
+        let is_invalid = || {
+            matches!(
+                FashionMnistDataset::test(dir.path()),
+                Err(ImageLoaderError::ParsingError(_))
+            )
+        };
+
+        // The labels file has the magic number of the images.
+        fs::write(&images_path, images_file(2)).unwrap();
+        let mut labels = labels_file(&[1, 2]);
+        labels[..4].copy_from_slice(&IMAGES_MAGIC.to_be_bytes());
+        fs::write(&labels_path, labels).unwrap();
+        assert!(is_invalid());
+
+        // The header counts one more image than the file contains.
+        let mut images = images_file(2);
+        images[4..8].copy_from_slice(&3u32.to_be_bytes());
+        fs::write(&images_path, images).unwrap();
+        fs::write(&labels_path, labels_file(&[1, 2])).unwrap();
+        assert!(is_invalid());
+
+        // The labels are out of the classes.
+        fs::write(&images_path, images_file(2)).unwrap();
+        fs::write(&labels_path, labels_file(&[1, 10])).unwrap();
+        assert!(is_invalid());
+
+        fs::write(&labels_path, labels_file(&[1, 9])).unwrap();
+        assert_eq!(FashionMnistDataset::test(dir.path()).unwrap().len(), 2);
+    }
+}
@@ -12,6 +12,13 @@ use thiserror::Error;
 
 const SUPPORTED_FILES: [&str; 4] = ["bmp", "jpg", "jpeg", "png"];
 const BBOX_MIN_NUM_VALUES: usize = 4;
+const YOLO_NUM_VALUES: usize = 5;
+const CITYSCAPES_IMAGE_SUFFIX: &str = "_leftImg8bit";
+const CITYSCAPES_LABEL_SUFFIX: &str = "_gtFine_labelIds";
+
+/// The class of the void pixels of the Cityscapes `_gtFine_trainIds` label images, to ignore in
+/// the losses and the metrics (e.g. with the ignore index of the segmentation metrics).
+pub const CITYSCAPES_IGNORE_INDEX: usize = 255;
 
 /// Image data type.
 #[derive(Debug, Clone, PartialEq)]
@@ -76,6 +83,7 @@ pub enum Annotation {
 /// Segmentation mask annotation.
 /// For semantic segmentation, a mask has a single channel (C = 1).
 /// For instance segmentation, there may be multiple masks per image (C >= 1).
+/// The channels are interleaved like the image pixels, in `[height, width, C]` order.
 #[derive(Debug, Clone, PartialEq)]
 pub struct SegmentationMask {
     /// Segmentation mask.
@@ -93,11 +101,21 @@ pub struct BoundingBox {
 }
 
 /// Image dataset item.
+///
+/// Items are created with [new](ImageDatasetItem::new), so that fields can be added without
+/// breaking the code building them.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct ImageDatasetItem {
     /// Image as a vector with a valid image type.
     pub image: Vec<PixelDepth>,
 
+    /// Image width, in pixels.
+    pub width: usize,
+
+    /// Image height, in pixels.
+    pub height: usize,
+
     /// Annotation for the image.
     pub annotation: Annotation,
 
@@ -105,6 +123,25 @@ pub struct ImageDatasetItem {
     pub image_path: String,
 }
 
+impl ImageDatasetItem {
+    /// Create an item from the pixels of an image of the given size.
+    pub fn new(
+        image: Vec<PixelDepth>,
+        width: usize,
+        height: usize,
+        annotation: Annotation,
+        image_path: String,
+    ) -> Self {
+        Self {
+            image,
+            width,
+            height,
+            annotation,
+            image_path,
+        }
+    }
+}
+
 /// Raw annotation types.
 #[derive(Deserialize, Serialize, Debug, Clone)]
 enum AnnotationRaw {
@@ -112,6 +149,20 @@ enum AnnotationRaw {
     MultiLabel(Vec<String>),
     BoundingBoxes(Vec<BoundingBox>),
     SegmentationMask(PathBuf),
+    InstanceMasks {
+        width: usize,
+        height: usize,
+        instances: Vec<(usize, SegmentationRaw)>,
+    },
+}
+
+/// Raw object segmentation in the COCO format.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+enum SegmentationRaw {
+    /// Polygons as lists of `[x1, y1, x2, y2, ...]` pixel coordinates.
+    Polygons(Vec<Vec<f32>>),
+    /// Run-length encoding of the mask in column-major order, starting with the background.
+    Rle(Vec<usize>),
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -163,6 +214,68 @@ fn segmentation_mask_to_vec_usize(mask_path: &PathBuf) -> Vec<usize> {
     img_vec
 }
 
+/// Rasterize the instance segmentations to a mask with one channel per instance, holding the
+/// label of the instance plus one on its pixels and zero elsewhere.
+fn instance_masks_to_vec_usize(
+    width: usize,
+    height: usize,
+    instances: &[(usize, SegmentationRaw)],
+) -> Vec<usize> {
+    let channels = instances.len();
+    let mut mask = vec![0; width * height * channels];
+
+    for (channel, (label, segmentation)) in instances.iter().enumerate() {
+        let mut set = |pixel: usize| mask[pixel * channels + channel] = label + 1;
+        match segmentation {
+            SegmentationRaw::Polygons(polygons) => polygons
+                .iter()
+                .for_each(|polygon| fill_polygon(polygon, width, height, &mut set)),
+            SegmentationRaw::Rle(counts) => fill_rle(counts, width, height, &mut set),
+        }
+    }
+
+    mask
+}
+
+/// Call `set` with the index of every pixel whose center is inside the polygon.
+fn fill_polygon<F: FnMut(usize)>(polygon: &[f32], width: usize, height: usize, set: &mut F) {
+    let points = polygon
+        .chunks_exact(2)
+        .map(|point| (point[0], point[1]))
+        .collect::<Vec<_>>();
+    let edges = points.iter().zip(points.iter().cycle().skip(1));
+
+    for y in 0..height {
+        let center = y as f32 + 0.5;
+        let mut crossings = edges
+            .clone()
+            .filter(|((_, y0), (_, y1))| (*y0 <= center) != (*y1 <= center))
+            .map(|((x0, y0), (x1, y1))| x0 + (center - y0) * (x1 - x0) / (y1 - y0))
+            .collect::<Vec<_>>();
+        crossings.sort_by(f32::total_cmp);
+
+        for span in crossings.chunks_exact(2) {
+            let start = (span[0] - 0.5).ceil().max(0.0) as usize;
+            let end = ((span[1] - 0.5).ceil().max(0.0) as usize).min(width);
+            (start..end).for_each(|x| set(y * width + x));
+        }
+    }
+}
+
+/// Call `set` with the index of every pixel of a column-major run-length encoded mask.
+fn fill_rle<F: FnMut(usize)>(counts: &[usize], width: usize, height: usize, set: &mut F) {
+    let num_pixels = width * height;
+    let mut start = 0;
+
+    for (run, count) in counts.iter().enumerate() {
+        let end = (start + count).min(num_pixels);
+        if run % 2 == 1 {
+            (start..end).for_each(|index| set((index % height) * width + index / height));
+        }
+        start = end;
+    }
+}
+
 /// Parse the image annotation to the corresponding type.
 fn parse_image_annotation(
     annotation: &AnnotationRaw,
@@ -188,9 +301,24 @@ fn parse_image_annotation(
             })
         }
         AnnotationRaw::BoundingBoxes(v) => Annotation::BoundingBoxes(v.clone()),
+        AnnotationRaw::InstanceMasks {
+            width,
+            height,
+            instances,
+        } => Annotation::SegmentationMask(SegmentationMask {
+            mask: instance_masks_to_vec_usize(*width, *height, instances),
+        }),
     }
 }
 
+/// Read the COCO annotations JSON.
+fn read_coco_json<P: AsRef<Path>>(annotations_json: P) -> Result<Value, ImageLoaderError> {
+    let file = fs::File::open(annotations_json)
+        .map_err(|e| ImageLoaderError::IOError(format!("Failed to open annotations: {}", e)))?;
+    serde_json::from_reader(file)
+        .map_err(|e| ImageLoaderError::ParsingError(format!("Failed to parse annotations: {}", e)))
+}
+
 /// Retrieve all available classes from the COCO JSON
 fn parse_coco_classes(
     json: &serde_json::Value,
@@ -227,6 +355,30 @@ fn parse_coco_classes(
     Ok(classes)
 }
 
+/// Retrieve the image ID and the class ID of a COCO annotation
+fn parse_coco_annotation_ids(
+    annotation: &serde_json::Value,
+) -> Result<(u64, usize), ImageLoaderError> {
+    let image_id = annotation["image_id"]
+        .as_u64()
+        .ok_or_else(|| ImageLoaderError::ParsingError("Invalid image ID in annotation".into()))?;
+
+    let class_id = annotation["category_id"]
+        .as_u64()
+        .ok_or_else(|| {
+            ImageLoaderError::ParsingError("Invalid class ID in annotations".to_string())
+        })
+        .and_then(|v| {
+            usize::try_from(v).map_err(|_| {
+                ImageLoaderError::ParsingError(
+                    "Class ID in annotations out of usize range".to_string(),
+                )
+            })
+        })?;
+
+    Ok((image_id, class_id))
+}
+
 /// Retrieve annotations from COCO JSON
 fn parse_coco_bbox_annotations(
     json: &serde_json::Value,
@@ -235,22 +387,7 @@ fn parse_coco_bbox_annotations(
 
     if let Some(json_annotations) = json["annotations"].as_array() {
         for annotation in json_annotations {
-            let image_id = annotation["image_id"].as_u64().ok_or_else(|| {
-                ImageLoaderError::ParsingError("Invalid image ID in annotation".into())
-            })?;
-
-            let class_id = annotation["category_id"]
-                .as_u64()
-                .ok_or_else(|| {
-                    ImageLoaderError::ParsingError("Invalid class ID in annotations".to_string())
-                })
-                .and_then(|v| {
-                    usize::try_from(v).map_err(|_| {
-                        ImageLoaderError::ParsingError(
-                            "Class ID in annotations out of usize range".to_string(),
-                        )
-                    })
-                })?;
+            let (image_id, class_id) = parse_coco_annotation_ids(annotation)?;
 
             let bbox_coords = annotation["bbox"]
                 .as_array()
@@ -302,12 +439,127 @@ fn parse_coco_bbox_annotations(
     Ok(annotations)
 }
 
-/// Retrieve all available images from the COCO JSON
-fn parse_coco_images<P: AsRef<Path>>(
+/// Retrieve instance segmentations from COCO JSON
+fn parse_coco_segmentation_annotations(
+    json: &serde_json::Value,
+) -> Result<HashMap<u64, Vec<(usize, SegmentationRaw)>>, ImageLoaderError> {
+    let mut annotations: HashMap<_, Vec<_>> = HashMap::new();
+
+    if let Some(json_annotations) = json["annotations"].as_array() {
+        for annotation in json_annotations {
+            let (image_id, class_id) = parse_coco_annotation_ids(annotation)?;
+            let segmentation = parse_coco_segmentation(&annotation["segmentation"])?;
+
+            annotations
+                .entry(image_id)
+                .or_default()
+                .push((class_id, segmentation));
+        }
+    }
+
+    if annotations.is_empty() {
+        return Err(ImageLoaderError::ParsingError(
+            "no annotations found".to_string(),
+        ));
+    }
+
+    Ok(annotations)
+}
+
+/// Parse a COCO segmentation, given as polygons or as an uncompressed or compressed RLE
+fn parse_coco_segmentation(
+    segmentation: &serde_json::Value,
+) -> Result<SegmentationRaw, ImageLoaderError> {
+    let invalid = || ImageLoaderError::ParsingError("invalid segmentation".to_string());
+
+    if let Some(polygons) = segmentation.as_array() {
+        let polygons = polygons
+            .iter()
+            .map(|polygon| {
+                polygon
+                    .as_array()
+                    .ok_or_else(invalid)?
+                    .iter()
+                    .map(|v| v.as_f64().map(|v| v as f32).ok_or_else(invalid))
+                    .collect::<Result<Vec<f32>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        return Ok(SegmentationRaw::Polygons(polygons));
+    }
+
+    let counts = match &segmentation["counts"] {
+        Value::Array(counts) => counts
+            .iter()
+            .map(|v| v.as_u64().map(|v| v as usize).ok_or_else(invalid))
+            .collect::<Result<Vec<_>, _>>()?,
+        Value::String(counts) => decode_coco_rle(counts)?,
+        _ => return Err(invalid()),
+    };
+
+    Ok(SegmentationRaw::Rle(counts))
+}
+
+/// Decode the run lengths of a compressed COCO RLE, where each count is stored as the
+/// difference to the count two runs before, in 5-bit chunks offset by 48.
+fn decode_coco_rle(counts: &str) -> Result<Vec<usize>, ImageLoaderError> {
+    let bytes = counts.as_bytes();
+    let mut decoded: Vec<i64> = Vec::new();
+    let mut position = 0;
+
+    while position < bytes.len() {
+        let (mut value, mut shift, mut more) = (0i64, 0, true);
+        while more {
+            let chunk = *bytes.get(position).ok_or_else(|| {
+                ImageLoaderError::ParsingError("truncated compressed RLE".to_string())
+            })? as i64
+                - 48;
+            value |= (chunk & 0x1f) << shift;
+            more = chunk & 0x20 != 0;
+            position += 1;
+            shift += 5;
+            if !more && chunk & 0x10 != 0 {
+                value |= -1 << shift;
+            }
+        }
+        if decoded.len() > 2 {
+            value += decoded[decoded.len() - 2];
+        }
+        decoded.push(value);
+    }
+
+    decoded
+        .into_iter()
+        .map(|count| {
+            usize::try_from(count).map_err(|_| {
+                ImageLoaderError::ParsingError("negative count in compressed RLE".to_string())
+            })
+        })
+        .collect()
+}
+
+/// Retrieve the size of an image from the COCO JSON
+fn parse_coco_image_size(image: &serde_json::Value) -> Result<(usize, usize), ImageLoaderError> {
+    let dimension = |key: &str| {
+        image[key]
+            .as_u64()
+            .map(|v| v as usize)
+            .ok_or_else(|| ImageLoaderError::ParsingError(format!("Invalid image {key}")))
+    };
+
+    Ok((dimension("width")?, dimension("height")?))
+}
+
+/// Retrieve all available images from the COCO JSON, with the annotation of each image
+fn parse_coco_images<P, F>(
     images_path: &P,
-    mut annotations: HashMap<u64, AnnotationRaw>,
     json: &serde_json::Value,
-) -> Result<Vec<ImageDatasetItemRaw>, ImageLoaderError> {
+    mut annotation: F,
+) -> Result<Vec<ImageDatasetItemRaw>, ImageLoaderError>
+where
+    P: AsRef<Path>,
+    F: FnMut(u64, &serde_json::Value) -> Result<AnnotationRaw, ImageLoaderError>,
+{
     let mut images = Vec::new();
     if let Some(json_images) = json["images"].as_array() {
         for image in json_images {
@@ -330,9 +582,7 @@ fn parse_coco_images<P: AsRef<Path>>(
                 )));
             }
 
-            let annotation = annotations
-                .remove(&image_id)
-                .unwrap_or_else(|| AnnotationRaw::BoundingBoxes(Vec::new()));
+            let annotation = annotation(image_id, image)?;
 
             images.push(ImageDatasetItemRaw {
                 annotation,
@@ -350,6 +600,149 @@ fn parse_coco_images<P: AsRef<Path>>(
     Ok(images)
 }
 
+/// Retrieve the contents of the `tag` elements of an XML document, ignoring their attributes.
+fn xml_elements<'a>(xml: &'a str, tag: &str) -> impl Iterator<Item = &'a str> {
+    let (open, close) = (format!("<{tag}"), format!("</{tag}>"));
+    let mut rest = xml;
+
+    std::iter::from_fn(move || {
+        loop {
+            let tail = &rest[rest.find(&open)? + open.len()..];
+            rest = tail;
+            // Skip the elements whose name only starts with the tag
+            if !tail.starts_with(|c: char| c == '>' || c.is_whitespace()) {
+                continue;
+            }
+
+            let content = &tail[tail.find('>')? + 1..];
+            let end = content.find(&close)?;
+            rest = &content[end + close.len()..];
+            return Some(content[..end].trim());
+        }
+    })
+}
+
+/// Retrieve the image file name and the bounding boxes of a Pascal VOC XML annotation
+fn parse_voc_annotation(
+    xml: &str,
+    path: &Path,
+    classes: &HashMap<String, usize>,
+) -> Result<(String, Vec<BoundingBox>), ImageLoaderError> {
+    let error =
+        |msg: String| ImageLoaderError::ParsingError(format!("{msg} in {}", path.display()));
+    // The elements are matched on the raw text, which these constructs would alter
+    if xml.contains("<!") {
+        return Err(error(
+            "Unsupported XML comment, CDATA section or declaration".to_string(),
+        ));
+    }
+    if xml.contains('&') {
+        return Err(error("Unsupported XML entity reference".to_string()));
+    }
+    let element = |xml, tag| {
+        xml_elements(xml, tag)
+            .next()
+            .ok_or_else(|| error(format!("Missing <{tag}> element")))
+    };
+
+    let file_name = element(xml, "filename")?.to_string();
+    let bboxes = xml_elements(xml, "object")
+        .map(|object| {
+            // Ignore the bounding boxes of the object parts, e.g. the head of a person
+            let object = object.split("<part").next().unwrap_or(object);
+            let name = element(object, "name")?;
+            let label = *classes
+                .get(name)
+                .ok_or_else(|| error(format!("Unknown class `{name}`")))?;
+
+            let bndbox = element(object, "bndbox")?;
+            let coord = |tag| {
+                element(bndbox, tag)?
+                    .parse::<f32>()
+                    .map_err(|_| error(format!("Invalid <{tag}> value")))
+            };
+            let (x_min, y_min) = (coord("xmin")?, coord("ymin")?);
+            let (x_max, y_max) = (coord("xmax")?, coord("ymax")?);
+
+            Ok(BoundingBox {
+                coords: [x_min, y_min, x_max - x_min, y_max - y_min],
+                label,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((file_name, bboxes))
+}
+
+/// Retrieve the bounding boxes of a YOLO label file, with one normalized
+/// `class x_center y_center width height` line per object
+fn parse_yolo_labels(
+    labels: &str,
+    width: f32,
+    height: f32,
+    num_classes: usize,
+) -> Result<Vec<BoundingBox>, ImageLoaderError> {
+    labels
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let values = line.split_whitespace().collect::<Vec<_>>();
+            if values.len() != YOLO_NUM_VALUES {
+                return Err(ImageLoaderError::ParsingError(format!(
+                    "Expected {YOLO_NUM_VALUES} values in YOLO label `{line}`"
+                )));
+            }
+
+            let label = values[0]
+                .parse::<usize>()
+                .ok()
+                .filter(|label| *label < num_classes)
+                .ok_or_else(|| {
+                    ImageLoaderError::ParsingError(format!("Invalid class in YOLO label `{line}`"))
+                })?;
+            let coords = values[1..]
+                .iter()
+                .map(|v| v.parse::<f32>())
+                .collect::<Result<Vec<_>, _>>()
+                .ok()
+                .filter(|coords| coords.iter().all(|v| (0.0..=1.0).contains(v)))
+                .ok_or_else(|| {
+                    ImageLoaderError::ParsingError(format!(
+                        "Invalid coordinates in YOLO label `{line}`, expected normalized values"
+                    ))
+                })?;
+
+            let (box_width, box_height) = (coords[2] * width, coords[3] * height);
+            Ok(BoundingBox {
+                coords: [
+                    coords[0] * width - box_width / 2.0,
+                    coords[1] * height - box_height / 2.0,
+                    box_width,
+                    box_height,
+                ],
+                label,
+            })
+        })
+        .collect()
+}
+
+/// Retrieve the files matching the patterns in the root folder, ordered by path
+fn glob_sorted<P: AsRef<Path>>(
+    root: P,
+    patterns: &[String],
+) -> Result<Vec<PathBuf>, ImageLoaderError> {
+    Ok(
+        globwalk::GlobWalkerBuilder::from_patterns(root.as_ref(), patterns)
+            .follow_links(true)
+            .sort_by(|p1: &DirEntry, p2: &DirEntry| p1.path().cmp(p2.path()))
+            .build()
+            .map_err(|err| ImageLoaderError::Unknown(format!("{err:?}")))?
+            .filter_map(Result::ok)
+            .map(|entry| entry.into_path())
+            .collect(),
+    )
+}
+
 impl Mapper<ImageDatasetItemRaw, ImageDatasetItem> for PathToImageDatasetItem {
     /// Convert a raw image dataset item (path-like) to a 3D image array with a target label.
     fn map(&self, item: &ImageDatasetItemRaw) -> ImageDatasetItem {
@@ -357,6 +750,7 @@ impl Mapper<ImageDatasetItemRaw, ImageDatasetItem> for PathToImageDatasetItem {
 
         // Load image from disk
         let image = image::open(&item.image_path).unwrap();
+        let (width, height) = (image.width() as usize, image.height() as usize);
 
         // Image as Vec<PixelDepth>
         let img_vec = match image.color() {
@@ -415,6 +809,8 @@ impl Mapper<ImageDatasetItemRaw, ImageDatasetItem> for PathToImageDatasetItem {
 
         ImageDatasetItem {
             image: img_vec,
+            width,
+            height,
             annotation,
             image_path: item.image_path.display().to_string(),
         }
@@ -650,15 +1046,54 @@ impl ImageFolderDataset {
         annotations_json: A,
         images_path: I,
     ) -> Result<Self, ImageLoaderError> {
-        let file = fs::File::open(annotations_json)
-            .map_err(|e| ImageLoaderError::IOError(format!("Failed to open annotations: {}", e)))?;
-        let json: Value = serde_json::from_reader(file).map_err(|e| {
-            ImageLoaderError::ParsingError(format!("Failed to parse annotations: {}", e))
+        let json = read_coco_json(annotations_json)?;
+
+        let classes = parse_coco_classes(&json)?;
+        let mut annotations = parse_coco_bbox_annotations(&json)?;
+        let items = parse_coco_images(&images_path, &json, |image_id, _| {
+            Ok(annotations
+                .remove(&image_id)
+                .unwrap_or_else(|| AnnotationRaw::BoundingBoxes(Vec::new())))
         })?;
+        let dataset = InMemDataset::new(items);
+        let mapper = PathToImageDatasetItem { classes };
+        let dataset = MapperDataset::new(dataset, mapper);
+
+        Ok(Self { dataset })
+    }
+
+    /// Create a COCO instance segmentation dataset based on the annotations JSON and image
+    /// directory.
+    ///
+    /// The polygons and the run-length encoded masks (RLE, compressed or not) of the objects are
+    /// rasterized to a [segmentation mask](SegmentationMask) with one channel per object, holding
+    /// the class ID of the object plus one on its pixels and zero elsewhere.
+    ///
+    /// # Arguments
+    ///
+    /// * `annotations_json` - Path to the JSON file containing annotations in COCO format (for
+    ///   example instances_train2017.json).
+    ///
+    /// * `images_path` - Path containing the images matching the annotations JSON.
+    ///
+    /// # Returns
+    /// A new dataset instance.
+    pub fn new_coco_segmentation<A: AsRef<Path>, I: AsRef<Path>>(
+        annotations_json: A,
+        images_path: I,
+    ) -> Result<Self, ImageLoaderError> {
+        let json = read_coco_json(annotations_json)?;
 
         let classes = parse_coco_classes(&json)?;
-        let annotations = parse_coco_bbox_annotations(&json)?;
-        let items = parse_coco_images(&images_path, annotations, &json)?;
+        let mut annotations = parse_coco_segmentation_annotations(&json)?;
+        let items = parse_coco_images(&images_path, &json, |image_id, image| {
+            let (width, height) = parse_coco_image_size(image)?;
+            Ok(AnnotationRaw::InstanceMasks {
+                width,
+                height,
+                instances: annotations.remove(&image_id).unwrap_or_default(),
+            })
+        })?;
         let dataset = InMemDataset::new(items);
         let mapper = PathToImageDatasetItem { classes };
         let dataset = MapperDataset::new(dataset, mapper);
@@ -666,6 +1101,237 @@ impl ImageFolderDataset {
         Ok(Self { dataset })
     }
 
+    /// Create a Pascal VOC detection dataset based on the XML annotations and image directories.
+    ///
+    /// The `[x_min, y_min, x_max, y_max]` boxes of the annotations are converted to the
+    /// `[x_min, y_min, width, height]` format of the [bounding boxes](BoundingBox).
+    ///
+    /// # Arguments
+    ///
+    /// * `annotations_path` - Path containing one XML annotation file per image (for example
+    ///   VOC2012/Annotations).
+    ///
+    /// * `images_path` - Path containing the images named in the annotations (for example
+    ///   VOC2012/JPEGImages).
+    ///
+    /// * `classes` - Dataset class names, matching the object names of the annotations.
+    ///
+    /// # Returns
+    /// A new dataset instance.
+    pub fn new_pascal_voc_detection<A, I, S>(
+        annotations_path: A,
+        images_path: I,
+        classes: &[S],
+    ) -> Result<Self, ImageLoaderError>
+    where
+        A: AsRef<Path>,
+        I: AsRef<Path>,
+        S: AsRef<str>,
+    {
+        let classes_map = Self::classes_map(classes);
+
+        let items = glob_sorted(annotations_path, &["*.xml".to_string()])?
+            .into_iter()
+            .map(|path| {
+                let xml = fs::read_to_string(&path).map_err(|e| {
+                    ImageLoaderError::IOError(format!("Failed to read {}: {}", path.display(), e))
+                })?;
+                let (file_name, bboxes) = parse_voc_annotation(&xml, &path, &classes_map)?;
+
+                let image_path = images_path.as_ref().join(file_name);
+                if !image_path.exists() {
+                    return Err(ImageLoaderError::IOError(format!(
+                        "Image {} not found",
+                        image_path.display()
+                    )));
+                }
+
+                Ok(ImageDatasetItemRaw::new(
+                    image_path,
+                    AnnotationRaw::BoundingBoxes(bboxes),
+                ))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self::with_items(items, classes)
+    }
+
+    /// Create a YOLO detection dataset based on the image and label directories.
+    ///
+    /// Each image has a text file at the same relative path in the label directory, with one
+    /// `class x_center y_center width height` line per object in coordinates normalized by the
+    /// image size, between 0 and 1. The images without a label file have no objects.
+    ///
+    /// # Arguments
+    ///
+    /// * `images_path` - Path containing the images (for example images/train).
+    ///
+    /// * `labels_path` - Path containing the label files (for example labels/train).
+    ///
+    /// * `classes` - Dataset class names, in the order of the class indices of the labels.
+    ///
+    /// # Returns
+    /// A new dataset instance.
+    pub fn new_yolo_detection<I, L, S>(
+        images_path: I,
+        labels_path: L,
+        classes: &[S],
+    ) -> Result<Self, ImageLoaderError>
+    where
+        I: AsRef<Path>,
+        L: AsRef<Path>,
+        S: AsRef<str>,
+    {
+        let images_path = images_path.as_ref();
+        let pattern = format!("*.{{{}}}", SUPPORTED_FILES.join(","));
+
+        let items = glob_sorted(images_path, &[pattern])?
+            .into_iter()
+            .map(|image_path| {
+                let relative = image_path.strip_prefix(images_path).unwrap_or(&image_path);
+                let label_path = labels_path.as_ref().join(relative).with_extension("txt");
+
+                let bboxes = match fs::read_to_string(&label_path) {
+                    Ok(labels) => {
+                        let (width, height) =
+                            image::image_dimensions(&image_path).map_err(|e| {
+                                ImageLoaderError::IOError(format!(
+                                    "Failed to read image {}: {}",
+                                    image_path.display(),
+                                    e
+                                ))
+                            })?;
+                        parse_yolo_labels(&labels, width as f32, height as f32, classes.len())?
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+                    Err(e) => {
+                        return Err(ImageLoaderError::IOError(format!(
+                            "Failed to read {}: {}",
+                            label_path.display(),
+                            e
+                        )));
+                    }
+                };
+
+                Ok(ImageDatasetItemRaw::new(
+                    image_path,
+                    AnnotationRaw::BoundingBoxes(bboxes),
+                ))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self::with_items(items, classes)
+    }
+
+    /// Create a Cityscapes segmentation dataset based on the image and label directories.
+    ///
+    /// Each `<name>_leftImg8bit.png` image has a `<name>_gtFine_labelIds.png` label image at the
+    /// same relative path in the label directory, whose pixel values are the class indices.
+    ///
+    /// The pixel values are kept as is. Use
+    /// [new_cityscapes_segmentation_with](Self::new_cityscapes_segmentation_with) with the
+    /// `_gtFine_trainIds` suffix for the 19 training classes, whose void pixels have the
+    /// [ignore index](CITYSCAPES_IGNORE_INDEX) 255.
+    ///
+    /// # Arguments
+    ///
+    /// * `images_path` - Path containing the images (for example leftImg8bit/train).
+    ///
+    /// * `labels_path` - Path containing the label images (for example gtFine/train).
+    ///
+    /// * `classes` - Dataset class names, in the order of the label IDs.
+    ///
+    /// # Returns
+    /// A new dataset instance.
+    pub fn new_cityscapes_segmentation<I, L, S>(
+        images_path: I,
+        labels_path: L,
+        classes: &[S],
+    ) -> Result<Self, ImageLoaderError>
+    where
+        I: AsRef<Path>,
+        L: AsRef<Path>,
+        S: AsRef<str>,
+    {
+        Self::new_cityscapes_segmentation_with(
+            images_path,
+            labels_path,
+            CITYSCAPES_LABEL_SUFFIX,
+            classes,
+        )
+    }
+
+    /// Create a Cityscapes segmentation dataset based on the image and label directories.
+    /// The label images are selected with the provided suffix.
+    ///
+    /// The pixel values are kept as is, so the void pixels of the `_gtFine_trainIds` label images
+    /// have the class [CITYSCAPES_IGNORE_INDEX], which should be ignored in the losses and the
+    /// metrics.
+    ///
+    /// # Arguments
+    ///
+    /// * `images_path` - Path containing the images (for example leftImg8bit/train).
+    ///
+    /// * `labels_path` - Path containing the label images (for example gtFine/train).
+    ///
+    /// * `label_suffix` - Suffix of the label images replacing the `_leftImg8bit` suffix of the
+    ///   images (for example `_gtFine_trainIds`).
+    ///
+    /// * `classes` - Dataset class names, in the order of the label IDs.
+    ///
+    /// # Returns
+    /// A new dataset instance.
+    pub fn new_cityscapes_segmentation_with<I, L, S>(
+        images_path: I,
+        labels_path: L,
+        label_suffix: &str,
+        classes: &[S],
+    ) -> Result<Self, ImageLoaderError>
+    where
+        I: AsRef<Path>,
+        L: AsRef<Path>,
+        S: AsRef<str>,
+    {
+        let images_path = images_path.as_ref();
+        let pattern = format!("*{CITYSCAPES_IMAGE_SUFFIX}.png");
+
+        let items = glob_sorted(images_path, &[pattern])?
+            .into_iter()
+            .map(|image_path| {
+                let relative = image_path.strip_prefix(images_path).unwrap_or(&image_path);
+                let name = relative
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(|name| name.strip_suffix(".png"))
+                    .and_then(|name| name.strip_suffix(CITYSCAPES_IMAGE_SUFFIX))
+                    .ok_or_else(|| {
+                        ImageLoaderError::IOError(format!(
+                            "Invalid image name {}",
+                            image_path.display()
+                        ))
+                    })?;
+
+                let mask_path = labels_path
+                    .as_ref()
+                    .join(relative)
+                    .with_file_name(format!("{name}{label_suffix}.png"));
+                if !mask_path.exists() {
+                    return Err(ImageLoaderError::IOError(format!(
+                        "Label image {} not found",
+                        mask_path.display()
+                    )));
+                }
+
+                Ok(ImageDatasetItemRaw::new(
+                    image_path,
+                    AnnotationRaw::SegmentationMask(mask_path),
+                ))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self::with_items(items, classes)
+    }
+
     /// Create an image dataset with the specified items.
     ///
     /// # Arguments
@@ -683,26 +1349,27 @@ impl ImageFolderDataset {
         // the method is private. We assume it's already validated.
         let dataset = InMemDataset::new(items);
 
-        // Class names to index map
-        let classes = classes.iter().map(|c| c.as_ref()).collect::<Vec<_>>();
-        let classes_map: HashMap<_, _> = classes
-            .into_iter()
-            .enumerate()
-            .map(|(idx, cls)| (cls.to_string(), idx))
-            .collect();
-
         let mapper = PathToImageDatasetItem {
-            classes: classes_map,
+            classes: Self::classes_map(classes),
         };
         let dataset = MapperDataset::new(dataset, mapper);
 
         Ok(Self { dataset })
     }
 
-    /// Check if extension is supported.
+    /// Class names to index map.
+    fn classes_map<S: AsRef<str>>(classes: &[S]) -> HashMap<String, usize> {
+        classes
+            .iter()
+            .enumerate()
+            .map(|(idx, cls)| (cls.as_ref().to_string(), idx))
+            .collect()
+    }
+
+    /// Check if extension is supported, ignoring its case (e.g. the `JPEG` files of ImageNet).
     fn check_extension<S: AsRef<str>>(extension: &S) -> Result<String, ImageLoaderError> {
         let extension = extension.as_ref();
-        if !SUPPORTED_FILES.contains(&extension) {
+        if !SUPPORTED_FILES.contains(&extension.to_lowercase().as_str()) {
             Err(ImageLoaderError::InvalidFileExtensionError(
                 extension.to_string(),
             ))
@@ -719,6 +1386,10 @@ mod tests {
     const SEGMASK_ROOT: &str = "tests/data/segmask_folder";
     const COCO_JSON: &str = "tests/data/dataset_coco.json";
     const COCO_IMAGES: &str = "tests/data/image_folder_coco";
+    const COCO_SEGMENTATION_JSON: &str = "tests/data/dataset_coco_segmentation.json";
+    const VOC_ANNOTATIONS: &str = "tests/data/voc_annotations";
+    const YOLO_LABELS: &str = "tests/data/yolo_labels";
+    const CITYSCAPES_ROOT: &str = "tests/data/cityscapes";
 
     #[test]
     pub fn image_folder_dataset() {
@@ -1071,4 +1742,186 @@ mod tests {
             }
         }
     }
+
+    fn bboxes(item: ImageDatasetItem) -> Vec<BoundingBox> {
+        match item.annotation {
+            Annotation::BoundingBoxes(bboxes) => bboxes,
+            _ => panic!("unexpected annotation"),
+        }
+    }
+
+    fn bbox(coords: [f32; 4], label: usize) -> BoundingBox {
+        BoundingBox { coords, label }
+    }
+
+    #[test]
+    pub fn pascal_voc_detection_dataset() {
+        let dataset = ImageFolderDataset::new_pascal_voc_detection(
+            VOC_ANNOTATIONS,
+            COCO_IMAGES,
+            &["dot", "triangle"],
+        )
+        .unwrap();
+        assert_eq!(dataset.len(), 2);
+
+        // The bounding box of the part of the object is ignored
+        let item = dataset.get(0).unwrap();
+        assert!(item.image_path.ends_with("one_dot.jpg"));
+        assert_eq!(bboxes(item), vec![bbox([10.5, 9.5, 11.0, 11.5], 0)]);
+
+        assert_eq!(
+            bboxes(dataset.get(1).unwrap()),
+            vec![
+                bbox([3.0, 18.0, 11.0, 11.0], 0),
+                bbox([3.0, 3.0, 11.0, 11.0], 0),
+                bbox([15.0, 3.0, 13.0, 12.0], 1),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn pascal_voc_detection_dataset_unknown_class() {
+        let result =
+            ImageFolderDataset::new_pascal_voc_detection(VOC_ANNOTATIONS, COCO_IMAGES, &["dot"]);
+        assert!(matches!(result, Err(ImageLoaderError::ParsingError(_))));
+    }
+
+    #[test]
+    pub fn yolo_detection_dataset() {
+        let dataset =
+            ImageFolderDataset::new_yolo_detection(COCO_IMAGES, YOLO_LABELS, &["dot", "triangle"])
+                .unwrap();
+        assert_eq!(dataset.len(), 3);
+
+        // dot_triangle.jpg has no label file
+        assert_eq!(bboxes(dataset.get(0).unwrap()), vec![]);
+        assert_eq!(
+            bboxes(dataset.get(1).unwrap()),
+            vec![bbox([12.0, 8.0, 8.0, 16.0], 0)]
+        );
+        assert_eq!(
+            bboxes(dataset.get(2).unwrap()),
+            vec![
+                bbox([3.0, 18.0, 11.0, 11.0], 0),
+                bbox([15.0, 3.0, 13.0, 12.0], 1),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn yolo_labels_invalid_class() {
+        let result = parse_yolo_labels("2 0.5 0.5 0.1 0.1", 32.0, 32.0, 2);
+        assert!(matches!(result, Err(ImageLoaderError::ParsingError(_))));
+    }
+
+    #[test]
+    pub fn yolo_labels_unnormalized_coordinates() {
+        let result = parse_yolo_labels("0 16 16 8 8", 32.0, 32.0, 2);
+        assert!(matches!(result, Err(ImageLoaderError::ParsingError(_))));
+    }
+
+    #[test]
+    pub fn pascal_voc_annotation_unsupported_xml() {
+        let classes = HashMap::from([("dot".to_string(), 0)]);
+        let object = "<object><name>dot</name><bndbox><xmin>1</xmin><ymin>1</ymin>\
+                      <xmax>2</xmax><ymax>2</ymax></bndbox></object>";
+
+        for filename in [
+            "<filename>a.jpg</filename><!-- <filename>b.jpg</filename> -->",
+            "<filename><![CDATA[a.jpg]]></filename>",
+            "<filename>a&amp;b.jpg</filename>",
+        ] {
+            let xml = format!("<annotation>{filename}{object}</annotation>");
+            let result = parse_voc_annotation(&xml, Path::new("a.xml"), &classes);
+            assert!(matches!(result, Err(ImageLoaderError::ParsingError(_))));
+        }
+
+        let xml = format!("<annotation><filename>a.jpg</filename>{object}</annotation>");
+        let (file_name, bboxes) = parse_voc_annotation(&xml, Path::new("a.xml"), &classes).unwrap();
+        assert_eq!(file_name, "a.jpg");
+        assert_eq!(bboxes, vec![bbox([1.0, 1.0, 1.0, 1.0], 0)]);
+    }
+
+    #[test]
+    pub fn coco_rle_decoding() {
+        assert_eq!(
+            decode_coco_rle("T35k00i>N").unwrap(),
+            vec![100, 5, 27, 5, 500, 3]
+        );
+    }
+
+    #[test]
+    pub fn coco_segmentation_dataset() {
+        let dataset =
+            ImageFolderDataset::new_coco_segmentation(COCO_SEGMENTATION_JSON, COCO_IMAGES).unwrap();
+        assert_eq!(dataset.len(), 3);
+
+        let mask = |index| match dataset.get(index).unwrap().annotation {
+            Annotation::SegmentationMask(mask) => mask.mask,
+            _ => panic!("unexpected annotation"),
+        };
+        let pixels = |mask: &[usize], channels: usize, channel: usize, value: usize| {
+            mask.iter()
+                .skip(channel)
+                .step_by(channels)
+                .enumerate()
+                .filter(|(_, v)| **v == value)
+                .map(|(index, _)| (index / 32, index % 32))
+                .collect::<Vec<_>>()
+        };
+
+        // A polygon of the first class and an RLE of the second class
+        let two_instances = mask(0);
+        assert_eq!(two_instances.len(), 32 * 32 * 2);
+        let square = pixels(&two_instances, 2, 0, 1);
+        assert_eq!(square.len(), 64);
+        assert_eq!((square[0], square[63]), ((4, 4), (11, 11)));
+        assert_eq!(
+            pixels(&two_instances, 2, 1, 2),
+            vec![(1, 1), (1, 2), (2, 1), (2, 2)]
+        );
+
+        // No instances
+        assert!(mask(1).is_empty());
+
+        // A compressed RLE of the second class, in column-major order
+        let compressed = pixels(&mask(2), 1, 0, 2);
+        assert_eq!(compressed.len(), 13);
+        assert_eq!(&compressed[..2], &[(4, 3), (4, 4)]);
+        assert_eq!(compressed[12], (31, 19));
+    }
+
+    #[test]
+    pub fn cityscapes_segmentation_dataset() {
+        let root = Path::new(CITYSCAPES_ROOT);
+        let dataset = ImageFolderDataset::new_cityscapes_segmentation(
+            root.join("leftImg8bit").join("train"),
+            root.join("gtFine").join("train"),
+            &["unlabeled", "ego vehicle", "rectification border"],
+        )
+        .unwrap();
+        assert_eq!(dataset.len(), 1);
+
+        let mask = segmentation_mask_to_vec_usize(
+            &Path::new(SEGMASK_ROOT)
+                .join("annotations")
+                .join("mask_checkerboard.png"),
+        );
+        assert_eq!(
+            dataset.get(0).unwrap().annotation,
+            Annotation::SegmentationMask(SegmentationMask { mask })
+        );
+    }
+
+    #[test]
+    pub fn cityscapes_segmentation_dataset_missing_labels() {
+        let root = Path::new(CITYSCAPES_ROOT);
+        let result = ImageFolderDataset::new_cityscapes_segmentation_with(
+            root.join("leftImg8bit").join("train"),
+            root.join("gtFine").join("train"),
+            "_gtFine_trainIds",
+            &["unlabeled"],
+        );
+        assert!(matches!(result, Err(ImageLoaderError::IOError(_))));
+    }
 }
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{ImageDatasetItem, ImageFolderDataset, ImageLoaderError};
+use crate::Dataset;
+
+/// An [ImageNet](https://www.image-net.org/)-style classification dataset, whose classes are
+/// WordNet synsets (e.g. `n01440764`).
+///
+/// The classes are ordered as in a synset mapping file, such as the `LOC_synset_mapping.txt`
+/// file of ILSVRC2012, with one `<synset> <class names>` line per class. The images are read
+/// from a local directory, without downloading anything.
+pub struct ImageNetDataset {
+    dataset: ImageFolderDataset,
+    synsets: Vec<String>,
+    class_names: Vec<String>,
+}
+
+impl Dataset<ImageDatasetItem> for ImageNetDataset {
+    fn get(&self, index: usize) -> Option<ImageDatasetItem> {
+        self.dataset.get(index)
+    }
+
+    fn len(&self) -> usize {
+        self.dataset.len()
+    }
+}
+
+impl ImageNetDataset {
+    /// Create an ImageNet dataset from a directory with one folder of images per synset, such as
+    /// the `train` directory of ILSVRC2012.
+    ///
+    /// # Arguments
+    ///
+    /// * `images_path` - Path containing the synset folders.
+    /// * `synset_mapping` - Path to the synset mapping file.
+    ///
+    /// # Returns
+    /// A new dataset instance.
+    pub fn new<I: AsRef<Path>, M: AsRef<Path>>(
+        images_path: I,
+        synset_mapping: M,
+    ) -> Result<Self, ImageLoaderError> {
+        let (synsets, class_names) = read_synset_mapping(synset_mapping.as_ref())?;
+
+        let mut items = Vec::new();
+        for synset in synsets.iter() {
+            let folder = images_path.as_ref().join(synset);
+            if !folder.exists() {
+                continue;
+            }
+
+            for image_path in sorted_files(&folder)? {
+                items.push((image_path, synset.clone()));
+            }
+        }
+
+        Self::with_items(items, synsets, class_names)
+    }
+
+    /// Create an ImageNet dataset from a directory of images with a CSV label file, such as the
+    /// `val` directory of ILSVRC2012 with the `LOC_val_solution.csv` file.
+    ///
+    /// Each line of the label file is `<image id>,<synset> ...`, where the image ID is the file
+    /// name of the image without its extension, and only the first synset is kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `images_path` - Path containing the images.
+    /// * `synset_mapping` - Path to the synset mapping file.
+    /// * `labels_csv` - Path to the CSV label file.
+    ///
+    /// # Returns
+    /// A new dataset instance.
+    pub fn new_with_labels<I, M, L>(
+        images_path: I,
+        synset_mapping: M,
+        labels_csv: L,
+    ) -> Result<Self, ImageLoaderError>
+    where
+        I: AsRef<Path>,
+        M: AsRef<Path>,
+        L: AsRef<Path>,
+    {
+        let (synsets, class_names) = read_synset_mapping(synset_mapping.as_ref())?;
+        let labels_csv = labels_csv.as_ref();
+        let content = read_to_string(labels_csv)?;
+
+        let mut labels = HashMap::new();
+        for (index, line) in content.lines().enumerate() {
+            let Some((image_id, predictions)) = line.split_once(',') else {
+                continue;
+            };
+            let synset = predictions.split_whitespace().next().unwrap_or_default();
+
+            if !synsets.iter().any(|s| s == synset) {
+                // Skip the header
+                if index == 0 {
+                    continue;
+                }
+                return Err(ImageLoaderError::ParsingError(format!(
+                    "Unknown synset `{synset}` in {}",
+                    labels_csv.display()
+                )));
+            }
+            labels.insert(image_id.trim().to_string(), synset.to_string());
+        }
+
+        let items = sorted_files(images_path.as_ref())?
+            .into_iter()
+            .map(|image_path| {
+                let image_id = image_path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let synset = labels.get(&image_id).ok_or_else(|| {
+                    ImageLoaderError::ParsingError(format!(
+                        "No label for image {}",
+                        image_path.display()
+                    ))
+                })?;
+
+                Ok((image_path, synset.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self::with_items(items, synsets, class_names)
+    }
+
+    /// The synsets of the classes, in the order of the labels.
+    pub fn synsets(&self) -> &[String] {
+        &self.synsets
+    }
+
+    /// The names of the classes, in the order of the labels.
+    pub fn class_names(&self) -> &[String] {
+        &self.class_names
+    }
+
+    fn with_items(
+        items: Vec<(PathBuf, String)>,
+        synsets: Vec<String>,
+        class_names: Vec<String>,
+    ) -> Result<Self, ImageLoaderError> {
+        let dataset = ImageFolderDataset::new_classification_with_items(items, &synsets)?;
+
+        Ok(Self {
+            dataset,
+            synsets,
+            class_names,
+        })
+    }
+}
+
+fn read_to_string(path: &Path) -> Result<String, ImageLoaderError> {
+    fs::read_to_string(path).map_err(|err| {
+        ImageLoaderError::IOError(format!("Failed to read {}: {err}", path.display()))
+    })
+}
+
+/// Read the synsets and the class names of a synset mapping file.
+fn read_synset_mapping(path: &Path) -> Result<(Vec<String>, Vec<String>), ImageLoaderError> {
+    Ok(read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (synset, names) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+            (synset.to_string(), names.trim().to_string())
+        })
+        .unzip())
+}
+
+/// The files of a directory, ordered by path.
+fn sorted_files(directory: &Path) -> Result<Vec<PathBuf>, ImageLoaderError> {
+    let mut files = fs::read_dir(directory)
+        .and_then(|entries| {
+            entries
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(|err| {
+            ImageLoaderError::IOError(format!("Failed to read {}: {err}", directory.display()))
+        })?;
+    files.retain(|path| path.is_file());
+    files.sort();
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vision::Annotation;
+
+    const IMAGENET_ROOT: &str = "tests/data/imagenet";
+
+    fn mapping() -> PathBuf {
+        Path::new(IMAGENET_ROOT).join("LOC_synset_mapping.txt")
+    }
+
+    #[test]
+    fn test_imagenet_synset_folders() {
+        let dataset =
+            ImageNetDataset::new(Path::new(IMAGENET_ROOT).join("train"), mapping()).unwrap();
+
+        assert_eq!(dataset.synsets(), ["n01440764", "n01443537", "n01484850"]);
+        assert_eq!(dataset.class_names()[0], "tench, Tinca tinca");
+        assert_eq!(dataset.len(), 3);
+        assert_eq!(dataset.get(0).unwrap().annotation, Annotation::Label(0));
+        assert_eq!(dataset.get(2).unwrap().annotation, Annotation::Label(2));
+    }
+
+    #[test]
+    fn test_imagenet_labels_file() {
+        let root = Path::new(IMAGENET_ROOT);
+        let dataset = ImageNetDataset::new_with_labels(
+            root.join("val"),
+            mapping(),
+            root.join("LOC_val_solution.csv"),
+        )
+        .unwrap();
+
+        assert_eq!(dataset.len(), 2);
+        assert_eq!(dataset.get(0).unwrap().annotation, Annotation::Label(2));
+        assert_eq!(dataset.get(1).unwrap().annotation, Annotation::Label(0));
+    }
+}
@@ -1,5 +1,14 @@
+mod cifar;
+mod fashion_mnist;
 mod image_folder;
+mod imagenet;
 mod mnist;
 
+/// Random image transformations, to augment the image datasets.
+pub mod augmentation;
+
+pub use cifar::*;
+pub use fashion_mnist::*;
 pub use image_folder::*;
+pub use imagenet::*;
 pub use mnist::*;
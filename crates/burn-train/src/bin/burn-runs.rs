@@ -0,0 +1,100 @@
+//! Lists the runs recorded by a [RunTracker](burn_train::tracking::RunTracker), compares their
+//! configs and prints their metrics side by side.
+
+use burn_train::tracking::{ConfigDiff, MetricsTable, Run, RunTracker};
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage:
+    burn-runs list <directory>
+    burn-runs diff <directory> <run> <run>...
+    burn-runs metrics <directory> <metric>[,<metric>...] [<run>...]
+
+A run is selected by identifier (e.g. 3 or run-3) or by name, in which case the most recent run
+with that name is used. Without runs, the metrics of all the runs are printed.";
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    let result = match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["list", directory] => list(&RunTracker::new(directory)),
+        ["diff", directory, ref runs @ ..] if runs.len() >= 2 => {
+            diff(&RunTracker::new(directory), runs)
+        }
+        ["metrics", directory, metrics, ref runs @ ..] => {
+            metrics_table(&RunTracker::new(directory), metrics, runs)
+        }
+        _ => Err(USAGE.to_string()),
+    };
+
+    match result {
+        Ok(output) => {
+            print!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn list(tracker: &RunTracker) -> Result<String, String> {
+    let mut output = String::new();
+    for run in tracker.runs() {
+        let info = run.info();
+        let commit = match &info.git {
+            Some(git) if git.dirty => format!("{:.8}+dirty", git.commit),
+            Some(git) => format!("{:.8}", git.commit),
+            None => "-".to_string(),
+        };
+        let epochs = info
+            .epochs
+            .map(|epochs| epochs.to_string())
+            .unwrap_or_else(|| "-".to_string());
+
+        output += &format!(
+            "run-{:<4} {:<24} {:<12} epochs: {:<6} duration: {:<10} commit: {}\n",
+            info.id,
+            info.name,
+            info.status,
+            epochs,
+            format_duration(info.duration()),
+            commit,
+        );
+    }
+
+    Ok(output)
+}
+
+fn diff(tracker: &RunTracker, runs: &[&str]) -> Result<String, String> {
+    let runs = find_runs(tracker, runs)?;
+    let diff = ConfigDiff::new(&runs)?;
+
+    match diff.fields.is_empty() {
+        true => Ok("The configs are identical.\n".to_string()),
+        false => Ok(diff.to_string()),
+    }
+}
+
+fn metrics_table(tracker: &RunTracker, metrics: &str, runs: &[&str]) -> Result<String, String> {
+    let metrics = metrics.split(',').collect::<Vec<_>>();
+    let runs = match runs.is_empty() {
+        true => tracker.runs(),
+        false => find_runs(tracker, runs)?,
+    };
+
+    Ok(MetricsTable::new(&runs, &metrics).to_string())
+}
+
+fn find_runs(tracker: &RunTracker, runs: &[&str]) -> Result<Vec<Run>, String> {
+    runs.iter().map(|run| tracker.run(run)).collect()
+}
+
+fn format_duration(seconds: u64) -> String {
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
@@ -10,6 +10,7 @@ enum Message<R, B: Backend> {
     ),
     Save(usize, R),
     Delete(usize),
+    Sync(mpsc::SyncSender<()>),
     End,
 }
 
@@ -42,6 +43,9 @@ where
                     .checkpointer
                     .delete(epoch)
                     .expect("Can delete the state."),
+                Message::Sync(callback) => callback
+                    .send(())
+                    .expect("Can send response through callback channel."),
                 Message::End => {
                     return;
                 }
@@ -116,6 +120,17 @@ where
 
         Ok(())
     }
+
+    fn sync(&self) -> Result<(), CheckpointerError> {
+        let (sender, receiver) = mpsc::sync_channel(1);
+        self.sender
+            .send(Message::Sync(sender))
+            .map_err(|e| CheckpointerError::Unknown(e.to_string()))?;
+
+        receiver
+            .recv()
+            .map_err(|_| CheckpointerError::Unknown("Channel error.".to_string()))
+    }
 }
 
 impl<E, B> Drop for AsyncCheckpointer<E, B>
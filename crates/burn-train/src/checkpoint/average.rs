@@ -0,0 +1,272 @@
+use core::marker::PhantomData;
+
+use super::{Checkpointer, CheckpointerError};
+use burn_core::{
+    module::{Module, ModuleMapper, ModuleVisitor, ParamId},
+    tensor::{Tensor, backend::Backend, container::TensorContainer},
+};
+
+/// How the parameters of multiple modules are averaged.
+#[derive(Debug, Clone, Copy)]
+pub enum AveragingStrategy {
+    /// Every module has the same weight.
+    Uniform,
+
+    /// Exponential moving average, where the running average is multiplied by the `decay` before
+    /// adding the new module with a weight of `1 - decay`.
+    Ema {
+        /// The decay of the running average.
+        decay: f64,
+    },
+}
+
+/// Keeps a running average of the parameters of a [module](Module).
+///
+/// The average is computed for every float tensor visited by the module, which includes the
+/// running states such as the batch norm statistics.
+pub struct ModuleAverage<B: Backend> {
+    params: TensorContainer<ParamId>,
+    strategy: AveragingStrategy,
+    count: usize,
+    backend: PhantomData<B>,
+}
+
+impl<B: Backend> ModuleAverage<B> {
+    /// Creates a new empty average.
+    pub fn new(strategy: AveragingStrategy) -> Self {
+        Self {
+            params: TensorContainer::new(),
+            strategy,
+            count: 0,
+            backend: PhantomData,
+        }
+    }
+
+    /// The number of modules added to the average.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Add the parameters of the given module to the average.
+    pub fn update<M: Module<B>>(&mut self, module: &M) {
+        let weight = match self.strategy {
+            _ if self.count == 0 => 1.0,
+            AveragingStrategy::Uniform => 1.0 / (self.count + 1) as f64,
+            AveragingStrategy::Ema { decay } => 1.0 - decay,
+        };
+
+        let mut visitor = ModuleAverageUpdater::<B> {
+            params: &mut self.params,
+            weight,
+            backend: PhantomData,
+        };
+        module.visit(&mut visitor);
+        self.count += 1;
+    }
+
+    /// Restore an average of the given number of modules from a module holding the averaged
+    /// parameters, e.g. a module on which the average was [applied](Self::apply).
+    pub fn restore<M: Module<B>>(&mut self, module: &M, count: usize) {
+        self.params = TensorContainer::new();
+        self.count = 0;
+        self.update(module);
+        self.count = count;
+    }
+
+    /// Load the averaged parameters into the given module.
+    ///
+    /// Parameters that were never added to the average are kept as is.
+    pub fn apply<M: Module<B>>(&self, module: M) -> M {
+        let mut mapper = ModuleAverageLoader::<B> {
+            params: &self.params,
+            backend: PhantomData,
+        };
+        module.map(&mut mapper)
+    }
+}
+
+/// Average the checkpoints of a module saved at the given epochs.
+///
+/// # Arguments
+///
+/// * `module` - The module in which the checkpoints are loaded, e.g. a freshly initialized one.
+/// * `checkpointer` - The checkpointer used to save the module records, e.g. a
+///   [file checkpointer](super::FileCheckpointer).
+/// * `epochs` - The epochs of the checkpoints to average, in the order they are added.
+/// * `strategy` - How the checkpoints are averaged.
+/// * `device` - The device used to restore the records.
+///
+/// # Returns
+///
+/// The module of the last checkpoint with the averaged parameters.
+pub fn average_checkpoints<B, M, C>(
+    module: M,
+    checkpointer: &C,
+    epochs: &[usize],
+    strategy: AveragingStrategy,
+    device: &B::Device,
+) -> Result<M, CheckpointerError>
+where
+    B: Backend,
+    M: Module<B>,
+    C: Checkpointer<M::Record, B>,
+{
+    if epochs.is_empty() {
+        return Err(CheckpointerError::Unknown(
+            "At least one checkpoint is required to compute an average.".to_string(),
+        ));
+    }
+
+    let mut average = ModuleAverage::new(strategy);
+    let mut module = module;
+
+    // The parameters are averaged by id, which are the ids of the records rather than the ones
+    // of the given module, so the averages are applied to the last loaded checkpoint.
+    for epoch in epochs {
+        let record = checkpointer.restore(*epoch, device)?;
+        module = module.load_record(record);
+        average.update(&module);
+    }
+
+    Ok(average.apply(module))
+}
+
+struct ModuleAverageUpdater<'a, B: Backend> {
+    params: &'a mut TensorContainer<ParamId>,
+    weight: f64,
+    backend: PhantomData<B>,
+}
+
+impl<B: Backend> ModuleVisitor<B> for ModuleAverageUpdater<'_, B> {
+    fn visit_float<const D: usize>(&mut self, id: ParamId, tensor: &Tensor<B, D>) {
+        // Detached so that averaging autodiff modules doesn't track any operation.
+        let tensor = tensor.clone().detach();
+
+        let average = match self
+            .params
+            .remove::<B>(&id)
+            .map(Tensor::<B, D>::from_primitive)
+        {
+            Some(average) => average
+                .mul_scalar(1.0 - self.weight)
+                .add(tensor.mul_scalar(self.weight)),
+            None => tensor,
+        };
+
+        self.params.register::<B>(id, average.into_primitive());
+    }
+}
+
+struct ModuleAverageLoader<'a, B: Backend> {
+    params: &'a TensorContainer<ParamId>,
+    backend: PhantomData<B>,
+}
+
+impl<B: Backend> ModuleMapper<B> for ModuleAverageLoader<'_, B> {
+    fn map_float<const D: usize>(&mut self, id: ParamId, tensor: Tensor<B, D>) -> Tensor<B, D> {
+        let Some(average) = self.params.get::<B>(&id) else {
+            return tensor;
+        };
+
+        Tensor::<B, D>::from_primitive(average)
+            .to_device(&tensor.device())
+            .detach()
+            .set_require_grad(tensor.is_require_grad())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestBackend;
+    use crate::checkpoint::FileCheckpointer;
+    use crate::tests::test_directory;
+    use burn_core::{
+        module::Param,
+        nn::{Linear, LinearConfig},
+        record::{FullPrecisionSettings, NamedMpkFileRecorder},
+        tensor::{Tensor, TensorData},
+    };
+
+    fn module(values: [f32; 2]) -> Param<Tensor<TestBackend, 1>> {
+        Param::initialized(
+            ParamId::from(0),
+            Tensor::from_floats(values, &Default::default()),
+        )
+    }
+
+    #[test]
+    fn test_uniform_average() {
+        let mut average = ModuleAverage::new(AveragingStrategy::Uniform);
+
+        average.update(&module([1.0, 2.0]));
+        average.update(&module([3.0, 4.0]));
+        average.update(&module([5.0, 9.0]));
+
+        let averaged = average.apply(module([0.0, 0.0]));
+
+        averaged
+            .val()
+            .into_data()
+            .assert_approx_eq::<f32>(&TensorData::from([3.0, 5.0]), Default::default());
+        assert_eq!(average.count(), 3);
+    }
+
+    #[test]
+    fn test_ema_average() {
+        let mut average = ModuleAverage::new(AveragingStrategy::Ema { decay: 0.5 });
+
+        average.update(&module([1.0, 2.0]));
+        average.update(&module([3.0, 4.0]));
+        average.update(&module([5.0, 8.0]));
+
+        let averaged = average.apply(module([0.0, 0.0]));
+
+        averaged
+            .val()
+            .into_data()
+            .assert_approx_eq::<f32>(&TensorData::from([3.5, 5.5]), Default::default());
+    }
+
+    #[test]
+    fn test_average_checkpoints_into_a_new_module() {
+        let device = Default::default();
+        let directory = test_directory("average-checkpoints");
+        let checkpointer = FileCheckpointer::new(
+            NamedMpkFileRecorder::<FullPrecisionSettings>::new(),
+            &directory,
+            "model",
+        );
+        let trained: Linear<TestBackend> = LinearConfig::new(2, 1).init(&device);
+        let with_value = |linear: Linear<TestBackend>, value: f32| {
+            let mut linear = linear;
+            linear.weight = linear
+                .weight
+                .map(|weight| weight.zeros_like().add_scalar(value));
+            linear
+        };
+
+        // The checkpoints of a training share the parameter ids.
+        for (epoch, value) in [(1, 1.0), (2, 2.0), (3, 6.0)] {
+            let record = with_value(trained.clone(), value).into_record();
+            Checkpointer::<_, TestBackend>::save(&checkpointer, epoch, record).unwrap();
+        }
+
+        // The freshly initialized module has other parameter ids than the checkpoints.
+        let averaged: Linear<TestBackend> = average_checkpoints(
+            LinearConfig::new(2, 1).init(&device),
+            &checkpointer,
+            &[1, 2, 3],
+            AveragingStrategy::Uniform,
+            &device,
+        )
+        .unwrap();
+        std::fs::remove_dir_all(directory).ok();
+
+        averaged
+            .weight
+            .val()
+            .into_data()
+            .assert_approx_eq::<f32>(&TensorData::from([[3.0], [3.0]]), Default::default());
+    }
+}
@@ -44,4 +44,9 @@ where
     ///
     /// The record.
     fn restore(&self, epoch: usize, device: &B::Device) -> Result<R, CheckpointerError>;
+
+    /// Wait until the records given to the checkpointer are saved.
+    fn sync(&self) -> Result<(), CheckpointerError> {
+        Ok(())
+    }
 }
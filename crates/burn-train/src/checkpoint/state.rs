@@ -0,0 +1,163 @@
+use std::path::{Path, PathBuf};
+
+use super::CheckpointerError;
+use crate::learner::{EarlyStoppingState, SwaTrainingState};
+use burn_core::data::dataloader::DataLoaderState;
+use serde::{Deserialize, Serialize};
+
+const STATE_PREFIX: &str = "state-";
+const PARTIAL_PREFIX: &str = "partial-";
+
+/// The state of the training loop saved with each checkpoint, on top of the model, optimizer and
+/// learning rate scheduler records, so that the training resumes where the checkpoint was saved.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrainingState {
+    /// The epoch during which, or at the end of which, the checkpoint was saved.
+    pub epoch: usize,
+    /// The number of training iterations done during the epoch, when the checkpoint was saved
+    /// before the end of the epoch.
+    pub iteration: Option<usize>,
+    /// The number of backward passes whose gradients were accumulated since the last optimizer
+    /// step.
+    pub accumulation: usize,
+    /// The ids of the parameters with accumulated gradients.
+    pub accumulated_params: Vec<u64>,
+    /// The state of the early stopping strategy.
+    pub early_stopping: Option<EarlyStoppingState>,
+    /// The number of optimizer steps done since the start of the training.
+    #[serde(default)]
+    pub step: usize,
+    /// The position of the training data loader, from which the training resumes.
+    #[serde(default)]
+    pub dataloader: Option<DataLoaderState>,
+    /// The state of the stochastic weight averaging.
+    #[serde(default)]
+    pub swa: Option<SwaTrainingState>,
+    /// The number of times each item of the training dataset was loaded, when
+    /// [registered](crate::LearnerBuilder::load_counts).
+    #[serde(default)]
+    pub load_counts: Option<Vec<u64>>,
+}
+
+impl TrainingState {
+    /// Whether the checkpoint was saved before the end of its epoch.
+    pub fn is_partial(&self) -> bool {
+        self.iteration.is_some()
+    }
+}
+
+/// Saves the [training states](TrainingState) as json files next to the other records of the
+/// checkpoints.
+pub(crate) struct TrainingStateCheckpointer {
+    directory: PathBuf,
+}
+
+impl TrainingStateCheckpointer {
+    pub(crate) fn new(directory: impl AsRef<Path>) -> Self {
+        let directory = directory.as_ref();
+        std::fs::create_dir_all(directory).ok();
+
+        Self {
+            directory: directory.to_path_buf(),
+        }
+    }
+
+    fn path(&self, epoch: usize, partial: bool) -> PathBuf {
+        let prefix = if partial { PARTIAL_PREFIX } else { "" };
+        self.directory
+            .join(format!("{STATE_PREFIX}{prefix}{epoch}.json"))
+    }
+
+    pub(crate) fn save(&self, state: &TrainingState) -> Result<(), CheckpointerError> {
+        let path = self.path(state.epoch, state.is_partial());
+        let content = serde_json::to_string_pretty(state)
+            .map_err(|err| CheckpointerError::Unknown(err.to_string()))?;
+
+        // The state is written to a temporary file first, so that an interrupted training never
+        // leaves an incomplete state.
+        let path_tmp = path.with_extension("json.tmp");
+        std::fs::write(&path_tmp, content).map_err(CheckpointerError::IOError)?;
+        std::fs::rename(path_tmp, path).map_err(CheckpointerError::IOError)
+    }
+
+    pub(crate) fn restore(
+        &self,
+        epoch: usize,
+        partial: bool,
+    ) -> Result<TrainingState, CheckpointerError> {
+        let content = std::fs::read_to_string(self.path(epoch, partial))
+            .map_err(CheckpointerError::IOError)?;
+
+        serde_json::from_str(&content).map_err(|err| CheckpointerError::Unknown(err.to_string()))
+    }
+
+    pub(crate) fn delete(&self, epoch: usize, partial: bool) -> Result<(), CheckpointerError> {
+        let path = self.path(epoch, partial);
+
+        if path.exists() {
+            std::fs::remove_file(path).map_err(CheckpointerError::IOError)?;
+        }
+
+        Ok(())
+    }
+
+    /// The state of the most recent checkpoint, a checkpoint saved during an epoch being more
+    /// recent than the one saved at the end of the previous epoch.
+    pub(crate) fn latest(&self) -> Option<TrainingState> {
+        let (epoch, partial) = std::fs::read_dir(&self.directory)
+            .ok()?
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .filter_map(|name| {
+                let name = name.strip_prefix(STATE_PREFIX)?.strip_suffix(".json")?;
+                match name.strip_prefix(PARTIAL_PREFIX) {
+                    Some(epoch) => Some((epoch.parse::<usize>().ok()?, true)),
+                    None => Some((name.parse::<usize>().ok()?, false)),
+                }
+            })
+            .max_by_key(|(epoch, partial)| (*epoch, !partial))?;
+
+        match self.restore(epoch, partial) {
+            Ok(state) => Some(state),
+            Err(err) => {
+                log::warn!("Could not restore the training state of epoch {epoch}: {err:?}");
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latest_state_prefers_partial_epoch() {
+        let directory = std::env::temp_dir().join(format!(
+            "burn-train-state-{}-{}",
+            std::process::id(),
+            line!()
+        ));
+        let checkpointer = TrainingStateCheckpointer::new(&directory);
+        let state = |epoch, iteration| TrainingState {
+            epoch,
+            iteration,
+            ..Default::default()
+        };
+
+        assert_eq!(checkpointer.latest(), None);
+
+        checkpointer.save(&state(1, None)).unwrap();
+        checkpointer.save(&state(2, Some(30))).unwrap();
+        assert_eq!(checkpointer.latest(), Some(state(2, Some(30))));
+
+        checkpointer.save(&state(2, None)).unwrap();
+        assert_eq!(checkpointer.latest(), Some(state(2, None)));
+
+        checkpointer.delete(2, false).unwrap();
+        checkpointer.delete(2, true).unwrap();
+        assert_eq!(checkpointer.restore(1, false).unwrap(), state(1, None));
+        assert_eq!(checkpointer.latest(), Some(state(1, None)));
+
+        std::fs::remove_dir_all(directory).ok();
+    }
+}
@@ -0,0 +1,107 @@
+use core::ops::Range;
+
+/// The error type for collective operations.
+#[derive(Debug)]
+pub enum CollectiveError {
+    /// IO error.
+    IOError(std::io::Error),
+
+    /// The connection with the given rank was closed.
+    Disconnected(usize),
+
+    /// Other errors.
+    Unknown(String),
+}
+
+impl core::fmt::Display for CollectiveError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::IOError(err) => write!(f, "IO error: {err}"),
+            Self::Disconnected(rank) => write!(f, "The connection with rank {rank} was closed"),
+            Self::Unknown(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for CollectiveError {}
+
+impl From<std::io::Error> for CollectiveError {
+    fn from(err: std::io::Error) -> Self {
+        Self::IOError(err)
+    }
+}
+
+/// How the values of the ranks are reduced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReduceOp {
+    /// The sum of the values.
+    Sum,
+    /// The mean of the values.
+    Mean,
+}
+
+/// The algorithm used to exchange the values between the ranks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CollectiveAlgorithm {
+    /// The ranks form a ring and each one only exchanges with its neighbours.
+    ///
+    /// Every rank sends and receives about twice the size of the buffer for an all-reduce,
+    /// independently of the number of ranks, which is bandwidth optimal for large buffers.
+    #[default]
+    Ring,
+
+    /// The ranks form a binomial tree rooted at the first rank.
+    ///
+    /// Only a logarithmic number of messages is exchanged sequentially, which has a lower latency
+    /// for small buffers.
+    Tree,
+}
+
+/// Point-to-point communication between the ranks of a collective group.
+///
+/// Messages between two ranks must be received in the order they were sent. Sending must not
+/// wait for the message to be received, otherwise the collective operations would deadlock.
+pub trait Transport: Send {
+    /// The rank of this end of the group.
+    fn rank(&self) -> usize;
+
+    /// The number of ranks in the group.
+    fn world_size(&self) -> usize;
+
+    /// Send a message to the given rank.
+    fn send(&mut self, rank: usize, message: Vec<f32>) -> Result<(), CollectiveError>;
+
+    /// Receive the next message from the given rank.
+    fn recv(&mut self, rank: usize) -> Result<Vec<f32>, CollectiveError>;
+}
+
+impl<T: Transport + ?Sized> Transport for Box<T> {
+    fn rank(&self) -> usize {
+        (**self).rank()
+    }
+
+    fn world_size(&self) -> usize {
+        (**self).world_size()
+    }
+
+    fn send(&mut self, rank: usize, message: Vec<f32>) -> Result<(), CollectiveError> {
+        (**self).send(rank, message)
+    }
+
+    fn recv(&mut self, rank: usize) -> Result<Vec<f32>, CollectiveError> {
+        (**self).recv(rank)
+    }
+}
+
+/// The range of a buffer of the given length owned by a rank after a
+/// [reduce-scatter](super::Collective::reduce_scatter).
+///
+/// The buffer is split in contiguous shards whose lengths differ by at most one.
+pub fn shard_range(len: usize, world_size: usize, rank: usize) -> Range<usize> {
+    let base = len / world_size;
+    let remainder = len % world_size;
+    let start = rank * base + rank.min(remainder);
+    let end = start + base + usize::from(rank < remainder);
+
+    start..end
+}
@@ -0,0 +1,496 @@
+use core::marker::PhantomData;
+
+use super::{Collective, CollectiveError, ReduceOp, Transport};
+use burn_core::{
+    module::{AutodiffModule, Module, ModuleMapper, ModuleVisitor, ParamId},
+    optim::GradientsParams,
+    tensor::{
+        Shape, Tensor, TensorData,
+        backend::{AutodiffBackend, Backend},
+    },
+};
+
+impl<T: Transport> Collective<T> {
+    /// All-reduce the gradients of the module, so that every rank ends up with the same reduced
+    /// gradients.
+    ///
+    /// The gradients are exchanged as a single buffer in the order the parameters are visited,
+    /// so every rank must have the gradients of the same parameters. The reduced gradients are
+    /// placed on the devices of the given gradients.
+    pub fn all_reduce_gradients<B, M>(
+        &mut self,
+        module: &M,
+        mut grads: GradientsParams,
+        op: ReduceOp,
+    ) -> Result<GradientsParams, CollectiveError>
+    where
+        B: AutodiffBackend,
+        M: AutodiffModule<B>,
+    {
+        let mut flatten = GradientsFlatten::<B::InnerBackend, M, B> {
+            grads: &mut grads,
+            tensors: Vec::new(),
+            entries: Vec::new(),
+            phantom: PhantomData,
+        };
+        module.visit(&mut flatten);
+        let GradientsFlatten {
+            tensors, entries, ..
+        } = flatten;
+        let mut buffer = flatten_values(tensors);
+
+        self.all_reduce(&mut buffer, op)?;
+
+        let values = entries
+            .first()
+            .map(|(_, _, device)| unflatten_values(buffer, device));
+        let mut unflatten = GradientsUnflatten::<B::InnerBackend, M, B> {
+            grads: &mut grads,
+            values,
+            entries: entries.into_iter(),
+            offset: 0,
+            phantom: PhantomData,
+        };
+        module.visit(&mut unflatten);
+
+        Ok(grads)
+    }
+
+    /// Copy the parameters of the module of the given root rank to all the ranks, e.g. to make
+    /// sure the replicas start from the same parameters.
+    pub fn broadcast_module<B, M>(&mut self, module: M, root: usize) -> Result<M, CollectiveError>
+    where
+        B: Backend,
+        M: Module<B>,
+    {
+        let mut flatten = ParamsFlatten::<B> {
+            buffer: Vec::new(),
+            phantom: PhantomData,
+        };
+        module.visit(&mut flatten);
+        let mut buffer = flatten.buffer;
+
+        self.broadcast(&mut buffer, root)?;
+
+        let mut unflatten = ParamsUnflatten::<B> {
+            buffer: &buffer,
+            offset: 0,
+            phantom: PhantomData,
+        };
+        Ok(module.map(&mut unflatten))
+    }
+}
+
+/// All-reduce the gradients computed on multiple devices of the same process.
+///
+/// The gradients of every parameter are reduced with a ring all-reduce between the devices,
+/// without copying them to the host: every device only exchanges a fraction of the gradients
+/// with its neighbours, instead of sending all of them to a single device. The reduced gradients
+/// are returned in the same order as the given ones, each one on the devices of the
+/// corresponding input.
+///
+/// # Errors
+///
+/// Every device must have the gradients of the same parameters, an error is returned otherwise.
+pub fn all_reduce_devices<B, M>(
+    module: &M,
+    mut grads: Vec<GradientsParams>,
+    op: ReduceOp,
+) -> Result<Vec<GradientsParams>, CollectiveError>
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B>,
+{
+    let mut reduce = GradientsDevicesReduce::<M, B> {
+        grads: &mut grads,
+        op,
+        error: None,
+        phantom: PhantomData,
+    };
+    module.visit(&mut reduce);
+
+    match reduce.error {
+        Some(err) => Err(err),
+        None => Ok(grads),
+    }
+}
+
+pub(super) fn to_values<B: Backend, const D: usize>(tensor: Tensor<B, D>) -> Vec<f32> {
+    tensor
+        .into_data()
+        .convert::<f32>()
+        .into_vec::<f32>()
+        .expect("Float tensor data converted to f32")
+}
+
+/// Concatenate the flattened tensors on the device of the first one, then copy the values to
+/// the host in a single transfer.
+pub(super) fn flatten_values<B: Backend>(tensors: Vec<Tensor<B, 1>>) -> Vec<f32> {
+    let Some(device) = tensors.first().map(Tensor::device) else {
+        return Vec::new();
+    };
+    let tensors = tensors
+        .into_iter()
+        .map(|tensor| tensor.to_device(&device))
+        .collect();
+
+    to_values(Tensor::cat(tensors, 0))
+}
+
+/// Copy the values to the device in a single transfer, to be sliced into tensors there.
+pub(super) fn unflatten_values<B: Backend>(values: Vec<f32>, device: &B::Device) -> Tensor<B, 1> {
+    let len = values.len();
+    Tensor::from_data(TensorData::new(values, [len]), device)
+}
+
+struct GradientsDevicesReduce<'a, M, B> {
+    grads: &'a mut [GradientsParams],
+    op: ReduceOp,
+    error: Option<CollectiveError>,
+    phantom: PhantomData<(M, B)>,
+}
+
+impl<B, M> ModuleVisitor<B> for GradientsDevicesReduce<'_, M, B>
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B>,
+{
+    fn visit_float<const D: usize>(&mut self, id: ParamId, _tensor: &Tensor<B, D>) {
+        if self.error.is_some() {
+            return;
+        }
+
+        let grads = self
+            .grads
+            .iter_mut()
+            .map(|grads| grads.remove::<B::InnerBackend, D>(id))
+            .collect::<Vec<_>>();
+        if grads.iter().all(Option::is_none) {
+            return;
+        }
+        if let Some(device) = grads.iter().position(Option::is_none) {
+            self.error = Some(CollectiveError::Unknown(format!(
+                "The gradients of the parameter {id} are missing on the device {device}, every \
+                 device must have the gradients of the same parameters"
+            )));
+            return;
+        }
+
+        let grads = grads.into_iter().flatten().collect::<Vec<_>>();
+        let shape = grads[0].shape();
+        let grads = grads
+            .into_iter()
+            .map(|grad| grad.reshape([shape.num_elements()]))
+            .collect();
+
+        let reduced = ring_all_reduce(grads, self.op);
+        for (grads, grad) in self.grads.iter_mut().zip(reduced) {
+            grads.register::<B::InnerBackend, D>(id, grad.reshape(shape.clone()));
+        }
+    }
+}
+
+/// Ring all-reduce of tensors of the same length, each one on a different device.
+///
+/// The tensors are split into one chunk per device. During the reduce-scatter, every device
+/// adds the chunk received from the previous device of the ring to its own and sends the sum to
+/// the next one, so that every device holds a different fully reduced chunk after `n - 1`
+/// steps. The reduced chunks then go around the ring during the all-gather. At every step, each
+/// device sends a single chunk, so the transfers are spread over all the devices.
+fn ring_all_reduce<B: Backend>(tensors: Vec<Tensor<B, 1>>, op: ReduceOp) -> Vec<Tensor<B, 1>> {
+    let num_devices = tensors.len();
+    let len = tensors[0].dims()[0];
+    let devices = tensors.iter().map(Tensor::device).collect::<Vec<_>>();
+    let next = |device: usize| (device + 1) % num_devices;
+
+    // The chunks of every device, the last ones are empty with fewer values than devices.
+    let mut chunks = tensors
+        .iter()
+        .map(|tensor| {
+            (0..num_devices)
+                .map(|chunk| {
+                    let start = chunk * len / num_devices;
+                    let end = (chunk + 1) * len / num_devices;
+                    (end > start).then(|| tensor.clone().narrow(0, start, end - start))
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    // The chunks sent at a step, by every device to the next one.
+    let send = |chunks: &[Vec<Option<Tensor<B, 1>>>], chunk_of: &dyn Fn(usize) -> usize| {
+        (0..num_devices)
+            .map(|device| {
+                let chunk = chunk_of(device);
+                let values = chunks[device][chunk].clone();
+                (
+                    chunk,
+                    values.map(|values| values.to_device(&devices[next(device)])),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    for step in 0..num_devices - 1 {
+        let sent = send(&chunks, &|device| {
+            (device + num_devices - step) % num_devices
+        });
+        for (device, (chunk, values)) in sent.into_iter().enumerate() {
+            let received = &mut chunks[next(device)][chunk];
+            *received = received.take().zip(values).map(|(lhs, rhs)| lhs + rhs);
+        }
+    }
+
+    // Every device holds the reduced chunk following its own index.
+    if op == ReduceOp::Mean {
+        for (device, chunks) in chunks.iter_mut().enumerate() {
+            let reduced = &mut chunks[next(device)];
+            *reduced = reduced
+                .take()
+                .map(|values| values.div_scalar(num_devices as f32));
+        }
+    }
+
+    for step in 0..num_devices - 1 {
+        let sent = send(&chunks, &|device| {
+            (device + 1 + num_devices - step) % num_devices
+        });
+        for (device, (chunk, values)) in sent.into_iter().enumerate() {
+            chunks[next(device)][chunk] = values;
+        }
+    }
+
+    chunks
+        .into_iter()
+        .zip(tensors)
+        .map(|(chunks, tensor)| match len {
+            0 => tensor,
+            _ => Tensor::cat(chunks.into_iter().flatten().collect(), 0),
+        })
+        .collect()
+}
+
+struct GradientsFlatten<'a, B: Backend, M, BA> {
+    grads: &'a mut GradientsParams,
+    tensors: Vec<Tensor<B, 1>>,
+    entries: Vec<(ParamId, Shape, B::Device)>,
+    phantom: PhantomData<(M, BA)>,
+}
+
+impl<B, M> ModuleVisitor<B> for GradientsFlatten<'_, B::InnerBackend, M, B>
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B>,
+{
+    fn visit_float<const D: usize>(&mut self, id: ParamId, _tensor: &Tensor<B, D>) {
+        let Some(grad) = self.grads.remove::<B::InnerBackend, D>(id) else {
+            return;
+        };
+
+        let shape = grad.shape();
+        self.entries.push((id, shape.clone(), grad.device()));
+        self.tensors.push(grad.reshape([shape.num_elements()]));
+    }
+}
+
+struct GradientsUnflatten<'a, B: Backend, M, BA> {
+    grads: &'a mut GradientsParams,
+    // The reduced buffer, on the device of the first gradient.
+    values: Option<Tensor<B, 1>>,
+    entries: std::vec::IntoIter<(ParamId, Shape, B::Device)>,
+    offset: usize,
+    phantom: PhantomData<(M, BA)>,
+}
+
+impl<B, M> ModuleVisitor<B> for GradientsUnflatten<'_, B::InnerBackend, M, B>
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B>,
+{
+    fn visit_float<const D: usize>(&mut self, id: ParamId, _tensor: &Tensor<B, D>) {
+        // Parameters without gradients were skipped when flattening.
+        if self.entries.as_slice().first().map(|entry| entry.0) != Some(id) {
+            return;
+        }
+        let (id, shape, device) = self.entries.next().unwrap();
+
+        let len = shape.num_elements();
+        let values = self.values.clone().unwrap();
+        let grad = values
+            .narrow(0, self.offset, len)
+            .reshape(shape)
+            .to_device(&device);
+        self.offset += len;
+
+        self.grads.register::<B::InnerBackend, D>(id, grad);
+    }
+}
+
+struct ParamsFlatten<B: Backend> {
+    buffer: Vec<f32>,
+    phantom: PhantomData<B>,
+}
+
+impl<B: Backend> ModuleVisitor<B> for ParamsFlatten<B> {
+    fn visit_float<const D: usize>(&mut self, _id: ParamId, tensor: &Tensor<B, D>) {
+        self.buffer.extend(to_values(tensor.clone()));
+    }
+}
+
+struct ParamsUnflatten<'a, B: Backend> {
+    buffer: &'a [f32],
+    offset: usize,
+    phantom: PhantomData<B>,
+}
+
+impl<B: Backend> ModuleMapper<B> for ParamsUnflatten<'_, B> {
+    fn map_float<const D: usize>(&mut self, _id: ParamId, tensor: Tensor<B, D>) -> Tensor<B, D> {
+        let shape = tensor.shape();
+        let len = shape.num_elements();
+        let values = self.buffer[self.offset..self.offset + len].to_vec();
+        self.offset += len;
+
+        let param = Tensor::from_data(TensorData::new(values, shape), &tensor.device());
+        if tensor.is_require_grad() {
+            param.require_grad()
+        } else {
+            param
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestAutodiffBackend;
+    use crate::collective::{CollectiveAlgorithm, LocalTransport};
+    use burn_core::module::Param;
+    use std::thread::spawn;
+
+    fn module(values: [f32; 2]) -> Param<Tensor<TestAutodiffBackend, 1>> {
+        Param::initialized(
+            ParamId::from(0),
+            Tensor::from_floats(values, &Default::default()).require_grad(),
+        )
+    }
+
+    #[test]
+    fn test_all_reduce_gradients() {
+        let handles = LocalTransport::group(3)
+            .into_iter()
+            .map(|transport| {
+                spawn(move || {
+                    let mut collective =
+                        Collective::new(transport).with_algorithm(CollectiveAlgorithm::Tree);
+                    let scale = (collective.rank() + 1) as f32;
+                    let module = module([1.0, 2.0]);
+                    let loss = module.val().mul_scalar(scale).sum();
+                    let grads = GradientsParams::from_grads(loss.backward(), &module);
+
+                    let grads = collective
+                        .all_reduce_gradients(&module, grads, ReduceOp::Mean)
+                        .unwrap();
+                    grads
+                        .get::<crate::TestBackend, 1>(module.id)
+                        .unwrap()
+                        .into_data()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            handle
+                .join()
+                .unwrap()
+                .assert_eq(&TensorData::from([2.0, 2.0]), false);
+        }
+    }
+
+    #[test]
+    fn test_all_reduce_devices() {
+        let module = module([1.0, 2.0]);
+        let grads = (1..=2)
+            .map(|scale| {
+                let loss = module.val().mul_scalar(scale).sum();
+                GradientsParams::from_grads(loss.backward(), &module)
+            })
+            .collect();
+
+        let grads = all_reduce_devices(&module, grads, ReduceOp::Sum).unwrap();
+
+        assert_eq!(grads.len(), 2);
+        for grads in grads {
+            grads
+                .get::<crate::TestBackend, 1>(module.id)
+                .unwrap()
+                .into_data()
+                .assert_eq(&TensorData::from([3.0, 3.0]), false);
+        }
+    }
+
+    #[test]
+    fn test_ring_all_reduce_every_replica_has_the_reduced_values() {
+        let device = Default::default();
+
+        // More values than devices, and fewer values than devices.
+        for len in [7, 2] {
+            let values = |offset: f32| {
+                let values = (0..len)
+                    .map(|value| value as f32 + offset)
+                    .collect::<Vec<_>>();
+                TensorData::new(values, [len])
+            };
+            let tensors = (0..4)
+                .map(|replica| {
+                    Tensor::<crate::TestBackend, 1>::from_data(values(replica as f32), &device)
+                })
+                .collect();
+            let expected = values(1.5);
+
+            let reduced = ring_all_reduce(tensors, ReduceOp::Mean);
+
+            assert_eq!(reduced.len(), 4);
+            for tensor in reduced {
+                tensor
+                    .into_data()
+                    .assert_approx_eq::<f32>(&expected, Default::default());
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_reduce_devices_with_different_parameters() {
+        let module = module([1.0, 2.0]);
+        let loss = module.val().sum();
+        let grads = GradientsParams::from_grads(loss.backward(), &module);
+
+        let result =
+            all_reduce_devices(&module, vec![grads, GradientsParams::new()], ReduceOp::Sum);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_broadcast_module() {
+        let handles = LocalTransport::group(2)
+            .into_iter()
+            .map(|transport| {
+                spawn(move || {
+                    let mut collective = Collective::new(transport);
+                    let value = collective.rank() as f32;
+                    let module = collective
+                        .broadcast_module(module([value, value]), 1)
+                        .unwrap();
+
+                    (module.is_require_grad(), module.val().into_data())
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            let (require_grad, data) = handle.join().unwrap();
+            assert!(require_grad);
+            data.assert_eq(&TensorData::from([1.0, 1.0]), false);
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use std::process::{Child, Command};
+use std::thread::sleep;
+use std::time::Duration;
+
+use super::CollectiveError;
+
+/// Spawns the processes of a distributed training on the current node.
+///
+/// Each process receives its configuration through environment variables, which can be read
+/// with [DistributedConfig::from_env](super::DistributedConfig::from_env). For a training on
+/// multiple nodes, the launcher must be run on every node with the same master address.
+///
+/// # Example
+///
+/// ```rust, no_run
+/// use burn_train::collective::Launcher;
+/// use std::process::Command;
+///
+/// Launcher::new(4)
+///     .launch(|_local_rank| Command::new("./train"))
+///     .expect("All the processes should succeed");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Launcher {
+    nproc_per_node: usize,
+    num_nodes: usize,
+    node_rank: usize,
+    master_addr: String,
+    master_port: u16,
+}
+
+impl Launcher {
+    /// Creates a launcher for a single node training with the given number of processes, using
+    /// the port 29500 of the local host for the rendezvous.
+    pub fn new(nproc_per_node: usize) -> Self {
+        Self {
+            nproc_per_node,
+            num_nodes: 1,
+            node_rank: 0,
+            master_addr: "127.0.0.1".to_string(),
+            master_port: 29500,
+        }
+    }
+
+    /// Sets the number of nodes of the training and the rank of the current node.
+    ///
+    /// The node of rank 0 hosts the main process and must be reachable at the master address.
+    pub fn with_nodes(mut self, num_nodes: usize, node_rank: usize) -> Self {
+        self.num_nodes = num_nodes;
+        self.node_rank = node_rank;
+        self
+    }
+
+    /// Sets the address of the main process.
+    pub fn with_master_addr(mut self, host: impl Into<String>, port: u16) -> Self {
+        self.master_addr = host.into();
+        self.master_port = port;
+        self
+    }
+
+    /// Spawns the processes of the current node and waits for them to finish.
+    ///
+    /// The command of each process is created from its local rank. When a process fails, the
+    /// other ones are killed, since they would otherwise wait for it forever.
+    pub fn launch<F>(&self, mut command: F) -> Result<(), CollectiveError>
+    where
+        F: FnMut(usize) -> Command,
+    {
+        let world_size = self.num_nodes * self.nproc_per_node;
+        let mut children = Vec::with_capacity(self.nproc_per_node);
+
+        for local_rank in 0..self.nproc_per_node {
+            let rank = self.node_rank * self.nproc_per_node + local_rank;
+            let child = command(local_rank)
+                .env("RANK", rank.to_string())
+                .env("WORLD_SIZE", world_size.to_string())
+                .env("LOCAL_RANK", local_rank.to_string())
+                .env("MASTER_ADDR", &self.master_addr)
+                .env("MASTER_PORT", self.master_port.to_string())
+                .spawn();
+
+            match child {
+                Ok(child) => children.push(Some(child)),
+                Err(err) => {
+                    kill_all(&mut children);
+                    return Err(err.into());
+                }
+            }
+        }
+
+        while children.iter().any(Option::is_some) {
+            for (local_rank, slot) in children.iter_mut().enumerate() {
+                let Some(child) = slot else {
+                    continue;
+                };
+
+                let status = match child.try_wait() {
+                    Ok(None) => continue,
+                    Ok(Some(status)) => status,
+                    Err(err) => {
+                        kill_all(&mut children);
+                        return Err(err.into());
+                    }
+                };
+
+                slot.take();
+                if !status.success() {
+                    kill_all(&mut children);
+                    return Err(CollectiveError::Unknown(format!(
+                        "The process of local rank {local_rank} failed: {status}"
+                    )));
+                }
+            }
+
+            sleep(Duration::from_millis(50));
+        }
+
+        Ok(())
+    }
+}
+
+fn kill_all(children: &mut [Option<Child>]) {
+    for mut child in children.iter_mut().filter_map(Option::take) {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collective::{Collective, DistributedConfig, ReduceOp};
+    use std::net::TcpListener;
+
+    /// Runs the test binary again, only executing the given test.
+    fn test_command(test: &str) -> Command {
+        let mut command = Command::new(std::env::current_exe().unwrap());
+        command.args([test, "--exact", "--ignored", "--test-threads=1"]);
+        command
+    }
+
+    /// A port that is free for the rendezvous of a test.
+    fn free_port() -> u16 {
+        TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    }
+
+    #[test]
+    #[ignore = "Executed by the processes spawned by `test_launch_processes`"]
+    fn worker_all_reduce() {
+        if std::env::var("RANK").is_err() {
+            return;
+        }
+
+        let config = DistributedConfig::from_env().unwrap();
+        let mut collective = Collective::new(config.connect().unwrap());
+
+        let mut buffer = vec![config.rank as f32 + 1.0; 5];
+        collective.all_reduce(&mut buffer, ReduceOp::Sum).unwrap();
+
+        assert_eq!(buffer, vec![6.0; 5]);
+    }
+
+    #[test]
+    fn test_launch_processes() {
+        Launcher::new(3)
+            .with_master_addr("127.0.0.1", free_port())
+            .launch(|_| test_command("collective::launcher::tests::worker_all_reduce"))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_launch_should_fail_when_a_process_fails() {
+        let launcher = Launcher::new(2).with_master_addr("127.0.0.1", free_port());
+        let result = launcher.launch(|local_rank| match local_rank {
+            0 => test_command("collective::launcher::tests::worker_all_reduce"),
+            _ => {
+                let mut command = test_command("");
+                command.arg("--unknown-flag");
+                command
+            }
+        });
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,60 @@
+use std::sync::mpsc::{Receiver, Sender, channel};
+
+use super::{CollectiveError, Transport};
+
+/// Transport between threads of the same process, e.g. one thread per device.
+pub struct LocalTransport {
+    rank: usize,
+    senders: Vec<Sender<Vec<f32>>>,
+    receivers: Vec<Receiver<Vec<f32>>>,
+}
+
+impl LocalTransport {
+    /// Creates the transports of a group with the given number of ranks, ordered by rank.
+    pub fn group(world_size: usize) -> Vec<Self> {
+        let mut senders = (0..world_size).map(|_| Vec::new()).collect::<Vec<_>>();
+        let mut receivers = (0..world_size).map(|_| Vec::new()).collect::<Vec<_>>();
+
+        // The message from `i` to `j` goes through `senders[i][j]` and `receivers[j][i]`.
+        for from_senders in senders.iter_mut() {
+            for to_receivers in receivers.iter_mut() {
+                let (sender, receiver) = channel();
+                from_senders.push(sender);
+                to_receivers.push(receiver);
+            }
+        }
+
+        senders
+            .into_iter()
+            .zip(receivers)
+            .enumerate()
+            .map(|(rank, (senders, receivers))| Self {
+                rank,
+                senders,
+                receivers,
+            })
+            .collect()
+    }
+}
+
+impl Transport for LocalTransport {
+    fn rank(&self) -> usize {
+        self.rank
+    }
+
+    fn world_size(&self) -> usize {
+        self.senders.len()
+    }
+
+    fn send(&mut self, rank: usize, message: Vec<f32>) -> Result<(), CollectiveError> {
+        self.senders[rank]
+            .send(message)
+            .map_err(|_| CollectiveError::Disconnected(rank))
+    }
+
+    fn recv(&mut self, rank: usize) -> Result<Vec<f32>, CollectiveError> {
+        self.receivers[rank]
+            .recv()
+            .map_err(|_| CollectiveError::Disconnected(rank))
+    }
+}
@@ -0,0 +1,17 @@
+mod base;
+mod gradients;
+mod launcher;
+mod local;
+mod ops;
+mod rendezvous;
+mod tcp;
+mod zero;
+
+pub use base::*;
+pub use gradients::all_reduce_devices;
+pub use launcher::*;
+pub use local::*;
+pub use ops::*;
+pub use rendezvous::*;
+pub use tcp::*;
+pub use zero::*;
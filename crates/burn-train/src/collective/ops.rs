@@ -0,0 +1,371 @@
+use super::{CollectiveAlgorithm, CollectiveError, ReduceOp, Transport, shard_range};
+
+/// Collective operations between the ranks of a group, on buffers of values.
+///
+/// Every rank of the group must call the same operations in the same order, with buffers of the
+/// same length.
+pub struct Collective<T: Transport> {
+    transport: T,
+    algorithm: CollectiveAlgorithm,
+}
+
+impl<T: Transport> Collective<T> {
+    /// Creates the collective operations over the given transport with the
+    /// [ring algorithm](CollectiveAlgorithm::Ring).
+    pub fn new(transport: T) -> Self {
+        Self {
+            transport,
+            algorithm: CollectiveAlgorithm::default(),
+        }
+    }
+
+    /// Sets the algorithm used to exchange the values.
+    pub fn with_algorithm(mut self, algorithm: CollectiveAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Erase the type of the transport, e.g. to choose it at runtime.
+    pub fn boxed(self) -> Collective<Box<dyn Transport>>
+    where
+        T: 'static,
+    {
+        Collective {
+            transport: Box::new(self.transport),
+            algorithm: self.algorithm,
+        }
+    }
+
+    /// The rank of this end of the group.
+    pub fn rank(&self) -> usize {
+        self.transport.rank()
+    }
+
+    /// The number of ranks in the group.
+    pub fn world_size(&self) -> usize {
+        self.transport.world_size()
+    }
+
+    /// Reduce the buffers of all the ranks, every rank receiving the result.
+    pub fn all_reduce(&mut self, buffer: &mut [f32], op: ReduceOp) -> Result<(), CollectiveError> {
+        match self.algorithm {
+            CollectiveAlgorithm::Ring => {
+                self.ring_reduce_scatter(buffer)?;
+                self.ring_all_gather(buffer)?;
+            }
+            CollectiveAlgorithm::Tree => {
+                self.tree_reduce(buffer)?;
+                self.tree_broadcast(buffer)?;
+            }
+        }
+
+        if op == ReduceOp::Mean {
+            let world_size = self.world_size() as f32;
+            buffer.iter_mut().for_each(|value| *value /= world_size);
+        }
+
+        Ok(())
+    }
+
+    /// Copy the buffer of the given root rank to all the ranks.
+    pub fn broadcast(&mut self, buffer: &mut [f32], root: usize) -> Result<(), CollectiveError> {
+        let world_size = self.world_size();
+        if world_size == 1 {
+            return Ok(());
+        }
+
+        match self.algorithm {
+            CollectiveAlgorithm::Ring => {
+                let rank = self.rank();
+                let next = (rank + 1) % world_size;
+                let previous = (rank + world_size - 1) % world_size;
+
+                // The buffer is forwarded in shards so that the ranks work as a pipeline.
+                for shard in 0..world_size {
+                    let range = shard_range(buffer.len(), world_size, shard);
+                    if rank != root {
+                        let message = self.transport.recv(previous)?;
+                        buffer[range.clone()].copy_from_slice(&message);
+                    }
+                    if next != root {
+                        self.transport.send(next, buffer[range].to_vec())?;
+                    }
+                }
+            }
+            CollectiveAlgorithm::Tree => {
+                // The binomial tree is rooted at the first rank, so the buffer is moved there
+                // first.
+                if root != 0 {
+                    if self.rank() == root {
+                        self.transport.send(0, buffer.to_vec())?;
+                    }
+                    if self.rank() == 0 {
+                        let message = self.transport.recv(root)?;
+                        buffer.copy_from_slice(&message);
+                    }
+                }
+                self.tree_broadcast(buffer)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reduce the buffers of all the ranks, each rank only receiving its
+    /// [shard](shard_range) of the result.
+    pub fn reduce_scatter(
+        &mut self,
+        buffer: &[f32],
+        op: ReduceOp,
+    ) -> Result<Vec<f32>, CollectiveError> {
+        let mut buffer = buffer.to_vec();
+
+        match self.algorithm {
+            CollectiveAlgorithm::Ring => self.ring_reduce_scatter(&mut buffer)?,
+            CollectiveAlgorithm::Tree => {
+                self.tree_reduce(&mut buffer)?;
+                self.tree_broadcast(&mut buffer)?;
+            }
+        }
+
+        let mut shard = buffer[shard_range(buffer.len(), self.world_size(), self.rank())].to_vec();
+        if op == ReduceOp::Mean {
+            let world_size = self.world_size() as f32;
+            shard.iter_mut().for_each(|value| *value /= world_size);
+        }
+
+        Ok(shard)
+    }
+
+    /// Concatenate the [shards](shard_range) of all the ranks into a buffer of the given
+    /// length, every rank receiving the result.
+    pub fn all_gather(&mut self, shard: &[f32], len: usize) -> Result<Vec<f32>, CollectiveError> {
+        let range = shard_range(len, self.world_size(), self.rank());
+        if range.len() != shard.len() {
+            return Err(CollectiveError::Unknown(format!(
+                "Expected a shard of length {} for rank {}, got {}",
+                range.len(),
+                self.rank(),
+                shard.len()
+            )));
+        }
+
+        let mut buffer = vec![0.0; len];
+        buffer[range].copy_from_slice(shard);
+
+        match self.algorithm {
+            CollectiveAlgorithm::Ring => self.ring_all_gather(&mut buffer)?,
+            CollectiveAlgorithm::Tree => {
+                // The other shards are zeros, so reducing by sum gathers them.
+                self.tree_reduce(&mut buffer)?;
+                self.tree_broadcast(&mut buffer)?;
+            }
+        }
+
+        Ok(buffer)
+    }
+
+    /// Ring reduce-scatter, after which each rank holds the sum of its own shard.
+    fn ring_reduce_scatter(&mut self, buffer: &mut [f32]) -> Result<(), CollectiveError> {
+        let (rank, world_size) = (self.rank(), self.world_size());
+        let next = (rank + 1) % world_size;
+        let previous = (rank + world_size - 1) % world_size;
+
+        for step in 0..world_size - 1 {
+            let send = (rank + 2 * world_size - step - 1) % world_size;
+            let recv = (rank + 2 * world_size - step - 2) % world_size;
+
+            let range = shard_range(buffer.len(), world_size, send);
+            self.transport.send(next, buffer[range].to_vec())?;
+
+            let message = self.transport.recv(previous)?;
+            let range = shard_range(buffer.len(), world_size, recv);
+            for (value, received) in buffer[range].iter_mut().zip(message) {
+                *value += received;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Ring all-gather, where each rank starts with its own shard.
+    fn ring_all_gather(&mut self, buffer: &mut [f32]) -> Result<(), CollectiveError> {
+        let (rank, world_size) = (self.rank(), self.world_size());
+        let next = (rank + 1) % world_size;
+        let previous = (rank + world_size - 1) % world_size;
+
+        for step in 0..world_size - 1 {
+            let send = (rank + world_size - step) % world_size;
+            let recv = (rank + 2 * world_size - step - 1) % world_size;
+
+            let range = shard_range(buffer.len(), world_size, send);
+            self.transport.send(next, buffer[range].to_vec())?;
+
+            let message = self.transport.recv(previous)?;
+            let range = shard_range(buffer.len(), world_size, recv);
+            buffer[range].copy_from_slice(&message);
+        }
+
+        Ok(())
+    }
+
+    /// Sum the buffers into the first rank along a binomial tree.
+    ///
+    /// The parent of a rank is the rank with its lowest set bit cleared.
+    fn tree_reduce(&mut self, buffer: &mut [f32]) -> Result<(), CollectiveError> {
+        let (rank, world_size) = (self.rank(), self.world_size());
+
+        let mut mask = 1;
+        while mask < world_size {
+            if rank & mask != 0 {
+                self.transport.send(rank - mask, buffer.to_vec())?;
+                break;
+            }
+
+            let child = rank + mask;
+            if child < world_size {
+                let message = self.transport.recv(child)?;
+                for (value, received) in buffer.iter_mut().zip(message) {
+                    *value += received;
+                }
+            }
+            mask <<= 1;
+        }
+
+        Ok(())
+    }
+
+    /// Copy the buffer of the first rank to all the ranks along a binomial tree.
+    fn tree_broadcast(&mut self, buffer: &mut [f32]) -> Result<(), CollectiveError> {
+        let (rank, world_size) = (self.rank(), self.world_size());
+
+        // The children of a rank are the ranks with an additional bit lower than its lowest set
+        // bit.
+        let lowest_bit = match rank {
+            0 => world_size.next_power_of_two(),
+            rank => {
+                let lowest_bit = rank & rank.wrapping_neg();
+                let message = self.transport.recv(rank - lowest_bit)?;
+                buffer.copy_from_slice(&message);
+                lowest_bit
+            }
+        };
+
+        let mut mask = lowest_bit >> 1;
+        while mask > 0 {
+            if rank + mask < world_size {
+                self.transport.send(rank + mask, buffer.to_vec())?;
+            }
+            mask >>= 1;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collective::LocalTransport;
+    use std::thread::spawn;
+
+    /// Run the given operation on every rank of a local group.
+    fn run<F, R>(world_size: usize, algorithm: CollectiveAlgorithm, operation: F) -> Vec<R>
+    where
+        F: Fn(&mut Collective<LocalTransport>) -> R + Send + Sync + Copy + 'static,
+        R: Send + 'static,
+    {
+        let handles = LocalTransport::group(world_size)
+            .into_iter()
+            .map(|transport| {
+                spawn(move || {
+                    let mut collective = Collective::new(transport).with_algorithm(algorithm);
+                    operation(&mut collective)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    }
+
+    fn values(rank: usize, len: usize) -> Vec<f32> {
+        (0..len).map(|i| (rank * 100 + i) as f32).collect()
+    }
+
+    #[test]
+    fn test_all_reduce() {
+        for algorithm in [CollectiveAlgorithm::Ring, CollectiveAlgorithm::Tree] {
+            for world_size in [1, 2, 3, 5] {
+                let results = run(world_size, algorithm, move |collective| {
+                    let mut buffer = values(collective.rank(), 7);
+                    collective.all_reduce(&mut buffer, ReduceOp::Sum).unwrap();
+                    buffer
+                });
+
+                let expected = (0..7)
+                    .map(|i| (0..world_size).map(|rank| values(rank, 7)[i]).sum::<f32>())
+                    .collect::<Vec<_>>();
+                for result in results {
+                    assert_eq!(result, expected, "{algorithm:?} with {world_size} ranks");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_all_reduce_mean() {
+        let results = run(4, CollectiveAlgorithm::Ring, |collective| {
+            let mut buffer = vec![collective.rank() as f32; 3];
+            collective.all_reduce(&mut buffer, ReduceOp::Mean).unwrap();
+            buffer
+        });
+
+        for result in results {
+            assert_eq!(result, vec![1.5; 3]);
+        }
+    }
+
+    #[test]
+    fn test_broadcast() {
+        for algorithm in [CollectiveAlgorithm::Ring, CollectiveAlgorithm::Tree] {
+            let results = run(5, algorithm, |collective| {
+                let mut buffer = values(collective.rank(), 6);
+                collective.broadcast(&mut buffer, 3).unwrap();
+                buffer
+            });
+
+            for result in results {
+                assert_eq!(result, values(3, 6), "{algorithm:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_reduce_scatter_and_all_gather() {
+        for algorithm in [CollectiveAlgorithm::Ring, CollectiveAlgorithm::Tree] {
+            let results = run(3, algorithm, |collective| {
+                let buffer = values(collective.rank(), 8);
+                let shard = collective.reduce_scatter(&buffer, ReduceOp::Sum).unwrap();
+                let gathered = collective.all_gather(&shard, 8).unwrap();
+                (shard, gathered)
+            });
+
+            let expected = (0..8)
+                .map(|i| (0..3).map(|rank| values(rank, 8)[i]).sum::<f32>())
+                .collect::<Vec<_>>();
+            for (rank, (shard, gathered)) in results.into_iter().enumerate() {
+                assert_eq!(shard, expected[shard_range(8, 3, rank)], "{algorithm:?}");
+                assert_eq!(gathered, expected, "{algorithm:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_shard_range() {
+        let ranges = (0..3)
+            .map(|rank| shard_range(8, 3, rank))
+            .collect::<Vec<_>>();
+
+        assert_eq!(ranges, vec![0..3, 3..6, 6..8]);
+    }
+}
@@ -0,0 +1,305 @@
+use std::io::Write;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use super::tcp::{accept_until, connect_with_retry, read_exact_until};
+use super::{CollectiveError, TcpTransport};
+
+/// The maximum length of a socket address formatted as a string.
+const MAX_ADDRESS_LEN: u64 = 128;
+
+/// The configuration of a process of a distributed training.
+///
+/// The processes find each other through the main process, the rank 0, which listens on the
+/// master address. Every process then opens a direct connection with every other one, see
+/// [TcpTransport].
+#[derive(Debug, Clone)]
+pub struct DistributedConfig {
+    /// The rank of the process, between 0 and the world size.
+    pub rank: usize,
+    /// The number of processes of the training.
+    pub world_size: usize,
+    /// The rank of the process on its node, e.g. to select its device.
+    pub local_rank: usize,
+    /// The address of the main process, as `host:port`.
+    pub master_addr: String,
+    /// The address at which the other processes reach this one, which defaults to the address
+    /// of the interface connected to the main process.
+    pub host: Option<IpAddr>,
+    /// How long to wait for the other processes.
+    pub timeout: Duration,
+}
+
+impl DistributedConfig {
+    /// Creates the configuration of a process of a single node training, with a timeout of
+    /// 5 minutes.
+    pub fn new(rank: usize, world_size: usize, master_addr: impl Into<String>) -> Self {
+        Self {
+            rank,
+            world_size,
+            local_rank: rank,
+            master_addr: master_addr.into(),
+            host: None,
+            timeout: Duration::from_secs(300),
+        }
+    }
+
+    /// Reads the configuration from the environment variables set by the
+    /// [launcher](super::Launcher).
+    ///
+    /// The variables are `RANK`, `WORLD_SIZE`, `LOCAL_RANK`, `MASTER_ADDR` and `MASTER_PORT`,
+    /// as with PyTorch. `LOCAL_RANK` defaults to the rank and `MASTER_ADDR` to `127.0.0.1`.
+    pub fn from_env() -> Result<Self, CollectiveError> {
+        let rank = env_var("RANK")?;
+        let world_size = env_var("WORLD_SIZE")?;
+        let port: u16 = env_var("MASTER_PORT")?;
+        let host = std::env::var("MASTER_ADDR").unwrap_or_else(|_| "127.0.0.1".to_string());
+
+        let mut config = Self::new(rank, world_size, format!("{host}:{port}"));
+        if std::env::var("LOCAL_RANK").is_ok() {
+            config.local_rank = env_var("LOCAL_RANK")?;
+        }
+
+        Ok(config)
+    }
+
+    /// Sets the address at which the other processes reach this one, e.g. when the interface
+    /// connected to the main process isn't reachable by the other processes.
+    pub fn with_host(mut self, host: IpAddr) -> Self {
+        self.host = Some(host);
+        self
+    }
+
+    /// Sets how long to wait for the other processes.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Connect to all the processes of the training.
+    ///
+    /// Every process must call this method, they can start in any order. A timeout error is
+    /// returned when the processes don't all connect within the [timeout](Self::with_timeout).
+    pub fn connect(&self) -> Result<TcpTransport, CollectiveError> {
+        if self.rank >= self.world_size {
+            return Err(CollectiveError::Unknown(format!(
+                "Rank {} is out of bounds for a group of {} ranks",
+                self.rank, self.world_size
+            )));
+        }
+
+        let master = self.master_addr.to_socket_addrs()?.next().ok_or_else(|| {
+            CollectiveError::Unknown(format!("Unable to resolve {}", self.master_addr))
+        })?;
+
+        // The connections between the processes use a port chosen by the system, which is sent
+        // to the other processes through the main one.
+        let listener = TcpListener::bind(SocketAddr::new(unspecified(&master), 0))?;
+        let port = listener.local_addr()?.port();
+
+        let addresses = match self.rank {
+            0 => self.serve(master, port)?,
+            _ => self.join(master, port)?,
+        };
+
+        TcpTransport::from_listener(self.rank, listener, &addresses, self.timeout)
+    }
+
+    /// Collect the addresses of all the processes and send them back.
+    fn serve(&self, master: SocketAddr, port: u16) -> Result<Vec<SocketAddr>, CollectiveError> {
+        let server = TcpListener::bind(SocketAddr::new(unspecified(&master), master.port()))?;
+        let deadline = Instant::now() + self.timeout;
+
+        let mut addresses = vec![None; self.world_size];
+        addresses[0] = Some(SocketAddr::new(self.host.unwrap_or(master.ip()), port));
+        let mut streams = Vec::with_capacity(self.world_size - 1);
+
+        while streams.len() + 1 < self.world_size {
+            let mut stream = accept_until(&server, deadline)?;
+            let rank = read_u64(&mut stream, deadline)? as usize;
+            let world_size = read_u64(&mut stream, deadline)? as usize;
+            let address = read_address(&mut stream, deadline)?;
+
+            if world_size != self.world_size {
+                return Err(CollectiveError::Unknown(format!(
+                    "Rank {rank} expects {world_size} ranks instead of {}",
+                    self.world_size
+                )));
+            }
+            match addresses.get_mut(rank) {
+                Some(entry @ None) => *entry = Some(address),
+                _ => {
+                    return Err(CollectiveError::Unknown(format!(
+                        "Unexpected connection from rank {rank}"
+                    )));
+                }
+            }
+            streams.push(stream);
+        }
+
+        let addresses = addresses.into_iter().flatten().collect::<Vec<_>>();
+        for stream in streams.iter_mut() {
+            for address in addresses.iter() {
+                write_address(stream, address)?;
+            }
+        }
+
+        Ok(addresses)
+    }
+
+    /// Send the address of this process to the main one and receive the addresses of all the
+    /// processes.
+    fn join(&self, master: SocketAddr, port: u16) -> Result<Vec<SocketAddr>, CollectiveError> {
+        let deadline = Instant::now() + self.timeout;
+        let mut stream = connect_with_retry(&master, self.timeout)?;
+        let host = match self.host {
+            Some(host) => host,
+            None => stream.local_addr()?.ip(),
+        };
+
+        for value in [self.rank, self.world_size] {
+            stream.write_all(&(value as u64).to_le_bytes())?;
+        }
+        write_address(&mut stream, &SocketAddr::new(host, port))?;
+
+        (0..self.world_size)
+            .map(|_| read_address(&mut stream, deadline))
+            .collect()
+    }
+}
+
+fn env_var<T: core::str::FromStr>(name: &str) -> Result<T, CollectiveError> {
+    let value = std::env::var(name).map_err(|_| {
+        CollectiveError::Unknown(format!("The environment variable {name} is not set"))
+    })?;
+
+    value.parse().map_err(|_| {
+        CollectiveError::Unknown(format!(
+            "Invalid value for the environment variable {name}: {value}"
+        ))
+    })
+}
+
+/// The address listening on all the interfaces of the same family as the given address.
+fn unspecified(address: &SocketAddr) -> IpAddr {
+    match address {
+        SocketAddr::V4(_) => Ipv4Addr::UNSPECIFIED.into(),
+        SocketAddr::V6(_) => Ipv6Addr::UNSPECIFIED.into(),
+    }
+}
+
+fn read_u64(stream: &mut TcpStream, deadline: Instant) -> Result<u64, CollectiveError> {
+    let mut value = [0; 8];
+    read_exact_until(stream, &mut value, deadline)?;
+
+    Ok(u64::from_le_bytes(value))
+}
+
+fn write_address(stream: &mut TcpStream, address: &SocketAddr) -> Result<(), CollectiveError> {
+    let address = address.to_string();
+    stream.write_all(&(address.len() as u64).to_le_bytes())?;
+    stream.write_all(address.as_bytes())?;
+
+    Ok(())
+}
+
+fn read_address(stream: &mut TcpStream, deadline: Instant) -> Result<SocketAddr, CollectiveError> {
+    let len = read_u64(stream, deadline)?;
+    if len > MAX_ADDRESS_LEN {
+        return Err(CollectiveError::Unknown(format!(
+            "Invalid address length: {len}"
+        )));
+    }
+
+    let mut address = vec![0; len as usize];
+    read_exact_until(stream, &mut address, deadline)?;
+
+    String::from_utf8_lossy(&address)
+        .parse()
+        .map_err(|err| CollectiveError::Unknown(format!("Invalid address: {err}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collective::{Collective, ReduceOp};
+    use std::io::ErrorKind;
+    use std::thread::spawn;
+
+    #[test]
+    fn test_rendezvous() {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        let handles = (0..3)
+            .map(|rank| {
+                spawn(move || {
+                    let config = DistributedConfig::new(rank, 3, format!("127.0.0.1:{port}"))
+                        .with_timeout(Duration::from_secs(10));
+                    let mut collective = Collective::new(config.connect().unwrap());
+
+                    let mut buffer = vec![rank as f32; 4];
+                    collective.all_reduce(&mut buffer, ReduceOp::Sum).unwrap();
+                    buffer
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), vec![3.0; 4]);
+        }
+    }
+
+    #[test]
+    fn test_rendezvous_shares_the_advertised_addresses() {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let master = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port);
+        let config = move |rank| {
+            DistributedConfig::new(rank, 3, master.to_string())
+                .with_timeout(Duration::from_secs(10))
+        };
+
+        let main = spawn(move || config(0).serve(master, 1000));
+        let worker = spawn(move || {
+            config(1)
+                .with_host(Ipv4Addr::new(127, 0, 0, 2).into())
+                .join(master, 1001)
+        });
+        let addresses = config(2).join(master, 1002).unwrap();
+
+        // The last worker advertises the address of its connection with the main process.
+        let expected = ["127.0.0.1:1000", "127.0.0.2:1001", "127.0.0.1:1002"]
+            .map(|address| address.parse::<SocketAddr>().unwrap());
+        assert_eq!(addresses, expected);
+        assert_eq!(worker.join().unwrap().unwrap(), expected);
+        assert_eq!(main.join().unwrap().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_rendezvous_times_out() {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let master = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port);
+        let config = DistributedConfig::new(0, 3, master.to_string())
+            .with_timeout(Duration::from_millis(500));
+
+        let main = spawn(move || config.serve(master, 1000));
+        // A process connects without sending its address, the other one never connects.
+        let _stream = connect_with_retry(&master, Duration::from_secs(10)).unwrap();
+
+        match main.join().unwrap() {
+            Err(CollectiveError::IOError(err)) => assert_eq!(err.kind(), ErrorKind::TimedOut),
+            result => panic!("Expected a timeout, got {result:?}"),
+        }
+    }
+}
@@ -0,0 +1,345 @@
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{Sender, channel};
+use std::thread::{JoinHandle, sleep, spawn};
+use std::time::{Duration, Instant};
+
+use super::{CollectiveError, Transport};
+
+/// The maximum number of values of a received message (16 GiB), larger lengths come from a
+/// corrupted or foreign message.
+const MAX_MESSAGE_LEN: u64 = 1 << 32;
+
+/// Transport between processes over TCP, with a connection between every pair of ranks.
+///
+/// Messages are sent by a background thread per connection, so sending never waits for the
+/// peer to receive.
+pub struct TcpTransport {
+    rank: usize,
+    peers: Vec<Option<Peer>>,
+}
+
+struct Peer {
+    sender: Option<Sender<Vec<f32>>>,
+    reader: BufReader<TcpStream>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl TcpTransport {
+    /// Connect to all the ranks of the group.
+    ///
+    /// Each rank listens on its own address and connects to the ranks before it, retrying
+    /// until the given timeout so that the processes can start in any order.
+    ///
+    /// # Arguments
+    ///
+    /// * `rank` - The rank of this process.
+    /// * `addresses` - The address of every rank, ordered by rank.
+    /// * `timeout` - How long to wait for the other ranks.
+    pub fn connect(
+        rank: usize,
+        addresses: &[SocketAddr],
+        timeout: Duration,
+    ) -> Result<Self, CollectiveError> {
+        let address = addresses.get(rank).ok_or_else(|| {
+            CollectiveError::Unknown(format!(
+                "Rank {rank} is out of bounds for a group of {} ranks",
+                addresses.len()
+            ))
+        })?;
+        let listener = TcpListener::bind(address)?;
+
+        Self::from_listener(rank, listener, addresses, timeout)
+    }
+
+    /// Connect to all the ranks of the group, accepting the connections of the ranks after this
+    /// one on a listener already bound to its address.
+    pub(crate) fn from_listener(
+        rank: usize,
+        listener: TcpListener,
+        addresses: &[SocketAddr],
+        timeout: Duration,
+    ) -> Result<Self, CollectiveError> {
+        let deadline = Instant::now() + timeout;
+        let world_size = addresses.len();
+        let mut streams = (0..world_size).map(|_| None).collect::<Vec<_>>();
+
+        for (peer, address) in addresses.iter().enumerate().take(rank) {
+            let mut stream = connect_with_retry(address, timeout)?;
+            stream.write_all(&(rank as u64).to_le_bytes())?;
+            streams[peer] = Some(stream);
+        }
+
+        for _ in rank + 1..world_size {
+            let mut stream = accept_until(&listener, deadline)?;
+            let mut peer = [0; 8];
+            read_exact_until(&mut stream, &mut peer, deadline)?;
+
+            let peer = u64::from_le_bytes(peer) as usize;
+            if peer <= rank || peer >= world_size || streams[peer].is_some() {
+                return Err(CollectiveError::Unknown(format!(
+                    "Unexpected connection from rank {peer}"
+                )));
+            }
+            streams[peer] = Some(stream);
+        }
+
+        let peers = streams
+            .into_iter()
+            .map(|stream| stream.map(Peer::new).transpose())
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { rank, peers })
+    }
+
+    fn peer(&mut self, rank: usize) -> Result<&mut Peer, CollectiveError> {
+        self.peers
+            .get_mut(rank)
+            .and_then(Option::as_mut)
+            .ok_or_else(|| CollectiveError::Unknown(format!("No connection with rank {rank}")))
+    }
+}
+
+pub(crate) fn connect_with_retry(
+    address: &SocketAddr,
+    timeout: Duration,
+) -> Result<TcpStream, CollectiveError> {
+    let start = Instant::now();
+
+    loop {
+        match TcpStream::connect(address) {
+            Ok(stream) => return Ok(stream),
+            Err(err) if start.elapsed() >= timeout => return Err(err.into()),
+            Err(_) => sleep(Duration::from_millis(100)),
+        }
+    }
+}
+
+/// Accept a connection on the listener, polling it until the deadline.
+pub(crate) fn accept_until(
+    listener: &TcpListener,
+    deadline: Instant,
+) -> Result<TcpStream, CollectiveError> {
+    listener.set_nonblocking(true)?;
+
+    loop {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                // The accepted stream may inherit the non-blocking mode on some platforms.
+                stream.set_nonblocking(false)?;
+                return Ok(stream);
+            }
+            Err(err) if err.kind() == ErrorKind::WouldBlock => {
+                remaining(deadline)?;
+                sleep(Duration::from_millis(10));
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+/// The time left before the deadline, or a timeout error when it is passed.
+pub(crate) fn remaining(deadline: Instant) -> Result<Duration, CollectiveError> {
+    deadline
+        .checked_duration_since(Instant::now())
+        .filter(|remaining| !remaining.is_zero())
+        .ok_or_else(|| timed_out().into())
+}
+
+/// Fill the buffer from the stream, returning a timeout error when the deadline is passed.
+pub(crate) fn read_exact_until(
+    stream: &mut TcpStream,
+    buffer: &mut [u8],
+    deadline: Instant,
+) -> Result<(), CollectiveError> {
+    stream.set_read_timeout(Some(remaining(deadline)?))?;
+
+    match stream.read_exact(buffer) {
+        // The expired read timeout is reported as `WouldBlock` on Unix platforms.
+        Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+            Err(timed_out().into())
+        }
+        result => {
+            result?;
+            stream.set_read_timeout(None)?;
+            Ok(())
+        }
+    }
+}
+
+fn timed_out() -> std::io::Error {
+    std::io::Error::new(
+        ErrorKind::TimedOut,
+        "Timed out waiting for the other ranks to connect",
+    )
+}
+
+impl Peer {
+    fn new(stream: TcpStream) -> Result<Self, CollectiveError> {
+        stream.set_nodelay(true)?;
+
+        let mut writer = BufWriter::new(stream.try_clone()?);
+        let (sender, receiver) = channel::<Vec<f32>>();
+
+        let handle = spawn(move || {
+            for message in receiver {
+                let mut frame = Vec::with_capacity(8 + message.len() * 4);
+                frame.extend_from_slice(&(message.len() as u64).to_le_bytes());
+                for value in message {
+                    frame.extend_from_slice(&value.to_le_bytes());
+                }
+
+                if let Err(err) = writer.write_all(&frame).and_then(|_| writer.flush()) {
+                    log::error!("Unable to send a message: {err}");
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            sender: Some(sender),
+            reader: BufReader::new(stream),
+            writer: Some(handle),
+        })
+    }
+}
+
+impl Drop for Peer {
+    fn drop(&mut self) {
+        // Wait for the pending messages to be sent before closing the connection.
+        self.sender.take();
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+impl Transport for TcpTransport {
+    fn rank(&self) -> usize {
+        self.rank
+    }
+
+    fn world_size(&self) -> usize {
+        self.peers.len()
+    }
+
+    fn send(&mut self, rank: usize, message: Vec<f32>) -> Result<(), CollectiveError> {
+        self.peer(rank)?
+            .sender
+            .as_ref()
+            .and_then(|sender| sender.send(message).ok())
+            .ok_or(CollectiveError::Disconnected(rank))
+    }
+
+    fn recv(&mut self, rank: usize) -> Result<Vec<f32>, CollectiveError> {
+        let reader = &mut self.peer(rank)?.reader;
+        let disconnected = |err: std::io::Error| match err.kind() {
+            std::io::ErrorKind::UnexpectedEof => CollectiveError::Disconnected(rank),
+            _ => CollectiveError::IOError(err),
+        };
+
+        let mut len = [0; 8];
+        reader.read_exact(&mut len).map_err(disconnected)?;
+        let len = u64::from_le_bytes(len);
+        let num_bytes = Some(len)
+            .filter(|len| *len <= MAX_MESSAGE_LEN)
+            .and_then(|len| len.checked_mul(4))
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Invalid length {len} of a message received from rank {rank}"),
+                )
+            })?;
+
+        // The buffer grows with the received values rather than trusting the length.
+        let mut bytes = Vec::new();
+        reader
+            .take(num_bytes)
+            .read_to_end(&mut bytes)
+            .map_err(disconnected)?;
+        if (bytes.len() as u64) < num_bytes {
+            return Err(CollectiveError::Disconnected(rank));
+        }
+
+        Ok(bytes
+            .chunks_exact(4)
+            .map(|value| f32::from_le_bytes([value[0], value[1], value[2], value[3]]))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_tcp_transport() {
+        let listeners = (0..3)
+            .map(|_| TcpListener::bind("127.0.0.1:0").unwrap())
+            .collect::<Vec<_>>();
+        let addresses = listeners
+            .iter()
+            .map(|listener| listener.local_addr().unwrap())
+            .collect::<Vec<_>>();
+
+        let handles = listeners
+            .into_iter()
+            .enumerate()
+            .map(|(rank, listener)| {
+                let addresses = addresses.clone();
+                spawn(move || {
+                    let mut transport = TcpTransport::from_listener(
+                        rank,
+                        listener,
+                        &addresses,
+                        Duration::from_secs(10),
+                    )
+                    .unwrap();
+                    let next = (rank + 1) % 3;
+                    let previous = (rank + 2) % 3;
+
+                    transport.send(next, vec![rank as f32; 4]).unwrap();
+                    transport.recv(previous).unwrap()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for (rank, handle) in handles.into_iter().enumerate() {
+            assert_eq!(handle.join().unwrap(), vec![((rank + 2) % 3) as f32; 4]);
+        }
+    }
+
+    #[test]
+    fn test_tcp_transport_should_reject_invalid_message_lengths() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let peer = spawn(move || {
+            let mut stream = TcpStream::connect(address).unwrap();
+            stream.write_all(&1u64.to_le_bytes()).unwrap();
+            stream.write_all(&u64::MAX.to_le_bytes()).unwrap();
+            stream
+        });
+
+        let mut transport =
+            TcpTransport::from_listener(0, listener, &[address; 2], Duration::from_secs(10))
+                .unwrap();
+        let result = transport.recv(1);
+
+        assert!(matches!(result, Err(CollectiveError::IOError(_))));
+        drop(peer.join().unwrap());
+    }
+
+    #[test]
+    fn test_tcp_transport_should_time_out_without_peers() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addresses = vec![listener.local_addr().unwrap(); 2];
+        let start = Instant::now();
+
+        let result =
+            TcpTransport::from_listener(0, listener, &addresses, Duration::from_millis(200));
+
+        assert!(result.is_err());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,951 @@
+use core::marker::PhantomData;
+use core::ops::Range;
+use std::collections::HashMap;
+
+use super::gradients::{flatten_values, unflatten_values};
+use super::{Collective, CollectiveError, ReduceOp, Transport};
+use burn_core::{
+    LearningRate,
+    module::{AutodiffModule, Module, ModuleMapper, ModuleVisitor, ParamId},
+    optim::{GradientsParams, Optimizer},
+    tensor::{
+        Shape, Tensor,
+        backend::{AutodiffBackend, Backend},
+    },
+};
+
+/// What is sharded between the ranks of a data parallel training, following the stages of
+/// [ZeRO](https://arxiv.org/abs/1910.02054).
+///
+/// Each stage includes the previous ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ZeroStage {
+    /// Stage 1: each rank only keeps the optimizer state of the parameters it owns.
+    ///
+    /// The gradients are all-reduced, then every rank updates its own parameters and sends them
+    /// to the other ranks.
+    #[default]
+    OptimizerState,
+
+    /// Stage 2: each rank only receives the reduced gradients of the parameters it owns.
+    ///
+    /// The gradients are reduce-scattered instead of all-reduced, which halves the
+    /// communication and releases the other gradients before the optimizer step.
+    Gradients,
+
+    /// Stage 3: each rank only keeps the parameters it owns between the steps.
+    ///
+    /// The other parameters are released once the gradients are reduced, so the module returned
+    /// by the step is incomplete. They are gathered from their owners with
+    /// [gather_params](ShardedOptimizer::gather_params) before the next forward pass, which the
+    /// [learner](crate::Learner) does before each training step.
+    Parameters,
+}
+
+/// The assignment of every parameter of a module to the rank owning it.
+///
+/// The parameters are assigned whole, the largest ones first, to the rank owning the fewest
+/// elements so far. Every rank computes the same plan from the same module.
+#[derive(Debug, Clone)]
+pub struct ShardingPlan {
+    world_size: usize,
+    segment_len: usize,
+    params: HashMap<ParamId, ParamShard>,
+    num_elements: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+struct ParamShard {
+    owner: usize,
+    offset: usize,
+    shape: Shape,
+}
+
+impl ShardingPlan {
+    /// Assigns the parameters of the module to the given number of ranks.
+    pub fn new<B: Backend, M: Module<B>>(module: &M, world_size: usize) -> Self {
+        let mut collector = ParamsCollector::<B> {
+            params: Vec::new(),
+            phantom: PhantomData,
+        };
+        module.visit(&mut collector);
+        let params = collector.params;
+
+        let mut order = (0..params.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&index| core::cmp::Reverse(params[index].1.num_elements()));
+
+        let mut owners = vec![0; params.len()];
+        let mut num_elements = vec![0; world_size];
+        for index in order {
+            let (owner, _) = num_elements
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, num_elements)| **num_elements)
+                .expect("A minimum of one rank");
+            owners[index] = owner;
+            num_elements[owner] += params[index].1.num_elements();
+        }
+
+        // The parameters of each rank are laid out contiguously in the order they are visited.
+        let mut offsets = vec![0; world_size];
+        let params = params
+            .into_iter()
+            .zip(owners)
+            .map(|((id, shape), owner)| {
+                let offset = offsets[owner];
+                offsets[owner] += shape.num_elements();
+                (
+                    id,
+                    ParamShard {
+                        owner,
+                        offset,
+                        shape,
+                    },
+                )
+            })
+            .collect();
+
+        Self {
+            world_size,
+            segment_len: num_elements.iter().copied().max().unwrap_or(0),
+            params,
+            num_elements,
+        }
+    }
+
+    /// The number of ranks.
+    pub fn world_size(&self) -> usize {
+        self.world_size
+    }
+
+    /// The rank owning the given parameter, if it is part of the module.
+    pub fn owner(&self, id: ParamId) -> Option<usize> {
+        self.params.get(&id).map(|param| param.owner)
+    }
+
+    /// The number of parameter elements owned by the given rank.
+    pub fn num_elements(&self, rank: usize) -> usize {
+        self.num_elements[rank]
+    }
+
+    /// The length of the buffer exchanged between the ranks, where every rank has a segment of
+    /// the same length, padded after its own parameters.
+    fn buffer_len(&self) -> usize {
+        self.segment_len * self.world_size
+    }
+
+    /// The range of the parameter in the exchanged buffer.
+    fn range(&self, id: ParamId) -> Option<Range<usize>> {
+        self.params.get(&id).map(|param| {
+            let start = param.owner * self.segment_len + param.offset;
+            start..start + param.shape.num_elements()
+        })
+    }
+}
+
+/// Wraps an [optimizer](Optimizer) to shard its state, and optionally the gradients and the
+/// parameters, between the ranks of a data parallel training.
+///
+/// The gradients given to the optimizer must not be reduced between the ranks, which is done
+/// by the optimizer itself. With the [learner](crate::Learner), this requires
+/// [sharded_optimizer](crate::LearnerBuilder::sharded_optimizer).
+///
+/// The parameters are assigned to the ranks by a [sharding plan](ShardingPlan) created on the
+/// first step. Since [adapted optimizers](burn_core::optim::adaptor::OptimizerAdaptor) only
+/// create a state for the parameters with gradients, each rank only keeps the state of the
+/// parameters it owns.
+///
+/// # Notes
+///
+/// The [record](Optimizer::to_record) only contains the state of the parameters of the current
+/// rank, so every rank must save and load its own record to resume the training, which the
+/// learner does with a [sharded optimizer](crate::LearnerBuilder::sharded_optimizer).
+///
+/// The gradients and parameters are flattened on their device, and exchanged between the ranks
+/// with a single copy to and from the host per collective operation.
+///
+/// Gradient clipping by global norm is computed on the gradients of the current rank only.
+///
+/// The errors of the collective operations are returned by [try_step](Self::try_step). Since
+/// the [step](Optimizer::step) can't fail, its errors are returned by the next
+/// [gather_params](ShardedOptimizer::gather_params) instead.
+pub struct ZeroOptimizer<O, T: Transport> {
+    optim: O,
+    collective: Collective<T>,
+    stage: ZeroStage,
+    plan: Option<ShardingPlan>,
+    // Whether the parameters of the other ranks were released by the last step.
+    released: bool,
+    error: Option<CollectiveError>,
+}
+
+/// An [optimizer](Optimizer) sharding the parameters of the module between the ranks of a
+/// distributed training, such as the [ZeroOptimizer].
+pub trait ShardedOptimizer<M, B>: Optimizer<M, B>
+where
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    /// Gather the parameters released by the last step from the other ranks, returning the
+    /// complete module.
+    ///
+    /// All the ranks must call this method at the same time.
+    fn gather_params(&mut self, module: &M) -> Result<M, CollectiveError>;
+}
+
+/// Gathers the parameters released by a [sharded optimizer](ShardedOptimizer).
+pub(crate) type GatherParams<O, M> = fn(&mut O, &M) -> Result<M, CollectiveError>;
+
+impl<O, T: Transport> ZeroOptimizer<O, T> {
+    /// Creates the sharded optimizer, communicating with the other ranks through the given
+    /// collective operations.
+    pub fn new(optim: O, collective: Collective<T>, stage: ZeroStage) -> Self {
+        Self {
+            optim,
+            collective,
+            stage,
+            plan: None,
+            released: false,
+            error: None,
+        }
+    }
+
+    /// The stage of the sharding.
+    pub fn stage(&self) -> ZeroStage {
+        self.stage
+    }
+
+    /// The sharding plan, available after the first step.
+    pub fn plan(&self) -> Option<&ShardingPlan> {
+        self.plan.as_ref()
+    }
+
+    /// Performs the optimizer step, returning the errors of the collective operations.
+    ///
+    /// With the [Parameters](ZeroStage::Parameters) stage, the returned module only has the
+    /// parameters owned by the current rank.
+    pub fn try_step<M, B>(
+        &mut self,
+        lr: LearningRate,
+        module: M,
+        grads: GradientsParams,
+    ) -> Result<M, CollectiveError>
+    where
+        O: Optimizer<M, B>,
+        M: AutodiffModule<B>,
+        B: AutodiffBackend,
+    {
+        let grads = self.shard_gradients(&module, grads)?;
+
+        let module = match self.stage {
+            ZeroStage::Parameters => {
+                let plan = self.plan.as_ref().unwrap();
+                let mut release = ParamsRelease::<B> {
+                    plan,
+                    rank: self.collective.rank(),
+                    phantom: PhantomData,
+                };
+                self.released = true;
+                module.map(&mut release)
+            }
+            _ => module,
+        };
+
+        // Only the parameters with gradients, owned by the current rank, are updated.
+        let module = self.optim.step(lr, module, grads);
+
+        match self.stage {
+            ZeroStage::Parameters => Ok(module),
+            _ => self.gather(module),
+        }
+    }
+
+    /// Gather the parameters owned by the other ranks, replacing the ones of the module.
+    ///
+    /// All the ranks must call this method at the same time.
+    fn gather<B: Backend, M: Module<B>>(&mut self, module: M) -> Result<M, CollectiveError> {
+        self.plan_for(&module);
+        let plan = self.plan.as_ref().unwrap();
+        let rank = self.collective.rank();
+
+        let mut flatten = ParamsSegmentFlatten::<B> {
+            plan,
+            rank,
+            segment: Vec::new(),
+        };
+        module.visit(&mut flatten);
+
+        // The segment is padded after the parameters of the rank.
+        let mut segment = flatten_values(flatten.segment);
+        segment.resize(plan.segment_len, 0.0);
+        let buffer = self.collective.all_gather(&segment, plan.buffer_len())?;
+
+        let mut mapper = ParamsGather::<B> {
+            plan,
+            rank,
+            buffer,
+            values: None,
+        };
+        Ok(module.map(&mut mapper))
+    }
+
+    /// Creates the sharding plan from the module on the first call.
+    fn plan_for<B: Backend, M: Module<B>>(&mut self, module: &M) {
+        if self.plan.is_none() {
+            self.plan = Some(ShardingPlan::new(module, self.collective.world_size()));
+        }
+    }
+
+    /// Reduce the gradients, only keeping the ones of the parameters owned by the current rank.
+    fn shard_gradients<B, M>(
+        &mut self,
+        module: &M,
+        mut grads: GradientsParams,
+    ) -> Result<GradientsParams, CollectiveError>
+    where
+        B: AutodiffBackend,
+        M: AutodiffModule<B>,
+    {
+        self.plan_for(module);
+        let plan = self.plan.as_ref().unwrap();
+        let rank = self.collective.rank();
+
+        if self.stage == ZeroStage::OptimizerState {
+            let mut grads = self
+                .collective
+                .all_reduce_gradients(module, grads, ReduceOp::Mean)?;
+            let mut filter = GradientsFilter::<M, B> {
+                plan,
+                rank,
+                grads: &mut grads,
+                phantom: PhantomData,
+            };
+            module.visit(&mut filter);
+
+            return Ok(grads);
+        }
+
+        let mut flatten = GradientsScatterFlatten::<B::InnerBackend, M, B> {
+            plan,
+            grads: &mut grads,
+            segments: vec![Vec::new(); plan.world_size],
+            devices: HashMap::new(),
+            device: None,
+            phantom: PhantomData,
+        };
+        module.visit(&mut flatten);
+        let (buffer, devices) = flatten.into_buffer();
+
+        // Every segment has the same length, so the shards of the reduce-scatter are exactly the
+        // segments of the ranks.
+        let segment = self.collective.reduce_scatter(&buffer, ReduceOp::Mean)?;
+
+        let mut unflatten = GradientsScatterUnflatten::<B::InnerBackend, M, B> {
+            plan,
+            rank,
+            grads: &mut grads,
+            segment,
+            values: None,
+            devices,
+            phantom: PhantomData,
+        };
+        module.visit(&mut unflatten);
+
+        Ok(grads)
+    }
+}
+
+impl<O, T, M, B> Optimizer<M, B> for ZeroOptimizer<O, T>
+where
+    O: Optimizer<M, B>,
+    T: Transport,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    type Record = O::Record;
+
+    fn step(&mut self, lr: LearningRate, module: M, grads: GradientsParams) -> M {
+        // The error is returned when gathering the parameters, with the module left as is.
+        let fallback = module.clone();
+        match self.try_step(lr, module, grads) {
+            Ok(module) => module,
+            Err(err) => {
+                log::error!("The sharded optimizer step failed: {err}");
+                self.error = Some(err);
+                fallback
+            }
+        }
+    }
+
+    fn to_record(&self) -> Self::Record {
+        self.optim.to_record()
+    }
+
+    fn load_record(mut self, record: Self::Record) -> Self {
+        self.optim = self.optim.load_record(record);
+        self
+    }
+}
+
+impl<O, T, M, B> ShardedOptimizer<M, B> for ZeroOptimizer<O, T>
+where
+    O: Optimizer<M, B>,
+    T: Transport,
+    M: AutodiffModule<B>,
+    B: AutodiffBackend,
+{
+    fn gather_params(&mut self, module: &M) -> Result<M, CollectiveError> {
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
+        if !self.released {
+            return Ok(module.clone());
+        }
+
+        self.released = false;
+        self.gather(module.clone())
+    }
+}
+
+struct ParamsCollector<B: Backend> {
+    params: Vec<(ParamId, Shape)>,
+    phantom: PhantomData<B>,
+}
+
+impl<B: Backend> ModuleVisitor<B> for ParamsCollector<B> {
+    fn visit_float<const D: usize>(&mut self, id: ParamId, tensor: &Tensor<B, D>) {
+        self.params.push((id, tensor.shape()));
+    }
+}
+
+struct GradientsFilter<'a, M, B: AutodiffBackend> {
+    plan: &'a ShardingPlan,
+    rank: usize,
+    grads: &'a mut GradientsParams,
+    phantom: PhantomData<(M, B)>,
+}
+
+impl<B, M> ModuleVisitor<B> for GradientsFilter<'_, M, B>
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B>,
+{
+    fn visit_float<const D: usize>(&mut self, id: ParamId, _tensor: &Tensor<B, D>) {
+        if self.plan.owner(id) != Some(self.rank) {
+            self.grads.remove::<B::InnerBackend, D>(id);
+        }
+    }
+}
+
+struct GradientsScatterFlatten<'a, B: Backend, M, BA> {
+    plan: &'a ShardingPlan,
+    grads: &'a mut GradientsParams,
+    // The flattened gradients of the parameters owned by each rank, in the order of the segment.
+    segments: Vec<Vec<Tensor<B, 1>>>,
+    devices: HashMap<ParamId, B::Device>,
+    device: Option<B::Device>,
+    phantom: PhantomData<(M, BA)>,
+}
+
+impl<B: Backend, M, BA> GradientsScatterFlatten<'_, B, M, BA> {
+    /// The segments of all the ranks, each one padded to the same length.
+    fn into_buffer(self) -> (Vec<f32>, HashMap<ParamId, B::Device>) {
+        let mut tensors = Vec::new();
+
+        for (rank, segment) in self.segments.into_iter().enumerate() {
+            tensors.extend(segment);
+            let padding = self.plan.segment_len - self.plan.num_elements[rank];
+            if let (Some(device), true) = (&self.device, padding > 0) {
+                tensors.push(Tensor::zeros([padding], device));
+            }
+        }
+
+        (flatten_values(tensors), self.devices)
+    }
+}
+
+impl<B, M> ModuleVisitor<B> for GradientsScatterFlatten<'_, B::InnerBackend, M, B>
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B>,
+{
+    fn visit_float<const D: usize>(&mut self, id: ParamId, tensor: &Tensor<B, D>) {
+        let Some(param) = self.plan.params.get(&id) else {
+            return;
+        };
+        let num_elements = param.shape.num_elements();
+        let device = self.device.get_or_insert_with(|| tensor.device());
+
+        // The parameters without gradients are reduced as zeros, but stay without gradients.
+        let grad = match self.grads.remove::<B::InnerBackend, D>(id) {
+            Some(grad) => {
+                self.devices.insert(id, grad.device());
+                grad.reshape([num_elements]).to_device(device)
+            }
+            None => Tensor::zeros([num_elements], device),
+        };
+        self.segments[param.owner].push(grad);
+    }
+}
+
+struct GradientsScatterUnflatten<'a, B: Backend, M, BA> {
+    plan: &'a ShardingPlan,
+    rank: usize,
+    grads: &'a mut GradientsParams,
+    segment: Vec<f32>,
+    // The segment copied to the device of the first gradient.
+    values: Option<Tensor<B, 1>>,
+    devices: HashMap<ParamId, B::Device>,
+    phantom: PhantomData<(M, BA)>,
+}
+
+impl<B, M> ModuleVisitor<B> for GradientsScatterUnflatten<'_, B::InnerBackend, M, B>
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B>,
+{
+    fn visit_float<const D: usize>(&mut self, id: ParamId, _tensor: &Tensor<B, D>) {
+        let Some(param) = self.plan.params.get(&id) else {
+            return;
+        };
+        // Parameters without gradients stay without gradients.
+        let Some(device) = self.devices.get(&id) else {
+            return;
+        };
+        if param.owner != self.rank {
+            return;
+        }
+
+        let values = self
+            .values
+            .get_or_insert_with(|| unflatten_values(core::mem::take(&mut self.segment), device));
+        let range = param.offset..param.offset + param.shape.num_elements();
+        let grad = values
+            .clone()
+            .slice([range])
+            .reshape(param.shape.clone())
+            .to_device(device);
+        self.grads.register::<B::InnerBackend, D>(id, grad);
+    }
+}
+
+struct ParamsSegmentFlatten<'a, B: Backend> {
+    plan: &'a ShardingPlan,
+    rank: usize,
+    segment: Vec<Tensor<B, 1>>,
+}
+
+impl<B: Backend> ModuleVisitor<B> for ParamsSegmentFlatten<'_, B> {
+    fn visit_float<const D: usize>(&mut self, id: ParamId, tensor: &Tensor<B, D>) {
+        let Some(param) = self.plan.params.get(&id) else {
+            return;
+        };
+        if param.owner != self.rank {
+            return;
+        }
+
+        let num_elements = param.shape.num_elements();
+        self.segment
+            .push(tensor.clone().detach().reshape([num_elements]));
+    }
+}
+
+struct ParamsGather<'a, B: Backend> {
+    plan: &'a ShardingPlan,
+    rank: usize,
+    buffer: Vec<f32>,
+    // The buffer copied to the device of the first parameter.
+    values: Option<Tensor<B, 1>>,
+}
+
+impl<B: Backend> ModuleMapper<B> for ParamsGather<'_, B> {
+    fn map_float<const D: usize>(&mut self, id: ParamId, tensor: Tensor<B, D>) -> Tensor<B, D> {
+        let (Some(param), Some(range)) = (self.plan.params.get(&id), self.plan.range(id)) else {
+            return tensor;
+        };
+        if param.owner == self.rank {
+            return tensor;
+        }
+
+        let device = tensor.device();
+        let values = self
+            .values
+            .get_or_insert_with(|| unflatten_values(core::mem::take(&mut self.buffer), &device));
+        let gathered = values
+            .clone()
+            .slice([range])
+            .reshape(param.shape.clone())
+            .to_device(&device);
+        if tensor.is_require_grad() {
+            gathered.require_grad()
+        } else {
+            gathered
+        }
+    }
+}
+
+struct ParamsRelease<'a, B: Backend> {
+    plan: &'a ShardingPlan,
+    rank: usize,
+    phantom: PhantomData<B>,
+}
+
+impl<B: Backend> ModuleMapper<B> for ParamsRelease<'_, B> {
+    fn map_float<const D: usize>(&mut self, id: ParamId, tensor: Tensor<B, D>) -> Tensor<B, D> {
+        if self.plan.owner(id).is_none_or(|owner| owner == self.rank) {
+            return tensor;
+        }
+
+        let released = Tensor::empty([0; D], &tensor.device());
+        if tensor.is_require_grad() {
+            released.require_grad()
+        } else {
+            released
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestAutodiffBackend;
+    use crate::checkpoint::KeepLastNCheckpoints;
+    use crate::collective::LocalTransport;
+    use crate::tests::{
+        param_values, regression_dataloaders, regression_learner, regression_model, test_directory,
+    };
+    use crate::{CallbackContext, LearnerCallback};
+    use burn_core::module::Param;
+    use burn_core::optim::AdamConfig;
+    use burn_core::record::{FullPrecisionSettings, NamedMpkFileRecorder};
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+    use std::thread::spawn;
+
+    type TestModule = Vec<Param<Tensor<TestAutodiffBackend, 1>>>;
+
+    fn module() -> TestModule {
+        [vec![1.0, 2.0, 3.0], vec![4.0], vec![5.0, 6.0]]
+            .into_iter()
+            .enumerate()
+            .map(|(id, values)| {
+                Param::initialized(
+                    ParamId::from(id as u64),
+                    Tensor::from_data(values.as_slice(), &Default::default()).require_grad(),
+                )
+            })
+            .collect()
+    }
+
+    fn grads(module: &TestModule, scale: f32) -> GradientsParams {
+        let loss = module
+            .iter()
+            .map(|param| param.val().powf_scalar(2.0).sum().mul_scalar(scale))
+            .reduce(|a, b| a + b)
+            .unwrap();
+        GradientsParams::from_grads(loss.backward(), module)
+    }
+
+    fn values(module: &TestModule) -> Vec<Vec<f32>> {
+        module
+            .iter()
+            .map(|param| param.val().into_data().to_vec::<f32>().unwrap())
+            .collect()
+    }
+
+    /// The module after two Adam steps with the mean of the gradients of the ranks.
+    fn expected(world_size: usize) -> Vec<Vec<f32>> {
+        let mut optim = AdamConfig::new().init::<TestAutodiffBackend, TestModule>();
+        let mut module = module();
+        let scale = (1..=world_size).sum::<usize>() as f32 / world_size as f32;
+
+        for _ in 0..2 {
+            let grads = grads(&module, scale);
+            module = optim.step(0.1, module, grads);
+        }
+
+        values(&module)
+    }
+
+    fn run(stage: ZeroStage) -> Vec<(Vec<Vec<f32>>, usize)> {
+        let handles = LocalTransport::group(2)
+            .into_iter()
+            .map(|transport| {
+                spawn(move || {
+                    let collective = Collective::new(transport);
+                    let scale = (collective.rank() + 1) as f32;
+                    let adam = AdamConfig::new().init::<TestAutodiffBackend, TestModule>();
+                    let mut optim = ZeroOptimizer::new(adam, collective, stage);
+                    let mut module = module();
+
+                    for _ in 0..2 {
+                        module = optim.gather_params(&module).unwrap();
+                        let grads = grads(&module, scale);
+                        module = optim.step(0.1, module, grads);
+                    }
+                    let module = optim.gather_params(&module).unwrap();
+
+                    let record = Optimizer::<TestModule, TestAutodiffBackend>::to_record(&optim);
+                    (values(&module), record.len())
+                })
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_sharding_plan_balances_the_ranks() {
+        let plan = ShardingPlan::new::<TestAutodiffBackend, _>(&module(), 2);
+
+        assert_eq!(plan.owner(ParamId::from(0)), Some(0));
+        assert_eq!(plan.owner(ParamId::from(1)), Some(1));
+        assert_eq!(plan.owner(ParamId::from(2)), Some(1));
+        assert_eq!(plan.num_elements(0), 3);
+        assert_eq!(plan.num_elements(1), 3);
+        assert_eq!(plan.owner(ParamId::from(3)), None);
+    }
+
+    #[test]
+    fn test_zero_stages_match_data_parallel_training() {
+        let expected = expected(2);
+
+        for stage in [
+            ZeroStage::OptimizerState,
+            ZeroStage::Gradients,
+            ZeroStage::Parameters,
+        ] {
+            let results = run(stage);
+
+            // The first rank owns one parameter and the second rank the two others.
+            assert_eq!(results[0].1, 1);
+            assert_eq!(results[1].1, 2);
+            for (values, _) in results {
+                for (actual, expected) in values.iter().zip(expected.iter()) {
+                    for (actual, expected) in actual.iter().zip(expected) {
+                        assert!((actual - expected).abs() < 1e-5, "{stage:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_parameters_stay_sharded_until_gathered() {
+        let sizes = |module: &TestModule| {
+            module
+                .iter()
+                .map(|param| param.val().shape().num_elements())
+                .collect::<Vec<_>>()
+        };
+        let handles = LocalTransport::group(2)
+            .into_iter()
+            .map(|transport| {
+                spawn(move || {
+                    let collective = Collective::new(transport);
+                    let adam = AdamConfig::new().init::<TestAutodiffBackend, TestModule>();
+                    let mut optim = ZeroOptimizer::new(adam, collective, ZeroStage::Parameters);
+                    let module = module();
+                    let grads = grads(&module, 1.0);
+
+                    let module = optim.try_step(0.1, module, grads).unwrap();
+                    let sharded = sizes(&module);
+                    let module = optim.gather_params(&module).unwrap();
+
+                    (sharded, sizes(&module))
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let sizes = handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(sizes[0], (vec![3, 0, 0], vec![3, 1, 2]));
+        assert_eq!(sizes[1], (vec![0, 1, 2], vec![3, 1, 2]));
+    }
+
+    #[test]
+    fn test_step_errors_are_returned() {
+        let mut transports = LocalTransport::group(2);
+        // The other rank is disconnected.
+        transports.pop();
+        let collective = Collective::new(transports.pop().unwrap());
+        let adam = AdamConfig::new().init::<TestAutodiffBackend, TestModule>();
+        let mut optim = ZeroOptimizer::new(adam, collective, ZeroStage::Parameters);
+        let module = module();
+
+        let result = optim.try_step(0.1, module.clone(), grads(&module, 1.0));
+        assert!(result.is_err());
+
+        // The error of the step is returned when gathering the parameters.
+        let module = optim.step(0.1, module.clone(), grads(&module, 1.0));
+        assert_eq!(values(&module)[0], vec![1.0, 2.0, 3.0]);
+        assert!(optim.gather_params(&module).is_err());
+        assert!(optim.gather_params(&module).is_ok());
+    }
+
+    type TestModel = burn_core::nn::Linear<TestAutodiffBackend>;
+
+    /// Fit the regression on two ranks sharding the optimizer, saving the checkpoints in the
+    /// directory and resuming from the given epoch.
+    fn fit_sharded(
+        stage: ZeroStage,
+        directory: &Path,
+        num_epochs: usize,
+        checkpoint: Option<usize>,
+    ) -> Vec<TestModel> {
+        let handles = LocalTransport::group(2)
+            .into_iter()
+            .zip(LocalTransport::group(2))
+            .map(|(transport, transport_optim)| {
+                let directory = directory.to_path_buf();
+                spawn(move || {
+                    let optim = ZeroOptimizer::new(
+                        AdamConfig::new().init(),
+                        Collective::new(transport_optim),
+                        stage,
+                    );
+                    // Every rank trains on the same items, like a single process training.
+                    let (train, valid) = regression_dataloaders(regression_inputs(), 2);
+
+                    let mut builder =
+                        regression_learner(&directory)
+                            .distributed(Collective::new(transport))
+                            .sharded_optimizer()
+                            .with_file_checkpointer(
+                                NamedMpkFileRecorder::<FullPrecisionSettings>::new(),
+                            )
+                            .with_checkpointing_strategy(KeepLastNCheckpoints::new(2))
+                            .num_epochs(num_epochs);
+                    if let Some(checkpoint) = checkpoint {
+                        builder = builder.checkpoint(checkpoint);
+                    }
+
+                    builder
+                        .build(regression_model(), optim, 0.1)
+                        .fit(train, valid)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    }
+
+    fn regression_inputs() -> Vec<f32> {
+        (0..8).map(|x| x as f32 / 8.0).collect()
+    }
+
+    fn assert_same_params(models: &[TestModel], expected: &TestModel) {
+        for model in models {
+            for (actual, expected) in param_values(model).iter().zip(param_values(expected)) {
+                assert!((actual - expected).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_learner_with_sharded_parameters() {
+        let directory = test_directory("zero-parameters");
+        let (train, valid) = regression_dataloaders(regression_inputs(), 2);
+        let expected = regression_learner(&directory)
+            .num_epochs(2)
+            .build(regression_model(), AdamConfig::new().init(), 0.1)
+            .fit(train, valid);
+
+        let models = fit_sharded(ZeroStage::Parameters, &directory, 2, None);
+
+        assert_same_params(&models, &expected);
+        std::fs::remove_dir_all(directory).ok();
+    }
+
+    /// Records the number of parameter elements of the model after the steps and at the end of
+    /// the epochs.
+    #[derive(Clone, Default)]
+    struct ParamSizes(Arc<Mutex<Vec<(&'static str, usize)>>>);
+
+    impl<O> LearnerCallback<TestAutodiffBackend, TestModel, O> for ParamSizes {
+        fn on_batch_end(&mut self, ctx: &mut CallbackContext<TestModel, O>) {
+            let size = param_values(ctx.model).len();
+            self.0.lock().unwrap().push(("batch_end", size));
+        }
+
+        fn on_epoch_end(&mut self, ctx: &mut CallbackContext<TestModel, O>) {
+            let size = param_values(ctx.model).len();
+            self.0.lock().unwrap().push(("epoch_end", size));
+        }
+    }
+
+    #[test]
+    fn test_learner_keeps_the_parameters_sharded_between_the_steps() {
+        let directory = test_directory("zero-sharded");
+        let handles = LocalTransport::group(2)
+            .into_iter()
+            .zip(LocalTransport::group(2))
+            .map(|(transport, transport_optim)| {
+                let directory = directory.clone();
+                spawn(move || {
+                    let optim = ZeroOptimizer::new(
+                        AdamConfig::new().init(),
+                        Collective::new(transport_optim),
+                        ZeroStage::Parameters,
+                    );
+                    let (train, valid) = regression_dataloaders(regression_inputs(), 4);
+                    let sizes = ParamSizes::default();
+
+                    regression_learner(&directory)
+                        .distributed(Collective::new(transport))
+                        .sharded_optimizer()
+                        .callback(sizes.clone())
+                        .num_epochs(1)
+                        .build(regression_model(), optim, 0.1)
+                        .fit(train, valid);
+
+                    sizes.0.lock().unwrap().clone()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let sizes = handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>();
+        std::fs::remove_dir_all(directory).ok();
+
+        // The first rank owns the weight and the second one the bias.
+        let expected = |size| vec![("batch_end", size), ("batch_end", size), ("epoch_end", 2)];
+        assert_eq!(sizes, vec![expected(1), expected(1)]);
+    }
+
+    #[test]
+    fn test_learner_resumes_every_rank_from_its_checkpoint() {
+        let directory = test_directory("zero-resume");
+        let expected = fit_sharded(
+            ZeroStage::OptimizerState,
+            &directory.join("expected"),
+            2,
+            None,
+        );
+
+        fit_sharded(ZeroStage::OptimizerState, &directory, 1, None);
+        // Every rank saves its own optimizer state.
+        assert!(directory.join("checkpoint").join("optim-1.mpk").exists());
+        assert!(
+            directory
+                .join("checkpoint-rank-1")
+                .join("optim-1.mpk")
+                .exists()
+        );
+        let models = fit_sharded(ZeroStage::OptimizerState, &directory, 2, Some(1));
+
+        assert_same_params(&models, &expected[0]);
+        std::fs::remove_dir_all(directory).ok();
+    }
+}
@@ -1,13 +1,22 @@
-use crate::LearnerSummaryConfig;
-use crate::checkpoint::{Checkpointer, CheckpointingAction, CheckpointingStrategy};
+use crate::checkpoint::{
+    Checkpointer, CheckpointingAction, CheckpointingStrategy, TrainingState,
+    TrainingStateCheckpointer,
+};
+use crate::collective::{Collective, GatherParams, Transport};
 use crate::components::LearnerComponents;
-use crate::learner::EarlyStoppingStrategy;
+use crate::learner::{EarlyStoppingState, EarlyStoppingStrategy, LearnerCallback};
 use crate::metric::store::EventStoreClient;
+use crate::tracking::Run;
+use crate::{LearnerSummaryConfig, StochasticWeightAveraging};
+use burn_core::data::dataset::transform::LoadCounts;
+use burn_core::grad_clipping::GradientClipping;
 use burn_core::lr_scheduler::LrScheduler;
-use burn_core::module::Module;
-use burn_core::optim::Optimizer;
-use burn_core::tensor::Device;
-use burn_core::tensor::backend::Backend;
+use burn_core::module::{Module, ModuleMapper, ModuleVisitor, ParamId};
+use burn_core::optim::{GradientsParams, Optimizer};
+use burn_core::tensor::backend::{AutodiffBackend, Backend};
+use burn_core::tensor::{Device, Tensor};
+use std::collections::HashSet;
+use std::path::Path;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 
@@ -20,38 +29,116 @@ pub struct Learner<LC: LearnerComponents> {
     pub(crate) lr_scheduler: LC::LrScheduler,
     pub(crate) num_epochs: usize,
     pub(crate) checkpoint: Option<usize>,
+    pub(crate) resume: bool,
+    pub(crate) checkpoint_interval: Option<usize>,
+    pub(crate) seed: Option<u64>,
+    pub(crate) steps_per_epoch: Option<usize>,
+    pub(crate) max_steps: Option<usize>,
+    pub(crate) max_validation_batches: Option<usize>,
     pub(crate) grad_accumulation: Option<usize>,
+    pub(crate) grad_clipping: Option<GradientClipping>,
+    pub(crate) swa: Option<StochasticWeightAveraging>,
+    pub(crate) load_counts: Option<LoadCounts>,
     pub(crate) checkpointer: Option<LearnerCheckpointer<LC>>,
     pub(crate) devices: Vec<<LC::Backend as Backend>::Device>,
+    pub(crate) collective: Option<Collective<Box<dyn Transport>>>,
+    pub(crate) sharded_optimizer: Option<GatherParams<LC::Optimizer, LC::Model>>,
     pub(crate) interrupter: TrainingInterrupter,
     pub(crate) early_stopping: Option<Box<dyn EarlyStoppingStrategy>>,
+    #[allow(clippy::type_complexity)]
+    pub(crate) callbacks: Vec<Box<dyn LearnerCallback<LC::Backend, LC::Model, LC::Optimizer>>>,
     pub(crate) event_processor: LC::EventProcessor,
     pub(crate) event_store: Arc<EventStoreClient>,
     pub(crate) summary: Option<LearnerSummaryConfig>,
+    pub(crate) run: Option<Run>,
 }
 
-#[derive(new)]
-pub(crate) struct LearnerCheckpointer<LC: LearnerComponents> {
+/// The checkpointers of a [learner](Learner), saving the records of the model, the optimizer and
+/// the learning rate scheduler at the end of the epochs, and optionally during the epochs along
+/// with the accumulated gradients, as well as the [state](TrainingState) of the training loop
+/// and the parameters averaged by the [SWA](StochasticWeightAveraging).
+///
+/// Created by the [builder](crate::learner::LearnerBuilder) with a file checkpointer.
+pub struct LearnerCheckpointer<LC: LearnerComponents> {
     model: LC::CheckpointerModel,
     optim: LC::CheckpointerOptimizer,
     lr_scheduler: LC::CheckpointerLrScheduler,
     strategy: LC::CheckpointerStrategy,
+    // The checkpoints saved during an epoch, only the last one being kept.
+    model_partial: LC::CheckpointerModel,
+    optim_partial: LC::CheckpointerOptimizer,
+    lr_scheduler_partial: LC::CheckpointerLrScheduler,
+    grads_partial: LC::CheckpointerModel,
+    // The models with the parameters averaged by the SWA.
+    swa: LC::CheckpointerModel,
+    swa_partial: LC::CheckpointerModel,
+    state: TrainingStateCheckpointer,
+    early_stopping: Option<EarlyStoppingState>,
+    last_partial: Option<usize>,
 }
 
 impl<LC: LearnerComponents> LearnerCheckpointer<LC> {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        model: LC::CheckpointerModel,
+        optim: LC::CheckpointerOptimizer,
+        lr_scheduler: LC::CheckpointerLrScheduler,
+        model_partial: LC::CheckpointerModel,
+        optim_partial: LC::CheckpointerOptimizer,
+        lr_scheduler_partial: LC::CheckpointerLrScheduler,
+        grads_partial: LC::CheckpointerModel,
+        swa: LC::CheckpointerModel,
+        swa_partial: LC::CheckpointerModel,
+        strategy: LC::CheckpointerStrategy,
+        directory: impl AsRef<Path>,
+    ) -> Self {
+        Self {
+            model,
+            optim,
+            lr_scheduler,
+            strategy,
+            model_partial,
+            optim_partial,
+            lr_scheduler_partial,
+            grads_partial,
+            swa,
+            swa_partial,
+            state: TrainingStateCheckpointer::new(directory),
+            early_stopping: None,
+            last_partial: None,
+        }
+    }
+
+    /// The checkpoints to save and delete at the end of the given epoch.
+    pub(crate) fn actions(
+        &mut self,
+        epoch: usize,
+        store: &EventStoreClient,
+    ) -> Vec<CheckpointingAction> {
+        self.strategy.checkpointing(epoch, store)
+    }
+
+    /// Apply the [actions](Self::actions) of the end of an epoch.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn checkpoint(
         &mut self,
         model: &LC::Model,
         optim: &LC::Optimizer,
         scheduler: &LC::LrScheduler,
-        epoch: usize,
-        store: &EventStoreClient,
-    ) {
-        let actions = self.strategy.checkpointing(epoch, store);
+        swa: Option<&LC::Model>,
+        actions: Vec<CheckpointingAction>,
+        state: TrainingState,
+    ) -> Option<TrainingState> {
+        let epoch = state.epoch;
+        self.early_stopping = state.early_stopping;
+        let mut saved = None;
 
         for action in actions {
             match action {
                 CheckpointingAction::Delete(epoch) => {
+                    self.state
+                        .delete(epoch, false)
+                        .expect("Can delete training state checkpoint.");
                     self.model
                         .delete(epoch)
                         .expect("Can delete model checkpoint.");
@@ -61,6 +148,9 @@ impl<LC: LearnerComponents> LearnerCheckpointer<LC> {
                     self.lr_scheduler
                         .delete(epoch)
                         .expect("Can delete learning rate scheduler checkpoint.");
+                    self.swa
+                        .delete(epoch)
+                        .expect("Can delete SWA model checkpoint.");
                 }
                 CheckpointingAction::Save => {
                     self.model
@@ -72,38 +162,247 @@ impl<LC: LearnerComponents> LearnerCheckpointer<LC> {
                     self.lr_scheduler
                         .save(epoch, scheduler.to_record())
                         .expect("Can save learning rate scheduler checkpoint.");
+                    if let Some(swa) = swa {
+                        self.swa
+                            .save(epoch, swa.clone().into_record())
+                            .expect("Can save SWA model checkpoint.");
+                    }
+
+                    // The state is saved last, so it never refers to incomplete records.
+                    self.sync();
+                    self.state
+                        .save(&state)
+                        .expect("Can save training state checkpoint.");
+                    saved = Some(state.clone());
+
+                    // The checkpoint saved during the epoch is outdated.
+                    if self.last_partial == Some(epoch) {
+                        self.last_partial = None;
+                    }
+                    self.delete_partial(epoch);
                 }
             }
         }
+
+        saved
+    }
+
+    /// Save a checkpoint during an epoch, replacing the previous one.
+    pub(crate) fn checkpoint_partial(
+        &mut self,
+        model: &LC::Model,
+        optim: &LC::Optimizer,
+        scheduler: &LC::LrScheduler,
+        swa: Option<&LC::Model>,
+        grads: &GradientsParams,
+        mut state: TrainingState,
+    ) -> TrainingState {
+        let epoch = state.epoch;
+
+        // The gradients are saved in place of the parameters, using the model checkpointer.
+        let mut mapper = GradientsToParams {
+            grads,
+            ids: Vec::new(),
+        };
+        let record_grads = model.clone().map(&mut mapper).into_record();
+
+        self.model_partial
+            .save(epoch, model.clone().into_record())
+            .expect("Can save model checkpoint.");
+        self.optim_partial
+            .save(epoch, optim.to_record())
+            .expect("Can save optimizer checkpoint.");
+        self.lr_scheduler_partial
+            .save(epoch, scheduler.to_record())
+            .expect("Can save learning rate scheduler checkpoint.");
+        self.grads_partial
+            .save(epoch, record_grads)
+            .expect("Can save gradients checkpoint.");
+        if let Some(swa) = swa {
+            self.swa_partial
+                .save(epoch, swa.clone().into_record())
+                .expect("Can save SWA model checkpoint.");
+        }
+
+        // The state is saved last, so it never refers to incomplete records.
+        self.sync();
+        state.accumulated_params = mapper.ids;
+        state.early_stopping = self.early_stopping;
+        self.state
+            .save(&state)
+            .expect("Can save training state checkpoint.");
+
+        let previous = self.last_partial.replace(epoch);
+        if let Some(previous) = previous.filter(|previous| *previous != epoch) {
+            self.delete_partial(previous);
+        }
+
+        state
+    }
+
+    /// The state of the checkpoint saved at the end of the given epoch, which only contains the
+    /// epoch when the checkpoint was saved without it.
+    pub(crate) fn state(&self, epoch: usize) -> TrainingState {
+        self.state
+            .restore(epoch, false)
+            .unwrap_or_else(|_| TrainingState {
+                epoch,
+                ..Default::default()
+            })
+    }
+
+    /// The state of the most recent checkpoint.
+    pub(crate) fn latest_state(&self) -> Option<TrainingState> {
+        self.state.latest()
     }
 
+    /// Load the records of a checkpoint, returning the accumulated gradients and the model
+    /// with the parameters averaged by the SWA as well.
+    #[allow(clippy::type_complexity)]
     pub(crate) fn load_checkpoint(
-        &self,
+        &mut self,
         model: LC::Model,
         optim: LC::Optimizer,
         scheduler: LC::LrScheduler,
         device: &Device<LC::Backend>,
-        epoch: usize,
-    ) -> (LC::Model, LC::Optimizer, LC::LrScheduler) {
-        let record = self
-            .model
+        state: &TrainingState,
+    ) -> (
+        LC::Model,
+        LC::Optimizer,
+        LC::LrScheduler,
+        GradientsParams,
+        Option<LC::Model>,
+    ) {
+        let epoch = state.epoch;
+        let (checkpointer_model, checkpointer_optim, checkpointer_scheduler, checkpointer_swa) =
+            match state.is_partial() {
+                true => {
+                    self.last_partial = Some(epoch);
+                    (
+                        &self.model_partial,
+                        &self.optim_partial,
+                        &self.lr_scheduler_partial,
+                        &self.swa_partial,
+                    )
+                }
+                false => (&self.model, &self.optim, &self.lr_scheduler, &self.swa),
+            };
+        self.early_stopping = state.early_stopping;
+
+        let record = checkpointer_model
             .restore(epoch, device)
             .expect("Can load model checkpoint.");
         let model = model.load_record(record);
 
-        let record = self
-            .optim
+        let record = checkpointer_optim
             .restore(epoch, device)
             .expect("Can load optimizer checkpoint.");
         let optim = optim.load_record(record);
 
-        let record = self
-            .lr_scheduler
+        let record = checkpointer_scheduler
             .restore(epoch, device)
             .expect("Can load learning rate scheduler checkpoint.");
         let scheduler = scheduler.load_record(record);
 
-        (model, optim, scheduler)
+        let swa = state.swa.filter(|swa| swa.count > 0).map(|_| {
+            let record = checkpointer_swa
+                .restore(epoch, device)
+                .expect("Can load SWA model checkpoint.");
+            model.clone().load_record(record)
+        });
+
+        let mut collector = ParamsToGradients {
+            ids: state.accumulated_params.iter().copied().collect(),
+            grads: GradientsParams::new(),
+        };
+        if !collector.ids.is_empty() {
+            let record = self
+                .grads_partial
+                .restore(epoch, device)
+                .expect("Can load gradients checkpoint.");
+            model.clone().load_record(record).visit(&mut collector);
+        }
+
+        (model, optim, scheduler, collector.grads, swa)
+    }
+
+    fn delete_partial(&self, epoch: usize) {
+        // The state is deleted first, so it never refers to deleted records.
+        self.state
+            .delete(epoch, true)
+            .expect("Can delete training state checkpoint.");
+        self.model_partial
+            .delete(epoch)
+            .expect("Can delete model checkpoint.");
+        self.optim_partial
+            .delete(epoch)
+            .expect("Can delete optimizer checkpoint.");
+        self.lr_scheduler_partial
+            .delete(epoch)
+            .expect("Can delete learning rate scheduler checkpoint.");
+        self.grads_partial
+            .delete(epoch)
+            .expect("Can delete gradients checkpoint.");
+        self.swa_partial
+            .delete(epoch)
+            .expect("Can delete SWA model checkpoint.");
+    }
+
+    fn sync(&self) {
+        self.model.sync().expect("Can save model checkpoint.");
+        self.optim.sync().expect("Can save optimizer checkpoint.");
+        self.lr_scheduler
+            .sync()
+            .expect("Can save learning rate scheduler checkpoint.");
+        self.model_partial
+            .sync()
+            .expect("Can save model checkpoint.");
+        self.optim_partial
+            .sync()
+            .expect("Can save optimizer checkpoint.");
+        self.lr_scheduler_partial
+            .sync()
+            .expect("Can save learning rate scheduler checkpoint.");
+        self.grads_partial
+            .sync()
+            .expect("Can save gradients checkpoint.");
+        self.swa.sync().expect("Can save SWA model checkpoint.");
+        self.swa_partial
+            .sync()
+            .expect("Can save SWA model checkpoint.");
+    }
+}
+
+/// Replace the parameters of a module with their gradient, keeping track of their ids.
+struct GradientsToParams<'a> {
+    grads: &'a GradientsParams,
+    ids: Vec<u64>,
+}
+
+impl<B: AutodiffBackend> ModuleMapper<B> for GradientsToParams<'_> {
+    fn map_float<const D: usize>(&mut self, id: ParamId, tensor: Tensor<B, D>) -> Tensor<B, D> {
+        match self.grads.get::<B::InnerBackend, D>(id) {
+            Some(grad) => {
+                self.ids.push(id.val());
+                Tensor::from_inner(grad)
+            }
+            None => tensor,
+        }
+    }
+}
+
+/// Collect the gradients saved in place of the parameters of a module.
+struct ParamsToGradients {
+    ids: HashSet<u64>,
+    grads: GradientsParams,
+}
+
+impl<B: AutodiffBackend> ModuleVisitor<B> for ParamsToGradients {
+    fn visit_float<const D: usize>(&mut self, id: ParamId, tensor: &Tensor<B, D>) {
+        if self.ids.contains(&id.val()) {
+            self.grads
+                .register::<B::InnerBackend, D>(id, tensor.clone().inner());
+        }
     }
 }
 
@@ -129,3 +428,43 @@ impl TrainingInterrupter {
         self.state.load(Ordering::Relaxed)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TestAutodiffBackend, TestBackend};
+    use burn_core::nn::{Linear, LinearConfig};
+    use burn_core::tensor::Distribution;
+
+    #[test]
+    fn test_accumulated_gradients_saved_as_params() {
+        let device = Default::default();
+        let model: Linear<TestAutodiffBackend> = LinearConfig::new(4, 2).init(&device);
+        let x = Tensor::<TestAutodiffBackend, 2>::random([3, 4], Distribution::Default, &device);
+        let grads = model.forward(x).sum().backward();
+        let mut grads = GradientsParams::from_grads(grads, &model);
+        // Only the gradients of some parameters may be accumulated.
+        grads.remove::<TestBackend, 1>(model.bias.as_ref().unwrap().id);
+
+        let mut mapper = GradientsToParams {
+            grads: &grads,
+            ids: Vec::new(),
+        };
+        let record = model.clone().map(&mut mapper).into_record();
+        assert_eq!(mapper.ids, vec![model.weight.id.val()]);
+
+        let mut collector = ParamsToGradients {
+            ids: mapper.ids.into_iter().collect(),
+            grads: GradientsParams::new(),
+        };
+        model.clone().load_record(record).visit(&mut collector);
+
+        let expected = grads.get::<TestBackend, 2>(model.weight.id).unwrap();
+        let restored = collector
+            .grads
+            .get::<TestBackend, 2>(model.weight.id)
+            .unwrap();
+        assert_eq!(collector.grads.len(), 1);
+        restored.into_data().assert_eq(&expected.into_data(), true);
+    }
+}
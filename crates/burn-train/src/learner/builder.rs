@@ -7,18 +7,22 @@ use crate::checkpoint::{
     AsyncCheckpointer, CheckpointingStrategy, ComposedCheckpointingStrategy, FileCheckpointer,
     KeepLastNCheckpoints, MetricCheckpointingStrategy,
 };
+use crate::collective::{Collective, GatherParams, ShardedOptimizer, Transport};
 use crate::components::LearnerComponentsMarker;
-use crate::learner::EarlyStoppingStrategy;
 use crate::learner::base::TrainingInterrupter;
-use crate::logger::{FileMetricLogger, MetricLogger};
+use crate::learner::{EarlyStoppingStrategy, LearnerCallback};
+use crate::logger::{FileMetricLogger, InMemoryMetricLogger, MetricLogger};
 use crate::metric::processor::{AsyncProcessor, FullEventProcessor, ItemLazy, Metrics};
 use crate::metric::store::{Aggregate, Direction, EventStoreClient, LogEventStore, Split};
 use crate::metric::{Adaptor, LossMetric, Metric};
-use crate::renderer::{MetricsRenderer, default_renderer};
+use crate::renderer::{MetricsRenderer, NoopMetricsRenderer, default_renderer};
+use crate::tracking::Run;
 use crate::{
     ApplicationLoggerInstaller, FileApplicationLoggerInstaller, LearnerCheckpointer,
-    LearnerSummaryConfig,
+    LearnerSummaryConfig, StochasticWeightAveraging,
 };
+use burn_core::data::dataset::transform::LoadCounts;
+use burn_core::grad_clipping::GradientClipping;
 use burn_core::lr_scheduler::LrScheduler;
 use burn_core::module::AutodiffModule;
 use burn_core::optim::Optimizer;
@@ -38,17 +42,42 @@ where
     // Not that complex and very convenient when the traits are
     // already constrained correctly. Extracting in another type
     // would be more complex.
+    // The checkpointers are created for their directory when the learner is built, since it
+    // depends on the rank of the process.
     #[allow(clippy::type_complexity)]
-    checkpointers: Option<(
-        AsyncCheckpointer<M::Record, B>,
-        AsyncCheckpointer<O::Record, B>,
-        AsyncCheckpointer<S::Record<B>, B>,
-    )>,
-    num_epochs: usize,
+    checkpointers: Option<
+        Box<
+            dyn FnOnce(
+                &Path,
+            ) -> (
+                AsyncCheckpointer<M::Record, B>,
+                AsyncCheckpointer<O::Record, B>,
+                AsyncCheckpointer<S::Record<B>, B>,
+                AsyncCheckpointer<M::Record, B>,
+                AsyncCheckpointer<O::Record, B>,
+                AsyncCheckpointer<S::Record<B>, B>,
+                AsyncCheckpointer<M::Record, B>,
+                AsyncCheckpointer<M::Record, B>,
+                AsyncCheckpointer<M::Record, B>,
+            ),
+        >,
+    >,
+    num_epochs: Option<usize>,
+    steps_per_epoch: Option<usize>,
+    max_steps: Option<usize>,
+    max_validation_batches: Option<usize>,
     checkpoint: Option<usize>,
+    resume: bool,
+    checkpoint_interval: Option<usize>,
+    seed: Option<u64>,
     directory: PathBuf,
     grad_accumulation: Option<usize>,
+    grad_clipping: Option<GradientClipping>,
+    swa: Option<StochasticWeightAveraging>,
+    load_counts: Option<LoadCounts>,
     devices: Vec<B::Device>,
+    collective: Option<Collective<Box<dyn Transport>>>,
+    sharded_optimizer: Option<GatherParams<O, M>>,
     renderer: Option<Box<dyn MetricsRenderer + 'static>>,
     metrics: Metrics<T, V>,
     event_store: LogEventStore,
@@ -57,8 +86,10 @@ where
     num_loggers: usize,
     checkpointer_strategy: Box<dyn CheckpointingStrategy>,
     early_stopping: Option<Box<dyn EarlyStoppingStrategy>>,
+    callbacks: Vec<Box<dyn LearnerCallback<B, M, O>>>,
     summary_metrics: HashSet<String>,
     summary: bool,
+    run: Option<Run>,
 }
 
 impl<B, T, V, M, O, S> LearnerBuilder<B, T, V, M, O, S>
@@ -79,12 +110,23 @@ where
         let directory = directory.as_ref().to_path_buf();
         let experiment_log_file = directory.join("experiment.log");
         Self {
-            num_epochs: 1,
+            num_epochs: None,
+            steps_per_epoch: None,
+            max_steps: None,
+            max_validation_batches: None,
             checkpoint: None,
+            resume: false,
+            checkpoint_interval: None,
+            seed: None,
             checkpointers: None,
             directory,
             grad_accumulation: None,
+            grad_clipping: None,
+            swa: None,
+            load_counts: None,
             devices: vec![B::Device::default()],
+            collective: None,
+            sharded_optimizer: None,
             metrics: Metrics::default(),
             event_store: LogEventStore::default(),
             renderer: None,
@@ -105,8 +147,10 @@ where
                     .build(),
             ),
             early_stopping: None,
+            callbacks: Vec::new(),
             summary_metrics: HashSet::new(),
             summary: false,
+            run: None,
         }
     }
 
@@ -182,6 +226,46 @@ where
         self
     }
 
+    /// Enable gradients clipping over all the model parameters before each optimizer step.
+    ///
+    /// # Notes
+    ///
+    /// The L2 norm of the gradients computed before clipping is made available to the metrics,
+    /// see [GradientNormMetric](crate::metric::GradientNormMetric).
+    ///
+    /// This should not be combined with the gradient clipping of the [optimizer](Optimizer),
+    /// which is applied afterward.
+    pub fn grad_clipping(mut self, clipping: GradientClipping) -> Self {
+        self.grad_clipping = Some(clipping);
+        self
+    }
+
+    /// Enable [stochastic weight averaging](StochasticWeightAveraging) of the model parameters.
+    ///
+    /// # Notes
+    ///
+    /// The model returned by [fit](crate::Learner::fit) contains the averaged parameters, while
+    /// the checkpoints and the validation are done with the parameters being trained.
+    pub fn stochastic_weight_averaging(mut self, swa: StochasticWeightAveraging) -> Self {
+        self.swa = Some(swa);
+        self
+    }
+
+    /// Save the [load counts](LoadCounts) of the training dataset with the checkpoints, and
+    /// restore them when the training resumes, so that the items are randomly transformed as in
+    /// an uninterrupted training, e.g. by an augmented image dataset.
+    ///
+    /// # Notes
+    ///
+    /// A [multi-threaded data loader](burn_core::data::dataloader::MultiThreadDataLoader) loads
+    /// batches ahead of the training, whose items are counted when the checkpoint is saved
+    /// although they weren't trained on yet, so the resumed transforms only match exactly with a
+    /// single thread.
+    pub fn load_counts(mut self, counts: LoadCounts) -> Self {
+        self.load_counts = Some(counts);
+        self
+    }
+
     /// Register a [numeric](crate::metric::Numeric) training [metric](Metric).
     pub fn metric_train_numeric<Me>(mut self, metric: Me) -> Self
     where
@@ -207,8 +291,70 @@ where
     }
 
     /// The number of epochs the training should last.
+    ///
+    /// Defaults to one epoch, or to as many epochs as required to reach the
+    /// [maximum number of steps](Self::max_steps) when set.
     pub fn num_epochs(mut self, num_epochs: usize) -> Self {
-        self.num_epochs = num_epochs;
+        self.num_epochs = Some(num_epochs);
+        self
+    }
+
+    /// Make every epoch last `num_steps` training steps instead of a full iteration over the
+    /// training data loader, which starts a new iteration when it is exhausted. The validation,
+    /// the [checkpointing strategy](CheckpointingStrategy), the early stopping and the metric
+    /// logging then happen every `num_steps` steps, which suits very large or infinite
+    /// datasets.
+    ///
+    /// # Notes
+    ///
+    /// The steps of an epoch are its training iterations, i.e. its batches, whether they step
+    /// the optimizer or only accumulate their gradients. With multiple devices, every batch of
+    /// every device is a training step. Resuming the iteration over the data loader at every
+    /// epoch is cheap for the data loaders batching a fixed number of items, but requires to
+    /// load the batches already yielded otherwise.
+    ///
+    /// # Panics
+    ///
+    /// If `num_steps` is zero.
+    pub fn steps_per_epoch(mut self, num_steps: usize) -> Self {
+        assert!(
+            num_steps > 0,
+            "The number of steps per epoch must be at least 1"
+        );
+        self.steps_per_epoch = Some(num_steps);
+        self
+    }
+
+    /// Stop the training after `max_steps` optimizer steps. The epoch of the last step ends
+    /// right after it, and is validated and checkpointed as the other epochs.
+    ///
+    /// Unless the [number of epochs](Self::num_epochs) is set, the training lasts as many epochs
+    /// as required to reach the maximum number of steps, which requires the
+    /// [number of steps per epoch](Self::steps_per_epoch).
+    ///
+    /// # Notes
+    ///
+    /// With [gradients accumulation](Self::grads_accumulation), the optimizer is stepped every
+    /// `accumulation` iterations, and with multiple devices once the batches of every device
+    /// are processed. The [learning rate scheduler](LrScheduler) is stepped along the optimizer,
+    /// so it should be configured with `max_steps`, e.g. as the number of iterations of a cosine
+    /// annealing schedule.
+    ///
+    /// # Panics
+    ///
+    /// If `max_steps` is zero.
+    pub fn max_steps(mut self, max_steps: usize) -> Self {
+        assert!(
+            max_steps > 0,
+            "The maximum number of steps must be at least 1"
+        );
+        self.max_steps = Some(max_steps);
+        self
+    }
+
+    /// Only validate the model on the first `num_batches` batches of the validation data loader.
+    pub fn max_validation_batches(mut self, num_batches: usize) -> Self {
+        self.max_validation_batches = Some(num_batches);
         self
     }
 
@@ -218,12 +364,93 @@ where
         self
     }
 
+    /// Synchronize the training with the other processes of a distributed training, every
+    /// process averaging its gradients with the other ones before each optimizer step.
+    ///
+    /// # Notes
+    ///
+    /// The model is copied from the rank 0 at the start of the training. Only the rank 0 saves
+    /// the checkpoints, renders and logs the metrics, and decides when to stop early, the metrics
+    /// being computed on its own items only. The training dataloader of every process must
+    /// yield the same number of batches, which is the case when the dataset is sharded with a
+    /// [distributed sampler](burn_core::data::dataloader::DistributedSampler).
+    pub fn distributed<Tr: Transport + 'static>(mut self, collective: Collective<Tr>) -> Self {
+        self.collective = Some(collective.boxed());
+        self
+    }
+
+    /// Leave the reduction of the gradients between the processes of a
+    /// [distributed](Self::distributed) training to the optimizer, as done by a
+    /// [ZeroOptimizer](crate::collective::ZeroOptimizer).
+    ///
+    /// # Notes
+    ///
+    /// The [gradient clipping](Self::grad_clipping) is then applied to the gradients of the
+    /// current process only.
+    ///
+    /// Since the optimizer state differs between the processes, every process saves its own
+    /// checkpoints, in the `checkpoint-rank-{rank}` directory for the ranks other than 0, and
+    /// resumes from them. The checkpoints to keep are decided by the rank 0.
+    ///
+    /// The parameters released by the optimizer are [gathered](ShardedOptimizer::gather_params)
+    /// before each training step, and at the end of the epochs. The callbacks called during the
+    /// epochs after the optimizer steps may receive a module with the parameters of the current
+    /// process only.
+    ///
+    /// # Panics
+    ///
+    /// The training panics when the parameters can't be gathered, e.g. when the connection with
+    /// another process is lost, like for the other collective operations of the training.
+    pub fn sharded_optimizer(mut self) -> Self
+    where
+        O: ShardedOptimizer<M, B>,
+    {
+        self.sharded_optimizer = Some(O::gather_params);
+        self
+    }
+
     /// The epoch from which the training must resume.
     pub fn checkpoint(mut self, checkpoint: usize) -> Self {
         self.checkpoint = Some(checkpoint);
         self
     }
 
+    /// Resume the training from the most recent checkpoint saved in the directory, which may
+    /// have been saved during an epoch, or start it from scratch when there is none.
+    ///
+    /// # Notes
+    ///
+    /// Requires a [file checkpointer](Self::with_file_checkpointer), and takes precedence over
+    /// the [checkpoint](Self::checkpoint) epoch.
+    pub fn resume(mut self) -> Self {
+        self.resume = true;
+        self
+    }
+
+    /// Also save a checkpoint every `num_iterations` training iterations, and when the training
+    /// is interrupted, so a long epoch can be resumed where it stopped. Only the most recent of
+    /// these checkpoints is kept, until the end of its epoch.
+    ///
+    /// # Notes
+    ///
+    /// Along with the records of the model, the optimizer and the learning rate scheduler, the
+    /// checkpoint contains the gradients being [accumulated](Self::grads_accumulation). The
+    /// training dataloader resumes from the same batch when it shuffles its items with a seed,
+    /// and when its workers are
+    /// [ordered](burn_core::data::dataloader::DataLoaderBuilder::ordered_workers).
+    pub fn checkpoint_interval(mut self, num_iterations: usize) -> Self {
+        self.checkpoint_interval = Some(num_iterations);
+        self
+    }
+
+    /// Seed the backend before every training iteration with a seed derived from the given one,
+    /// the epoch and the iteration, so the random operations of a resumed training, such as the
+    /// dropout, are the same as the ones of an uninterrupted training.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
     /// Provides a handle that can be used to interrupt training.
     pub fn interrupter(&self) -> TrainingInterrupter {
         self.interrupter.clone()
@@ -239,6 +466,17 @@ where
         self
     }
 
+    /// Register a [callback](LearnerCallback) called at the different steps of the training.
+    ///
+    /// The callbacks are called in the order of registration.
+    pub fn callback<C>(mut self, callback: C) -> Self
+    where
+        C: LearnerCallback<B, M, O> + 'static,
+    {
+        self.callbacks.push(Box::new(callback));
+        self
+    }
+
     /// By default, Rust logs are captured and written into
     /// `experiment.log`. If disabled, standard Rust log handling
     /// will apply.
@@ -260,18 +498,37 @@ where
         M::Record: 'static,
         S::Record<B>: 'static,
     {
-        let checkpoint_dir = self.directory.join("checkpoint");
-        let checkpointer_model = FileCheckpointer::new(recorder.clone(), &checkpoint_dir, "model");
-        let checkpointer_optimizer =
-            FileCheckpointer::new(recorder.clone(), &checkpoint_dir, "optim");
-        let checkpointer_scheduler: FileCheckpointer<FR> =
-            FileCheckpointer::new(recorder, &checkpoint_dir, "scheduler");
-
-        self.checkpointers = Some((
-            AsyncCheckpointer::new(checkpointer_model),
-            AsyncCheckpointer::new(checkpointer_optimizer),
-            AsyncCheckpointer::new(checkpointer_scheduler),
-        ));
+        self.checkpointers = Some(Box::new(move |checkpoint_dir: &Path| {
+            let checkpointer_model =
+                FileCheckpointer::new(recorder.clone(), checkpoint_dir, "model");
+            let checkpointer_optimizer =
+                FileCheckpointer::new(recorder.clone(), checkpoint_dir, "optim");
+            let checkpointer_scheduler: FileCheckpointer<FR> =
+                FileCheckpointer::new(recorder.clone(), checkpoint_dir, "scheduler");
+            let checkpointer_model_partial =
+                FileCheckpointer::new(recorder.clone(), checkpoint_dir, "model-partial");
+            let checkpointer_optimizer_partial =
+                FileCheckpointer::new(recorder.clone(), checkpoint_dir, "optim-partial");
+            let checkpointer_scheduler_partial: FileCheckpointer<FR> =
+                FileCheckpointer::new(recorder.clone(), checkpoint_dir, "scheduler-partial");
+            let checkpointer_grads_partial =
+                FileCheckpointer::new(recorder.clone(), checkpoint_dir, "grads-partial");
+            let checkpointer_swa = FileCheckpointer::new(recorder.clone(), checkpoint_dir, "swa");
+            let checkpointer_swa_partial =
+                FileCheckpointer::new(recorder, checkpoint_dir, "swa-partial");
+
+            (
+                AsyncCheckpointer::new(checkpointer_model),
+                AsyncCheckpointer::new(checkpointer_optimizer),
+                AsyncCheckpointer::new(checkpointer_scheduler),
+                AsyncCheckpointer::new(checkpointer_model_partial),
+                AsyncCheckpointer::new(checkpointer_optimizer_partial),
+                AsyncCheckpointer::new(checkpointer_scheduler_partial),
+                AsyncCheckpointer::new(checkpointer_grads_partial),
+                AsyncCheckpointer::new(checkpointer_swa),
+                AsyncCheckpointer::new(checkpointer_swa_partial),
+            )
+        }));
 
         self
     }
@@ -284,9 +541,36 @@ where
         self
     }
 
+    /// Record the training in a [run](Run) of an experiment tracker.
+    ///
+    /// The devices are added to the run metadata, and at the end of `.fit()` the metrics history
+    /// and the summary of the registered metrics are exported to the run directory.
+    ///
+    /// # Notes
+    ///
+    /// The builder should be created with the [run directory](Run::directory), to save the logs
+    /// and checkpoints of the training with the run.
+    pub fn tracking(mut self, run: Run) -> Self {
+        if run.directory() != self.directory {
+            log::warn!(
+                "The learner directory {} differs from the run directory {}",
+                self.directory.display(),
+                run.directory().display()
+            );
+        }
+        self.run = Some(run);
+        self
+    }
+
     /// Create the [learner](Learner) from a [model](AutodiffModule) and an [optimizer](Optimizer).
     /// The [learning rate scheduler](LrScheduler) can also be a simple
     /// [learning rate](burn_core::LearningRate).
+    ///
+    /// # Panics
+    ///
+    /// If the [maximum number of steps](Self::max_steps) is set without the
+    /// [number of epochs](Self::num_epochs) nor the
+    /// [number of steps per epoch](Self::steps_per_epoch).
     #[allow(clippy::type_complexity)] // The goal for the builder is to handle all types and
     // creates a clean learner.
     pub fn build(
@@ -312,20 +596,34 @@ where
         O::Record: 'static,
         S::Record<B>: 'static,
     {
-        if self.tracing_logger.is_some() {
-            if let Err(e) = self.tracing_logger.as_ref().unwrap().install() {
+        let is_main_rank = self
+            .collective
+            .as_ref()
+            .is_none_or(|collective| collective.rank() == 0);
+
+        if let Some(tracing_logger) = self.tracing_logger.as_ref().filter(|_| is_main_rank) {
+            if let Err(e) = tracing_logger.install() {
                 log::warn!("Failed to install the experiment logger: {}", e);
             }
         }
-        let renderer = self
-            .renderer
-            .unwrap_or_else(|| default_renderer(self.interrupter.clone(), self.checkpoint));
+        let renderer = match self.renderer {
+            Some(renderer) => renderer,
+            None if is_main_rank => default_renderer(self.interrupter.clone(), self.checkpoint),
+            None => Box::new(NoopMetricsRenderer),
+        };
 
         if self.num_loggers == 0 {
-            self.event_store
-                .register_logger_train(FileMetricLogger::new(self.directory.join("train")));
-            self.event_store
-                .register_logger_valid(FileMetricLogger::new(self.directory.join("valid")));
+            if is_main_rank {
+                self.event_store
+                    .register_logger_train(FileMetricLogger::new(self.directory.join("train")));
+                self.event_store
+                    .register_logger_valid(FileMetricLogger::new(self.directory.join("valid")));
+            } else {
+                self.event_store
+                    .register_logger_train(InMemoryMetricLogger::new());
+                self.event_store
+                    .register_logger_valid(InMemoryMetricLogger::new());
+            }
         }
 
         let event_store = Arc::new(EventStoreClient::new(self.event_store));
@@ -335,33 +633,106 @@ where
             event_store.clone(),
         ));
 
-        let checkpointer = self.checkpointers.map(|(model, optim, scheduler)| {
-            LearnerCheckpointer::new(model, optim, scheduler, self.checkpointer_strategy)
+        // With a sharded optimizer, every process saves its own checkpoints.
+        let checkpoint_dir = match &self.collective {
+            Some(collective) if self.sharded_optimizer.is_some() && collective.rank() > 0 => self
+                .directory
+                .join(format!("checkpoint-rank-{}", collective.rank())),
+            _ => self.directory.join("checkpoint"),
+        };
+        let checkpointer = self
+            .checkpointers
+            .map(|checkpointers| checkpointers(&checkpoint_dir))
+            .map(
+                |(
+                    model,
+                    optim,
+                    scheduler,
+                    model_partial,
+                    optim_partial,
+                    scheduler_partial,
+                    grads,
+                    swa,
+                    swa_partial,
+                )| {
+                    LearnerCheckpointer::new(
+                        model,
+                        optim,
+                        scheduler,
+                        model_partial,
+                        optim_partial,
+                        scheduler_partial,
+                        grads,
+                        swa,
+                        swa_partial,
+                        self.checkpointer_strategy,
+                        checkpoint_dir,
+                    )
+                },
+            );
+
+        let mut summary_metrics = self.summary_metrics.into_iter().collect::<Vec<_>>();
+        summary_metrics.sort();
+
+        // Only the main process records the run.
+        let run = self.run.filter(|_| is_main_rank).map(|mut run| {
+            let devices = self.devices.iter().map(|device| format!("{device:?}"));
+            run.start_training(devices.collect(), summary_metrics.clone());
+            run
         });
 
         let summary = if self.summary {
             Some(LearnerSummaryConfig {
                 directory: self.directory,
-                metrics: self.summary_metrics.into_iter().collect::<Vec<_>>(),
+                metrics: summary_metrics,
             })
         } else {
             None
         };
 
+        let num_epochs = self
+            .num_epochs
+            .unwrap_or(match (self.max_steps, self.steps_per_epoch) {
+                (Some(max_steps), Some(steps_per_epoch)) => {
+                    // Every optimizer step takes an iteration per accumulation and per device.
+                    let iterations =
+                        max_steps * self.grad_accumulation.unwrap_or(1) * self.devices.len().max(1);
+                    iterations.div_ceil(steps_per_epoch)
+                }
+                (Some(_), None) => panic!(
+                    "The number of epochs or of steps per epoch is required with a maximum \
+                     number of steps"
+                ),
+                _ => 1,
+            });
+
         Learner {
             model,
             optim,
             lr_scheduler,
             checkpointer,
-            num_epochs: self.num_epochs,
+            num_epochs,
             event_processor,
             event_store,
             checkpoint: self.checkpoint,
+            resume: self.resume,
+            checkpoint_interval: self.checkpoint_interval,
+            seed: self.seed,
+            steps_per_epoch: self.steps_per_epoch,
+            max_steps: self.max_steps,
+            max_validation_batches: self.max_validation_batches,
             grad_accumulation: self.grad_accumulation,
+            grad_clipping: self.grad_clipping,
+            swa: self.swa,
+            load_counts: self.load_counts,
             devices: self.devices,
+            collective: self.collective,
+            sharded_optimizer: self.sharded_optimizer,
             interrupter: self.interrupter,
             early_stopping: self.early_stopping,
+            callbacks: self.callbacks,
             summary,
+            run,
         }
     }
 }
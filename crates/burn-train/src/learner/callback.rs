@@ -0,0 +1,390 @@
+use burn_core::optim::GradientsParams;
+use burn_core::tensor::backend::AutodiffBackend;
+
+use crate::checkpoint::TrainingState;
+use crate::metric::store::EventStoreClient;
+
+/// Hooks called by the [learner](crate::Learner) during the training, to customize it without
+/// rewriting the training loop, e.g. to log the gradient norm, keep an exponential moving
+/// average of the parameters or prune the model.
+///
+/// Registered with [callback](crate::LearnerBuilder::callback), the callbacks are called in the
+/// order of registration. Every hook does nothing by default.
+///
+/// # Notes
+///
+/// With multiple devices, a batch is the group of batches processed in parallel on the devices.
+/// In a distributed training, the callbacks are called on every process, except
+/// [on_checkpoint](LearnerCallback::on_checkpoint) which is only called on the main process.
+pub trait LearnerCallback<B: AutodiffBackend, M, O> {
+    /// Called once before the first training epoch.
+    fn on_train_start(&mut self, _ctx: &mut CallbackContext<M, O>) {}
+
+    /// Called once after the training, before the averaged model of the
+    /// [stochastic weight averaging](crate::StochasticWeightAveraging) is loaded.
+    fn on_train_end(&mut self, _ctx: &mut CallbackContext<M, O>) {}
+
+    /// Called before every training epoch.
+    fn on_epoch_start(&mut self, _ctx: &mut CallbackContext<M, O>) {}
+
+    /// Called after every epoch, once the model is validated and the checkpoint saved.
+    fn on_epoch_end(&mut self, _ctx: &mut CallbackContext<M, O>) {}
+
+    /// Called before the training step of every batch.
+    fn on_batch_start(&mut self, _ctx: &mut CallbackContext<M, O>) {}
+
+    /// Called after every batch, once its metrics are processed.
+    fn on_batch_end(&mut self, _ctx: &mut CallbackContext<M, O>) {}
+
+    /// Called right before every optimizer step with the gradients to be applied, after they are
+    /// accumulated, reduced between the processes and clipped.
+    fn on_before_optimizer_step(
+        &mut self,
+        _ctx: &mut CallbackContext<M, O>,
+        _grads: &mut GradientsParams,
+    ) {
+    }
+
+    /// Called after the validation of every epoch, with the store of the logged metrics.
+    fn on_validation_end(&mut self, _ctx: &mut CallbackContext<M, O>, _store: &EventStoreClient) {}
+
+    /// Called after a checkpoint is saved, with the [state](TrainingState) saved along the
+    /// records.
+    fn on_checkpoint(&mut self, _ctx: &mut CallbackContext<M, O>, _state: &TrainingState) {}
+}
+
+/// The training state passed to the [callbacks](LearnerCallback), giving access to the model
+/// and the optimizer.
+pub struct CallbackContext<'a, M, O> {
+    /// The model being trained.
+    pub model: &'a mut M,
+    /// The optimizer.
+    pub optim: &'a mut O,
+    /// The current epoch.
+    pub epoch: usize,
+    /// The total number of epochs.
+    pub epoch_total: usize,
+    /// The number of training iterations done during the current epoch.
+    pub iteration: usize,
+    /// The number of optimizer steps done since the start of the training.
+    pub step: usize,
+    stop: bool,
+}
+
+impl<M, O> CallbackContext<'_, M, O> {
+    /// Request the training to stop.
+    ///
+    /// When requested during an epoch, the epoch is interrupted as with the
+    /// [interrupter](crate::TrainingInterrupter). Otherwise, the training stops at the end of the
+    /// current epoch, once the checkpoint is saved, as with an
+    /// [early stopping strategy](crate::EarlyStoppingStrategy).
+    pub fn stop_training(&mut self) {
+        self.stop = true;
+    }
+}
+
+/// The [callbacks](LearnerCallback) registered in a [learner](crate::Learner).
+pub struct LearnerCallbacks<B: AutodiffBackend, M, O> {
+    callbacks: Vec<Box<dyn LearnerCallback<B, M, O>>>,
+    epoch_total: usize,
+    step: usize,
+    stop: bool,
+}
+
+impl<B: AutodiffBackend, M, O> LearnerCallbacks<B, M, O> {
+    pub(crate) fn new(
+        callbacks: Vec<Box<dyn LearnerCallback<B, M, O>>>,
+        epoch_total: usize,
+    ) -> Self {
+        Self {
+            callbacks,
+            epoch_total,
+            step: 0,
+            stop: false,
+        }
+    }
+
+    /// Set the number of optimizer steps done since the start of the training.
+    pub(crate) fn set_step(&mut self, step: usize) {
+        self.step = step;
+    }
+
+    /// Call a hook of every callback with the context of the given epoch and iteration.
+    pub(crate) fn call<F>(
+        &mut self,
+        model: &mut M,
+        optim: &mut O,
+        epoch: usize,
+        iteration: usize,
+        mut hook: F,
+    ) where
+        F: FnMut(&mut dyn LearnerCallback<B, M, O>, &mut CallbackContext<M, O>),
+    {
+        if self.callbacks.is_empty() {
+            return;
+        }
+
+        let mut ctx = CallbackContext {
+            model,
+            optim,
+            epoch,
+            epoch_total: self.epoch_total,
+            iteration,
+            step: self.step,
+            stop: false,
+        };
+        for callback in self.callbacks.iter_mut() {
+            hook(callback.as_mut(), &mut ctx);
+        }
+        self.stop |= ctx.stop;
+    }
+
+    /// Whether a callback requested the training to stop.
+    pub(crate) fn should_stop(&self) -> bool {
+        self.stop
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestAutodiffBackend;
+    use crate::tests::{
+        regression_dataloaders, regression_learner, regression_model, test_directory,
+    };
+    use burn_core::nn::Linear;
+    use burn_core::optim::SgdConfig;
+    use std::sync::{Arc, Mutex};
+
+    /// Records the calls in the model, and stops the training at the given epoch.
+    struct Recorder {
+        name: &'static str,
+        stop_epoch: usize,
+    }
+
+    impl LearnerCallback<TestAutodiffBackend, Vec<String>, ()> for Recorder {
+        fn on_epoch_start(&mut self, ctx: &mut CallbackContext<Vec<String>, ()>) {
+            ctx.model.push(format!("{}-{}", self.name, ctx.epoch));
+        }
+
+        fn on_epoch_end(&mut self, ctx: &mut CallbackContext<Vec<String>, ()>) {
+            if ctx.epoch == self.stop_epoch {
+                ctx.stop_training();
+            }
+        }
+    }
+
+    #[test]
+    fn test_callbacks_called_in_order_and_stop() {
+        let mut callbacks = LearnerCallbacks::<TestAutodiffBackend, _, _>::new(
+            vec![
+                Box::new(Recorder {
+                    name: "a",
+                    stop_epoch: 2,
+                }),
+                Box::new(Recorder {
+                    name: "b",
+                    stop_epoch: 3,
+                }),
+            ],
+            3,
+        );
+        let mut model = Vec::new();
+
+        for epoch in 1..=2 {
+            callbacks.call(&mut model, &mut (), epoch, 0, |callback, ctx| {
+                callback.on_epoch_start(ctx)
+            });
+            assert!(!callbacks.should_stop());
+            callbacks.call(&mut model, &mut (), epoch, 10, |callback, ctx| {
+                callback.on_epoch_end(ctx)
+            });
+        }
+
+        assert_eq!(model, vec!["a-1", "b-1", "a-2", "b-2"]);
+        assert!(callbacks.should_stop());
+    }
+
+    type Model = Linear<TestAutodiffBackend>;
+
+    /// Records the hooks called during a training, and stops it at the end of the given batch.
+    #[derive(Clone, Default)]
+    struct Hooks {
+        calls: Arc<Mutex<Vec<String>>>,
+        stop_at: Option<(usize, usize)>,
+    }
+
+    impl Hooks {
+        fn record<O>(&self, hook: &str, ctx: &CallbackContext<Model, O>) {
+            let call = format!("{hook} {}:{}", ctx.epoch, ctx.iteration);
+            self.calls.lock().unwrap().push(call);
+        }
+
+        fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    impl<O> LearnerCallback<TestAutodiffBackend, Model, O> for Hooks {
+        fn on_train_start(&mut self, ctx: &mut CallbackContext<Model, O>) {
+            self.record("train_start", ctx);
+        }
+
+        fn on_train_end(&mut self, ctx: &mut CallbackContext<Model, O>) {
+            self.record("train_end", ctx);
+        }
+
+        fn on_epoch_start(&mut self, ctx: &mut CallbackContext<Model, O>) {
+            self.record("epoch_start", ctx);
+        }
+
+        fn on_epoch_end(&mut self, ctx: &mut CallbackContext<Model, O>) {
+            self.record("epoch_end", ctx);
+        }
+
+        fn on_batch_start(&mut self, ctx: &mut CallbackContext<Model, O>) {
+            self.record("batch_start", ctx);
+        }
+
+        fn on_batch_end(&mut self, ctx: &mut CallbackContext<Model, O>) {
+            self.record("batch_end", ctx);
+            if self.stop_at == Some((ctx.epoch, ctx.iteration)) {
+                ctx.stop_training();
+            }
+        }
+
+        fn on_before_optimizer_step(
+            &mut self,
+            ctx: &mut CallbackContext<Model, O>,
+            _grads: &mut GradientsParams,
+        ) {
+            self.record("optimizer_step", ctx);
+        }
+
+        fn on_validation_end(
+            &mut self,
+            ctx: &mut CallbackContext<Model, O>,
+            _store: &EventStoreClient,
+        ) {
+            self.record("validation_end", ctx);
+        }
+    }
+
+    /// Fit the regression on 8 inputs in batches of 2 during 2 epochs.
+    fn fit(name: &str, hooks: &Hooks, num_devices: usize) {
+        let directory = test_directory(name);
+        let inputs = (0..8).map(|x| x as f32 / 8.0).collect();
+        let (train, valid) = regression_dataloaders(inputs, 2);
+
+        regression_learner(&directory)
+            .devices(vec![Default::default(); num_devices])
+            .callback(hooks.clone())
+            .num_epochs(2)
+            .build(regression_model(), SgdConfig::new().init(), 0.1)
+            .fit(train, valid);
+        std::fs::remove_dir_all(directory).ok();
+    }
+
+    /// The hooks of an epoch whose optimizer steps end at the given iterations.
+    fn epoch_calls(epoch: usize, steps: &[usize]) -> Vec<String> {
+        let mut calls = vec![format!("epoch_start {epoch}:0")];
+        let mut iteration = 0;
+        for step in steps {
+            calls.push(format!("batch_start {epoch}:{iteration}"));
+            calls.push(format!("optimizer_step {epoch}:{step}"));
+            calls.push(format!("batch_end {epoch}:{step}"));
+            iteration = *step;
+        }
+        calls.push(format!("validation_end {epoch}:0"));
+        calls.push(format!("epoch_end {epoch}:0"));
+        calls
+    }
+
+    #[test]
+    fn test_learner_calls_the_hooks_in_order() {
+        let hooks = Hooks::default();
+        fit("callback-hooks", &hooks, 1);
+
+        let mut expected = vec!["train_start 1:0".to_string()];
+        expected.extend(epoch_calls(1, &[1, 2, 3, 4]));
+        expected.extend(epoch_calls(2, &[1, 2, 3, 4]));
+        expected.push("train_end 2:0".to_string());
+        assert_eq!(hooks.calls(), expected);
+    }
+
+    #[test]
+    fn test_learner_calls_the_hooks_once_per_step_on_multiple_devices() {
+        let hooks = Hooks::default();
+        fit("callback-hooks-multi-device", &hooks, 2);
+
+        // Every device processes 2 batches per epoch.
+        let mut expected = vec!["train_start 1:0".to_string()];
+        expected.extend(epoch_calls(1, &[2, 4]));
+        expected.extend(epoch_calls(2, &[2, 4]));
+        expected.push("train_end 2:0".to_string());
+        assert_eq!(hooks.calls(), expected);
+    }
+
+    #[test]
+    fn test_callback_stops_the_training() {
+        let hooks = Hooks {
+            stop_at: Some((1, 2)),
+            ..Default::default()
+        };
+        fit("callback-stop", &hooks, 1);
+
+        // The epoch is interrupted, without validation.
+        let calls = hooks.calls();
+        assert_eq!(calls[calls.len() - 2], "batch_end 1:2");
+        assert_eq!(calls[calls.len() - 1], "train_end 1:0");
+        assert!(!calls.iter().any(|call| call.starts_with("batch_start 1:2")));
+    }
+
+    /// Records the number of optimizer steps in the context of the hooks.
+    #[derive(Clone, Default)]
+    struct Steps(Arc<Mutex<Vec<String>>>);
+
+    impl<O> LearnerCallback<TestAutodiffBackend, Model, O> for Steps {
+        fn on_before_optimizer_step(
+            &mut self,
+            ctx: &mut CallbackContext<Model, O>,
+            _grads: &mut GradientsParams,
+        ) {
+            let call = format!("optimizer_step {}", ctx.step);
+            self.0.lock().unwrap().push(call);
+        }
+
+        fn on_epoch_end(&mut self, ctx: &mut CallbackContext<Model, O>) {
+            let call = format!("epoch_end {}", ctx.step);
+            self.0.lock().unwrap().push(call);
+        }
+    }
+
+    #[test]
+    fn test_callback_context_counts_the_optimizer_steps() {
+        let directory = test_directory("callback-steps");
+        let inputs = (0..8).map(|x| x as f32 / 8.0).collect();
+        let (train, valid) = regression_dataloaders(inputs, 2);
+        let steps = Steps::default();
+
+        regression_learner(&directory)
+            .callback(steps.clone())
+            .grads_accumulation(2)
+            .num_epochs(2)
+            .build(regression_model(), SgdConfig::new().init(), 0.1)
+            .fit(train, valid);
+        std::fs::remove_dir_all(directory).ok();
+
+        assert_eq!(
+            *steps.0.lock().unwrap(),
+            [
+                "optimizer_step 0",
+                "optimizer_step 1",
+                "epoch_end 2",
+                "optimizer_step 2",
+                "optimizer_step 3",
+                "epoch_end 4",
+            ]
+        );
+    }
+}
@@ -2,6 +2,7 @@ use crate::metric::{
     Metric,
     store::{Aggregate, Direction, EventStoreClient, Split},
 };
+use serde::{Deserialize, Serialize};
 
 /// The condition that [early stopping strategies](EarlyStoppingStrategy) should follow.
 pub enum StoppingCondition {
@@ -16,6 +17,26 @@ pub enum StoppingCondition {
 pub trait EarlyStoppingStrategy {
     /// Update its current state and returns if the training should be stopped.
     fn should_stop(&mut self, epoch: usize, store: &EventStoreClient) -> bool;
+
+    /// The current state of the strategy, saved with the checkpoints so that it can be
+    /// [restored](EarlyStoppingStrategy::load_state) when the training resumes.
+    fn state(&self) -> Option<EarlyStoppingState> {
+        None
+    }
+
+    /// Restore the state of the strategy saved with a checkpoint.
+    fn load_state(&mut self, state: EarlyStoppingState) {
+        let _ = state;
+    }
+}
+
+/// The state of an [early stopping strategy](EarlyStoppingStrategy).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EarlyStoppingState {
+    /// The epoch with the best value so far.
+    pub best_epoch: usize,
+    /// The best value so far.
+    pub best_value: f64,
 }
 
 /// An [early stopping strategy](EarlyStoppingStrategy) based on a metrics collected
@@ -79,6 +100,18 @@ impl EarlyStoppingStrategy for MetricEarlyStoppingStrategy {
             }
         }
     }
+
+    fn state(&self) -> Option<EarlyStoppingState> {
+        Some(EarlyStoppingState {
+            best_epoch: self.best_epoch,
+            best_value: self.best_value,
+        })
+    }
+
+    fn load_state(&mut self, state: EarlyStoppingState) {
+        self.best_epoch = state.best_epoch;
+        self.best_value = state.best_value;
+    }
 }
 
 impl MetricEarlyStoppingStrategy {
@@ -185,6 +218,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn early_stop_after_restoring_state() {
+        let loss = LossMetric::<TestBackend>::new();
+        let new_strategy = || {
+            MetricEarlyStoppingStrategy::new(
+                &loss,
+                Aggregate::Mean,
+                Direction::Lowest,
+                Split::Train,
+                StoppingCondition::NoImprovementSince { n_epochs: 2 },
+            )
+        };
+        let mut store = LogEventStore::default();
+        let mut metrics = Metrics::<f64, f64>::default();
+        store.register_logger_train(InMemoryMetricLogger::default());
+        metrics.register_train_metric_numeric(LossMetric::<TestBackend>::new());
+        let store = Arc::new(EventStoreClient::new(store));
+        let mut processor = MinimalEventProcessor::new(metrics, store.clone());
+
+        let mut early_stopping = new_strategy();
+        for (epoch, point) in [(1, 0.5), (2, 0.3)] {
+            process_train(&mut processor, point, epoch);
+            end_epoch(&mut processor, epoch);
+            assert!(!early_stopping.should_stop(epoch, &store));
+        }
+
+        let mut resumed = new_strategy();
+        resumed.load_state(early_stopping.state().unwrap());
+        assert_eq!(resumed.state(), early_stopping.state());
+
+        process_train(&mut processor, 0.4, 3);
+        end_epoch(&mut processor, 3);
+        assert!(!resumed.should_stop(3, &store));
+        process_train(&mut processor, 0.4, 4);
+        end_epoch(&mut processor, 4);
+        assert!(resumed.should_stop(4, &store));
+    }
+
     fn test_early_stopping(n_epochs: usize, data: &[(&[f64], bool, &str)]) {
         let loss = LossMetric::<TestBackend>::new();
         let mut early_stopping = MetricEarlyStoppingStrategy::new(
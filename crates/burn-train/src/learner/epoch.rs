@@ -1,12 +1,22 @@
-use burn_core::data::dataloader::DataLoader;
+use burn_core::data::dataloader::{DataLoader, DataLoaderIterator, DataLoaderState, Progress};
+use burn_core::data::dataset::transform::LoadCounts;
+use burn_core::grad_clipping::GradientClipping;
+use burn_core::optim::GradientsParams;
 use burn_core::tensor::backend::AutodiffBackend;
 use burn_core::{
-    lr_scheduler::LrScheduler, module::AutodiffModule, optim::GradientsAccumulator,
+    LearningRate,
+    lr_scheduler::LrScheduler,
+    module::{AutodiffModule, Module},
+    optim::GradientsAccumulator,
     tensor::backend::Backend,
 };
 use std::sync::Arc;
 
+use super::swa::{SwaState, update_running_states};
+use crate::checkpoint::{CheckpointingAction, TrainingState};
+use crate::collective::{Collective, GatherParams, ReduceOp, Transport, all_reduce_devices};
 use crate::metric::processor::{Event, EventProcessor, LearnerItem};
+use crate::{LearnerCallbacks, LearnerCheckpointer};
 use crate::{MultiDevicesTrainStep, TrainStep, ValidStep};
 use crate::{components::LearnerComponents, learner::base::TrainingInterrupter};
 
@@ -16,6 +26,11 @@ pub struct ValidEpoch<B: Backend, VI> {
     dataloader: Arc<dyn DataLoader<B, VI>>,
     epoch: usize,
     epoch_total: usize,
+    #[new(default)]
+    max_batches: Option<usize>,
+    // The number of optimizer steps done since the start of the training.
+    #[new(default)]
+    step: usize,
 }
 
 /// A training epoch.
@@ -25,6 +40,77 @@ pub struct TrainEpoch<B: AutodiffBackend, TI> {
     epoch: usize,
     epoch_total: usize,
     grad_accumulation: Option<usize>,
+    grad_clipping: Option<GradientClipping>,
+    #[new(default)]
+    swa: Option<SwaState<B>>,
+    #[new(default)]
+    load_counts: Option<LoadCounts>,
+    #[new(default)]
+    collective: Option<Collective<Box<dyn Transport>>>,
+    #[new(default)]
+    reduce_gradients: bool,
+    #[new(default)]
+    checkpoint_interval: Option<usize>,
+    #[new(default)]
+    seed: Option<u64>,
+    #[new(default)]
+    resume: Option<ResumeState>,
+    #[new(default)]
+    steps_per_epoch: Option<usize>,
+    #[new(default)]
+    max_steps: Option<usize>,
+    // The number of optimizer steps done since the start of the training.
+    #[new(default)]
+    step: usize,
+    // The position of the iteration over the training data loader.
+    #[new(default)]
+    data: DataLoaderState,
+    // The number of steps since the stop decision was last shared between the processes.
+    #[new(default)]
+    steps_since_stop_sync: usize,
+}
+
+/// Gathers the parameters released by a sharded optimizer after its last step.
+fn gather_params<M, O>(sharded: Option<GatherParams<O, M>>, model: M, optim: &mut O) -> M {
+    match sharded {
+        Some(gather) => {
+            gather(optim, &model).expect("The parameters should be gathered from the processes")
+        }
+        None => model,
+    }
+}
+
+/// The number of steps between the synchronizations of the stop decision of a distributed
+/// training during an epoch, so that the processes don't wait for each other at every step.
+const STOP_SYNC_INTERVAL: usize = 16;
+
+/// Where the first training epoch resumes from a checkpoint.
+pub(crate) struct ResumeState {
+    iteration: usize,
+    accumulation: usize,
+    grads: GradientsParams,
+    step: usize,
+    data: DataLoaderState,
+}
+
+impl ResumeState {
+    /// Resume from the given checkpoint state and its accumulated gradients.
+    pub(crate) fn new(state: &TrainingState, grads: GradientsParams, num_devices: usize) -> Self {
+        // Without the position of the data loader, the checkpoint was saved while the epochs
+        // were iterating over the whole data loader.
+        let data = state.dataloader.unwrap_or(match state.iteration {
+            Some(iteration) => DataLoaderState::new(state.epoch - 1, iteration / num_devices),
+            None => DataLoaderState::new(state.epoch, 0),
+        });
+
+        Self {
+            iteration: state.iteration.unwrap_or(0),
+            accumulation: state.accumulation,
+            grads,
+            step: state.step,
+            data,
+        }
+    }
 }
 
 impl<B: Backend, VI> ValidEpoch<B, VI> {
@@ -50,12 +136,21 @@ impl<B: Backend, VI> ValidEpoch<B, VI> {
         let mut iterator = self.dataloader.iter();
         let mut iteration = 0;
 
-        while let Some(item) = iterator.next() {
-            let progress = iterator.progress();
+        loop {
+            if self
+                .max_batches
+                .is_some_and(|max_batches| iteration >= max_batches)
+            {
+                break;
+            }
+            let Some(item) = iterator.next() else {
+                break;
+            };
             iteration += 1;
+            let progress = self.progress(iteration, iterator.progress());
 
             let item = model.step(item);
-            let item = LearnerItem::new(
+            let mut item = LearnerItem::new(
                 item,
                 progress,
                 self.epoch,
@@ -63,6 +158,7 @@ impl<B: Backend, VI> ValidEpoch<B, VI> {
                 iteration,
                 None,
             );
+            item.step = self.step;
 
             processor.process_valid(Event::ProcessedItem(item));
 
@@ -73,6 +169,31 @@ impl<B: Backend, VI> ValidEpoch<B, VI> {
         }
         processor.process_valid(Event::EndEpoch(self.epoch));
     }
+
+    /// The progress of the validation, whose total is capped by the maximum number of batches.
+    fn progress(&self, iteration: usize, progress: Progress) -> Progress {
+        match self.max_batches {
+            Some(max_batches) => {
+                // The batches are assumed to have the same size as the ones already loaded.
+                let batch_size = progress.items_processed.div_ceil(iteration);
+                let items_total = progress.items_total.min(batch_size * max_batches);
+                Progress::new(progress.items_processed, items_total)
+            }
+            None => progress,
+        }
+    }
+
+    /// Only validate on the first `max_batches` batches of the data loader.
+    pub(crate) fn with_max_batches(mut self, max_batches: Option<usize>) -> Self {
+        self.max_batches = max_batches;
+        self
+    }
+
+    /// Log the items at the given number of optimizer steps of the training.
+    pub(crate) fn with_step(mut self, step: usize) -> Self {
+        self.step = step;
+        self
+    }
 }
 
 impl<B: AutodiffBackend, TI> TrainEpoch<B, TI> {
@@ -84,10 +205,14 @@ impl<B: AutodiffBackend, TI> TrainEpoch<B, TI> {
     /// * `optim` - The optimizer to use.
     /// * `scheduler` - The learning rate scheduler to use.
     /// * `processor` - The event processor to use.
+    /// * `checkpointer` - The checkpointer saving the checkpoints during the epoch.
+    /// * `callbacks` - The callbacks to call during the epoch.
+    /// * `sharded` - Gathers the parameters released by a sharded optimizer.
     ///
     /// # Returns
     ///
     /// The trained model and the optimizer.
+    #[allow(clippy::too_many_arguments)]
     pub fn run<LC: LearnerComponents<Backend = B>, TO>(
         &mut self,
         mut model: LC::Model,
@@ -95,6 +220,9 @@ impl<B: AutodiffBackend, TI> TrainEpoch<B, TI> {
         scheduler: &mut LC::LrScheduler,
         processor: &mut LC::EventProcessor,
         interrupter: &TrainingInterrupter,
+        mut checkpointer: Option<&mut LearnerCheckpointer<LC>>,
+        callbacks: &mut LearnerCallbacks<B, LC::Model, LC::Optimizer>,
+        sharded: Option<GatherParams<LC::Optimizer, LC::Model>>,
     ) -> (LC::Model, LC::Optimizer)
     where
         LC::EventProcessor: EventProcessor<ItemTrain = TO>,
@@ -103,51 +231,146 @@ impl<B: AutodiffBackend, TI> TrainEpoch<B, TI> {
         log::info!("Executing training step for epoch {}", self.epoch,);
 
         // Single device / dataloader
-        let mut iterator = self.dataloader[0].iter();
+        let dataloader = self.dataloader[0].clone();
+        let mut iterator = self.iterator(&dataloader);
         let mut iteration = 0;
         let mut accumulator = GradientsAccumulator::new();
         let mut accumulation_current = 0;
 
-        while let Some(item) = iterator.next() {
+        if let Some(resume) = self.resume.take() {
+            iteration = resume.iteration;
+            accumulation_current = resume.accumulation;
+            accumulator.accumulate(&model, resume.grads);
+        }
+
+        loop {
+            if self.is_epoch_done(iteration) {
+                break;
+            }
+            self.seed_iteration(iteration);
+            let item = match iterator.next() {
+                Some(item) => item,
+                // The epochs last a number of steps, a new iteration over the data starts.
+                None if self.steps_per_epoch.is_some() => {
+                    self.data = DataLoaderState::new(self.data.epoch + 1, 0);
+                    iterator = dataloader.iter_from(self.data);
+                    match iterator.next() {
+                        Some(item) => item,
+                        None => break,
+                    }
+                }
+                None => break,
+            };
+            self.data.batches += 1;
+            callbacks.call(
+                &mut model,
+                &mut optim,
+                self.epoch,
+                iteration,
+                |callback, ctx| callback.on_batch_start(ctx),
+            );
             iteration += 1;
-            let lr = scheduler.step();
             log::info!("Iteration {}", iteration);
 
-            let progress = iterator.progress();
+            let progress = self.progress(iteration, iterator.progress());
+            model = gather_params(sharded, model, &mut optim);
             let item = model.step(item);
+            // Only the iterations stepping the optimizer have a learning rate and a gradient norm.
+            let mut lr = None;
+            let mut grad_norm = None;
+            // The step applying the gradients of the item.
+            let step = self.step + 1;
 
-            match self.grad_accumulation {
+            let grads = match self.grad_accumulation {
                 Some(accumulation) => {
                     accumulator.accumulate(&model, item.grads);
                     accumulation_current += 1;
 
-                    if accumulation <= accumulation_current {
-                        let grads = accumulator.grads();
-                        model = model.optimize(&mut optim, lr, grads);
-                        accumulation_current = 0;
+                    match accumulation <= accumulation_current {
+                        true => {
+                            accumulation_current = 0;
+                            Some(accumulator.grads())
+                        }
+                        false => None,
                     }
                 }
-                None => model = model.optimize(&mut optim, lr, item.grads),
+                None => Some(item.grads),
+            };
+            if let Some(grads) = grads {
+                let grads = self.reduce_gradients(&model, grads);
+                let mut grads = self.clip_gradients(&model, grads, &mut grad_norm);
+                callbacks.call(
+                    &mut model,
+                    &mut optim,
+                    self.epoch,
+                    iteration,
+                    |callback, ctx| callback.on_before_optimizer_step(ctx, &mut grads),
+                );
+                let step_lr = self.next_lr(scheduler);
+                model = model.optimize(&mut optim, step_lr, grads);
+                lr = Some(step_lr);
+                self.step += 1;
+                callbacks.set_step(self.step);
             }
 
-            let item = LearnerItem::new(
+            let mut item = LearnerItem::new(
                 item.item,
                 progress,
                 self.epoch,
                 self.epoch_total,
                 iteration,
-                Some(lr),
+                lr,
             );
+            item.grad_norm = grad_norm;
+            item.step = step;
 
             processor.process_train(Event::ProcessedItem(item));
+            callbacks.call(
+                &mut model,
+                &mut optim,
+                self.epoch,
+                iteration,
+                |callback, ctx| callback.on_batch_end(ctx),
+            );
 
-            if interrupter.should_stop() {
+            let stop = self.sync_stop_step(interrupter.should_stop() || callbacks.should_stop());
+            if let Some(checkpointer) = checkpointer
+                .as_deref_mut()
+                .filter(|_| self.should_checkpoint(iteration, 1, stop))
+            {
+                model = gather_params(sharded, model, &mut optim);
+                let state = checkpointer.checkpoint_partial(
+                    &model,
+                    &optim,
+                    scheduler,
+                    self.swa_averaged(&model).as_ref(),
+                    accumulator.peek(),
+                    self.training_state(Some(iteration), accumulation_current),
+                );
+                callbacks.call(
+                    &mut model,
+                    &mut optim,
+                    self.epoch,
+                    iteration,
+                    |callback, ctx| callback.on_checkpoint(ctx, &state),
+                );
+            }
+
+            if stop {
                 log::info!("Training interrupted.");
                 break;
             }
         }
         processor.process_train(Event::EndEpoch(self.epoch));
+        // The model is complete for the validation and the checkpoints at the end of the epoch.
+        let model = gather_params(sharded, model, &mut optim);
 
+        if let Some(swa) = &mut self.swa {
+            swa.update(self.epoch, &model);
+        }
+        if self.steps_per_epoch.is_none() {
+            self.data = DataLoaderState::new(self.epoch, 0);
+        }
         self.epoch += 1;
 
         (model, optim)
@@ -164,10 +387,14 @@ impl<B: AutodiffBackend, TI> TrainEpoch<B, TI> {
     /// * `lr_scheduler` - The learning rate scheduler to use.
     /// * `processor` - The event processor to use.
     /// * `devices` - The devices to use.
+    /// * `checkpointer` - The checkpointer saving the checkpoints during the epoch.
+    /// * `callbacks` - The callbacks to call during the epoch.
+    /// * `sharded` - Gathers the parameters released by a sharded optimizer.
     ///
     /// # Returns
     ///
     /// The trained model and the optimizer.
+    #[allow(clippy::too_many_arguments)]
     pub fn run_multi_device<LC: LearnerComponents<Backend = B>, TO>(
         &mut self,
         mut model: LC::Model,
@@ -176,6 +403,9 @@ impl<B: AutodiffBackend, TI> TrainEpoch<B, TI> {
         processor: &mut LC::EventProcessor,
         devices: Vec<<LC::Backend as Backend>::Device>,
         interrupter: &TrainingInterrupter,
+        mut checkpointer: Option<&mut LearnerCheckpointer<LC>>,
+        callbacks: &mut LearnerCallbacks<B, LC::Model, LC::Optimizer>,
+        sharded: Option<GatherParams<LC::Optimizer, LC::Model>>,
     ) -> (LC::Model, LC::Optimizer)
     where
         LC::EventProcessor: EventProcessor<ItemTrain = TO>,
@@ -189,12 +419,22 @@ impl<B: AutodiffBackend, TI> TrainEpoch<B, TI> {
             devices
         );
 
-        let mut iterators = self.dataloader.iter().map(|d| d.iter()).collect::<Vec<_>>();
+        let dataloaders = self.dataloader.clone();
+        let mut iterators = dataloaders
+            .iter()
+            .map(|d| self.iterator(d))
+            .collect::<Vec<_>>();
         let mut iteration = 0;
         let mut accumulator = GradientsAccumulator::new();
         let mut accumulation_current = 0;
 
-        let accumulation = self.grad_accumulation.unwrap_or(1) * devices.len();
+        if let Some(resume) = self.resume.take() {
+            iteration = resume.iteration;
+            accumulation_current = resume.accumulation;
+            accumulator.accumulate(&model, resume.grads);
+        }
+
+        let accumulation = self.grad_accumulation.unwrap_or(1);
         let step = MultiDevicesTrainStep::new(&devices);
 
         // The main device is always the first in the list.
@@ -202,39 +442,125 @@ impl<B: AutodiffBackend, TI> TrainEpoch<B, TI> {
         let mut interrupted = false;
 
         loop {
-            let (items, progress) = step.step(iterators.as_mut_slice(), &model);
-            if items.is_empty() {
+            if self.is_epoch_done(iteration) {
                 break;
             }
+            self.seed_iteration(iteration);
+            let (mut items, mut progress) = step.next_items(iterators.as_mut_slice());
+            // The epochs last a number of steps, a new iteration over the data starts.
+            if items.iter().all(Option::is_none) && self.steps_per_epoch.is_some() {
+                self.data = DataLoaderState::new(self.data.epoch + 1, 0);
+                iterators = dataloaders.iter().map(|d| d.iter_from(self.data)).collect();
+                (items, progress) = step.next_items(iterators.as_mut_slice());
+            }
+            if items.iter().all(Option::is_none) {
+                break;
+            }
+            self.data.batches += 1;
+            callbacks.call(
+                &mut model,
+                &mut optim,
+                self.epoch,
+                iteration,
+                |callback, ctx| callback.on_batch_start(ctx),
+            );
+            model = gather_params(sharded, model, &mut optim);
+            let items = step.train(items, &model);
 
-            for item in items {
-                iteration += 1;
-                let lr = lr_scheduler.step();
+            let num_items = items.len();
+            let (grads, items): (Vec<_>, Vec<_>) = items
+                .into_iter()
+                .map(|output| (output.grads, output.item))
+                .unzip();
+
+            // The gradients are summed between the devices with a ring all-reduce, the ones of
+            // the first device, usually the main one, are accumulated.
+            let grads = all_reduce_devices(&model, grads, ReduceOp::Sum)
+                .expect("Every device should have the gradients of the same parameters")
+                .swap_remove(0);
+            accumulator.accumulate(&model, grads.to_device(&device_main, &model));
 
-                // TODO: aggregate multi device (all-reduce)
-                let grads = item.grads.to_device(&device_main, &model);
+            // The step applying the gradients of the items.
+            let step = self.step + 1;
+            for (index, item) in items.into_iter().enumerate() {
+                // Only the iterations stepping the optimizer have a learning rate and a gradient
+                // norm.
+                let mut lr = None;
+                let mut grad_norm = None;
+                iteration += 1;
 
-                accumulator.accumulate(&model, grads);
-                accumulation_current += 1;
+                if index + 1 == num_items {
+                    accumulation_current += 1;
 
-                if accumulation <= accumulation_current {
-                    let grads = accumulator.grads();
-                    model = model.optimize(&mut optim, lr, grads);
-                    accumulation_current = 0;
+                    if accumulation <= accumulation_current {
+                        let grads = self.reduce_gradients(&model, accumulator.grads());
+                        let mut grads = self.clip_gradients(&model, grads, &mut grad_norm);
+                        callbacks.call(
+                            &mut model,
+                            &mut optim,
+                            self.epoch,
+                            iteration,
+                            |callback, ctx| callback.on_before_optimizer_step(ctx, &mut grads),
+                        );
+                        let step_lr = self.next_lr(lr_scheduler);
+                        model = model.optimize(&mut optim, step_lr, grads);
+                        lr = Some(step_lr);
+                        accumulation_current = 0;
+                        self.step += 1;
+                        callbacks.set_step(self.step);
+                    }
                 }
 
-                let item = LearnerItem::new(
-                    item.item,
-                    progress.clone(),
+                let mut item = LearnerItem::new(
+                    item,
+                    self.progress(iteration, progress.clone()),
                     self.epoch,
                     self.epoch_total,
                     iteration,
-                    Some(lr),
+                    lr,
                 );
+                item.grad_norm = grad_norm;
+                item.step = step;
 
                 processor.process_train(Event::ProcessedItem(item));
 
-                if interrupter.should_stop() {
+                if index + 1 == num_items {
+                    callbacks.call(
+                        &mut model,
+                        &mut optim,
+                        self.epoch,
+                        iteration,
+                        |callback, ctx| callback.on_batch_end(ctx),
+                    );
+                }
+
+                // The checkpoints are only saved and the training only stops once the items of
+                // all devices are processed, so the training resumes from the next items of
+                // every device.
+                let stop = index + 1 == num_items
+                    && self.sync_stop_step(interrupter.should_stop() || callbacks.should_stop());
+                if let Some(checkpointer) = checkpointer.as_deref_mut().filter(|_| {
+                    index + 1 == num_items && self.should_checkpoint(iteration, num_items, stop)
+                }) {
+                    model = gather_params(sharded, model, &mut optim);
+                    let state = checkpointer.checkpoint_partial(
+                        &model,
+                        &optim,
+                        lr_scheduler,
+                        self.swa_averaged(&model).as_ref(),
+                        accumulator.peek(),
+                        self.training_state(Some(iteration), accumulation_current),
+                    );
+                    callbacks.call(
+                        &mut model,
+                        &mut optim,
+                        self.epoch,
+                        iteration,
+                        |callback, ctx| callback.on_checkpoint(ctx, &state),
+                    );
+                }
+
+                if stop {
                     log::info!("Training interrupted.");
                     interrupted = true;
                     break;
@@ -247,9 +573,568 @@ impl<B: AutodiffBackend, TI> TrainEpoch<B, TI> {
         }
 
         processor.process_train(Event::EndEpoch(self.epoch));
+        // The model is complete for the validation and the checkpoints at the end of the epoch.
+        let model = gather_params(sharded, model, &mut optim);
 
+        if let Some(swa) = &mut self.swa {
+            swa.update(self.epoch, &model);
+        }
+        if self.steps_per_epoch.is_none() {
+            self.data = DataLoaderState::new(self.epoch, 0);
+        }
         self.epoch += 1;
 
         (model, optim)
     }
 }
+
+impl<B: AutodiffBackend, TI> TrainEpoch<B, TI> {
+    /// Save the load counts of the training dataset with the checkpoints.
+    pub(crate) fn with_load_counts(mut self, load_counts: Option<LoadCounts>) -> Self {
+        self.load_counts = load_counts;
+        self
+    }
+
+    /// Enable the stochastic weight averaging of the model.
+    pub(crate) fn with_swa(mut self, swa: Option<SwaState<B>>) -> Self {
+        self.swa = swa;
+        self
+    }
+
+    /// Save a checkpoint every `checkpoint_interval` iterations and when the training is
+    /// interrupted, seeding the backend before every iteration when a seed is provided.
+    pub(crate) fn with_checkpointing(
+        mut self,
+        checkpoint_interval: Option<usize>,
+        seed: Option<u64>,
+    ) -> Self {
+        self.checkpoint_interval = checkpoint_interval;
+        self.seed = seed;
+        self
+    }
+
+    /// Resume the first epoch from a checkpoint.
+    pub(crate) fn with_resume(mut self, resume: Option<ResumeState>) -> Self {
+        if let Some(resume) = &resume {
+            self.step = resume.step;
+            self.data = resume.data;
+        }
+        self.resume = resume;
+        self
+    }
+
+    /// Make the epochs last a number of training steps, continuing the iteration over the data
+    /// loader of the previous epoch, and stop the training after a number of optimizer steps.
+    pub(crate) fn with_steps(
+        mut self,
+        steps_per_epoch: Option<usize>,
+        max_steps: Option<usize>,
+    ) -> Self {
+        self.steps_per_epoch = steps_per_epoch;
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// The number of optimizer steps done since the start of the training.
+    pub(crate) fn step(&self) -> usize {
+        self.step
+    }
+
+    /// Whether the maximum number of optimizer steps is reached.
+    pub(crate) fn is_done(&self) -> bool {
+        self.max_steps
+            .is_some_and(|max_steps| self.step >= max_steps)
+    }
+
+    /// The state of the training loop to save with a checkpoint, during the current epoch if an
+    /// iteration is provided or at the end of the previous epoch otherwise.
+    pub(crate) fn training_state(
+        &self,
+        iteration: Option<usize>,
+        accumulation: usize,
+    ) -> TrainingState {
+        let epoch = match iteration {
+            Some(_) => self.epoch,
+            None => self.epoch - 1,
+        };
+
+        TrainingState {
+            epoch,
+            iteration,
+            accumulation,
+            step: self.step,
+            dataloader: Some(self.data),
+            swa: self.swa.as_ref().map(SwaState::state),
+            load_counts: self.load_counts.as_ref().map(LoadCounts::to_vec),
+            ..Default::default()
+        }
+    }
+
+    /// The model with the parameters averaged by the SWA, to save in a checkpoint.
+    pub(crate) fn swa_averaged<M: Module<B>>(&self, model: &M) -> Option<M> {
+        self.swa.as_ref().and_then(|swa| swa.averaged(model))
+    }
+
+    /// Synchronize the training with the other processes of a distributed training, reducing
+    /// the gradients unless the optimizer does it.
+    pub(crate) fn with_collective(
+        mut self,
+        collective: Option<Collective<Box<dyn Transport>>>,
+        reduce_gradients: bool,
+    ) -> Self {
+        self.collective = collective;
+        self.reduce_gradients = reduce_gradients;
+        self
+    }
+
+    /// Whether the current process is the main one, which is always the case when the training
+    /// isn't distributed.
+    pub(crate) fn is_main_rank(&self) -> bool {
+        self.collective
+            .as_ref()
+            .is_none_or(|collective| collective.rank() == 0)
+    }
+
+    /// Whether the current process saves checkpoints, which is the case of the main process, and
+    /// of every process when the optimizer state is sharded between them.
+    pub(crate) fn saves_checkpoints(&self) -> bool {
+        self.is_main_rank() || !self.reduce_gradients
+    }
+
+    /// Share the checkpointing actions of the main process with the other processes saving
+    /// checkpoints, so that they all keep the same checkpoints.
+    pub(crate) fn sync_checkpointing(
+        &mut self,
+        actions: Vec<CheckpointingAction>,
+    ) -> Vec<CheckpointingAction> {
+        let collective = match &mut self.collective {
+            Some(collective) if !self.reduce_gradients => collective,
+            _ => return actions,
+        };
+
+        // The actions are sent as the epoch to delete, or -1 to save.
+        let mut len = [actions.len() as f32];
+        collective
+            .broadcast(&mut len, 0)
+            .expect("The checkpointing actions should be shared between the processes");
+        let mut values = actions
+            .iter()
+            .map(|action| match action {
+                CheckpointingAction::Save => -1.0,
+                CheckpointingAction::Delete(epoch) => *epoch as f32,
+            })
+            .collect::<Vec<_>>();
+        values.resize(len[0] as usize, 0.0);
+        collective
+            .broadcast(&mut values, 0)
+            .expect("The checkpointing actions should be shared between the processes");
+
+        values
+            .into_iter()
+            .map(|value| match value < 0.0 {
+                true => CheckpointingAction::Save,
+                false => CheckpointingAction::Delete(value as usize),
+            })
+            .collect()
+    }
+
+    /// Copy the model of the main process to all the processes of a distributed training.
+    pub(crate) fn broadcast_model<M: Module<B>>(&mut self, model: M) -> M {
+        match &mut self.collective {
+            Some(collective) => collective
+                .broadcast_module(model, 0)
+                .expect("The model should be broadcast from the main process"),
+            None => model,
+        }
+    }
+
+    /// Whether the training should stop, which is the case for all the processes of a
+    /// distributed training as soon as one of them should stop.
+    pub(crate) fn sync_stop(&mut self, stop: bool) -> bool {
+        match &mut self.collective {
+            Some(collective) => {
+                let mut stops = [if stop { 1.0 } else { 0.0 }];
+                collective
+                    .all_reduce(&mut stops, ReduceOp::Sum)
+                    .expect("The stop decision should be shared between the processes");
+                stops[0] > 0.0
+            }
+            None => stop,
+        }
+    }
+
+    /// Whether the training should stop after the current step.
+    ///
+    /// In a distributed training, the stop decision is only shared every few steps, the
+    /// processes then stopping at the same step. It is also shared at the end of each epoch
+    /// with [sync_stop](Self::sync_stop).
+    fn sync_stop_step(&mut self, stop: bool) -> bool {
+        if self.collective.is_none() {
+            return stop;
+        }
+
+        self.steps_since_stop_sync += 1;
+        if self.steps_since_stop_sync < STOP_SYNC_INTERVAL {
+            return false;
+        }
+
+        self.steps_since_stop_sync = 0;
+        self.sync_stop(stop)
+    }
+
+    /// The iterator over the items of the epoch, which continues the iteration of the previous
+    /// epoch when the epochs last a number of steps, and skips the items already processed when
+    /// the epoch is resumed from a checkpoint.
+    fn iterator<'a>(
+        &self,
+        dataloader: &'a Arc<dyn DataLoader<B, TI>>,
+    ) -> Box<dyn DataLoaderIterator<TI> + 'a> {
+        match self.resume.is_some() || self.steps_per_epoch.is_some() {
+            true => dataloader.iter_from(self.data),
+            false => dataloader.iter(),
+        }
+    }
+
+    /// The learning rate of the next optimizer step, the scheduler being stepped once per
+    /// optimizer step.
+    fn next_lr<S: LrScheduler>(&mut self, scheduler: &mut S) -> LearningRate {
+        let lr = scheduler.step();
+
+        match &mut self.swa {
+            Some(swa) => swa.lr(self.epoch, lr),
+            None => lr,
+        }
+    }
+
+    /// Whether the epoch ends after the given number of iterations.
+    fn is_epoch_done(&self, iteration: usize) -> bool {
+        self.steps_per_epoch
+            .is_some_and(|steps_per_epoch| iteration >= steps_per_epoch)
+            || self.is_done()
+    }
+
+    /// The progress of the epoch, in steps when the epochs last a number of steps.
+    fn progress(&self, iteration: usize, progress: Progress) -> Progress {
+        match self.steps_per_epoch {
+            Some(steps_per_epoch) => Progress::new(iteration, steps_per_epoch),
+            None => progress,
+        }
+    }
+
+    /// Whether a checkpoint should be saved after the last `num_iterations` iterations, which is
+    /// the case on the processes saving checkpoints when the training is interrupted or when a
+    /// multiple of the checkpoint interval is reached.
+    fn should_checkpoint(&self, iteration: usize, num_iterations: usize, stop: bool) -> bool {
+        match self.checkpoint_interval {
+            Some(interval) if self.saves_checkpoints() => {
+                stop || (interval > 0
+                    && iteration / interval > (iteration - num_iterations) / interval)
+            }
+            _ => false,
+        }
+    }
+
+    /// Seed the backend before the given iteration of the epoch.
+    fn seed_iteration(&self, iteration: usize) {
+        if let Some(seed) = self.seed {
+            B::seed(iteration_seed(seed, self.epoch, iteration));
+        }
+    }
+
+    /// Average the gradients of all the processes of a distributed training.
+    fn reduce_gradients<M: AutodiffModule<B>>(
+        &mut self,
+        model: &M,
+        grads: GradientsParams,
+    ) -> GradientsParams {
+        match &mut self.collective {
+            Some(collective) if self.reduce_gradients => collective
+                .all_reduce_gradients(model, grads, ReduceOp::Mean)
+                .expect("Gradients should be all-reduced between the processes"),
+            _ => grads,
+        }
+    }
+
+    /// Loads the averaged parameters into the model when stochastic weight averaging is enabled,
+    /// recomputing the batch norm statistics with a pass over the training data if required.
+    pub(crate) fn swa_model<M, TO>(&self, model: M) -> M
+    where
+        M: AutodiffModule<B> + TrainStep<TI, TO>,
+    {
+        let Some(swa) = &self.swa else {
+            return model;
+        };
+        if swa.count() == 0 {
+            log::warn!("No model was averaged, the SWA start epoch was never reached.");
+            return model;
+        }
+
+        let model = swa.apply(model);
+
+        if swa.update_batch_norm() {
+            log::info!("Updating the batch norm statistics of the averaged model");
+            update_running_states(&model, self.dataloader[0].iter());
+        }
+
+        model
+    }
+
+    /// Clip the gradients when enabled, keeping track of their norm before clipping.
+    fn clip_gradients<M: AutodiffModule<B>>(
+        &self,
+        model: &M,
+        grads: GradientsParams,
+        grad_norm: &mut Option<f64>,
+    ) -> GradientsParams {
+        match &self.grad_clipping {
+            Some(clipping @ GradientClipping::GlobalNorm(_)) => {
+                let (grads, norm) = clipping.clip_gradients_params(model, grads);
+                *grad_norm = norm.map(|norm| norm as f64);
+                grads
+            }
+            Some(clipping) => {
+                // The other clippings don't compute the norm, which is tracked anyway.
+                *grad_norm = Some(GradientClipping::global_norm(model, &grads) as f64);
+                clipping.clip_gradients_params(model, grads).0
+            }
+            None => grads,
+        }
+    }
+}
+
+/// Derive the seed of an iteration from the seed of the training, mixing the bits so close
+/// iterations get unrelated seeds.
+fn iteration_seed(seed: u64, epoch: usize, iteration: usize) -> u64 {
+    // SplitMix64 finalizer.
+    let mut x = seed ^ ((epoch as u64) << 32) ^ iteration as u64;
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::LossMetric;
+    use crate::metric::store::{Aggregate, EventStoreClient, Split};
+    use crate::tests::{
+        RegressionBatcher, regression_dataloaders, regression_learner, regression_model,
+        test_directory,
+    };
+    use crate::{CallbackContext, LearnerCallback};
+    use burn_core::data::dataloader::DataLoaderBuilder;
+    use burn_core::data::dataset::Dataset;
+    use burn_core::nn::Linear;
+    use burn_core::optim::SgdConfig;
+    use burn_core::record::{FullPrecisionSettings, NamedMpkFileRecorder};
+    use std::sync::Mutex;
+
+    /// Records the number of iterations of every epoch and the optimizer steps.
+    #[derive(Clone, Default)]
+    struct Steps {
+        epochs: Arc<Mutex<Vec<usize>>>,
+        optimizer_steps: Arc<Mutex<usize>>,
+    }
+
+    impl<B: AutodiffBackend, O> LearnerCallback<B, Linear<B>, O> for Steps {
+        fn on_epoch_end(&mut self, ctx: &mut CallbackContext<Linear<B>, O>) {
+            self.epochs.lock().unwrap().push(ctx.epoch);
+        }
+
+        fn on_before_optimizer_step(
+            &mut self,
+            _ctx: &mut CallbackContext<Linear<B>, O>,
+            _grads: &mut GradientsParams,
+        ) {
+            *self.optimizer_steps.lock().unwrap() += 1;
+        }
+    }
+
+    #[test]
+    fn test_max_steps_counts_the_optimizer_steps() {
+        let directory = test_directory("max-steps");
+        let steps = Steps::default();
+        let inputs = (0..8).map(|x| x as f32 / 8.0).collect();
+        let (train, valid) = regression_dataloaders(inputs, 2);
+
+        // 2 optimizer steps per epoch of 4 iterations, so the last step is in the second epoch.
+        regression_learner(&directory)
+            .grads_accumulation(2)
+            .steps_per_epoch(4)
+            .max_steps(3)
+            .callback(steps.clone())
+            .build(regression_model(), SgdConfig::new().init(), 0.1)
+            .fit(train, valid);
+
+        assert_eq!(*steps.optimizer_steps.lock().unwrap(), 3);
+        assert_eq!(*steps.epochs.lock().unwrap(), [1, 2]);
+        std::fs::remove_dir_all(directory).ok();
+    }
+
+    /// A constant learning rate counting its steps.
+    #[derive(Clone, Default)]
+    struct CountedLr {
+        steps: Arc<Mutex<usize>>,
+    }
+
+    impl LrScheduler for CountedLr {
+        type Record<B: Backend> = ();
+
+        fn step(&mut self) -> LearningRate {
+            *self.steps.lock().unwrap() += 1;
+            0.1
+        }
+
+        fn to_record<B: Backend>(&self) -> Self::Record<B> {}
+
+        fn load_record<B: Backend>(self, _record: Self::Record<B>) -> Self {
+            self
+        }
+    }
+
+    #[test]
+    fn test_lr_scheduler_is_stepped_with_the_optimizer() {
+        let directory = test_directory("lr-scheduler-steps");
+        let scheduler = CountedLr::default();
+        let inputs = (0..8).map(|x| x as f32 / 8.0).collect();
+        let (train, valid) = regression_dataloaders(inputs, 2);
+
+        // 4 iterations accumulating the gradients of 2 of them.
+        regression_learner(&directory)
+            .grads_accumulation(2)
+            .build(
+                regression_model(),
+                SgdConfig::new().init(),
+                scheduler.clone(),
+            )
+            .fit(train, valid);
+        std::fs::remove_dir_all(directory).ok();
+
+        assert_eq!(*scheduler.steps.lock().unwrap(), 2);
+    }
+
+    /// Records whether the validation of every epoch has a loss.
+    #[derive(Clone, Default)]
+    struct ValidationLoss(Arc<Mutex<Vec<bool>>>);
+
+    impl<B: AutodiffBackend, O> LearnerCallback<B, Linear<B>, O> for ValidationLoss {
+        fn on_validation_end(
+            &mut self,
+            ctx: &mut CallbackContext<Linear<B>, O>,
+            store: &EventStoreClient,
+        ) {
+            let loss = store.find_metric("Loss", ctx.epoch, Aggregate::Mean, Split::Valid);
+            self.0.lock().unwrap().push(loss.is_some());
+        }
+    }
+
+    #[test]
+    fn test_max_validation_batches_of_zero_skips_the_validation() {
+        let directory = test_directory("zero-validation-batches");
+        let validated = ValidationLoss::default();
+        let (train, valid) = regression_dataloaders(vec![0.0, 0.5, 1.0], 2);
+
+        regression_learner(&directory)
+            .metric_valid_numeric(LossMetric::new())
+            .max_validation_batches(0)
+            .callback(validated.clone())
+            .build(regression_model(), SgdConfig::new().init(), 0.1)
+            .fit(train, valid);
+        std::fs::remove_dir_all(directory).ok();
+
+        assert_eq!(*validated.0.lock().unwrap(), [false]);
+    }
+
+    #[test]
+    fn test_validation_progress_is_capped_by_the_data_loader() {
+        let (_, valid) = regression_dataloaders(vec![0.0; 8], 2);
+        let epoch =
+            |max_batches| ValidEpoch::new(valid.clone(), 1, 1).with_max_batches(max_batches);
+
+        // 2 batches of 2 items are loaded.
+        let progress = Progress::new(4, 8);
+        assert_eq!(epoch(Some(3)).progress(2, progress.clone()).items_total, 6);
+        assert_eq!(epoch(Some(10)).progress(2, progress.clone()).items_total, 8);
+        assert_eq!(epoch(None).progress(2, progress).items_total, 8);
+    }
+
+    /// A dataset counting the loads of its items.
+    struct Counted {
+        items: Vec<f32>,
+        counts: LoadCounts,
+    }
+
+    impl Dataset<f32> for Counted {
+        fn get(&self, index: usize) -> Option<f32> {
+            self.counts.increment(index);
+            self.items.get(index).copied()
+        }
+
+        fn len(&self) -> usize {
+            self.items.len()
+        }
+    }
+
+    #[test]
+    fn test_load_counts_are_restored_on_resume() {
+        let directory = test_directory("load-counts");
+        let fit = |counts: LoadCounts, checkpoint: Option<usize>| {
+            let items = (0..8).map(|x| x as f32 / 8.0).collect();
+            let dataset = Counted {
+                items,
+                counts: counts.clone(),
+            };
+            let train = DataLoaderBuilder::new(RegressionBatcher)
+                .batch_size(2)
+                .build(dataset);
+            let (_, valid) = regression_dataloaders(vec![0.0, 1.0], 2);
+            let mut builder = regression_learner(&directory)
+                .with_file_checkpointer(NamedMpkFileRecorder::<FullPrecisionSettings>::new())
+                .load_counts(counts)
+                .num_epochs(2);
+            if let Some(checkpoint) = checkpoint {
+                builder = builder.checkpoint(checkpoint);
+            }
+            builder
+                .build(regression_model(), SgdConfig::new().init(), 0.1)
+                .fit(train, valid);
+        };
+
+        let counts = LoadCounts::new(8);
+        fit(counts.clone(), None);
+        assert_eq!(counts.to_vec(), [2; 8]);
+
+        // The second epoch resumes from the counts of the first one.
+        let resumed = LoadCounts::new(8);
+        fit(resumed.clone(), Some(1));
+        assert_eq!(resumed.to_vec(), [2; 8]);
+        std::fs::remove_dir_all(directory).ok();
+    }
+
+    #[test]
+    #[should_panic = "The number of steps per epoch must be at least 1"]
+    fn test_steps_per_epoch_must_not_be_zero() {
+        let _learner = regression_learner(&test_directory("zero-steps"))
+            .steps_per_epoch(0)
+            .build(regression_model(), SgdConfig::new().init(), 0.1);
+    }
+
+    #[test]
+    fn test_resume_dataloader_position() {
+        let state = |epoch, iteration, dataloader| TrainingState {
+            epoch,
+            iteration,
+            dataloader,
+            ..Default::default()
+        };
+        let data = |state: &TrainingState| ResumeState::new(state, GradientsParams::new(), 2).data;
+
+        assert_eq!(data(&state(3, None, None)), DataLoaderState::new(3, 0));
+        assert_eq!(data(&state(3, Some(10), None)), DataLoaderState::new(2, 5));
+        assert_eq!(
+            data(&state(3, Some(10), Some(DataLoaderState::new(7, 4)))),
+            DataLoaderState::new(7, 4)
+        );
+    }
+}
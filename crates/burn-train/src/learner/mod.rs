@@ -1,22 +1,32 @@
 mod application_logger;
 mod base;
 mod builder;
+mod callback;
 mod classification;
 mod early_stopping;
 mod epoch;
+mod ranking;
 mod regression;
+mod segmentation;
+mod sequence;
 mod step;
 mod summary;
+mod swa;
 mod train_val;
 
 pub use application_logger::*;
 pub use base::*;
 pub use builder::*;
+pub use callback::*;
 pub use classification::*;
 pub use early_stopping::*;
 pub use epoch::*;
+pub use ranking::*;
 pub use regression::*;
+pub use segmentation::*;
+pub use sequence::*;
 pub use step::*;
 pub use summary::*;
+pub use swa::{StochasticWeightAveraging, SwaAnnealing, SwaTrainingState};
 pub use train::*;
 pub use train_val::*;
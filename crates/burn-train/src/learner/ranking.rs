@@ -0,0 +1,132 @@
+use crate::metric::processor::ItemLazy;
+use crate::metric::{Adaptor, LossInput, RankingInput};
+use burn_core::tensor::backend::Backend;
+use burn_core::tensor::{Tensor, Transaction};
+use burn_ndarray::NdArray;
+
+/// Ranking output adapted for multiple metrics.
+#[derive(new)]
+pub struct RankingOutput<B: Backend> {
+    /// The loss.
+    pub loss: Tensor<B, 1>,
+
+    /// The predicted scores with shape `[num_queries, num_items]`, higher is ranked first.
+    pub scores: Tensor<B, 2>,
+
+    /// The graded relevance of the items with shape `[num_queries, num_items]`.
+    pub relevance: Tensor<B, 2>,
+}
+
+impl<B: Backend> ItemLazy for RankingOutput<B> {
+    type ItemSync = RankingOutput<NdArray>;
+
+    fn sync(self) -> Self::ItemSync {
+        let [scores, loss, relevance] = Transaction::default()
+            .register(self.scores)
+            .register(self.loss)
+            .register(self.relevance)
+            .execute()
+            .try_into()
+            .expect("Correct amount of tensor data");
+
+        let device = &Default::default();
+
+        RankingOutput {
+            scores: Tensor::from_data(scores, device),
+            loss: Tensor::from_data(loss, device),
+            relevance: Tensor::from_data(relevance, device),
+        }
+    }
+}
+
+impl<B: Backend> Adaptor<LossInput<B>> for RankingOutput<B> {
+    fn adapt(&self) -> LossInput<B> {
+        LossInput::new(self.loss.clone())
+    }
+}
+
+impl<B: Backend> Adaptor<RankingInput<B>> for RankingOutput<B> {
+    fn adapt(&self) -> RankingInput<B> {
+        RankingInput::new(self.scores.clone(), self.relevance.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logger::InMemoryMetricLogger;
+    use crate::metric::MeanReciprocalRankMetric;
+    use crate::metric::store::{Aggregate, EventStoreClient, Split};
+    use crate::renderer::NoopMetricsRenderer;
+    use crate::tests::{RegressionBatch, regression_dataloaders, regression_model, test_directory};
+    use crate::{
+        CallbackContext, LearnerBuilder, LearnerCallback, TestAutodiffBackend, TestBackend,
+        TrainOutput, TrainStep, ValidStep,
+    };
+    use burn_core::nn::Linear;
+    use burn_core::nn::loss::{MseLoss, Reduction};
+    use burn_core::optim::SgdConfig;
+    use burn_core::tensor::backend::AutodiffBackend;
+    use std::sync::{Arc, Mutex};
+
+    /// Ranks the items of a batch, a single query, where only the inputs above 0.5 are relevant.
+    fn rank<B: Backend>(linear: &Linear<B>, batch: RegressionBatch<B>) -> RankingOutput<B> {
+        let output = linear.forward(batch.inputs.clone());
+        let loss = MseLoss::new().forward(output.clone(), batch.targets, Reduction::Mean);
+        let relevance = batch.inputs.greater_elem(0.5).float();
+
+        RankingOutput::new(loss, output.transpose(), relevance.transpose())
+    }
+
+    impl<B: AutodiffBackend> TrainStep<RegressionBatch<B>, RankingOutput<B>> for Linear<B> {
+        fn step(&self, batch: RegressionBatch<B>) -> TrainOutput<RankingOutput<B>> {
+            let item = rank(self, batch);
+            TrainOutput::new(self, item.loss.backward(), item)
+        }
+    }
+
+    impl<B: Backend> ValidStep<RegressionBatch<B>, RankingOutput<B>> for Linear<B> {
+        fn step(&self, batch: RegressionBatch<B>) -> RankingOutput<B> {
+            rank(self, batch)
+        }
+    }
+
+    /// Records the mean reciprocal rank of the validation of each epoch.
+    struct ValidationMrr(Arc<Mutex<Vec<f64>>>);
+
+    impl<M, O> LearnerCallback<TestAutodiffBackend, M, O> for ValidationMrr {
+        fn on_validation_end(&mut self, ctx: &mut CallbackContext<M, O>, store: &EventStoreClient) {
+            let value = store.find_metric("MRR", ctx.epoch, Aggregate::Mean, Split::Valid);
+            self.0.lock().unwrap().extend(value);
+        }
+    }
+
+    #[test]
+    fn test_ranking_metrics_are_computed_by_the_learner() {
+        let directory = test_directory("ranking-output");
+        let inputs = (0..8).map(|x| x as f32 / 8.0).collect();
+        let (train, valid) = regression_dataloaders(inputs, 4);
+        let values = Arc::new(Mutex::new(Vec::new()));
+
+        LearnerBuilder::<
+            TestAutodiffBackend,
+            RankingOutput<TestAutodiffBackend>,
+            RankingOutput<TestBackend>,
+            _,
+            _,
+            _,
+        >::new(&directory)
+        .metric_valid_numeric(MeanReciprocalRankMetric::new())
+        .metric_loggers(InMemoryMetricLogger::new(), InMemoryMetricLogger::new())
+        .renderer(NoopMetricsRenderer)
+        .with_application_logger(None)
+        .callback(ValidationMrr(values.clone()))
+        .num_epochs(1)
+        .build(regression_model(), SgdConfig::new().init(), 0.1)
+        .fit(train, valid);
+        std::fs::remove_dir_all(directory).ok();
+
+        // The first batch has no relevant item, the largest input of the second one is relevant.
+        assert_eq!(*values.lock().unwrap(), vec![0.5]);
+    }
+}
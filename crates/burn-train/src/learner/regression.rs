@@ -1,5 +1,5 @@
 use crate::metric::processor::ItemLazy;
-use crate::metric::{Adaptor, LossInput};
+use crate::metric::{Adaptor, LossInput, RegressionInput};
 use burn_core::tensor::backend::Backend;
 use burn_core::tensor::{Tensor, Transaction};
 use burn_ndarray::NdArray;
@@ -23,6 +23,12 @@ impl<B: Backend> Adaptor<LossInput<B>> for RegressionOutput<B> {
     }
 }
 
+impl<B: Backend> Adaptor<RegressionInput<B>> for RegressionOutput<B> {
+    fn adapt(&self) -> RegressionInput<B> {
+        RegressionInput::new(self.output.clone(), self.targets.clone())
+    }
+}
+
 impl<B: Backend> ItemLazy for RegressionOutput<B> {
     type ItemSync = RegressionOutput<NdArray>;
 
@@ -0,0 +1,52 @@
+use crate::metric::processor::ItemLazy;
+use crate::metric::{Adaptor, LossInput, SegmentationInput};
+use burn_core::tensor::backend::Backend;
+use burn_core::tensor::{Int, Tensor, Transaction};
+use burn_ndarray::NdArray;
+
+/// Semantic segmentation output adapted for multiple metrics.
+#[derive(new)]
+pub struct SegmentationOutput<B: Backend> {
+    /// The loss.
+    pub loss: Tensor<B, 1>,
+
+    /// The class scores with shape `[batch_size, num_classes, height, width]`.
+    pub output: Tensor<B, 4>,
+
+    /// The class of each pixel with shape `[batch_size, height, width]`.
+    pub targets: Tensor<B, 3, Int>,
+}
+
+impl<B: Backend> ItemLazy for SegmentationOutput<B> {
+    type ItemSync = SegmentationOutput<NdArray>;
+
+    fn sync(self) -> Self::ItemSync {
+        let [output, loss, targets] = Transaction::default()
+            .register(self.output)
+            .register(self.loss)
+            .register(self.targets)
+            .execute()
+            .try_into()
+            .expect("Correct amount of tensor data");
+
+        let device = &Default::default();
+
+        SegmentationOutput {
+            output: Tensor::from_data(output, device),
+            loss: Tensor::from_data(loss, device),
+            targets: Tensor::from_data(targets, device),
+        }
+    }
+}
+
+impl<B: Backend> Adaptor<LossInput<B>> for SegmentationOutput<B> {
+    fn adapt(&self) -> LossInput<B> {
+        LossInput::new(self.loss.clone())
+    }
+}
+
+impl<B: Backend> Adaptor<SegmentationInput<B>> for SegmentationOutput<B> {
+    fn adapt(&self) -> SegmentationInput<B> {
+        SegmentationInput::new(self.output.clone(), self.targets.clone())
+    }
+}
@@ -0,0 +1,128 @@
+use crate::metric::processor::ItemLazy;
+use crate::metric::{
+    Adaptor, CharacterErrorRateInput, LossInput, TextGenerationInput, WordErrorRateInput,
+};
+use burn_core::tensor::backend::Backend;
+use burn_core::tensor::{Int, Tensor, Transaction};
+use burn_ndarray::NdArray;
+
+/// Sequence generation output adapted for multiple metrics.
+#[derive(new)]
+pub struct SequenceOutput<B: Backend> {
+    /// The loss.
+    pub loss: Tensor<B, 1>,
+
+    /// The logits with shape `[batch_size, seq_length, vocab_size]`.
+    pub output: Tensor<B, 3>,
+
+    /// The target tokens with shape `[batch_size, seq_length]`.
+    pub targets: Tensor<B, 2, Int>,
+
+    /// The padding tokens removed from the sequences before computing the metrics.
+    #[new(default)]
+    pub pad_tokens: Vec<usize>,
+}
+
+impl<B: Backend> SequenceOutput<B> {
+    /// Sets the padding tokens removed from the sequences before computing the metrics.
+    pub fn with_pad_tokens(mut self, pad_tokens: Vec<usize>) -> Self {
+        self.pad_tokens = pad_tokens;
+        self
+    }
+}
+
+impl<B: Backend> ItemLazy for SequenceOutput<B> {
+    type ItemSync = SequenceOutput<NdArray>;
+
+    fn sync(self) -> Self::ItemSync {
+        let [output, loss, targets] = Transaction::default()
+            .register(self.output)
+            .register(self.loss)
+            .register(self.targets)
+            .execute()
+            .try_into()
+            .expect("Correct amount of tensor data");
+
+        let device = &Default::default();
+
+        SequenceOutput {
+            output: Tensor::from_data(output, device),
+            loss: Tensor::from_data(loss, device),
+            targets: Tensor::from_data(targets, device),
+            pad_tokens: self.pad_tokens,
+        }
+    }
+}
+
+impl<B: Backend> Adaptor<LossInput<B>> for SequenceOutput<B> {
+    fn adapt(&self) -> LossInput<B> {
+        LossInput::new(self.loss.clone())
+    }
+}
+
+impl<B: Backend> Adaptor<TextGenerationInput> for SequenceOutput<B> {
+    fn adapt(&self) -> TextGenerationInput {
+        // Greedy decoding of the logits.
+        let predictions = self.output.clone().argmax(2).squeeze::<2>(2);
+
+        TextGenerationInput::from_tensors(predictions, self.targets.clone(), &self.pad_tokens)
+    }
+}
+
+/// The tokens are used as characters, which is only meaningful for character-level vocabularies.
+impl<B: Backend> Adaptor<CharacterErrorRateInput> for SequenceOutput<B> {
+    fn adapt(&self) -> CharacterErrorRateInput {
+        CharacterErrorRateInput::new(self.adapt())
+    }
+}
+
+/// The tokens are used as words, which is only meaningful for word-level vocabularies.
+impl<B: Backend> Adaptor<WordErrorRateInput> for SequenceOutput<B> {
+    fn adapt(&self) -> WordErrorRateInput {
+        WordErrorRateInput::new(self.adapt())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestBackend;
+
+    #[test]
+    fn test_adapt_text_generation_input() {
+        let device = Default::default();
+        let output = SequenceOutput::<TestBackend>::new(
+            Tensor::from_data([1.0], &device),
+            Tensor::from_data(
+                [
+                    [[0.1, 0.9, 0.0], [0.8, 0.1, 0.1]],
+                    [[0.0, 0.2, 0.8], [0.0, 0.9, 0.1]],
+                ],
+                &device,
+            ),
+            Tensor::from_data([[1, 2], [2, 0]], &device),
+        )
+        .with_pad_tokens(vec![0]);
+
+        let input: TextGenerationInput = output.adapt();
+
+        assert_eq!(input.predictions, vec![vec![1], vec![2, 1]]);
+        assert_eq!(input.references, vec![vec![1, 2], vec![2]]);
+    }
+
+    #[test]
+    fn test_adapt_error_rate_inputs() {
+        let device = Default::default();
+        let output = SequenceOutput::<TestBackend>::new(
+            Tensor::from_data([1.0], &device),
+            Tensor::from_data([[[0.1, 0.9], [0.8, 0.2]]], &device),
+            Tensor::from_data([[1, 1]], &device),
+        );
+
+        let cer: CharacterErrorRateInput = output.adapt();
+        let wer: WordErrorRateInput = output.adapt();
+
+        assert_eq!(cer.sequences.predictions, vec![vec![1, 0]]);
+        assert_eq!(wer.sequences.references, vec![vec![1, 1]]);
+    }
+}
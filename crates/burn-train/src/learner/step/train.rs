@@ -9,7 +9,7 @@ use std::thread::spawn;
 /// Multi devices train step.
 pub struct MultiDevicesTrainStep<B: AutodiffBackend, M, TI, TO> {
     workers: Vec<Worker<B, M, TI>>,
-    receiver: Receiver<TrainOutput<TO>>,
+    receiver: Receiver<(usize, TrainOutput<TO>)>,
 }
 
 struct Message<M, TI> {
@@ -20,6 +20,7 @@ struct Message<M, TI> {
 struct Worker<B: AutodiffBackend, M, TI> {
     sender_input: Sender<Message<M, TI>>,
     device: B::Device,
+    index: usize,
 }
 
 impl<B, M, TI> Worker<B, M, TI>
@@ -37,7 +38,7 @@ where
 
     fn start<TO>(
         &self,
-        sender_output: Sender<TrainOutput<TO>>,
+        sender_output: Sender<(usize, TrainOutput<TO>)>,
         receiver_input: Receiver<Message<M, TI>>,
     ) where
         TI: Send + 'static,
@@ -45,6 +46,7 @@ where
         M: TrainStep<TI, TO> + Send + 'static,
     {
         let device = self.device.clone();
+        let index = self.index;
 
         spawn(move || {
             loop {
@@ -53,7 +55,7 @@ where
                         let model = item.model.fork(&device);
                         let output = model.step(item.item);
 
-                        sender_output.send(output).unwrap();
+                        sender_output.send((index, output)).unwrap();
                     }
                     Err(_err) => {
                         log::info!("Closing thread on device {:?}", device);
@@ -88,11 +90,13 @@ where
         let (sender_output, receiver_output) = std::sync::mpsc::channel();
         let workers = devices
             .iter()
-            .map(|device| {
+            .enumerate()
+            .map(|(index, device)| {
                 let (sender_input, receiver_input) = std::sync::mpsc::channel();
                 let worker = Worker {
                     sender_input,
                     device: device.clone(),
+                    index,
                 };
 
                 worker.start(sender_output.clone(), receiver_input);
@@ -121,29 +125,71 @@ where
         dataloaders: &mut [Box<dyn DataLoaderIterator<TI> + 'a>],
         model: &M,
     ) -> (Vec<TrainOutput<TO>>, Progress) {
-        let mut num_send = 0;
+        let (items, progress) = self.next_items(dataloaders);
+
+        (self.train(items, model), progress)
+    }
 
+    /// Fetch the next item of the data loader of every worker.
+    ///
+    /// # Arguments
+    ///
+    /// * `dataloaders` - The data loader for each worker.
+    ///
+    /// # Returns
+    ///
+    /// The item of every worker, `None` when its data loader is exhausted, and the progress.
+    pub fn next_items<'a>(
+        &self,
+        dataloaders: &mut [Box<dyn DataLoaderIterator<TI> + 'a>],
+    ) -> (Vec<Option<TI>>, Progress) {
         let mut items_total = 0;
         let mut items_processed = 0;
 
-        for (i, worker) in self.workers.iter().enumerate() {
-            let dataloader = &mut dataloaders[i];
-            if let Some(item) = dataloader.next() {
+        let items = dataloaders
+            .iter_mut()
+            .take(self.workers.len())
+            .map(|dataloader| {
+                let item = dataloader.next();
+                if item.is_some() {
+                    let progress = dataloader.progress();
+                    items_total += progress.items_total;
+                    items_processed += progress.items_processed;
+                }
+                item
+            })
+            .collect();
+
+        (items, Progress::new(items_processed, items_total))
+    }
+
+    /// Train the model on the items fetched with [next_items](Self::next_items).
+    ///
+    /// # Arguments
+    ///
+    /// * `items` - The item of every worker.
+    /// * `model` - Model.
+    ///
+    /// # Returns
+    ///
+    /// The outputs, in the order of the workers.
+    pub fn train(&self, items: Vec<Option<TI>>, model: &M) -> Vec<TrainOutput<TO>> {
+        let mut num_send = 0;
+
+        for (worker, item) in self.workers.iter().zip(items) {
+            if let Some(item) = item {
                 worker.register(item, model);
                 num_send += 1;
-                let progress = dataloader.progress();
-                items_total += progress.items_total;
-                items_processed += progress.items_processed;
             }
         }
 
         let mut outputs = Vec::with_capacity(num_send);
 
         for _ in 0..num_send {
-            let output = self.receiver.recv().unwrap();
-            outputs.push(output);
+            outputs.push(self.receiver.recv().unwrap());
         }
+        outputs.sort_by_key(|(index, _)| *index);
 
-        (outputs, Progress::new(items_processed, items_total))
+        outputs.into_iter().map(|(_, output)| output).collect()
     }
 }
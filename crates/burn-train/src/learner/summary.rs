@@ -4,12 +4,15 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use serde::{Deserialize, Serialize};
+
 use crate::{
     logger::FileMetricLogger,
     metric::store::{Aggregate, EventStore, LogEventStore, Split},
 };
 
 /// Contains the metric value at a given time.
+#[derive(Serialize, Deserialize)]
 pub struct MetricEntry {
     /// The step at which the metric was recorded (i.e., epoch).
     pub step: usize,
@@ -18,6 +21,7 @@ pub struct MetricEntry {
 }
 
 /// Contains the summary of recorded values for a given metric.
+#[derive(Serialize, Deserialize)]
 pub struct MetricSummary {
     /// The metric name.
     pub name: String,
@@ -52,6 +56,7 @@ impl MetricSummary {
 }
 
 /// Contains the summary of recorded metrics for the training and validation steps.
+#[derive(Serialize, Deserialize)]
 pub struct SummaryMetrics {
     /// Training metrics summary.
     pub train: Vec<MetricSummary>,
@@ -60,12 +65,14 @@ pub struct SummaryMetrics {
 }
 
 /// Detailed training summary.
+#[derive(Serialize, Deserialize)]
 pub struct LearnerSummary {
     /// The number of epochs completed.
     pub epochs: usize,
     /// The summary of recorded metrics during training.
     pub metrics: SummaryMetrics,
     /// The model name (only recorded within the learner).
+    #[serde(default)]
     pub(crate) model: Option<String>,
 }
 
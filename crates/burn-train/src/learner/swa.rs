@@ -0,0 +1,336 @@
+use crate::TrainStep;
+use crate::checkpoint::{AveragingStrategy, ModuleAverage};
+use burn_core::{
+    LearningRate,
+    module::{AutodiffModule, Module, ModuleVisitor, RunningState},
+    tensor::{
+        Tensor,
+        backend::{AutodiffBackend, Backend},
+    },
+};
+use serde::{Deserialize, Serialize};
+
+/// How the learning rate is annealed toward the [SWA](StochasticWeightAveraging) learning rate.
+#[derive(Debug, Clone, Copy)]
+pub enum SwaAnnealing {
+    /// Linear annealing.
+    Linear,
+    /// Cosine annealing.
+    Cosine,
+}
+
+/// Stochastic Weight Averaging (SWA) of the model parameters during training.
+///
+/// Starting at the given epoch, the learning rate is annealed toward a constant SWA learning rate
+/// and the parameters of the model are averaged at the end of each epoch. When training is done,
+/// the averaged parameters are loaded into the returned model.
+///
+/// See [Averaging Weights Leads to Wider Optima and Better Generalization](https://arxiv.org/abs/1803.05407).
+#[derive(Debug, Clone)]
+pub struct StochasticWeightAveraging {
+    start_epoch: usize,
+    lr: LearningRate,
+    anneal_iterations: usize,
+    annealing: SwaAnnealing,
+    update_batch_norm: bool,
+}
+
+impl StochasticWeightAveraging {
+    /// Creates a new SWA starting at the given epoch with the given learning rate.
+    ///
+    /// By default, the learning rate is switched to the SWA learning rate without annealing and
+    /// the batch norm statistics are recomputed for the averaged model.
+    pub fn new(start_epoch: usize, lr: LearningRate) -> Self {
+        Self {
+            start_epoch,
+            lr,
+            anneal_iterations: 0,
+            annealing: SwaAnnealing::Cosine,
+            update_batch_norm: true,
+        }
+    }
+
+    /// Anneal the learning rate from the scheduled learning rate to the SWA learning rate during
+    /// the given number of iterations.
+    pub fn with_annealing(mut self, iterations: usize, annealing: SwaAnnealing) -> Self {
+        self.anneal_iterations = iterations;
+        self.annealing = annealing;
+        self
+    }
+
+    /// Whether the batch norm statistics are recomputed for the averaged model with a pass over
+    /// the training data.
+    ///
+    /// The running statistics are reset and recomputed as the average of the statistics of all
+    /// the batches, with forward passes of the [training step](crate::TrainStep::step) on the
+    /// model with its parameters detached, so the backward passes are skipped. This is only
+    /// useful for models with batch norm layers.
+    pub fn with_batch_norm_update(mut self, update: bool) -> Self {
+        self.update_batch_norm = update;
+        self
+    }
+}
+
+/// The state of the [SWA](StochasticWeightAveraging) saved with the
+/// [training state](crate::checkpoint::TrainingState) of a checkpoint, the averaged parameters
+/// being saved with the model checkpointer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SwaTrainingState {
+    /// The number of models that were averaged.
+    pub count: usize,
+    /// The number of iterations since the start of the SWA, for the annealing of the learning
+    /// rate.
+    pub iteration: usize,
+    /// The scheduled learning rate when the SWA started.
+    pub lr_start: Option<LearningRate>,
+}
+
+/// The state of the [SWA](StochasticWeightAveraging) during training.
+pub(crate) struct SwaState<B: Backend> {
+    config: StochasticWeightAveraging,
+    average: ModuleAverage<B>,
+    lr_start: Option<LearningRate>,
+    iteration: usize,
+}
+
+impl<B: Backend> SwaState<B> {
+    pub(crate) fn new(config: StochasticWeightAveraging) -> Self {
+        Self {
+            config,
+            average: ModuleAverage::new(AveragingStrategy::Uniform),
+            lr_start: None,
+            iteration: 0,
+        }
+    }
+
+    /// The learning rate to use at the given epoch, given the one of the scheduler.
+    pub(crate) fn lr(&mut self, epoch: usize, scheduled: LearningRate) -> LearningRate {
+        if epoch < self.config.start_epoch {
+            return scheduled;
+        }
+
+        let lr_start = *self.lr_start.get_or_insert(scheduled);
+        self.iteration += 1;
+
+        let progress = match self.config.anneal_iterations {
+            0 => 1.0,
+            iterations => f64::min(self.iteration as f64 / iterations as f64, 1.0),
+        };
+        let factor = match self.config.annealing {
+            SwaAnnealing::Linear => progress,
+            SwaAnnealing::Cosine => (1.0 - f64::cos(core::f64::consts::PI * progress)) / 2.0,
+        };
+
+        self.config.lr * factor + lr_start * (1.0 - factor)
+    }
+
+    /// Add the model to the average at the end of the given epoch.
+    pub(crate) fn update<M: Module<B>>(&mut self, epoch: usize, model: &M) {
+        if epoch >= self.config.start_epoch {
+            self.average.update(model);
+        }
+    }
+
+    /// The number of models that were averaged.
+    pub(crate) fn count(&self) -> usize {
+        self.average.count()
+    }
+
+    /// The state to save in a checkpoint, along with the [averaged model](Self::averaged).
+    pub(crate) fn state(&self) -> SwaTrainingState {
+        SwaTrainingState {
+            count: self.average.count(),
+            iteration: self.iteration,
+            lr_start: self.lr_start,
+        }
+    }
+
+    /// The model with the averaged parameters, when at least one model was averaged.
+    pub(crate) fn averaged<M: Module<B>>(&self, model: &M) -> Option<M> {
+        match self.average.count() {
+            0 => None,
+            _ => Some(self.average.apply(model.clone())),
+        }
+    }
+
+    /// Restore the state saved in a checkpoint, with the averaged model when there is one.
+    pub(crate) fn with_state<M: Module<B>>(
+        mut self,
+        state: SwaTrainingState,
+        averaged: Option<&M>,
+    ) -> Self {
+        self.iteration = state.iteration;
+        self.lr_start = state.lr_start;
+        if let Some(averaged) = averaged.filter(|_| state.count > 0) {
+            self.average.restore(averaged, state.count);
+        }
+        self
+    }
+
+    /// Load the averaged parameters into the model.
+    pub(crate) fn apply<M: Module<B>>(&self, model: M) -> M {
+        self.average.apply(model)
+    }
+
+    pub(crate) fn update_batch_norm(&self) -> bool {
+        self.config.update_batch_norm
+    }
+}
+
+/// Recompute the running statistics of the model, such as the ones of its batch norm layers, as
+/// the average of their values over the given items.
+pub(crate) fn update_running_states<B, M, TI, TO>(model: &M, items: impl Iterator<Item = TI>)
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B> + TrainStep<TI, TO>,
+{
+    model.visit(&mut RunningStatesCumulative { cumulative: true });
+
+    // The running states are shared with the detached model, whose training step only runs the
+    // forward pass since no tensor requires a gradient.
+    let detached = model.clone().no_grad();
+    for item in items {
+        let _ = detached.step(item);
+    }
+
+    model.visit(&mut RunningStatesCumulative { cumulative: false });
+}
+
+/// Switch the running states of a module to or from a cumulative average.
+struct RunningStatesCumulative {
+    cumulative: bool,
+}
+
+impl<B: Backend> ModuleVisitor<B> for RunningStatesCumulative {
+    fn visit_running_state<const D: usize>(&mut self, state: &RunningState<Tensor<B, D>>) {
+        state.set_cumulative(self.cumulative);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        param_values, regression_dataloaders, regression_learner, regression_model, test_directory,
+    };
+    use crate::{TestAutodiffBackend, TestBackend, TrainOutput};
+    use burn_core::{
+        nn::{BatchNorm, BatchNormConfig},
+        optim::AdamConfig,
+        record::{FullPrecisionSettings, NamedMpkFileRecorder},
+    };
+
+    #[test]
+    fn test_lr_before_start_epoch_is_scheduled() {
+        let mut swa = SwaState::<TestBackend>::new(StochasticWeightAveraging::new(2, 0.01));
+
+        assert_eq!(swa.lr(1, 0.1), 0.1);
+    }
+
+    #[test]
+    fn test_lr_without_annealing() {
+        let mut swa = SwaState::<TestBackend>::new(StochasticWeightAveraging::new(2, 0.01));
+
+        assert_eq!(swa.lr(2, 0.1), 0.01);
+        assert_eq!(swa.lr(3, 0.2), 0.01);
+    }
+
+    #[test]
+    fn test_lr_linear_annealing() {
+        let mut swa = SwaState::<TestBackend>::new(
+            StochasticWeightAveraging::new(1, 0.0).with_annealing(4, SwaAnnealing::Linear),
+        );
+
+        let lrs = (0..5).map(|_| swa.lr(1, 1.0)).collect::<Vec<_>>();
+
+        assert_eq!(lrs, vec![0.75, 0.5, 0.25, 0.0, 0.0]);
+    }
+
+    impl<B: AutodiffBackend> TrainStep<Tensor<B, 2>, ()> for BatchNorm<B, 0> {
+        fn step(&self, item: Tensor<B, 2>) -> TrainOutput<()> {
+            let loss = self.forward(item).sum();
+            TrainOutput::new(self, loss.backward(), ())
+        }
+    }
+
+    #[test]
+    fn test_state_is_restored() {
+        let device = Default::default();
+        let config = StochasticWeightAveraging::new(1, 0.0).with_annealing(4, SwaAnnealing::Linear);
+        let mut swa = SwaState::<TestBackend>::new(config.clone());
+        // The models share the ids of their parameters, with a gamma of the given value.
+        let base = BatchNormConfig::new(1).init::<TestBackend, 0>(&device);
+        let model = |value: f32| {
+            let mut model = base.clone();
+            model.gamma = model.gamma.map(|gamma| gamma.mul_scalar(value));
+            model
+        };
+        swa.lr(1, 1.0);
+        swa.update(1, &model(1.0));
+        swa.update(1, &model(3.0));
+
+        let state = swa.state();
+        let averaged = swa.averaged(&model(0.0));
+        let mut restored =
+            SwaState::<TestBackend>::new(config).with_state(state, averaged.as_ref());
+        restored.update(2, &model(8.0));
+
+        assert_eq!(restored.state().count, 3);
+        assert_eq!(restored.lr(2, 0.9), 0.5);
+        let gamma = restored.apply(model(0.0)).gamma.val().into_data();
+        assert_eq!(gamma.to_vec::<f32>().unwrap(), vec![4.0]);
+    }
+
+    #[test]
+    fn test_running_states_are_the_average_of_the_items() {
+        let device = Default::default();
+        let model = BatchNormConfig::new(2).init::<TestAutodiffBackend, 0>(&device);
+        let _ = model.step(Tensor::from_floats([[10.0, 10.0], [12.0, 10.0]], &device));
+
+        let items = [[[1.0, 2.0], [3.0, 2.0]], [[5.0, 2.0], [7.0, 2.0]]]
+            .map(|item| Tensor::<TestAutodiffBackend, 2>::from_floats(item, &device));
+        update_running_states(&model, items.into_iter());
+
+        let mean = model.running_mean.value_sync().into_data();
+        assert_eq!(mean.to_vec::<f32>().unwrap(), vec![4.0, 2.0]);
+        let var = model.running_var.value_sync().into_data();
+        assert_eq!(var.to_vec::<f32>().unwrap(), vec![1.0, 0.0]);
+
+        // The next updates use the momentum of the model again.
+        let _ = model.step(Tensor::from_floats([[4.0, 2.0], [4.0, 2.0]], &device));
+        let var = model.running_var.value_sync().into_data();
+        assert_eq!(var.to_vec::<f32>().unwrap(), vec![0.9, 0.0]);
+    }
+
+    #[test]
+    fn test_learner_resumes_the_average_from_the_checkpoint() {
+        let directory = test_directory("swa-resume");
+        let fit = |directory: &std::path::Path, checkpoint: Option<usize>| {
+            let inputs = (0..8).map(|x| x as f32 / 8.0).collect();
+            let (train, valid) = regression_dataloaders(inputs, 2);
+            let mut builder = regression_learner(directory)
+                .with_file_checkpointer(NamedMpkFileRecorder::<FullPrecisionSettings>::new())
+                .stochastic_weight_averaging(
+                    StochasticWeightAveraging::new(2, 0.01).with_annealing(6, SwaAnnealing::Linear),
+                )
+                .num_epochs(4);
+            if let Some(checkpoint) = checkpoint {
+                builder = builder.checkpoint(checkpoint);
+            }
+            builder
+                .build(regression_model(), AdamConfig::new().init(), 0.1)
+                .fit(train, valid)
+        };
+
+        let expected = fit(&directory.join("expected"), None);
+        let resumed = directory.join("resumed");
+        fit(&resumed, None);
+        let actual = fit(&resumed, Some(3));
+
+        assert!(resumed.join("checkpoint/swa-3.mpk").exists());
+        for (actual, expected) in param_values(&actual).iter().zip(param_values(&expected)) {
+            assert!((actual - expected).abs() < 1e-6, "{actual} != {expected}");
+        }
+        std::fs::remove_dir_all(directory).ok();
+    }
+}
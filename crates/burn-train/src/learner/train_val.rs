@@ -1,6 +1,10 @@
+use crate::checkpoint::TrainingState;
 use crate::components::{LearnerComponents, TrainBackend, ValidBackend};
+use crate::learner::epoch::ResumeState;
+use crate::learner::swa::SwaState;
 use crate::metric::processor::{Event, EventProcessor};
-use crate::{Learner, TrainEpoch, ValidEpoch};
+use crate::tracking::RunStatus;
+use crate::{Learner, LearnerCallbacks, TrainEpoch, ValidEpoch};
 use burn_core::data::dataloader::DataLoader;
 use burn_core::data::dataloader::split::split_dataloader;
 use burn_core::module::{AutodiffModule, Module};
@@ -131,20 +135,62 @@ impl<LC: LearnerComponents> Learner<LC> {
             dataloader_valid = dataloader_valid.to_device(device);
         }
 
-        let starting_epoch = match self.checkpoint {
-            Some(checkpoint) => {
+        let state = match (&self.checkpointer, self.checkpoint) {
+            (Some(checkpointer), _) if self.resume => checkpointer.latest_state(),
+            (Some(checkpointer), Some(checkpoint)) => Some(checkpointer.state(checkpoint)),
+            (None, Some(checkpoint)) => Some(TrainingState {
+                epoch: checkpoint,
+                ..Default::default()
+            }),
+            _ => None,
+        };
+
+        let mut swa_resume = None;
+        let (starting_epoch, resume) = match state {
+            Some(state) => {
+                let mut grads = GradientsParams::new();
+                let mut swa_averaged = None;
                 if let Some(checkpointer) = &mut self.checkpointer {
-                    (self.model, self.optim, self.lr_scheduler) = checkpointer.load_checkpoint(
+                    log::info!("Resuming the training from the checkpoint {state:?}");
+                    (
+                        self.model,
+                        self.optim,
+                        self.lr_scheduler,
+                        grads,
+                        swa_averaged,
+                    ) = checkpointer.load_checkpoint(
                         self.model,
                         self.optim,
                         self.lr_scheduler,
                         &Default::default(), // Load the checkpoint on the default device.
-                        checkpoint,
+                        &state,
                     );
                 }
-                checkpoint + 1
+                if let (Some(early_stopping), Some(early_stopping_state)) =
+                    (&mut self.early_stopping, state.early_stopping)
+                {
+                    early_stopping.load_state(early_stopping_state);
+                }
+                if let (Some(counts), Some(saved)) = (&self.load_counts, &state.load_counts) {
+                    counts.restore(saved);
+                }
+                if let (Some(swa), Some(swa_state)) = (self.swa.take(), state.swa) {
+                    swa_resume =
+                        Some(SwaState::new(swa).with_state(swa_state, swa_averaged.as_ref()));
+                }
+
+                // A checkpoint saved during an epoch resumes that epoch.
+                let epoch = match state.iteration {
+                    Some(_) => state.epoch,
+                    None => state.epoch + 1,
+                };
+                let iteration = state.iteration.unwrap_or(0);
+                self.event_store.resume(epoch, iteration);
+
+                let num_devices = self.devices.len().max(1);
+                (epoch, Some(ResumeState::new(&state, grads, num_devices)))
             }
-            None => 1,
+            None => (1, None),
         };
 
         // `MultiDevicesTrainStep` has one worker per device, so we use a fixed device strategy
@@ -158,9 +204,39 @@ impl<LC: LearnerComponents> Learner<LC> {
             starting_epoch,
             self.num_epochs,
             self.grad_accumulation,
+            self.grad_clipping.clone(),
+        )
+        .with_swa(swa_resume.or_else(|| self.swa.take().map(SwaState::new)))
+        .with_load_counts(self.load_counts.clone())
+        .with_collective(self.collective.take(), self.sharded_optimizer.is_none())
+        .with_checkpointing(self.checkpoint_interval, self.seed)
+        .with_steps(self.steps_per_epoch, self.max_steps)
+        .with_resume(resume);
+
+        // Every process of a distributed training starts from the same parameters.
+        self.model = epoch_train.broadcast_model(self.model);
+
+        let mut callbacks =
+            LearnerCallbacks::new(core::mem::take(&mut self.callbacks), self.num_epochs);
+        callbacks.set_step(epoch_train.step());
+        callbacks.call(
+            &mut self.model,
+            &mut self.optim,
+            starting_epoch,
+            0,
+            |callback, ctx| callback.on_train_start(ctx),
         );
 
-        for epoch in starting_epoch..self.num_epochs + 1 {
+        let mut epoch = starting_epoch;
+        while epoch <= self.num_epochs && !epoch_train.is_done() {
+            callbacks.call(
+                &mut self.model,
+                &mut self.optim,
+                epoch,
+                0,
+                |callback, ctx| callback.on_epoch_start(ctx),
+            );
+
             if self.devices.len() > 1 {
                 (self.model, self.optim) = epoch_train.run_multi_device::<LC, OutputTrain>(
                     self.model,
@@ -169,6 +245,9 @@ impl<LC: LearnerComponents> Learner<LC> {
                     &mut self.event_processor,
                     self.devices.clone(),
                     &self.interrupter,
+                    self.checkpointer.as_mut(),
+                    &mut callbacks,
+                    self.sharded_optimizer,
                 )
             } else {
                 (self.model, self.optim) = epoch_train.run::<LC, OutputTrain>(
@@ -177,43 +256,109 @@ impl<LC: LearnerComponents> Learner<LC> {
                     &mut self.lr_scheduler,
                     &mut self.event_processor,
                     &self.interrupter,
+                    self.checkpointer.as_mut(),
+                    &mut callbacks,
+                    self.sharded_optimizer,
                 );
             }
 
-            if self.interrupter.should_stop() {
+            if epoch_train.sync_stop(self.interrupter.should_stop() || callbacks.should_stop()) {
                 break;
             }
 
             // TODO: multi-device validation?
-            let epoch_valid = ValidEpoch::new(dataloader_valid.clone(), epoch, self.num_epochs);
+            let epoch_valid = ValidEpoch::new(dataloader_valid.clone(), epoch, self.num_epochs)
+                .with_max_batches(self.max_validation_batches)
+                .with_step(epoch_train.step());
             epoch_valid.run::<LC, OutputValid>(
                 &self.model,
                 &mut self.event_processor,
                 &self.interrupter,
             );
+            // The callbacks and the early stopping read the metrics of the validation.
+            self.event_processor.sync();
+            callbacks.call(
+                &mut self.model,
+                &mut self.optim,
+                epoch,
+                0,
+                |callback, ctx| callback.on_validation_end(ctx, &self.event_store),
+            );
+
+            // Only the main process logs the metrics, so it decides when to stop for all.
+            let is_main_rank = epoch_train.is_main_rank();
+            let stop = match &mut self.early_stopping {
+                Some(early_stopping) if is_main_rank => {
+                    early_stopping.should_stop(epoch, &self.event_store)
+                }
+                _ => false,
+            };
 
-            if let Some(checkpointer) = &mut self.checkpointer {
+            // The checkpoint is saved after the early stopping strategy is updated, so it
+            // resumes with the state of the end of the epoch.
+            let checkpointer = self
+                .checkpointer
+                .as_mut()
+                .filter(|_| epoch_train.saves_checkpoints());
+            let state = checkpointer.and_then(|checkpointer| {
+                let mut state = epoch_train.training_state(None, 0);
+                state.early_stopping = self
+                    .early_stopping
+                    .as_ref()
+                    .and_then(|early_stopping| early_stopping.state());
+                let actions = checkpointer.actions(epoch, &self.event_store);
                 checkpointer.checkpoint(
                     &self.model,
                     &self.optim,
                     &self.lr_scheduler,
+                    epoch_train.swa_averaged(&self.model).as_ref(),
+                    epoch_train.sync_checkpointing(actions),
+                    state,
+                )
+            });
+            if let Some(state) = state {
+                callbacks.call(
+                    &mut self.model,
+                    &mut self.optim,
                     epoch,
-                    &self.event_store,
+                    0,
+                    |callback, ctx| callback.on_checkpoint(ctx, &state),
                 );
             }
+            callbacks.call(
+                &mut self.model,
+                &mut self.optim,
+                epoch,
+                0,
+                |callback, ctx| callback.on_epoch_end(ctx),
+            );
 
-            if let Some(early_stopping) = &mut self.early_stopping {
-                if early_stopping.should_stop(epoch, &self.event_store) {
-                    break;
-                }
+            // The maximum number of steps is reached at the same time by every process.
+            if epoch_train.sync_stop(stop || callbacks.should_stop()) || epoch_train.is_done() {
+                break;
             }
+            epoch += 1;
         }
 
+        // The epoch is past the last one when the training wasn't stopped.
+        let epoch = epoch.min(self.num_epochs);
+        callbacks.call(
+            &mut self.model,
+            &mut self.optim,
+            epoch,
+            0,
+            |callback, ctx| callback.on_train_end(ctx),
+        );
+
+        self.model = epoch_train.swa_model(self.model);
+
         // Signal training end. For the TUI renderer, this handles the exit & return to main screen.
         self.event_processor.process_train(Event::End);
+        // The summary and the tracked run read the logged metrics.
+        self.event_processor.sync();
 
         // Display learner summary
-        if let Some(summary) = self.summary {
+        if let Some(summary) = self.summary.filter(|_| epoch_train.is_main_rank()) {
             match summary.init() {
                 Ok(summary) => {
                     println!("{}", summary.with_model(self.model.to_string()))
@@ -222,6 +367,14 @@ impl<LC: LearnerComponents> Learner<LC> {
             }
         }
 
+        if let Some(run) = self.run {
+            let status = match self.interrupter.should_stop() {
+                true => RunStatus::Interrupted,
+                false => RunStatus::Completed,
+            };
+            run.finish_training(status, self.model.to_string());
+        }
+
         self.model
     }
 }
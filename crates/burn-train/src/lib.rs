@@ -9,6 +9,9 @@ extern crate derive_new;
 /// The checkpoint module.
 pub mod checkpoint;
 
+/// The collective communication module.
+pub mod collective;
+
 pub(crate) mod components;
 
 /// Renderer modules to display metrics and training information.
@@ -20,6 +23,12 @@ pub mod logger;
 /// The metric module.
 pub mod metric;
 
+/// The experiment tracking module.
+pub mod tracking;
+
+/// The hyperparameter search module.
+pub mod search;
+
 mod learner;
 
 pub use learner::*;
@@ -27,11 +36,27 @@ pub use learner::*;
 #[cfg(test)]
 pub(crate) type TestBackend = burn_ndarray::NdArray<f32>;
 
+#[cfg(test)]
+pub(crate) type TestAutodiffBackend = burn_autodiff::Autodiff<TestBackend>;
+
 #[cfg(test)]
 pub(crate) mod tests {
-    use crate::TestBackend;
+    use crate::renderer::NoopMetricsRenderer;
+    use crate::{
+        LearnerBuilder, RegressionOutput, TestAutodiffBackend, TestBackend, TrainOutput, TrainStep,
+        ValidStep, logger::InMemoryMetricLogger,
+    };
+    use burn_core::data::dataloader::{DataLoader, DataLoaderBuilder, batcher::Batcher};
+    use burn_core::data::dataset::InMemDataset;
+    use burn_core::lr_scheduler::LrScheduler;
+    use burn_core::nn::{Initializer, Linear, LinearConfig, loss::MseLoss, loss::Reduction};
+    use burn_core::optim::Optimizer;
+    use burn_core::tensor::TensorData;
+    use burn_core::tensor::backend::{AutodiffBackend, Backend};
     use burn_core::{prelude::Tensor, tensor::Bool};
     use std::default::Default;
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
 
     /// Probability of tp before adding errors
     pub const THRESHOLD: f64 = 0.5;
@@ -105,4 +130,102 @@ pub(crate) mod tests {
             }
         }
     }
+
+    /// A batch of the linear regression used to test the learner.
+    #[derive(Clone, Debug)]
+    pub struct RegressionBatch<B: Backend> {
+        pub inputs: Tensor<B, 2>,
+        pub targets: Tensor<B, 2>,
+    }
+
+    /// Batches the inputs `x` with the targets `2x + 1`.
+    #[derive(Clone)]
+    pub struct RegressionBatcher;
+
+    impl<B: Backend> Batcher<B, f32, RegressionBatch<B>> for RegressionBatcher {
+        fn batch(&self, items: Vec<f32>, device: &B::Device) -> RegressionBatch<B> {
+            let num_items = items.len();
+            let targets = items.iter().map(|x| 2.0 * x + 1.0).collect::<Vec<_>>();
+
+            RegressionBatch {
+                inputs: Tensor::from_data(TensorData::new(items, [num_items, 1]), device),
+                targets: Tensor::from_data(TensorData::new(targets, [num_items, 1]), device),
+            }
+        }
+    }
+
+    impl<B: AutodiffBackend> TrainStep<RegressionBatch<B>, RegressionOutput<B>> for Linear<B> {
+        fn step(&self, batch: RegressionBatch<B>) -> TrainOutput<RegressionOutput<B>> {
+            let item: RegressionOutput<B> = ValidStep::step(self, batch);
+            TrainOutput::new(self, item.loss.backward(), item)
+        }
+    }
+
+    impl<B: Backend> ValidStep<RegressionBatch<B>, RegressionOutput<B>> for Linear<B> {
+        fn step(&self, batch: RegressionBatch<B>) -> RegressionOutput<B> {
+            let output = self.forward(batch.inputs);
+            let loss =
+                MseLoss::new().forward(output.clone(), batch.targets.clone(), Reduction::Mean);
+            RegressionOutput::new(loss, output, batch.targets)
+        }
+    }
+
+    pub type RegressionDataLoaders = (
+        Arc<dyn DataLoader<TestAutodiffBackend, RegressionBatch<TestAutodiffBackend>>>,
+        Arc<dyn DataLoader<TestBackend, RegressionBatch<TestBackend>>>,
+    );
+
+    /// The training and validation data loaders of the regression, on the same inputs.
+    pub fn regression_dataloaders(inputs: Vec<f32>, batch_size: usize) -> RegressionDataLoaders {
+        let train = DataLoaderBuilder::new(RegressionBatcher)
+            .batch_size(batch_size)
+            .build(InMemDataset::new(inputs.clone()));
+        let valid = DataLoaderBuilder::new(RegressionBatcher)
+            .batch_size(batch_size)
+            .build(InMemDataset::new(inputs));
+
+        (train, valid)
+    }
+
+    /// The model of the regression, with constant initial parameters.
+    pub fn regression_model() -> Linear<TestAutodiffBackend> {
+        LinearConfig::new(1, 1)
+            .with_initializer(Initializer::Constant { value: 0.5 })
+            .init(&Default::default())
+    }
+
+    /// A learner builder for the regression, without rendering nor writing any file.
+    pub fn regression_learner<O, S>(
+        directory: &Path,
+    ) -> LearnerBuilder<
+        TestAutodiffBackend,
+        RegressionOutput<TestAutodiffBackend>,
+        RegressionOutput<TestBackend>,
+        Linear<TestAutodiffBackend>,
+        O,
+        S,
+    >
+    where
+        O: Optimizer<Linear<TestAutodiffBackend>, TestAutodiffBackend>,
+        S: LrScheduler,
+    {
+        LearnerBuilder::new(directory)
+            .metric_loggers(InMemoryMetricLogger::new(), InMemoryMetricLogger::new())
+            .renderer(NoopMetricsRenderer)
+            .with_application_logger(None)
+    }
+
+    /// A directory unique to the test and the process, in the temporary directory.
+    pub fn test_directory(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("burn-train-{name}-{}", std::process::id()))
+    }
+
+    /// The values of the parameters of a module.
+    pub fn param_values(model: &Linear<TestAutodiffBackend>) -> Vec<f32> {
+        let mut values = model.weight.val().into_data().to_vec::<f32>().unwrap();
+        if let Some(bias) = &model.bias {
+            values.extend(bias.val().into_data().to_vec::<f32>().unwrap());
+        }
+        values
+    }
 }
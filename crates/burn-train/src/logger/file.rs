@@ -34,6 +34,33 @@ impl FileLogger {
 
         Self { file }
     }
+
+    /// Create a file logger appending to the file if it already exists.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path.
+    ///
+    /// # Returns
+    ///
+    /// The file logger.
+    pub fn append(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        let mut options = std::fs::File::options();
+        let file = options
+            .append(true)
+            .create(true)
+            .open(path)
+            .unwrap_or_else(|err| {
+                panic!(
+                    "Should be able to open the file '{}': {}",
+                    path.display(),
+                    err
+                )
+            });
+
+        Self { file }
+    }
 }
 
 impl<T> Logger<T> for FileLogger
@@ -17,6 +17,18 @@ pub trait MetricLogger: Send {
     /// * `item` - The item.
     fn log(&mut self, item: &MetricEntry);
 
+    /// Sets the global step of the training at which the next items are logged, i.e. the number
+    /// of optimizer steps, counting the step applying the gradients of the training items.
+    ///
+    /// The items logged at the end of an epoch keep the step of the last item of the epoch.
+    ///
+    /// # Arguments
+    ///
+    /// * `step` - The global step.
+    fn set_step(&mut self, step: usize) {
+        let _ = step;
+    }
+
     /// Logs an epoch.
     ///
     /// # Arguments
@@ -26,13 +38,34 @@ pub trait MetricLogger: Send {
 
     /// Read the logs for an epoch.
     fn read_numeric(&mut self, name: &str, epoch: usize) -> Result<Vec<NumericEntry>, String>;
+
+    /// Resume the logging during the given epoch of a training resumed from a checkpoint, after
+    /// the given number of items, discarding what was logged afterward by the interrupted
+    /// training.
+    ///
+    /// # Arguments
+    ///
+    /// * `epoch` - The epoch.
+    /// * `num_items` - The number of items logged during the epoch before the checkpoint.
+    fn resume(&mut self, epoch: usize, num_items: usize) {
+        let _ = (epoch, num_items);
+    }
 }
 
 /// The file metric logger.
+///
+/// # Notes
+///
+/// When [resumed](MetricLogger::resume), the log files of the epoch are truncated to their first
+/// `num_items` lines, assuming that every metric logs one line per item. A metric skipping some
+/// items, e.g. the learning rate with gradients accumulation, may keep some lines logged after
+/// the checkpoint.
 pub struct FileMetricLogger {
     loggers: HashMap<String, AsyncLogger<String>>,
     directory: PathBuf,
     epoch: usize,
+    // Whether the logs of the current epoch were started by an interrupted training.
+    resumed: bool,
 }
 
 impl FileMetricLogger {
@@ -50,34 +83,34 @@ impl FileMetricLogger {
             loggers: HashMap::new(),
             directory: directory.as_ref().to_path_buf(),
             epoch: 1,
+            resumed: false,
         }
     }
 
     /// Number of epochs recorded.
     pub(crate) fn epochs(&self) -> usize {
-        let mut max_epoch = 0;
-
-        for path in fs::read_dir(&self.directory).unwrap() {
-            let path = path.unwrap();
-
-            if fs::metadata(path.path()).unwrap().is_dir() {
-                let dir_name = path.file_name().into_string().unwrap();
-
-                if !dir_name.starts_with(EPOCH_PREFIX) {
-                    continue;
-                }
-
-                let epoch = dir_name.replace(EPOCH_PREFIX, "").parse::<usize>().ok();
+        self.epoch_directories()
+            .into_iter()
+            .map(|(epoch, _)| epoch)
+            .max()
+            .unwrap_or(0)
+    }
 
-                if let Some(epoch) = epoch {
-                    if epoch > max_epoch {
-                        max_epoch = epoch;
-                    }
-                }
-            }
-        }
+    /// The epochs with a log directory.
+    pub(crate) fn epoch_directories(&self) -> Vec<(usize, PathBuf)> {
+        let Ok(entries) = fs::read_dir(&self.directory) else {
+            return Vec::new();
+        };
 
-        max_epoch
+        entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| {
+                let dir_name = entry.file_name().into_string().ok()?;
+                let epoch = dir_name.strip_prefix(EPOCH_PREFIX)?.parse::<usize>().ok()?;
+                Some((epoch, entry.path()))
+            })
+            .collect()
     }
 
     fn epoch_directory(&self, epoch: usize) -> PathBuf {
@@ -109,7 +142,10 @@ impl MetricLogger for FileMetricLogger {
                 self.create_directory(self.epoch);
 
                 let file_path = self.file_path(key, self.epoch);
-                let logger = FileLogger::new(file_path);
+                let logger = match self.resumed {
+                    true => FileLogger::append(file_path),
+                    false => FileLogger::new(file_path),
+                };
                 let logger = AsyncLogger::new(logger);
 
                 self.loggers.insert(key.clone(), logger);
@@ -125,6 +161,7 @@ impl MetricLogger for FileMetricLogger {
     fn end_epoch(&mut self, epoch: usize) {
         self.loggers.clear();
         self.epoch = epoch + 1;
+        self.resumed = false;
     }
 
     fn read_numeric(&mut self, name: &str, epoch: usize) -> Result<Vec<NumericEntry>, String> {
@@ -161,12 +198,37 @@ impl MetricLogger for FileMetricLogger {
             Ok(data)
         }
     }
+
+    fn resume(&mut self, epoch: usize, num_items: usize) {
+        self.loggers.clear();
+        self.epoch = epoch;
+        self.resumed = true;
+
+        for (logged_epoch, directory) in self.epoch_directories() {
+            if logged_epoch > epoch {
+                fs::remove_dir_all(directory).ok();
+            } else if logged_epoch == epoch {
+                // Each metric logs one entry per item.
+                for file in fs::read_dir(directory).into_iter().flatten().flatten() {
+                    let content = fs::read_to_string(file.path()).unwrap_or_default();
+                    let content = content
+                        .lines()
+                        .take(num_items)
+                        .map(|line| format!("{line}\n"))
+                        .collect::<String>();
+                    fs::write(file.path(), content).ok();
+                }
+            }
+        }
+    }
 }
 
 /// In memory metric logger, useful when testing and debugging.
 #[derive(Default)]
 pub struct InMemoryMetricLogger {
     values: HashMap<String, Vec<InMemoryLogger>>,
+    // The number of epochs before the current one.
+    epochs: usize,
 }
 
 impl InMemoryMetricLogger {
@@ -178,8 +240,9 @@ impl InMemoryMetricLogger {
 impl MetricLogger for InMemoryMetricLogger {
     fn log(&mut self, item: &MetricEntry) {
         if !self.values.contains_key(&item.name) {
-            self.values
-                .insert(item.name.clone(), vec![InMemoryLogger::default()]);
+            let mut values = Vec::new();
+            values.resize_with(self.epochs + 1, InMemoryLogger::default);
+            self.values.insert(item.name.clone(), values);
         }
 
         let values = self.values.get_mut(&item.name).unwrap();
@@ -191,6 +254,7 @@ impl MetricLogger for InMemoryMetricLogger {
         for (_, values) in self.values.iter_mut() {
             values.push(InMemoryLogger::default());
         }
+        self.epochs += 1;
     }
 
     fn read_numeric(&mut self, name: &str, epoch: usize) -> Result<Vec<NumericEntry>, String> {
@@ -208,4 +272,15 @@ impl MetricLogger for InMemoryMetricLogger {
             None => Ok(Vec::new()),
         }
     }
+
+    fn resume(&mut self, epoch: usize, num_items: usize) {
+        self.epochs = epoch.saturating_sub(1);
+
+        for (_, values) in self.values.iter_mut() {
+            values.resize_with(epoch, InMemoryLogger::default);
+            if let Some(logger) = values.last_mut() {
+                logger.values.truncate(num_items);
+            }
+        }
+    }
 }
@@ -0,0 +1,759 @@
+use super::MetricLogger;
+use crate::learner::{CallbackContext, LearnerCallback};
+use crate::metric::{MetricEntry, NumericEntry};
+use crate::tracking::flatten_json;
+use burn_core::config::Config;
+use burn_core::module::{AutodiffModule, ModuleVisitor, ParamId};
+use burn_core::optim::GradientsParams;
+use burn_core::tensor::Tensor;
+use burn_core::tensor::backend::{AutodiffBackend, Backend};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const NUM_BUCKETS: usize = 30;
+
+// Distinguishes the event files created in the same second by the same process.
+static FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Writes the scalars, histograms and hyperparameters of a run to a TensorBoard event file.
+///
+/// The event file is created in the given directory, next to the event files of the other
+/// writers of the same run.
+pub struct TensorBoardWriter {
+    file: BufWriter<File>,
+}
+
+impl TensorBoardWriter {
+    /// Create a new event file in the given directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The directory of the run.
+    ///
+    /// # Returns
+    ///
+    /// The TensorBoard writer.
+    pub fn new(directory: impl AsRef<Path>) -> Self {
+        let directory = directory.as_ref();
+        std::fs::create_dir_all(directory).ok();
+
+        let path = directory.join(format!(
+            "events.out.tfevents.{}.burn.{}.{}",
+            wall_time() as u64,
+            std::process::id(),
+            FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = File::create(&path).unwrap_or_else(|err| {
+            panic!(
+                "Should be able to create the new file '{}': {}",
+                path.display(),
+                err
+            )
+        });
+
+        let mut writer = Self {
+            file: BufWriter::new(file),
+        };
+        let mut event = Proto::default();
+        event.double(1, wall_time());
+        event.string(3, "brain.Event:2");
+        writer.write_record(&event.0);
+        writer.flush();
+        writer
+    }
+
+    /// Add the value of a scalar at the given step.
+    pub fn add_scalar(&mut self, tag: &str, value: f64, step: usize) {
+        let mut summary_value = Proto::default();
+        summary_value.string(1, tag);
+        summary_value.float(2, value as f32);
+
+        self.write_summary(summary_value, step);
+    }
+
+    /// Add the histogram of the given values at the given step.
+    pub fn add_histogram(&mut self, tag: &str, values: &[f64], step: usize) {
+        let values = values
+            .iter()
+            .copied()
+            .filter(|value| value.is_finite())
+            .collect::<Vec<_>>();
+        if values.is_empty() {
+            return;
+        }
+
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let width = (max - min) / NUM_BUCKETS as f64;
+
+        // The buckets are delimited by their right edge, the last one ending at the maximum.
+        let (limits, buckets) = match width > 0.0 {
+            true => {
+                let mut buckets = vec![0.0; NUM_BUCKETS];
+                for value in values.iter() {
+                    let index = ((value - min) / width) as usize;
+                    buckets[index.min(NUM_BUCKETS - 1)] += 1.0;
+                }
+                let limits = (1..=NUM_BUCKETS)
+                    .map(|index| match index {
+                        NUM_BUCKETS => max,
+                        _ => min + width * index as f64,
+                    })
+                    .collect();
+                (limits, buckets)
+            }
+            false => (vec![max], vec![values.len() as f64]),
+        };
+
+        let mut histogram = Proto::default();
+        histogram.double(1, min);
+        histogram.double(2, max);
+        histogram.double(3, values.len() as f64);
+        histogram.double(4, values.iter().sum());
+        histogram.double(5, values.iter().map(|value| value * value).sum());
+        histogram.packed_doubles(6, &limits);
+        histogram.packed_doubles(7, &buckets);
+
+        let mut summary_value = Proto::default();
+        summary_value.string(1, tag);
+        summary_value.message(5, &histogram);
+
+        self.write_summary(summary_value, step);
+    }
+
+    /// Add the hyperparameters of the run, from the fields of a [config](Config).
+    ///
+    /// The fields of nested configs are named after their path, e.g. `optimizer.momentum`, and
+    /// the lists are written as strings.
+    pub fn add_hparams<C: Config>(&mut self, config: &C) {
+        let value = serde_json::to_value(config).expect("Config should be serializable");
+        let mut hparams = Vec::new();
+        flatten_json("", &value, &mut hparams);
+
+        // The hyperparameters are written as the start of a session of the hparams plugin.
+        let mut session_start_info = Proto::default();
+        for (name, value) in hparams {
+            let mut hparam_value = Proto::default();
+            match value {
+                serde_json::Value::Number(number) => {
+                    hparam_value.double(2, number.as_f64().unwrap_or_default())
+                }
+                serde_json::Value::String(string) => hparam_value.string(3, &string),
+                serde_json::Value::Bool(bool) => hparam_value.varint(4, bool as u64),
+                value => hparam_value.string(3, &value.to_string()),
+            }
+            let mut entry = Proto::default();
+            entry.string(1, &name);
+            entry.message(2, &hparam_value);
+            session_start_info.message(1, &entry);
+        }
+        session_start_info.double(5, wall_time());
+
+        let mut plugin_content = Proto::default();
+        plugin_content.message(3, &session_start_info);
+
+        let mut plugin_data = Proto::default();
+        plugin_data.string(1, "hparams");
+        plugin_data.bytes(2, &plugin_content.0);
+        let mut metadata = Proto::default();
+        metadata.message(1, &plugin_data);
+
+        // The plugin expects an empty float tensor.
+        let mut tensor = Proto::default();
+        tensor.varint(1, 1);
+        tensor.message(2, &Proto::default());
+
+        let mut summary_value = Proto::default();
+        summary_value.string(1, "_hparams_/session_start_info");
+        summary_value.message(8, &tensor);
+        summary_value.message(9, &metadata);
+
+        self.write_summary(summary_value, 0);
+        self.flush();
+    }
+
+    /// Add the start of a session at the given step, so that TensorBoard discards the events of
+    /// the same run written at this step or after by the previous sessions, e.g. by a training
+    /// interrupted after the checkpoint from which it is resumed.
+    pub fn add_session_start(&mut self, step: usize) {
+        // The status of the session log is `START`.
+        let mut session_log = Proto::default();
+        session_log.varint(1, 1);
+
+        let mut event = Proto::default();
+        event.double(1, wall_time());
+        event.varint(2, step as u64);
+        event.message(7, &session_log);
+        self.write_record(&event.0);
+    }
+
+    /// Flush the events written to the file.
+    pub fn flush(&mut self) {
+        self.file.flush().ok();
+    }
+
+    fn write_summary(&mut self, summary_value: Proto, step: usize) {
+        let mut summary = Proto::default();
+        summary.message(1, &summary_value);
+
+        let mut event = Proto::default();
+        event.double(1, wall_time());
+        event.varint(2, step as u64);
+        event.message(5, &summary);
+
+        self.write_record(&event.0);
+    }
+
+    fn write_record(&mut self, data: &[u8]) {
+        let length = (data.len() as u64).to_le_bytes();
+
+        let mut record = Vec::with_capacity(data.len() + 16);
+        record.extend_from_slice(&length);
+        record.extend_from_slice(&masked_crc32c(&length).to_le_bytes());
+        record.extend_from_slice(data);
+        record.extend_from_slice(&masked_crc32c(data).to_le_bytes());
+
+        if let Err(err) = self.file.write_all(&record) {
+            log::warn!("Failed to write the TensorBoard event: {err}");
+        }
+    }
+}
+
+/// Metric logger writing the numeric metrics as TensorBoard scalars.
+///
+/// The items are written at the global step of the training, the number of optimizer steps, and
+/// the items logged at the same step are written once as their mean, e.g. when the gradients are
+/// accumulated or during the validation. The mean of every epoch is written with the `/epoch`
+/// suffix at the epoch step. A logger should be registered for each split, in a different
+/// directory of the same parent directory, e.g. `runs/name/train` and `runs/name/valid`, so the
+/// splits can be compared as different runs.
+///
+/// Only the sum of the items of every epoch is kept in memory, so the logs of an epoch are read
+/// as their mean. When the training is resumed from a checkpoint, the events written after the
+/// checkpoint by the interrupted training are discarded by TensorBoard.
+pub struct TensorBoardMetricLogger {
+    writer: TensorBoardWriter,
+    // The global step of the items being logged.
+    step: usize,
+    // The sum and the number of the items logged at the current step, for each metric.
+    step_sums: HashMap<String, (f64, usize)>,
+    // The sum and the number of the items logged during each epoch, for each metric.
+    epoch_sums: HashMap<String, Vec<(f64, usize)>>,
+    epoch: usize,
+    // Whether the events of the interrupted training must be discarded at the next step written.
+    resumed: bool,
+}
+
+impl TensorBoardMetricLogger {
+    /// Create a new TensorBoard metric logger.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The directory of the event file.
+    ///
+    /// # Returns
+    ///
+    /// The TensorBoard metric logger.
+    pub fn new(directory: impl AsRef<Path>) -> Self {
+        Self {
+            writer: TensorBoardWriter::new(directory),
+            step: 0,
+            step_sums: HashMap::new(),
+            epoch_sums: HashMap::new(),
+            epoch: 1,
+            resumed: false,
+        }
+    }
+
+    /// Write the hyperparameters of the run, from the fields of a [config](Config).
+    pub fn with_hparams<C: Config>(mut self, config: &C) -> Self {
+        self.writer.add_hparams(config);
+        self
+    }
+
+    /// Write the mean of the items logged at the current step.
+    fn write_step(&mut self) {
+        if self.step_sums.is_empty() {
+            return;
+        }
+        if self.resumed {
+            self.writer.add_session_start(self.step);
+            self.resumed = false;
+        }
+
+        for (name, (sum, count)) in self.step_sums.drain() {
+            self.writer.add_scalar(&name, sum / count as f64, self.step);
+        }
+    }
+}
+
+impl MetricLogger for TensorBoardMetricLogger {
+    fn log(&mut self, item: &MetricEntry) {
+        // Only the numeric metrics can be written as scalars.
+        let (sum, count) = match NumericEntry::deserialize(&item.serialize) {
+            Ok(NumericEntry::Value(value)) => (value, 1),
+            Ok(NumericEntry::Aggregated(value, numel)) => (value * numel as f64, numel),
+            Err(_) => return,
+        };
+
+        let step_sum = self.step_sums.entry(item.name.clone()).or_default();
+        step_sum.0 += sum;
+        step_sum.1 += count;
+
+        let epoch_sums = self.epoch_sums.entry(item.name.clone()).or_default();
+        epoch_sums.resize(self.epoch, (0.0, 0));
+        let epoch_sum = &mut epoch_sums[self.epoch - 1];
+        epoch_sum.0 += sum;
+        epoch_sum.1 += count;
+    }
+
+    fn set_step(&mut self, step: usize) {
+        if step != self.step {
+            self.write_step();
+            self.step = step;
+        }
+    }
+
+    fn end_epoch(&mut self, epoch: usize) {
+        self.write_step();
+
+        for (name, epoch_sums) in self.epoch_sums.iter() {
+            if let Some((sum, count)) = epoch_sums.get(epoch - 1).filter(|(_, count)| *count > 0) {
+                self.writer
+                    .add_scalar(&format!("{name}/epoch"), sum / *count as f64, epoch);
+            }
+        }
+
+        self.epoch = epoch + 1;
+        self.writer.flush();
+    }
+
+    fn read_numeric(&mut self, name: &str, epoch: usize) -> Result<Vec<NumericEntry>, String> {
+        let epoch_sum = self
+            .epoch_sums
+            .get(name)
+            .and_then(|epoch_sums| epoch_sums.get(epoch - 1));
+
+        match epoch_sum {
+            Some((sum, count)) if *count > 0 => {
+                Ok(vec![NumericEntry::Aggregated(sum / *count as f64, *count)])
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    fn resume(&mut self, epoch: usize, _num_items: usize) {
+        // The items of the epoch logged before the checkpoint are not kept.
+        for epoch_sums in self.epoch_sums.values_mut() {
+            epoch_sums.truncate(epoch - 1);
+        }
+        self.step_sums.clear();
+        self.epoch = epoch;
+        self.resumed = true;
+    }
+}
+
+/// [Callback](LearnerCallback) writing the histograms of the parameters at the end of every
+/// epoch, and of the gradients before every `interval` optimizer steps, to TensorBoard.
+///
+/// The histograms are tagged `parameters/<path>` and `gradients/<path>`, where `<path>` is the
+/// path of the parameter in the model, e.g. `layers.0.weight`, and are written at the global step
+/// of the training, the number of optimizer steps.
+pub struct TensorBoardHistograms {
+    writer: TensorBoardWriter,
+    interval: usize,
+}
+
+impl TensorBoardHistograms {
+    /// Create a new callback writing the histograms to an event file in the given directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The directory of the event file.
+    /// * `interval` - The number of optimizer steps between two histograms of the gradients.
+    pub fn new(directory: impl AsRef<Path>, interval: usize) -> Self {
+        Self {
+            writer: TensorBoardWriter::new(directory),
+            interval: interval.max(1),
+        }
+    }
+}
+
+impl<B, M, O> LearnerCallback<B, M, O> for TensorBoardHistograms
+where
+    B: AutodiffBackend,
+    M: AutodiffModule<B>,
+{
+    fn on_train_start(&mut self, ctx: &mut CallbackContext<M, O>) {
+        // The training is resumed from a checkpoint.
+        if ctx.step > 0 {
+            self.writer.add_session_start(ctx.step + 1);
+        }
+    }
+
+    fn on_epoch_end(&mut self, ctx: &mut CallbackContext<M, O>) {
+        let mut visitor = HistogramVisitor::new(&mut self.writer, None, ctx.step);
+        ctx.model.visit(&mut visitor);
+        self.writer.flush();
+    }
+
+    fn on_before_optimizer_step(
+        &mut self,
+        ctx: &mut CallbackContext<M, O>,
+        grads: &mut GradientsParams,
+    ) {
+        if ctx.step % self.interval != 0 {
+            return;
+        }
+
+        let mut visitor = HistogramVisitor::new(&mut self.writer, Some(grads), ctx.step + 1);
+        ctx.model.visit(&mut visitor);
+        self.writer.flush();
+    }
+}
+
+/// Write the histograms of the parameters of a module, or of their gradients.
+#[derive(new)]
+struct HistogramVisitor<'a> {
+    writer: &'a mut TensorBoardWriter,
+    grads: Option<&'a GradientsParams>,
+    step: usize,
+    // The names of the modules entered, from the model.
+    #[new(default)]
+    path: Vec<String>,
+}
+
+impl HistogramVisitor<'_> {
+    /// The tag of the histogram of a parameter, after its path in the model.
+    fn tag(&self, prefix: &str, id: ParamId) -> String {
+        match self.path.is_empty() {
+            true => format!("{prefix}/{id}"),
+            false => format!("{prefix}/{}", self.path.join(".")),
+        }
+    }
+}
+
+impl<B: AutodiffBackend> ModuleVisitor<B> for HistogramVisitor<'_> {
+    fn visit_float<const D: usize>(&mut self, id: ParamId, tensor: &Tensor<B, D>) {
+        match self.grads {
+            Some(grads) => {
+                if let Some(grad) = grads.get::<B::InnerBackend, D>(id) {
+                    let values = tensor_values(grad);
+                    let tag = self.tag("gradients", id);
+                    self.writer.add_histogram(&tag, &values, self.step);
+                }
+            }
+            None => {
+                let values = tensor_values(tensor.clone());
+                let tag = self.tag("parameters", id);
+                self.writer.add_histogram(&tag, &values, self.step);
+            }
+        }
+    }
+
+    fn enter_module(&mut self, name: &str) {
+        self.path.push(name.to_string());
+    }
+
+    fn exit_module(&mut self, _name: &str) {
+        self.path.pop();
+    }
+}
+
+fn tensor_values<B: Backend, const D: usize>(tensor: Tensor<B, D>) -> Vec<f64> {
+    tensor
+        .into_data()
+        .convert::<f64>()
+        .to_vec::<f64>()
+        .unwrap_or_default()
+}
+
+fn wall_time() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs_f64())
+        .unwrap_or_default()
+}
+
+/// A protocol buffers message, encoded field by field.
+#[derive(Default)]
+struct Proto(Vec<u8>);
+
+impl Proto {
+    fn key(&mut self, field: u32, wire_type: u32) {
+        self.raw_varint(((field << 3) | wire_type) as u64);
+    }
+
+    fn raw_varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.0.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        self.0.push(value as u8);
+    }
+
+    fn varint(&mut self, field: u32, value: u64) {
+        self.key(field, 0);
+        self.raw_varint(value);
+    }
+
+    fn double(&mut self, field: u32, value: f64) {
+        self.key(field, 1);
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn float(&mut self, field: u32, value: f32) {
+        self.key(field, 5);
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn bytes(&mut self, field: u32, value: &[u8]) {
+        self.key(field, 2);
+        self.raw_varint(value.len() as u64);
+        self.0.extend_from_slice(value);
+    }
+
+    fn string(&mut self, field: u32, value: &str) {
+        self.bytes(field, value.as_bytes());
+    }
+
+    fn message(&mut self, field: u32, message: &Proto) {
+        self.bytes(field, &message.0);
+    }
+
+    fn packed_doubles(&mut self, field: u32, values: &[f64]) {
+        let bytes = values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect::<Vec<_>>();
+        self.bytes(field, &bytes);
+    }
+}
+
+/// The CRC-32C checksum of the records, masked as in the TFRecord format.
+fn masked_crc32c(data: &[u8]) -> u32 {
+    let crc = crc32c(data);
+    crc.rotate_right(15).wrapping_add(0xa282_ead8)
+}
+
+fn crc32c(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = match crc & 1 {
+                1 => (crc >> 1) ^ 0x82f6_3b78,
+                _ => crc >> 1,
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{regression_model, test_directory};
+    use burn_core::module::Module;
+
+    /// The tag, step and scalar value of the events, the session starts being tagged `START`.
+    type TestEvent = (String, u64, Option<f32>);
+
+    /// Read the records of the event file of the directory, checking their framing.
+    fn read_records(directory: &Path) -> Vec<Vec<u8>> {
+        let file = std::fs::read_dir(directory)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        let content = std::fs::read(file.path()).unwrap();
+        let mut records = Vec::new();
+        let mut remaining = content.as_slice();
+        while !remaining.is_empty() {
+            let length = u64::from_le_bytes(remaining[..8].try_into().unwrap()) as usize;
+            let length_crc = u32::from_le_bytes(remaining[8..12].try_into().unwrap());
+            assert_eq!(length_crc, masked_crc32c(&remaining[..8]));
+            let data = &remaining[12..12 + length];
+            let data_crc =
+                u32::from_le_bytes(remaining[12 + length..16 + length].try_into().unwrap());
+            assert_eq!(data_crc, masked_crc32c(data));
+            records.push(data.to_vec());
+            remaining = &remaining[16 + length..];
+        }
+        records
+    }
+
+    fn read_varint(data: &mut &[u8]) -> u64 {
+        let mut value = 0;
+        let mut shift = 0;
+        loop {
+            let byte = data[0];
+            *data = &data[1..];
+            value |= ((byte & 0x7f) as u64) << shift;
+            shift += 7;
+            if byte < 0x80 {
+                return value;
+            }
+        }
+    }
+
+    /// The fields of a protocol buffers message, the varints being decoded.
+    fn read_fields(mut data: &[u8]) -> HashMap<u32, Vec<u8>> {
+        let mut fields = HashMap::new();
+        while !data.is_empty() {
+            let key = read_varint(&mut data);
+            let length = match key & 7 {
+                0 => {
+                    let value = read_varint(&mut data);
+                    fields.insert((key >> 3) as u32, value.to_le_bytes().to_vec());
+                    continue;
+                }
+                1 => 8,
+                5 => 4,
+                _ => read_varint(&mut data) as usize,
+            };
+            fields.insert((key >> 3) as u32, data[..length].to_vec());
+            data = &data[length..];
+        }
+        fields
+    }
+
+    fn read_events(directory: &Path) -> Vec<TestEvent> {
+        let mut events = Vec::new();
+        for record in read_records(directory) {
+            let event = read_fields(&record);
+            let step = event
+                .get(&2)
+                .map(|step| u64::from_le_bytes(step[..].try_into().unwrap()))
+                .unwrap_or_default();
+
+            if event.contains_key(&7) {
+                events.push(("START".to_string(), step, None));
+            }
+            if let Some(summary) = event.get(&5) {
+                let value = read_fields(&read_fields(summary)[&1]);
+                let tag = String::from_utf8(value[&1].clone()).unwrap();
+                let scalar = value
+                    .get(&2)
+                    .map(|scalar| f32::from_le_bytes(scalar[..].try_into().unwrap()));
+                events.push((tag, step, scalar));
+            }
+        }
+        events
+    }
+
+    fn log(logger: &mut TensorBoardMetricLogger, value: f64) {
+        logger.log(&MetricEntry::new(
+            "Loss".to_string(),
+            value.to_string(),
+            value.to_string(),
+        ));
+    }
+
+    #[test]
+    fn test_crc32c() {
+        assert_eq!(crc32c(b"123456789"), 0xe306_9283);
+    }
+
+    #[test]
+    fn test_records_are_framed() {
+        let directory = test_directory("tensorboard-records");
+        let mut logger = TensorBoardMetricLogger::new(&directory);
+        log(&mut logger, 0.5);
+        log(&mut logger, 1.5);
+        logger.end_epoch(1);
+
+        let records = read_records(&directory);
+
+        // The file version, the mean of the items at their step and the mean of the epoch.
+        assert_eq!(records.len(), 3);
+        assert!(records[2].windows(10).any(|tag| tag == b"Loss/epoch"));
+
+        std::fs::remove_dir_all(directory).ok();
+    }
+
+    #[test]
+    fn test_items_are_written_at_the_global_step() {
+        let directory = test_directory("tensorboard-steps");
+        let mut logger = TensorBoardMetricLogger::new(&directory);
+        logger.set_step(1);
+        log(&mut logger, 1.0);
+        log(&mut logger, 2.0);
+        logger.set_step(2);
+        log(&mut logger, 3.0);
+        logger.end_epoch(1);
+
+        assert_eq!(
+            read_events(&directory),
+            [
+                ("Loss".to_string(), 1, Some(1.5)),
+                ("Loss".to_string(), 2, Some(3.0)),
+                ("Loss/epoch".to_string(), 1, Some(2.0)),
+            ]
+        );
+        // Only the mean of the epoch is kept.
+        assert!(matches!(
+            logger.read_numeric("Loss", 1).unwrap()[..],
+            [NumericEntry::Aggregated(mean, 3)] if mean == 2.0
+        ));
+        assert!(logger.read_numeric("Loss", 2).unwrap().is_empty());
+
+        std::fs::remove_dir_all(directory).ok();
+    }
+
+    #[test]
+    fn test_resumed_logger_discards_the_later_events() {
+        let directory = test_directory("tensorboard-resume");
+        let mut logger = TensorBoardMetricLogger::new(&directory);
+        logger.resume(2, 0);
+        logger.set_step(3);
+        log(&mut logger, 4.0);
+        logger.set_step(4);
+        log(&mut logger, 6.0);
+        logger.end_epoch(2);
+
+        assert_eq!(
+            read_events(&directory),
+            [
+                ("START".to_string(), 3, None),
+                ("Loss".to_string(), 3, Some(4.0)),
+                ("Loss".to_string(), 4, Some(6.0)),
+                ("Loss/epoch".to_string(), 2, Some(5.0)),
+            ]
+        );
+        assert!(logger.read_numeric("Loss", 1).unwrap().is_empty());
+
+        std::fs::remove_dir_all(directory).ok();
+    }
+
+    #[test]
+    fn test_histograms_are_tagged_with_the_paths_of_the_parameters() {
+        let directory = test_directory("tensorboard-histograms");
+        let mut writer = TensorBoardWriter::new(&directory);
+        let model = vec![regression_model(), regression_model()];
+
+        let mut visitor = HistogramVisitor::new(&mut writer, None, 3);
+        model.visit(&mut visitor);
+        writer.flush();
+
+        let tags = read_events(&directory)
+            .into_iter()
+            .map(|(tag, step, _)| format!("{tag} {step}"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tags,
+            [
+                "parameters/0.weight 3",
+                "parameters/0.bias 3",
+                "parameters/1.weight 3",
+                "parameters/1.bias 3",
+            ]
+        );
+
+        std::fs::remove_dir_all(directory).ok();
+    }
+}
@@ -16,6 +16,9 @@ pub struct MetricMetadata {
 
     /// The current learning rate.
     pub lr: Option<LearningRate>,
+
+    /// The L2 norm of the gradients computed over all parameters before clipping.
+    pub grad_norm: Option<f64>,
 }
 
 impl MetricMetadata {
@@ -31,6 +34,7 @@ impl MetricMetadata {
             epoch_total: 1,
             iteration: 0,
             lr: None,
+            grad_norm: None,
         }
     }
 }
@@ -56,8 +60,27 @@ pub trait Metric: Send + Sync {
 
     /// Update the metric state and returns the current metric entry.
     fn update(&mut self, item: &Self::Input, metadata: &MetricMetadata) -> MetricEntry;
+
+    /// Update the metric state, or skip the update when there is no value for the item, e.g.
+    /// when the value is only available at some iterations. Skipped updates are neither logged
+    /// nor rendered.
+    ///
+    /// By default, every item is [updated](Metric::update).
+    fn try_update(&mut self, item: &Self::Input, metadata: &MetricMetadata) -> Option<MetricEntry> {
+        Some(self.update(item, metadata))
+    }
+
     /// Clear the metric state.
     fn clear(&mut self);
+
+    /// The entry of the whole epoch, called at the end of each epoch before the state is
+    /// [cleared](Metric::clear).
+    ///
+    /// This is required for metrics whose value of the epoch isn't the mean of the values logged
+    /// by each [update](Metric::update), which then shouldn't be logged.
+    fn epoch_entry(&mut self) -> Option<MetricEntry> {
+        None
+    }
 }
 
 /// Adaptor are used to transform types so that they can be used by metrics.
@@ -88,6 +111,13 @@ pub struct MetricEntry {
     pub serialize: String,
 }
 
+impl MetricEntry {
+    /// Whether the entry is logged, which isn't the case of entries that are only rendered.
+    pub fn is_logged(&self) -> bool {
+        !self.serialize.is_empty()
+    }
+}
+
 /// Numeric metric entry.
 pub enum NumericEntry {
     /// Single numeric value.
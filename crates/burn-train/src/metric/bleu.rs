@@ -0,0 +1,204 @@
+use super::state::{FormatOptions, NumericMetricState};
+use super::text_generation::ngram_counts;
+use super::{MetricEntry, MetricMetadata, TextGenerationInput};
+use crate::metric::{Metric, Numeric};
+
+/// The corpus BLEU score, in the range `[0, 100]`.
+///
+/// The n-gram matches and the lengths are accumulated over the epoch, so the epoch value is the
+/// BLEU score of the whole corpus rather than the mean of the batch scores. No smoothing is
+/// applied, so a corpus without any matching n-gram of the maximum order has a score of 0.
+///
+/// See [BLEU: a Method for Automatic Evaluation of Machine Translation](https://aclanthology.org/P02-1040/).
+pub struct BleuMetric {
+    state: NumericMetricState,
+    stats: BleuStats,
+}
+
+#[derive(Clone)]
+struct BleuStats {
+    matches: Vec<usize>,
+    totals: Vec<usize>,
+    prediction_length: usize,
+    reference_length: usize,
+}
+
+impl BleuStats {
+    fn new(max_order: usize) -> Self {
+        Self {
+            matches: vec![0; max_order],
+            totals: vec![0; max_order],
+            prediction_length: 0,
+            reference_length: 0,
+        }
+    }
+
+    fn update(&mut self, input: &TextGenerationInput) {
+        for (prediction, reference) in input.pairs() {
+            self.prediction_length += prediction.len();
+            self.reference_length += reference.len();
+
+            for (index, (matches, total)) in self
+                .matches
+                .iter_mut()
+                .zip(self.totals.iter_mut())
+                .enumerate()
+            {
+                let order = index + 1;
+                let reference_counts = ngram_counts(reference, order);
+
+                for (ngram, count) in ngram_counts(prediction, order) {
+                    let reference_count = reference_counts.get(ngram).copied().unwrap_or(0);
+                    *matches += count.min(reference_count);
+                }
+                *total += prediction.len().saturating_sub(order - 1);
+            }
+        }
+    }
+
+    fn score(&self) -> f64 {
+        if self.prediction_length == 0 {
+            return 0.0;
+        }
+
+        let mut log_precision = 0.0;
+        for (matches, total) in self.matches.iter().zip(self.totals.iter()) {
+            if *matches == 0 {
+                return 0.0;
+            }
+            log_precision += f64::ln(*matches as f64 / *total as f64);
+        }
+        log_precision /= self.matches.len() as f64;
+
+        let brevity_penalty = match self.prediction_length < self.reference_length {
+            true => f64::exp(1.0 - self.reference_length as f64 / self.prediction_length as f64),
+            false => 1.0,
+        };
+
+        100.0 * brevity_penalty * f64::exp(log_precision)
+    }
+}
+
+impl BleuMetric {
+    /// Creates the metric with n-grams up to the order 4.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum order of the n-grams.
+    ///
+    /// # Panics
+    ///
+    /// If the maximum order is 0.
+    pub fn with_max_order(mut self, max_order: usize) -> Self {
+        assert!(
+            max_order >= 1,
+            "The maximum n-gram order must be at least 1"
+        );
+        self.stats = BleuStats::new(max_order);
+        self
+    }
+}
+
+impl Default for BleuMetric {
+    fn default() -> Self {
+        Self {
+            state: NumericMetricState::default(),
+            stats: BleuStats::new(4),
+        }
+    }
+}
+
+impl Metric for BleuMetric {
+    type Input = TextGenerationInput;
+
+    fn update(&mut self, input: &TextGenerationInput, _metadata: &MetricMetadata) -> MetricEntry {
+        let mut batch = BleuStats::new(self.stats.matches.len());
+        batch.update(input);
+        self.stats.update(input);
+
+        self.state.update_accumulated(
+            batch.score(),
+            self.stats.score(),
+            input.batch_size(),
+            FormatOptions::new(self.name()).precision(2),
+        )
+    }
+
+    fn epoch_entry(&mut self) -> Option<MetricEntry> {
+        self.state
+            .epoch_entry(FormatOptions::new(self.name()).precision(2))
+    }
+
+    fn clear(&mut self) {
+        self.state.reset();
+        self.stats = BleuStats::new(self.stats.matches.len());
+    }
+
+    fn name(&self) -> String {
+        format!("BLEU-{}", self.stats.matches.len())
+    }
+}
+
+impl Numeric for BleuMetric {
+    fn value(&self) -> f64 {
+        self.state.value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bleu_perfect_match() {
+        let mut metric = BleuMetric::new();
+        let input = TextGenerationInput::from_words(
+            &["the cat sat on the mat"],
+            &["the cat sat on the mat"],
+        );
+
+        let _entry = metric.update(&input, &MetricMetadata::fake());
+
+        assert!((metric.value() - 100.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_bleu_is_accumulated_over_the_corpus() {
+        let mut metric = BleuMetric::new();
+
+        let _entry = metric.update(
+            &TextGenerationInput::new(vec![vec![1, 2, 3, 4]], vec![vec![1, 2, 3, 4]]),
+            &MetricMetadata::fake(),
+        );
+        let entry = metric.update(
+            &TextGenerationInput::new(vec![vec![1, 2, 3, 4]], vec![vec![1, 2, 3, 5]]),
+            &MetricMetadata::fake(),
+        );
+
+        // Precisions 7/8, 5/6, 3/4 and 1/2 over the corpus, while the second batch alone has no
+        // matching 4-gram.
+        let expected = 100.0 * f64::powf(7.0 / 8.0 * 5.0 / 6.0 * 3.0 / 4.0 * 1.0 / 2.0, 0.25);
+        assert_eq!(metric.value(), 0.0);
+        assert_eq!(
+            entry.formatted,
+            format!("epoch {expected:.2} - batch 0.00e0")
+        );
+    }
+
+    #[test]
+    fn test_bleu_brevity_penalty() {
+        let mut metric = BleuMetric::new().with_max_order(1);
+        let input = TextGenerationInput::new(vec![vec![1, 2]], vec![vec![1, 2, 3, 4]]);
+
+        let _entry = metric.update(&input, &MetricMetadata::fake());
+
+        assert!((metric.value() - 100.0 * f64::exp(-1.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    #[should_panic = "The maximum n-gram order must be at least 1"]
+    fn test_bleu_max_order_must_be_positive() {
+        let _metric = BleuMetric::new().with_max_order(0);
+    }
+}
@@ -0,0 +1,264 @@
+use core::marker::PhantomData;
+
+use super::state::{FormatOptions, NumericMetricState};
+use super::{ColumnStats, MetricEntry, MetricMetadata, RegressionInput, RegressionStats};
+use crate::metric::{Metric, Numeric};
+use burn_core::tensor::backend::Backend;
+
+/// The Pearson correlation coefficient between the predictions and the targets.
+///
+/// The value is computed for each output over all the items of the epoch, then averaged over
+/// the outputs. An output whose predictions or targets are constant scores 1 when it is
+/// perfectly predicted and 0 otherwise.
+#[derive(Default)]
+pub struct PearsonCorrelationMetric<B: Backend> {
+    state: NumericMetricState,
+    stats: RegressionStats,
+    _b: PhantomData<B>,
+}
+
+impl<B: Backend> PearsonCorrelationMetric<B> {
+    /// Creates the metric.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn pearson(column: &ColumnStats) -> f64 {
+    // The correlation isn't defined without variance, e.g. for a batch of a single item.
+    if column.is_output_constant() || column.is_target_constant() {
+        return column.constant_score();
+    }
+
+    let covariance =
+        column.sum_output_target - column.sum_output * column.sum_target / column.count;
+
+    covariance / f64::sqrt(column.output_sum_squares() * column.target_sum_squares())
+}
+
+impl<B: Backend> Metric for PearsonCorrelationMetric<B> {
+    type Input = RegressionInput<B>;
+
+    fn update(&mut self, input: &RegressionInput<B>, _metadata: &MetricMetadata) -> MetricEntry {
+        let batch = self.stats.update(input);
+
+        self.state.update_accumulated(
+            batch.mean_over_outputs(pearson),
+            self.stats.mean_over_outputs(pearson),
+            input.batch_size(),
+            FormatOptions::new(self.name()).precision(4),
+        )
+    }
+
+    fn epoch_entry(&mut self) -> Option<MetricEntry> {
+        self.state
+            .epoch_entry(FormatOptions::new(self.name()).precision(4))
+    }
+
+    fn clear(&mut self) {
+        self.state.reset();
+        self.stats.reset();
+    }
+
+    fn name(&self) -> String {
+        "Pearson Correlation".to_string()
+    }
+}
+
+impl<B: Backend> Numeric for PearsonCorrelationMetric<B> {
+    fn value(&self) -> f64 {
+        self.state.value()
+    }
+}
+
+/// The Spearman rank correlation coefficient between the predictions and the targets.
+///
+/// Ranks can't be accumulated, so the predictions and targets of the whole epoch are kept in
+/// memory and the value of the epoch is only computed at the end of the epoch. Ties are assigned
+/// the average of their ranks.
+#[derive(Default)]
+pub struct SpearmanCorrelationMetric<B: Backend> {
+    state: NumericMetricState,
+    outputs: Vec<Vec<f64>>,
+    targets: Vec<Vec<f64>>,
+    _b: PhantomData<B>,
+}
+
+impl<B: Backend> SpearmanCorrelationMetric<B> {
+    /// Creates the metric.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// The rank of each value, starting at 1, where ties get the average of their ranks.
+fn ranks(values: &[f64]) -> Vec<f64> {
+    let mut indices = (0..values.len()).collect::<Vec<_>>();
+    indices.sort_by(|a, b| values[*a].total_cmp(&values[*b]));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+
+    while start < indices.len() {
+        let mut end = start + 1;
+        while end < indices.len() && values[indices[end]] == values[indices[start]] {
+            end += 1;
+        }
+
+        // Average of the ranks `start + 1..=end`.
+        let rank = (start + end + 1) as f64 / 2.0;
+        for index in &indices[start..end] {
+            ranks[*index] = rank;
+        }
+        start = end;
+    }
+
+    ranks
+}
+
+fn spearman(outputs: &[Vec<f64>], targets: &[Vec<f64>]) -> f64 {
+    let sum = outputs
+        .iter()
+        .zip(targets)
+        .map(|(outputs, targets)| {
+            let mut stats = ColumnStats::default();
+            for (output, target) in ranks(outputs).into_iter().zip(ranks(targets)) {
+                stats.update(output, target);
+            }
+            pearson(&stats)
+        })
+        .sum::<f64>();
+
+    sum / outputs.len() as f64
+}
+
+impl<B: Backend> Metric for SpearmanCorrelationMetric<B> {
+    type Input = RegressionInput<B>;
+
+    fn update(&mut self, input: &RegressionInput<B>, _metadata: &MetricMetadata) -> MetricEntry {
+        let (outputs, targets) = input.columns();
+
+        if self.outputs.is_empty() {
+            self.outputs = vec![Vec::new(); outputs.len()];
+            self.targets = vec![Vec::new(); targets.len()];
+        }
+        for (all, batch) in self.outputs.iter_mut().zip(&outputs) {
+            all.extend(batch);
+        }
+        for (all, batch) in self.targets.iter_mut().zip(&targets) {
+            all.extend(batch);
+        }
+
+        self.state.update_batch(
+            spearman(&outputs, &targets),
+            input.batch_size(),
+            FormatOptions::new(self.name()).precision(4),
+        )
+    }
+
+    fn epoch_entry(&mut self) -> Option<MetricEntry> {
+        if self.outputs.is_empty() {
+            return None;
+        }
+
+        Some(self.state.update_epoch(
+            spearman(&self.outputs, &self.targets),
+            FormatOptions::new(self.name()).precision(4),
+        ))
+    }
+
+    fn clear(&mut self) {
+        self.state.reset();
+        self.outputs.clear();
+        self.targets.clear();
+    }
+
+    fn name(&self) -> String {
+        "Spearman Correlation".to_string()
+    }
+}
+
+impl<B: Backend> Numeric for SpearmanCorrelationMetric<B> {
+    fn value(&self) -> f64 {
+        self.state.value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestBackend;
+    use burn_core::tensor::Tensor;
+
+    #[test]
+    fn test_pearson_correlation() {
+        let device = Default::default();
+        let mut metric = PearsonCorrelationMetric::<TestBackend>::new();
+        let input = RegressionInput::new(
+            Tensor::from_data([[1.0, 3.0], [2.0, 2.0], [3.0, 1.0]], &device),
+            Tensor::from_data([[2.0, 1.0], [4.0, 2.0], [6.0, 3.0]], &device),
+        );
+
+        let _entry = metric.update(&input, &MetricMetadata::fake());
+
+        // Perfectly correlated first output and anti-correlated second output.
+        assert!(metric.value().abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_pearson_correlation_without_variance() {
+        let device = Default::default();
+        let mut metric = PearsonCorrelationMetric::<TestBackend>::new();
+        let metadata = MetricMetadata::fake();
+
+        // A single item, perfectly predicted.
+        let input = RegressionInput::new(
+            Tensor::from_data([[2.0]], &device),
+            Tensor::from_data([[2.0]], &device),
+        );
+        let _entry = metric.update(&input, &metadata);
+        assert_eq!(metric.value(), 1.0);
+
+        // Constant predictions of varying targets.
+        let input = RegressionInput::new(
+            Tensor::from_data([[1.0], [1.0]], &device),
+            Tensor::from_data([[3.0], [4.0]], &device),
+        );
+        let entry = metric.update(&input, &metadata);
+        assert_eq!(metric.value(), 0.0);
+        assert!(!entry.formatted.contains("NaN"), "{}", entry.formatted);
+    }
+
+    #[test]
+    fn test_spearman_correlation_is_accumulated_over_batches() {
+        let device = Default::default();
+        let mut metric = SpearmanCorrelationMetric::<TestBackend>::new();
+
+        let _entry = metric.update(
+            &RegressionInput::new(
+                Tensor::from_data([[1.0], [10.0]], &device),
+                Tensor::from_data([[1.0], [2.0]], &device),
+            ),
+            &MetricMetadata::fake(),
+        );
+        let entry = metric.update(
+            &RegressionInput::new(
+                Tensor::from_data([[100.0], [0.0]], &device),
+                Tensor::from_data([[3.0], [0.0]], &device),
+            ),
+            &MetricMetadata::fake(),
+        );
+        assert_eq!(entry.formatted, "batch 1.0000");
+        assert!(!entry.is_logged());
+
+        let entry = metric.epoch_entry().unwrap();
+
+        assert_eq!(entry.formatted, "epoch 1.0000");
+        assert_eq!(metric.value(), 1.0);
+    }
+
+    #[test]
+    fn test_ranks_with_ties() {
+        assert_eq!(ranks(&[3.0, 1.0, 3.0, 2.0]), vec![3.5, 1.0, 3.5, 2.0]);
+    }
+}
@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+
+use super::segmentation::{format_per_class, mean_over_classes};
+use super::state::{FormatOptions, NumericMetricState};
+use super::{MetricEntry, MetricMetadata};
+use crate::metric::{Metric, Numeric};
+
+/// A bounding box predicted by a detection model.
+#[derive(new, Debug, Clone, Copy, PartialEq)]
+pub struct PredictedBox {
+    /// Coordinates in [x_min, y_min, width, height] format.
+    pub coords: [f32; 4],
+    /// Box class label.
+    pub label: usize,
+    /// Confidence score of the box.
+    pub score: f32,
+}
+
+/// A ground truth bounding box.
+#[derive(new, Debug, Clone, Copy, PartialEq)]
+pub struct TargetBox {
+    /// Coordinates in [x_min, y_min, width, height] format.
+    pub coords: [f32; 4],
+    /// Box class label.
+    pub label: usize,
+}
+
+/// The input type of the [detection mean average precision](DetectionMapMetric).
+#[derive(new, Debug, Clone, Default)]
+pub struct DetectionInput {
+    /// The predicted boxes of each image.
+    pub predictions: Vec<Vec<PredictedBox>>,
+    /// The ground truth boxes of each image.
+    pub targets: Vec<Vec<TargetBox>>,
+}
+
+/// COCO-style mean average precision (mAP) of bounding boxes, averaged over the IoU thresholds
+/// from 0.5 to 0.95 with a step of 0.05, in percentage.
+///
+/// The predictions are matched with the targets of their image and kept over the epoch, so the
+/// epoch value is the mAP of all the images rather than the mean of the batch values. It is
+/// computed once at the end of the epoch, while each update only displays the batch value. The
+/// average precision is computed with a 101-point interpolation of the precision-recall curve,
+/// and classes without any target are excluded from the mean. The average precision of each
+/// class is displayed below the mean.
+pub struct DetectionMapMetric {
+    state: NumericMetricState,
+    stats: DetectionStats,
+    class_names: Option<Vec<String>>,
+}
+
+impl DetectionMapMetric {
+    /// Creates the metric for the given number of classes.
+    pub fn new(num_classes: usize) -> Self {
+        Self {
+            state: NumericMetricState::default(),
+            stats: DetectionStats::new(num_classes, 100),
+            class_names: None,
+        }
+    }
+
+    /// Sets the maximum number of predictions with the highest scores kept per image.
+    pub fn with_max_detections(mut self, max_detections: usize) -> Self {
+        self.stats = DetectionStats::new(self.stats.num_targets.len(), max_detections);
+        self
+    }
+
+    /// Sets the names of the classes displayed in the per-class breakdown.
+    pub fn with_class_names(mut self, names: Vec<String>) -> Self {
+        self.class_names = Some(names);
+        self
+    }
+}
+
+const NUM_IOU_THRESHOLDS: usize = 10;
+const NUM_RECALL_THRESHOLDS: usize = 101;
+
+fn iou_threshold(index: usize) -> f32 {
+    (50 + 5 * index) as f32 / 100.0
+}
+
+/// Predictions of the epoch, where each entry is the score of a prediction and whether it was
+/// matched with a target for each IoU threshold.
+#[derive(Debug, Clone)]
+struct DetectionStats {
+    max_detections: usize,
+    num_targets: Vec<usize>,
+    predictions: HashMap<usize, Vec<(f32, [bool; NUM_IOU_THRESHOLDS])>>,
+}
+
+fn iou(a: &[f32; 4], b: &[f32; 4]) -> f32 {
+    let width = f32::min(a[0] + a[2], b[0] + b[2]) - f32::max(a[0], b[0]);
+    let height = f32::min(a[1] + a[3], b[1] + b[3]) - f32::max(a[1], b[1]);
+    let intersection = width.max(0.0) * height.max(0.0);
+    let union = a[2] * a[3] + b[2] * b[3] - intersection;
+
+    match union > 0.0 {
+        true => intersection / union,
+        false => 0.0,
+    }
+}
+
+impl DetectionStats {
+    fn new(num_classes: usize, max_detections: usize) -> Self {
+        Self {
+            max_detections,
+            num_targets: vec![0; num_classes],
+            predictions: HashMap::new(),
+        }
+    }
+
+    fn empty(&self) -> Self {
+        Self::new(self.num_targets.len(), self.max_detections)
+    }
+
+    fn update(&mut self, input: &DetectionInput) {
+        for (predictions, targets) in input.predictions.iter().zip(input.targets.iter()) {
+            let mut predictions = predictions.clone();
+            predictions.sort_by(|a, b| b.score.total_cmp(&a.score));
+            predictions.truncate(self.max_detections);
+
+            for target in targets {
+                if let Some(count) = self.num_targets.get_mut(target.label) {
+                    *count += 1;
+                }
+            }
+
+            let mut matched = vec![[false; NUM_IOU_THRESHOLDS]; targets.len()];
+
+            for prediction in predictions {
+                let mut is_match = [false; NUM_IOU_THRESHOLDS];
+
+                for (threshold, is_match) in is_match.iter_mut().enumerate() {
+                    // Greedy matching with the best unmatched target of the same class.
+                    let best = targets
+                        .iter()
+                        .enumerate()
+                        .filter(|(index, target)| {
+                            target.label == prediction.label && !matched[*index][threshold]
+                        })
+                        .map(|(index, target)| (index, iou(&prediction.coords, &target.coords)))
+                        .filter(|(_, iou)| *iou >= iou_threshold(threshold))
+                        .max_by(|a, b| a.1.total_cmp(&b.1));
+
+                    if let Some((index, _)) = best {
+                        matched[index][threshold] = true;
+                        *is_match = true;
+                    }
+                }
+
+                self.predictions
+                    .entry(prediction.label)
+                    .or_default()
+                    .push((prediction.score, is_match));
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.num_targets.fill(0);
+        self.predictions.clear();
+    }
+
+    /// The average precision of each class over the IoU thresholds, `None` for classes without
+    /// any target.
+    fn average_precision(&self) -> Vec<Option<f64>> {
+        self.num_targets
+            .iter()
+            .enumerate()
+            .map(|(class, num_targets)| {
+                if *num_targets == 0 {
+                    return None;
+                }
+
+                let mut predictions = self.predictions.get(&class).cloned().unwrap_or_default();
+                predictions.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+                let sum = (0..NUM_IOU_THRESHOLDS)
+                    .map(|threshold| {
+                        let matches = predictions
+                            .iter()
+                            .map(|(_, is_match)| is_match[threshold])
+                            .collect::<Vec<_>>();
+                        interpolated_average_precision(&matches, *num_targets)
+                    })
+                    .sum::<f64>();
+
+                Some(sum / NUM_IOU_THRESHOLDS as f64)
+            })
+            .collect()
+    }
+}
+
+/// The 101-point interpolated average precision of the predictions sorted by decreasing score.
+fn interpolated_average_precision(matches: &[bool], num_targets: usize) -> f64 {
+    let mut recalls = Vec::with_capacity(matches.len());
+    let mut precisions = Vec::with_capacity(matches.len());
+    let mut true_positives = 0;
+
+    for (index, is_match) in matches.iter().enumerate() {
+        if *is_match {
+            true_positives += 1;
+        }
+        recalls.push(true_positives as f64 / num_targets as f64);
+        precisions.push(true_positives as f64 / (index + 1) as f64);
+    }
+
+    // Precision envelope, so the precision never increases with the recall.
+    for index in (1..precisions.len()).rev() {
+        precisions[index - 1] = f64::max(precisions[index - 1], precisions[index]);
+    }
+
+    let sum = (0..NUM_RECALL_THRESHOLDS)
+        .map(|index| {
+            let recall = index as f64 / (NUM_RECALL_THRESHOLDS - 1) as f64;
+            let position = recalls.partition_point(|value| *value < recall);
+            precisions.get(position).copied().unwrap_or(0.0)
+        })
+        .sum::<f64>();
+
+    sum / NUM_RECALL_THRESHOLDS as f64
+}
+
+impl Metric for DetectionMapMetric {
+    type Input = DetectionInput;
+
+    fn update(&mut self, input: &DetectionInput, _metadata: &MetricMetadata) -> MetricEntry {
+        let mut batch = self.stats.empty();
+        batch.update(input);
+        self.stats.update(input);
+
+        self.state.update_batch(
+            100.0 * mean_over_classes(&batch.average_precision()),
+            input.predictions.len(),
+            FormatOptions::new(self.name()).unit("%").precision(2),
+        )
+    }
+
+    fn epoch_entry(&mut self) -> Option<MetricEntry> {
+        if self.stats.predictions.is_empty() && self.stats.num_targets.iter().all(|n| *n == 0) {
+            return None;
+        }
+
+        let per_class = self
+            .stats
+            .average_precision()
+            .into_iter()
+            .map(|value| value.map(|value| 100.0 * value))
+            .collect::<Vec<_>>();
+
+        let mut entry = self.state.update_epoch(
+            mean_over_classes(&per_class),
+            FormatOptions::new(self.name()).unit("%").precision(2),
+        );
+        entry.formatted += &format_per_class(&per_class, self.class_names.as_deref(), " %");
+        Some(entry)
+    }
+
+    fn clear(&mut self) {
+        self.state.reset();
+        self.stats.reset();
+    }
+
+    fn name(&self) -> String {
+        "mAP@[.5:.95]".to_string()
+    }
+}
+
+impl Numeric for DetectionMapMetric {
+    fn value(&self) -> f64 {
+        self.state.value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iou() {
+        assert_eq!(iou(&[0.0, 0.0, 2.0, 2.0], &[1.0, 0.0, 2.0, 2.0]), 1.0 / 3.0);
+        assert_eq!(iou(&[0.0, 0.0, 1.0, 1.0], &[2.0, 2.0, 1.0, 1.0]), 0.0);
+    }
+
+    #[test]
+    fn test_perfect_detections() {
+        let mut metric = DetectionMapMetric::new(2);
+        let input = DetectionInput::new(
+            vec![vec![
+                PredictedBox::new([0.0, 0.0, 10.0, 10.0], 0, 0.9),
+                PredictedBox::new([20.0, 20.0, 5.0, 5.0], 1, 0.8),
+            ]],
+            vec![vec![
+                TargetBox::new([0.0, 0.0, 10.0, 10.0], 0),
+                TargetBox::new([20.0, 20.0, 5.0, 5.0], 1),
+            ]],
+        );
+
+        let _entry = metric.update(&input, &MetricMetadata::fake());
+
+        assert!((metric.value() - 100.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_map_over_iou_thresholds() {
+        let mut metric = DetectionMapMetric::new(1);
+        // IoU of 0.6 with the target, so it is only matched for the thresholds 0.5, 0.55 and 0.6.
+        let input = DetectionInput::new(
+            vec![vec![PredictedBox::new([0.0, 0.0, 10.0, 6.0], 0, 0.9)]],
+            vec![vec![TargetBox::new([0.0, 0.0, 10.0, 10.0], 0)]],
+        );
+
+        let _entry = metric.update(&input, &MetricMetadata::fake());
+
+        assert!((metric.value() - 30.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_map_is_accumulated_over_batches() {
+        let mut metric = DetectionMapMetric::new(1).with_class_names(vec!["cat".to_string()]);
+        let target = TargetBox::new([0.0, 0.0, 10.0, 10.0], 0);
+
+        // A false positive with a high score, then a true positive.
+        let _entry = metric.update(
+            &DetectionInput::new(
+                vec![vec![PredictedBox::new([50.0, 50.0, 10.0, 10.0], 0, 0.9)]],
+                vec![vec![]],
+            ),
+            &MetricMetadata::fake(),
+        );
+        let entry = metric.update(
+            &DetectionInput::new(
+                vec![vec![PredictedBox::new([0.0, 0.0, 10.0, 10.0], 0, 0.5)]],
+                vec![vec![target]],
+            ),
+            &MetricMetadata::fake(),
+        );
+        assert_eq!(entry.formatted, "batch 100.00 %");
+
+        let entry = metric.epoch_entry().unwrap();
+
+        // Over the epoch, the target is found with a precision of 1/2.
+        assert_eq!(entry.formatted, "epoch 50.00 %\ncat: 50.00 %");
+        assert!((metric.value() - 50.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_interpolated_average_precision() {
+        // Precisions 1, 1/2 and 2/3 at recalls 1/2, 1/2 and 1.
+        let ap = interpolated_average_precision(&[true, false, true], 2);
+        let expected = (51.0 * 1.0 + 50.0 * 2.0 / 3.0) / 101.0;
+
+        assert!((ap - expected).abs() < 1e-9);
+    }
+}
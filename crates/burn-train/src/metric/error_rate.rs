@@ -0,0 +1,191 @@
+use super::state::{FormatOptions, NumericMetricState};
+use super::text_generation::edit_distance;
+use super::{MetricEntry, MetricMetadata, TextGenerationInput};
+use crate::metric::{Metric, Numeric};
+
+/// The input type of the [character error rate metric](CharacterErrorRateMetric).
+///
+/// Each token is a character, see [from_chars](TextGenerationInput::from_chars).
+#[derive(new, Debug, Clone)]
+pub struct CharacterErrorRateInput {
+    /// The sequences of characters.
+    pub sequences: TextGenerationInput,
+}
+
+/// The input type of the [word error rate metric](WordErrorRateMetric).
+///
+/// Each token is a word, see [from_words](TextGenerationInput::from_words).
+#[derive(new, Debug, Clone)]
+pub struct WordErrorRateInput {
+    /// The sequences of words.
+    pub sequences: TextGenerationInput,
+}
+
+/// The character error rate (CER) metric in percentage.
+///
+/// The edit distances and the reference lengths are accumulated over the epoch, so the epoch
+/// value is the error rate of the whole corpus.
+#[derive(Default)]
+pub struct CharacterErrorRateMetric {
+    state: ErrorRateState,
+}
+
+/// The word error rate (WER) metric in percentage.
+///
+/// The edit distances and the reference lengths are accumulated over the epoch, so the epoch
+/// value is the error rate of the whole corpus.
+#[derive(Default)]
+pub struct WordErrorRateMetric {
+    state: ErrorRateState,
+}
+
+impl CharacterErrorRateMetric {
+    /// Creates the metric.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl WordErrorRateMetric {
+    /// Creates the metric.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[derive(Default)]
+struct ErrorRateState {
+    state: NumericMetricState,
+    errors: usize,
+    length: usize,
+}
+
+impl ErrorRateState {
+    fn update(&mut self, input: &TextGenerationInput, format: FormatOptions) -> MetricEntry {
+        let (errors, length) = input
+            .pairs()
+            .map(|(prediction, reference)| (edit_distance(prediction, reference), reference.len()))
+            .fold((0, 0), |(errors, length), (e, l)| (errors + e, length + l));
+
+        self.errors += errors;
+        self.length += length;
+
+        let rate = |errors: usize, length: usize| 100.0 * errors as f64 / length.max(1) as f64;
+
+        self.state.update_accumulated(
+            rate(errors, length),
+            rate(self.errors, self.length),
+            input.batch_size(),
+            format,
+        )
+    }
+
+    fn epoch_entry(&mut self, format: FormatOptions) -> Option<MetricEntry> {
+        self.state.epoch_entry(format)
+    }
+
+    fn reset(&mut self) {
+        self.state.reset();
+        self.errors = 0;
+        self.length = 0;
+    }
+}
+
+impl Metric for CharacterErrorRateMetric {
+    type Input = CharacterErrorRateInput;
+
+    fn update(
+        &mut self,
+        input: &CharacterErrorRateInput,
+        _metadata: &MetricMetadata,
+    ) -> MetricEntry {
+        self.state.update(
+            &input.sequences,
+            FormatOptions::new(self.name()).unit("%").precision(2),
+        )
+    }
+
+    fn epoch_entry(&mut self) -> Option<MetricEntry> {
+        self.state
+            .epoch_entry(FormatOptions::new(self.name()).unit("%").precision(2))
+    }
+
+    fn clear(&mut self) {
+        self.state.reset()
+    }
+
+    fn name(&self) -> String {
+        "CER".to_string()
+    }
+}
+
+impl Numeric for CharacterErrorRateMetric {
+    fn value(&self) -> f64 {
+        self.state.state.value()
+    }
+}
+
+impl Metric for WordErrorRateMetric {
+    type Input = WordErrorRateInput;
+
+    fn update(&mut self, input: &WordErrorRateInput, _metadata: &MetricMetadata) -> MetricEntry {
+        self.state.update(
+            &input.sequences,
+            FormatOptions::new(self.name()).unit("%").precision(2),
+        )
+    }
+
+    fn epoch_entry(&mut self) -> Option<MetricEntry> {
+        self.state
+            .epoch_entry(FormatOptions::new(self.name()).unit("%").precision(2))
+    }
+
+    fn clear(&mut self) {
+        self.state.reset()
+    }
+
+    fn name(&self) -> String {
+        "WER".to_string()
+    }
+}
+
+impl Numeric for WordErrorRateMetric {
+    fn value(&self) -> f64 {
+        self.state.state.value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_character_error_rate() {
+        let mut metric = CharacterErrorRateMetric::new();
+        let input = CharacterErrorRateInput::new(TextGenerationInput::from_chars(
+            &["kitten", "abc"],
+            &["sitting", "abc"],
+        ));
+
+        let _entry = metric.update(&input, &MetricMetadata::fake());
+
+        assert!((metric.value() - 30.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_word_error_rate_is_accumulated_over_the_corpus() {
+        let mut metric = WordErrorRateMetric::new();
+
+        let _entry = metric.update(
+            &WordErrorRateInput::new(TextGenerationInput::from_words(&["a b c"], &["a b d"])),
+            &MetricMetadata::fake(),
+        );
+        let entry = metric.update(
+            &WordErrorRateInput::new(TextGenerationInput::from_words(&["a"], &["a b c d e f g"])),
+            &MetricMetadata::fake(),
+        );
+
+        // (1 + 6) errors over 10 words, the mean of the batch rates would be 66.67.
+        assert_eq!(entry.formatted, "epoch 70.00 % - batch 85.71 %");
+    }
+}
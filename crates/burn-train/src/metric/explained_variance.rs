@@ -0,0 +1,114 @@
+use core::marker::PhantomData;
+
+use super::state::{FormatOptions, NumericMetricState};
+use super::{MetricEntry, MetricMetadata, RegressionInput, RegressionStats};
+use crate::metric::{Metric, Numeric};
+use burn_core::tensor::backend::Backend;
+
+/// The explained variance metric.
+///
+/// Unlike the [R²](super::RSquaredMetric), a constant bias of the predictions doesn't lower the
+/// explained variance. The value is averaged over the outputs. An output whose targets are
+/// constant scores 1 when it is predicted up to a constant bias and 0 otherwise.
+#[derive(Default)]
+pub struct ExplainedVarianceMetric<B: Backend> {
+    state: NumericMetricState,
+    stats: RegressionStats,
+    _b: PhantomData<B>,
+}
+
+impl<B: Backend> ExplainedVarianceMetric<B> {
+    /// Creates the metric.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn explained_variance(stats: &RegressionStats) -> f64 {
+    stats.mean_over_outputs(|column| {
+        let mean_residual = column.sum_residual / column.count;
+        let var_residual = column.sum_residual_sq / column.count - mean_residual * mean_residual;
+
+        if column.is_target_constant() {
+            // The variance of the targets is only explained when the residuals are constant, up
+            // to the rounding errors.
+            let is_explained = var_residual <= 1e-12 * column.sum_residual_sq / column.count;
+            return if is_explained { 1.0 } else { 0.0 };
+        }
+
+        let var_target = column.target_sum_squares() / column.count;
+
+        1.0 - var_residual / var_target
+    })
+}
+
+impl<B: Backend> Metric for ExplainedVarianceMetric<B> {
+    type Input = RegressionInput<B>;
+
+    fn update(&mut self, input: &RegressionInput<B>, _metadata: &MetricMetadata) -> MetricEntry {
+        let batch = self.stats.update(input);
+
+        self.state.update_accumulated(
+            explained_variance(&batch),
+            explained_variance(&self.stats),
+            input.batch_size(),
+            FormatOptions::new(self.name()).precision(4),
+        )
+    }
+
+    fn epoch_entry(&mut self) -> Option<MetricEntry> {
+        self.state
+            .epoch_entry(FormatOptions::new(self.name()).precision(4))
+    }
+
+    fn clear(&mut self) {
+        self.state.reset();
+        self.stats.reset();
+    }
+
+    fn name(&self) -> String {
+        "Explained Variance".to_string()
+    }
+}
+
+impl<B: Backend> Numeric for ExplainedVarianceMetric<B> {
+    fn value(&self) -> f64 {
+        self.state.value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestBackend;
+    use burn_core::tensor::Tensor;
+
+    #[test]
+    fn test_explained_variance_ignores_bias() {
+        let device = Default::default();
+        let mut metric = ExplainedVarianceMetric::<TestBackend>::new();
+        let input = RegressionInput::new(
+            Tensor::from_data([[3.0], [4.0], [5.0]], &device),
+            Tensor::from_data([[1.0], [2.0], [3.0]], &device),
+        );
+
+        let _entry = metric.update(&input, &MetricMetadata::fake());
+
+        assert!((metric.value() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_explained_variance_with_constant_targets() {
+        let device = Default::default();
+        let mut metric = ExplainedVarianceMetric::<TestBackend>::new();
+        let input = RegressionInput::new(
+            Tensor::from_data([[0.3, 0.1], [0.3, 0.3], [0.3, 0.2]], &device),
+            Tensor::from_data([[0.1, 0.1], [0.1, 0.1], [0.1, 0.1]], &device),
+        );
+
+        let _entry = metric.update(&input, &MetricMetadata::fake());
+
+        // Biased first output and wrong second output.
+        assert_eq!(metric.value(), 0.5);
+    }
+}
@@ -0,0 +1,88 @@
+use super::{
+    MetricMetadata, Numeric,
+    state::{FormatOptions, NumericMetricState},
+};
+use crate::metric::{Metric, MetricEntry};
+
+/// Track the L2 norm of the gradients computed over all parameters before clipping.
+///
+/// # Notes
+///
+/// The norm is only computed when gradient clipping is enabled on the
+/// [learner](crate::learner::LearnerBuilder::grad_clipping), and only at the iterations where the
+/// optimizer steps when the gradients are accumulated. The other iterations are skipped.
+pub struct GradientNormMetric {
+    state: NumericMetricState,
+}
+
+impl GradientNormMetric {
+    /// Creates a new gradient norm metric.
+    pub fn new() -> Self {
+        Self {
+            state: NumericMetricState::new(),
+        }
+    }
+}
+
+impl Default for GradientNormMetric {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metric for GradientNormMetric {
+    type Input = ();
+
+    fn update(&mut self, item: &(), metadata: &MetricMetadata) -> MetricEntry {
+        match self.try_update(item, metadata) {
+            Some(entry) => entry,
+            None => self
+                .state
+                .current_entry(FormatOptions::new(self.name()).precision(3)),
+        }
+    }
+
+    fn try_update(&mut self, _item: &(), metadata: &MetricMetadata) -> Option<MetricEntry> {
+        let grad_norm = metadata.grad_norm?;
+
+        Some(
+            self.state
+                .update(grad_norm, 1, FormatOptions::new(self.name()).precision(3)),
+        )
+    }
+
+    fn clear(&mut self) {
+        self.state.reset()
+    }
+
+    fn name(&self) -> String {
+        "Gradient Norm".to_string()
+    }
+}
+
+impl Numeric for GradientNormMetric {
+    fn value(&self) -> f64 {
+        self.state.value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iterations_without_norm_are_skipped() {
+        let mut metric = GradientNormMetric::new();
+        let mut metadata = MetricMetadata::fake();
+
+        metadata.grad_norm = Some(2.0);
+        assert!(metric.try_update(&(), &metadata).is_some());
+        metadata.grad_norm = None;
+        assert!(metric.try_update(&(), &metadata).is_none());
+        metadata.grad_norm = Some(4.0);
+        let entry = metric.try_update(&(), &metadata).unwrap();
+
+        assert_eq!(entry.serialize, "4,1");
+        assert_eq!(entry.formatted, "epoch 3.000 - batch 4.000");
+    }
+}
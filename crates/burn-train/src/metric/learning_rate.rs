@@ -5,6 +5,11 @@ use super::{
 use crate::metric::{Metric, MetricEntry};
 
 /// Track the learning rate across iterations.
+///
+/// # Notes
+///
+/// The learning rate is only known at the iterations where the optimizer steps when the
+/// gradients are accumulated. The other iterations are skipped.
 pub struct LearningRateMetric {
     state: NumericMetricState,
 }
@@ -27,11 +32,22 @@ impl Default for LearningRateMetric {
 impl Metric for LearningRateMetric {
     type Input = ();
 
-    fn update(&mut self, _item: &(), metadata: &MetricMetadata) -> MetricEntry {
-        let lr = metadata.lr.unwrap_or(0.0);
+    fn update(&mut self, item: &(), metadata: &MetricMetadata) -> MetricEntry {
+        match self.try_update(item, metadata) {
+            Some(entry) => entry,
+            None => self
+                .state
+                .current_entry(FormatOptions::new(self.name()).precision(2)),
+        }
+    }
+
+    fn try_update(&mut self, _item: &(), metadata: &MetricMetadata) -> Option<MetricEntry> {
+        let lr = metadata.lr?;
 
-        self.state
-            .update(lr, 1, FormatOptions::new(self.name()).precision(2))
+        Some(
+            self.state
+                .update(lr, 1, FormatOptions::new(self.name()).precision(2)),
+        )
     }
 
     fn clear(&mut self) {
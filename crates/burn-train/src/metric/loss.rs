@@ -16,7 +16,7 @@ pub struct LossMetric<B: Backend> {
 /// The [loss metric](LossMetric) input type.
 #[derive(new)]
 pub struct LossInput<B: Backend> {
-    tensor: Tensor<B, 1>,
+    pub(crate) tensor: Tensor<B, 1>,
 }
 
 impl<B: Backend> LossMetric<B> {
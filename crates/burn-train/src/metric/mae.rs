@@ -0,0 +1,77 @@
+use core::marker::PhantomData;
+
+use super::state::{FormatOptions, NumericMetricState};
+use super::{MetricEntry, MetricMetadata, RegressionInput};
+use crate::metric::{Metric, Numeric};
+use burn_core::tensor::ElementConversion;
+use burn_core::tensor::backend::Backend;
+
+/// The mean absolute error (MAE) metric.
+#[derive(Default)]
+pub struct MeanAbsoluteErrorMetric<B: Backend> {
+    state: NumericMetricState,
+    _b: PhantomData<B>,
+}
+
+impl<B: Backend> MeanAbsoluteErrorMetric<B> {
+    /// Creates the metric.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<B: Backend> Metric for MeanAbsoluteErrorMetric<B> {
+    type Input = RegressionInput<B>;
+
+    fn update(&mut self, input: &RegressionInput<B>, _metadata: &MetricMetadata) -> MetricEntry {
+        let batch_size = input.batch_size();
+        let mae = input
+            .outputs
+            .clone()
+            .sub(input.targets.clone())
+            .abs()
+            .mean()
+            .into_scalar()
+            .elem::<f64>();
+
+        self.state.update(
+            mae,
+            batch_size,
+            FormatOptions::new(self.name()).precision(4),
+        )
+    }
+
+    fn clear(&mut self) {
+        self.state.reset()
+    }
+
+    fn name(&self) -> String {
+        "MAE".to_string()
+    }
+}
+
+impl<B: Backend> Numeric for MeanAbsoluteErrorMetric<B> {
+    fn value(&self) -> f64 {
+        self.state.value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestBackend;
+    use burn_core::tensor::Tensor;
+
+    #[test]
+    fn test_mean_absolute_error() {
+        let device = Default::default();
+        let mut metric = MeanAbsoluteErrorMetric::<TestBackend>::new();
+        let input = RegressionInput::new(
+            Tensor::from_data([[1.0, 2.0], [3.0, 4.0]], &device),
+            Tensor::from_data([[1.5, 2.0], [2.0, 6.0]], &device),
+        );
+
+        let _entry = metric.update(&input, &MetricMetadata::fake());
+        assert_eq!(0.875, metric.value());
+    }
+}
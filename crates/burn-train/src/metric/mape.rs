@@ -0,0 +1,98 @@
+use core::marker::PhantomData;
+
+use super::state::{FormatOptions, NumericMetricState};
+use super::{MetricEntry, MetricMetadata, RegressionInput};
+use crate::metric::{Metric, Numeric};
+use burn_core::tensor::ElementConversion;
+use burn_core::tensor::backend::Backend;
+
+/// The mean absolute percentage error (MAPE) metric.
+///
+/// Targets close to zero are clamped to a small epsilon to avoid dividing by zero, so the metric
+/// is only meaningful when the targets are far from zero.
+pub struct MeanAbsolutePercentageErrorMetric<B: Backend> {
+    state: NumericMetricState,
+    epsilon: f64,
+    _b: PhantomData<B>,
+}
+
+impl<B: Backend> Default for MeanAbsolutePercentageErrorMetric<B> {
+    fn default() -> Self {
+        Self {
+            state: NumericMetricState::default(),
+            epsilon: f64::EPSILON,
+            _b: PhantomData,
+        }
+    }
+}
+
+impl<B: Backend> MeanAbsolutePercentageErrorMetric<B> {
+    /// Creates the metric.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the minimum absolute value of the targets used as denominator.
+    pub fn with_epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+}
+
+impl<B: Backend> Metric for MeanAbsolutePercentageErrorMetric<B> {
+    type Input = RegressionInput<B>;
+
+    fn update(&mut self, input: &RegressionInput<B>, _metadata: &MetricMetadata) -> MetricEntry {
+        let batch_size = input.batch_size();
+        let targets = input.targets.clone();
+        let mape = input
+            .outputs
+            .clone()
+            .sub(targets.clone())
+            .abs()
+            .div(targets.abs().clamp_min(self.epsilon))
+            .mean()
+            .into_scalar()
+            .elem::<f64>();
+
+        self.state.update(
+            100.0 * mape,
+            batch_size,
+            FormatOptions::new(self.name()).unit("%").precision(2),
+        )
+    }
+
+    fn clear(&mut self) {
+        self.state.reset()
+    }
+
+    fn name(&self) -> String {
+        "MAPE".to_string()
+    }
+}
+
+impl<B: Backend> Numeric for MeanAbsolutePercentageErrorMetric<B> {
+    fn value(&self) -> f64 {
+        self.state.value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestBackend;
+    use burn_core::tensor::Tensor;
+
+    #[test]
+    fn test_mean_absolute_percentage_error() {
+        let device = Default::default();
+        let mut metric = MeanAbsolutePercentageErrorMetric::<TestBackend>::new();
+        let input = RegressionInput::new(
+            Tensor::from_data([[1.0], [3.0], [-4.0], [8.0]], &device),
+            Tensor::from_data([[2.0], [3.0], [-5.0], [10.0]], &device),
+        );
+
+        let _entry = metric.update(&input, &MetricMetadata::fake());
+        assert!((metric.value() - 22.5).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,95 @@
+use core::marker::PhantomData;
+
+use super::state::{FormatOptions, NumericMetricState};
+use super::{MetricEntry, MetricMetadata, RankingInput};
+use crate::metric::{Metric, Numeric};
+use burn_core::tensor::backend::Backend;
+
+/// The mean average precision (MAP) metric of a ranking.
+///
+/// The average precision of a query is the mean of the precisions at the positions of its
+/// relevant items, or 0 when it has no relevant item.
+#[derive(Default)]
+pub struct MeanAveragePrecisionMetric<B: Backend> {
+    state: NumericMetricState,
+    _b: PhantomData<B>,
+}
+
+impl<B: Backend> MeanAveragePrecisionMetric<B> {
+    /// Creates the metric.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn average_precision(ranked: &[f64]) -> f64 {
+    let mut num_relevant = 0;
+    let mut sum = 0.0;
+
+    for (position, relevance) in ranked.iter().enumerate() {
+        if *relevance > 0.0 {
+            num_relevant += 1;
+            sum += num_relevant as f64 / (position + 1) as f64;
+        }
+    }
+
+    match num_relevant {
+        0 => 0.0,
+        num_relevant => sum / num_relevant as f64,
+    }
+}
+
+impl<B: Backend> Metric for MeanAveragePrecisionMetric<B> {
+    type Input = RankingInput<B>;
+
+    fn update(&mut self, input: &RankingInput<B>, _metadata: &MetricMetadata) -> MetricEntry {
+        let num_queries = input.num_queries();
+        let sum = input
+            .ranked_relevance()
+            .iter()
+            .map(|ranked| average_precision(ranked))
+            .sum::<f64>();
+
+        self.state.update(
+            sum / num_queries as f64,
+            num_queries,
+            FormatOptions::new(self.name()).precision(4),
+        )
+    }
+
+    fn clear(&mut self) {
+        self.state.reset()
+    }
+
+    fn name(&self) -> String {
+        "MAP".to_string()
+    }
+}
+
+impl<B: Backend> Numeric for MeanAveragePrecisionMetric<B> {
+    fn value(&self) -> f64 {
+        self.state.value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestBackend;
+    use burn_core::tensor::Tensor;
+
+    #[test]
+    fn test_mean_average_precision() {
+        let device = Default::default();
+        let mut metric = MeanAveragePrecisionMetric::<TestBackend>::new();
+        let input = RankingInput::new(
+            Tensor::from_data([[0.4, 0.3, 0.2, 0.1], [0.1, 0.2, 0.3, 0.4]], &device),
+            Tensor::from_data([[1.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 0.0]], &device),
+        );
+
+        let _entry = metric.update(&input, &MetricMetadata::fake());
+
+        // Average precision (1 + 2/3) / 2 and 0.
+        assert!((metric.value() - 5.0 / 12.0).abs() < 1e-6);
+    }
+}
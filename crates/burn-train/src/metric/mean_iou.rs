@@ -0,0 +1,130 @@
+use core::marker::PhantomData;
+
+use super::segmentation::{SegmentationStats, format_per_class, mean_over_classes};
+use super::state::{FormatOptions, NumericMetricState};
+use super::{MetricEntry, MetricMetadata, SegmentationInput};
+use crate::metric::{Metric, Numeric};
+use burn_core::tensor::backend::Backend;
+
+/// The mean intersection over union (IoU) of the segmentation classes in percentage.
+///
+/// The pixels are accumulated over the epoch, so the epoch value is computed on all the masks
+/// rather than averaged over the batches. Classes that are neither predicted nor present in the
+/// targets are excluded from the mean. The value of each class is displayed below the mean.
+pub struct MeanIouMetric<B: Backend> {
+    state: NumericMetricState,
+    stats: SegmentationStats,
+    class_names: Option<Vec<String>>,
+    _b: PhantomData<B>,
+}
+
+impl<B: Backend> MeanIouMetric<B> {
+    /// Creates the metric for the given number of classes.
+    pub fn new(num_classes: usize) -> Self {
+        Self {
+            state: NumericMetricState::default(),
+            stats: SegmentationStats::new(num_classes, None),
+            class_names: None,
+            _b: PhantomData,
+        }
+    }
+
+    /// Ignore the pixels with the given target class.
+    pub fn with_ignore_index(mut self, index: usize) -> Self {
+        self.stats = SegmentationStats::new(self.stats.num_classes(), Some(index));
+        self
+    }
+
+    /// Sets the names of the classes displayed in the per-class breakdown.
+    pub fn with_class_names(mut self, names: Vec<String>) -> Self {
+        self.class_names = Some(names);
+        self
+    }
+
+    fn per_class(&self) -> Vec<Option<f64>> {
+        self.stats
+            .iou()
+            .into_iter()
+            .map(|value| value.map(|value| 100.0 * value))
+            .collect()
+    }
+}
+
+impl<B: Backend> Metric for MeanIouMetric<B> {
+    type Input = SegmentationInput<B>;
+
+    fn update(&mut self, input: &SegmentationInput<B>, _metadata: &MetricMetadata) -> MetricEntry {
+        let batch = self.stats.batch(input);
+        self.stats.update(input);
+
+        let per_class = self.per_class();
+        let mut entry = self.state.update_accumulated(
+            100.0 * mean_over_classes(&batch.iou()),
+            mean_over_classes(&per_class),
+            input.batch_size(),
+            FormatOptions::new(self.name()).unit("%").precision(2),
+        );
+        entry.formatted += &format_per_class(&per_class, self.class_names.as_deref(), " %");
+        entry
+    }
+
+    fn epoch_entry(&mut self) -> Option<MetricEntry> {
+        let mut entry = self
+            .state
+            .epoch_entry(FormatOptions::new(self.name()).unit("%").precision(2))?;
+        entry.formatted += &format_per_class(&self.per_class(), self.class_names.as_deref(), " %");
+        Some(entry)
+    }
+
+    fn clear(&mut self) {
+        self.state.reset();
+        self.stats.reset();
+    }
+
+    fn name(&self) -> String {
+        "Mean IoU".to_string()
+    }
+}
+
+impl<B: Backend> Numeric for MeanIouMetric<B> {
+    fn value(&self) -> f64 {
+        self.state.value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestBackend;
+    use burn_core::tensor::Tensor;
+
+    #[test]
+    fn test_mean_iou_is_accumulated_over_batches() {
+        let device = Default::default();
+        let mut metric = MeanIouMetric::<TestBackend>::new(2)
+            .with_class_names(vec!["background".to_string(), "object".to_string()]);
+
+        // Predictions [0, 1] against targets [0, 0].
+        let _entry = metric.update(
+            &SegmentationInput::new(
+                Tensor::from_data([[[[0.9, 0.1]], [[0.1, 0.9]]]], &device),
+                Tensor::from_data([[[0, 0]]], &device),
+            ),
+            &MetricMetadata::fake(),
+        );
+        // Predictions [1, 1] against targets [1, 1].
+        let entry = metric.update(
+            &SegmentationInput::new(
+                Tensor::from_data([[[[0.1, 0.1]], [[0.9, 0.9]]]], &device),
+                Tensor::from_data([[[1, 1]]], &device),
+            ),
+            &MetricMetadata::fake(),
+        );
+
+        // Over the epoch: background 1/2 and object 2/3.
+        assert_eq!(
+            entry.formatted,
+            "epoch 58.33 % - batch 100.00 %\nbackground: 50.00 %\nobject: 66.67 %"
+        );
+    }
+}
@@ -25,27 +25,69 @@ pub use memory_use::*;
 mod acc;
 mod auroc;
 mod base;
+mod bleu;
 mod confusion_stats;
+mod correlation;
+mod detection;
+mod dice;
+mod error_rate;
+mod explained_variance;
 mod fbetascore;
+mod grad_norm;
 mod hamming;
 mod iteration;
 mod learning_rate;
 mod loss;
+mod mae;
+mod mape;
+mod mean_average_precision;
+mod mean_iou;
+mod mrr;
+mod ndcg;
+mod perplexity;
 mod precision;
+mod r2;
+mod ranking;
 mod recall;
+mod regression;
+mod rmse;
+mod rouge;
+mod segmentation;
+mod text_generation;
 mod top_k_acc;
 
 pub use acc::*;
 pub use auroc::*;
 pub use base::*;
+pub use bleu::*;
 pub use confusion_stats::ConfusionStatsInput;
+pub use correlation::*;
+pub use detection::*;
+pub use dice::*;
+pub use error_rate::*;
+pub use explained_variance::*;
 pub use fbetascore::*;
+pub use grad_norm::*;
 pub use hamming::*;
 pub use iteration::*;
 pub use learning_rate::*;
 pub use loss::*;
+pub use mae::*;
+pub use mape::*;
+pub use mean_average_precision::*;
+pub use mean_iou::*;
+pub use mrr::*;
+pub use ndcg::*;
+pub use perplexity::*;
 pub use precision::*;
+pub use r2::*;
+pub use ranking::*;
 pub use recall::*;
+pub use regression::*;
+pub use rmse::*;
+pub use rouge::*;
+pub use segmentation::SegmentationInput;
+pub use text_generation::TextGenerationInput;
 pub use top_k_acc::*;
 
 pub(crate) mod classification;
@@ -0,0 +1,83 @@
+use core::marker::PhantomData;
+
+use super::state::{FormatOptions, NumericMetricState};
+use super::{MetricEntry, MetricMetadata, RankingInput};
+use crate::metric::{Metric, Numeric};
+use burn_core::tensor::backend::Backend;
+
+/// The mean reciprocal rank (MRR) metric.
+///
+/// The reciprocal rank of a query is the inverse of the position of its first relevant item, or
+/// 0 when it has no relevant item.
+#[derive(Default)]
+pub struct MeanReciprocalRankMetric<B: Backend> {
+    state: NumericMetricState,
+    _b: PhantomData<B>,
+}
+
+impl<B: Backend> MeanReciprocalRankMetric<B> {
+    /// Creates the metric.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<B: Backend> Metric for MeanReciprocalRankMetric<B> {
+    type Input = RankingInput<B>;
+
+    fn update(&mut self, input: &RankingInput<B>, _metadata: &MetricMetadata) -> MetricEntry {
+        let num_queries = input.num_queries();
+        let sum = input
+            .ranked_relevance()
+            .iter()
+            .map(
+                |ranked| match ranked.iter().position(|relevance| *relevance > 0.0) {
+                    Some(position) => 1.0 / (position + 1) as f64,
+                    None => 0.0,
+                },
+            )
+            .sum::<f64>();
+
+        self.state.update(
+            sum / num_queries as f64,
+            num_queries,
+            FormatOptions::new(self.name()).precision(4),
+        )
+    }
+
+    fn clear(&mut self) {
+        self.state.reset()
+    }
+
+    fn name(&self) -> String {
+        "MRR".to_string()
+    }
+}
+
+impl<B: Backend> Numeric for MeanReciprocalRankMetric<B> {
+    fn value(&self) -> f64 {
+        self.state.value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestBackend;
+    use burn_core::tensor::Tensor;
+
+    #[test]
+    fn test_mean_reciprocal_rank() {
+        let device = Default::default();
+        let mut metric = MeanReciprocalRankMetric::<TestBackend>::new();
+        let input = RankingInput::new(
+            Tensor::from_data([[0.9, 0.5, 0.1], [0.1, 0.2, 0.3], [0.3, 0.2, 0.1]], &device),
+            Tensor::from_data([[1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 0.0, 0.0]], &device),
+        );
+
+        let _entry = metric.update(&input, &MetricMetadata::fake());
+
+        // Reciprocal ranks 1, 1/2 and 0.
+        assert!((metric.value() - 0.5).abs() < 1e-6);
+    }
+}
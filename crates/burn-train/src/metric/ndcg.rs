@@ -0,0 +1,119 @@
+use core::marker::PhantomData;
+
+use super::state::{FormatOptions, NumericMetricState};
+use super::{MetricEntry, MetricMetadata, RankingInput};
+use crate::metric::{Metric, Numeric};
+use burn_core::tensor::backend::Backend;
+
+/// The normalized discounted cumulative gain at k (NDCG@k) metric.
+///
+/// The gain of an item is its relevance, discounted by the logarithm of its position. Queries
+/// without any relevant item have a NDCG of 0.
+pub struct NdcgMetric<B: Backend> {
+    state: NumericMetricState,
+    k: usize,
+    _b: PhantomData<B>,
+}
+
+impl<B: Backend> NdcgMetric<B> {
+    /// Creates the metric for the given number of top ranked items.
+    pub fn new(k: usize) -> Self {
+        Self {
+            state: NumericMetricState::default(),
+            k,
+            _b: PhantomData,
+        }
+    }
+}
+
+fn dcg(relevance: &[f64], k: usize) -> f64 {
+    relevance
+        .iter()
+        .take(k)
+        .enumerate()
+        .map(|(position, relevance)| relevance / f64::log2(position as f64 + 2.0))
+        .sum()
+}
+
+fn ndcg(ranked: &[f64], k: usize) -> f64 {
+    let mut ideal = ranked.to_vec();
+    ideal.sort_by(|a, b| b.total_cmp(a));
+
+    let idcg = dcg(&ideal, k);
+    if idcg <= 0.0 {
+        return 0.0;
+    }
+
+    dcg(ranked, k) / idcg
+}
+
+impl<B: Backend> Metric for NdcgMetric<B> {
+    type Input = RankingInput<B>;
+
+    fn update(&mut self, input: &RankingInput<B>, _metadata: &MetricMetadata) -> MetricEntry {
+        let num_queries = input.num_queries();
+        let sum = input
+            .ranked_relevance()
+            .iter()
+            .map(|ranked| ndcg(ranked, self.k))
+            .sum::<f64>();
+
+        self.state.update(
+            sum / num_queries as f64,
+            num_queries,
+            FormatOptions::new(self.name()).precision(4),
+        )
+    }
+
+    fn clear(&mut self) {
+        self.state.reset()
+    }
+
+    fn name(&self) -> String {
+        format!("NDCG@{}", self.k)
+    }
+}
+
+impl<B: Backend> Numeric for NdcgMetric<B> {
+    fn value(&self) -> f64 {
+        self.state.value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestBackend;
+    use burn_core::tensor::Tensor;
+
+    #[test]
+    fn test_ndcg() {
+        let device = Default::default();
+        let mut metric = NdcgMetric::<TestBackend>::new(3);
+        let input = RankingInput::new(
+            Tensor::from_data([[0.9, 0.5, 0.1, 0.0], [0.1, 0.2, 0.3, 0.4]], &device),
+            Tensor::from_data([[3.0, 2.0, 0.0, 1.0], [0.0, 0.0, 0.0, 0.0]], &device),
+        );
+
+        let _entry = metric.update(&input, &MetricMetadata::fake());
+
+        // Ranked relevance [3, 2, 0] against the ideal [3, 2, 1], and no relevant item.
+        let dcg = 3.0 + 2.0 / 3f64.log2();
+        let idcg = dcg + 0.5;
+        assert!((metric.value() - dcg / idcg / 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_ndcg_perfect_ranking() {
+        let device = Default::default();
+        let mut metric = NdcgMetric::<TestBackend>::new(2);
+        let input = RankingInput::new(
+            Tensor::from_data([[0.3, 0.2, 0.1]], &device),
+            Tensor::from_data([[2.0, 1.0, 0.0]], &device),
+        );
+
+        let _entry = metric.update(&input, &MetricMetadata::fake());
+
+        assert!((metric.value() - 1.0).abs() < 1e-6);
+    }
+}
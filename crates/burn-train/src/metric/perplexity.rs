@@ -0,0 +1,122 @@
+use super::MetricEntry;
+use super::MetricMetadata;
+use super::state::FormatOptions;
+use super::state::NumericMetricState;
+use crate::metric::{LossInput, Metric, Numeric};
+use burn_core::tensor::ElementConversion;
+use burn_core::tensor::backend::Backend;
+
+/// The perplexity metric, computed as the exponential of the cross-entropy loss.
+///
+/// The loss is averaged over the epoch before the exponential is applied, so the epoch value is
+/// the perplexity of the whole epoch rather than the mean of the batch perplexities. The loss
+/// must be the mean negative log-likelihood of the tokens in nats, e.g. the
+/// [cross-entropy loss](burn_core::nn::loss::CrossEntropyLoss).
+#[derive(Default)]
+pub struct PerplexityMetric<B: Backend> {
+    state: NumericMetricState,
+    loss_sum: f64,
+    count: usize,
+    _b: B,
+}
+
+impl<B: Backend> PerplexityMetric<B> {
+    /// Create the metric.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<B: Backend> Metric for PerplexityMetric<B> {
+    type Input = LossInput<B>;
+
+    fn update(&mut self, loss: &Self::Input, _metadata: &MetricMetadata) -> MetricEntry {
+        let [batch_size] = loss.tensor.dims();
+        let loss = loss.tensor.clone().mean().into_scalar().elem::<f64>();
+
+        self.loss_sum += loss * batch_size as f64;
+        self.count += batch_size;
+
+        self.state.update_accumulated(
+            f64::exp(loss),
+            f64::exp(self.loss_sum / self.count as f64),
+            batch_size,
+            FormatOptions::new(self.name()).precision(2),
+        )
+    }
+
+    fn epoch_entry(&mut self) -> Option<MetricEntry> {
+        self.state
+            .epoch_entry(FormatOptions::new(self.name()).precision(2))
+    }
+
+    fn clear(&mut self) {
+        self.state.reset();
+        self.loss_sum = 0.0;
+        self.count = 0;
+    }
+
+    fn name(&self) -> String {
+        "Perplexity".to_string()
+    }
+}
+
+impl<B: Backend> Numeric for PerplexityMetric<B> {
+    fn value(&self) -> f64 {
+        self.state.value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::TestBackend;
+    use crate::logger::InMemoryMetricLogger;
+    use crate::metric::processor::{
+        Metrics, MinimalEventProcessor,
+        test_utils::{end_epoch, process_train},
+    };
+    use crate::metric::store::{Aggregate, EventStoreClient, LogEventStore, Split};
+    use burn_core::tensor::Tensor;
+
+    #[test]
+    fn test_perplexity_is_accumulated_over_batches() {
+        let device = Default::default();
+        let mut metric = PerplexityMetric::<TestBackend>::new();
+
+        let _entry = metric.update(
+            &LossInput::new(Tensor::from_data([1.0], &device)),
+            &MetricMetadata::fake(),
+        );
+        assert!((metric.value() - f64::exp(1.0)).abs() < 1e-5);
+
+        let entry = metric.update(
+            &LossInput::new(Tensor::from_data([3.0], &device)),
+            &MetricMetadata::fake(),
+        );
+
+        // exp((1 + 3) / 2) rather than (exp(1) + exp(3)) / 2.
+        assert_eq!(entry.formatted, "epoch 7.39 - batch 20.09");
+    }
+
+    #[test]
+    fn test_perplexity_logs_the_value_of_the_epoch() {
+        let mut store = LogEventStore::default();
+        let mut metrics = Metrics::<f64, f64>::default();
+        store.register_logger_train(InMemoryMetricLogger::default());
+        metrics.register_train_metric_numeric(PerplexityMetric::<TestBackend>::new());
+        let store = Arc::new(EventStoreClient::new(store));
+        let mut processor = MinimalEventProcessor::new(metrics, store.clone());
+
+        process_train(&mut processor, 1.0, 1);
+        process_train(&mut processor, 3.0, 1);
+        end_epoch(&mut processor, 1);
+
+        let value = store
+            .find_metric("Perplexity", 1, Aggregate::Mean, Split::Train)
+            .unwrap();
+        assert!((value - f64::exp(2.0)).abs() < 1e-5);
+    }
+}
@@ -19,6 +19,10 @@ impl<P: EventProcessor + 'static> Worker<P> {
                 match msg {
                     Message::Train(event) => worker.processor.process_train(event),
                     Message::Valid(event) => worker.processor.process_valid(event),
+                    Message::Sync(sender) => {
+                        worker.processor.sync();
+                        sender.send_blocking(()).ok();
+                    }
                 }
             }
         });
@@ -38,6 +42,7 @@ impl<P: EventProcessor + 'static> AsyncProcessor<P> {
 enum Message<P: EventProcessor> {
     Train(Event<P::ItemTrain>),
     Valid(Event<P::ItemValid>),
+    Sync(Sender<()>),
 }
 
 impl<P: EventProcessor> EventProcessor for AsyncProcessor<P> {
@@ -51,4 +56,10 @@ impl<P: EventProcessor> EventProcessor for AsyncProcessor<P> {
     fn process_valid(&mut self, event: Event<Self::ItemValid>) {
         self.sender.send_blocking(Message::Valid(event)).unwrap();
     }
+
+    fn sync(&mut self) {
+        let (sender, receiver) = async_channel::bounded(1);
+        self.sender.send_blocking(Message::Sync(sender)).unwrap();
+        receiver.recv_blocking().unwrap();
+    }
 }
@@ -33,6 +33,8 @@ pub trait EventProcessor: Send {
     fn process_train(&mut self, event: Event<Self::ItemTrain>);
     /// Collect a validation event.
     fn process_valid(&mut self, event: Event<Self::ItemValid>);
+    /// Wait until the collected events are processed, e.g. before reading the logged metrics.
+    fn sync(&mut self) {}
 }
 
 /// A learner item.
@@ -55,6 +57,15 @@ pub struct LearnerItem<T> {
 
     /// The learning rate.
     pub lr: Option<LearningRate>,
+
+    /// The L2 norm of the gradients computed over all parameters before clipping.
+    #[new(default)]
+    pub grad_norm: Option<f64>,
+
+    /// The global step of the training, i.e. the number of optimizer steps, counting the step
+    /// applying the gradients of a training item.
+    #[new(default)]
+    pub step: usize,
 }
 
 impl<T: ItemLazy> ItemLazy for LearnerItem<T> {
@@ -68,6 +79,8 @@ impl<T: ItemLazy> ItemLazy for LearnerItem<T> {
             epoch_total: self.epoch_total,
             iteration: self.iteration,
             lr: self.lr,
+            grad_norm: self.grad_norm,
+            step: self.step,
         }
     }
 }
@@ -58,7 +58,24 @@ impl<T: ItemLazy, V: ItemLazy> EventProcessor for FullEventProcessor<T, V> {
                 self.renderer.render_train(progress);
             }
             Event::EndEpoch(epoch) => {
-                self.metrics.end_epoch_train();
+                let update = self.metrics.end_epoch_train();
+
+                self.store
+                    .add_event_train(crate::metric::store::Event::MetricsUpdate(update.clone()));
+
+                update
+                    .entries
+                    .into_iter()
+                    .for_each(|entry| self.renderer.update_train(MetricState::Generic(entry)));
+
+                update
+                    .entries_numeric
+                    .into_iter()
+                    .for_each(|(entry, value)| {
+                        self.renderer
+                            .update_train(MetricState::Numeric(entry, value))
+                    });
+
                 self.store
                     .add_event_train(crate::metric::store::Event::EndEpoch(epoch));
             }
@@ -96,7 +113,24 @@ impl<T: ItemLazy, V: ItemLazy> EventProcessor for FullEventProcessor<T, V> {
                 self.renderer.render_valid(progress);
             }
             Event::EndEpoch(epoch) => {
-                self.metrics.end_epoch_valid();
+                let update = self.metrics.end_epoch_valid();
+
+                self.store
+                    .add_event_valid(crate::metric::store::Event::MetricsUpdate(update.clone()));
+
+                update
+                    .entries
+                    .into_iter()
+                    .for_each(|entry| self.renderer.update_valid(MetricState::Generic(entry)));
+
+                update
+                    .entries_numeric
+                    .into_iter()
+                    .for_each(|(entry, value)| {
+                        self.renderer
+                            .update_valid(MetricState::Numeric(entry, value))
+                    });
+
                 self.store
                     .add_event_valid(crate::metric::store::Event::EndEpoch(epoch));
             }
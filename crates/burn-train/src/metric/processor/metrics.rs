@@ -73,16 +73,16 @@ impl<T: ItemLazy, V: ItemLazy> Metrics<T, V> {
         let mut entries_numeric = Vec::with_capacity(self.train_numeric.len());
 
         for metric in self.train.iter_mut() {
-            let state = metric.update(item, metadata);
-            entries.push(state);
+            entries.extend(metric.update(item, metadata));
         }
 
         for metric in self.train_numeric.iter_mut() {
-            let (state, value) = metric.update(item, metadata);
-            entries_numeric.push((state, value));
+            entries_numeric.extend(metric.update(item, metadata));
         }
 
-        MetricsUpdate::new(entries, entries_numeric)
+        let mut update = MetricsUpdate::new(entries, entries_numeric);
+        update.step = Some(item.step);
+        update
     }
 
     /// Update the training information from the validation item.
@@ -95,36 +95,54 @@ impl<T: ItemLazy, V: ItemLazy> Metrics<T, V> {
         let mut entries_numeric = Vec::with_capacity(self.valid_numeric.len());
 
         for metric in self.valid.iter_mut() {
-            let state = metric.update(item, metadata);
-            entries.push(state);
+            entries.extend(metric.update(item, metadata));
         }
 
         for metric in self.valid_numeric.iter_mut() {
-            let (state, value) = metric.update(item, metadata);
-            entries_numeric.push((state, value));
+            entries_numeric.extend(metric.update(item, metadata));
         }
 
-        MetricsUpdate::new(entries, entries_numeric)
+        let mut update = MetricsUpdate::new(entries, entries_numeric);
+        update.step = Some(item.step);
+        update
     }
 
     /// Signal the end of a training epoch.
-    pub(crate) fn end_epoch_train(&mut self) {
+    ///
+    /// Returns the [epoch entries](Metric::epoch_entry) of the metrics.
+    pub(crate) fn end_epoch_train(&mut self) -> MetricsUpdate {
+        let mut entries = Vec::new();
+        let mut entries_numeric = Vec::new();
+
         for metric in self.train.iter_mut() {
+            entries.extend(metric.epoch_entry());
             metric.clear();
         }
         for metric in self.train_numeric.iter_mut() {
+            entries_numeric.extend(metric.epoch_entry());
             metric.clear();
         }
+
+        MetricsUpdate::new(entries, entries_numeric)
     }
 
     /// Signal the end of a validation epoch.
-    pub(crate) fn end_epoch_valid(&mut self) {
+    ///
+    /// Returns the [epoch entries](Metric::epoch_entry) of the metrics.
+    pub(crate) fn end_epoch_valid(&mut self) -> MetricsUpdate {
+        let mut entries = Vec::new();
+        let mut entries_numeric = Vec::new();
+
         for metric in self.valid.iter_mut() {
+            entries.extend(metric.epoch_entry());
             metric.clear();
         }
         for metric in self.valid_numeric.iter_mut() {
+            entries_numeric.extend(metric.epoch_entry());
             metric.clear();
         }
+
+        MetricsUpdate::new(entries, entries_numeric)
     }
 }
 
@@ -147,17 +165,24 @@ impl<T> From<&LearnerItem<T>> for MetricMetadata {
             epoch_total: item.epoch_total,
             iteration: item.iteration,
             lr: item.lr,
+            grad_norm: item.grad_norm,
         }
     }
 }
 
 trait NumericMetricUpdater<T>: Send + Sync {
-    fn update(&mut self, item: &LearnerItem<T>, metadata: &MetricMetadata) -> (MetricEntry, f64);
+    fn update(
+        &mut self,
+        item: &LearnerItem<T>,
+        metadata: &MetricMetadata,
+    ) -> Option<(MetricEntry, f64)>;
+    fn epoch_entry(&mut self) -> Option<(MetricEntry, f64)>;
     fn clear(&mut self);
 }
 
 trait MetricUpdater<T>: Send + Sync {
-    fn update(&mut self, item: &LearnerItem<T>, metadata: &MetricMetadata) -> MetricEntry;
+    fn update(&mut self, item: &LearnerItem<T>, metadata: &MetricMetadata) -> Option<MetricEntry>;
+    fn epoch_entry(&mut self) -> Option<MetricEntry>;
     fn clear(&mut self);
 }
 
@@ -172,11 +197,20 @@ where
     M: Metric + Numeric + 'static,
     T: Adaptor<M::Input>,
 {
-    fn update(&mut self, item: &LearnerItem<T>, metadata: &MetricMetadata) -> (MetricEntry, f64) {
-        let update = self.metric.update(&item.item.adapt(), metadata);
+    fn update(
+        &mut self,
+        item: &LearnerItem<T>,
+        metadata: &MetricMetadata,
+    ) -> Option<(MetricEntry, f64)> {
+        let update = self.metric.try_update(&item.item.adapt(), metadata)?;
         let numeric = self.metric.value();
 
-        (update, numeric)
+        Some((update, numeric))
+    }
+
+    fn epoch_entry(&mut self) -> Option<(MetricEntry, f64)> {
+        let entry = self.metric.epoch_entry()?;
+        Some((entry, self.metric.value()))
     }
 
     fn clear(&mut self) {
@@ -190,8 +224,12 @@ where
     M: Metric + 'static,
     T: Adaptor<M::Input>,
 {
-    fn update(&mut self, item: &LearnerItem<T>, metadata: &MetricMetadata) -> MetricEntry {
-        self.metric.update(&item.item.adapt(), metadata)
+    fn update(&mut self, item: &LearnerItem<T>, metadata: &MetricMetadata) -> Option<MetricEntry> {
+        self.metric.try_update(&item.item.adapt(), metadata)
+    }
+
+    fn epoch_entry(&mut self) -> Option<MetricEntry> {
+        self.metric.epoch_entry()
     }
 
     fn clear(&mut self) {
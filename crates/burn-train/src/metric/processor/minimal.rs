@@ -26,7 +26,10 @@ impl<T: ItemLazy, V: ItemLazy> EventProcessor for MinimalEventProcessor<T, V> {
                     .add_event_train(crate::metric::store::Event::MetricsUpdate(update));
             }
             Event::EndEpoch(epoch) => {
-                self.metrics.end_epoch_train();
+                let update = self.metrics.end_epoch_train();
+
+                self.store
+                    .add_event_train(crate::metric::store::Event::MetricsUpdate(update));
                 self.store
                     .add_event_train(crate::metric::store::Event::EndEpoch(epoch));
             }
@@ -46,7 +49,10 @@ impl<T: ItemLazy, V: ItemLazy> EventProcessor for MinimalEventProcessor<T, V> {
                     .add_event_valid(crate::metric::store::Event::MetricsUpdate(update));
             }
             Event::EndEpoch(epoch) => {
-                self.metrics.end_epoch_valid();
+                let update = self.metrics.end_epoch_valid();
+
+                self.store
+                    .add_event_valid(crate::metric::store::Event::MetricsUpdate(update));
                 self.store
                     .add_event_valid(crate::metric::store::Event::EndEpoch(epoch));
             }
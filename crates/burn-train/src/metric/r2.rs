@@ -0,0 +1,131 @@
+use core::marker::PhantomData;
+
+use super::state::{FormatOptions, NumericMetricState};
+use super::{MetricEntry, MetricMetadata, RegressionInput, RegressionStats};
+use crate::metric::{Metric, Numeric};
+use burn_core::tensor::backend::Backend;
+
+/// The coefficient of determination (R²) metric.
+///
+/// The value is computed for each output over all the items of the epoch, then averaged over
+/// the outputs. An output whose targets are constant scores 1 when it is perfectly predicted and
+/// 0 otherwise.
+#[derive(Default)]
+pub struct RSquaredMetric<B: Backend> {
+    state: NumericMetricState,
+    stats: RegressionStats,
+    _b: PhantomData<B>,
+}
+
+impl<B: Backend> RSquaredMetric<B> {
+    /// Creates the metric.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn r2(stats: &RegressionStats) -> f64 {
+    stats.mean_over_outputs(|column| {
+        if column.is_target_constant() {
+            return column.constant_score();
+        }
+
+        1.0 - column.sum_residual_sq / column.target_sum_squares()
+    })
+}
+
+impl<B: Backend> Metric for RSquaredMetric<B> {
+    type Input = RegressionInput<B>;
+
+    fn update(&mut self, input: &RegressionInput<B>, _metadata: &MetricMetadata) -> MetricEntry {
+        let batch = self.stats.update(input);
+
+        self.state.update_accumulated(
+            r2(&batch),
+            r2(&self.stats),
+            input.batch_size(),
+            FormatOptions::new(self.name()).precision(4),
+        )
+    }
+
+    fn epoch_entry(&mut self) -> Option<MetricEntry> {
+        self.state
+            .epoch_entry(FormatOptions::new(self.name()).precision(4))
+    }
+
+    fn clear(&mut self) {
+        self.state.reset();
+        self.stats.reset();
+    }
+
+    fn name(&self) -> String {
+        "R²".to_string()
+    }
+}
+
+impl<B: Backend> Numeric for RSquaredMetric<B> {
+    fn value(&self) -> f64 {
+        self.state.value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestBackend;
+    use burn_core::tensor::Tensor;
+
+    #[test]
+    fn test_r_squared() {
+        let device = Default::default();
+        let mut metric = RSquaredMetric::<TestBackend>::new();
+        let input = RegressionInput::new(
+            Tensor::from_data([[1.0, 2.0], [2.0, 2.0], [4.0, 2.0]], &device),
+            Tensor::from_data([[1.0, 1.0], [3.0, 2.0], [5.0, 3.0]], &device),
+        );
+
+        let _entry = metric.update(&input, &MetricMetadata::fake());
+
+        // First output: 1 - 2 / 8, second output: 1 - 2 / 2.
+        assert!((metric.value() - 0.375).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_r_squared_is_accumulated_over_batches() {
+        let device = Default::default();
+        let mut metric = RSquaredMetric::<TestBackend>::new();
+
+        let _entry = metric.update(
+            &RegressionInput::new(
+                Tensor::from_data([[1.0], [2.0]], &device),
+                Tensor::from_data([[1.0], [3.0]], &device),
+            ),
+            &MetricMetadata::fake(),
+        );
+        let entry = metric.update(
+            &RegressionInput::new(
+                Tensor::from_data([[4.0], [7.0]], &device),
+                Tensor::from_data([[5.0], [7.0]], &device),
+            ),
+            &MetricMetadata::fake(),
+        );
+
+        // Residuals [0, 1, 1, 0] for targets [1, 3, 5, 7] with a mean of 4.
+        assert_eq!(entry.formatted, "epoch 0.9000 - batch 0.5000");
+    }
+
+    #[test]
+    fn test_r_squared_with_constant_targets() {
+        let device = Default::default();
+        let mut metric = RSquaredMetric::<TestBackend>::new();
+        let input = RegressionInput::new(
+            Tensor::from_data([[0.1, 0.1], [0.1, 0.3], [0.1, 0.2]], &device),
+            Tensor::from_data([[0.1, 0.1], [0.1, 0.1], [0.1, 0.1]], &device),
+        );
+
+        let _entry = metric.update(&input, &MetricMetadata::fake());
+
+        // Perfect first output and wrong second output.
+        assert_eq!(metric.value(), 0.5);
+    }
+}
@@ -0,0 +1,38 @@
+use burn_core::tensor::{Tensor, backend::Backend};
+
+/// The input type of the ranking metrics.
+///
+/// Each row is a query and each column a candidate item of that query.
+#[derive(new, Debug, Clone)]
+pub struct RankingInput<B: Backend> {
+    /// Predicted scores with shape `[num_queries, num_items]`, higher is ranked first.
+    pub scores: Tensor<B, 2>,
+    /// Graded relevance of the items with shape `[num_queries, num_items]`, where an item is
+    /// considered relevant when its relevance is positive.
+    pub relevance: Tensor<B, 2>,
+}
+
+impl<B: Backend> RankingInput<B> {
+    /// The number of queries in the batch.
+    pub(crate) fn num_queries(&self) -> usize {
+        self.scores.dims()[0]
+    }
+
+    /// The relevance of the items of each query, sorted by decreasing score.
+    pub(crate) fn ranked_relevance(&self) -> Vec<Vec<f64>> {
+        let [_num_queries, num_items] = self.scores.dims();
+        let scores = self.scores.to_data().iter::<f64>().collect::<Vec<_>>();
+        let relevance = self.relevance.to_data().iter::<f64>().collect::<Vec<_>>();
+
+        scores
+            .chunks(num_items)
+            .zip(relevance.chunks(num_items))
+            .map(|(scores, relevance)| {
+                let mut indices = (0..num_items).collect::<Vec<_>>();
+                // Stable sort, so ties keep the order of the items.
+                indices.sort_by(|a, b| scores[*b].total_cmp(&scores[*a]));
+                indices.into_iter().map(|index| relevance[index]).collect()
+            })
+            .collect()
+    }
+}
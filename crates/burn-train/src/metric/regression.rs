@@ -0,0 +1,162 @@
+use burn_core::tensor::{Tensor, backend::Backend};
+
+/// The input type of the regression metrics.
+#[derive(new, Debug, Clone)]
+pub struct RegressionInput<B: Backend> {
+    /// Predictions with shape `[batch_size, num_outputs]`.
+    pub outputs: Tensor<B, 2>,
+    /// Targets with shape `[batch_size, num_outputs]`.
+    pub targets: Tensor<B, 2>,
+}
+
+impl<B: Backend> RegressionInput<B> {
+    /// The number of items in the batch.
+    pub(crate) fn batch_size(&self) -> usize {
+        self.outputs.dims()[0]
+    }
+
+    /// The outputs and the targets as column major values, one vector per output.
+    pub(crate) fn columns(&self) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+        let [_batch_size, num_outputs] = self.outputs.dims();
+        let columns = |tensor: &Tensor<B, 2>| {
+            let values = tensor.to_data().iter::<f64>().collect::<Vec<_>>();
+            (0..num_outputs)
+                .map(|col| {
+                    values
+                        .iter()
+                        .skip(col)
+                        .step_by(num_outputs)
+                        .copied()
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        (columns(&self.outputs), columns(&self.targets))
+    }
+}
+
+/// Sums accumulated for each output of a regression, from which most regression metrics can be
+/// computed without keeping the items in memory.
+#[derive(Default, Debug, Clone)]
+pub(crate) struct RegressionStats {
+    columns: Vec<ColumnStats>,
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+pub(crate) struct ColumnStats {
+    pub count: f64,
+    pub sum_output: f64,
+    pub sum_output_sq: f64,
+    pub sum_target: f64,
+    pub sum_target_sq: f64,
+    pub sum_output_target: f64,
+    pub sum_residual: f64,
+    pub sum_residual_sq: f64,
+}
+
+impl RegressionStats {
+    /// The statistics of a single batch.
+    pub(crate) fn from_input<B: Backend>(input: &RegressionInput<B>) -> Self {
+        let (outputs, targets) = input.columns();
+        let mut columns = vec![ColumnStats::default(); outputs.len()];
+
+        for ((stats, outputs), targets) in columns.iter_mut().zip(outputs).zip(targets) {
+            for (output, target) in outputs.into_iter().zip(targets) {
+                stats.update(output, target);
+            }
+        }
+
+        Self { columns }
+    }
+
+    /// Accumulate the statistics of the given batch, and return the statistics of the batch.
+    pub(crate) fn update<B: Backend>(&mut self, input: &RegressionInput<B>) -> Self {
+        let batch = Self::from_input(input);
+
+        if self.columns.is_empty() {
+            self.columns = vec![ColumnStats::default(); batch.columns.len()];
+        }
+
+        for (stats, batch) in self.columns.iter_mut().zip(&batch.columns) {
+            stats.merge(batch);
+        }
+
+        batch
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.columns.clear();
+    }
+
+    /// Compute the mean over all outputs of the given per-output metric.
+    pub(crate) fn mean_over_outputs<F: Fn(&ColumnStats) -> f64>(&self, metric: F) -> f64 {
+        let sum = self.columns.iter().map(metric).sum::<f64>();
+        sum / self.columns.len() as f64
+    }
+
+    /// The sum of the given per-output statistic.
+    pub(crate) fn sum<F: Fn(&ColumnStats) -> f64>(&self, statistic: F) -> f64 {
+        self.columns.iter().map(statistic).sum()
+    }
+}
+
+impl ColumnStats {
+    /// Accumulate a single item.
+    pub(crate) fn update(&mut self, output: f64, target: f64) {
+        let residual = target - output;
+
+        self.count += 1.0;
+        self.sum_output += output;
+        self.sum_output_sq += output * output;
+        self.sum_target += target;
+        self.sum_target_sq += target * target;
+        self.sum_output_target += output * target;
+        self.sum_residual += residual;
+        self.sum_residual_sq += residual * residual;
+    }
+
+    /// Accumulate the statistics of other items.
+    fn merge(&mut self, other: &Self) {
+        self.count += other.count;
+        self.sum_output += other.sum_output;
+        self.sum_output_sq += other.sum_output_sq;
+        self.sum_target += other.sum_target;
+        self.sum_target_sq += other.sum_target_sq;
+        self.sum_output_target += other.sum_output_target;
+        self.sum_residual += other.sum_residual;
+        self.sum_residual_sq += other.sum_residual_sq;
+    }
+
+    /// Sum of squares of the targets around their mean.
+    pub(crate) fn target_sum_squares(&self) -> f64 {
+        self.sum_target_sq - self.sum_target * self.sum_target / self.count
+    }
+
+    /// Whether all the targets are equal, up to the rounding errors of the
+    /// [sum of squares](Self::target_sum_squares).
+    pub(crate) fn is_target_constant(&self) -> bool {
+        self.target_sum_squares() <= 1e-12 * self.sum_target_sq
+    }
+
+    /// Whether all the outputs are equal, up to the rounding errors of the
+    /// [sum of squares](Self::output_sum_squares).
+    pub(crate) fn is_output_constant(&self) -> bool {
+        self.output_sum_squares() <= 1e-12 * self.sum_output_sq
+    }
+
+    /// The score of an output whose values have no variance, e.g. with a single item: 1 for
+    /// perfect predictions and 0 otherwise, instead of dividing by zero.
+    pub(crate) fn constant_score(&self) -> f64 {
+        if self.sum_residual_sq == 0.0 {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    /// Sum of squares of the outputs around their mean.
+    pub(crate) fn output_sum_squares(&self) -> f64 {
+        self.sum_output_sq - self.sum_output * self.sum_output / self.count
+    }
+}
@@ -0,0 +1,96 @@
+use core::marker::PhantomData;
+
+use super::state::{FormatOptions, NumericMetricState};
+use super::{MetricEntry, MetricMetadata, RegressionInput, RegressionStats};
+use crate::metric::{Metric, Numeric};
+use burn_core::tensor::backend::Backend;
+
+/// The root mean squared error (RMSE) metric.
+///
+/// The squared errors are accumulated over the epoch, so the epoch value is the RMSE of all the
+/// items rather than the mean of the batch values.
+#[derive(Default)]
+pub struct RootMeanSquaredErrorMetric<B: Backend> {
+    state: NumericMetricState,
+    stats: RegressionStats,
+    _b: PhantomData<B>,
+}
+
+impl<B: Backend> RootMeanSquaredErrorMetric<B> {
+    /// Creates the metric.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn rmse(stats: &RegressionStats) -> f64 {
+    let count = stats.sum(|column| column.count);
+    f64::sqrt(stats.sum(|column| column.sum_residual_sq) / count)
+}
+
+impl<B: Backend> Metric for RootMeanSquaredErrorMetric<B> {
+    type Input = RegressionInput<B>;
+
+    fn update(&mut self, input: &RegressionInput<B>, _metadata: &MetricMetadata) -> MetricEntry {
+        let batch = self.stats.update(input);
+
+        self.state.update_accumulated(
+            rmse(&batch),
+            rmse(&self.stats),
+            input.batch_size(),
+            FormatOptions::new(self.name()).precision(4),
+        )
+    }
+
+    fn epoch_entry(&mut self) -> Option<MetricEntry> {
+        self.state
+            .epoch_entry(FormatOptions::new(self.name()).precision(4))
+    }
+
+    fn clear(&mut self) {
+        self.state.reset();
+        self.stats.reset();
+    }
+
+    fn name(&self) -> String {
+        "RMSE".to_string()
+    }
+}
+
+impl<B: Backend> Numeric for RootMeanSquaredErrorMetric<B> {
+    fn value(&self) -> f64 {
+        self.state.value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestBackend;
+    use burn_core::tensor::Tensor;
+
+    #[test]
+    fn test_rmse_is_accumulated_over_batches() {
+        let device = Default::default();
+        let mut metric = RootMeanSquaredErrorMetric::<TestBackend>::new();
+
+        let _entry = metric.update(
+            &RegressionInput::new(
+                Tensor::from_data([[0.0]], &device),
+                Tensor::from_data([[3.0]], &device),
+            ),
+            &MetricMetadata::fake(),
+        );
+        assert_eq!(3.0, metric.value());
+
+        let entry = metric.update(
+            &RegressionInput::new(
+                Tensor::from_data([[1.0], [2.0], [3.0]], &device),
+                Tensor::from_data([[2.0], [1.0], [6.0]], &device),
+            ),
+            &MetricMetadata::fake(),
+        );
+        // Batch sqrt((1 + 1 + 9) / 3) and epoch sqrt((9 + 1 + 1 + 9) / 4).
+        assert_eq!(entry.formatted, "epoch 2.2361 - batch 1.9149");
+    }
+}
@@ -0,0 +1,103 @@
+use super::state::{FormatOptions, NumericMetricState};
+use super::text_generation::longest_common_subsequence;
+use super::{MetricEntry, MetricMetadata, TextGenerationInput};
+use crate::metric::{Metric, Numeric};
+
+/// The ROUGE-L F-measure, based on the longest common subsequence between the prediction and
+/// the reference, in the range `[0, 100]`.
+///
+/// The F-measure is computed for each sequence, then averaged over all the sequences of the
+/// epoch.
+///
+/// See [ROUGE: A Package for Automatic Evaluation of Summaries](https://aclanthology.org/W04-1013/).
+pub struct RougeLMetric {
+    state: NumericMetricState,
+    beta: f64,
+}
+
+impl RougeLMetric {
+    /// Creates the metric with the F<sub>1</sub> measure.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the weight of the recall relative to the precision in the F-measure.
+    pub fn with_beta(mut self, beta: f64) -> Self {
+        self.beta = beta;
+        self
+    }
+
+    fn f_measure(&self, prediction: &[usize], reference: &[usize]) -> f64 {
+        let lcs = longest_common_subsequence(prediction, reference) as f64;
+        if lcs == 0.0 {
+            return 0.0;
+        }
+
+        let precision = lcs / prediction.len() as f64;
+        let recall = lcs / reference.len() as f64;
+        let beta_sq = self.beta * self.beta;
+
+        (1.0 + beta_sq) * precision * recall / (recall + beta_sq * precision)
+    }
+}
+
+impl Default for RougeLMetric {
+    fn default() -> Self {
+        Self {
+            state: NumericMetricState::default(),
+            beta: 1.0,
+        }
+    }
+}
+
+impl Metric for RougeLMetric {
+    type Input = TextGenerationInput;
+
+    fn update(&mut self, input: &TextGenerationInput, _metadata: &MetricMetadata) -> MetricEntry {
+        let batch_size = input.batch_size();
+        let sum = input
+            .pairs()
+            .map(|(prediction, reference)| self.f_measure(prediction, reference))
+            .sum::<f64>();
+
+        self.state.update(
+            100.0 * sum / batch_size as f64,
+            batch_size,
+            FormatOptions::new(self.name()).precision(2),
+        )
+    }
+
+    fn clear(&mut self) {
+        self.state.reset()
+    }
+
+    fn name(&self) -> String {
+        "ROUGE-L".to_string()
+    }
+}
+
+impl Numeric for RougeLMetric {
+    fn value(&self) -> f64 {
+        self.state.value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rouge_l() {
+        let mut metric = RougeLMetric::new();
+        let input = TextGenerationInput::from_words(
+            &["the cat was found under the bed", "hello"],
+            &["the cat was under the bed", "world"],
+        );
+
+        let _entry = metric.update(&input, &MetricMetadata::fake());
+
+        // Precision 6/7 and recall 1, then no common word.
+        let f1 = 2.0 * (6.0 / 7.0) / (6.0 / 7.0 + 1.0);
+        assert!((metric.value() - 100.0 * f1 / 2.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,201 @@
+use burn_core::tensor::{Int, Tensor, backend::Backend};
+
+/// The input type of the segmentation metrics.
+#[derive(new, Debug, Clone)]
+pub struct SegmentationInput<B: Backend> {
+    /// The class scores with shape `[batch_size, num_classes, height, width]`.
+    pub outputs: Tensor<B, 4>,
+    /// The class of each pixel with shape `[batch_size, height, width]`.
+    pub targets: Tensor<B, 3, Int>,
+}
+
+impl<B: Backend> SegmentationInput<B> {
+    pub(crate) fn batch_size(&self) -> usize {
+        self.targets.dims()[0]
+    }
+}
+
+/// Pixel-wise confusion matrix accumulated over the segmentation masks, where the rows are the
+/// target classes and the columns the predicted classes.
+#[derive(Debug, Clone)]
+pub(crate) struct SegmentationStats {
+    num_classes: usize,
+    ignore_index: Option<usize>,
+    confusion: Vec<usize>,
+}
+
+impl SegmentationStats {
+    pub(crate) fn new(num_classes: usize, ignore_index: Option<usize>) -> Self {
+        Self {
+            num_classes,
+            ignore_index,
+            confusion: vec![0; num_classes * num_classes],
+        }
+    }
+
+    pub(crate) fn num_classes(&self) -> usize {
+        self.num_classes
+    }
+
+    /// Accumulate the pixels of the given batch.
+    ///
+    /// # Panics
+    ///
+    /// If the number of channels of the outputs isn't the number of classes.
+    pub(crate) fn update<B: Backend>(&mut self, input: &SegmentationInput<B>) {
+        let [_, num_channels, _, _] = input.outputs.dims();
+        assert_eq!(
+            num_channels, self.num_classes,
+            "The segmentation outputs have {num_channels} channels, but the metric has {} classes",
+            self.num_classes
+        );
+
+        let predictions = input.outputs.clone().argmax(1).into_data();
+        let targets = input.targets.to_data();
+
+        for (prediction, target) in predictions.iter::<i64>().zip(targets.iter::<i64>()) {
+            let (prediction, target) = (prediction as usize, target as usize);
+
+            if Some(target) == self.ignore_index || target >= self.num_classes {
+                continue;
+            }
+
+            self.confusion[target * self.num_classes + prediction] += 1;
+        }
+    }
+
+    /// The statistics of a single batch.
+    pub(crate) fn batch<B: Backend>(&self, input: &SegmentationInput<B>) -> Self {
+        let mut stats = Self::new(self.num_classes, self.ignore_index);
+        stats.update(input);
+        stats
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.confusion.fill(0);
+    }
+
+    /// The number of pixels of each class that are correctly predicted, predicted and targeted.
+    fn class_counts(&self, class: usize) -> (usize, usize, usize) {
+        let n = self.num_classes;
+        let intersection = self.confusion[class * n + class];
+        let predicted = (0..n)
+            .map(|target| self.confusion[target * n + class])
+            .sum();
+        let targeted = self.confusion[class * n..(class + 1) * n].iter().sum();
+
+        (intersection, predicted, targeted)
+    }
+
+    /// The intersection over union of each class, `None` for classes that are neither predicted
+    /// nor targeted.
+    pub(crate) fn iou(&self) -> Vec<Option<f64>> {
+        (0..self.num_classes)
+            .map(|class| {
+                let (intersection, predicted, targeted) = self.class_counts(class);
+                let union = predicted + targeted - intersection;
+                (union > 0).then(|| intersection as f64 / union as f64)
+            })
+            .collect()
+    }
+
+    /// The Dice coefficient of each class, `None` for classes that are neither predicted nor
+    /// targeted.
+    pub(crate) fn dice(&self) -> Vec<Option<f64>> {
+        (0..self.num_classes)
+            .map(|class| {
+                let (intersection, predicted, targeted) = self.class_counts(class);
+                let total = predicted + targeted;
+                (total > 0).then(|| 2.0 * intersection as f64 / total as f64)
+            })
+            .collect()
+    }
+}
+
+/// The mean of the defined per-class values, or 0 when none is defined.
+pub(crate) fn mean_over_classes(values: &[Option<f64>]) -> f64 {
+    let values = values.iter().flatten().collect::<Vec<_>>();
+
+    match values.len() {
+        0 => 0.0,
+        len => values.into_iter().sum::<f64>() / len as f64,
+    }
+}
+
+/// Format the per-class values as additional lines of the [metric entry](super::MetricEntry).
+pub(crate) fn format_per_class(
+    values: &[Option<f64>],
+    class_names: Option<&[String]>,
+    unit: &str,
+) -> String {
+    let mut formatted = String::new();
+
+    for (class, value) in values.iter().enumerate() {
+        let Some(value) = value else {
+            continue;
+        };
+
+        let name = match class_names.and_then(|names| names.get(class)) {
+            Some(name) => name.clone(),
+            None => format!("Class {class}"),
+        };
+        formatted += &format!("\n{name}: {value:.2}{unit}");
+    }
+
+    formatted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestBackend;
+
+    fn input() -> SegmentationInput<TestBackend> {
+        let device = Default::default();
+        // Predictions [[0, 1], [1, 1]] against targets [[0, 0], [1, 2]].
+        SegmentationInput::new(
+            Tensor::from_data(
+                [[
+                    [[0.9, 0.1], [0.2, 0.0]],
+                    [[0.1, 0.8], [0.7, 0.6]],
+                    [[0.0, 0.1], [0.1, 0.4]],
+                ]],
+                &device,
+            ),
+            Tensor::from_data([[[0, 0], [1, 2]]], &device),
+        )
+    }
+
+    #[test]
+    fn test_iou_and_dice() {
+        let mut stats = SegmentationStats::new(3, None);
+        stats.update(&input());
+
+        assert_eq!(stats.iou(), vec![Some(0.5), Some(1.0 / 3.0), Some(0.0)]);
+        assert_eq!(stats.dice(), vec![Some(2.0 / 3.0), Some(0.5), Some(0.0)]);
+    }
+
+    #[test]
+    fn test_ignore_index() {
+        let mut stats = SegmentationStats::new(3, Some(2));
+        stats.update(&input());
+
+        assert_eq!(stats.iou(), vec![Some(0.5), Some(0.5), None]);
+    }
+
+    #[test]
+    fn test_format_per_class() {
+        let names = ["road".to_string()];
+        let formatted = format_per_class(&[Some(0.5), None, Some(1.0)], Some(&names), " %");
+
+        assert_eq!(formatted, "\nroad: 0.50 %\nClass 2: 1.00 %");
+    }
+
+    #[test]
+    #[should_panic = "The segmentation outputs have 3 channels, but the metric has 2 classes"]
+    fn test_outputs_must_have_a_channel_per_class() {
+        let mut stats = SegmentationStats::new(2, None);
+
+        stats.update(&input());
+    }
+}
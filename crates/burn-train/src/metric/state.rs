@@ -65,11 +65,105 @@ impl NumericMetricState {
         self.count += batch_size;
         self.current = value;
 
-        let value_current = value;
         let value_running = self.sum / self.count as f64;
         // Numeric metric state is an aggregated value
-        let serialized = NumericEntry::Aggregated(value_current, batch_size).serialize();
+        let serialized = NumericEntry::Aggregated(value, batch_size).serialize();
 
+        Self::entry(value, value_running, serialized, format)
+    }
+
+    /// Update the state with a metric computed from statistics accumulated since the start of the
+    /// epoch, rather than averaged over the batches.
+    ///
+    /// This is required for metrics that are not linear in the items, such as the root mean
+    /// squared error, where the mean of the batch values is not the value of the epoch. The
+    /// returned entry is only rendered: the value of the epoch is logged once by the
+    /// [epoch entry](Self::epoch_entry).
+    ///
+    /// # Arguments
+    ///
+    /// * `value_batch` - The value computed only on the current batch.
+    /// * `value_epoch` - The value computed on all the items of the epoch, current batch included.
+    /// * `batch_size` - The number of items in the current batch.
+    /// * `format` - The formatting options.
+    pub fn update_accumulated(
+        &mut self,
+        value_batch: f64,
+        value_epoch: f64,
+        batch_size: usize,
+        format: FormatOptions,
+    ) -> MetricEntry {
+        self.count += batch_size;
+        self.sum = value_epoch * self.count as f64;
+        self.current = value_batch;
+
+        Self::entry(value_batch, value_epoch, String::new(), format)
+    }
+
+    /// Update the state with a metric whose value of the epoch is only computed at the end of the
+    /// epoch, e.g. because it requires all the items.
+    ///
+    /// The returned entry only renders the value of the batch, the value of the epoch is logged
+    /// by the [epoch update](Self::update_epoch).
+    pub fn update_batch(
+        &mut self,
+        value_batch: f64,
+        batch_size: usize,
+        format: FormatOptions,
+    ) -> MetricEntry {
+        self.count += batch_size;
+        self.current = value_batch;
+
+        let formatted = Self::format(value_batch, &format);
+        MetricEntry::new(format.name, format!("batch {formatted}"), String::new())
+    }
+
+    /// Update the state with the value of the whole epoch, and return the entry to log.
+    pub fn update_epoch(&mut self, value_epoch: f64, format: FormatOptions) -> MetricEntry {
+        self.sum = value_epoch * self.count as f64;
+        self.current = value_epoch;
+
+        let formatted = Self::format(value_epoch, &format);
+        let serialized = NumericEntry::Value(value_epoch).serialize();
+
+        MetricEntry::new(format.name, format!("epoch {formatted}"), serialized)
+    }
+
+    /// The entry of the current state without any update, which is only rendered.
+    pub fn current_entry(&self, format: FormatOptions) -> MetricEntry {
+        let value_running = self.sum / self.count as f64;
+
+        Self::entry(self.current, value_running, String::new(), format)
+    }
+
+    /// The entry of the epoch of a metric [accumulated](Self::update_accumulated) over the
+    /// epoch, or `None` when there was no update.
+    pub fn epoch_entry(&mut self, format: FormatOptions) -> Option<MetricEntry> {
+        if self.count == 0 {
+            return None;
+        }
+
+        Some(self.update_epoch(self.sum / self.count as f64, format))
+    }
+
+    fn format(value: f64, format: &FormatOptions) -> String {
+        let formatted = match format.precision {
+            Some(precision) => format_float(value, precision),
+            None => format!("{value}"),
+        };
+
+        match &format.unit {
+            Some(unit) => format!("{formatted} {unit}"),
+            None => formatted,
+        }
+    }
+
+    fn entry(
+        value_current: f64,
+        value_running: f64,
+        serialized: String,
+        format: FormatOptions,
+    ) -> MetricEntry {
         let (formatted_current, formatted_running) = match format.precision {
             Some(precision) => (
                 format_float(value_current, precision),
@@ -100,3 +194,39 @@ impl Default for NumericMetricState {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_accumulated_logs_only_the_epoch_value() {
+        let mut state = NumericMetricState::new();
+        let format = || FormatOptions::new("Metric");
+
+        let entries = [
+            state.update_accumulated(1.0, 1.0, 2, format()),
+            state.update_accumulated(4.0, 2.0, 1, format()),
+            state.update_accumulated(0.0, 3.0, 3, format()),
+        ];
+        assert!(entries.iter().all(|entry| entry.serialize.is_empty()));
+        assert_eq!(entries[2].formatted, "epoch 3 - batch 0");
+        assert_eq!(state.value(), 0.0);
+
+        let entry = state.epoch_entry(format()).unwrap();
+
+        assert_eq!(entry.formatted, "epoch 3");
+        assert!(matches!(
+            NumericEntry::deserialize(&entry.serialize),
+            Ok(NumericEntry::Value(3.0))
+        ));
+        assert_eq!(state.value(), 3.0);
+    }
+
+    #[test]
+    fn test_epoch_entry_without_updates() {
+        let mut state = NumericMetricState::new();
+
+        assert!(state.epoch_entry(FormatOptions::new("Metric")).is_none());
+    }
+}
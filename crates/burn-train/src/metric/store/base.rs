@@ -15,6 +15,10 @@ pub struct MetricsUpdate {
     pub entries: Vec<MetricEntry>,
     /// Metrics information related to numeric metrics.
     pub entries_numeric: Vec<(MetricEntry, f64)>,
+    /// The global step of the training, i.e. the number of optimizer steps, counting the step
+    /// applying the gradients of the item that updated the metrics. None at the end of an epoch.
+    #[new(default)]
+    pub step: Option<usize>,
 }
 
 /// Defines how training and validation events are collected and searched.
@@ -41,6 +45,12 @@ pub trait EventStore: Send {
         aggregate: Aggregate,
         split: Split,
     ) -> Option<f64>;
+
+    /// Resume the collection during the given epoch of a training resumed from a checkpoint,
+    /// after the given number of training items.
+    fn resume(&mut self, epoch: usize, num_items_train: usize) {
+        let _ = (epoch, num_items_train);
+    }
 }
 
 #[derive(Copy, Clone, Hash, PartialEq, Eq, Debug)]
@@ -39,6 +39,14 @@ impl EventStoreClient {
             .expect("Can send event to event store thread.");
     }
 
+    /// Resume the collection during the given epoch of a training resumed from a checkpoint,
+    /// after the given number of training items.
+    pub(crate) fn resume(&self, epoch: usize, num_items_train: usize) {
+        self.sender
+            .send(Message::Resume(epoch, num_items_train))
+            .expect("Can send event to event store thread.");
+    }
+
     /// Find the epoch following the given criteria from the collected data.
     pub fn find_epoch(
         &self,
@@ -120,6 +128,9 @@ where
                 }
                 Message::OnEventTrain(event) => self.store.add_event(event, Split::Train),
                 Message::OnEventValid(event) => self.store.add_event(event, Split::Valid),
+                Message::Resume(epoch, num_items_train) => {
+                    self.store.resume(epoch, num_items_train)
+                }
             }
         }
     }
@@ -128,6 +139,7 @@ where
 enum Message {
     OnEventTrain(Event),
     OnEventValid(Event),
+    Resume(usize, usize),
     End,
     FindEpoch(
         String,
@@ -14,10 +14,16 @@ impl EventStore for LogEventStore {
         match event {
             Event::MetricsUpdate(update) => match split {
                 Split::Train => {
+                    if let Some(step) = update.step {
+                        self.loggers_train
+                            .iter_mut()
+                            .for_each(|logger| logger.set_step(step));
+                    }
                     update
                         .entries
                         .iter()
                         .chain(update.entries_numeric.iter().map(|(entry, _value)| entry))
+                        .filter(|entry| entry.is_logged())
                         .for_each(|entry| {
                             self.loggers_train
                                 .iter_mut()
@@ -25,10 +31,16 @@ impl EventStore for LogEventStore {
                         });
                 }
                 Split::Valid => {
+                    if let Some(step) = update.step {
+                        self.loggers_valid
+                            .iter_mut()
+                            .for_each(|logger| logger.set_step(step));
+                    }
                     update
                         .entries
                         .iter()
                         .chain(update.entries_numeric.iter().map(|(entry, _value)| entry))
+                        .filter(|entry| entry.is_logged())
                         .for_each(|entry| {
                             self.loggers_valid
                                 .iter_mut()
@@ -86,6 +98,16 @@ impl EventStore for LogEventStore {
             }
         }
     }
+
+    fn resume(&mut self, epoch: usize, num_items_train: usize) {
+        self.loggers_train
+            .iter_mut()
+            .for_each(|logger| logger.resume(epoch, num_items_train));
+        // The validation of the epoch always happens after its training.
+        self.loggers_valid
+            .iter_mut()
+            .for_each(|logger| logger.resume(epoch, 0));
+    }
 }
 
 impl LogEventStore {
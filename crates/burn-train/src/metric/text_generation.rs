@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use burn_core::tensor::{Int, Tensor, backend::Backend};
+
+/// The input type of the text generation metrics.
+///
+/// The sequences are made of token ids, so the metrics don't depend on any tokenizer. The ids
+/// only have to be consistent between a prediction and its reference.
+#[derive(new, Debug, Clone, Default)]
+pub struct TextGenerationInput {
+    /// The generated sequences.
+    pub predictions: Vec<Vec<usize>>,
+    /// The reference sequence of each generated sequence.
+    pub references: Vec<Vec<usize>>,
+}
+
+impl TextGenerationInput {
+    /// Creates the input from tensors of token ids with shape `[batch_size, seq_length]`, where
+    /// the given padding tokens are removed.
+    pub fn from_tensors<B: Backend>(
+        predictions: Tensor<B, 2, Int>,
+        references: Tensor<B, 2, Int>,
+        pad_tokens: &[usize],
+    ) -> Self {
+        let sequences = |tensor: Tensor<B, 2, Int>| {
+            let [_batch_size, seq_length] = tensor.dims();
+            let tokens = tensor
+                .into_data()
+                .iter::<i64>()
+                .map(|token| token as usize)
+                .collect::<Vec<_>>();
+
+            tokens
+                .chunks(seq_length.max(1))
+                .map(|sequence| {
+                    sequence
+                        .iter()
+                        .filter(|token| !pad_tokens.contains(token))
+                        .copied()
+                        .collect()
+                })
+                .collect()
+        };
+
+        Self::new(sequences(predictions), sequences(references))
+    }
+
+    /// Creates the input from texts split into characters.
+    pub fn from_chars<S: AsRef<str>>(predictions: &[S], references: &[S]) -> Self {
+        let chars = |texts: &[S]| {
+            texts
+                .iter()
+                .map(|text| text.as_ref().chars().map(|c| c as usize).collect())
+                .collect()
+        };
+
+        Self::new(chars(predictions), chars(references))
+    }
+
+    /// Creates the input from texts split into words on whitespaces.
+    pub fn from_words<S: AsRef<str>>(predictions: &[S], references: &[S]) -> Self {
+        let mut vocab = HashMap::new();
+        let mut words = |texts: &[S]| {
+            texts
+                .iter()
+                .map(|text| {
+                    text.as_ref()
+                        .split_whitespace()
+                        .map(|word| {
+                            let id = vocab.len();
+                            *vocab.entry(word.to_string()).or_insert(id)
+                        })
+                        .collect()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let predictions = words(predictions);
+        let references = words(references);
+
+        Self::new(predictions, references)
+    }
+
+    /// The number of sequences in the batch.
+    pub(crate) fn batch_size(&self) -> usize {
+        self.predictions.len()
+    }
+
+    /// Iterate over the pairs of prediction and reference.
+    pub(crate) fn pairs(&self) -> impl Iterator<Item = (&[usize], &[usize])> {
+        self.predictions
+            .iter()
+            .zip(self.references.iter())
+            .map(|(prediction, reference)| (prediction.as_slice(), reference.as_slice()))
+    }
+}
+
+/// The Levenshtein distance between two sequences.
+pub(crate) fn edit_distance(prediction: &[usize], reference: &[usize]) -> usize {
+    let mut previous = (0..=reference.len()).collect::<Vec<_>>();
+    let mut current = vec![0; reference.len() + 1];
+
+    for (i, token) in prediction.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, expected) in reference.iter().enumerate() {
+            let substitution = previous[j] + usize::from(token != expected);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        core::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[reference.len()]
+}
+
+/// The length of the longest common subsequence of two sequences.
+pub(crate) fn longest_common_subsequence(a: &[usize], b: &[usize]) -> usize {
+    let mut previous = vec![0; b.len() + 1];
+    let mut current = vec![0; b.len() + 1];
+
+    for token in a {
+        for (j, other) in b.iter().enumerate() {
+            current[j + 1] = match token == other {
+                true => previous[j] + 1,
+                false => previous[j + 1].max(current[j]),
+            };
+        }
+        core::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// The count of each n-gram of the given order.
+pub(crate) fn ngram_counts(tokens: &[usize], order: usize) -> HashMap<&[usize], usize> {
+    let mut counts = HashMap::new();
+
+    if order > 0 {
+        for ngram in tokens.windows(order) {
+            *counts.entry(ngram).or_insert(0) += 1;
+        }
+    }
+
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestBackend;
+
+    #[test]
+    fn test_edit_distance() {
+        let input = TextGenerationInput::from_chars(&["kitten"], &["sitting"]);
+        let (prediction, reference) = input.pairs().next().unwrap();
+
+        assert_eq!(edit_distance(prediction, reference), 3);
+        assert_eq!(edit_distance(&[], reference), 7);
+    }
+
+    #[test]
+    fn test_longest_common_subsequence() {
+        assert_eq!(longest_common_subsequence(&[1, 2, 3, 4], &[1, 3, 5, 4]), 3);
+    }
+
+    #[test]
+    fn test_from_words_shares_vocab() {
+        let input = TextGenerationInput::from_words(&["the cat sat"], &["the dog  sat"]);
+
+        assert_eq!(input.predictions, vec![vec![0, 1, 2]]);
+        assert_eq!(input.references, vec![vec![0, 3, 2]]);
+    }
+
+    #[test]
+    fn test_from_tensors_removes_padding() {
+        let device = Default::default();
+        let input = TextGenerationInput::from_tensors(
+            Tensor::<TestBackend, 2, Int>::from_data([[4, 5, 0], [6, 0, 0]], &device),
+            Tensor::<TestBackend, 2, Int>::from_data([[4, 5, 7], [6, 8, 0]], &device),
+            &[0],
+        );
+
+        assert_eq!(input.predictions, vec![vec![4, 5], vec![6]]);
+        assert_eq!(input.references, vec![vec![4, 5, 7], vec![6, 8]]);
+    }
+}
@@ -24,3 +24,16 @@ impl MetricsRenderer for CliMetricsRenderer {
         println!("{:?}", item);
     }
 }
+
+/// A renderer displaying nothing, used by the secondary ranks of a distributed training.
+pub(crate) struct NoopMetricsRenderer;
+
+impl MetricsRenderer for NoopMetricsRenderer {
+    fn update_train(&mut self, _state: MetricState) {}
+
+    fn update_valid(&mut self, _state: MetricState) {}
+
+    fn render_train(&mut self, _item: TrainingProgress) {}
+
+    fn render_valid(&mut self, _item: TrainingProgress) {}
+}
@@ -5,6 +5,7 @@ mod base;
 pub use base::*;
 
 mod cli;
+pub(crate) use cli::NoopMetricsRenderer;
 
 /// The tui renderer
 #[cfg(feature = "tui")]
@@ -69,6 +69,13 @@ impl TextMetricView {
                 Span::from(formatted.to_string()).italic(),
             ]
         };
+        // Additional lines of a formatted entry, e.g. the per-class values of a metric.
+        let detail_line = |formatted: &str| {
+            vec![
+                Span::from("         "),
+                Span::from(formatted.to_string()).italic(),
+            ]
+        };
 
         for name in names {
             lines.push(start_line(name));
@@ -76,11 +83,15 @@ impl TextMetricView {
             let entry = data.get(name).unwrap();
 
             if let Some(entry) = &entry.train {
-                lines.push(train_line(&entry.formatted));
+                let mut formatted = entry.formatted.lines();
+                lines.push(train_line(formatted.next().unwrap_or_default()));
+                lines.extend(formatted.map(detail_line));
             }
 
             if let Some(entry) = &entry.valid {
-                lines.push(valid_line(&entry.formatted));
+                let mut formatted = entry.formatted.lines();
+                lines.push(valid_line(formatted.next().unwrap_or_default()));
+                lines.extend(formatted.map(detail_line));
             }
 
             lines.push(vec![Span::from("")]);
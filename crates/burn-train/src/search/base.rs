@@ -0,0 +1,326 @@
+use super::{
+    Leaderboard, Params, RandomSampler, Sampler, SearchSpace, SuccessiveHalving, TrialPruner,
+    TrialRecord, TrialStatus, config_with_params, pruner::TrialProgress,
+};
+use crate::LearnerSummary;
+use crate::metric::Metric;
+use crate::metric::store::{Direction, Split};
+use burn_core::config::Config;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// A hyperparameter search over the fields of a [config](Config), training a model for every
+/// trial and ranking the trials on a metric in a [leaderboard](Leaderboard).
+///
+/// Every trial is trained in its own `trial-{id}` directory, and its result is appended to the
+/// `leaderboard.jsonl` file of the search directory. Running a search again in the same
+/// directory resumes it, skipping the trials already done.
+///
+/// # Example
+///
+/// ```rust, ignore
+/// let space = SearchSpace::new()
+///     .log_uniform("learning_rate", 1e-5, 1e-2)
+///     .choice("batch_size", [32, 64, 128]);
+///
+/// let leaderboard = HyperparameterSearch::new(
+///     config,
+///     space,
+///     "/tmp/search",
+///     &LossMetric::<B>::new(),
+///     Direction::Lowest,
+///     Split::Valid,
+/// )
+/// .with_sampler(TpeSampler::new(42))
+/// .with_num_trials(50)
+/// .with_successive_halving(SuccessiveHalving::new(1, 3))
+/// .run(|trial| {
+///     let learner = LearnerBuilder::new(trial.directory())
+///         .metric_valid(LossMetric::new())
+///         .callback(trial.pruner())
+///         .num_epochs(trial.config.num_epochs)
+///         .build(model, optim, trial.config.learning_rate);
+///     learner.fit(dataloader_train, dataloader_valid);
+/// })?;
+///
+/// println!("{leaderboard}");
+/// ```
+pub struct HyperparameterSearch<C: Config> {
+    base: C,
+    space: SearchSpace,
+    directory: PathBuf,
+    metric: String,
+    direction: Direction,
+    split: Split,
+    sampler: Box<dyn Sampler>,
+    num_trials: usize,
+    successive_halving: Option<SuccessiveHalving>,
+}
+
+impl<C: Config> HyperparameterSearch<C> {
+    /// Create a new search of 10 random trials.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - The config the searched fields are set in.
+    /// * `space` - The searched fields.
+    /// * `directory` - The directory of the trials and of the leaderboard.
+    /// * `metric` - The objective metric.
+    /// * `direction` - Whether a lower or higher objective is better.
+    /// * `split` - The split the objective metric is logged on.
+    pub fn new<M: Metric>(
+        base: C,
+        space: SearchSpace,
+        directory: impl AsRef<Path>,
+        metric: &M,
+        direction: Direction,
+        split: Split,
+    ) -> Self {
+        Self {
+            base,
+            space,
+            directory: directory.as_ref().to_path_buf(),
+            metric: metric.name(),
+            direction,
+            split,
+            sampler: Box::new(RandomSampler::new(0)),
+            num_trials: 10,
+            successive_halving: None,
+        }
+    }
+
+    /// Set the [sampler](Sampler) choosing the params of the trials, e.g. a
+    /// [grid](super::GridSampler) or [TPE](super::TpeSampler) sampler.
+    pub fn with_sampler<S: Sampler + 'static>(mut self, sampler: S) -> Self {
+        self.sampler = Box::new(sampler);
+        self
+    }
+
+    /// Set the number of trials, including those of a resumed search.
+    ///
+    /// The search also ends when the sampler has no more params to try.
+    pub fn with_num_trials(mut self, num_trials: usize) -> Self {
+        self.num_trials = num_trials;
+        self
+    }
+
+    /// Prune the unpromising trials with [successive halving](SuccessiveHalving).
+    ///
+    /// The trials are only pruned when their [pruner](Trial::pruner) is registered in the
+    /// learner.
+    pub fn with_successive_halving(mut self, successive_halving: SuccessiveHalving) -> Self {
+        self.successive_halving = Some(successive_halving);
+        self
+    }
+
+    /// Run the trials, calling the given function to train the model of each trial.
+    ///
+    /// The values of the objective metric are reported by the [pruner](Trial::pruner) of the
+    /// trial, or otherwise read from the logs of the trial directory once the training ended.
+    pub fn run<F>(&mut self, mut train: F) -> Result<Leaderboard, String>
+    where
+        F: FnMut(&Trial<C>),
+    {
+        fs::create_dir_all(&self.directory).map_err(|err| err.to_string())?;
+        let mut leaderboard =
+            Leaderboard::load(self.directory.join("leaderboard.jsonl"), self.direction)?;
+
+        let scheduler = self.successive_halving.clone().map(|mut scheduler| {
+            for record in leaderboard.records() {
+                scheduler.restore(&record.values, self.direction);
+            }
+            Arc::new(Mutex::new(scheduler))
+        });
+
+        while leaderboard.records().len() < self.num_trials {
+            let id = leaderboard
+                .records()
+                .iter()
+                .map(|record| record.id)
+                .max()
+                .unwrap_or(0)
+                + 1;
+            let Some(params) =
+                self.sampler
+                    .sample(&self.space, leaderboard.records(), self.direction, id)
+            else {
+                break;
+            };
+            let config = config_with_params(&self.base, &params)?;
+
+            // The directory may contain the artifacts of an interrupted trial.
+            let directory = self.directory.join(format!("trial-{id}"));
+            fs::remove_dir_all(&directory).ok();
+            fs::create_dir_all(&directory).map_err(|err| err.to_string())?;
+            config
+                .save(directory.join("config.json"))
+                .map_err(|err| err.to_string())?;
+
+            let progress = Arc::new(Mutex::new(TrialProgress::default()));
+            let trial = Trial {
+                id,
+                params,
+                config,
+                directory,
+                pruner: TrialPruner::new(
+                    self.metric.clone(),
+                    self.split,
+                    self.direction,
+                    scheduler.clone(),
+                    progress.clone(),
+                ),
+            };
+
+            log::info!("Starting trial {id} with {:?}", trial.params);
+            train(&trial);
+
+            let progress = progress.lock().unwrap();
+            let values = match progress.values.is_empty() {
+                true => self.logged_values(&trial.directory),
+                false => progress.values.clone(),
+            };
+            let status = match (values.is_empty(), progress.pruned) {
+                (true, _) => TrialStatus::Failed,
+                (false, true) => TrialStatus::Pruned,
+                (false, false) => TrialStatus::Completed,
+            };
+            let objective = values.iter().copied().reduce(|a, b| match self.direction {
+                Direction::Lowest => a.min(b),
+                Direction::Highest => a.max(b),
+            });
+
+            leaderboard
+                .push(TrialRecord {
+                    id,
+                    params: trial.params,
+                    status,
+                    objective,
+                    values,
+                })
+                .map_err(|err| format!("Could not save the leaderboard: {err}"))?;
+        }
+
+        Ok(leaderboard)
+    }
+
+    /// The epoch values of the objective metric, read from the logs of a trial.
+    fn logged_values(&self, directory: &Path) -> Vec<f64> {
+        let Ok(summary) = LearnerSummary::new(directory, &[&self.metric]) else {
+            return Vec::new();
+        };
+        let metrics = match self.split {
+            Split::Train => summary.metrics.train,
+            Split::Valid => summary.metrics.valid,
+        };
+
+        metrics
+            .into_iter()
+            .flat_map(|metric| metric.entries)
+            .map(|entry| entry.value)
+            .collect()
+    }
+}
+
+/// A trial of a [hyperparameter search](HyperparameterSearch).
+pub struct Trial<C> {
+    /// The identifier of the trial, starting at 1.
+    pub id: usize,
+    /// The values of the searched fields.
+    pub params: Params,
+    /// The config with the searched fields set.
+    pub config: C,
+    directory: PathBuf,
+    pruner: TrialPruner,
+}
+
+impl<C> Trial<C> {
+    /// The directory of the trial, to create the [learner builder](crate::LearnerBuilder) with.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// The [callback](crate::LearnerCallback) reporting the objective metric to the search, and
+    /// pruning the trial with [successive halving](SuccessiveHalving).
+    pub fn pruner(&self) -> TrialPruner {
+        self.pruner.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::LossMetric;
+    use crate::tests::test_directory;
+    use crate::{TestBackend, search::GridSampler};
+    use burn_core as burn;
+
+    #[derive(Config)]
+    struct TrainingConfig {
+        learning_rate: f64,
+        #[config(default = 4)]
+        num_epochs: usize,
+    }
+
+    /// Logs a loss decreasing with the epochs, lower for the learning rates closer to
+    /// 1e-3.
+    fn train(trial: &Trial<TrainingConfig>) {
+        let distance = (trial.config.learning_rate.log10() + 3.0).abs();
+        for epoch in 1..=trial.config.num_epochs {
+            for split in ["train", "valid"] {
+                let dir = trial.directory().join(format!("{split}/epoch-{epoch}"));
+                fs::create_dir_all(&dir).unwrap();
+                let loss = distance + 1.0 / epoch as f64;
+                fs::write(dir.join("Loss.log"), format!("{loss}\n")).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_search_ranks_and_resumes_trials() {
+        let dir = &test_directory("hyperparameter-search");
+        fs::remove_dir_all(dir).ok();
+        let search = |num_trials| {
+            HyperparameterSearch::new(
+                TrainingConfig::new(1e-3),
+                SearchSpace::new().log_uniform("learning_rate", 1e-5, 1e-1),
+                dir,
+                &LossMetric::<TestBackend>::new(),
+                Direction::Lowest,
+                Split::Valid,
+            )
+            .with_sampler(GridSampler::new(5))
+            .with_num_trials(num_trials)
+        };
+
+        let mut num_calls = 0;
+        search(2)
+            .run(|trial| {
+                num_calls += 1;
+                train(trial)
+            })
+            .unwrap();
+        let leaderboard = search(10)
+            .run(|trial| {
+                num_calls += 1;
+                train(trial)
+            })
+            .unwrap();
+
+        // The grid of five learning rates is exhausted after five trials.
+        assert_eq!(num_calls, 5);
+        assert_eq!(leaderboard.records().len(), 5);
+        let best = leaderboard.best().unwrap();
+        assert_eq!(best.id, 3);
+        assert_eq!(best.status, TrialStatus::Completed);
+        assert!((best.objective.unwrap() - 0.25).abs() < 1e-9);
+        assert_eq!(best.values.len(), 4);
+        assert!(
+            leaderboard
+                .to_string()
+                .contains("| 1    | 3     | Completed")
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,197 @@
+use super::Params;
+use crate::metric::store::Direction;
+use crate::tracking::write_table;
+use core::fmt::Display;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// The outcome of a trial.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrialStatus {
+    /// The training of the trial ended.
+    Completed,
+    /// The training was stopped early by [successive halving](super::SuccessiveHalving).
+    Pruned,
+    /// No value of the objective metric was logged.
+    Failed,
+}
+
+/// The result of a trial, saved as a line of the leaderboard file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrialRecord {
+    /// The identifier of the trial, starting at 1.
+    pub id: usize,
+    /// The values of the searched fields.
+    pub params: Params,
+    /// The outcome of the trial.
+    pub status: TrialStatus,
+    /// The best value of the objective metric.
+    pub objective: Option<f64>,
+    /// The value of the objective metric at every epoch.
+    pub values: Vec<f64>,
+}
+
+/// The results of the trials of a [search](super::HyperparameterSearch), saved in a JSON lines
+/// file as they complete, so that an interrupted search can be resumed.
+pub struct Leaderboard {
+    path: PathBuf,
+    records: Vec<TrialRecord>,
+    direction: Direction,
+}
+
+impl Leaderboard {
+    /// Load the leaderboard from its file, or start an empty one when it doesn't exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the leaderboard file.
+    /// * `direction` - Whether a lower or higher objective is better.
+    pub fn load(path: impl AsRef<Path>, direction: Direction) -> Result<Self, String> {
+        let path = path.as_ref().to_path_buf();
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(format!("Could not read {}: {err}", path.display())),
+        };
+
+        let records = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str(line)
+                    .map_err(|err| format!("Invalid trial in {}: {err}", path.display()))
+            })
+            .collect::<Result<Vec<TrialRecord>, _>>()?;
+
+        Ok(Self {
+            path,
+            records,
+            direction,
+        })
+    }
+
+    /// Add the result of a trial, appending it to the file.
+    pub(crate) fn push(&mut self, record: TrialRecord) -> std::io::Result<()> {
+        let mut file = fs::File::options()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let line = serde_json::to_string(&record).map_err(std::io::Error::other)?;
+        writeln!(file, "{line}")?;
+
+        self.records.push(record);
+        Ok(())
+    }
+
+    /// The results of the trials, in the order they ran.
+    pub fn records(&self) -> &[TrialRecord] {
+        &self.records
+    }
+
+    /// The trials with an objective value, from the best to the worst.
+    ///
+    /// The [pruned](TrialStatus::Pruned) trials are ranked after the completed ones, since their
+    /// objective is measured on fewer epochs.
+    pub fn ranked(&self) -> Vec<&TrialRecord> {
+        let mut ranked = self
+            .records
+            .iter()
+            .filter(|record| record.objective.is_some())
+            .collect::<Vec<_>>();
+        ranked.sort_by(|a, b| {
+            let pruned = (a.status == TrialStatus::Pruned).cmp(&(b.status == TrialStatus::Pruned));
+            let (a, b) = (a.objective.unwrap(), b.objective.unwrap());
+            pruned.then(match self.direction {
+                Direction::Lowest => a.total_cmp(&b),
+                Direction::Highest => b.total_cmp(&a),
+            })
+        });
+        ranked
+    }
+
+    /// The completed trial with the best objective value.
+    pub fn best(&self) -> Option<&TrialRecord> {
+        self.ranked()
+            .into_iter()
+            .find(|record| record.status == TrialStatus::Completed)
+    }
+}
+
+impl Display for Leaderboard {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let ranked = self.ranked();
+        let mut fields = ranked
+            .iter()
+            .flat_map(|record| record.params.keys())
+            .cloned()
+            .collect::<Vec<_>>();
+        fields.sort();
+        fields.dedup();
+
+        let mut header = ["Rank", "Trial", "Status", "Objective"]
+            .map(String::from)
+            .to_vec();
+        header.extend(fields.iter().cloned());
+
+        let rows = ranked
+            .iter()
+            .enumerate()
+            .map(|(rank, record)| {
+                let mut row = vec![
+                    (rank + 1).to_string(),
+                    record.id.to_string(),
+                    format!("{:?}", record.status),
+                    format!("{:.4}", record.objective.unwrap_or_default()),
+                ];
+                row.extend(fields.iter().map(|field| match record.params.get(field) {
+                    Some(serde_json::Value::String(value)) => value.clone(),
+                    Some(value) => value.to_string(),
+                    None => "-".to_string(),
+                }));
+                row
+            })
+            .collect::<Vec<_>>();
+
+        write_table(f, &header, &rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: usize, status: TrialStatus, objective: Option<f64>) -> TrialRecord {
+        TrialRecord {
+            id,
+            params: Params::new(),
+            status,
+            objective,
+            values: objective.into_iter().collect(),
+        }
+    }
+
+    #[test]
+    fn test_pruned_trials_are_ranked_after_the_completed_ones() {
+        let leaderboard = Leaderboard {
+            path: PathBuf::new(),
+            records: vec![
+                record(1, TrialStatus::Completed, Some(0.5)),
+                record(2, TrialStatus::Pruned, Some(0.1)),
+                record(3, TrialStatus::Failed, None),
+                record(4, TrialStatus::Completed, Some(0.3)),
+                record(5, TrialStatus::Pruned, Some(0.2)),
+            ],
+            direction: Direction::Lowest,
+        };
+
+        let ranked = leaderboard
+            .ranked()
+            .iter()
+            .map(|record| record.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ranked, [4, 1, 2, 5]);
+        assert_eq!(leaderboard.best().unwrap().id, 4);
+    }
+}
@@ -0,0 +1,11 @@
+mod base;
+mod leaderboard;
+mod pruner;
+mod sampler;
+mod space;
+
+pub use base::*;
+pub use leaderboard::*;
+pub use pruner::*;
+pub use sampler::*;
+pub use space::*;
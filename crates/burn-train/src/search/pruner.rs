@@ -0,0 +1,231 @@
+use crate::learner::{CallbackContext, LearnerCallback};
+use crate::metric::store::{Aggregate, Direction, EventStoreClient, Split};
+use burn_core::tensor::backend::AutodiffBackend;
+use std::sync::{Arc, Mutex};
+
+/// Early termination of the unpromising trials with asynchronous successive halving (ASHA).
+///
+/// The trials are compared at the rungs, every `min_epochs * reduction_factor^k` epochs. A trial
+/// continues past a rung only when its value is in the best `1 / reduction_factor` of the values
+/// reported at that rung by all the trials so far, and is pruned otherwise. While a rung has less
+/// than `reduction_factor` values, only the trials at least as good as the best value continue.
+#[derive(Debug, Clone)]
+pub struct SuccessiveHalving {
+    min_epochs: usize,
+    reduction_factor: usize,
+    rungs: Vec<Vec<f64>>,
+}
+
+impl SuccessiveHalving {
+    /// Create a new successive halving scheduler.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_epochs` - The number of epochs every trial is trained for before the first rung.
+    /// * `reduction_factor` - The inverse of the fraction of the trials promoted at every rung.
+    pub fn new(min_epochs: usize, reduction_factor: usize) -> Self {
+        assert!(
+            min_epochs > 0,
+            "The minimum number of epochs should be positive"
+        );
+        assert!(
+            reduction_factor > 1,
+            "The reduction factor should be at least 2"
+        );
+
+        Self {
+            min_epochs,
+            reduction_factor,
+            rungs: Vec::new(),
+        }
+    }
+
+    /// The rung at the given epoch, if any.
+    fn rung(&self, epoch: usize) -> Option<usize> {
+        let mut milestone = self.min_epochs;
+        let mut rung = 0;
+        while milestone < epoch {
+            milestone *= self.reduction_factor;
+            rung += 1;
+        }
+
+        (milestone == epoch).then_some(rung)
+    }
+
+    /// Report the value of a trial at an epoch, returning whether the trial should continue.
+    pub(crate) fn report(&mut self, epoch: usize, value: f64, direction: Direction) -> bool {
+        let Some(rung) = self.rung(epoch) else {
+            return true;
+        };
+        if self.rungs.len() <= rung {
+            self.rungs.resize(rung + 1, Vec::new());
+        }
+
+        let values = &mut self.rungs[rung];
+        values.push(value);
+        values.sort_by(|a, b| match direction {
+            Direction::Lowest => a.total_cmp(b),
+            Direction::Highest => b.total_cmp(a),
+        });
+
+        let num_promoted = (values.len() / self.reduction_factor).max(1);
+        let threshold = values[num_promoted - 1];
+        match direction {
+            Direction::Lowest => value <= threshold,
+            Direction::Highest => value >= threshold,
+        }
+    }
+
+    /// Add the values of a previous trial to the rungs, when resuming a search.
+    pub(crate) fn restore(&mut self, values: &[f64], direction: Direction) {
+        for (index, value) in values.iter().enumerate() {
+            self.report(index + 1, *value, direction);
+        }
+    }
+}
+
+/// The values of the objective metric reported during a trial.
+#[derive(Default)]
+pub(crate) struct TrialProgress {
+    pub(crate) values: Vec<f64>,
+    pub(crate) pruned: bool,
+}
+
+/// A [callback](LearnerCallback) reporting the objective metric of a trial to the
+/// [search](super::HyperparameterSearch) after every validation, and stopping the training when
+/// the trial is pruned by [successive halving](SuccessiveHalving).
+///
+/// Created by the [trial](super::Trial::pruner), and registered in the learner with
+/// [callback](crate::LearnerBuilder::callback), independently of its
+/// [early stopping strategy](crate::EarlyStoppingStrategy).
+#[derive(Clone)]
+pub struct TrialPruner {
+    metric: String,
+    split: Split,
+    direction: Direction,
+    scheduler: Option<Arc<Mutex<SuccessiveHalving>>>,
+    progress: Arc<Mutex<TrialProgress>>,
+}
+
+impl TrialPruner {
+    pub(crate) fn new(
+        metric: String,
+        split: Split,
+        direction: Direction,
+        scheduler: Option<Arc<Mutex<SuccessiveHalving>>>,
+        progress: Arc<Mutex<TrialProgress>>,
+    ) -> Self {
+        Self {
+            metric,
+            split,
+            direction,
+            scheduler,
+            progress,
+        }
+    }
+}
+
+impl TrialPruner {
+    /// Report the objective metric of the given epoch, returning whether the trial is pruned.
+    fn report(&self, epoch: usize, store: &EventStoreClient) -> bool {
+        let Some(value) = store.find_metric(&self.metric, epoch, Aggregate::Mean, self.split)
+        else {
+            log::warn!(
+                "Can't find the objective metric {} of the trial.",
+                self.metric
+            );
+            return false;
+        };
+
+        let mut progress = self.progress.lock().unwrap();
+        progress.values.push(value);
+
+        let promoted = match &self.scheduler {
+            Some(scheduler) => scheduler
+                .lock()
+                .unwrap()
+                .report(epoch, value, self.direction),
+            None => true,
+        };
+        if !promoted {
+            log::info!(
+                "Trial pruned at epoch {epoch} with {}: {value}",
+                self.metric
+            );
+            progress.pruned = true;
+        }
+
+        !promoted
+    }
+}
+
+impl<B: AutodiffBackend, M, O> LearnerCallback<B, M, O> for TrialPruner {
+    fn on_validation_end(&mut self, ctx: &mut CallbackContext<M, O>, store: &EventStoreClient) {
+        if self.report(ctx.epoch, store) {
+            ctx.stop_training();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::LossMetric;
+    use crate::tests::{
+        regression_dataloaders, regression_learner, regression_model, test_directory,
+    };
+    use burn_core::optim::SgdConfig;
+
+    #[test]
+    fn test_successive_halving_promotes_the_best_trials() {
+        let mut asha = SuccessiveHalving::new(1, 2);
+
+        // Rungs at epochs 1, 2, 4...
+        assert!(asha.report(1, 0.5, Direction::Lowest));
+        assert!(!asha.report(1, 0.6, Direction::Lowest));
+        assert!(asha.report(3, 10.0, Direction::Lowest));
+        // The best half of the three values is the best value.
+        assert!(asha.report(1, 0.4, Direction::Lowest));
+        // With four values, the two best are promoted.
+        assert!(asha.report(1, 0.45, Direction::Lowest));
+        assert!(!asha.report(1, 0.55, Direction::Lowest));
+
+        assert!(asha.report(2, 0.3, Direction::Lowest));
+
+        let mut asha = SuccessiveHalving::new(2, 3);
+        assert!(asha.report(2, 0.9, Direction::Highest));
+        assert!(!asha.report(2, 0.8, Direction::Highest));
+        assert!(asha.report(6, 0.7, Direction::Highest));
+    }
+
+    #[test]
+    fn test_pruner_stops_the_training_of_a_pruned_trial() {
+        let directory = test_directory("search-pruner");
+        let inputs = (0..8).map(|x| x as f32 / 8.0).collect();
+        let (train, valid) = regression_dataloaders(inputs, 2);
+
+        // A previous trial reached a loss no trial can beat at the first rung.
+        let mut scheduler = SuccessiveHalving::new(1, 2);
+        scheduler.restore(&[-1.0], Direction::Lowest);
+        let progress = Arc::new(Mutex::new(TrialProgress::default()));
+        let pruner = TrialPruner::new(
+            "Loss".to_string(),
+            Split::Valid,
+            Direction::Lowest,
+            Some(Arc::new(Mutex::new(scheduler))),
+            progress.clone(),
+        );
+
+        regression_learner(&directory)
+            .metric_valid_numeric(LossMetric::new())
+            .callback(pruner)
+            .num_epochs(3)
+            .build(regression_model(), SgdConfig::new().init(), 0.1)
+            .fit(train, valid);
+        std::fs::remove_dir_all(directory).ok();
+
+        let progress = progress.lock().unwrap();
+        assert!(progress.pruned);
+        assert_eq!(progress.values.len(), 1);
+    }
+}
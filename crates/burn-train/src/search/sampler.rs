@@ -0,0 +1,546 @@
+use super::{Distribution, Params, SearchSpace, TrialRecord, TrialStatus};
+use crate::metric::store::Direction;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Chooses the params of the trials of a [search](super::HyperparameterSearch).
+pub trait Sampler {
+    /// Sample the params of a trial, or return `None` when the search space is exhausted.
+    ///
+    /// # Arguments
+    ///
+    /// * `space` - The search space.
+    /// * `trials` - The results of the previous trials, including those of a resumed search.
+    /// * `direction` - Whether a lower or higher objective is better.
+    /// * `trial` - The identifier of the trial, to seed the sampling so that a resumed search
+    ///   samples the same params.
+    fn sample(
+        &mut self,
+        space: &SearchSpace,
+        trials: &[TrialRecord],
+        direction: Direction,
+        trial: usize,
+    ) -> Option<Params>;
+}
+
+/// Tries every combination of the values of the fields, in order.
+///
+/// The float fields are searched at evenly spaced values, logarithmically for the
+/// [log-uniform](Distribution::LogUniform) fields, and so are the integer fields with more values
+/// than the number of points.
+pub struct GridSampler {
+    num_points: usize,
+}
+
+impl GridSampler {
+    /// Create a grid sampler with the given number of values for the float fields.
+    pub fn new(num_points: usize) -> Self {
+        assert!(num_points > 0, "The number of points should be positive");
+        Self { num_points }
+    }
+
+    fn values(&self, distribution: &Distribution) -> Vec<serde_json::Value> {
+        let points = |low: f64, high: f64| -> Vec<f64> {
+            match self.num_points {
+                1 => vec![(low + high) / 2.0],
+                n => (0..n)
+                    .map(|i| low + (high - low) * i as f64 / (n - 1) as f64)
+                    .collect(),
+            }
+        };
+
+        match distribution {
+            Distribution::Choice(values) => values.clone(),
+            Distribution::Uniform { low, high } => {
+                points(*low, *high).into_iter().map(Into::into).collect()
+            }
+            Distribution::LogUniform { low, high } => points(low.ln(), high.ln())
+                .into_iter()
+                .map(|value| value.exp().into())
+                .collect(),
+            Distribution::Int { low, high } => {
+                // The number of values may not fit in an integer.
+                let num_values = high
+                    .checked_sub(*low)
+                    .and_then(|range| usize::try_from(range).ok())
+                    .and_then(|range| range.checked_add(1));
+                let mut values = match num_values.is_some_and(|num| num <= self.num_points) {
+                    true => (*low..=*high).collect::<Vec<_>>(),
+                    false => points(*low as f64, *high as f64)
+                        .into_iter()
+                        .map(|value| value.round() as i64)
+                        .collect(),
+                };
+                values.dedup();
+                values.into_iter().map(Into::into).collect()
+            }
+        }
+    }
+}
+
+impl Default for GridSampler {
+    fn default() -> Self {
+        Self::new(5)
+    }
+}
+
+impl Sampler for GridSampler {
+    fn sample(
+        &mut self,
+        space: &SearchSpace,
+        trials: &[TrialRecord],
+        _direction: Direction,
+        _trial: usize,
+    ) -> Option<Params> {
+        let values = space
+            .fields
+            .iter()
+            .map(|(_, distribution)| self.values(distribution))
+            .collect::<Vec<_>>();
+        let num_combinations = values.iter().map(Vec::len).product::<usize>();
+
+        (0..num_combinations)
+            .map(|mut index| {
+                // The last field changes the fastest.
+                let mut params = Params::new();
+                for ((field, _), values) in space.fields.iter().zip(values.iter()).rev() {
+                    params.insert(field.clone(), values[index % values.len()].clone());
+                    index /= values.len();
+                }
+                params
+            })
+            .find(|params| {
+                !trials
+                    .iter()
+                    .any(|trial| same_params(&trial.params, params))
+            })
+    }
+}
+
+/// Whether the params are the same, up to the rounding of the floats saved in the leaderboard.
+fn same_params(a: &Params, b: &Params) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b.iter()).all(|((field_a, a), (field_b, b))| {
+            field_a == field_b
+                && match (a.as_f64(), b.as_f64()) {
+                    (Some(a), Some(b)) => (a - b).abs() <= 1e-12 * a.abs().max(b.abs()),
+                    _ => a == b,
+                }
+        })
+}
+
+/// Samples the params of every trial independently from their distribution.
+pub struct RandomSampler {
+    seed: u64,
+}
+
+impl RandomSampler {
+    /// Create a random sampler with the given seed.
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+}
+
+impl Sampler for RandomSampler {
+    fn sample(
+        &mut self,
+        space: &SearchSpace,
+        _trials: &[TrialRecord],
+        _direction: Direction,
+        trial: usize,
+    ) -> Option<Params> {
+        let mut rng = trial_rng(self.seed, trial);
+        Some(sample_random(space, &mut rng))
+    }
+}
+
+/// Samples the params with the tree-structured Parzen estimator (TPE), a Bayesian optimization
+/// method.
+///
+/// The completed trials are split between the best ones, a `gamma` fraction of them, and the
+/// others. For every field, the density of the values of each group is estimated with a mixture
+/// of gaussians, and the value maximizing the ratio between the density of the best trials and
+/// the density of the others is chosen among candidates drawn from the former. The first trials
+/// are sampled randomly.
+pub struct TpeSampler {
+    seed: u64,
+    num_startup_trials: usize,
+    num_candidates: usize,
+    gamma: f64,
+}
+
+impl TpeSampler {
+    /// Create a TPE sampler with the given seed, sampling the first 10 trials randomly.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            num_startup_trials: 10,
+            num_candidates: 24,
+            gamma: 0.25,
+        }
+    }
+
+    /// Set the number of completed trials sampled randomly before the estimation starts.
+    pub fn with_startup_trials(mut self, num_startup_trials: usize) -> Self {
+        self.num_startup_trials = num_startup_trials;
+        self
+    }
+
+    /// Set the number of candidates drawn for every field.
+    pub fn with_candidates(mut self, num_candidates: usize) -> Self {
+        assert!(
+            num_candidates > 0,
+            "The number of candidates should be positive"
+        );
+        self.num_candidates = num_candidates;
+        self
+    }
+
+    /// Set the fraction of the trials considered as the best ones.
+    pub fn with_gamma(mut self, gamma: f64) -> Self {
+        assert!(
+            0.0 < gamma && gamma < 1.0,
+            "Gamma should be between 0 and 1"
+        );
+        self.gamma = gamma;
+        self
+    }
+
+    fn sample_choice(
+        &self,
+        values: &[serde_json::Value],
+        good: &[&Params],
+        bad: &[&Params],
+        field: &str,
+        rng: &mut StdRng,
+    ) -> serde_json::Value {
+        // The counts of the values, with a prior of one.
+        let weights = |trials: &[&Params]| {
+            values
+                .iter()
+                .map(|value| {
+                    let count = trials
+                        .iter()
+                        .filter(|p| p.get(field) == Some(value))
+                        .count();
+                    (count + 1) as f64 / (trials.len() + values.len()) as f64
+                })
+                .collect::<Vec<_>>()
+        };
+        let (good_weights, bad_weights) = (weights(good), weights(bad));
+        let total = good_weights.iter().sum::<f64>();
+
+        (0..self.num_candidates)
+            .map(|_| {
+                let mut target = rng.random::<f64>() * total;
+                let index = good_weights
+                    .iter()
+                    .position(|weight| {
+                        target -= weight;
+                        target < 0.0
+                    })
+                    .unwrap_or(values.len() - 1);
+                (index, good_weights[index] / bad_weights[index])
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(index, _)| values[index].clone())
+            .unwrap()
+    }
+
+    fn sample_numeric(
+        &self,
+        low: f64,
+        high: f64,
+        good: &[f64],
+        bad: &[f64],
+        rng: &mut StdRng,
+    ) -> f64 {
+        let good = ParzenEstimator::new(good, low, high);
+        let bad = ParzenEstimator::new(bad, low, high);
+
+        (0..self.num_candidates)
+            .map(|_| {
+                let value = good.sample(rng);
+                (value, good.log_density(value) - bad.log_density(value))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(value, _)| value)
+            .unwrap()
+    }
+}
+
+impl Sampler for TpeSampler {
+    fn sample(
+        &mut self,
+        space: &SearchSpace,
+        trials: &[TrialRecord],
+        direction: Direction,
+        trial: usize,
+    ) -> Option<Params> {
+        let mut rng = trial_rng(self.seed, trial);
+
+        let mut completed = trials
+            .iter()
+            .filter(|trial| trial.status == TrialStatus::Completed && trial.objective.is_some())
+            .collect::<Vec<_>>();
+        if completed.len() < self.num_startup_trials.max(2) {
+            return Some(sample_random(space, &mut rng));
+        }
+        completed.sort_by(|a, b| {
+            let (a, b) = (a.objective.unwrap(), b.objective.unwrap());
+            match direction {
+                Direction::Lowest => a.total_cmp(&b),
+                Direction::Highest => b.total_cmp(&a),
+            }
+        });
+
+        let num_good = ((completed.len() as f64 * self.gamma).ceil() as usize).max(1);
+        let params = completed.iter().map(|trial| &trial.params);
+        let good = params.clone().take(num_good).collect::<Vec<_>>();
+        let bad = params.skip(num_good).collect::<Vec<_>>();
+
+        let mut params = Params::new();
+        for (field, distribution) in space.fields.iter() {
+            // The numeric fields are estimated in the space where they are uniform.
+            let observations = |trials: &[&Params], transform: fn(f64) -> f64| {
+                trials
+                    .iter()
+                    .filter_map(|params| params.get(field)?.as_f64())
+                    .map(transform)
+                    .collect::<Vec<_>>()
+            };
+            let identity = |value| value;
+
+            let value = match distribution {
+                Distribution::Choice(values) => {
+                    self.sample_choice(values, &good, &bad, field, &mut rng)
+                }
+                Distribution::Uniform { low, high } => self
+                    .sample_numeric(
+                        *low,
+                        *high,
+                        &observations(&good, identity),
+                        &observations(&bad, identity),
+                        &mut rng,
+                    )
+                    .into(),
+                Distribution::LogUniform { low, high } => self
+                    .sample_numeric(
+                        low.ln(),
+                        high.ln(),
+                        &observations(&good, f64::ln),
+                        &observations(&bad, f64::ln),
+                        &mut rng,
+                    )
+                    .exp()
+                    .clamp(*low, *high)
+                    .into(),
+                Distribution::Int { low, high } => (self
+                    .sample_numeric(
+                        *low as f64 - 0.5,
+                        *high as f64 + 0.5,
+                        &observations(&good, identity),
+                        &observations(&bad, identity),
+                        &mut rng,
+                    )
+                    .round() as i64)
+                    .clamp(*low, *high)
+                    .into(),
+            };
+            params.insert(field.clone(), value);
+        }
+
+        Some(params)
+    }
+}
+
+/// A mixture of gaussians centered on the observations, and of a wide gaussian centered between
+/// the bounds acting as a prior.
+struct ParzenEstimator {
+    means: Vec<f64>,
+    std: f64,
+    prior_std: f64,
+    low: f64,
+    high: f64,
+}
+
+impl ParzenEstimator {
+    fn new(observations: &[f64], low: f64, high: f64) -> Self {
+        let range = (high - low).max(f64::EPSILON);
+        let mut means = observations.to_vec();
+        means.push((low + high) / 2.0);
+
+        // The bandwidth shrinks as observations are added, as with Scott's rule.
+        let std = (range * (means.len() as f64).powf(-0.2)).max(range / 100.0);
+
+        Self {
+            means,
+            std,
+            prior_std: range,
+            low,
+            high,
+        }
+    }
+
+    fn std(&self, index: usize) -> f64 {
+        match index == self.means.len() - 1 {
+            true => self.prior_std,
+            false => self.std,
+        }
+    }
+
+    fn sample(&self, rng: &mut StdRng) -> f64 {
+        let index = rng.random_range(0..self.means.len());
+        let (mean, std) = (self.means[index], self.std(index));
+
+        // Rejection sampling of the gaussian truncated to the bounds.
+        for _ in 0..16 {
+            let value = mean + std * standard_normal(rng);
+            if self.low <= value && value <= self.high {
+                return value;
+            }
+        }
+        mean.clamp(self.low, self.high)
+    }
+
+    fn log_density(&self, value: f64) -> f64 {
+        let density = self
+            .means
+            .iter()
+            .enumerate()
+            .map(|(index, mean)| {
+                let std = self.std(index);
+                let z = (value - mean) / std;
+                (-0.5 * z * z).exp() / (std * (2.0 * core::f64::consts::PI).sqrt())
+            })
+            .sum::<f64>()
+            / self.means.len() as f64;
+
+        density.max(f64::MIN_POSITIVE).ln()
+    }
+}
+
+fn standard_normal(rng: &mut StdRng) -> f64 {
+    // Box-Muller transform, with the first uniform value in (0, 1].
+    let u1 = 1.0 - rng.random::<f64>();
+    let u2 = rng.random::<f64>();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * core::f64::consts::PI * u2).cos()
+}
+
+fn trial_rng(seed: u64, trial: usize) -> StdRng {
+    StdRng::seed_from_u64(seed.wrapping_add((trial as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)))
+}
+
+fn sample_random(space: &SearchSpace, rng: &mut StdRng) -> Params {
+    space
+        .fields
+        .iter()
+        .map(|(field, distribution)| {
+            let value = match distribution {
+                Distribution::Choice(values) => values[rng.random_range(0..values.len())].clone(),
+                Distribution::Uniform { low, high } => {
+                    (low + (high - low) * rng.random::<f64>()).into()
+                }
+                Distribution::LogUniform { low, high } => {
+                    let (low, high) = (low.ln(), high.ln());
+                    (low + (high - low) * rng.random::<f64>()).exp().into()
+                }
+                Distribution::Int { low, high } => rng.random_range(*low..=*high).into(),
+            };
+            (field.clone(), value)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(id: usize, params: Params, objective: f64) -> TrialRecord {
+        TrialRecord {
+            id,
+            params,
+            status: TrialStatus::Completed,
+            objective: Some(objective),
+            values: vec![objective],
+        }
+    }
+
+    #[test]
+    fn test_grid_sampler_skips_the_previous_trials() {
+        let space = SearchSpace::new()
+            .choice("optimizer", ["adam", "sgd"])
+            .log_uniform("lr", 1e-4, 1e-2);
+        let mut sampler = GridSampler::new(3);
+        let mut trials = Vec::new();
+
+        while let Some(params) = sampler.sample(&space, &trials, Direction::Lowest, 0) {
+            trials.push(record(trials.len() + 1, params, 0.0));
+        }
+
+        assert_eq!(trials.len(), 6);
+        assert_eq!(trials[0].params["optimizer"], "adam");
+        assert_eq!(trials[3].params["optimizer"], "sgd");
+        let lr = trials[4].params["lr"].as_f64().unwrap();
+        assert!((lr - 1e-3).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_grid_sampler_handles_the_full_integer_range() {
+        let space = SearchSpace::new().int("seed", i64::MIN, i64::MAX);
+        let mut sampler = GridSampler::new(3);
+        let mut trials = Vec::new();
+
+        while let Some(params) = sampler.sample(&space, &trials, Direction::Lowest, 0) {
+            trials.push(record(trials.len() + 1, params, 0.0));
+        }
+
+        assert_eq!(trials.len(), 3);
+        assert_eq!(trials[0].params["seed"], i64::MIN);
+        assert_eq!(trials[2].params["seed"], i64::MAX);
+    }
+
+    #[test]
+    fn test_random_sampler_is_reproducible() {
+        let space = SearchSpace::new()
+            .uniform("dropout", 0.1, 0.5)
+            .int("layers", 1, 4);
+        let mut sampler = RandomSampler::new(42);
+
+        let first = sampler.sample(&space, &[], Direction::Lowest, 1).unwrap();
+        let second = sampler.sample(&space, &[], Direction::Lowest, 2).unwrap();
+
+        assert_eq!(
+            first,
+            sampler.sample(&space, &[], Direction::Lowest, 1).unwrap()
+        );
+        assert_ne!(first, second);
+        let dropout = first["dropout"].as_f64().unwrap();
+        assert!((0.1..=0.5).contains(&dropout));
+        assert!((1..=4).contains(&first["layers"].as_i64().unwrap()));
+    }
+
+    #[test]
+    fn test_tpe_sampler_favors_the_best_region() {
+        let space = SearchSpace::new().uniform("x", 0.0, 10.0);
+        let mut sampler = TpeSampler::new(0).with_startup_trials(5);
+        let objective = |x: f64| (x - 2.0).powi(2);
+
+        let mut trials = Vec::new();
+        for id in 1..=40 {
+            let params = sampler
+                .sample(&space, &trials, Direction::Lowest, id)
+                .unwrap();
+            let x = params["x"].as_f64().unwrap();
+            trials.push(record(id, params, objective(x)));
+        }
+
+        // The last trials are sampled close to the minimum.
+        let last = trials[30..]
+            .iter()
+            .map(|trial| trial.params["x"].as_f64().unwrap())
+            .collect::<Vec<_>>();
+        let mean = last.iter().sum::<f64>() / last.len() as f64;
+        assert!((mean - 2.0).abs() < 1.5, "{last:?}");
+    }
+}
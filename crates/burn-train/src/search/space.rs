@@ -0,0 +1,149 @@
+use burn_core::config::Config;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// The values of the searched fields of a trial, by field path.
+pub type Params = BTreeMap<String, serde_json::Value>;
+
+/// The distribution of the values of a searched field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Distribution {
+    /// One of the given values.
+    Choice(Vec<serde_json::Value>),
+    /// A float uniformly distributed between the bounds.
+    Uniform {
+        /// The lower bound.
+        low: f64,
+        /// The upper bound.
+        high: f64,
+    },
+    /// A float whose logarithm is uniformly distributed between the logarithms of the bounds,
+    /// e.g. for a learning rate.
+    LogUniform {
+        /// The lower bound, strictly positive.
+        low: f64,
+        /// The upper bound.
+        high: f64,
+    },
+    /// An integer uniformly distributed between the bounds, included.
+    Int {
+        /// The lower bound.
+        low: i64,
+        /// The upper bound.
+        high: i64,
+    },
+}
+
+/// The fields of a [config](Config) to search, with the distribution of their values.
+///
+/// The fields are named after their path in the config, e.g. `optimizer.momentum` for the
+/// `momentum` field of the `optimizer` field.
+#[derive(Debug, Clone, Default)]
+pub struct SearchSpace {
+    pub(crate) fields: Vec<(String, Distribution)>,
+}
+
+impl SearchSpace {
+    /// Create an empty search space.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Search a field among the given values.
+    pub fn choice<T: Serialize>(self, field: &str, values: impl IntoIterator<Item = T>) -> Self {
+        let values = values
+            .into_iter()
+            .map(|value| serde_json::to_value(value).expect("Value should be serializable"))
+            .collect::<Vec<_>>();
+        assert!(
+            !values.is_empty(),
+            "The choices of {field} should not be empty"
+        );
+
+        self.field(field, Distribution::Choice(values))
+    }
+
+    /// Search a float field uniformly between the bounds.
+    pub fn uniform(self, field: &str, low: f64, high: f64) -> Self {
+        assert!(low <= high, "The bounds of {field} should be ordered");
+        self.field(field, Distribution::Uniform { low, high })
+    }
+
+    /// Search a float field log-uniformly between the bounds.
+    pub fn log_uniform(self, field: &str, low: f64, high: f64) -> Self {
+        assert!(
+            0.0 < low && low <= high,
+            "The bounds of {field} should be positive and ordered"
+        );
+        self.field(field, Distribution::LogUniform { low, high })
+    }
+
+    /// Search an integer field uniformly between the bounds, included.
+    pub fn int(self, field: &str, low: i64, high: i64) -> Self {
+        assert!(low <= high, "The bounds of {field} should be ordered");
+        self.field(field, Distribution::Int { low, high })
+    }
+
+    fn field(mut self, field: &str, distribution: Distribution) -> Self {
+        self.fields.retain(|(name, _)| name != field);
+        self.fields.push((field.to_string(), distribution));
+        self
+    }
+
+    /// The searched fields with their distribution.
+    pub fn fields(&self) -> &[(String, Distribution)] {
+        &self.fields
+    }
+}
+
+/// Create a config from a base config with the fields set to the params of a trial.
+///
+/// Fails when a field doesn't exist in the config, or when a value has the wrong type.
+pub fn config_with_params<C: Config>(base: &C, params: &Params) -> Result<C, String> {
+    let mut value = serde_json::to_value(base).map_err(|err| err.to_string())?;
+
+    for (field, param) in params {
+        let mut current = &mut value;
+        for name in field.split('.') {
+            current = current
+                .as_object_mut()
+                .and_then(|fields| fields.get_mut(name))
+                .ok_or_else(|| format!("The config has no field {field}"))?;
+        }
+        *current = param.clone();
+    }
+
+    serde_json::from_value(value).map_err(|err| format!("Invalid params {params:?}: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use burn_core as burn;
+
+    #[derive(Config)]
+    struct OptimizerConfig {
+        momentum: f64,
+    }
+
+    #[derive(Config)]
+    struct TrainingConfig {
+        optimizer: OptimizerConfig,
+        batch_size: usize,
+    }
+
+    #[test]
+    fn test_params_are_applied_to_nested_fields() {
+        let base = TrainingConfig::new(OptimizerConfig::new(0.9), 32);
+        let mut params = Params::new();
+        params.insert("optimizer.momentum".into(), 0.99.into());
+        params.insert("batch_size".into(), 64.into());
+
+        let config = config_with_params(&base, &params).unwrap();
+        assert_eq!(config.optimizer.momentum, 0.99);
+        assert_eq!(config.batch_size, 64);
+
+        params.insert("optimizer.lr".into(), 0.1.into());
+        assert!(config_with_params(&base, &params).is_err());
+    }
+}
@@ -0,0 +1,292 @@
+use super::Run;
+use core::fmt::Display;
+use std::collections::BTreeMap;
+
+/// The config fields whose values differ between runs.
+pub struct ConfigDiff {
+    /// The labels of the compared runs.
+    pub runs: Vec<String>,
+    /// The differing fields, sorted by key.
+    pub fields: Vec<ConfigFieldDiff>,
+}
+
+/// The values of a config field in the compared runs.
+pub struct ConfigFieldDiff {
+    /// The key of the field, starting with the config name, e.g. `training.optimizer.momentum`.
+    pub key: String,
+    /// The value of the field in each run, if the run has it.
+    pub values: Vec<Option<serde_json::Value>>,
+}
+
+impl ConfigDiff {
+    /// Compare the [saved configs](Run::save_config) of the runs.
+    pub fn new(runs: &[Run]) -> Result<Self, String> {
+        let mut fields = BTreeMap::<String, Vec<Option<serde_json::Value>>>::new();
+
+        for (index, run) in runs.iter().enumerate() {
+            for (name, config) in run.configs()? {
+                let mut values = Vec::new();
+                flatten_json(&name, &config, &mut values);
+
+                for (key, value) in values {
+                    fields.entry(key).or_insert_with(|| vec![None; runs.len()])[index] =
+                        Some(value);
+                }
+            }
+        }
+
+        let fields = fields
+            .into_iter()
+            .filter(|(_, values)| values.iter().any(|value| value != &values[0]))
+            .map(|(key, values)| ConfigFieldDiff { key, values })
+            .collect();
+
+        Ok(Self {
+            runs: runs.iter().map(run_label).collect(),
+            fields,
+        })
+    }
+}
+
+impl Display for ConfigDiff {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut header = vec!["Field".to_string()];
+        header.extend(self.runs.iter().cloned());
+
+        let rows = self
+            .fields
+            .iter()
+            .map(|field| {
+                let mut row = vec![field.key.clone()];
+                row.extend(field.values.iter().map(|value| match value {
+                    Some(serde_json::Value::String(value)) => value.clone(),
+                    Some(value) => value.to_string(),
+                    None => "-".to_string(),
+                }));
+                row
+            })
+            .collect::<Vec<_>>();
+
+        write_table(f, &header, &rows)
+    }
+}
+
+/// The minimum, maximum and last epoch values of metrics across runs.
+pub struct MetricsTable {
+    /// The rows of the table, by run, split and metric.
+    pub rows: Vec<MetricsTableRow>,
+}
+
+/// The epoch values of a metric in a run, aggregated with the mean over the epochs.
+pub struct MetricsTableRow {
+    /// The label of the run.
+    pub run: String,
+    /// The split, either `Train` or `Valid`.
+    pub split: String,
+    /// The metric name.
+    pub metric: String,
+    /// The minimum value and its epoch.
+    pub min: (f64, usize),
+    /// The maximum value and its epoch.
+    pub max: (f64, usize),
+    /// The value of the last epoch.
+    pub last: f64,
+}
+
+impl MetricsTable {
+    /// Collect the values of the given metrics from the logs of the runs.
+    ///
+    /// The runs without logs are skipped.
+    pub fn new<S: AsRef<str>>(runs: &[Run], metrics: &[S]) -> Self {
+        let mut rows = Vec::new();
+
+        for run in runs {
+            let Ok(summary) = run.summary(metrics) else {
+                continue;
+            };
+            let splits = [
+                ("Train", summary.metrics.train),
+                ("Valid", summary.metrics.valid),
+            ];
+
+            for (split, metrics) in splits {
+                for metric in metrics {
+                    let Some(last) = metric.entries.last() else {
+                        continue;
+                    };
+                    let mut min = (f64::INFINITY, 0);
+                    let mut max = (f64::NEG_INFINITY, 0);
+                    for entry in metric.entries.iter() {
+                        if entry.value < min.0 {
+                            min = (entry.value, entry.step);
+                        }
+                        if entry.value > max.0 {
+                            max = (entry.value, entry.step);
+                        }
+                    }
+
+                    rows.push(MetricsTableRow {
+                        run: run_label(run),
+                        split: split.to_string(),
+                        metric: metric.name.clone(),
+                        min,
+                        max,
+                        last: last.value,
+                    });
+                }
+            }
+        }
+
+        Self { rows }
+    }
+}
+
+impl Display for MetricsTable {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let header = [
+            "Run", "Split", "Metric", "Min.", "Epoch", "Max.", "Epoch", "Last",
+        ]
+        .map(String::from);
+
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| {
+                vec![
+                    row.run.clone(),
+                    row.split.clone(),
+                    row.metric.clone(),
+                    fmt_value(row.min.0),
+                    row.min.1.to_string(),
+                    fmt_value(row.max.0),
+                    row.max.1.to_string(),
+                    fmt_value(row.last),
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        write_table(f, &header, &rows)
+    }
+}
+
+/// The label of a run in the tables, made of its directory name and its name.
+pub(crate) fn run_label(run: &Run) -> String {
+    format!("run-{} ({})", run.info().id, run.info().name)
+}
+
+/// Flatten the fields of nested objects, naming them after their path.
+pub(crate) fn flatten_json(
+    prefix: &str,
+    value: &serde_json::Value,
+    values: &mut Vec<(String, serde_json::Value)>,
+) {
+    match value {
+        serde_json::Value::Object(fields) => {
+            for (name, value) in fields {
+                let name = match prefix.is_empty() {
+                    true => name.clone(),
+                    false => format!("{prefix}.{name}"),
+                };
+                flatten_json(&name, value, values);
+            }
+        }
+        serde_json::Value::Null => {}
+        value => values.push((prefix.to_string(), value.clone())),
+    }
+}
+
+fn fmt_value(value: f64) -> String {
+    if value.abs() < 1e-2 && value != 0.0 {
+        // Use scientific notation for small values which would otherwise be truncated
+        format!("{value:.3e}")
+    } else {
+        format!("{value:.3}")
+    }
+}
+
+/// Write a table with columns fitting their content.
+pub(crate) fn write_table(
+    f: &mut core::fmt::Formatter<'_>,
+    header: &[String],
+    rows: &[Vec<String>],
+) -> core::fmt::Result {
+    let widths = header
+        .iter()
+        .enumerate()
+        .map(|(column, name)| {
+            rows.iter()
+                .map(|row| row[column].len())
+                .fold(name.len(), usize::max)
+        })
+        .collect::<Vec<_>>();
+
+    let write_row = |f: &mut core::fmt::Formatter<'_>, row: &[String]| {
+        for (cell, width) in row.iter().zip(widths.iter()) {
+            write!(f, "| {cell:<width$} ")?;
+        }
+        writeln!(f, "|")
+    };
+
+    write_row(f, header)?;
+    for width in widths.iter() {
+        write!(f, "|{:-<width$}", "", width = width + 2)?;
+    }
+    writeln!(f, "|")?;
+    for row in rows {
+        write_row(f, row)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_directory;
+    use crate::tracking::RunTracker;
+    use burn_core as burn;
+    use burn_core::config::Config;
+
+    #[derive(Config)]
+    struct OptimizerConfig {
+        momentum: f64,
+        #[config(default = 1e-4)]
+        weight_decay: f64,
+    }
+
+    #[test]
+    fn test_configs_and_metrics_are_compared() {
+        let dir = &test_directory("tracking-compare");
+        std::fs::remove_dir_all(dir).ok();
+        let tracker = RunTracker::new(dir);
+
+        let mut runs = Vec::new();
+        for (momentum, losses) in [(0.9, "2.0\n1.0\n"), (0.99, "3.0\n")] {
+            let mut run = tracker.start("sgd").unwrap();
+            run.save_config("optimizer", &OptimizerConfig::new(momentum))
+                .unwrap();
+            let epoch_dir = run.directory().join("train/epoch-1");
+            std::fs::create_dir_all(&epoch_dir).unwrap();
+            std::fs::write(epoch_dir.join("Loss.log"), losses).unwrap();
+            runs.push(run);
+        }
+
+        let diff = ConfigDiff::new(&runs).unwrap();
+        assert_eq!(diff.runs, vec!["run-1 (sgd)", "run-2 (sgd)"]);
+        assert_eq!(diff.fields.len(), 1);
+        assert_eq!(diff.fields[0].key, "optimizer.momentum");
+        assert_eq!(
+            diff.to_string(),
+            "| Field              | run-1 (sgd) | run-2 (sgd) |\n\
+             |--------------------|-------------|-------------|\n\
+             | optimizer.momentum | 0.9         | 0.99        |\n"
+        );
+
+        let table = MetricsTable::new(&runs, &["Loss"]);
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0].last, 1.5);
+        assert_eq!(table.rows[1].min, (3.0, 1));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,198 @@
+use crate::logger::FileMetricLogger;
+use crate::metric::NumericEntry;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// A numeric value logged by a metric during the training.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricRecord {
+    /// The split, either `train` or `valid`.
+    pub split: String,
+    /// The epoch.
+    pub epoch: usize,
+    /// The iteration in the epoch, starting at 1.
+    pub iteration: usize,
+    /// The metric name, as used for its log file.
+    pub metric: String,
+    /// The metric value.
+    pub value: f64,
+}
+
+/// The file formats to export the [metrics history](MetricRecord) of a run to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricsFormat {
+    /// A `metrics.csv` file with a header row.
+    Csv,
+    /// A `metrics.jsonl` file with a JSON object per line.
+    JsonLines,
+    /// A `metrics.parquet` file.
+    #[cfg(feature = "parquet")]
+    Parquet,
+}
+
+/// Read the values logged by the [file metric loggers](FileMetricLogger) of a training
+/// directory, sorted by split, epoch and metric.
+///
+/// The values which aren't numeric are ignored.
+pub fn read_metrics(directory: impl AsRef<Path>) -> Vec<MetricRecord> {
+    let directory = directory.as_ref();
+    let mut records = Vec::new();
+
+    for split in ["train", "valid"] {
+        let logger = FileMetricLogger::new(directory.join(split));
+        let mut epochs = logger.epoch_directories();
+        epochs.sort();
+
+        for (epoch, epoch_directory) in epochs {
+            let mut files = fs::read_dir(epoch_directory)
+                .into_iter()
+                .flatten()
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+                .collect::<Vec<_>>();
+            files.sort();
+
+            for file in files {
+                let Some(metric) = file.file_stem().and_then(|stem| stem.to_str()) else {
+                    continue;
+                };
+                let content = fs::read_to_string(&file).unwrap_or_default();
+                let values = content.lines().map_while(|line| {
+                    match NumericEntry::deserialize(line).ok()? {
+                        NumericEntry::Value(value) => Some(value),
+                        NumericEntry::Aggregated(value, _) => Some(value),
+                    }
+                });
+
+                for (index, value) in values.enumerate() {
+                    records.push(MetricRecord {
+                        split: split.to_string(),
+                        epoch,
+                        iteration: index + 1,
+                        metric: metric.to_string(),
+                        value,
+                    });
+                }
+            }
+        }
+    }
+
+    records
+}
+
+/// Write the metrics history in the given format to the directory.
+pub fn export_metrics(
+    records: &[MetricRecord],
+    directory: impl AsRef<Path>,
+    format: MetricsFormat,
+) -> std::io::Result<()> {
+    let directory = directory.as_ref();
+
+    match format {
+        MetricsFormat::Csv => {
+            let mut file = BufWriter::new(fs::File::create(directory.join("metrics.csv"))?);
+            writeln!(file, "split,epoch,iteration,metric,value")?;
+            for record in records {
+                writeln!(
+                    file,
+                    "{},{},{},{},{}",
+                    record.split,
+                    record.epoch,
+                    record.iteration,
+                    csv_field(&record.metric),
+                    record.value
+                )?;
+            }
+            file.flush()
+        }
+        MetricsFormat::JsonLines => {
+            let mut file = BufWriter::new(fs::File::create(directory.join("metrics.jsonl"))?);
+            for record in records {
+                serde_json::to_writer(&mut file, record)?;
+                writeln!(file)?;
+            }
+            file.flush()
+        }
+        #[cfg(feature = "parquet")]
+        MetricsFormat::Parquet => {
+            use polars::prelude::*;
+
+            let mut df = df!(
+                "split" => records.iter().map(|record| record.split.as_str()).collect::<Vec<_>>(),
+                "epoch" => records.iter().map(|record| record.epoch as u64).collect::<Vec<_>>(),
+                "iteration" => records.iter().map(|record| record.iteration as u64).collect::<Vec<_>>(),
+                "metric" => records.iter().map(|record| record.metric.as_str()).collect::<Vec<_>>(),
+                "value" => records.iter().map(|record| record.value).collect::<Vec<_>>(),
+            )
+            .map_err(std::io::Error::other)?;
+            let file = fs::File::create(directory.join("metrics.parquet"))?;
+            ParquetWriter::new(file)
+                .finish(&mut df)
+                .map_err(std::io::Error::other)?;
+            Ok(())
+        }
+    }
+}
+
+/// Quote a CSV field when it contains a separator, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_directory;
+
+    #[test]
+    fn test_metrics_history_is_read_and_exported() {
+        let dir = &test_directory("tracking-metrics-history");
+        fs::create_dir_all(dir.join("train/epoch-1")).unwrap();
+        fs::create_dir_all(dir.join("train/epoch-2")).unwrap();
+        fs::create_dir_all(dir.join("valid/epoch-1")).unwrap();
+        fs::write(dir.join("train/epoch-1/Loss.log"), "2.0\n1.5\n").unwrap();
+        fs::write(dir.join("train/epoch-2/Loss.log"), "1.0\n").unwrap();
+        fs::write(dir.join("valid/epoch-1/Accuracy.log"), "0.5,10\n").unwrap();
+
+        let records = read_metrics(dir);
+
+        let values = records
+            .iter()
+            .map(|r| {
+                (
+                    r.split.as_str(),
+                    r.epoch,
+                    r.iteration,
+                    r.metric.as_str(),
+                    r.value,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![
+                ("train", 1, 1, "Loss", 2.0),
+                ("train", 1, 2, "Loss", 1.5),
+                ("train", 2, 1, "Loss", 1.0),
+                ("valid", 1, 1, "Accuracy", 0.5),
+            ]
+        );
+
+        export_metrics(&records, dir, MetricsFormat::Csv).unwrap();
+        export_metrics(&records, dir, MetricsFormat::JsonLines).unwrap();
+        let csv = fs::read_to_string(dir.join("metrics.csv")).unwrap();
+        assert_eq!(csv.lines().nth(1), Some("train,1,1,Loss,2"));
+        let jsonl = fs::read_to_string(dir.join("metrics.jsonl")).unwrap();
+        let last: MetricRecord = serde_json::from_str(jsonl.lines().last().unwrap()).unwrap();
+        assert_eq!(last, records[3]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The version of the layout of the run directories, increased when it changes.
+pub const RUN_FORMAT_VERSION: u32 = 1;
+
+/// The prefixes of the environment variables recorded with a run, which may change the behavior
+/// of the backends.
+const ENV_PREFIXES: [&str; 5] = ["BURN_", "CUBECL_", "CUDA_", "HIP_", "WGPU_"];
+
+/// The status of a run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RunStatus {
+    /// The training is running, or the process was killed before its end.
+    Running,
+    /// The training completed, possibly stopped early by a strategy or a callback.
+    Completed,
+    /// The training was interrupted by the [interrupter](crate::TrainingInterrupter).
+    Interrupted,
+    /// The training panicked, or the learner was dropped before the end of the training.
+    Failed,
+}
+
+impl core::fmt::Display for RunStatus {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Running => f.write_str("running"),
+            Self::Completed => f.write_str("completed"),
+            Self::Interrupted => f.write_str("interrupted"),
+            Self::Failed => f.write_str("failed"),
+        }
+    }
+}
+
+/// The metadata of a [run](crate::tracking::Run), saved in its `run.json` file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunInfo {
+    /// The version of the layout of the run directory.
+    pub version: u32,
+    /// The identifier of the run, unique in its tracking directory.
+    pub id: usize,
+    /// The name of the run.
+    pub name: String,
+    /// The status of the run.
+    pub status: RunStatus,
+    /// When the run started, in seconds since the Unix epoch.
+    pub started: u64,
+    /// When the training ended, in seconds since the Unix epoch.
+    pub finished: Option<u64>,
+    /// The number of epochs completed, known once the training ended.
+    pub epochs: Option<usize>,
+    /// The names of the configs saved with the run.
+    pub configs: Vec<String>,
+    /// The state of the git repository the run was started from.
+    pub git: Option<GitInfo>,
+    /// The environment of the process.
+    pub environment: EnvironmentInfo,
+    /// The hardware of the machine.
+    pub hardware: HardwareInfo,
+}
+
+impl RunInfo {
+    pub(crate) fn new(id: usize, name: &str, command_args: bool) -> Self {
+        Self {
+            version: RUN_FORMAT_VERSION,
+            id,
+            name: name.to_string(),
+            status: RunStatus::Running,
+            started: timestamp(),
+            finished: None,
+            epochs: None,
+            configs: Vec::new(),
+            git: GitInfo::collect(),
+            environment: EnvironmentInfo::collect(command_args),
+            hardware: HardwareInfo::collect(),
+        }
+    }
+
+    pub(crate) fn load(directory: &Path) -> Result<Self, String> {
+        let path = directory.join("run.json");
+        let content = std::fs::read_to_string(&path)
+            .map_err(|err| format!("Could not read {}: {err}", path.display()))?;
+        serde_json::from_str(&content)
+            .map_err(|err| format!("Invalid run file {}: {err}", path.display()))
+    }
+
+    pub(crate) fn save(&self, directory: &Path) -> std::io::Result<()> {
+        let content = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(directory.join("run.json"), content)
+    }
+
+    /// The duration of the run in seconds, up to now when the training hasn't ended.
+    pub fn duration(&self) -> u64 {
+        self.finished
+            .unwrap_or_else(timestamp)
+            .saturating_sub(self.started)
+    }
+}
+
+/// The commit of the git repository a run was started from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitInfo {
+    /// The hash of the checked out commit.
+    pub commit: String,
+    /// Whether the working tree had uncommitted changes.
+    pub dirty: bool,
+}
+
+impl GitInfo {
+    /// Read the state of the repository of the working directory, if git is installed and it is
+    /// in a repository.
+    pub fn collect() -> Option<Self> {
+        let output = Command::new("git")
+            .args(["rev-parse", "HEAD"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())?;
+        let commit = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let dirty = Command::new("git")
+            .args(["status", "--porcelain", "--untracked-files=no"])
+            .output()
+            .map(|output| !output.stdout.is_empty())
+            .unwrap_or(false);
+
+        Some(Self { commit, dirty })
+    }
+}
+
+/// The environment of the process of a run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvironmentInfo {
+    /// The version of burn.
+    pub burn_version: String,
+    /// The operating system.
+    pub os: String,
+    /// The architecture of the CPU.
+    pub arch: String,
+    /// The name of the machine.
+    pub hostname: Option<String>,
+    /// The command line of the process, only its program unless the arguments are
+    /// [recorded](crate::tracking::RunTracker::with_command_args), as they may contain secrets.
+    pub command: Vec<String>,
+    /// The working directory of the process.
+    pub working_directory: Option<String>,
+    /// The environment variables configuring burn and the backends.
+    pub variables: BTreeMap<String, String>,
+}
+
+impl EnvironmentInfo {
+    /// Collect the environment of the current process.
+    ///
+    /// # Arguments
+    ///
+    /// * `command_args` - Whether to record the arguments of the command line, besides the
+    ///   program.
+    pub fn collect(command_args: bool) -> Self {
+        let variables = std::env::vars()
+            .filter(|(name, _)| ENV_PREFIXES.iter().any(|prefix| name.starts_with(prefix)))
+            .collect();
+
+        Self {
+            burn_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            hostname: hostname(),
+            command: match command_args {
+                true => std::env::args().collect(),
+                false => std::env::args().take(1).collect(),
+            },
+            working_directory: std::env::current_dir()
+                .ok()
+                .map(|dir| dir.display().to_string()),
+            variables,
+        }
+    }
+}
+
+/// The hardware a run is trained on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HardwareInfo {
+    /// The brand of the CPU.
+    pub cpu: Option<String>,
+    /// The number of logical CPUs.
+    pub num_cpus: usize,
+    /// The total memory in bytes.
+    pub memory: Option<u64>,
+    /// The devices the model is trained on.
+    pub devices: Vec<String>,
+}
+
+impl HardwareInfo {
+    /// Collect the hardware of the machine. The devices are set by the
+    /// [learner](crate::LearnerBuilder::tracking).
+    #[cfg(feature = "sys-metrics")]
+    pub fn collect() -> Self {
+        let mut sys = sysinfo::System::new();
+        sys.refresh_cpu_all();
+        sys.refresh_memory();
+
+        Self {
+            cpu: sys.cpus().first().map(|cpu| cpu.brand().trim().to_string()),
+            num_cpus: num_cpus(),
+            memory: Some(sys.total_memory()),
+            devices: Vec::new(),
+        }
+    }
+
+    /// Collect the hardware of the machine. The devices are set by the
+    /// [learner](crate::LearnerBuilder::tracking).
+    #[cfg(not(feature = "sys-metrics"))]
+    pub fn collect() -> Self {
+        Self {
+            cpu: None,
+            num_cpus: num_cpus(),
+            memory: None,
+            devices: Vec::new(),
+        }
+    }
+}
+
+fn num_cpus() -> usize {
+    std::thread::available_parallelism()
+        .map(|num| num.get())
+        .unwrap_or(1)
+}
+
+#[cfg(feature = "sys-metrics")]
+fn hostname() -> Option<String> {
+    sysinfo::System::host_name()
+}
+
+#[cfg(not(feature = "sys-metrics"))]
+fn hostname() -> Option<String> {
+    std::env::var("HOSTNAME").ok()
+}
+
+/// The current time in seconds since the Unix epoch.
+pub(crate) fn timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
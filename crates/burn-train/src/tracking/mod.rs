@@ -0,0 +1,11 @@
+mod compare;
+mod history;
+mod info;
+mod run;
+mod tracker;
+
+pub use compare::*;
+pub use history::*;
+pub use info::*;
+pub use run::*;
+pub use tracker::*;
@@ -0,0 +1,182 @@
+use super::{
+    MetricRecord, MetricsFormat, RunInfo, RunStatus, export_metrics, read_metrics, timestamp,
+};
+use crate::LearnerSummary;
+use burn_core::config::Config;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CONFIG_DIR: &str = "config";
+
+/// A training run recorded in a versioned directory of a [tracker](crate::tracking::RunTracker).
+///
+/// Along the logs and checkpoints of the [learner](crate::Learner), the directory contains:
+///
+/// - `run.json`: the [metadata](RunInfo) of the run, i.e. its status, git commit, environment
+///   and hardware.
+/// - `config/{name}.json`: the [configs](Config) saved with [save_config](Run::save_config).
+/// - `metrics.{csv,jsonl,parquet}`: the [metrics history](MetricRecord), once the training ended.
+/// - `summary.json` and `summary.txt`: the final [summary](LearnerSummary) of the training.
+///
+/// A run dropped during its training, e.g. when the training panics, is marked as
+/// [failed](RunStatus::Failed).
+pub struct Run {
+    directory: PathBuf,
+    info: RunInfo,
+    formats: Vec<MetricsFormat>,
+    summary_metrics: Vec<String>,
+    // Whether the training started and hasn't finished.
+    training: bool,
+}
+
+impl Run {
+    pub(crate) fn create(directory: PathBuf, info: RunInfo) -> std::io::Result<Self> {
+        fs::create_dir_all(directory.join(CONFIG_DIR))?;
+        info.save(&directory)?;
+
+        Ok(Self {
+            directory,
+            info,
+            formats: vec![MetricsFormat::Csv, MetricsFormat::JsonLines],
+            summary_metrics: Vec::new(),
+            training: false,
+        })
+    }
+
+    /// Open the run saved in the given directory.
+    pub fn open(directory: impl AsRef<Path>) -> Result<Self, String> {
+        let directory = directory.as_ref().to_path_buf();
+        let info = RunInfo::load(&directory)?;
+
+        Ok(Self {
+            directory,
+            info,
+            formats: vec![MetricsFormat::Csv, MetricsFormat::JsonLines],
+            summary_metrics: Vec::new(),
+            training: false,
+        })
+    }
+
+    /// The directory of the run, to create the [learner builder](crate::LearnerBuilder) with.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// The metadata of the run.
+    pub fn info(&self) -> &RunInfo {
+        &self.info
+    }
+
+    /// The formats the metrics history is exported to at the end of the training.
+    ///
+    /// Defaults to CSV and JSON lines.
+    pub fn with_metrics_formats(mut self, formats: &[MetricsFormat]) -> Self {
+        self.formats = formats.to_vec();
+        self
+    }
+
+    /// Save a config of the run, e.g. of the model, the optimizer or the dataset.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the config, used for its file.
+    /// * `config` - The config.
+    pub fn save_config<C: Config>(&mut self, name: &str, config: &C) -> std::io::Result<()> {
+        config.save(self.config_path(name))?;
+
+        if !self.info.configs.iter().any(|config| config == name) {
+            self.info.configs.push(name.to_string());
+        }
+        self.info.save(&self.directory)
+    }
+
+    /// The saved configs of the run by name, as JSON values.
+    pub fn configs(&self) -> Result<BTreeMap<String, serde_json::Value>, String> {
+        self.info
+            .configs
+            .iter()
+            .map(|name| {
+                let path = self.config_path(name);
+                let content = fs::read_to_string(&path)
+                    .map_err(|err| format!("Could not read {}: {err}", path.display()))?;
+                let value = serde_json::from_str(&content)
+                    .map_err(|err| format!("Invalid config {}: {err}", path.display()))?;
+                Ok((name.clone(), value))
+            })
+            .collect()
+    }
+
+    /// The values logged by the metrics of the run.
+    pub fn metrics(&self) -> Vec<MetricRecord> {
+        read_metrics(&self.directory)
+    }
+
+    /// The summary of the given metrics, computed from the logs of the run.
+    pub fn summary<S: AsRef<str>>(&self, metrics: &[S]) -> Result<LearnerSummary, String> {
+        LearnerSummary::new(&self.directory, metrics)
+    }
+
+    fn config_path(&self, name: &str) -> PathBuf {
+        self.directory.join(CONFIG_DIR).join(format!("{name}.json"))
+    }
+
+    /// Record the devices of the learner and the metrics to summarize at the end of the training.
+    pub(crate) fn start_training(&mut self, devices: Vec<String>, summary_metrics: Vec<String>) {
+        self.info.hardware.devices = devices;
+        self.summary_metrics = summary_metrics;
+        self.training = true;
+
+        if let Err(err) = self.info.save(&self.directory) {
+            log::error!("Could not save the run info:\n{err}");
+        }
+    }
+
+    /// Export the metrics history and the summary of the training, and mark the run as ended.
+    pub(crate) fn finish_training(mut self, status: RunStatus, model: String) {
+        let records = self.metrics();
+        for format in self.formats.iter() {
+            if let Err(err) = export_metrics(&records, &self.directory, *format) {
+                log::error!("Could not export the metrics history to {format:?}:\n{err}");
+            }
+        }
+
+        match self.summary(&self.summary_metrics) {
+            Ok(summary) => {
+                let summary = summary.with_model(model);
+                self.info.epochs = Some(summary.epochs);
+
+                let result = serde_json::to_string_pretty(&summary)
+                    .map_err(std::io::Error::other)
+                    .and_then(|json| fs::write(self.directory.join("summary.json"), json))
+                    .and_then(|_| {
+                        fs::write(self.directory.join("summary.txt"), summary.to_string())
+                    });
+                if let Err(err) = result {
+                    log::error!("Could not save the run summary:\n{err}");
+                }
+            }
+            Err(err) => log::error!("Could not retrieve the run summary:\n{err}"),
+        }
+
+        self.finish(status);
+    }
+
+    fn finish(&mut self, status: RunStatus) {
+        self.training = false;
+        self.info.status = status;
+        self.info.finished = Some(timestamp());
+        if let Err(err) = self.info.save(&self.directory) {
+            log::error!("Could not save the run info:\n{err}");
+        }
+    }
+}
+
+impl Drop for Run {
+    fn drop(&mut self) {
+        // The learner was dropped before the end of the training, e.g. when it panicked.
+        if self.training {
+            self.finish(RunStatus::Failed);
+        }
+    }
+}
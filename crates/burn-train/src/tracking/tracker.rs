@@ -0,0 +1,199 @@
+use super::{Run, RunInfo};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const RUN_PREFIX: &str = "run-";
+
+/// Records the training runs of an experiment in versioned directories, named `run-{id}` after
+/// the increasing identifier of the runs.
+///
+/// # Example
+///
+/// ```rust, ignore
+/// let mut run = RunTracker::new("/tmp/experiment").start("baseline")?;
+/// run.save_config("training", &config)?;
+///
+/// let learner = LearnerBuilder::new(run.directory())
+///     .metric_valid(LossMetric::new())
+///     .tracking(run)
+///     .build(model, optim, lr);
+/// ```
+pub struct RunTracker {
+    directory: PathBuf,
+    command_args: bool,
+}
+
+impl RunTracker {
+    /// Create a tracker recording the runs in the given directory.
+    pub fn new(directory: impl AsRef<Path>) -> Self {
+        Self {
+            directory: directory.as_ref().to_path_buf(),
+            command_args: false,
+        }
+    }
+
+    /// Record the arguments of the command line with the environment of the runs.
+    ///
+    /// Disabled by default, since the arguments may contain secrets, e.g. tokens or passwords.
+    pub fn with_command_args(mut self, enabled: bool) -> Self {
+        self.command_args = enabled;
+        self
+    }
+
+    /// Start a new run, with the next identifier.
+    ///
+    /// The git commit, environment and hardware are recorded when the run is created.
+    pub fn start(&self, name: &str) -> std::io::Result<Run> {
+        fs::create_dir_all(&self.directory)?;
+
+        let mut id = self.last_id().map(|id| id + 1).unwrap_or(1);
+        loop {
+            // Creating the directory reserves the identifier against concurrent runs.
+            let directory = self.directory.join(format!("{RUN_PREFIX}{id}"));
+            match fs::create_dir(&directory) {
+                Ok(()) => {
+                    let info = RunInfo::new(id, name, self.command_args);
+                    return Run::create(directory, info);
+                }
+                Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => id += 1,
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// The recorded runs, sorted by identifier.
+    pub fn runs(&self) -> Vec<Run> {
+        let mut runs = self
+            .run_directories()
+            .into_iter()
+            .filter_map(|(_, directory)| match Run::open(&directory) {
+                Ok(run) => Some(run),
+                Err(err) => {
+                    log::warn!("Skipping the invalid run {}: {err}", directory.display());
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        runs.sort_by_key(|run| run.info().id);
+        runs
+    }
+
+    /// Find a run by identifier, directory name or name. With a name, the most recent run with
+    /// that name is returned.
+    pub fn run(&self, key: &str) -> Result<Run, String> {
+        let key_id = key
+            .strip_prefix(RUN_PREFIX)
+            .unwrap_or(key)
+            .parse::<usize>()
+            .ok();
+
+        self.runs()
+            .into_iter()
+            .rev()
+            .find(|run| Some(run.info().id) == key_id || run.info().name == key)
+            .ok_or_else(|| format!("No run '{key}' in {}", self.directory.display()))
+    }
+
+    fn last_id(&self) -> Option<usize> {
+        self.run_directories().into_iter().map(|(id, _)| id).max()
+    }
+
+    fn run_directories(&self) -> Vec<(usize, PathBuf)> {
+        let Ok(entries) = fs::read_dir(&self.directory) else {
+            return Vec::new();
+        };
+
+        entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| {
+                let dir_name = entry.file_name().into_string().ok()?;
+                let id = dir_name.strip_prefix(RUN_PREFIX)?.parse::<usize>().ok()?;
+                Some((id, entry.path()))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::test_directory;
+    use crate::tracking::RunStatus;
+    use burn_core as burn;
+    use burn_core::config::Config;
+
+    #[derive(Config)]
+    struct TestConfig {
+        learning_rate: f64,
+    }
+
+    #[test]
+    fn test_runs_are_versioned_and_found() {
+        let dir = &test_directory("tracking-runs");
+        fs::remove_dir_all(dir).ok();
+        let tracker = RunTracker::new(dir);
+
+        let mut first = tracker.start("baseline").unwrap();
+        first
+            .save_config("training", &TestConfig::new(1e-3))
+            .unwrap();
+        let second = tracker.start("baseline").unwrap();
+        second.finish_training(RunStatus::Completed, "model".to_string());
+
+        let runs = tracker.runs();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].directory(), dir.join("run-1"));
+        assert_eq!(runs[0].info().status, RunStatus::Running);
+        assert_eq!(
+            runs[0].configs().unwrap()["training"]["learning_rate"],
+            1e-3
+        );
+        assert_eq!(runs[1].info().status, RunStatus::Completed);
+
+        assert_eq!(tracker.run("run-1").unwrap().info().id, 1);
+        assert_eq!(tracker.run("baseline").unwrap().info().id, 2);
+        assert!(tracker.run("3").is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_run_dropped_during_the_training_is_failed() {
+        let dir = &test_directory("tracking-failed-run");
+        fs::remove_dir_all(dir).ok();
+        let tracker = RunTracker::new(dir);
+
+        let mut run = tracker.start("baseline").unwrap();
+        run.start_training(Vec::new(), Vec::new());
+        let result = std::panic::catch_unwind(move || {
+            let _run = run;
+            panic!("The training failed");
+        });
+
+        assert!(result.is_err());
+        let info = tracker.run("baseline").unwrap().info().clone();
+        assert_eq!(info.status, RunStatus::Failed);
+        assert!(info.finished.is_some());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_command_args_are_only_recorded_when_enabled() {
+        let dir = &test_directory("tracking-command-args");
+        fs::remove_dir_all(dir).ok();
+
+        let run = RunTracker::new(dir).start("baseline").unwrap();
+        assert_eq!(run.info().environment.command.len(), 1);
+
+        let run = RunTracker::new(dir)
+            .with_command_args(true)
+            .start("baseline")
+            .unwrap();
+        let args = std::env::args().collect::<Vec<_>>();
+        assert_eq!(run.info().environment.command, args);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}